@@ -503,6 +503,7 @@ fn benchmark_cache_pressure(c: &mut Criterion) {
                             default_ttl: Duration::from_secs(300),
                             cleanup_interval: Duration::from_secs(60),
                             max_response_size: 1024 * 1024,
+                            adaptive_ttl: None,
                         };
                         CommandCache::new(config)
                     },
@@ -0,0 +1,119 @@
+/*
+ * Payload Sharing Benchmarks
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+
+use bevy_debugger_mcp::command_cache::{CacheConfig, CacheKey, CommandCache};
+use bevy_debugger_mcp::response_pool::SharedResponse;
+
+/// An observe response listing `count` entities with a few components each
+fn entity_response(count: usize) -> Value {
+    let entities: Vec<Value> = (0..count)
+        .map(|id| {
+            json!({
+                "id": id,
+                "components": {
+                    "bevy_transform::components::transform::Transform": {
+                        "translation": [id as f32, 0.0, 1.5],
+                        "rotation": [0.0, 0.0, 0.0, 1.0],
+                        "scale": [1.0, 1.0, 1.0]
+                    },
+                    "game::Velocity": {"linear": [0.5, 0.0, 0.0]},
+                    "game::Name": format!("entity_{}", id)
+                }
+            })
+        })
+        .collect();
+    json!({"result": {"type": "Entities", "data": entities}})
+}
+
+/// Cache hit followed by sending the response, with and without payload sharing
+fn benchmark_cached_response_delivery(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("cached_response_delivery");
+
+    for entity_count in [10, 1_000, 10_000] {
+        let cache = runtime.block_on(async {
+            let cache = CommandCache::new(CacheConfig {
+                max_response_size: 64 * 1024 * 1024,
+                ..CacheConfig::default()
+            });
+            let key = CacheKey::new("observe", &json!({"query": "list all entities"})).unwrap();
+            cache
+                .put(&key, entity_response(entity_count), vec![])
+                .await
+                .unwrap();
+            (cache, key)
+        });
+
+        // Previous hot path: copy the value out of the cache, then serialize it
+        group.bench_with_input(
+            BenchmarkId::new("clone_and_serialize", entity_count),
+            &cache,
+            |b, (cache, key)| {
+                b.to_async(&runtime).iter(|| async {
+                    let response = cache.get(key).await.unwrap();
+                    black_box(serde_json::to_vec(&response).unwrap())
+                });
+            },
+        );
+
+        // Shared hot path: reuse the encoding made when the entry was stored
+        group.bench_with_input(
+            BenchmarkId::new("shared_bytes", entity_count),
+            &cache,
+            |b, (cache, key)| {
+                b.to_async(&runtime).iter(|| async {
+                    let response = cache.get_shared(key).await.unwrap();
+                    black_box(response.to_bytes().unwrap())
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Handing one response to several consumers
+fn benchmark_fan_out(c: &mut Criterion) {
+    let response = entity_response(1_000);
+    let shared = SharedResponse::new(response.clone());
+
+    c.bench_function("fan_out_value_clone", |b| {
+        b.iter(|| {
+            let copies: Vec<Value> = (0..3).map(|_| response.clone()).collect();
+            black_box(copies)
+        });
+    });
+    c.bench_function("fan_out_shared", |b| {
+        b.iter(|| {
+            let copies: Vec<SharedResponse> = (0..3).map(|_| shared.clone()).collect();
+            black_box(copies)
+        });
+    });
+}
+
+criterion_group!(
+    payload_sharing_benches,
+    benchmark_cached_response_delivery,
+    benchmark_fan_out,
+);
+
+criterion_main!(payload_sharing_benches);
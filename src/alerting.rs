@@ -0,0 +1,419 @@
+/*
+ * Bevy Debugger MCP Server - Threshold Alerting
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! User-defined threshold alerts
+//!
+//! A rule fires once a metric has stayed above or below its threshold for
+//! `for_secs`, and fires again at most once per `cooldown_secs`. Rules see
+//! the health probe's signals (`brp_connected`, `circuit_breaker_open`,
+//! `memory_rss_bytes`, `cpu_percent`, `memory_budget_utilization`) and the
+//! latest value of every [`metrics_store`](crate::metrics_store) series.
+//! Booleans are `1` or `0`, so "disconnected for 10s" is `brp_connected`
+//! below `1` with `for_secs: 10`.
+//!
+//! Firings and resolutions are logged, kept in a short history, sent to
+//! subscribers (MCP clients receive them as log notifications) and, with the
+//! `alert-webhooks` feature, POSTed to the rule's webhook.
+//!
+//! These rules are unrelated to the statistical detectors in
+//! [`anomaly_detector`](crate::anomaly_detector).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::health_endpoints::{HealthProbe, HealthSignals};
+use crate::metrics_store;
+
+/// How often rules are evaluated
+pub const ALERT_EVALUATION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Minimum time between firings of one rule unless it sets `cooldown_secs`
+pub const DEFAULT_ALERT_COOLDOWN_SECS: u64 = 300;
+
+/// Alert events kept for the `alerts` tool
+const MAX_ALERT_HISTORY: usize = 200;
+
+/// Which side of the threshold is a problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertCondition {
+    Above,
+    Below,
+}
+
+/// A threshold on one metric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub metric: String,
+    pub condition: AlertCondition,
+    pub threshold: f64,
+    /// How long the threshold must be crossed before the rule fires
+    #[serde(default)]
+    pub for_secs: u64,
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// URL the alert is POSTed to as JSON
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+fn default_cooldown_secs() -> u64 {
+    DEFAULT_ALERT_COOLDOWN_SECS
+}
+
+impl AlertRule {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() || self.metric.trim().is_empty() {
+            return Err(Error::Validation(
+                "Alert rules need a name and a metric".to_string(),
+            ));
+        }
+        if !self.threshold.is_finite() {
+            return Err(Error::Validation(format!(
+                "Alert rule '{}' has an invalid threshold",
+                self.name
+            )));
+        }
+        if self.webhook.is_some() && !cfg!(feature = "alert-webhooks") {
+            return Err(Error::Validation(format!(
+                "Alert rule '{}' has a webhook, which requires the alert-webhooks feature",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn crossed(&self, value: f64) -> bool {
+        match self.condition {
+            AlertCondition::Above => value > self.threshold,
+            AlertCondition::Below => value < self.threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+/// A rule starting or stopping to fire
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub rule: String,
+    pub metric: String,
+    pub state: AlertState,
+    pub value: f64,
+    pub threshold: f64,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct RuleStatus {
+    crossed_since: Option<Instant>,
+    firing: bool,
+    last_fired: Option<Instant>,
+}
+
+/// Alert rules and their evaluation state
+#[derive(Debug)]
+pub struct AlertManager {
+    rules: Mutex<Vec<AlertRule>>,
+    status: Mutex<HashMap<String, RuleStatus>>,
+    history: Mutex<VecDeque<AlertEvent>>,
+    events: broadcast::Sender<AlertEvent>,
+}
+
+impl Default for AlertManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AlertManager {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            rules: Mutex::new(Vec::new()),
+            status: Mutex::new(HashMap::new()),
+            history: Mutex::new(VecDeque::new()),
+            events,
+        }
+    }
+
+    /// Add the rules in the JSON file named by `BEVY_MCP_ALERT_RULES`, returning how many
+    pub fn load_rules_from_env(&self) -> Result<usize> {
+        let Ok(path) = std::env::var("BEVY_MCP_ALERT_RULES") else {
+            return Ok(0);
+        };
+        let rules: Vec<AlertRule> = serde_json::from_slice(&std::fs::read(&path)?)
+            .map_err(|e| Error::Config(format!("Invalid alert rules in {path}: {e}")))?;
+        let count = rules.len();
+        for rule in rules {
+            self.add_rule(rule)?;
+        }
+        Ok(count)
+    }
+
+    /// Add `rule`, replacing any rule with the same name
+    pub fn add_rule(&self, rule: AlertRule) -> Result<()> {
+        rule.validate()?;
+        self.remove_rule(&rule.name);
+        if let Ok(mut rules) = self.rules.lock() {
+            rules.push(rule);
+        }
+        Ok(())
+    }
+
+    pub fn remove_rule(&self, name: &str) -> bool {
+        if let Ok(mut status) = self.status.lock() {
+            status.remove(name);
+        }
+        let Ok(mut rules) = self.rules.lock() else {
+            return false;
+        };
+        let before = rules.len();
+        rules.retain(|rule| rule.name != name);
+        rules.len() != before
+    }
+
+    pub fn rules(&self) -> Vec<AlertRule> {
+        self.rules
+            .lock()
+            .map(|rules| rules.clone())
+            .unwrap_or_default()
+    }
+
+    /// Names of the rules currently firing
+    pub fn firing(&self) -> Vec<String> {
+        self.status
+            .lock()
+            .map(|status| {
+                let mut firing: Vec<String> = status
+                    .iter()
+                    .filter(|(_, status)| status.firing)
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                firing.sort();
+                firing
+            })
+            .unwrap_or_default()
+    }
+
+    /// Recent alert events, oldest first
+    pub fn history(&self) -> Vec<AlertEvent> {
+        self.history
+            .lock()
+            .map(|history| history.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Alert events from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<AlertEvent> {
+        self.events.subscribe()
+    }
+
+    /// Check every rule against `metrics` at `now`, returning what changed
+    ///
+    /// Rules whose metric is missing keep their state.
+    pub fn evaluate(&self, metrics: &HashMap<String, f64>, now: Instant) -> Vec<AlertEvent> {
+        let rules = self.rules();
+        let mut changes = Vec::new();
+        {
+            let Ok(mut statuses) = self.status.lock() else {
+                return changes;
+            };
+            for rule in &rules {
+                let Some(&value) = metrics.get(&rule.metric) else {
+                    continue;
+                };
+                let status = statuses.entry(rule.name.clone()).or_default();
+                let state = if rule.crossed(value) {
+                    let since = *status.crossed_since.get_or_insert(now);
+                    let held = now.duration_since(since) >= Duration::from_secs(rule.for_secs);
+                    let cooled_down = match status.last_fired {
+                        Some(last) => {
+                            now.duration_since(last) >= Duration::from_secs(rule.cooldown_secs)
+                        }
+                        None => true,
+                    };
+                    if status.firing || !held || !cooled_down {
+                        continue;
+                    }
+                    status.firing = true;
+                    status.last_fired = Some(now);
+                    AlertState::Firing
+                } else {
+                    status.crossed_since = None;
+                    if !status.firing {
+                        continue;
+                    }
+                    status.firing = false;
+                    AlertState::Resolved
+                };
+                changes.push(AlertEvent {
+                    rule: rule.name.clone(),
+                    metric: rule.metric.clone(),
+                    state,
+                    value,
+                    threshold: rule.threshold,
+                    at: Utc::now(),
+                });
+            }
+        }
+
+        for event in &changes {
+            match event.state {
+                AlertState::Firing => warn!(
+                    "Alert '{}' firing: {} is {} (threshold {})",
+                    event.rule, event.metric, event.value, event.threshold
+                ),
+                AlertState::Resolved => info!(
+                    "Alert '{}' resolved: {} is {}",
+                    event.rule, event.metric, event.value
+                ),
+            }
+            if let Ok(mut history) = self.history.lock() {
+                if history.len() >= MAX_ALERT_HISTORY {
+                    history.pop_front();
+                }
+                history.push_back(event.clone());
+            }
+            // No subscribers is fine
+            let _ = self.events.send(event.clone());
+        }
+        changes
+    }
+}
+
+/// Values rules are evaluated against
+pub fn current_metrics(signals: &HealthSignals) -> HashMap<String, f64> {
+    let flag = |set: bool| if set { 1.0 } else { 0.0 };
+    let mut metrics: HashMap<String, f64> = metrics_store::global().latest().into_iter().collect();
+    metrics.insert("brp_connected".to_string(), flag(signals.brp_connected));
+    metrics.insert(
+        "circuit_breaker_open".to_string(),
+        flag(signals.circuit_breaker_open),
+    );
+    metrics.insert("memory_rss_bytes".to_string(), signals.rss_bytes as f64);
+    metrics.insert("cpu_percent".to_string(), signals.cpu_percent as f64);
+    metrics.insert(
+        "memory_budget_utilization".to_string(),
+        signals.memory_budget_utilization,
+    );
+    metrics
+}
+
+/// Evaluate `manager`'s rules in the background, sending webhooks as they fire
+pub fn spawn_evaluator(manager: Arc<AlertManager>, probe: HealthProbe) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ALERT_EVALUATION_INTERVAL);
+        loop {
+            interval.tick().await;
+            let rules = manager.rules();
+            if rules.is_empty() {
+                continue;
+            }
+            let metrics = current_metrics(&probe.check().await.signals);
+            for event in manager.evaluate(&metrics, Instant::now()) {
+                let webhook = rules
+                    .iter()
+                    .find(|rule| rule.name == event.rule)
+                    .and_then(|rule| rule.webhook.clone());
+                if let Some(url) = webhook {
+                    send_webhook(url, event);
+                }
+            }
+        }
+    });
+}
+
+/// POST `payload` to `url` as JSON in the background, logging failures
+#[cfg(feature = "alert-webhooks")]
+pub(crate) fn send_webhook<T: Serialize + Send + 'static>(url: String, payload: T) {
+    tokio::spawn(async move {
+        let result = reqwest::Client::new()
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            warn!("Webhook {} failed: {}", url, e);
+        }
+    });
+}
+
+// Rules and schedules with webhooks are rejected without the feature
+#[cfg(not(feature = "alert-webhooks"))]
+pub(crate) fn send_webhook<T: Serialize + Send + 'static>(_url: String, _payload: T) {}
+
+/// The manager the server evaluates
+pub fn global() -> Arc<AlertManager> {
+    static GLOBAL: OnceLock<Arc<AlertManager>> = OnceLock::new();
+    GLOBAL.get_or_init(Default::default).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_fires_after_duration_and_respects_cooldown() {
+        let manager = AlertManager::new();
+        manager
+            .add_rule(AlertRule {
+                name: "game-disconnected".to_string(),
+                metric: "brp_connected".to_string(),
+                condition: AlertCondition::Below,
+                threshold: 1.0,
+                for_secs: 10,
+                cooldown_secs: 60,
+                webhook: None,
+            })
+            .unwrap();
+        let disconnected = HashMap::from([("brp_connected".to_string(), 0.0)]);
+        let connected = HashMap::from([("brp_connected".to_string(), 1.0)]);
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        assert!(manager.evaluate(&disconnected, at(0)).is_empty());
+        assert!(manager.evaluate(&disconnected, at(5)).is_empty());
+        let fired = manager.evaluate(&disconnected, at(10));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].state, AlertState::Firing);
+        assert_eq!(manager.firing(), vec!["game-disconnected"]);
+
+        let resolved = manager.evaluate(&connected, at(12));
+        assert_eq!(resolved[0].state, AlertState::Resolved);
+
+        // Crossed long enough again, but still cooling down
+        manager.evaluate(&disconnected, at(20));
+        assert!(manager.evaluate(&disconnected, at(30)).is_empty());
+        assert_eq!(manager.evaluate(&disconnected, at(70)).len(), 1);
+        assert_eq!(manager.history().len(), 3);
+    }
+}
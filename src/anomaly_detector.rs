@@ -6,6 +6,7 @@ use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
 use crate::brp_messages::EntityData;
+use crate::diagnostics_bridge::DiagnosticsSnapshot;
 use crate::error::{Error, Result};
 
 /// Types of anomalies that can be detected
@@ -44,6 +45,9 @@ impl AnomalyType {
 /// Detected anomaly with context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Anomaly {
+    /// Unique ID, used to tag recordings and reports about this anomaly
+    #[serde(default = "new_anomaly_id")]
+    pub id: String,
     pub anomaly_type: AnomalyType,
     pub entity_id: Option<u64>,
     pub component: Option<String>,
@@ -53,6 +57,10 @@ pub struct Anomaly {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+fn new_anomaly_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
 /// Configuration for anomaly detection
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnomalyConfig {
@@ -226,6 +234,11 @@ pub trait AnomalyDetector: Send + Sync {
 
     /// Update detector configuration
     fn configure(&mut self, config: &AnomalyConfig);
+
+    /// Process diagnostics read from the game (frame time, entity count)
+    fn detect_diagnostics(&mut self, _snapshot: &DiagnosticsSnapshot) -> Vec<Anomaly> {
+        Vec::new()
+    }
 }
 
 /// Physics violation detector
@@ -295,6 +308,7 @@ impl AnomalyDetector for PhysicsDetector {
                         .collect();
 
                         anomalies.push(Anomaly {
+                            id: new_anomaly_id(),
                             anomaly_type: AnomalyType::PhysicsViolation,
                             entity_id: Some(entity.id),
                             component: Some("Velocity".to_string()),
@@ -375,6 +389,7 @@ impl AnomalyDetector for PerformanceDetector {
                     .collect();
 
                     anomalies.push(Anomaly {
+                        id: new_anomaly_id(),
                         anomaly_type: AnomalyType::EntityCountSpike,
                         entity_id: None,
                         component: None,
@@ -400,6 +415,55 @@ impl AnomalyDetector for PerformanceDetector {
     fn configure(&mut self, config: &AnomalyConfig) {
         self.config = config.clone();
     }
+
+    fn detect_diagnostics(&mut self, snapshot: &DiagnosticsSnapshot) -> Vec<Anomaly> {
+        let Some(frame_time_ms) = snapshot.frame_time_ms() else {
+            return Vec::new();
+        };
+
+        let mut anomalies = Vec::new();
+
+        // Compare against the baseline before adding the new sample
+        if self.frame_times.len() >= self.config.min_samples {
+            let values: Vec<f32> = self.frame_times.values().map(|dp| dp.value).collect();
+            let baseline = Statistics::mean(&values);
+
+            if baseline > 0.0 && frame_time_ms > baseline * self.config.performance_threshold {
+                let ratio = frame_time_ms / baseline;
+                let severity = ((ratio - 1.0) / (self.config.performance_threshold * 2.0)).min(1.0);
+
+                let metadata = [
+                    ("frame_time_ms", serde_json::json!(frame_time_ms)),
+                    ("baseline_ms", serde_json::json!(baseline)),
+                    ("frame", serde_json::json!(snapshot.frame_count())),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect();
+
+                anomalies.push(Anomaly {
+                    id: new_anomaly_id(),
+                    anomaly_type: AnomalyType::PerformanceSpike,
+                    entity_id: None,
+                    component: None,
+                    severity,
+                    description: format!(
+                        "Frame time {:.2}ms is {:.1}x the recent average of {:.2}ms",
+                        frame_time_ms, ratio, baseline
+                    ),
+                    detected_at: chrono::Utc::now(),
+                    metadata,
+                });
+            }
+        }
+
+        self.frame_times.push(DataPoint {
+            value: frame_time_ms,
+            timestamp: Instant::now(),
+        });
+
+        anomalies
+    }
 }
 
 /// State consistency detector
@@ -437,6 +501,7 @@ impl ConsistencyDetector {
             .collect();
 
             return Some(Anomaly {
+                id: new_anomaly_id(),
                 anomaly_type: AnomalyType::StateInconsistency,
                 entity_id: Some(entity.id),
                 component: Some("Health/Alive".to_string()),
@@ -545,6 +610,23 @@ impl AnomalyDetectionSystem {
         Ok(all_anomalies)
     }
 
+    /// Process diagnostics read from the game through all detectors
+    pub fn detect_diagnostic_anomalies(&mut self, snapshot: &DiagnosticsSnapshot) -> Vec<Anomaly> {
+        let anomalies: Vec<Anomaly> = self
+            .detectors
+            .iter_mut()
+            .flat_map(|detector| detector.detect_diagnostics(snapshot))
+            .collect();
+
+        let mut anomalies = self.filter_whitelisted(anomalies);
+        anomalies.sort_by(|a, b| {
+            b.severity
+                .partial_cmp(&a.severity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        anomalies
+    }
+
     /// Start monitoring loop for async operation
     pub async fn start_monitoring(mut self) -> Result<()> {
         let mut receiver = self
@@ -797,4 +879,26 @@ mod tests {
             .iter()
             .any(|a| a.anomaly_type == AnomalyType::StateInconsistency));
     }
+
+    #[test]
+    fn test_frame_time_spike_from_diagnostics() {
+        let mut system = AnomalyDetectionSystem::new(AnomalyConfig::default());
+        let snapshot = |frame_time: f64| {
+            DiagnosticsSnapshot::new(vec![crate::brp_messages::DiagnosticMeasurement {
+                path: crate::diagnostics_bridge::paths::FRAME_TIME.to_string(),
+                value: Some(frame_time),
+                average: None,
+                smoothed: None,
+                suffix: Some("ms".to_string()),
+            }])
+        };
+
+        for _ in 0..20 {
+            assert!(system.detect_diagnostic_anomalies(&snapshot(16.0)).is_empty());
+        }
+
+        let anomalies = system.detect_diagnostic_anomalies(&snapshot(50.0));
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].anomaly_type, AnomalyType::PerformanceSpike);
+    }
 }
@@ -0,0 +1,283 @@
+/*
+ * Bevy Debugger MCP Server - Tamper-Evident Audit Chain
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use base64::Engine as _;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::security::AuditEntry;
+
+/// `prev_hash` of the first entry in a chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// SHA-256 of the entry with its own hash and signature left out
+pub fn entry_hash(entry: &AuditEntry) -> Result<String> {
+    let mut unsealed = entry.clone();
+    unsealed.hash = None;
+    unsealed.signature = None;
+    let digest = ring::digest::digest(&ring::digest::SHA256, &serde_json::to_vec(&unsealed)?);
+    Ok(digest
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+fn hmac_key(key: &str) -> hmac::Key {
+    hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes())
+}
+
+fn sign(key: &hmac::Key, hash: &str) -> String {
+    base64::engine::general_purpose::STANDARD_NO_PAD.encode(hmac::sign(key, hash.as_bytes()))
+}
+
+/// Links each audit entry to the one before it
+///
+/// Every entry carries the previous entry's hash and its own, so editing,
+/// removing or reordering entries breaks the chain. With a signing key every
+/// `signature_interval`th entry is also signed, which stops someone who can
+/// rewrite the file from simply recomputing every hash.
+pub struct AuditChain {
+    signing_key: Option<hmac::Key>,
+    signature_interval: u64,
+    /// Last hash handed out; `None` until resumed from the existing log
+    last_hash: Option<String>,
+    sealed: u64,
+}
+
+impl std::fmt::Debug for AuditChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditChain")
+            .field("signed", &self.signing_key.is_some())
+            .field("signature_interval", &self.signature_interval)
+            .field("last_hash", &self.last_hash)
+            .finish()
+    }
+}
+
+impl AuditChain {
+    pub fn new(signing_key: Option<&str>, signature_interval: u64) -> Self {
+        Self {
+            signing_key: signing_key.map(hmac_key),
+            signature_interval: signature_interval.max(1),
+            last_hash: None,
+            sealed: 0,
+        }
+    }
+
+    /// Whether the chain still needs the last hash of an existing log
+    pub fn needs_resume(&self) -> bool {
+        self.last_hash.is_none()
+    }
+
+    /// Continue after `last`, the newest entry already written, if any
+    pub fn resume(&mut self, last: Option<&AuditEntry>) {
+        self.last_hash = Some(
+            last.and_then(|entry| entry.hash.clone())
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+        );
+    }
+
+    /// Fill in `prev_hash`, `hash` and, when due, `signature`
+    pub fn seal(&mut self, entry: &mut AuditEntry) -> Result<()> {
+        entry.prev_hash = Some(
+            self.last_hash
+                .clone()
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+        );
+        entry.signature = None;
+        let hash = entry_hash(entry)?;
+        self.sealed += 1;
+        if let Some(key) = &self.signing_key {
+            if self.sealed % self.signature_interval == 0 {
+                entry.signature = Some(sign(key, &hash));
+            }
+        }
+        entry.hash = Some(hash.clone());
+        self.last_hash = Some(hash);
+        Ok(())
+    }
+}
+
+/// Where and why a chain stopped verifying
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditChainBreak {
+    /// Position in the verified entries, oldest first
+    pub index: usize,
+    pub entry_id: String,
+    pub reason: String,
+}
+
+/// Result of checking a sequence of audit entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditVerification {
+    pub valid: bool,
+    pub entries_checked: usize,
+    /// Entries written before integrity mode was enabled
+    pub unsealed_entries: usize,
+    pub signatures_checked: usize,
+    /// False when older entries were rotated away, so the chain starts mid-way
+    pub starts_at_genesis: bool,
+    pub first_break: Option<AuditChainBreak>,
+}
+
+/// Check that `entries`, oldest first, form an unbroken chain
+///
+/// Entries without a hash are only accepted before the first sealed entry.
+/// Signatures are checked when `signing_key` is given.
+pub fn verify_chain(entries: &[AuditEntry], signing_key: Option<&str>) -> AuditVerification {
+    let key = signing_key.map(hmac_key);
+    let mut report = AuditVerification {
+        valid: true,
+        entries_checked: entries.len(),
+        unsealed_entries: 0,
+        signatures_checked: 0,
+        starts_at_genesis: false,
+        first_break: None,
+    };
+    let mut previous: Option<&str> = None;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let broken = |reason: &str| AuditChainBreak {
+            index,
+            entry_id: entry.id.clone(),
+            reason: reason.to_string(),
+        };
+        let failure = match (&entry.hash, &entry.prev_hash) {
+            (None, _) if previous.is_none() => {
+                report.unsealed_entries += 1;
+                continue;
+            }
+            (None, _) => Some(broken("Entry is missing its hash")),
+            (Some(_), None) => Some(broken("Entry is missing its previous hash")),
+            (Some(hash), Some(prev_hash)) => {
+                if previous.is_none() {
+                    report.starts_at_genesis = prev_hash == GENESIS_HASH;
+                }
+                if matches!(previous, Some(previous) if previous != prev_hash) {
+                    Some(broken(
+                        "Previous hash does not match; entries were removed or reordered",
+                    ))
+                } else if entry_hash(entry).ok().as_deref() != Some(hash.as_str()) {
+                    Some(broken("Hash does not match contents; entry was modified"))
+                } else {
+                    match (&entry.signature, &key) {
+                        (Some(signature), Some(key)) => {
+                            report.signatures_checked += 1;
+                            let expected = sign(key, hash);
+                            (expected != *signature).then(|| broken("Signature is invalid"))
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        };
+        if let Some(failure) = failure {
+            report.valid = false;
+            report.first_break = Some(failure);
+            return report;
+        }
+        previous = entry.hash.as_deref();
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(action: &str) -> AuditEntry {
+        AuditEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: "admin".to_string(),
+            username: "admin".to_string(),
+            action: action.to_string(),
+            resource: None,
+            success: true,
+            error_message: None,
+            timestamp: Utc::now(),
+            ip_address: None,
+            user_agent: None,
+            session_id: None,
+            prev_hash: None,
+            hash: None,
+            signature: None,
+        }
+    }
+
+    fn sealed_chain(key: Option<&str>, count: usize) -> Vec<AuditEntry> {
+        let mut chain = AuditChain::new(key, 2);
+        chain.resume(None);
+        (0..count)
+            .map(|i| {
+                let mut entry = entry(&format!("action_{}", i));
+                chain.seal(&mut entry).unwrap();
+                entry
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let entries = sealed_chain(Some("audit-signing-key"), 5);
+        assert_eq!(entries[0].prev_hash.as_deref(), Some(GENESIS_HASH));
+        assert_eq!(entries[1].prev_hash, entries[0].hash);
+        assert!(entries[1].signature.is_some() && entries[2].signature.is_none());
+
+        let report = verify_chain(&entries, Some("audit-signing-key"));
+        assert!(report.valid, "{:?}", report.first_break);
+        assert!(report.starts_at_genesis);
+        assert_eq!(report.signatures_checked, 2);
+
+        // Older entries rotated away still leave a verifiable tail
+        let report = verify_chain(&entries[2..], None);
+        assert!(report.valid && !report.starts_at_genesis);
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let entries = sealed_chain(Some("audit-signing-key"), 4);
+
+        let mut edited = entries.clone();
+        edited[2].success = false;
+        let report = verify_chain(&edited, None);
+        assert_eq!(report.first_break.unwrap().index, 2);
+
+        let mut removed = entries.clone();
+        removed.remove(1);
+        assert_eq!(verify_chain(&removed, None).first_break.unwrap().index, 1);
+
+        // Recomputing the hash does not help without the signing key
+        let mut forged = entries.clone();
+        forged[1].action = "nothing_to_see".to_string();
+        forged[1].hash = Some(entry_hash(&forged[1]).unwrap());
+        forged[2].prev_hash = forged[1].hash.clone();
+        forged[2].hash = Some(entry_hash(&forged[2]).unwrap());
+        assert!(verify_chain(&forged, Some("audit-signing-key"))
+            .first_break
+            .unwrap()
+            .reason
+            .contains("Signature"));
+
+        let mut injected = entries;
+        injected.insert(2, entry("unsealed"));
+        assert!(!verify_chain(&injected, None).valid);
+    }
+}
@@ -0,0 +1,445 @@
+/*
+ * Bevy Debugger MCP Server - Persistent Audit Log
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::security::AuditEntry;
+
+/// Where audit entries are written and when the file is rotated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditFileConfig {
+    /// Active log file; rotated files are written next to it as `<name>.<timestamp>.gz`
+    pub path: PathBuf,
+    /// Rotate once the active file would grow past this size
+    pub max_bytes: u64,
+    /// Rotate once the active file is this old; `None` rotates on size only
+    pub rotate_interval_hours: Option<u64>,
+    /// Delete rotated files older than this
+    pub retention_days: u64,
+    /// Keep at most this many rotated files; `None` keeps all within retention
+    pub max_files: Option<usize>,
+}
+
+/// Result of a rotation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRotation {
+    /// Compressed file the active log was moved to, if it had entries
+    pub rotated_to: Option<PathBuf>,
+    /// Rotated files deleted by retention
+    pub removed: usize,
+}
+
+/// Active file state
+struct ActiveFile {
+    file: tokio::fs::File,
+    size: u64,
+    opened_at: DateTime<Utc>,
+}
+
+/// Appends audit entries as JSON lines with size and time based rotation
+pub struct AuditFileLogger {
+    config: AuditFileConfig,
+    active: Mutex<Option<ActiveFile>>,
+}
+
+impl std::fmt::Debug for AuditFileLogger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditFileLogger")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditFileLogger {
+    pub fn new(config: AuditFileConfig) -> Self {
+        Self {
+            config,
+            active: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &AuditFileConfig {
+        &self.config
+    }
+
+    /// Append one entry, rotating first when the file is full or too old
+    pub async fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut active = self.active.lock().await;
+        if let Some(current) = active.as_ref() {
+            if self.rotation_due(current, line.len() as u64) {
+                *active = None;
+                self.rotate_file().await?;
+            }
+        }
+        if active.is_none() {
+            *active = Some(self.open().await?);
+        }
+
+        let current = active.as_mut().expect("audit file opened above");
+        current.file.write_all(&line).await?;
+        current.file.flush().await?;
+        current.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Every entry still on disk, oldest first: rotated files by age, then the active file
+    pub async fn read_entries(&self) -> Result<Vec<AuditEntry>> {
+        let _active = self.active.lock().await;
+        let mut paths: Vec<PathBuf> = self
+            .rotated_files()
+            .await?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        // Rotated names end in a sortable timestamp
+        paths.sort();
+        paths.push(self.config.path.clone());
+        tokio::task::spawn_blocking(move || -> Result<Vec<AuditEntry>> {
+            let mut entries = Vec::new();
+            for path in &paths {
+                entries.extend(read_entries_from(path)?);
+            }
+            Ok(entries)
+        })
+        .await
+        .map_err(|e| Error::DebugError(format!("Audit log read failed: {}", e)))?
+    }
+
+    /// The newest entry on disk, if any
+    pub async fn last_entry(&self) -> Result<Option<AuditEntry>> {
+        let _active = self.active.lock().await;
+        let mut candidates = vec![self.config.path.clone()];
+        if let Some((newest, _)) = self
+            .rotated_files()
+            .await?
+            .into_iter()
+            .max_by(|a, b| a.0.cmp(&b.0))
+        {
+            candidates.push(newest);
+        }
+        tokio::task::spawn_blocking(move || -> Result<Option<AuditEntry>> {
+            for path in &candidates {
+                if let Some(entry) = read_entries_from(path)?.pop() {
+                    return Ok(Some(entry));
+                }
+            }
+            Ok(None)
+        })
+        .await
+        .map_err(|e| Error::DebugError(format!("Audit log read failed: {}", e)))?
+    }
+
+    /// Rotate now regardless of size or age, then apply retention
+    pub async fn rotate(&self) -> Result<AuditRotation> {
+        let mut active = self.active.lock().await;
+        *active = None;
+        let rotated_to = self.rotate_file().await?;
+        let removed = self.enforce_retention().await?;
+        Ok(AuditRotation {
+            rotated_to,
+            removed,
+        })
+    }
+
+    /// Rotate if the active file is past its age limit, then apply retention
+    pub async fn maintain(&self) -> Result<AuditRotation> {
+        let mut active = self.active.lock().await;
+        let mut rotated_to = None;
+        if active
+            .as_ref()
+            .is_some_and(|current| current.size > 0 && self.rotation_due(current, 0))
+        {
+            *active = None;
+            rotated_to = self.rotate_file().await?;
+        }
+        let removed = self.enforce_retention().await?;
+        Ok(AuditRotation {
+            rotated_to,
+            removed,
+        })
+    }
+
+    fn rotation_due(&self, current: &ActiveFile, incoming: u64) -> bool {
+        let too_large = current.size > 0 && current.size + incoming > self.config.max_bytes;
+        let too_old = self.config.rotate_interval_hours.is_some_and(|hours| {
+            Utc::now().signed_duration_since(current.opened_at)
+                >= chrono::Duration::hours(hours as i64)
+        });
+        too_large || too_old
+    }
+
+    async fn open(&self) -> Result<ActiveFile> {
+        if let Some(parent) = self
+            .config
+            .path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.path)
+            .await?;
+        let metadata = file.metadata().await?;
+        // A file left by a previous run keeps aging from when it was last written
+        let opened_at = metadata
+            .modified()
+            .ok()
+            .filter(|_| metadata.len() > 0)
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(Utc::now);
+        Ok(ActiveFile {
+            file,
+            size: metadata.len(),
+            opened_at,
+        })
+    }
+
+    /// Move the active file aside and compress it; the caller must have closed it
+    async fn rotate_file(&self) -> Result<Option<PathBuf>> {
+        let path = &self.config.path;
+        match tokio::fs::metadata(path).await {
+            Ok(metadata) if metadata.len() > 0 => {}
+            Ok(_) => return Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+        let plain = rotated_path(path, &format!("{}", stamp));
+        tokio::fs::rename(path, &plain).await?;
+
+        let compressed = PathBuf::from(format!("{}.gz", plain.display()));
+        let (source, target) = (plain.clone(), compressed.clone());
+        tokio::task::spawn_blocking(move || gzip_file(&source, &target))
+            .await
+            .map_err(|e| Error::DebugError(format!("Audit log compression failed: {}", e)))??;
+        tokio::fs::remove_file(&plain).await?;
+
+        info!("Rotated audit log to {}", compressed.display());
+        Ok(Some(compressed))
+    }
+
+    /// Delete rotated files past the retention period or the file limit
+    async fn enforce_retention(&self) -> Result<usize> {
+        let mut rotated = self.rotated_files().await?;
+        // Newest first, so the file limit keeps the most recent
+        rotated.sort_by_key(|r| Reverse(r.1));
+
+        let cutoff = Utc::now() - chrono::Duration::days(self.config.retention_days as i64);
+        let mut removed = 0;
+        for (index, (path, modified)) in rotated.iter().enumerate() {
+            let over_limit = self.config.max_files.is_some_and(|max| index >= max);
+            if *modified < cutoff || over_limit {
+                match tokio::fs::remove_file(path).await {
+                    Ok(()) => removed += 1,
+                    Err(e) => warn!("Failed to remove old audit log {}: {}", path.display(), e),
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn rotated_files(&self) -> Result<Vec<(PathBuf, DateTime<Utc>)>> {
+        let path = &self.config.path;
+        let directory = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let Some(prefix) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| format!("{}.", n))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut files = Vec::new();
+        let mut entries = match tokio::fs::read_dir(directory).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+            Err(e) => return Err(e.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            if name.starts_with(&prefix) && name.ends_with(".gz") {
+                let modified = entry.metadata().await?.modified()?;
+                files.push((entry.path(), DateTime::<Utc>::from(modified)));
+            }
+        }
+        Ok(files)
+    }
+}
+
+fn rotated_path(path: &Path, stamp: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "audit.log".to_string());
+    path.with_file_name(format!("{}.{}", name, stamp))
+}
+
+/// Entries in a JSON lines file, gzipped when its name ends in `.gz`
+fn read_entries_from(path: &Path) -> Result<Vec<AuditEntry>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let reader: Box<dyn Read> = if path.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(entries)
+}
+
+fn gzip_file(source: &Path, target: &Path) -> Result<()> {
+    let mut input = File::open(source)?;
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(target)?),
+        Compression::default(),
+    );
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: &str) -> AuditEntry {
+        AuditEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: "admin".to_string(),
+            username: "admin".to_string(),
+            action: action.to_string(),
+            resource: None,
+            success: true,
+            error_message: None,
+            timestamp: Utc::now(),
+            ip_address: None,
+            user_agent: None,
+            session_id: None,
+            prev_hash: None,
+            hash: None,
+            signature: None,
+        }
+    }
+
+    fn logger(dir: &Path, max_bytes: u64, max_files: Option<usize>) -> AuditFileLogger {
+        AuditFileLogger::new(AuditFileConfig {
+            path: dir.join("audit.log"),
+            max_bytes,
+            rotate_interval_hours: None,
+            retention_days: 90,
+            max_files,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_size_rotation_compresses_old_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let logger = logger(dir.path(), 200, None);
+
+        logger.append(&entry("first")).await.unwrap();
+        logger.append(&entry("second")).await.unwrap();
+
+        let rotated = logger.rotated_files().await.unwrap();
+        assert_eq!(rotated.len(), 1);
+        let mut contents = String::new();
+        GzDecoder::new(File::open(&rotated[0].0).unwrap())
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert!(contents.contains("\"first\""));
+
+        let active = std::fs::read_to_string(dir.path().join("audit.log")).unwrap();
+        assert!(active.contains("\"second\"") && !active.contains("\"first\""));
+    }
+
+    #[tokio::test]
+    async fn test_read_entries_spans_rotated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let logger = logger(dir.path(), u64::MAX, None);
+        assert!(logger.last_entry().await.unwrap().is_none());
+
+        logger.append(&entry("first")).await.unwrap();
+        logger.rotate().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        logger.append(&entry("second")).await.unwrap();
+        logger.rotate().await.unwrap();
+        assert_eq!(logger.last_entry().await.unwrap().unwrap().action, "second");
+        logger.append(&entry("third")).await.unwrap();
+
+        let actions: Vec<String> = logger
+            .read_entries()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(actions, ["first", "second", "third"]);
+        assert_eq!(logger.last_entry().await.unwrap().unwrap().action, "third");
+    }
+
+    #[tokio::test]
+    async fn test_manual_rotation_enforces_file_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let logger = logger(dir.path(), u64::MAX, Some(2));
+
+        for action in ["a", "b", "c"] {
+            logger.append(&entry(action)).await.unwrap();
+            let rotation = logger.rotate().await.unwrap();
+            assert!(rotation.rotated_to.is_some());
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        assert_eq!(logger.rotated_files().await.unwrap().len(), 2);
+
+        // Nothing to rotate when the active file is empty
+        assert!(logger.rotate().await.unwrap().rotated_to.is_none());
+    }
+}
@@ -0,0 +1,539 @@
+/*
+ * Bevy Debugger MCP Server - Audit Log Export
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, OnceCell};
+use tracing::error;
+
+use crate::audit_chain::AuditChain;
+use crate::audit_log::AuditFileLogger;
+use crate::error::{Error, Result};
+use crate::security::AuditEntry;
+
+/// Name audit events are reported under
+const APP_NAME: &str = "bevy-debugger-mcp";
+
+/// Syslog facility for security/authorization messages (authpriv)
+const SYSLOG_FACILITY_AUTHPRIV: u8 = 10;
+
+/// Destination for audit entries besides the in-memory log
+#[async_trait]
+pub trait AuditSink: Send + Sync + std::fmt::Debug {
+    fn name(&self) -> &str;
+
+    async fn write(&self, entry: &AuditEntry) -> Result<()>;
+}
+
+/// Where audit entries are exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuditSinkConfig {
+    /// RFC 5424 syslog over UDP, e.g. `syslog://siem.studio:514`
+    Syslog { address: String },
+    /// RFC 5424 syslog to a local socket, e.g. `syslog:///dev/log`
+    SyslogUnix { path: String },
+    /// JSON array batches POSTed to a bulk endpoint
+    Http { url: String },
+    /// The systemd journal's native protocol
+    Journald,
+}
+
+/// Parse a comma separated list of sink URLs
+pub fn parse_audit_sinks(spec: &str) -> Result<Vec<AuditSinkConfig>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|sink| !sink.is_empty())
+        .map(|sink| {
+            if sink == "journald" {
+                Ok(AuditSinkConfig::Journald)
+            } else if let Some(path) = sink.strip_prefix("syslog:///") {
+                Ok(AuditSinkConfig::SyslogUnix {
+                    path: format!("/{}", path),
+                })
+            } else if let Some(address) = sink.strip_prefix("syslog://") {
+                Ok(AuditSinkConfig::Syslog {
+                    address: address.to_string(),
+                })
+            } else if sink.starts_with("http://") || sink.starts_with("https://") {
+                Ok(AuditSinkConfig::Http {
+                    url: sink.to_string(),
+                })
+            } else {
+                Err(Error::Validation(format!("Unknown audit sink: {}", sink)))
+            }
+        })
+        .collect()
+}
+
+/// Create the sink for a config entry
+pub fn build_sink(
+    config: &AuditSinkConfig,
+    http_token: Option<String>,
+) -> Result<Arc<dyn AuditSink>> {
+    match config {
+        AuditSinkConfig::Syslog { address } => Ok(Arc::new(SyslogSink::udp(address))),
+        AuditSinkConfig::SyslogUnix { path } => Ok(Arc::new(SyslogSink::unix(path))),
+        AuditSinkConfig::Journald => Ok(Arc::new(JournaldSink::new())),
+        #[cfg(feature = "audit-http")]
+        AuditSinkConfig::Http { url } => Ok(Arc::new(HttpBulkSink::new(url, http_token))),
+        #[cfg(not(feature = "audit-http"))]
+        AuditSinkConfig::Http { url } => {
+            let _ = http_token;
+            Err(Error::Validation(format!(
+                "Audit sink {} requires the audit-http feature",
+                url
+            )))
+        }
+    }
+}
+
+/// Fans audit entries out to the rotating file and any export sinks
+#[derive(Debug, Default)]
+pub struct AuditLogger {
+    file: Option<Arc<AuditFileLogger>>,
+    sinks: Vec<Arc<dyn AuditSink>>,
+    /// Hash chain entries are sealed with in integrity mode
+    chain: Option<Mutex<AuditChain>>,
+}
+
+impl AuditLogger {
+    pub fn new(file: Option<Arc<AuditFileLogger>>) -> Self {
+        Self {
+            file,
+            sinks: Vec::new(),
+            chain: None,
+        }
+    }
+
+    /// Seal every entry into a hash chain before it is written
+    pub fn with_chain(mut self, chain: AuditChain) -> Self {
+        self.chain = Some(Mutex::new(chain));
+        self
+    }
+
+    pub fn integrity_enabled(&self) -> bool {
+        self.chain.is_some()
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// The local file, when audit persistence is enabled
+    pub fn file(&self) -> Option<&Arc<AuditFileLogger>> {
+        self.file.as_ref()
+    }
+
+    pub fn sink_names(&self) -> Vec<String> {
+        self.sinks
+            .iter()
+            .map(|sink| sink.name().to_string())
+            .collect()
+    }
+
+    /// Write an entry everywhere; a failing sink never blocks the others
+    ///
+    /// In integrity mode the entry is sealed first, and sealing and writing
+    /// the file happen under one lock so the file order matches the chain.
+    pub async fn record(&self, entry: &mut AuditEntry) {
+        match &self.chain {
+            Some(chain) => {
+                let mut chain = chain.lock().await;
+                if chain.needs_resume() {
+                    let last = match &self.file {
+                        Some(file) => file.last_entry().await.unwrap_or_else(|e| {
+                            error!(
+                                "Failed to read the audit log tail, starting a new chain: {}",
+                                e
+                            );
+                            None
+                        }),
+                        None => None,
+                    };
+                    chain.resume(last.as_ref());
+                }
+                if let Err(e) = chain.seal(entry) {
+                    error!("Failed to seal audit entry: {}", e);
+                }
+                self.append_to_file(entry).await;
+            }
+            None => self.append_to_file(entry).await,
+        }
+        for sink in &self.sinks {
+            if let Err(e) = sink.write(entry).await {
+                error!("Failed to export audit entry to {}: {}", sink.name(), e);
+            }
+        }
+    }
+
+    async fn append_to_file(&self, entry: &AuditEntry) {
+        if let Some(file) = &self.file {
+            if let Err(e) = file.append(entry).await {
+                error!("Failed to write audit log file: {}", e);
+            }
+        }
+    }
+}
+
+/// Syslog severity: failures are warnings, everything else informational
+fn severity(entry: &AuditEntry) -> u8 {
+    if entry.success {
+        6
+    } else {
+        4
+    }
+}
+
+/// Format an entry as an RFC 5424 message with the entry as JSON payload
+pub fn format_syslog(entry: &AuditEntry, hostname: &str) -> Result<String> {
+    let priority = SYSLOG_FACILITY_AUTHPRIV * 8 + severity(entry);
+    let msg_id: String = entry
+        .action
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(32)
+        .collect();
+    Ok(format!(
+        "<{}>1 {} {} {} {} {} - {}",
+        priority,
+        entry
+            .timestamp
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        hostname,
+        APP_NAME,
+        std::process::id(),
+        if msg_id.is_empty() { "-" } else { &msg_id },
+        serde_json::to_string(entry)?
+    ))
+}
+
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Syslog over UDP or a local datagram socket
+#[derive(Debug)]
+pub struct SyslogSink {
+    target: SyslogTarget,
+    name: String,
+    hostname: String,
+    udp: OnceCell<tokio::net::UdpSocket>,
+}
+
+#[derive(Debug)]
+enum SyslogTarget {
+    Udp(String),
+    Unix(String),
+}
+
+impl SyslogSink {
+    pub fn udp(address: &str) -> Self {
+        Self {
+            target: SyslogTarget::Udp(address.to_string()),
+            name: format!("syslog://{}", address),
+            hostname: hostname(),
+            udp: OnceCell::new(),
+        }
+    }
+
+    pub fn unix(path: &str) -> Self {
+        Self {
+            target: SyslogTarget::Unix(path.to_string()),
+            name: format!("syslog://{}", path),
+            hostname: hostname(),
+            udp: OnceCell::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn write(&self, entry: &AuditEntry) -> Result<()> {
+        let message = format_syslog(entry, &self.hostname)?;
+        match &self.target {
+            SyslogTarget::Udp(address) => {
+                let socket = self
+                    .udp
+                    .get_or_try_init(|| tokio::net::UdpSocket::bind("0.0.0.0:0"))
+                    .await?;
+                socket.send_to(message.as_bytes(), address.as_str()).await?;
+            }
+            #[cfg(unix)]
+            SyslogTarget::Unix(path) => {
+                let socket = tokio::net::UnixDatagram::unbound()?;
+                socket.send_to(message.as_bytes(), path).await?;
+            }
+            #[cfg(not(unix))]
+            SyslogTarget::Unix(path) => {
+                return Err(Error::Validation(format!(
+                    "Syslog socket {} is only supported on Unix",
+                    path
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format an entry as journald native protocol fields
+pub fn format_journald(entry: &AuditEntry) -> Result<String> {
+    // JSON escapes newlines, so every value fits the single-line field form
+    let message = format!(
+        "audit {} by {}: {}",
+        entry.action,
+        entry.user_id,
+        if entry.success { "success" } else { "failure" }
+    )
+    .replace('\n', " ");
+    let mut fields = format!(
+        "MESSAGE={}\nPRIORITY={}\nSYSLOG_IDENTIFIER={}\nAUDIT_ACTION={}\nAUDIT_USER={}\nAUDIT_SUCCESS={}\n",
+        message,
+        severity(entry),
+        APP_NAME,
+        entry.action.replace('\n', " "),
+        entry.user_id.replace('\n', " "),
+        entry.success
+    );
+    fields.push_str(&format!("AUDIT_ENTRY={}\n", serde_json::to_string(entry)?));
+    Ok(fields)
+}
+
+/// The systemd journal
+#[derive(Debug, Default)]
+pub struct JournaldSink;
+
+impl JournaldSink {
+    const SOCKET: &'static str = "/run/systemd/journal/socket";
+
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl AuditSink for JournaldSink {
+    fn name(&self) -> &str {
+        "journald"
+    }
+
+    async fn write(&self, entry: &AuditEntry) -> Result<()> {
+        let fields = format_journald(entry)?;
+        #[cfg(unix)]
+        {
+            let socket = tokio::net::UnixDatagram::unbound()?;
+            socket.send_to(fields.as_bytes(), Self::SOCKET).await?;
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = fields;
+            Err(Error::Validation(
+                "journald is only available on Linux".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "audit-http")]
+pub use http::HttpBulkSink;
+
+#[cfg(feature = "audit-http")]
+mod http {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use tokio::sync::mpsc;
+    use tracing::warn;
+
+    use super::AuditSink;
+    use crate::error::{Error, Result};
+    use crate::security::AuditEntry;
+
+    /// Entries sent per request
+    const BATCH_SIZE: usize = 100;
+
+    /// Longest an entry waits before its batch is sent
+    const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Entries kept while the endpoint is unreachable; the oldest are dropped first
+    const MAX_PENDING: usize = 10_000;
+
+    /// POSTs batches of entries as a JSON array, in the background
+    #[derive(Debug)]
+    pub struct HttpBulkSink {
+        url: String,
+        sender: mpsc::Sender<AuditEntry>,
+    }
+
+    impl HttpBulkSink {
+        pub fn new(url: &str, token: Option<String>) -> Self {
+            let (sender, receiver) = mpsc::channel(MAX_PENDING);
+            tokio::spawn(run(url.to_string(), token, receiver));
+            Self {
+                url: url.to_string(),
+                sender,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AuditSink for HttpBulkSink {
+        fn name(&self) -> &str {
+            &self.url
+        }
+
+        async fn write(&self, entry: &AuditEntry) -> Result<()> {
+            self.sender
+                .try_send(entry.clone())
+                .map_err(|e| Error::Connection(format!("Audit export queue unavailable: {}", e)))
+        }
+    }
+
+    async fn run(url: String, token: Option<String>, mut receiver: mpsc::Receiver<AuditEntry>) {
+        let client = reqwest::Client::new();
+        let mut pending: Vec<AuditEntry> = Vec::new();
+        let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+
+        loop {
+            let closed = tokio::select! {
+                entry = receiver.recv() => match entry {
+                    Some(entry) => {
+                        pending.push(entry);
+                        if pending.len() < BATCH_SIZE {
+                            continue;
+                        }
+                        false
+                    }
+                    None => true,
+                },
+                _ = ticker.tick() => false,
+            };
+
+            while !pending.is_empty() {
+                let batch_len = pending.len().min(BATCH_SIZE);
+                let mut request = client.post(&url).json(&pending[..batch_len]);
+                if let Some(token) = &token {
+                    request = request.bearer_auth(token);
+                }
+                match request.send().await.and_then(|r| r.error_for_status()) {
+                    Ok(_) => {
+                        pending.drain(..batch_len);
+                    }
+                    Err(e) => {
+                        warn!("Audit export to {} failed: {}", url, e);
+                        break;
+                    }
+                }
+            }
+            if pending.len() > MAX_PENDING {
+                let excess = pending.len() - MAX_PENDING;
+                pending.drain(..excess);
+                warn!("Dropped {} audit entries waiting for {}", excess, url);
+            }
+            if closed {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entry(success: bool) -> AuditEntry {
+        AuditEntry {
+            id: "id-1".to_string(),
+            user_id: "admin".to_string(),
+            username: "admin".to_string(),
+            action: "authentication".to_string(),
+            resource: None,
+            success,
+            error_message: (!success).then(|| "Invalid password".to_string()),
+            timestamp: Utc::now(),
+            ip_address: Some("10.0.0.5".to_string()),
+            user_agent: None,
+            session_id: None,
+            prev_hash: None,
+            hash: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_audit_sinks() {
+        let sinks =
+            parse_audit_sinks("syslog://siem:514, syslog:///dev/log,https://siem/bulk,journald")
+                .unwrap();
+        assert_eq!(
+            sinks,
+            vec![
+                AuditSinkConfig::Syslog {
+                    address: "siem:514".to_string()
+                },
+                AuditSinkConfig::SyslogUnix {
+                    path: "/dev/log".to_string()
+                },
+                AuditSinkConfig::Http {
+                    url: "https://siem/bulk".to_string()
+                },
+                AuditSinkConfig::Journald,
+            ]
+        );
+        assert!(parse_audit_sinks("ftp://siem").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_syslog_sink_sends_rfc5424_datagram() {
+        let receiver = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = SyslogSink::udp(&receiver.local_addr().unwrap().to_string());
+        sink.write(&entry(false)).await.unwrap();
+
+        let mut buf = [0u8; 4096];
+        let len = receiver.recv(&mut buf).await.unwrap();
+        let message = std::str::from_utf8(&buf[..len]).unwrap();
+        // authpriv.warning
+        assert!(message.starts_with("<84>1 "));
+        assert!(message.contains(" bevy-debugger-mcp "));
+        assert!(message.contains(" authentication - {"));
+        assert!(message.contains("Invalid password"));
+    }
+
+    #[test]
+    fn test_journald_fields_are_single_line() {
+        let mut failing = entry(false);
+        failing.error_message = Some("line one\nline two".to_string());
+        let fields = format_journald(&failing).unwrap();
+        assert!(fields.contains("PRIORITY=4\n"));
+        assert!(fields
+            .lines()
+            .all(|line| line.contains('=') && !line.is_empty()));
+        assert_eq!(fields.lines().count(), 7);
+    }
+}
@@ -22,63 +22,28 @@
 //! that require domain-specific knowledge for proper inspection and visualization.
 
 use serde_json::{json, Value};
-use crate::bevy_reflection::inspector::{CustomInspector, InspectedValue};
+use crate::bevy_reflection::inspector::{CustomInspector, InspectedValue, ValueMetadata};
 use crate::brp_messages::ComponentValue;
 use crate::error::{Error, Result};
 
+/// How many levels of nested Options, enums and collections are expanded
+pub const MAX_INSPECTION_DEPTH: usize = 4;
+
+/// List elements shown when no range is requested
+pub const DEFAULT_LIST_PAGE: usize = 10;
+
+/// Map entries shown when no range is requested
+pub const DEFAULT_MAP_PAGE: usize = 15;
+
 /// Inspector for Option<T> types - handles Some/None variants
+///
+/// Accepts `null`, Bevy's reflected `"None"` and `{"Some": value}`, as well
+/// as a bare value for Some.
 pub struct OptionInspector;
 
 impl CustomInspector for OptionInspector {
     fn inspect(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
-        match value {
-            Value::Null => {
-                Ok(InspectedValue {
-                    name: "Option".to_string(),
-                    raw_value: value.clone(),
-                    type_info: type_name.to_string(),
-                    display_value: "None".to_string(),
-                    inspectable: false,
-                    children: None,
-                })
-            }
-            Value::Object(obj) if obj.contains_key("Some") => {
-                let some_value = &obj["Some"];
-                Ok(InspectedValue {
-                    name: "Option".to_string(),
-                    raw_value: value.clone(),
-                    type_info: type_name.to_string(),
-                    display_value: format!("Some({})", self.format_value_preview(some_value)),
-                    inspectable: true,
-                    children: Some(vec![InspectedValue {
-                        name: "Some".to_string(),
-                        raw_value: some_value.clone(),
-                        type_info: self.infer_inner_type(some_value),
-                        display_value: self.format_value_preview(some_value),
-                        inspectable: self.is_value_inspectable(some_value),
-                        children: None,
-                    }]),
-                })
-            }
-            _ => {
-                // Assume non-null value is Some variant
-                Ok(InspectedValue {
-                    name: "Option".to_string(),
-                    raw_value: value.clone(),
-                    type_info: type_name.to_string(),
-                    display_value: format!("Some({})", self.format_value_preview(value)),
-                    inspectable: true,
-                    children: Some(vec![InspectedValue {
-                        name: "Some".to_string(),
-                        raw_value: value.clone(),
-                        type_info: self.infer_inner_type(value),
-                        display_value: self.format_value_preview(value),
-                        inspectable: self.is_value_inspectable(value),
-                        children: None,
-                    }]),
-                })
-            }
-        }
+        self.inspect_at(value, type_name, 0)
     }
 
     fn name(&self) -> &str {
@@ -94,34 +59,122 @@ impl CustomInspector for OptionInspector {
 }
 
 impl OptionInspector {
-    fn format_value_preview(&self, value: &Value) -> String {
-        match value {
-            Value::Null => "null".to_string(),
-            Value::Bool(b) => b.to_string(),
-            Value::Number(n) => n.to_string(),
-            Value::String(s) => format!("\"{}\"", s),
-            Value::Array(arr) => format!("[{} items]", arr.len()),
-            Value::Object(obj) => format!("{{ {} fields }}", obj.len()),
-        }
+    fn inspect_at(&self, value: &Value, type_name: &str, depth: usize) -> Result<InspectedValue> {
+        let some_value = match value {
+            Value::Null => None,
+            Value::String(s) if s == "None" => None,
+            Value::Object(obj) if obj.len() == 1 && obj.contains_key("Some") => Some(&obj["Some"]),
+            // Assume non-null value is Some variant
+            other => Some(other),
+        };
+
+        let Some(some_value) = some_value else {
+            return Ok(InspectedValue {
+                name: "Option".to_string(),
+                raw_value: value.clone(),
+                type_info: type_name.to_string(),
+                display_value: "None".to_string(),
+                inspectable: false,
+                children: None,
+                metadata: Some(ValueMetadata {
+                    variant: Some("None".to_string()),
+                    ..ValueMetadata::of(value)
+                }),
+            });
+        };
+
+        let inner_type = first_generic_arg(type_name);
+        Ok(InspectedValue {
+            name: "Option".to_string(),
+            raw_value: value.clone(),
+            type_info: type_name.to_string(),
+            display_value: format!("Some({})", preview(some_value)),
+            inspectable: true,
+            children: Some(vec![inspect_element("Some", some_value, &inner_type, depth + 1)]),
+            metadata: Some(ValueMetadata {
+                variant: Some("Some".to_string()),
+                ..ValueMetadata::of(value)
+            }),
+        })
     }
+}
 
-    fn infer_inner_type(&self, value: &Value) -> String {
-        match value {
-            Value::Null => "()".to_string(),
-            Value::Bool(_) => "bool".to_string(),
-            Value::Number(n) => {
-                if n.is_i64() { "i64".to_string() }
-                else if n.is_u64() { "u64".to_string() }
-                else { "f64".to_string() }
-            }
-            Value::String(_) => "String".to_string(),
-            Value::Array(_) => "Vec<T>".to_string(),
-            Value::Object(_) => "Struct".to_string(),
-        }
+/// Inspector for enums as Bevy's reflection serializes them
+///
+/// Unit variants are their name (`"Visible"`); variants with data are an
+/// object with the variant as its only key, holding a value for newtype
+/// variants, an array for tuple variants or an object for struct variants.
+pub struct EnumInspector;
+
+impl CustomInspector for EnumInspector {
+    fn inspect(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
+        self.inspect_at(value, type_name, 0)
     }
 
-    fn is_value_inspectable(&self, value: &Value) -> bool {
-        matches!(value, Value::Object(_) | Value::Array(_))
+    fn name(&self) -> &str {
+        "EnumInspector"
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        // Enums are recognised by TypeCategory::Enum or by their shape, not by name
+        Vec::new()
+    }
+}
+
+impl EnumInspector {
+    fn inspect_at(&self, value: &Value, type_name: &str, depth: usize) -> Result<InspectedValue> {
+        let variant_and_payload = match value {
+            Value::String(variant) => Some((variant.as_str(), None)),
+            Value::Object(obj) if is_variant_object(value) => {
+                obj.iter().next().map(|(variant, payload)| (variant.as_str(), Some(payload)))
+            }
+            _ => None,
+        };
+        let Some((variant, payload)) = variant_and_payload else {
+            return Err(Error::DebugError(
+                "Enum inspector expects a variant name or a single-variant object".to_string(),
+            ));
+        };
+
+        let (display_value, children) = match payload {
+            None => (variant.to_string(), None),
+            Some(Value::Object(fields)) => (
+                format!("{} {{ {} }}", variant, fields.keys().cloned().collect::<Vec<_>>().join(", ")),
+                Some(
+                    fields
+                        .iter()
+                        .map(|(field, val)| inspect_element(field.clone(), val, "unknown", depth + 1))
+                        .collect(),
+                ),
+            ),
+            Some(Value::Array(items)) => (
+                format!("{}({})", variant, items.iter().map(preview).collect::<Vec<_>>().join(", ")),
+                Some(
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| indexed_element(index.to_string(), index, item, "unknown", depth))
+                        .collect(),
+                ),
+            ),
+            Some(inner) => (
+                format!("{}({})", variant, preview(inner)),
+                Some(vec![inspect_element("0", inner, "unknown", depth + 1)]),
+            ),
+        };
+
+        Ok(InspectedValue {
+            name: "Enum".to_string(),
+            raw_value: value.clone(),
+            type_info: type_name.to_string(),
+            display_value,
+            inspectable: children.is_some(),
+            children,
+            metadata: Some(ValueMetadata {
+                variant: Some(variant.to_string()),
+                ..ValueMetadata::of(value)
+            }),
+        })
     }
 }
 
@@ -130,48 +183,7 @@ pub struct VecInspector;
 
 impl CustomInspector for VecInspector {
     fn inspect(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
-        if let Value::Array(arr) = value {
-            let mut children = Vec::new();
-            
-            // Inspect each element
-            for (index, item) in arr.iter().enumerate() {
-                children.push(InspectedValue {
-                    name: format!("[{}]", index),
-                    raw_value: item.clone(),
-                    type_info: self.infer_element_type(item),
-                    display_value: self.format_element(item),
-                    inspectable: self.is_value_inspectable(item),
-                    children: None,
-                });
-            }
-
-            // Show only first few elements for large arrays
-            let display_children = if children.len() > 10 {
-                let mut truncated = children.into_iter().take(10).collect::<Vec<_>>();
-                truncated.push(InspectedValue {
-                    name: format!("... and {} more", arr.len() - 10),
-                    raw_value: Value::Null,
-                    type_info: "...".to_string(),
-                    display_value: format!("({} total elements)", arr.len()),
-                    inspectable: false,
-                    children: None,
-                });
-                truncated
-            } else {
-                children
-            };
-
-            Ok(InspectedValue {
-                name: "Vec".to_string(),
-                raw_value: value.clone(),
-                type_info: type_name.to_string(),
-                display_value: format!("Vec<T>[{}]", arr.len()),
-                inspectable: !arr.is_empty(),
-                children: if arr.is_empty() { None } else { Some(display_children) },
-            })
-        } else {
-            Err(Error::DebugError("Vec inspector expects array value".to_string()))
-        }
+        self.inspect_range(value, type_name, 0, DEFAULT_LIST_PAGE)
     }
 
     fn name(&self) -> &str {
@@ -183,39 +195,49 @@ impl CustomInspector for VecInspector {
             "alloc::vec::Vec".to_string(),
             "Vec".to_string(),
             "std::vec::Vec".to_string(),
+            "alloc::collections::vec_deque::VecDeque".to_string(),
+            "std::collections::HashSet".to_string(),
+            "alloc::collections::btree::set::BTreeSet".to_string(),
+            "smallvec::SmallVec".to_string(),
         ]
     }
 }
 
 impl VecInspector {
-    fn infer_element_type(&self, value: &Value) -> String {
-        match value {
-            Value::Null => "()".to_string(),
-            Value::Bool(_) => "bool".to_string(),
-            Value::Number(n) => {
-                if n.is_i64() { "i64".to_string() }
-                else if n.is_u64() { "u64".to_string() }
-                else { "f64".to_string() }
-            }
-            Value::String(_) => "String".to_string(),
-            Value::Array(_) => "Vec<T>".to_string(),
-            Value::Object(_) => "T".to_string(),
-        }
+    /// Elements `offset..offset + limit`, each with its index and element type
+    pub fn inspect_range(&self, value: &ComponentValue, type_name: &str, offset: usize, limit: usize) -> Result<InspectedValue> {
+        self.inspect_range_at(value, type_name, offset, limit, 0)
     }
 
-    fn format_element(&self, value: &Value) -> String {
-        match value {
-            Value::Null => "null".to_string(),
-            Value::Bool(b) => b.to_string(),
-            Value::Number(n) => n.to_string(),
-            Value::String(s) => format!("\"{}\"", if s.len() > 30 { format!("{}...", &s[..30]) } else { s.clone() }),
-            Value::Array(arr) => format!("[{} items]", arr.len()),
-            Value::Object(obj) => format!("{{ {} }}", obj.keys().take(3).cloned().collect::<Vec<_>>().join(", ")),
+    fn inspect_range_at(&self, value: &Value, type_name: &str, offset: usize, limit: usize, depth: usize) -> Result<InspectedValue> {
+        let Value::Array(arr) = value else {
+            return Err(Error::DebugError("Vec inspector expects array value".to_string()));
+        };
+
+        let element_type = first_generic_arg(type_name);
+        let mut children: Vec<InspectedValue> = arr
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(limit)
+            .map(|(index, item)| indexed_element(format!("[{}]", index), index, item, &element_type, depth))
+            .collect();
+
+        // Large lists show one page plus a marker for the rest
+        let remaining = arr.len().saturating_sub(offset.saturating_add(children.len()));
+        if remaining > 0 {
+            children.push(more_marker(remaining, format!("({} total elements)", arr.len())));
         }
-    }
 
-    fn is_value_inspectable(&self, value: &Value) -> bool {
-        matches!(value, Value::Object(_) | Value::Array(_))
+        Ok(InspectedValue {
+            name: "Vec".to_string(),
+            raw_value: value.clone(),
+            type_info: type_name.to_string(),
+            display_value: format!("Vec<T>[{}]", arr.len()),
+            inspectable: !arr.is_empty(),
+            children: if arr.is_empty() { None } else { Some(children) },
+            metadata: Some(ValueMetadata::of(value)),
+        })
     }
 }
 
@@ -224,48 +246,7 @@ pub struct HashMapInspector;
 
 impl CustomInspector for HashMapInspector {
     fn inspect(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
-        if let Value::Object(obj) = value {
-            let mut children = Vec::new();
-            
-            // Inspect each key-value pair
-            for (key, val) in obj {
-                children.push(InspectedValue {
-                    name: format!("[\"{}\"]", key),
-                    raw_value: json!({"key": key, "value": val}),
-                    type_info: format!("({}, {})", self.infer_key_type(key), self.infer_value_type(val)),
-                    display_value: format!("{}: {}", key, self.format_value(val)),
-                    inspectable: self.is_value_inspectable(val),
-                    children: None,
-                });
-            }
-
-            // Show only first few entries for large maps
-            let display_children = if children.len() > 15 {
-                let mut truncated = children.into_iter().take(15).collect::<Vec<_>>();
-                truncated.push(InspectedValue {
-                    name: format!("... and {} more", obj.len() - 15),
-                    raw_value: Value::Null,
-                    type_info: "...".to_string(),
-                    display_value: format!("({} total entries)", obj.len()),
-                    inspectable: false,
-                    children: None,
-                });
-                truncated
-            } else {
-                children
-            };
-
-            Ok(InspectedValue {
-                name: "HashMap".to_string(),
-                raw_value: value.clone(),
-                type_info: type_name.to_string(),
-                display_value: format!("HashMap<K,V>[{}]", obj.len()),
-                inspectable: !obj.is_empty(),
-                children: if obj.is_empty() { None } else { Some(display_children) },
-            })
-        } else {
-            Err(Error::DebugError("HashMap inspector expects object value".to_string()))
-        }
+        self.inspect_range(value, type_name, 0, DEFAULT_MAP_PAGE)
     }
 
     fn name(&self) -> &str {
@@ -277,43 +258,273 @@ impl CustomInspector for HashMapInspector {
             "std::collections::HashMap".to_string(),
             "HashMap".to_string(),
             "std::collections::hash_map::HashMap".to_string(),
+            "alloc::collections::btree::map::BTreeMap".to_string(),
+            "bevy_platform::collections::hash_map::HashMap".to_string(),
         ]
     }
 }
 
 impl HashMapInspector {
-    fn infer_key_type(&self, _key: &str) -> String {
-        "String".to_string() // JSON keys are always strings
-    }
-
-    fn infer_value_type(&self, value: &Value) -> String {
-        match value {
-            Value::Null => "()".to_string(),
-            Value::Bool(_) => "bool".to_string(),
-            Value::Number(n) => {
-                if n.is_i64() { "i64".to_string() }
-                else if n.is_u64() { "u64".to_string() }
-                else { "f64".to_string() }
+    /// Entries `offset..offset + limit` in key order, each with its key, position and value type
+    pub fn inspect_range(&self, value: &ComponentValue, type_name: &str, offset: usize, limit: usize) -> Result<InspectedValue> {
+        self.inspect_range_at(value, type_name, offset, limit, 0)
+    }
+
+    fn inspect_range_at(&self, value: &Value, type_name: &str, offset: usize, limit: usize, depth: usize) -> Result<InspectedValue> {
+        let Value::Object(obj) = value else {
+            return Err(Error::DebugError("HashMap inspector expects object value".to_string()));
+        };
+
+        let value_type = generic_args(type_name).into_iter().nth(1).unwrap_or_else(|| "unknown".to_string());
+        let mut children: Vec<InspectedValue> = obj
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(limit)
+            .map(|(index, (key, val))| {
+                let mut child = indexed_element(format!("[\"{}\"]", key), index, val, &value_type, depth);
+                child.display_value = format!("{}: {}", key, preview(val));
+                if let Some(metadata) = child.metadata.as_mut() {
+                    metadata.key = Some(key.clone());
+                }
+                child
+            })
+            .collect();
+
+        // Large maps show one page plus a marker for the rest
+        let remaining = obj.len().saturating_sub(offset.saturating_add(children.len()));
+        if remaining > 0 {
+            children.push(more_marker(remaining, format!("({} total entries)", obj.len())));
+        }
+
+        Ok(InspectedValue {
+            name: "HashMap".to_string(),
+            raw_value: value.clone(),
+            type_info: type_name.to_string(),
+            display_value: format!("HashMap<K,V>[{}]", obj.len()),
+            inspectable: !obj.is_empty(),
+            children: if obj.is_empty() { None } else { Some(children) },
+            metadata: Some(ValueMetadata::of(value)),
+        })
+    }
+}
+
+/// Inspect `value` of type `type_name` as a child named `name`, `depth` levels down
+///
+/// Options, enums, lists, maps and structs are expanded until
+/// [`MAX_INSPECTION_DEPTH`]; anything else, or anything deeper, is a leaf.
+/// Pass `"unknown"` when the type isn't known and the shape will be used.
+pub fn inspect_element(name: impl Into<String>, value: &Value, type_name: &str, depth: usize) -> InspectedValue {
+    // A dynamically-typed value is inspected as its concrete type
+    if depth < MAX_INSPECTION_DEPTH {
+        if let Some((concrete_type, inner)) = dynamic_value(value, type_name) {
+            let mut inspected = inspect_element(name, inner, concrete_type, depth + 1);
+            inspected.raw_value = value.clone();
+            if let Some(metadata) = inspected.metadata.as_mut() {
+                metadata.dynamic_type = Some(concrete_type.to_string());
             }
-            Value::String(_) => "String".to_string(),
-            Value::Array(_) => "Vec<T>".to_string(),
-            Value::Object(_) => "V".to_string(),
+            return inspected;
         }
     }
 
-    fn format_value(&self, value: &Value) -> String {
-        match value {
-            Value::Null => "null".to_string(),
-            Value::Bool(b) => b.to_string(),
-            Value::Number(n) => n.to_string(),
-            Value::String(s) => format!("\"{}\"", if s.len() > 20 { format!("{}...", &s[..20]) } else { s.clone() }),
-            Value::Array(arr) => format!("[{} items]", arr.len()),
-            Value::Object(obj) => format!("{{ {} fields }}", obj.len()),
+    let expanded = if depth >= MAX_INSPECTION_DEPTH {
+        None
+    } else {
+        match base_type_name(type_name) {
+            "Option" => OptionInspector.inspect_at(value, type_name, depth).ok(),
+            "Vec" | "VecDeque" | "HashSet" | "BTreeSet" | "SmallVec" => {
+                VecInspector.inspect_range_at(value, type_name, 0, DEFAULT_LIST_PAGE, depth).ok()
+            }
+            "HashMap" | "BTreeMap" => {
+                HashMapInspector.inspect_range_at(value, type_name, 0, DEFAULT_MAP_PAGE, depth).ok()
+            }
+            // A missing value of unknown type is almost always an Option
+            "unknown" if value.is_null() => OptionInspector.inspect_at(value, "Option<T>", depth).ok(),
+            _ if value.is_array() => VecInspector.inspect_range_at(value, type_name, 0, DEFAULT_LIST_PAGE, depth).ok(),
+            _ if is_variant_object(value) => EnumInspector.inspect_at(value, type_name, depth).ok(),
+            _ => match value {
+                Value::Object(fields) => Some(InspectedValue {
+                    name: String::new(),
+                    raw_value: value.clone(),
+                    type_info: known_or_inferred_type(type_name, value),
+                    display_value: preview(value),
+                    inspectable: !fields.is_empty(),
+                    children: Some(
+                        fields
+                            .iter()
+                            .map(|(field, val)| inspect_element(field.clone(), val, "unknown", depth + 1))
+                            .collect(),
+                    ),
+                    metadata: Some(ValueMetadata::of(value)),
+                }),
+                _ => None,
+            },
+        }
+    };
+
+    let mut inspected = expanded.unwrap_or_else(|| InspectedValue {
+        name: String::new(),
+        raw_value: value.clone(),
+        type_info: known_or_inferred_type(type_name, value),
+        display_value: preview(value),
+        inspectable: matches!(value, Value::Object(_) | Value::Array(_)),
+        children: None,
+        metadata: Some(ValueMetadata::of(value)),
+    });
+    inspected.name = name.into();
+    if let Some(metadata) = inspected.metadata.as_mut() {
+        if metadata.generic_args.is_empty() {
+            metadata.generic_args = generic_args(type_name);
+        }
+    }
+    inspected
+}
+
+/// Whether values of `type_name` only have a concrete type at runtime, as
+/// with `Box<dyn Reflect>` or Bevy's `DynamicStruct`
+pub fn is_dynamic_type(type_name: &str) -> bool {
+    type_name.contains("dyn ")
+        || matches!(
+            base_type_name(type_name),
+            "DynamicStruct"
+                | "DynamicTupleStruct"
+                | "DynamicTuple"
+                | "DynamicEnum"
+                | "DynamicList"
+                | "DynamicArray"
+                | "DynamicMap"
+                | "DynamicSet"
+        )
+}
+
+/// Concrete type and value of a dynamically-typed value
+///
+/// BRP serializes reflected trait objects as `{"type::Path": value}`; the
+/// key is taken as a type when it is a path or the declared type is dynamic.
+fn dynamic_value<'a>(value: &'a Value, type_name: &str) -> Option<(&'a str, &'a Value)> {
+    let Value::Object(obj) = value else {
+        return None;
+    };
+    if obj.len() != 1 {
+        return None;
+    }
+    let (key, inner) = obj.iter().next()?;
+    let is_type = !key.is_empty()
+        && !key.contains(char::is_whitespace)
+        && (key.contains("::") || is_dynamic_type(type_name));
+    is_type.then_some((key.as_str(), inner))
+}
+
+/// Whether `value` looks like an enum variant with data: an object whose only
+/// key is a CamelCase variant name
+pub fn is_variant_object(value: &Value) -> bool {
+    match value {
+        Value::Object(obj) if obj.len() == 1 => obj
+            .keys()
+            .next()
+            .is_some_and(|key| key.starts_with(|c: char| c.is_ascii_uppercase())),
+        _ => false,
+    }
+}
+
+/// Top-level generic arguments, e.g. `["String", "Vec<u8>"]` for `HashMap<String, Vec<u8>>`
+pub fn generic_args(type_name: &str) -> Vec<String> {
+    let (Some(start), Some(end)) = (type_name.find('<'), type_name.rfind('>')) else {
+        return Vec::new();
+    };
+    if end <= start {
+        return Vec::new();
+    }
+
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut nesting = 0i32;
+    for c in type_name[start + 1..end].chars() {
+        match c {
+            '<' | '(' | '[' => nesting += 1,
+            '>' | ')' | ']' => nesting -= 1,
+            ',' if nesting == 0 => {
+                args.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        args.push(current.trim().to_string());
+    }
+    args
+}
+
+/// `Vec` for `alloc::vec::Vec<f32>`
+fn base_type_name(type_name: &str) -> &str {
+    let path = type_name.split('<').next().unwrap_or(type_name).trim();
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+fn first_generic_arg(type_name: &str) -> String {
+    generic_args(type_name).into_iter().next().unwrap_or_else(|| "unknown".to_string())
+}
+
+/// A collection element, with its position recorded in its metadata
+fn indexed_element(name: String, index: usize, value: &Value, type_name: &str, depth: usize) -> InspectedValue {
+    let mut child = inspect_element(name, value, type_name, depth + 1);
+    if let Some(metadata) = child.metadata.as_mut() {
+        metadata.index = Some(index);
+    }
+    child
+}
+
+/// Placeholder for the `remaining` elements not shown
+fn more_marker(remaining: usize, display_value: String) -> InspectedValue {
+    InspectedValue {
+        name: format!("... and {} more", remaining),
+        raw_value: Value::Null,
+        type_info: "...".to_string(),
+        display_value,
+        inspectable: false,
+        children: None,
+        metadata: None,
+    }
+}
+
+fn known_or_inferred_type(type_name: &str, value: &Value) -> String {
+    if type_name == "unknown" {
+        infer_type(value)
+    } else {
+        type_name.to_string()
+    }
+}
+
+fn infer_type(value: &Value) -> String {
+    match value {
+        Value::Null => "()".to_string(),
+        Value::Bool(_) => "bool".to_string(),
+        Value::Number(n) => {
+            if n.is_i64() { "i64".to_string() }
+            else if n.is_u64() { "u64".to_string() }
+            else { "f64".to_string() }
         }
+        Value::String(_) => "String".to_string(),
+        Value::Array(_) => "Vec<T>".to_string(),
+        Value::Object(_) => "Struct".to_string(),
     }
+}
 
-    fn is_value_inspectable(&self, value: &Value) -> bool {
-        matches!(value, Value::Object(_) | Value::Array(_))
+/// Short one-line rendering of `value`
+fn preview(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if s.chars().count() > 30 => {
+            format!("\"{}...\"", s.chars().take(30).collect::<String>())
+        }
+        Value::String(s) => format!("\"{}\"", s),
+        Value::Array(arr) => format!("[{} items]", arr.len()),
+        Value::Object(obj) => format!("{{ {} fields }}", obj.len()),
     }
 }
 
@@ -354,6 +565,7 @@ impl CustomInspector for EntityInspector {
             display_value: display,
             inspectable: false, // Entities themselves aren't inspectable via reflection
             children: None,
+            metadata: None,
         })
     }
 
@@ -394,6 +606,7 @@ impl CustomInspector for ColorInspector {
                         display_value: format!("{:.3}", r),
                         inspectable: false,
                         children: None,
+                        metadata: None,
                     });
                     
                     kids.push(InspectedValue {
@@ -403,6 +616,7 @@ impl CustomInspector for ColorInspector {
                         display_value: format!("{:.3}", g),
                         inspectable: false,
                         children: None,
+                        metadata: None,
                     });
                     
                     kids.push(InspectedValue {
@@ -412,6 +626,7 @@ impl CustomInspector for ColorInspector {
                         display_value: format!("{:.3}", b),
                         inspectable: false,
                         children: None,
+                        metadata: None,
                     });
                     
                     if a != 1.0 {
@@ -422,6 +637,7 @@ impl CustomInspector for ColorInspector {
                             display_value: format!("{:.3}", a),
                             inspectable: false,
                             children: None,
+                            metadata: None,
                         });
                     }
 
@@ -448,6 +664,7 @@ impl CustomInspector for ColorInspector {
             display_value: color_info,
             inspectable: children.is_some(),
             children,
+            metadata: None,
         })
     }
 
@@ -479,10 +696,99 @@ impl ColorInspector {
     }
 }
 
+/// Signature of the closure behind an [`FnInspector`]
+pub type InspectFn = dyn Fn(&ComponentValue, &str) -> Result<InspectedValue> + Send + Sync;
+
+/// Inspector built from a closure, for types that do not warrant their own
+/// [`CustomInspector`] impl
+///
+/// ```
+/// use bevy_debugger_mcp::bevy_reflection::FnInspector;
+///
+/// let inspector = FnInspector::builder("InventoryInspector")
+///     .for_type("my_game::Inventory")
+///     .display_with(|value| format!("{} items", value["items"].as_array().map_or(0, Vec::len)));
+/// bevy_debugger_mcp::bevy_reflection::register_inspector(inspector);
+/// ```
+pub struct FnInspector {
+    name: String,
+    supported_types: Vec<String>,
+    inspect: Box<InspectFn>,
+}
+
+impl FnInspector {
+    pub fn builder(name: impl Into<String>) -> FnInspectorBuilder {
+        FnInspectorBuilder {
+            name: name.into(),
+            supported_types: Vec::new(),
+        }
+    }
+}
+
+impl CustomInspector for FnInspector {
+    fn inspect(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
+        (self.inspect)(value, type_name)
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        self.supported_types.clone()
+    }
+}
+
+/// Builder for [`FnInspector`]
+pub struct FnInspectorBuilder {
+    name: String,
+    supported_types: Vec<String>,
+}
+
+impl FnInspectorBuilder {
+    /// Use the inspector for `type_path`; generic arguments are ignored when matching
+    pub fn for_type(mut self, type_path: impl Into<String>) -> Self {
+        self.supported_types.push(type_path.into());
+        self
+    }
+
+    /// Inspect values with `inspect`
+    pub fn inspect_with<F>(self, inspect: F) -> FnInspector
+    where
+        F: Fn(&ComponentValue, &str) -> Result<InspectedValue> + Send + Sync + 'static,
+    {
+        FnInspector {
+            name: self.name,
+            supported_types: self.supported_types,
+            inspect: Box::new(inspect),
+        }
+    }
+
+    /// Show values as the string `display` returns, keeping the generic
+    /// field breakdown out of the result
+    pub fn display_with<F>(self, display: F) -> FnInspector
+    where
+        F: Fn(&ComponentValue) -> String + Send + Sync + 'static,
+    {
+        self.inspect_with(move |value, type_name| {
+            Ok(InspectedValue {
+                name: "root".to_string(),
+                raw_value: value.clone(),
+                type_info: type_name.to_string(),
+                display_value: display(value),
+                inspectable: false,
+                children: None,
+                metadata: Some(ValueMetadata::of(value)),
+            })
+        })
+    }
+}
+
 /// Factory for creating all default custom inspectors
 pub fn create_default_inspectors() -> Vec<Box<dyn CustomInspector + Send + Sync>> {
     vec![
         Box::new(OptionInspector),
+        Box::new(EnumInspector),
         Box::new(VecInspector),
         Box::new(HashMapInspector),
         Box::new(EntityInspector),
@@ -571,6 +877,59 @@ mod tests {
         assert_eq!(inspector.rgba_to_hex(0.0, 0.0, 1.0, 0.5), "0000FF80");
     }
 
+    #[test]
+    fn test_enum_inspector_variants() {
+        let inspector = EnumInspector;
+
+        let unit = inspector.inspect(&json!("Hidden"), "Visibility").unwrap();
+        assert_eq!(unit.display_value, "Hidden");
+        assert_eq!(unit.metadata.unwrap().variant.as_deref(), Some("Hidden"));
+        assert!(unit.children.is_none());
+
+        let tuple = inspector.inspect(&json!({"Rgba": [1.0, 0.5]}), "Tint").unwrap();
+        assert_eq!(tuple.display_value, "Rgba(1.0, 0.5)");
+        let fields = tuple.children.unwrap();
+        assert_eq!(fields[1].metadata.as_ref().unwrap().index, Some(1));
+
+        let with_fields = inspector
+            .inspect(&json!({"Moving": {"speed": 2.0, "target": null}}), "State")
+            .unwrap();
+        let fields = with_fields.children.unwrap();
+        let target = fields.iter().find(|f| f.name == "target").unwrap();
+        assert_eq!(target.display_value, "None");
+        assert_eq!(target.metadata.as_ref().unwrap().variant.as_deref(), Some("None"));
+
+        assert!(inspector.inspect(&json!({"a": 1, "b": 2}), "State").is_err());
+    }
+
+    #[test]
+    fn test_collection_elements_carry_metadata() {
+        let value = json!([{"Some": 3}, "None", {"Some": 7}]);
+        let result = VecInspector
+            .inspect_range(&value, "Vec<Option<u32>>", 1, 1)
+            .unwrap();
+        let children = result.children.unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name, "[1]");
+        assert_eq!(children[0].type_info, "Option<u32>");
+        let metadata = children[0].metadata.as_ref().unwrap();
+        assert_eq!((metadata.index, metadata.variant.as_deref()), (Some(1), Some("None")));
+        assert_eq!(children[1].name, "... and 1 more");
+
+        let map = json!({"player": [1, 2], "enemy": []});
+        let result = HashMapInspector
+            .inspect(&map, "HashMap<String, Vec<u8>>")
+            .unwrap();
+        let children = result.children.unwrap();
+        let entry = children.iter().find(|c| c.name == "[\"enemy\"]").unwrap();
+        assert_eq!(entry.type_info, "Vec<u8>");
+        let metadata = entry.metadata.as_ref().unwrap();
+        assert_eq!(metadata.key.as_deref(), Some("enemy"));
+        assert_eq!((metadata.kind.as_str(), metadata.len), ("array", Some(0)));
+
+        assert_eq!(generic_args("HashMap<(u8, u8), Vec<f32>>"), vec!["(u8, u8)", "Vec<f32>"]);
+    }
+
     #[test]
     fn test_large_vec_truncation() {
         let inspector = VecInspector;
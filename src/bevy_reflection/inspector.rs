@@ -39,16 +39,20 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn, error};
 
 use crate::error::{Error, Result};
 
-fn default_type_id() -> TypeId {
+pub(crate) fn default_type_id() -> TypeId {
     TypeId::of::<()>()
 }
 use crate::brp_messages::ComponentValue;
+use crate::bevy_reflection::custom_inspectors::{
+    create_default_inspectors, generic_args, inspect_element, is_dynamic_type, is_variant_object,
+    EnumInspector,
+};
 
 /// Reflection-based component inspector for Bevy components
 #[derive(Clone)]
@@ -92,6 +96,29 @@ pub struct FieldMetadata {
     pub is_reflected: bool,
     /// Custom inspector for this field
     pub inspector_name: Option<String>,
+    /// Generic arguments of the field type, e.g. `["f32"]` for `CooldownOf<f32>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generic_args: Vec<String>,
+    /// Whether the field holds a value whose type is only known at runtime,
+    /// such as `Box<dyn Reflect>`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dynamic: bool,
+}
+
+impl FieldMetadata {
+    /// A reflected field with no custom inspector
+    pub fn new(name: impl Into<String>, type_name: impl Into<String>, index: usize) -> Self {
+        let type_name = type_name.into();
+        Self {
+            name: name.into(),
+            generic_args: generic_args(&type_name),
+            dynamic: is_dynamic_type(&type_name),
+            type_name,
+            index,
+            is_reflected: true,
+            inspector_name: None,
+        }
+    }
 }
 
 /// Category of reflected type
@@ -136,6 +163,53 @@ pub struct InspectedValue {
     pub inspectable: bool,
     /// Child values for nested structures
     pub children: Option<Vec<InspectedValue>>,
+    /// Variant, position and size, for enums, Options and collection elements
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<ValueMetadata>,
+}
+
+/// Shape of an inspected value and where it sits in its parent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValueMetadata {
+    /// JSON kind: null, bool, number, string, array or object
+    pub kind: String,
+    /// Element count of an array or object
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub len: Option<usize>,
+    /// Position in the parent list, map or tuple variant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// Key in the parent map
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Enum or Option variant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// Generic arguments of the value's declared type
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generic_args: Vec<String>,
+    /// Concrete type of a dynamically-typed value such as a `Box<dyn Reflect>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dynamic_type: Option<String>,
+}
+
+impl ValueMetadata {
+    /// Kind and length of `value`, with no position
+    pub fn of(value: &Value) -> Self {
+        let (kind, len) = match value {
+            Value::Null => ("null", None),
+            Value::Bool(_) => ("bool", None),
+            Value::Number(_) => ("number", None),
+            Value::String(_) => ("string", None),
+            Value::Array(arr) => ("array", Some(arr.len())),
+            Value::Object(obj) => ("object", Some(obj.len())),
+        };
+        Self {
+            kind: kind.to_string(),
+            len,
+            ..Default::default()
+        }
+    }
 }
 
 /// Trait for custom component inspectors
@@ -150,6 +224,44 @@ pub trait CustomInspector: Any + Send + Sync {
     fn supported_types(&self) -> Vec<String>;
 }
 
+/// Inspectors registered by [`register_inspector`], shared by every
+/// [`BevyReflectionInspector`] created afterwards
+fn registered_inspectors() -> &'static std::sync::RwLock<Vec<Arc<dyn CustomInspector>>> {
+    static REGISTERED: OnceLock<std::sync::RwLock<Vec<Arc<dyn CustomInspector>>>> = OnceLock::new();
+    REGISTERED.get_or_init(Default::default)
+}
+
+/// Register an inspector for every reflection inspector this process creates
+///
+/// Lets a game crate plug in inspectors for its own component types without
+/// patching this crate; call it before starting the server. An inspector with
+/// the same name as a built-in one replaces it.
+pub fn register_inspector(inspector: impl CustomInspector) {
+    info!("Registered custom inspector: {}", inspector.name());
+    let mut registered = registered_inspectors()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    registered.retain(|existing| existing.name() != inspector.name());
+    registered.push(Arc::new(inspector));
+}
+
+/// A registered inspector as held by one [`BevyReflectionInspector`]
+struct SharedInspector(Arc<dyn CustomInspector>);
+
+impl CustomInspector for SharedInspector {
+    fn inspect(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
+        self.0.inspect(value, type_name)
+    }
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn supported_types(&self) -> Vec<String> {
+        self.0.supported_types()
+    }
+}
+
 /// Result of reflection-based diffing
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReflectionDiffResult {
@@ -224,14 +336,28 @@ impl BevyReflectionInspector {
         inspector
     }
 
-    /// Register default custom inspectors for common Bevy types
+    /// Register default custom inspectors for common Bevy types, then those
+    /// added with [`register_inspector`]
     fn register_default_inspectors(&mut self) {
-        // Note: Implementation would register inspectors here
-        // This is a placeholder for the structure
         debug!("Registering default custom inspectors");
+        // Nothing else holds the map yet
+        if let Some(inspectors) = Arc::get_mut(&mut self.custom_inspectors) {
+            let inspectors = inspectors.get_mut();
+            let mut defaults = create_default_inspectors();
+            defaults.push(Box::new(TransformInspector));
+            let registered = registered_inspectors()
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            defaults.extend(registered.iter().map(|inspector| {
+                Box::new(SharedInspector(Arc::clone(inspector))) as Box<dyn CustomInspector + Send + Sync>
+            }));
+            for inspector in defaults {
+                inspectors.insert(inspector.name().to_string(), inspector);
+            }
+        }
     }
 
-    /// Register a custom inspector for specific types
+    /// Register a custom inspector for specific types on this inspector only
     pub async fn register_custom_inspector(&self, inspector: Box<dyn CustomInspector + Send + Sync>) {
         let inspector_name = inspector.name().to_string();
         let mut inspectors = self.custom_inspectors.write().await;
@@ -266,12 +392,10 @@ impl BevyReflectionInspector {
             TypeInfo::Struct(struct_info) => {
                 let mut field_metadata = Vec::new();
                 for (index, field) in struct_info.iter().enumerate() {
+                    // If it's in the registry, it should be reflected
                     field_metadata.push(FieldMetadata {
-                        name: field.name().to_string(),
-                        type_name: field.type_path().to_string(),
-                        index,
-                        is_reflected: true, // If it's in the registry, it should be reflected
                         inspector_name: self.find_inspector_for_type(field.type_path()).await,
+                        ..FieldMetadata::new(field.name(), field.type_path(), index)
                     });
                 }
                 (TypeCategory::Struct, field_metadata)
@@ -280,11 +404,8 @@ impl BevyReflectionInspector {
                 let mut field_metadata = Vec::new();
                 for (index, field) in tuple_info.iter().enumerate() {
                     field_metadata.push(FieldMetadata {
-                        name: format!("field_{}", index),
-                        type_name: field.type_path().to_string(),
-                        index,
-                        is_reflected: true,
                         inspector_name: self.find_inspector_for_type(field.type_path()).await,
+                        ..FieldMetadata::new(format!("field_{}", index), field.type_path(), index)
                     });
                 }
                 (TypeCategory::TupleStruct, field_metadata)
@@ -334,11 +455,12 @@ impl BevyReflectionInspector {
         }
     }
 
-    /// Find custom inspector for a type
+    /// Find custom inspector for a type, ignoring generic arguments
     async fn find_inspector_for_type(&self, type_path: &str) -> Option<String> {
+        let base_path = type_path.split('<').next().unwrap_or(type_path).trim();
         let inspectors = self.custom_inspectors.read().await;
         for inspector in inspectors.values() {
-            if inspector.supported_types().contains(&type_path.to_string()) {
+            if inspector.supported_types().iter().any(|t| t == type_path || t == base_path) {
                 return Some(inspector.name().to_string());
             }
         }
@@ -353,8 +475,13 @@ impl BevyReflectionInspector {
         let mut field_values = HashMap::new();
         let mut errors = Vec::new();
 
-        // Try custom inspector first
-        if let Some(inspector_name) = &metadata.fields.get(0).and_then(|f| f.inspector_name.as_ref()) {
+        // Try custom inspector first: one named by the metadata, or one for the type
+        let inspector_name = match metadata.fields.first().and_then(|f| f.inspector_name.clone()) {
+            Some(name) => Some(name),
+            None if matches!(metadata.type_category, TypeCategory::Enum) => Some("EnumInspector".to_string()),
+            None => self.find_inspector_for_type(component_type).await,
+        };
+        if let Some(inspector_name) = &inspector_name {
             let inspectors = self.custom_inspectors.read().await;
             if let Some(inspector) = inspectors.get(inspector_name.as_str()) {
                 match inspector.inspect(component_value, component_type) {
@@ -390,21 +517,38 @@ impl BevyReflectionInspector {
     }
 
     /// Generic value inspection for fallback
+    ///
+    /// Fields and elements are expanded by shape: enum variants, `null`
+    /// Options and nested collections get children and [`ValueMetadata`].
     pub async fn inspect_value_generic(&self, value: &ComponentValue, type_name: &str) -> Result<InspectedValue> {
-        let inspected = match value {
+        if is_variant_object(value) {
+            return EnumInspector.inspect(value, type_name);
+        }
+
+        // Declared field types carry generic arguments and mark dynamic fields
+        let field_types: HashMap<String, String> = self
+            .reflection_cache
+            .read()
+            .await
+            .get(type_name)
+            .map(|metadata| {
+                metadata
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.clone(), field.type_name.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut inspected = match value {
             Value::Object(obj) => {
-                let mut children = Vec::new();
-                for (key, val) in obj {
-                    let child = Box::pin(self.inspect_value_generic(val, "unknown")).await?;
-                    children.push(InspectedValue {
-                        name: key.clone(),
-                        raw_value: val.clone(),
-                        type_info: self.infer_type_from_value(val),
-                        display_value: self.format_display_value(val),
-                        inspectable: self.is_value_inspectable(val),
-                        children: None,
-                    });
-                }
+                let children = obj
+                    .iter()
+                    .map(|(key, val)| {
+                        let field_type = field_types.get(key).map_or("unknown", String::as_str);
+                        inspect_element(key.clone(), val, field_type, 1)
+                    })
+                    .collect();
                 
                 InspectedValue {
                     name: "root".to_string(),
@@ -413,21 +557,21 @@ impl BevyReflectionInspector {
                     display_value: format!("{} {{ {} fields }}", type_name, obj.len()),
                     inspectable: true,
                     children: Some(children),
+                    metadata: Some(ValueMetadata::of(value)),
                 }
             }
             Value::Array(arr) => {
-                let mut children = Vec::new();
-                for (i, val) in arr.iter().enumerate() {
-                    let child = Box::pin(self.inspect_value_generic(val, "unknown")).await?;
-                    children.push(InspectedValue {
-                        name: format!("[{}]", i),
-                        raw_value: val.clone(),
-                        type_info: self.infer_type_from_value(val),
-                        display_value: self.format_display_value(val),
-                        inspectable: self.is_value_inspectable(val),
-                        children: None,
-                    });
-                }
+                let children = arr
+                    .iter()
+                    .enumerate()
+                    .map(|(i, val)| {
+                        let mut child = inspect_element(format!("[{}]", i), val, "unknown", 1);
+                        if let Some(metadata) = child.metadata.as_mut() {
+                            metadata.index = Some(i);
+                        }
+                        child
+                    })
+                    .collect();
                 
                 InspectedValue {
                     name: "root".to_string(),
@@ -436,6 +580,7 @@ impl BevyReflectionInspector {
                     display_value: format!("Array[{}]", arr.len()),
                     inspectable: true,
                     children: Some(children),
+                    metadata: Some(ValueMetadata::of(value)),
                 }
             }
             _ => {
@@ -446,10 +591,14 @@ impl BevyReflectionInspector {
                     display_value: self.format_display_value(value),
                     inspectable: false,
                     children: None,
+                    metadata: Some(ValueMetadata::of(value)),
                 }
             }
         };
 
+        if let Some(metadata) = inspected.metadata.as_mut() {
+            metadata.generic_args = generic_args(type_name);
+        }
         Ok(inspected)
     }
 
@@ -485,11 +634,6 @@ impl BevyReflectionInspector {
         }
     }
 
-    /// Check if value can be further inspected
-    fn is_value_inspectable(&self, value: &Value) -> bool {
-        matches!(value, Value::Object(_) | Value::Array(_))
-    }
-
     /// Get or create metadata for a component type
     async fn get_or_create_metadata(&self, component_type: &str) -> Result<ReflectionMetadata> {
         let mut cache = self.reflection_cache.write().await;
@@ -710,6 +854,14 @@ impl BevyReflectionInspector {
         self.reflection_cache.read().await.clone()
     }
 
+    /// Cache metadata for a type, such as one built from the game's registry schema
+    pub async fn cache_metadata(&self, metadata: ReflectionMetadata) {
+        self.reflection_cache
+            .write()
+            .await
+            .insert(metadata.type_name.clone(), metadata);
+    }
+
     /// Clear the reflection cache
     pub async fn clear_cache(&self) {
         let mut cache = self.reflection_cache.write().await;
@@ -754,6 +906,7 @@ impl CustomInspector for TransformInspector {
                     display_value: self.format_vec3(translation),
                     inspectable: true,
                     children: None,
+                    metadata: None,
                 });
             }
 
@@ -766,6 +919,7 @@ impl CustomInspector for TransformInspector {
                     display_value: self.format_quat(rotation),
                     inspectable: true,
                     children: None,
+                    metadata: None,
                 });
             }
 
@@ -778,6 +932,7 @@ impl CustomInspector for TransformInspector {
                     display_value: self.format_vec3(scale),
                     inspectable: true,
                     children: None,
+                    metadata: None,
                 });
             }
 
@@ -788,6 +943,7 @@ impl CustomInspector for TransformInspector {
                 display_value: "Transform Component".to_string(),
                 inspectable: true,
                 children: Some(children),
+                metadata: None,
             })
         } else {
             Err(Error::DebugError("Transform component should be an object".to_string()))
@@ -900,6 +1056,59 @@ mod tests {
         assert!(diff_result.summary.added_fields > 0);
     }
 
+    #[tokio::test]
+    async fn test_generic_and_dynamic_fields() {
+        let inspector = BevyReflectionInspector::new();
+        inspector
+            .cache_metadata(ReflectionMetadata {
+                type_name: "game::Ability".to_string(),
+                type_id: default_type_id(),
+                is_reflected: true,
+                fields: vec![
+                    FieldMetadata::new("cooldown", "game::CooldownOf<f32>", 0),
+                    FieldMetadata::new("effect", "alloc::boxed::Box<dyn bevy_reflect::Reflect>", 1),
+                ],
+                type_category: TypeCategory::Struct,
+                type_info: None,
+                last_updated: 0,
+            })
+            .await;
+
+        let value = json!({
+            "cooldown": {"remaining": 1.5},
+            "effect": {"game::Burn": {"damage": 3}},
+        });
+        let result = inspector.inspect_component("game::Ability", &value).await.unwrap();
+        let children = result.field_values["root"].children.as_ref().unwrap();
+        let field = |name: &str| children.iter().find(|c| c.name == name).unwrap();
+
+        let cooldown = field("cooldown").metadata.as_ref().unwrap();
+        assert_eq!(cooldown.generic_args, vec!["f32"]);
+        let effect = field("effect");
+        assert_eq!(effect.type_info, "game::Burn");
+        assert_eq!(effect.metadata.as_ref().unwrap().dynamic_type.as_deref(), Some("game::Burn"));
+        assert_eq!(effect.children.as_ref().unwrap()[0].name, "damage");
+    }
+
+    #[tokio::test]
+    async fn test_registered_inspector_applies_to_new_inspectors() {
+        use crate::bevy_reflection::custom_inspectors::FnInspector;
+
+        register_inspector(
+            FnInspector::builder("SecretInspector")
+                .for_type("game::Secret")
+                .display_with(|_| "<redacted>".to_string()),
+        );
+        let inspector = BevyReflectionInspector::new();
+
+        let result = inspector
+            .inspect_component("game::Secret<u8>", &json!({"key": 42}))
+            .await
+            .unwrap();
+        assert_eq!(result.field_values["root"].display_value, "<redacted>");
+        assert!(result.errors.is_empty());
+    }
+
     #[test]
     fn test_change_severity_assessment() {
         let inspector = BevyReflectionInspector::new();
@@ -22,22 +22,26 @@
 //! - Core reflection inspector and metadata structures
 //! - Custom inspectors for Bevy-specific types
 //! - TypeRegistry integration tools
-//! - Reflection-based query optimization
+//! - Reflection-based query optimization and type indexing
 
 pub mod inspector;
 pub mod custom_inspectors;
 pub mod type_registry_tools;
 pub mod reflection_queries;
+pub mod query_planner;
+pub mod reflection_index;
 
 // Re-export main types from inspector module
 pub use inspector::{
     BevyReflectionInspector, ReflectionMetadata, FieldMetadata, TypeCategory,
-    ReflectionInspectionResult, InspectedValue, CustomInspector,
+    ReflectionInspectionResult, InspectedValue, ValueMetadata, CustomInspector,
     ReflectionDiffResult, FieldDiff, ChangeType, ChangeSeverity, DiffSummary,
-    TransformInspector,
+    TransformInspector, register_inspector,
 };
 
 // Export submodule types
 pub use custom_inspectors::*;
 pub use type_registry_tools::*;
-pub use reflection_queries::*;
\ No newline at end of file
+pub use reflection_queries::*;
+pub use query_planner::*;
+pub use reflection_index::*;
\ No newline at end of file
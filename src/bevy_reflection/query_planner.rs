@@ -0,0 +1,422 @@
+/*
+ * Bevy Debugger MCP Server - Reflection Query Planner
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Query Planning for Reflection-based Searches
+//!
+//! Orders a reflection query's filters so component-presence checks run
+//! before value predicates, and estimates how many entities each step
+//! touches and how many components it has to deserialize.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::bevy_reflection::reflection_queries::{
+    FieldValueFilter, FilterOperation, ReflectionQuery,
+};
+use crate::brp_messages::EntityData;
+
+/// Components per entity assumed when estimating reflection work
+const TYPICAL_COMPONENTS_PER_ENTITY: usize = 8;
+
+/// Share of entities assumed to have a given component
+const PRESENCE_SELECTIVITY: f64 = 0.5;
+
+/// Kind of work a plan step does, in the order steps run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStepKind {
+    /// Fetch entities from the game, with required components sent along in the BRP query
+    Fetch,
+    /// Keep entities that have (or lack) a component, without reading its value
+    PresenceFilter,
+    /// Compare a component field against a value, deserializing the component
+    ValuePredicate,
+    /// Inspect the remaining entities' components through reflection
+    Reflect,
+}
+
+/// One step of a query plan with its estimated work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub kind: PlanStepKind,
+    pub description: String,
+    pub estimated_entities_in: usize,
+    pub estimated_entities_out: usize,
+    pub estimated_components_deserialized: usize,
+    /// Relative cost; only meaningful compared to other plans
+    pub cost: f64,
+}
+
+/// How a reflection query will run, for `explain` output and execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryPlan {
+    pub steps: Vec<PlanStep>,
+    /// Components fetched entities must have, pushed into the BRP query
+    pub required_components: Vec<String>,
+    /// Components fetched entities must not have, pushed into the BRP query
+    pub excluded_components: Vec<String>,
+    /// Indices into the query's field value filters, in evaluation order
+    pub filter_order: Vec<usize>,
+    pub estimated_entities_touched: usize,
+    pub estimated_components_deserialized: usize,
+    pub estimated_cost: f64,
+}
+
+impl QueryPlan {
+    /// Human-readable plan, one line per step
+    pub fn explain(&self) -> String {
+        let mut lines = vec![format!(
+            "Estimated cost {:.1}: {} entities touched, {} components deserialized",
+            self.estimated_cost,
+            self.estimated_entities_touched,
+            self.estimated_components_deserialized
+        )];
+        for (i, step) in self.steps.iter().enumerate() {
+            lines.push(format!(
+                "{}. {} ({} -> {} entities, {} components, cost {:.1})",
+                i + 1,
+                step.description,
+                step.estimated_entities_in,
+                step.estimated_entities_out,
+                step.estimated_components_deserialized,
+                step.cost
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl FieldValueFilter {
+    /// Whether the filter only checks that a component is present or absent
+    pub fn is_presence_check(&self) -> bool {
+        self.field_path.is_empty()
+            && matches!(
+                self.operation,
+                FilterOperation::Exists | FilterOperation::NotExists
+            )
+    }
+
+    /// Estimated share of entities with the component that pass the filter
+    fn selectivity(&self) -> f64 {
+        match self.operation {
+            FilterOperation::Equals => 0.1,
+            FilterOperation::NotEquals => 0.9,
+            FilterOperation::GreaterThan | FilterOperation::LessThan => 0.33,
+            FilterOperation::Contains | FilterOperation::StartsWith | FilterOperation::EndsWith => {
+                0.25
+            }
+            FilterOperation::Exists if self.is_presence_check() => PRESENCE_SELECTIVITY,
+            FilterOperation::NotExists if self.is_presence_check() => 1.0 - PRESENCE_SELECTIVITY,
+            FilterOperation::Exists => 0.9,
+            FilterOperation::NotExists => 0.1,
+        }
+    }
+
+    /// Estimated cost of evaluating the filter on one entity
+    fn cost_per_entity(&self) -> f64 {
+        if self.is_presence_check() {
+            return 1.0;
+        }
+        let depth = self.field_path.split('.').count() as f64;
+        let string_op = matches!(
+            self.operation,
+            FilterOperation::Contains | FilterOperation::StartsWith | FilterOperation::EndsWith
+        );
+        2.0 + 0.5 * depth + if string_op { 1.0 } else { 0.0 }
+    }
+
+    /// Whether `entity` passes the filter
+    pub fn matches(&self, entity: &EntityData) -> bool {
+        let component = entity.components.iter().find_map(|(name, value)| {
+            let matches = name == &self.component_type
+                || name.ends_with(&format!("::{}", self.component_type));
+            matches.then_some(value)
+        });
+        let field = component.and_then(|value| field_at(value, &self.field_path));
+
+        match (&self.operation, field) {
+            (FilterOperation::Exists, field) => field.is_some(),
+            (FilterOperation::NotExists, field) => field.is_none(),
+            (FilterOperation::Equals, field) => field == Some(&self.value),
+            (FilterOperation::NotEquals, field) => field.is_some() && field != Some(&self.value),
+            (FilterOperation::GreaterThan, Some(field)) => {
+                matches!((field.as_f64(), self.value.as_f64()), (Some(a), Some(b)) if a > b)
+            }
+            (FilterOperation::LessThan, Some(field)) => {
+                matches!((field.as_f64(), self.value.as_f64()), (Some(a), Some(b)) if a < b)
+            }
+            (FilterOperation::Contains, Some(Value::Array(items))) => items.contains(&self.value),
+            (FilterOperation::Contains, Some(Value::String(s))) => {
+                self.value.as_str().is_some_and(|v| s.contains(v))
+            }
+            (FilterOperation::StartsWith, Some(Value::String(s))) => {
+                self.value.as_str().is_some_and(|v| s.starts_with(v))
+            }
+            (FilterOperation::EndsWith, Some(Value::String(s))) => {
+                self.value.as_str().is_some_and(|v| s.ends_with(v))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Follow a dotted field path into a component value
+///
+/// Array elements are addressed by index, and `x`/`y`/`z`/`w` also address
+/// the elements of vectors serialized as arrays.
+fn field_at<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    path.split('.')
+        .try_fold(value, |current, segment| match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => {
+                let index = match segment {
+                    "x" => 0,
+                    "y" => 1,
+                    "z" => 2,
+                    "w" => 3,
+                    _ => segment.parse().ok()?,
+                };
+                items.get(index)
+            }
+            _ => None,
+        })
+}
+
+/// Plan `query` assuming its base query returns about `estimated_entities` entities
+///
+/// Presence checks are pushed into the BRP query and run first; value
+/// predicates follow, ordered by cost over the share of entities they drop
+/// so cheap, selective predicates run before expensive, permissive ones.
+pub fn plan_query(query: &ReflectionQuery, estimated_entities: usize) -> QueryPlan {
+    let filters = &query.reflection_params.field_value_filters;
+    let mut required_components = Vec::new();
+    let mut excluded_components = Vec::new();
+    for filter in filters {
+        let component = filter.component_type.clone();
+        let list = match filter.operation {
+            FilterOperation::NotExists if filter.is_presence_check() => &mut excluded_components,
+            _ => &mut required_components,
+        };
+        if !list.contains(&component) {
+            list.push(component);
+        }
+    }
+
+    let mut filter_order: Vec<usize> = (0..filters.len()).collect();
+    filter_order.sort_by(|&a, &b| {
+        let rank = |f: &FieldValueFilter| {
+            (
+                !f.is_presence_check(),
+                f.cost_per_entity() / (1.0 - f.selectivity()).max(0.01),
+            )
+        };
+        let (a, b) = (rank(&filters[a]), rank(&filters[b]));
+        a.0.cmp(&b.0).then(a.1.total_cmp(&b.1))
+    });
+
+    let mut steps = Vec::new();
+    let pushed_down = required_components.len() + excluded_components.len();
+    let fetched =
+        (estimated_entities as f64 * PRESENCE_SELECTIVITY.powi(pushed_down as i32)).ceil() as usize;
+    steps.push(PlanStep {
+        kind: PlanStepKind::Fetch,
+        description: if pushed_down == 0 {
+            format!("Fetch '{}'", query.base_query)
+        } else {
+            format!(
+                "Fetch '{}' with {} required and {} excluded components",
+                query.base_query,
+                required_components.len(),
+                excluded_components.len()
+            )
+        },
+        estimated_entities_in: estimated_entities,
+        estimated_entities_out: fetched,
+        estimated_components_deserialized: 0,
+        cost: estimated_entities as f64 * 0.1,
+    });
+
+    let mut remaining = fetched as f64;
+    for &index in &filter_order {
+        let filter = &filters[index];
+        let presence = filter.is_presence_check();
+        // Pushed-down checks are re-applied locally, and the fetch already paid for them
+        let selectivity = if presence { 1.0 } else { filter.selectivity() };
+        let entities_in = remaining.ceil() as usize;
+        remaining *= selectivity;
+        steps.push(PlanStep {
+            kind: if presence {
+                PlanStepKind::PresenceFilter
+            } else {
+                PlanStepKind::ValuePredicate
+            },
+            description: if presence {
+                format!("{:?} {}", filter.operation, filter.component_type)
+            } else {
+                format!(
+                    "{}.{} {:?} {}",
+                    filter.component_type, filter.field_path, filter.operation, filter.value
+                )
+            },
+            estimated_entities_in: entities_in,
+            estimated_entities_out: remaining.ceil() as usize,
+            estimated_components_deserialized: if presence { 0 } else { entities_in },
+            cost: entities_in as f64 * filter.cost_per_entity(),
+        });
+    }
+
+    let reflected = (remaining.ceil() as usize).min(query.limits.max_entities);
+    let components =
+        reflected * TYPICAL_COMPONENTS_PER_ENTITY.min(query.limits.max_components_per_entity);
+    let params = &query.reflection_params;
+    steps.push(PlanStep {
+        kind: PlanStepKind::Reflect,
+        description: format!(
+            "Reflect up to {} entities{}",
+            query.limits.max_entities,
+            if params.deep_inspection {
+                " (deep)"
+            } else {
+                ""
+            }
+        ),
+        estimated_entities_in: reflected,
+        estimated_entities_out: reflected,
+        estimated_components_deserialized: if params.include_reflection {
+            components
+        } else {
+            0
+        },
+        cost: components as f64
+            * match (params.include_reflection, params.deep_inspection) {
+                (false, _) => 0.5,
+                (true, false) => 10.0,
+                (true, true) => 20.0,
+            },
+    });
+
+    QueryPlan {
+        estimated_entities_touched: fetched,
+        estimated_components_deserialized: steps
+            .iter()
+            .map(|s| s.estimated_components_deserialized)
+            .sum(),
+        estimated_cost: steps.iter().map(|s| s.cost).sum(),
+        steps,
+        required_components,
+        excluded_components,
+        filter_order,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bevy_reflection::reflection_queries::{QueryLimits, ReflectionQueryParams};
+    use serde_json::json;
+
+    fn filter(
+        component: &str,
+        path: &str,
+        operation: FilterOperation,
+        value: Value,
+    ) -> FieldValueFilter {
+        FieldValueFilter {
+            component_type: component.to_string(),
+            field_path: path.to_string(),
+            operation,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_presence_filters_run_before_value_predicates() {
+        let query = ReflectionQuery {
+            base_query: "list all entities".to_string(),
+            reflection_params: ReflectionQueryParams {
+                field_value_filters: vec![
+                    filter("Name", "", FilterOperation::StartsWith, json!("enemy")),
+                    filter("Health", "current", FilterOperation::Equals, json!(0)),
+                    filter("Player", "", FilterOperation::NotExists, Value::Null),
+                ],
+                ..ReflectionQueryParams::default()
+            },
+            limits: QueryLimits::default(),
+        };
+
+        let plan = plan_query(&query, 1000);
+        assert_eq!(plan.filter_order, vec![2, 1, 0]);
+        assert_eq!(plan.required_components, vec!["Name", "Health"]);
+        assert_eq!(plan.excluded_components, vec!["Player"]);
+
+        let kinds: Vec<_> = plan.steps.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PlanStepKind::Fetch,
+                PlanStepKind::PresenceFilter,
+                PlanStepKind::ValuePredicate,
+                PlanStepKind::ValuePredicate,
+                PlanStepKind::Reflect,
+            ]
+        );
+        // Three components pushed down: 1000 * 0.5^3
+        assert_eq!(plan.estimated_entities_touched, 125);
+        assert_eq!(plan.steps[3].estimated_entities_in, 13);
+        assert!(plan.explain().contains("Health.current Equals 0"));
+    }
+
+    #[test]
+    fn test_filters_match_entities() {
+        let entity = EntityData {
+            id: 7,
+            components: [
+                (
+                    "bevy_transform::components::transform::Transform".to_string(),
+                    json!({"translation": [12.0, 0.0, -3.0]}),
+                ),
+                ("game::Name".to_string(), json!("enemy_7")),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        assert!(filter(
+            "Transform",
+            "translation.x",
+            FilterOperation::GreaterThan,
+            json!(10.0)
+        )
+        .matches(&entity));
+        assert!(!filter(
+            "Transform",
+            "translation.z",
+            FilterOperation::GreaterThan,
+            json!(0.0)
+        )
+        .matches(&entity));
+        assert!(filter("Name", "", FilterOperation::StartsWith, json!("enemy")).matches(&entity));
+        assert!(filter("Health", "", FilterOperation::NotExists, Value::Null).matches(&entity));
+        assert!(
+            !filter("Health", "current", FilterOperation::NotEquals, json!(0)).matches(&entity)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+/*
+ * Bevy Debugger MCP Server - Reflection Query Index
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Type Index for Reflection Queries
+//!
+//! Keeps which entities have each component type and which component types
+//! have each field, updated from the entity listings reflection queries
+//! already fetch. Lookups then cost a set intersection instead of a walk
+//! over every entity and component value.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::brp_messages::{EntityData, EntityId};
+
+/// Deepest nested field path recorded per component type
+const MAX_FIELD_DEPTH: usize = 4;
+
+/// Index of component types and fields over the entities seen so far
+#[derive(Debug, Default, Clone)]
+pub struct ReflectionIndex {
+    entities_by_type: HashMap<String, HashSet<EntityId>>,
+    types_by_entity: HashMap<EntityId, HashSet<String>>,
+    /// Field path to JSON kind, by component type
+    fields_by_type: HashMap<String, BTreeMap<String, String>>,
+    /// Component types by field path
+    types_by_field: HashMap<String, HashSet<String>>,
+    /// Whether an unfiltered listing has been indexed, so absent entities are known gone
+    complete: bool,
+}
+
+/// Size of a [`ReflectionIndex`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReflectionIndexStats {
+    pub entities: usize,
+    pub component_types: usize,
+    pub fields: usize,
+    pub complete: bool,
+}
+
+impl ReflectionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index one entity, replacing what was indexed for it before
+    pub fn index_entity(&mut self, entity: &EntityData) {
+        let types: HashSet<String> = entity.components.keys().cloned().collect();
+        let previous = self.types_by_entity.remove(&entity.id).unwrap_or_default();
+        for removed in previous.difference(&types) {
+            self.unlink(entity.id, removed);
+        }
+        for added in types.difference(&previous) {
+            self.entities_by_type
+                .entry(added.clone())
+                .or_default()
+                .insert(entity.id);
+        }
+        for (type_name, value) in &entity.components {
+            self.index_fields(type_name, value);
+        }
+        self.types_by_entity.insert(entity.id, types);
+    }
+
+    /// Index a listing from the game
+    ///
+    /// A `complete` listing was not filtered, so indexed entities missing
+    /// from it have been despawned and are dropped.
+    pub fn index_listing(&mut self, entities: &[EntityData], complete: bool) {
+        for entity in entities {
+            self.index_entity(entity);
+        }
+        if complete {
+            let listed: HashSet<EntityId> = entities.iter().map(|e| e.id).collect();
+            let gone: Vec<EntityId> = self
+                .types_by_entity
+                .keys()
+                .filter(|id| !listed.contains(id))
+                .copied()
+                .collect();
+            for id in gone {
+                self.remove_entity(id);
+            }
+            self.complete = true;
+        }
+    }
+
+    pub fn remove_entity(&mut self, id: EntityId) {
+        for type_name in self.types_by_entity.remove(&id).unwrap_or_default() {
+            self.unlink(id, &type_name);
+        }
+    }
+
+    fn unlink(&mut self, id: EntityId, type_name: &str) {
+        if let Some(entities) = self.entities_by_type.get_mut(type_name) {
+            entities.remove(&id);
+            if entities.is_empty() {
+                self.entities_by_type.remove(type_name);
+            }
+        }
+    }
+
+    /// Record field paths of `type_name`, walking the value only when it
+    /// has top-level fields not seen before
+    fn index_fields(&mut self, type_name: &str, value: &Value) {
+        let known = self.fields_by_type.get(type_name);
+        let up_to_date = match value {
+            Value::Object(obj) => {
+                known.is_some_and(|fields| obj.keys().all(|key| fields.contains_key(key)))
+            }
+            _ => known.is_some(),
+        };
+        if up_to_date {
+            return;
+        }
+
+        let mut found = Vec::new();
+        collect_fields(value, "", 0, &mut found);
+        let fields = self
+            .fields_by_type
+            .entry(type_name.to_string())
+            .or_default();
+        for (path, kind) in found {
+            self.types_by_field
+                .entry(path.clone())
+                .or_default()
+                .insert(type_name.to_string());
+            fields.entry(path).or_insert(kind);
+        }
+    }
+
+    /// Whether an unfiltered listing has been indexed
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn entity_count(&self) -> usize {
+        self.types_by_entity.len()
+    }
+
+    /// Entities with a component of `type_name`
+    pub fn count_with(&self, type_name: &str) -> usize {
+        self.entities_by_type.get(type_name).map_or(0, HashSet::len)
+    }
+
+    /// Entities with every component in `required` and none in `excluded`, sorted
+    pub fn entities_with(&self, required: &[String], excluded: &[String]) -> Vec<EntityId> {
+        let mut sets: Vec<&HashSet<EntityId>> = Vec::with_capacity(required.len());
+        for type_name in required {
+            match self.entities_by_type.get(type_name) {
+                Some(set) => sets.push(set),
+                None => return Vec::new(),
+            }
+        }
+        sets.sort_by_key(|set| set.len());
+
+        let has_excluded = |id: &EntityId| {
+            excluded
+                .iter()
+                .any(|t| self.entities_by_type.get(t).is_some_and(|s| s.contains(id)))
+        };
+        let mut ids: Vec<EntityId> = match sets.split_first() {
+            Some((smallest, rest)) => smallest
+                .iter()
+                .filter(|id| rest.iter().all(|set| set.contains(id)) && !has_excluded(id))
+                .copied()
+                .collect(),
+            None => self
+                .types_by_entity
+                .keys()
+                .filter(|id| !has_excluded(id))
+                .copied()
+                .collect(),
+        };
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Component types with a field at `path` (dotted for nested fields), sorted
+    pub fn types_with_field(&self, path: &str) -> Vec<String> {
+        let mut types: Vec<String> = self
+            .types_by_field
+            .get(path)
+            .map(|types| types.iter().cloned().collect())
+            .unwrap_or_default();
+        types.sort();
+        types
+    }
+
+    /// JSON kind of `path` in `type_name`, such as `number` or `object`
+    pub fn field_kind(&self, type_name: &str, path: &str) -> Option<&str> {
+        self.fields_by_type
+            .get(type_name)?
+            .get(path)
+            .map(String::as_str)
+    }
+
+    /// Indexed component types containing any of `patterns`, all types when empty
+    pub fn types_matching(&self, patterns: &[String]) -> HashSet<String> {
+        self.entities_by_type
+            .keys()
+            .filter(|type_name| {
+                patterns.is_empty() || patterns.iter().any(|p| type_name.contains(p.as_str()))
+            })
+            .cloned()
+            .collect()
+    }
+
+    pub fn stats(&self) -> ReflectionIndexStats {
+        ReflectionIndexStats {
+            entities: self.entity_count(),
+            component_types: self.entities_by_type.len(),
+            fields: self.types_by_field.len(),
+            complete: self.complete,
+        }
+    }
+}
+
+fn collect_fields(value: &Value, prefix: &str, depth: usize, found: &mut Vec<(String, String)>) {
+    let Value::Object(obj) = value else {
+        return;
+    };
+    if depth >= MAX_FIELD_DEPTH {
+        return;
+    }
+    for (key, child) in obj {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        collect_fields(child, &path, depth + 1, found);
+        found.push((path, json_kind(child).to_string()));
+    }
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entity(id: EntityId, components: &[(&str, Value)]) -> EntityData {
+        EntityData {
+            id,
+            components: components
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_index_tracks_listings_incrementally() {
+        let transform = json!({"translation": {"x": 1.0, "y": 0.0}});
+        let mut index = ReflectionIndex::new();
+        index.index_listing(
+            &[
+                entity(
+                    1,
+                    &[("Transform", transform.clone()), ("Player", json!({}))],
+                ),
+                entity(2, &[("Transform", transform.clone())]),
+                entity(3, &[("Enemy", json!({"hp": 5}))]),
+            ],
+            true,
+        );
+
+        assert_eq!(
+            index.entities_with(&["Transform".to_string()], &[]),
+            vec![1, 2]
+        );
+        assert_eq!(
+            index.entities_with(&["Transform".to_string()], &["Player".to_string()]),
+            vec![2]
+        );
+        assert_eq!(index.types_with_field("translation.x"), vec!["Transform"]);
+        assert_eq!(index.field_kind("Enemy", "hp"), Some("number"));
+
+        // Entity 1 lost Player, entity 3 despawned; a filtered listing drops nothing
+        index.index_listing(&[entity(1, &[("Transform", transform.clone())])], false);
+        assert_eq!(index.count_with("Player"), 0);
+        assert_eq!(index.entity_count(), 3);
+        index.index_listing(
+            &[
+                entity(1, &[("Transform", transform.clone())]),
+                entity(2, &[("Transform", transform)]),
+            ],
+            true,
+        );
+        assert_eq!(index.entity_count(), 2);
+        assert!(index.entities_with(&["Enemy".to_string()], &[]).is_empty());
+    }
+}
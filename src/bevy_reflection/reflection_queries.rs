@@ -23,14 +23,16 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::bevy_reflection::inspector::{BevyReflectionInspector, ReflectionMetadata, TypeCategory};
+use crate::bevy_reflection::query_planner::{plan_query, QueryPlan};
+use crate::bevy_reflection::reflection_index::{ReflectionIndex, ReflectionIndexStats};
 use crate::bevy_reflection::type_registry_tools::{TypeRegistryManager, TypeQuery};
-use crate::brp_messages::{BrpRequest, EntityData, ComponentValue};
+use crate::brp_messages::{BrpRequest, EntityData, EntityId, ComponentValue, QueryFilter};
 use crate::brp_client::BrpClient;
 use crate::error::{Error, Result};
 
@@ -45,6 +47,10 @@ pub struct ReflectionQueryEngine {
     query_cache: Arc<RwLock<HashMap<String, CachedQueryResult>>>,
     /// Query statistics
     stats: Arc<RwLock<QueryStats>>,
+    /// Entities each base query returned last time, for planning
+    entity_estimates: Arc<RwLock<HashMap<String, usize>>>,
+    /// Component types and fields of the entities fetched so far
+    index: Arc<RwLock<ReflectionIndex>>,
 }
 
 /// Cached query result
@@ -80,6 +86,9 @@ pub struct ReflectionQueryResult {
     pub discovered_types: HashMap<String, ReflectionMetadata>,
     /// Suggestions for query improvement
     pub suggestions: Vec<String>,
+    /// Plan the query ran with, when `explain` was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<QueryPlan>,
 }
 
 /// Entity data enhanced with reflection information
@@ -194,6 +203,8 @@ pub struct ReflectionQueryParams {
     pub component_type_filters: Vec<String>,
     /// Filter by field value patterns
     pub field_value_filters: Vec<FieldValueFilter>,
+    /// Include the chosen query plan in the result
+    pub explain: bool,
 }
 
 /// Field value filter for reflection queries
@@ -244,9 +255,48 @@ impl ReflectionQueryEngine {
             type_registry: TypeRegistryManager::new(),
             query_cache: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(QueryStats::default())),
+            entity_estimates: Arc::new(RwLock::new(HashMap::new())),
+            index: Arc::new(RwLock::new(ReflectionIndex::new())),
         }
     }
 
+    /// Plan `query` without running it
+    pub async fn explain(&self, query: &ReflectionQuery) -> QueryPlan {
+        let known = self
+            .entity_estimates
+            .read()
+            .await
+            .get(&query.base_query)
+            .copied();
+        let estimated_entities = match known {
+            Some(estimate) => estimate,
+            None => {
+                let index = self.index.read().await;
+                if index.is_complete() {
+                    index.entity_count()
+                } else {
+                    query.limits.max_entities
+                }
+            }
+        };
+        plan_query(query, estimated_entities)
+    }
+
+    /// Entities seen with every component in `required` and none in
+    /// `excluded`, answered from the index without asking the game
+    pub async fn indexed_entities_with(&self, required: &[String], excluded: &[String]) -> Vec<EntityId> {
+        self.index.read().await.entities_with(required, excluded)
+    }
+
+    /// Component types seen with a field at `path`, such as `translation.x`
+    pub async fn indexed_types_with_field(&self, path: &str) -> Vec<String> {
+        self.index.read().await.types_with_field(path)
+    }
+
+    pub async fn index_stats(&self) -> ReflectionIndexStats {
+        self.index.read().await.stats()
+    }
+
     /// Execute a reflection-enhanced query
     pub async fn execute_query(
         &self,
@@ -256,15 +306,42 @@ impl ReflectionQueryEngine {
         let start_time = std::time::Instant::now();
         debug!("Executing reflection query: {}", query.base_query);
 
+        // Filters change the result, so they are part of the cache key
+        let params = &query.reflection_params;
+        let cache_key = if params.component_type_filters.is_empty() && params.field_value_filters.is_empty() {
+            query.base_query.clone()
+        } else {
+            format!(
+                "{}|{:?}|{:?}",
+                query.base_query, params.component_type_filters, params.field_value_filters
+            )
+        };
+
         // Check cache first
-        if let Some(cached_result) = self.get_cached_result(&query.base_query).await {
+        if let Some(cached_result) = self.get_cached_result(&cache_key).await {
             info!("Query cache hit for: {}", query.base_query);
             self.update_stats(true, start_time.elapsed().as_millis() as u64).await;
             return Ok(cached_result);
         }
 
-        // Execute base query through BRP
-        let base_entities = self.execute_base_query(&query.base_query, brp_client).await?;
+        let plan = self.explain(&query).await;
+        debug!("Reflection query plan:\n{}", plan.explain());
+
+        // Execute base query through BRP, with presence checks pushed down
+        let base_entities = self.execute_base_query(&query.base_query, &plan, brp_client).await?;
+        self.entity_estimates
+            .write()
+            .await
+            .insert(query.base_query.clone(), base_entities.len());
+        let unfiltered = plan.required_components.is_empty() && plan.excluded_components.is_empty();
+        self.index.write().await.index_listing(&base_entities, unfiltered);
+
+        // Cheap filters first, in the order the plan chose
+        let filters = &params.field_value_filters;
+        let base_entities: Vec<EntityData> = base_entities
+            .into_iter()
+            .filter(|entity| plan.filter_order.iter().all(|&i| filters[i].matches(entity)))
+            .collect();
         
         // Enhance with reflection data
         let reflected_entities = self.enhance_entities_with_reflection(
@@ -299,10 +376,11 @@ impl ReflectionQueryEngine {
             },
             discovered_types,
             suggestions,
+            plan: query.reflection_params.explain.then_some(plan),
         };
 
         // Cache the result
-        self.cache_result(&query.base_query, &result).await;
+        self.cache_result(&cache_key, &result).await;
 
         // Update statistics
         self.update_stats(false, execution_time).await;
@@ -314,6 +392,7 @@ impl ReflectionQueryEngine {
     async fn execute_base_query(
         &self,
         query: &str,
+        plan: &QueryPlan,
         brp_client: Arc<RwLock<BrpClient>>,
     ) -> Result<Vec<EntityData>> {
         // This would integrate with the existing query parser
@@ -321,7 +400,7 @@ impl ReflectionQueryEngine {
         let mut client = brp_client.write().await;
         
         // Parse the query and convert to BRP request
-        let brp_request = self.parse_query_to_brp(query)?;
+        let brp_request = self.parse_query_to_brp(query, plan)?;
         
         let response = client.send_request(&brp_request).await?;
         
@@ -340,14 +419,25 @@ impl ReflectionQueryEngine {
     }
 
     /// Parse query string to BRP request (simplified)
-    fn parse_query_to_brp(&self, query: &str) -> Result<BrpRequest> {
+    fn parse_query_to_brp(&self, query: &str, plan: &QueryPlan) -> Result<BrpRequest> {
+        // Components the plan requires or excludes narrow the listing in the game itself
+        let filter = if plan.required_components.is_empty() && plan.excluded_components.is_empty() {
+            None
+        } else {
+            Some(QueryFilter {
+                with: Some(plan.required_components.clone()).filter(|c| !c.is_empty()),
+                without: Some(plan.excluded_components.clone()).filter(|c| !c.is_empty()),
+                where_clause: None,
+            })
+        };
+
         // This is a simplified implementation
         // In a real system, this would integrate with the existing query parser
         if query.contains("list all") || query.contains("all entities") {
-            Ok(BrpRequest::ListEntities { filter: None })
+            Ok(BrpRequest::ListEntities { filter })
         } else {
             // Default to listing all entities for now
-            Ok(BrpRequest::ListEntities { filter: None })
+            Ok(BrpRequest::ListEntities { filter })
         }
     }
 
@@ -360,6 +450,8 @@ impl ReflectionQueryEngine {
     ) -> Result<Vec<ReflectedEntityData>> {
         let mut reflected_entities = Vec::new();
         let mut processed_count = 0;
+        // Match type filters once per type rather than per component
+        let allowed_types = self.index.read().await.types_matching(&params.component_type_filters);
 
         for entity in entities {
             if processed_count >= limits.max_entities {
@@ -367,7 +459,7 @@ impl ReflectionQueryEngine {
                 break;
             }
 
-            let reflected_entity = self.enhance_single_entity(entity, params, limits).await?;
+            let reflected_entity = self.enhance_single_entity(entity, params, limits, &allowed_types).await?;
             reflected_entities.push(reflected_entity);
             processed_count += 1;
         }
@@ -381,6 +473,7 @@ impl ReflectionQueryEngine {
         entity: EntityData,
         params: &ReflectionQueryParams,
         limits: &QueryLimits,
+        allowed_types: &HashSet<String>,
     ) -> Result<ReflectedEntityData> {
         let mut reflected_components = HashMap::new();
         let mut component_count = 0;
@@ -391,12 +484,8 @@ impl ReflectionQueryEngine {
             }
 
             // Apply component type filters
-            if !params.component_type_filters.is_empty() {
-                let matches_filter = params.component_type_filters.iter()
-                    .any(|filter| component_type.contains(filter));
-                if !matches_filter {
-                    continue;
-                }
+            if !allowed_types.contains(component_type) {
+                continue;
             }
 
             let start_inspection = std::time::Instant::now();
@@ -697,6 +786,7 @@ impl Default for ReflectionQueryParams {
             include_metrics: true,
             component_type_filters: Vec::new(),
             field_value_filters: Vec::new(),
+            explain: false,
         }
     }
 }
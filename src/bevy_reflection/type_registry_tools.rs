@@ -34,7 +34,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use crate::bevy_reflection::inspector::{ReflectionMetadata, FieldMetadata, TypeCategory};
+use crate::bevy_reflection::inspector::{default_type_id, ReflectionMetadata, FieldMetadata, TypeCategory};
 use crate::error::{Error, Result};
 
 /// TypeRegistry manager for dynamic component type discovery
@@ -67,6 +67,21 @@ pub struct CachedTypeInfo {
     pub last_updated: u64,
     /// Cache hit count for performance monitoring
     pub hit_count: u64,
+    /// Whether the type is used as a component, resource or event
+    #[serde(default)]
+    pub ecs_role: Option<EcsRole>,
+    /// Doc comment, when the game was built with reflection documentation
+    #[serde(default)]
+    pub docs: Option<String>,
+}
+
+/// How a reflected type is used in the ECS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EcsRole {
+    Component,
+    Resource,
+    Event,
 }
 
 /// Statistics about type discovery operations
@@ -110,6 +125,10 @@ pub struct TypeQueryMatch {
     pub relevance_score: f64,
     /// Reasons why this type matched
     pub match_reasons: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecs_role: Option<EcsRole>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
 }
 
 impl TypeRegistryManager {
@@ -204,6 +223,14 @@ impl TypeRegistryManager {
             child_types: Vec::new(),  // Would be populated by analyzing inheritance
             last_updated: chrono::Utc::now().timestamp_micros() as u64,
             hit_count: 0,
+            ecs_role: if registration.data::<ReflectComponent>().is_some() {
+                Some(EcsRole::Component)
+            } else if registration.data::<ReflectResource>().is_some() {
+                Some(EcsRole::Resource)
+            } else {
+                None
+            },
+            docs: None,
         })
     }
 
@@ -218,26 +245,15 @@ impl TypeRegistryManager {
             TypeInfo::Struct(struct_info) => {
                 let mut field_metadata = Vec::new();
                 for (index, field) in struct_info.iter().enumerate() {
-                    field_metadata.push(FieldMetadata {
-                        name: field.name().to_string(),
-                        type_name: field.type_path().to_string(),
-                        index,
-                        is_reflected: true, // If it's in struct_info, it should be reflected
-                        inspector_name: None, // Would be populated by checking custom inspectors
-                    });
+                    // If it's in struct_info, it should be reflected
+                    field_metadata.push(FieldMetadata::new(field.name(), field.type_path(), index));
                 }
                 (TypeCategory::Struct, field_metadata)
             }
             TypeInfo::TupleStruct(tuple_info) => {
                 let mut field_metadata = Vec::new();
                 for (index, field) in tuple_info.iter().enumerate() {
-                    field_metadata.push(FieldMetadata {
-                        name: format!("field_{}", index),
-                        type_name: field.type_path().to_string(),
-                        index,
-                        is_reflected: true,
-                        inspector_name: None,
-                    });
+                    field_metadata.push(FieldMetadata::new(format!("field_{}", index), field.type_path(), index));
                 }
                 (TypeCategory::TupleStruct, field_metadata)
             }
@@ -355,6 +371,30 @@ impl TypeRegistryManager {
         aliases
     }
 
+    /// Replace the cache with the types a connected game reported through
+    /// `bevy/registry/schema`
+    pub async fn discover_types_from_schemas(&self, schemas: &HashMap<String, Value>) -> Result<usize> {
+        let start_time = std::time::Instant::now();
+        let discovered: HashMap<String, CachedTypeInfo> = schemas
+            .iter()
+            .map(|(type_path, schema)| (type_path.clone(), cached_type_from_schema(type_path, schema)))
+            .collect();
+        let discovered_count = discovered.len();
+
+        *self.type_cache.write().await = discovered;
+        self.type_id_cache.write().await.clear();
+        {
+            let mut stats = self.stats.write().await;
+            stats.total_types = discovered_count;
+            stats.reflected_types = discovered_count;
+            stats.last_discovery = Some(chrono::Utc::now().timestamp_micros() as u64);
+            stats.discovery_time_ms = start_time.elapsed().as_millis() as u64;
+        }
+
+        info!("Type discovery from game schemas complete: {} types", discovered_count);
+        Ok(discovered_count)
+    }
+
     /// Query types by name pattern or characteristics
     pub async fn query_types(&self, query: &TypeQuery) -> Result<TypeQueryResult> {
         let start_time = std::time::Instant::now();
@@ -371,21 +411,29 @@ impl TypeRegistryManager {
                     metadata: cached_info.metadata.clone(),
                     relevance_score: score,
                     match_reasons: self.get_match_reasons(type_name, cached_info, query).await,
+                    ecs_role: cached_info.ecs_role,
+                    docs: cached_info.docs.clone(),
                 });
             }
         }
 
-        // Sort by relevance score (descending)
-        matches.sort_by(|a, b| b.relevance_score.partial_cmp(&a.relevance_score).unwrap_or(std::cmp::Ordering::Equal));
+        // Sort by relevance score (descending), then name for stable pages
+        matches.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.type_name.cmp(&b.type_name))
+        });
 
         // Apply limit
-        let truncated = matches.len() > query.limit;
+        let total_matches = matches.len();
+        let truncated = total_matches > query.limit;
         matches.truncate(query.limit);
 
         let execution_time = start_time.elapsed().as_millis() as u64;
 
         Ok(TypeQueryResult {
-            total_matches: matches.len(),
+            total_matches,
             types: matches,
             execution_time_ms: execution_time,
             truncated,
@@ -394,7 +442,24 @@ impl TypeRegistryManager {
 
     /// Calculate match score for a type against query
     async fn calculate_match_score(&self, type_name: &str, cached_info: &CachedTypeInfo, query: &TypeQuery) -> f64 {
-        let mut score = 0.0;
+        if query.ecs_role.is_some() && cached_info.ecs_role != query.ecs_role {
+            return 0.0; // Hard requirement
+        }
+
+        // A query without criteria lists every type
+        let mut score = if query.name_pattern.is_none()
+            && query.category.is_none()
+            && query.ecs_role.is_none()
+            && query.required_fields.is_none()
+        {
+            1.0
+        } else {
+            0.0
+        };
+
+        if query.ecs_role.is_some() {
+            score += 0.5;
+        }
 
         // Name matching
         if let Some(name_pattern) = &query.name_pattern {
@@ -403,6 +468,12 @@ impl TypeRegistryManager {
                 if type_name.to_lowercase() == name_pattern.to_lowercase() {
                     score += 0.2; // Exact match bonus
                 }
+            } else if let Some(fuzzy) = fuzzy_match_score(name_pattern, type_path_utils::extract_short_name(type_name)) {
+                score += 0.5 * fuzzy;
+            }
+
+            if cached_info.docs.as_ref().is_some_and(|docs| docs.to_lowercase().contains(&name_pattern.to_lowercase())) {
+                score += 0.3;
             }
             
             // Check aliases
@@ -448,6 +519,12 @@ impl TypeRegistryManager {
         if let Some(name_pattern) = &query.name_pattern {
             if type_name.to_lowercase().contains(&name_pattern.to_lowercase()) {
                 reasons.push(format!("Name contains '{}'", name_pattern));
+            } else if fuzzy_match_score(name_pattern, type_path_utils::extract_short_name(type_name)).is_some() {
+                reasons.push(format!("Name fuzzy-matches '{}'", name_pattern));
+            }
+
+            if cached_info.docs.as_ref().is_some_and(|docs| docs.to_lowercase().contains(&name_pattern.to_lowercase())) {
+                reasons.push(format!("Docs mention '{}'", name_pattern));
             }
             
             for alias in &cached_info.aliases {
@@ -457,6 +534,10 @@ impl TypeRegistryManager {
             }
         }
 
+        if let Some(role) = query.ecs_role {
+            reasons.push(format!("Used as a {:?}", role).to_lowercase());
+        }
+
         if let Some(category) = &query.category {
             if std::mem::discriminant(&cached_info.metadata.type_category) == std::mem::discriminant(category) {
                 reasons.push(format!("Type category matches: {:?}", category));
@@ -544,6 +625,8 @@ pub struct TypeQuery {
     pub requires_constructible: bool,
     /// Required field names
     pub required_fields: Option<Vec<String>>,
+    /// Required ECS role
+    pub ecs_role: Option<EcsRole>,
 }
 
 impl Default for TypeQuery {
@@ -556,8 +639,123 @@ impl Default for TypeQuery {
             requires_reflection: false,
             requires_constructible: false,
             required_fields: None,
+            ecs_role: None,
+        }
+    }
+}
+
+/// How closely `pattern` matches `name` as an in-order subsequence, ignoring case
+///
+/// `None` when some character of the pattern does not occur; otherwise the
+/// pattern length over the length of the shortest span containing it, so
+/// `"gtrans"` scores higher against `GlobalTransform` than `"gm"` does.
+pub fn fuzzy_match_score(pattern: &str, name: &str) -> Option<f64> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<usize> = None;
+    for start in (0..name.len()).filter(|&i| name[i] == pattern[0]) {
+        let mut next = 1;
+        for (offset, c) in name[start + 1..].iter().enumerate() {
+            if next == pattern.len() {
+                break;
+            }
+            if *c == pattern[next] {
+                next += 1;
+                if next == pattern.len() {
+                    let span = offset + 2;
+                    best = Some(best.map_or(span, |b| b.min(span)));
+                }
+            }
+        }
+        if pattern.len() == 1 {
+            best = Some(1);
         }
     }
+    best.map(|span| pattern.len() as f64 / span as f64)
+}
+
+/// Cache entry for a type described by Bevy's `bevy/registry/schema`
+pub fn cached_type_from_schema(type_path: &str, schema: &Value) -> CachedTypeInfo {
+    let reflect_types: Vec<&str> = schema
+        .get("reflectTypes")
+        .and_then(Value::as_array)
+        .map(|types| types.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let has = |name: &str| reflect_types.contains(&name);
+
+    let type_category = match schema.get("kind").and_then(Value::as_str) {
+        Some("Struct") => TypeCategory::Struct,
+        Some("TupleStruct") | Some("Tuple") => TypeCategory::TupleStruct,
+        Some("Enum") => TypeCategory::Enum,
+        Some("Array") => TypeCategory::Array,
+        Some("List") | Some("Set") => TypeCategory::List,
+        Some("Map") => TypeCategory::Map,
+        _ => TypeCategory::Value,
+    };
+
+    let field_type = |field: &Value| {
+        field
+            .get("$ref")
+            .or_else(|| field.get("type").and_then(|t| t.get("$ref")))
+            .and_then(Value::as_str)
+            .map(|r| r.trim_start_matches("#/$defs/").to_string())
+            .or_else(|| field.get("type").and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_default()
+    };
+    let mut fields: Vec<FieldMetadata> = Vec::new();
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (index, (name, field)) in properties.iter().enumerate() {
+            fields.push(FieldMetadata::new(name.clone(), field_type(field), index));
+        }
+    } else if let Some(items) = schema.get("prefixItems").and_then(Value::as_array) {
+        for (index, field) in items.iter().enumerate() {
+            fields.push(FieldMetadata::new(format!("field_{}", index), field_type(field), index));
+        }
+    }
+
+    let mut aliases = Vec::new();
+    if let Some(short_path) = schema.get("shortPath").and_then(Value::as_str) {
+        if short_path != type_path {
+            aliases.push(short_path.to_string());
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp_micros() as u64;
+    CachedTypeInfo {
+        metadata: ReflectionMetadata {
+            type_name: type_path.to_string(),
+            type_id: default_type_id(),
+            is_reflected: true,
+            fields,
+            type_category,
+            type_info: Some(schema.clone()),
+            last_updated: now,
+        },
+        constructible: has("Default"),
+        serializable: has("Serialize") || has("Deserialize"),
+        aliases,
+        parent_types: Vec::new(),
+        child_types: Vec::new(),
+        last_updated: now,
+        hit_count: 0,
+        ecs_role: if has("Component") {
+            Some(EcsRole::Component)
+        } else if has("Resource") {
+            Some(EcsRole::Resource)
+        } else if has("Event") {
+            Some(EcsRole::Event)
+        } else {
+            None
+        },
+        docs: schema
+            .get("description")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
 }
 
 /// Utility functions for working with type paths
@@ -615,6 +813,53 @@ mod tests {
         assert!(!query.requires_reflection);
     }
 
+    #[tokio::test]
+    async fn test_search_types_from_game_schemas() {
+        let schemas = HashMap::from([
+            (
+                "bevy_transform::components::global_transform::GlobalTransform".to_string(),
+                json!({"shortPath": "GlobalTransform", "kind": "TupleStruct", "reflectTypes": ["Component", "Default"],
+                       "prefixItems": [{"type": {"$ref": "#/$defs/glam::Affine3A"}}]}),
+            ),
+            (
+                "bevy_time::time::Time".to_string(),
+                json!({"shortPath": "Time", "kind": "Struct", "reflectTypes": ["Resource", "Default"],
+                       "properties": {"delta": {"type": {"$ref": "#/$defs/core::time::Duration"}}},
+                       "description": "A generic clock resource that tracks how much it has advanced."}),
+            ),
+        ]);
+        let manager = TypeRegistryManager::new();
+        assert_eq!(manager.discover_types_from_schemas(&schemas).await.unwrap(), 2);
+
+        let all = manager.query_types(&TypeQuery::default()).await.unwrap();
+        assert_eq!(all.total_matches, 2);
+
+        let fuzzy = manager
+            .query_types(&TypeQuery {
+                name_pattern: Some("gtrans".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(fuzzy.types.len(), 1);
+        assert_eq!(fuzzy.types[0].ecs_role, Some(EcsRole::Component));
+        assert_eq!(fuzzy.types[0].metadata.fields[0].type_name, "glam::Affine3A");
+
+        let resources = manager
+            .query_types(&TypeQuery {
+                name_pattern: Some("clock".to_string()),
+                ecs_role: Some(EcsRole::Resource),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(resources.types[0].type_name, "bevy_time::time::Time");
+        assert!(resources.types[0].match_reasons.contains(&"Docs mention 'clock'".to_string()));
+
+        assert!(fuzzy_match_score("xyz", "Transform").is_none());
+        assert!(fuzzy_match_score("tfm", "Transform").unwrap() < 1.0);
+    }
+
     #[test]
     fn test_type_path_utils() {
         use type_path_utils::*;
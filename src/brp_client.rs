@@ -3,17 +3,18 @@ use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::net::TcpStream;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
 use tokio::time::{interval, Instant};
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
 use url::Url;
 
-use crate::brp_messages::{BrpRequest, BrpResponse, DebugCommand};
+use crate::brp_messages::{BrpRequest, BrpResponse, EntityMutation};
 use crate::brp_command_handler::{CommandHandlerRegistry, CoreBrpHandler, BrpCommandHandler};
 use crate::config::Config;
 use crate::debug_command_processor::{DebugCommandRouter, DebugCommandRequest};
 use crate::error::{Error, Result};
+use crate::flight_recorder::{self, FlightEventKind};
 use crate::resource_manager::ResourceManager;
 
 /// Batched request for efficient processing with proper cleanup
@@ -59,6 +60,9 @@ pub struct BrpClient {
     batch_processor_handle: Option<tokio::task::JoinHandle<()>>,
     command_registry: Arc<CommandHandlerRegistry>,
     debug_router: Option<Arc<DebugCommandRouter>>,
+    mutations: broadcast::Sender<EntityMutation>,
+    /// Number of times a connection has been established
+    connections: watch::Sender<u64>,
 }
 
 impl std::fmt::Debug for BrpClient {
@@ -87,6 +91,8 @@ impl BrpClient {
             batch_processor_handle: None,
             command_registry,
             debug_router: None,
+            mutations: broadcast::channel(256).0,
+            connections: watch::channel(0).0,
         }
     }
 
@@ -119,6 +125,16 @@ impl BrpClient {
         self.command_registry.clone()
     }
 
+    /// Watch the number of connections made; it changes each time the game connects
+    pub fn subscribe_connections(&self) -> watch::Receiver<u64> {
+        self.connections.subscribe()
+    }
+
+    /// Receive every entity mutation this client successfully sends
+    pub fn subscribe_mutations(&self) -> broadcast::Receiver<EntityMutation> {
+        self.mutations.subscribe()
+    }
+
     pub async fn connect_with_retry(&mut self) -> Result<()> {
         const MAX_RETRIES: u32 = 5;
         const BASE_DELAY: Duration = Duration::from_millis(1000);
@@ -158,16 +174,46 @@ impl BrpClient {
             .map_err(|e| Error::WebSocket(Box::new(e)))?;
 
         self.ws_stream = Some(ws_stream);
-        self.connected = true;
+        // Periodic reconnects of a live connection are not a new game connecting
+        if !self.connected {
+            self.connections.send_modify(|count| *count += 1);
+        }
+        self.set_connected(true);
 
         Ok(())
     }
 
+    /// Update the connection state, noting changes in the flight recorder
+    fn set_connected(&mut self, connected: bool) {
+        if self.connected != connected {
+            let state = if connected {
+                "connected"
+            } else {
+                "disconnected"
+            };
+            flight_recorder::record(FlightEventKind::StateTransition {
+                component: "brp_connection".to_string(),
+                state: state.to_string(),
+            });
+        }
+        self.connected = connected;
+    }
+
     pub fn is_connected(&self) -> bool {
         self.connected
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     /// Send a BRP request and return the response (with resource management)
+    #[instrument(
+        name = "brp.request",
+        skip_all,
+        fields(otel.kind = "client", brp.method = tracing::field::Empty),
+        err
+    )]
     pub async fn send_request(&mut self, request: &BrpRequest) -> Result<BrpResponse> {
         // Check rate limiting if resource manager is available
         if let Some(ref rm) = self.resource_manager {
@@ -210,13 +256,37 @@ impl BrpClient {
             }
         }
 
+        if let (Ok(BrpResponse::Success(_)), Some(mutation)) = (&result, request.mutation()) {
+            // No subscribers is fine; nothing is caching world state yet
+            let _ = self.mutations.send(mutation);
+        }
+
         result
     }
 
     /// Internal send request without resource management
     async fn send_request_internal(&mut self, request: &BrpRequest) -> Result<BrpResponse> {
-        let request_json = serde_json::to_string(request)?;
-        self.send_message(&request_json).await?;
+        let request_value = serde_json::to_value(request)?;
+        let method = request_value
+            .get("method")
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        tracing::Span::current().record("brp.method", method.as_str());
+
+        let started = Instant::now();
+        let result = self.exchange(&request_value).await;
+        flight_recorder::record(FlightEventKind::BrpRequest {
+            method,
+            status: flight_recorder::status(!matches!(result, Ok(BrpResponse::Success(_)))),
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        result
+    }
+
+    /// Send `request_value` and wait for its response
+    async fn exchange(&mut self, request_value: &serde_json::Value) -> Result<BrpResponse> {
+        self.send_message(&request_value.to_string()).await?;
 
         // Wait for response with timeout
         let response = tokio::time::timeout(Duration::from_secs(5), self.receive_message())
@@ -362,13 +432,13 @@ impl BrpClient {
                 }
                 Some(Ok(Message::Close(_))) => {
                     warn!("BRP connection closed");
-                    self.connected = false;
+                    self.set_connected(false);
                     self.ws_stream = None;
                     Ok(None)
                 }
                 Some(Err(e)) => {
                     error!("BRP WebSocket error: {}", e);
-                    self.connected = false;
+                    self.set_connected(false);
                     self.ws_stream = None;
                     Err(Error::WebSocket(Box::new(e)))
                 }
@@ -384,7 +454,7 @@ impl BrpClient {
         if let Some(mut ws_stream) = self.ws_stream.take() {
             let _ = ws_stream.close(None).await;
         }
-        self.connected = false;
+        self.set_connected(false);
         info!("Disconnected from BRP");
     }
 }
@@ -161,6 +161,38 @@ pub enum BrpRequest {
     /// Query a specific entity (for experiment system)
     QueryEntity { entity_id: EntityId },
 
+    /// Read a resource's current value
+    #[serde(rename = "bevy/get_resource")]
+    GetResource {
+        /// Fully qualified resource type name
+        resource: String,
+    },
+
+    /// Insert or overwrite a resource
+    #[serde(rename = "bevy/insert_resource")]
+    InsertResource {
+        /// Fully qualified resource type name
+        resource: String,
+        /// Serialized resource value
+        value: ComponentValue,
+    },
+
+    /// Describe every reflected type registered in the game (Bevy 0.16)
+    #[serde(rename = "bevy/registry/schema")]
+    RegistrySchema {
+        /// Only types from these crates
+        with_crates: Option<Vec<String>>,
+        /// Leave out types from these crates
+        without_crates: Option<Vec<String>>,
+    },
+
+    /// Read diagnostics from Bevy's diagnostics plugins (frame time, entity count, ...)
+    #[serde(rename = "bevy_debugger/diagnostics")]
+    GetDiagnostics {
+        /// Diagnostic paths to read (all registered diagnostics if omitted)
+        paths: Option<Vec<String>>,
+    },
+
     /// Debug command wrapper for extensible debugging operations
     #[serde(rename = "bevy_debugger/debug")]
     Debug {
@@ -173,6 +205,69 @@ pub enum BrpRequest {
     },
 }
 
+/// How a request changes the game world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MutationKind {
+    /// Components of an existing entity were set, added or removed
+    Modify,
+    Spawn,
+    Destroy,
+}
+
+/// Entities and components touched by a mutating request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityMutation {
+    pub kind: MutationKind,
+    /// The entity changed; unknown for spawns until the game assigns one
+    pub entity: Option<EntityId>,
+    pub components: Vec<ComponentTypeId>,
+}
+
+impl BrpRequest {
+    /// What this request changes, or `None` for read-only requests
+    #[must_use]
+    pub fn mutation(&self) -> Option<EntityMutation> {
+        let modify = |entity: EntityId, components: Vec<ComponentTypeId>| EntityMutation {
+            kind: MutationKind::Modify,
+            entity: Some(entity),
+            components,
+        };
+        match self {
+            Self::Set { entity, components } | Self::Insert { entity, components } => {
+                Some(modify(*entity, components.keys().cloned().collect()))
+            }
+            Self::Remove { entity, components } => Some(modify(*entity, components.clone())),
+            Self::ModifyEntity {
+                entity_id,
+                components,
+            } => Some(modify(
+                *entity_id,
+                components.iter().map(|(id, _)| id.clone()).collect(),
+            )),
+            Self::Reparent { entity, .. } => Some(modify(*entity, Vec::new())),
+            Self::Spawn { components } => Some(EntityMutation {
+                kind: MutationKind::Spawn,
+                entity: None,
+                components: components.keys().cloned().collect(),
+            }),
+            Self::SpawnEntity { components } => Some(EntityMutation {
+                kind: MutationKind::Spawn,
+                entity: None,
+                components: components.iter().map(|(id, _)| id.clone()).collect(),
+            }),
+            Self::Destroy { entity } | Self::DeleteEntity { entity_id: entity } => {
+                Some(EntityMutation {
+                    kind: MutationKind::Destroy,
+                    entity: Some(*entity),
+                    components: Vec::new(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Query filter for selecting entities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -265,6 +360,12 @@ pub enum DebugCommand {
         track_allocations: Option<bool>,
     },
 
+    /// Collect GPU pass timings and classify recent frames as CPU or GPU bound
+    ProfileGpu {
+        /// Number of recent frames to analyze (default: 120)
+        frame_count: Option<usize>,
+    },
+
     /// Enable/disable visual debugging overlays
     SetVisualDebug {
         /// Type of overlay
@@ -275,6 +376,51 @@ pub enum DebugCommand {
         config: Option<serde_json::Value>,
     },
 
+    /// Place a named world-space marker, replacing any marker with the same id
+    PlaceMarker {
+        marker: WorldMarker,
+    },
+
+    /// Remove a marker by id
+    RemoveMarker {
+        id: String,
+    },
+
+    /// Remove all markers
+    ClearMarkers,
+
+    /// List active markers
+    ListMarkers,
+
+    /// Define (or replace) a data-driven overlay rendered generically by the game
+    DefineOverlay {
+        definition: CustomOverlayDefinition,
+    },
+
+    /// Remove a data-driven overlay by name
+    RemoveCustomOverlay {
+        name: String,
+    },
+
+    /// Nudge, rotate or scale an entity's transform through BRP and show the
+    /// change with the transforms overlay gizmo
+    ManipulateTransform {
+        entity: EntityId,
+        operation: TransformOperation,
+        #[serde(default)]
+        space: TransformSpace,
+    },
+
+    /// Get which overlays are enabled in the game, including changes made
+    /// with in-game hotkeys
+    GetOverlayState,
+
+    /// Recolor all overlays from a palette preset, adjusted for contrast
+    /// against the scene background
+    SetTheme {
+        theme: OverlayTheme,
+    },
+
     /// Execute a validated ECS query
     ExecuteQuery {
         /// Validated query structure
@@ -309,6 +455,13 @@ pub enum DebugCommand {
         params: serde_json::Value,
     },
 
+    /// Complete a partially typed filter such as `Transform.transl` or `Health.current >`
+    QuerySuggest {
+        partial: String,
+        /// Maximum completions (default 20)
+        limit: Option<usize>,
+    },
+
     /// Memory profiling command
     ProfileMemory {
         /// Capture allocation backtraces
@@ -452,6 +605,13 @@ pub enum DebugCommand {
     /// Get budget monitoring statistics
     GetBudgetStatistics,
 
+    /// Resolve asset handles held by an entity's components to their asset
+    /// path, load state and strong/weak status
+    ResolveAssetHandles {
+        entity: EntityId,
+        handles: Vec<AssetHandleRef>,
+    },
+
     /// Custom debug command for extensions
     Custom {
         /// Command name
@@ -481,10 +641,286 @@ pub enum DebugOverlayType {
     PerformanceMetrics,
     /// Debug markers
     DebugMarkers,
+    /// Entity bounding boxes (Aabb)
+    BoundingBoxes,
+    /// Light and shadow gizmos
+    Lights,
+    /// Camera frustums and culled entities
+    Frustum,
+    /// Entity density heatmap
+    Heatmap,
+    /// World-space component field labels
+    Labels,
+    /// bevy_ui node layout boxes
+    UiLayout,
     /// Custom overlay
     Custom(String),
 }
 
+/// Shape drawn for a world-space marker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkerShape {
+    #[default]
+    Sphere,
+    /// Arrow from `position` along `direction`
+    Arrow,
+    /// Text only, anchored at `position`
+    Text,
+}
+
+/// Named marker placed in the game world to annotate the scene
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldMarker {
+    /// Unique marker id; placing a marker with an existing id updates it
+    pub id: String,
+    #[serde(default)]
+    pub shape: MarkerShape,
+    /// World position
+    pub position: [f32; 3],
+    /// Arrow direction and length (arrows only)
+    #[serde(default)]
+    pub direction: Option<[f32; 3]>,
+    /// Text shown next to the marker
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Color (RGBA)
+    #[serde(default = "default_marker_color")]
+    pub color: [f32; 4],
+    /// Sphere radius or text scale
+    #[serde(default = "default_marker_size")]
+    pub size: f32,
+    /// Time to live in milliseconds; `None` keeps the marker until removed
+    #[serde(default)]
+    pub ttl_ms: Option<u64>,
+}
+
+fn default_marker_color() -> [f32; 4] {
+    [1.0, 0.0, 1.0, 1.0]
+}
+
+fn default_marker_size() -> f32 {
+    0.5
+}
+
+/// Shape drawn at each entity matched by a data-driven overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayShapeKind {
+    #[default]
+    Sphere,
+    /// Box aligned with the entity's rotation
+    Cuboid,
+    /// Three axis-aligned lines
+    Cross,
+    /// Arrow along `direction`, rotated with the entity
+    Arrow,
+    /// Circle facing up (Y)
+    Circle,
+}
+
+/// One shape of a data-driven overlay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverlayShape {
+    #[serde(default)]
+    pub kind: OverlayShapeKind,
+    /// Radius, half extent or arrow length
+    #[serde(default = "default_marker_size")]
+    pub size: f32,
+    /// Color (RGBA)
+    #[serde(default = "default_marker_color")]
+    pub color: [f32; 4],
+    /// Offset from the entity in its local space
+    #[serde(default)]
+    pub offset: [f32; 3],
+    /// Arrow direction in the entity's local space (arrows only, default forward)
+    #[serde(default)]
+    pub direction: Option<[f32; 3]>,
+}
+
+/// Entities a data-driven overlay draws shapes for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverlayQuery {
+    /// Components the entity must have (full path or short name)
+    #[serde(default)]
+    pub with: Vec<String>,
+    /// Components the entity must not have
+    #[serde(default)]
+    pub without: Vec<String>,
+    /// Substring the entity's `Name` must contain
+    #[serde(default)]
+    pub name_contains: Option<String>,
+}
+
+/// Overlay described entirely in data so new visualizations need no game rebuild
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomOverlayDefinition {
+    /// Overlay name; addressed as `DebugOverlayType::Custom(name)`
+    pub name: String,
+    #[serde(default)]
+    pub query: OverlayQuery,
+    pub shapes: Vec<OverlayShape>,
+    /// Maximum entities drawn per frame
+    #[serde(default = "default_custom_overlay_max_entities")]
+    pub max_entities: usize,
+}
+
+fn default_custom_overlay_max_entities() -> usize {
+    500
+}
+
+/// Change applied by `DebugCommand::ManipulateTransform`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TransformOperation {
+    /// Move by `delta`
+    Nudge { delta: [f32; 3] },
+    /// Rotate around `axis` by `degrees`
+    Rotate { axis: [f32; 3], degrees: f32 },
+    /// Multiply the scale per axis
+    Scale { factor: [f32; 3] },
+}
+
+/// Axes a transform operation is expressed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformSpace {
+    /// The parent's axes (world axes for root entities)
+    #[default]
+    Parent,
+    /// The entity's own rotated axes
+    Local,
+}
+
+/// Local transform as serialized by Bevy reflection
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TransformState {
+    pub translation: [f32; 3],
+    /// Quaternion as `[x, y, z, w]`
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl Default for TransformState {
+    fn default() -> Self {
+        Self {
+            translation: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+/// Overlay enabled flags as seen by the game
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayToggleState {
+    /// Incremented on every enable/disable in the game
+    pub revision: u64,
+    /// Enabled flag by overlay key
+    pub overlays: HashMap<String, bool>,
+}
+
+/// A `Handle<T>` found in a component, located by field path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetHandleRef {
+    pub component: ComponentTypeId,
+    /// Dotted path to the handle within the component; empty for the component itself
+    pub path: String,
+}
+
+/// Load state of an asset as reported by the game's `AssetServer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssetLoadState {
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed,
+}
+
+impl std::fmt::Display for AssetLoadState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = match self {
+            Self::NotLoaded => "Not loaded",
+            Self::Loading => "Loading",
+            Self::Loaded => "Loaded",
+            Self::Failed => "Failed",
+        };
+        f.write_str(state)
+    }
+}
+
+/// What an asset handle points at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetHandleInfo {
+    #[serde(flatten)]
+    pub handle: AssetHandleRef,
+    /// Type name of the asset, e.g. `bevy_image::image::Image`
+    pub asset_type: Option<String>,
+    /// Path the asset was loaded from; `None` for assets created at runtime
+    pub asset_path: Option<String>,
+    pub asset_id: Option<String>,
+    pub load_state: AssetLoadState,
+    /// Whether the handle keeps the asset alive
+    pub strong: bool,
+}
+
+impl AssetHandleInfo {
+    /// Short form such as `textures/player.png (Loaded)`
+    pub fn describe(&self) -> String {
+        let target = self
+            .asset_path
+            .clone()
+            .or_else(|| self.asset_id.clone())
+            .unwrap_or_else(|| "<runtime asset>".to_string());
+        let weak = if self.strong { "" } else { ", weak" };
+        format!("{} ({}{})", target, self.load_state, weak)
+    }
+}
+
+/// Palette presets for overlay colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreset {
+    /// The overlays' built-in colors
+    #[default]
+    Default,
+    /// Saturated colors for busy or washed-out scenes
+    HighContrast,
+    /// Safe for red-green color blindness with weak green perception
+    Deuteranopia,
+    /// Safe for red-green color blindness with weak red perception
+    Protanopia,
+}
+
+/// Theme set with `DebugCommand::SetTheme`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverlayTheme {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    /// Dominant scene background as sRGB; colors are only adjusted for
+    /// contrast when this is set
+    #[serde(default)]
+    pub background: Option<[f32; 3]>,
+    /// Minimum contrast ratio against the background (default: 3.0)
+    #[serde(default)]
+    pub min_contrast: Option<f32>,
+}
+
+/// Resolved theme colors as sRGBA, by role
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThemePalette {
+    pub primary: [f32; 4],
+    pub secondary: [f32; 4],
+    pub accent: [f32; 4],
+    pub positive: [f32; 4],
+    pub warning: [f32; 4],
+    pub critical: [f32; 4],
+    pub neutral: [f32; 4],
+    pub text: [f32; 4],
+    /// Low, middle and high end of density gradients
+    pub heat: [[f32; 4]; 3],
+}
+
 /// Validated query structure for safe ECS queries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidatedQuery {
@@ -509,6 +945,28 @@ pub struct QueryCost {
     pub estimated_memory: usize,
 }
 
+/// Which part of a filter a completion fills in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryCompletionKind {
+    Component,
+    Field,
+    Operator,
+    Value,
+}
+
+/// One completion of a partially typed filter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryCompletion {
+    /// The partial filter with this completion applied
+    pub text: String,
+    /// The completed token alone, e.g. `translation` or `gte`
+    pub label: String,
+    /// Type of a component field, or what an operator does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
 /// Session operations for debug session management
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -572,6 +1030,14 @@ pub enum DebugResponse {
         anomalies: Vec<serde_json::Value>,
     },
 
+    /// Raw GPU frame timings reported by the game
+    GpuTimings {
+        frames: Vec<GpuFrameTiming>,
+    },
+
+    /// GPU timing analysis result
+    GpuProfile(GpuTimingAnalysis),
+
     /// Visual debug status
     VisualDebugStatus {
         overlay_type: DebugOverlayType,
@@ -579,6 +1045,32 @@ pub enum DebugResponse {
         config: Option<serde_json::Value>,
     },
 
+    /// Active world-space markers
+    Markers {
+        markers: Vec<WorldMarker>,
+    },
+
+    /// Transform before and after a manipulation
+    TransformManipulated {
+        entity: EntityId,
+        before: TransformState,
+        after: TransformState,
+    },
+
+    /// Overlay enabled flags in the game
+    OverlayState(OverlayToggleState),
+
+    /// Theme applied to overlays
+    ThemeApplied {
+        theme: OverlayTheme,
+        palette: ThemePalette,
+        /// Overlays that were recolored
+        overlays: Vec<String>,
+    },
+
+    /// Resolved asset handles, in request order
+    AssetHandles(Vec<AssetHandleInfo>),
+
     /// Query execution result
     QueryResult {
         entities: Vec<EntityData>,
@@ -631,6 +1123,12 @@ pub enum DebugResponse {
         query_complexity: u32,
     },
 
+    /// Completions for a partially typed filter
+    QueryCompletions {
+        kind: QueryCompletionKind,
+        completions: Vec<QueryCompletion>,
+    },
+
     /// Query execution result
     QueryExecution {
         success: bool,
@@ -670,6 +1168,9 @@ pub struct EntityMetadata {
     pub archetype_id: Option<u32>,
     /// Entity location in world storage
     pub location_info: Option<EntityLocationInfo>,
+    /// Asset handles held by the entity's components, resolved by the game
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub asset_handles: Vec<AssetHandleInfo>,
 }
 
 /// Detailed component type information with reflection data
@@ -749,6 +1250,73 @@ pub struct ProfileSample {
     pub allocations: Option<usize>,
 }
 
+/// GPU timing for a single render pass (from wgpu timestamp queries)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuPassTiming {
+    /// Render graph pass name
+    pub pass_name: String,
+    /// GPU execution time in microseconds
+    pub duration_us: u64,
+}
+
+/// CPU and GPU timing for a single frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuFrameTiming {
+    /// Frame number
+    pub frame_number: u64,
+    /// CPU frame time in microseconds
+    pub cpu_time_us: u64,
+    /// Total GPU time in microseconds
+    pub gpu_time_us: u64,
+    /// Per-pass GPU timings
+    pub passes: Vec<GpuPassTiming>,
+}
+
+/// Which side of the pipeline limited a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FrameBound {
+    /// CPU work (systems, render extraction) dominated the frame
+    Cpu,
+    /// GPU work dominated the frame
+    Gpu,
+    /// CPU and GPU time were roughly equal
+    Balanced,
+}
+
+/// Aggregated statistics for one GPU pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuPassStats {
+    /// Render graph pass name
+    pub pass_name: String,
+    /// Average GPU time in microseconds
+    pub avg_time_us: u64,
+    /// Maximum GPU time in microseconds
+    pub max_time_us: u64,
+    /// Number of frames the pass appeared in
+    pub sample_count: usize,
+}
+
+/// GPU vs CPU analysis over recent frames
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuTimingAnalysis {
+    /// Number of frames analyzed
+    pub frame_count: usize,
+    /// Frames limited by the CPU
+    pub cpu_bound_frames: usize,
+    /// Frames limited by the GPU
+    pub gpu_bound_frames: usize,
+    /// Frames with balanced CPU and GPU time
+    pub balanced_frames: usize,
+    /// Average CPU frame time in microseconds
+    pub avg_cpu_time_us: u64,
+    /// Average GPU frame time in microseconds
+    pub avg_gpu_time_us: u64,
+    /// Most common classification, if any frames were analyzed
+    pub dominant: Option<FrameBound>,
+    /// Per-pass statistics sorted by average time, slowest first
+    pub passes: Vec<GpuPassStats>,
+}
+
 /// System profile data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemProfile {
@@ -884,6 +1452,18 @@ pub enum BrpResult {
     /// Debug command response
     #[serde(rename = "debug")]
     Debug(Box<DebugResponse>),
+
+    /// Diagnostic measurements
+    #[serde(rename = "diagnostics")]
+    Diagnostics(Vec<DiagnosticMeasurement>),
+
+    /// Resource value
+    #[serde(rename = "resource")]
+    Resource(ComponentValue),
+
+    /// JSON schemas of reflected types by type path
+    #[serde(rename = "type_schemas")]
+    TypeSchemas(HashMap<String, serde_json::Value>),
 }
 
 /// Entity data with components
@@ -895,6 +1475,21 @@ pub struct EntityData {
     pub components: HashMap<ComponentTypeId, ComponentValue>,
 }
 
+/// Latest value of a Bevy diagnostic (e.g. `frame_time`, `fps`, `entity_count`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticMeasurement {
+    /// Diagnostic path
+    pub path: String,
+    /// Most recent measurement
+    pub value: Option<f64>,
+    /// Average over the diagnostic's history
+    pub average: Option<f64>,
+    /// Exponentially smoothed value
+    pub smoothed: Option<f64>,
+    /// Unit suffix (e.g. "ms")
+    pub suffix: Option<String>,
+}
+
 /// Information about a component type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentTypeInfo {
@@ -395,7 +395,7 @@ impl BrpValidator {
                 .checked_sub(now.duration_since(session.rate_state.window_start))
                 .unwrap_or(Duration::ZERO);
             
-            return Err(Error::Validation(format!(
+            return Err(Error::RateLimited(format!(
                 "Rate limit exceeded: {} operations per second. Try again in {:?}. Consider reducing request frequency or using batch operations.",
                 self.config.rate_limit, reset_in
             )));
@@ -443,7 +443,10 @@ impl BrpValidator {
             BrpRequest::Query { .. }
             | BrpRequest::Get { .. }
             | BrpRequest::ListEntities { .. }
-            | BrpRequest::ListComponents => PermissionLevel::Read,
+            | BrpRequest::ListComponents
+            | BrpRequest::GetDiagnostics { .. }
+            | BrpRequest::RegistrySchema { .. }
+            | BrpRequest::GetResource { .. } => PermissionLevel::Read,
             
             BrpRequest::Set { .. }
             | BrpRequest::Spawn { .. }
@@ -454,7 +457,8 @@ impl BrpValidator {
             | BrpRequest::QueryEntity { .. }
             | BrpRequest::Insert { .. }
             | BrpRequest::Remove { .. }
-            | BrpRequest::Reparent { .. } => PermissionLevel::Write,
+            | BrpRequest::Reparent { .. }
+            | BrpRequest::InsertResource { .. } => PermissionLevel::Write,
             
             BrpRequest::Screenshot { .. }
             | BrpRequest::Debug { .. } => PermissionLevel::Admin,
@@ -7,6 +7,9 @@ use tracing::{debug, error, info, warn};
 
 use crate::error::{Error, Result};
 
+/// zstd level for checkpoint files; world snapshots compress well even at low levels
+const CHECKPOINT_COMPRESSION_LEVEL: i32 = 3;
+
 /// A checkpoint represents a saved state that can be restored later
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Checkpoint {
@@ -222,8 +225,10 @@ impl CheckpointManager {
             let mut checkpoints = self.checkpoints.write()
                 .map_err(|_| Self::handle_lock_poison::<()>())?;
 
-            // Remove oldest checkpoints if we're at the limit
-            while checkpoints.len() >= self.config.max_checkpoints {
+            // Remove oldest checkpoints if we're at the limit; replacing one doesn't grow the map
+            while !checkpoints.contains_key(&checkpoint_id)
+                && checkpoints.len() >= self.config.max_checkpoints
+            {
                 if let Some((oldest_id, _)) = checkpoints
                     .iter()
                     .min_by_key(|(_, cp)| cp.timestamp)
@@ -346,8 +351,11 @@ impl CheckpointManager {
         // Remove from disk if configured
         if self.config.persist_to_disk {
             let file_path = self.get_checkpoint_file_path(checkpoint_id);
-            if fs::metadata(&file_path).await.is_ok() {
-                fs::remove_file(&file_path).await?;
+            let legacy_path = self.get_legacy_checkpoint_file_path(checkpoint_id);
+            for path in [file_path, legacy_path] {
+                if fs::metadata(&path).await.is_ok() {
+                    fs::remove_file(&path).await?;
+                }
             }
         }
 
@@ -456,16 +464,28 @@ impl CheckpointManager {
 
     async fn save_checkpoint_to_disk(&self, checkpoint: &Checkpoint) -> Result<()> {
         let file_path = self.get_checkpoint_file_path(&checkpoint.id);
-        let data = serde_json::to_string_pretty(checkpoint)?;
-        fs::write(&file_path, data).await?;
-        debug!("Saved checkpoint to disk: {}", file_path.display());
+        let json = serde_json::to_vec(checkpoint)?;
+        let data = zstd::stream::encode_all(json.as_slice(), CHECKPOINT_COMPRESSION_LEVEL)?;
+        fs::write(&file_path, &data).await?;
+        debug!(
+            "Saved checkpoint to disk: {} ({} bytes, {:.1}x compressed)",
+            file_path.display(),
+            data.len(),
+            json.len() as f64 / data.len().max(1) as f64
+        );
         Ok(())
     }
 
     async fn load_checkpoint_from_disk(&self, checkpoint_id: &str) -> Result<Checkpoint> {
         let file_path = self.get_checkpoint_file_path(checkpoint_id);
-        let data = fs::read_to_string(&file_path).await?;
-        let checkpoint: Checkpoint = serde_json::from_str(&data)?;
+        let checkpoint: Checkpoint = match fs::read(&file_path).await {
+            Ok(data) => serde_json::from_slice(&zstd::stream::decode_all(data.as_slice())?)?,
+            // Checkpoints saved before compression are plain JSON
+            Err(_) => {
+                let legacy_path = self.get_legacy_checkpoint_file_path(checkpoint_id);
+                serde_json::from_slice(&fs::read(legacy_path).await?)?
+            }
+        };
         Ok(checkpoint)
     }
 
@@ -480,9 +500,10 @@ impl CheckpointManager {
 
         while let Some(entry) = entries.next_entry().await? {
             if let Some(file_name) = entry.file_name().to_str() {
-                if file_name.ends_with(".json") {
-                    let checkpoint_id = file_name.trim_end_matches(".json");
-
+                let checkpoint_id = file_name
+                    .strip_suffix(".json.zst")
+                    .or_else(|| file_name.strip_suffix(".json"));
+                if let Some(checkpoint_id) = checkpoint_id {
                     match self.load_checkpoint_from_disk(checkpoint_id).await {
                         Ok(checkpoint) => {
                             if !checkpoint.is_expired() {
@@ -518,7 +539,13 @@ impl CheckpointManager {
             panic!("Invalid checkpoint ID: {checkpoint_id}");
         }
 
-        std::path::Path::new(&self.config.storage_directory).join(format!("{sanitized_id}.json"))
+        std::path::Path::new(&self.config.storage_directory)
+            .join(format!("{sanitized_id}.json.zst"))
+    }
+
+    /// Where checkpoints were saved as plain JSON before files were compressed
+    fn get_legacy_checkpoint_file_path(&self, checkpoint_id: &str) -> std::path::PathBuf {
+        self.get_checkpoint_file_path(checkpoint_id).with_extension("")
     }
 
     /// Shutdown the checkpoint manager
@@ -1,13 +1,81 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use async_trait::async_trait;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
 use serde_json::Value;
 use tracing::{debug, info, warn};
 
+use crate::brp_messages::{EntityId, EntityMutation, MutationKind};
 use crate::error::{Error, Result};
+use crate::memory_budget::MemoryConsumer;
+use crate::response_pool::SharedResponse;
+
+/// Tag shared by every cached response that holds entity data
+pub const ENTITY_DATA_TAG: &str = "entity_data";
+
+/// Tag for entity responses that covered no entities, which any mutation may change
+pub const NO_ENTITIES_TAG: &str = "entities:none";
+
+/// Tag for cached responses that include `entity`
+pub fn entity_tag(entity: EntityId) -> String {
+    format!("entity:{}", entity)
+}
+
+/// Tag for cached responses that include an entity with `component`
+pub fn component_tag(component: &str) -> String {
+    format!("component:{}", component)
+}
+
+/// Tags for the entities and components found anywhere in `response`
+///
+/// Any object with a numeric `id` and a `components` map counts as an entity.
+pub fn coverage_tags(response: &Value) -> Vec<String> {
+    fn collect(value: &Value, tags: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let (Some(id), Some(Value::Object(components))) = (map.get("id").and_then(Value::as_u64), map.get("components")) {
+                    tags.push(entity_tag(id));
+                    tags.extend(components.keys().map(|component| component_tag(component)));
+                }
+                map.values().for_each(|child| collect(child, tags));
+            }
+            Value::Array(items) => items.iter().for_each(|item| collect(item, tags)),
+            _ => {}
+        }
+    }
+
+    let mut tags = Vec::new();
+    collect(response, &mut tags);
+    if tags.is_empty() {
+        tags.push(NO_ENTITIES_TAG.to_string());
+    }
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Tags of the cached responses a mutation may have made stale
+///
+/// Changes to an entity affect responses covering it or any entity with the
+/// same components. A spawn can add an entity to any query, so it affects
+/// every response holding entity data.
+pub fn mutation_tags(mutation: &EntityMutation) -> Vec<String> {
+    if mutation.kind == MutationKind::Spawn {
+        return vec![ENTITY_DATA_TAG.to_string()];
+    }
+    let mut tags: Vec<String> = mutation.components.iter().map(|component| component_tag(component)).collect();
+    tags.extend(mutation.entity.map(entity_tag));
+    if mutation.kind == MutationKind::Modify {
+        tags.push(NO_ENTITIES_TAG.to_string());
+    }
+    tags
+}
 
 /// Configuration for command result caching
 #[derive(Debug, Clone)]
@@ -20,6 +88,28 @@ pub struct CacheConfig {
     pub cleanup_interval: Duration,
     /// Maximum size of cached response data in bytes
     pub max_response_size: usize,
+    /// Learn each entry's TTL from how often its response changes
+    pub adaptive_ttl: Option<AdaptiveTtlConfig>,
+}
+
+/// Bounds for TTLs learned from how often a response changes
+///
+/// An entry starts at the default TTL. Each time it is stored again the TTL
+/// doubles if the response is unchanged and halves if it changed, so static
+/// scene data settles at `max_ttl` and fast-moving data near `min_ttl`.
+#[derive(Debug, Clone)]
+pub struct AdaptiveTtlConfig {
+    pub min_ttl: Duration,
+    pub max_ttl: Duration,
+}
+
+impl Default for AdaptiveTtlConfig {
+    fn default() -> Self {
+        Self {
+            min_ttl: Duration::from_millis(50),
+            max_ttl: Duration::from_secs(1800), // 30 minutes
+        }
+    }
 }
 
 impl Default for CacheConfig {
@@ -29,6 +119,7 @@ impl Default for CacheConfig {
             default_ttl: Duration::from_secs(300), // 5 minutes
             cleanup_interval: Duration::from_secs(60), // 1 minute
             max_response_size: 1024 * 1024, // 1MB per response
+            adaptive_ttl: None,
         }
     }
 }
@@ -73,8 +164,8 @@ impl CacheKey {
 /// Cached command result with metadata
 #[derive(Debug, Clone)]
 pub struct CachedResult {
-    /// The cached response value
-    pub response: Value,
+    /// The cached response, shared with every caller it is handed to
+    pub response: SharedResponse,
     /// When this entry was cached
     pub cached_at: Instant,
     /// TTL for this specific entry
@@ -117,6 +208,75 @@ pub struct CacheStatistics {
     pub newest_entry_age_seconds: f64,
     pub cleanup_runs: u64,
     pub evicted_entries: u64,
+    /// TTLs learned so far when adaptive TTLs are enabled
+    #[serde(default)]
+    pub learned_ttls: Vec<LearnedTtl>,
+}
+
+/// The TTL learned for one cache key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LearnedTtl {
+    pub tool_name: String,
+    pub key: String,
+    pub ttl_ms: u64,
+    /// Times the response was stored
+    pub observations: u64,
+    /// Times the stored response differed from the previous one
+    pub changes: u64,
+}
+
+/// How a key's response has behaved each time it was stored
+#[derive(Debug, Clone)]
+struct TtlLearning {
+    ttl: Duration,
+    content_digest: String,
+    observations: u64,
+    changes: u64,
+    last_seen: Instant,
+}
+
+/// Fields that differ on every call without the response itself changing
+const VOLATILE_FIELDS: &[&str] = &["timestamp", "execution_time_ms", "cache_hit"];
+
+/// Digest of `response` ignoring [`VOLATILE_FIELDS`]
+fn content_digest(response: &Value) -> String {
+    fn strip(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                map.retain(|key, _| !VOLATILE_FIELDS.contains(&key.as_str()));
+                map.values_mut().for_each(strip);
+            }
+            Value::Array(items) => items.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+    
+    let mut content = response.clone();
+    strip(&mut content);
+    format!("{:x}", Sha256::digest(content.to_string().as_bytes()))
+}
+
+/// Version of the on-disk snapshot format
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// A cache entry as written to the snapshot file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedEntry {
+    key: String,
+    response: Value,
+    /// Wall clock expiry in milliseconds since the Unix epoch
+    expires_at_ms: u64,
+    size_bytes: usize,
+    hit_count: u64,
+    tags: Vec<String>,
+}
+
+/// Snapshot file contents; `checksum` is the SHA-256 of the serialized entries
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheSnapshot {
+    version: u32,
+    checksum: String,
+    entries: Vec<PersistedEntry>,
 }
 
 /// Command result cache with LRU eviction and TTL support
@@ -129,27 +289,68 @@ pub struct CommandCache {
     stats: Arc<RwLock<CacheStatistics>>,
     /// Access order for LRU eviction
     access_order: Arc<RwLock<Vec<String>>>,
+    /// Snapshot file that lets entries survive restarts
+    persist_path: Option<PathBuf>,
+    /// Set when entries changed since the last snapshot
+    dirty: Arc<AtomicBool>,
+    /// Per-key change history behind adaptive TTLs
+    learned: Arc<RwLock<HashMap<String, TtlLearning>>>,
 }
 
 impl CommandCache {
     /// Create a new command cache
     pub fn new(config: CacheConfig) -> Self {
+        Self::build(config, None)
+    }
+    
+    /// Create a cache that is restored from and periodically saved to `path`
+    ///
+    /// A snapshot that fails its integrity check is discarded and the cache
+    /// starts empty. Expired entries are dropped on load.
+    pub fn with_persistence(config: CacheConfig, path: impl Into<PathBuf>) -> Self {
+        Self::build(config, Some(path.into()))
+    }
+    
+    fn build(config: CacheConfig, persist_path: Option<PathBuf>) -> Self {
+        let restored = match persist_path.as_deref().map(load_snapshot) {
+            Some(Ok(entries)) => entries,
+            Some(Err(e)) => {
+                warn!("Discarding command cache snapshot: {}", e);
+                if let Some(path) = &persist_path {
+                    let _ = std::fs::remove_file(path);
+                }
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        let restored: Vec<_> = restored.into_iter().take(config.max_entries).collect();
+        if !restored.is_empty() {
+            info!("Restored {} command cache entries from disk", restored.len());
+        }
+        let total_entries = restored.len();
+        let total_size_bytes = restored.iter().map(|(_, entry)| entry.size_bytes).sum();
+        let access_order = restored.iter().map(|(key, _)| key.clone()).collect();
+        
         let cache = Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache: Arc::new(RwLock::new(restored.into_iter().collect())),
             config,
             stats: Arc::new(RwLock::new(CacheStatistics {
-                total_entries: 0,
+                total_entries,
                 total_hits: 0,
                 total_misses: 0,
-                total_size_bytes: 0,
+                total_size_bytes,
                 hit_rate: 0.0,
                 average_entry_size: 0.0,
                 oldest_entry_age_seconds: 0.0,
                 newest_entry_age_seconds: 0.0,
                 cleanup_runs: 0,
                 evicted_entries: 0,
+                learned_ttls: Vec::new(),
             })),
-            access_order: Arc::new(RwLock::new(Vec::new())),
+            access_order: Arc::new(RwLock::new(access_order)),
+            persist_path,
+            dirty: Arc::new(AtomicBool::new(false)),
+            learned: Arc::new(RwLock::new(HashMap::new())),
         };
         
         // Start background cleanup task
@@ -160,6 +361,11 @@ impl CommandCache {
     
     /// Get a cached result if available and not expired
     pub async fn get(&self, key: &CacheKey) -> Option<Value> {
+        self.get_shared(key).await.map(SharedResponse::into_value)
+    }
+    
+    /// Get a cached result without copying it
+    pub async fn get_shared(&self, key: &CacheKey) -> Option<SharedResponse> {
         let string_key = key.to_string_key();
         let mut cache = self.cache.write().await;
         let mut stats = self.stats.write().await;
@@ -192,13 +398,12 @@ impl CommandCache {
     }
     
     /// Store a result in the cache
-    pub async fn put(&self, key: &CacheKey, response: Value, tags: Vec<String>) -> Result<()> {
+    pub async fn put(&self, key: &CacheKey, response: impl Into<SharedResponse>, tags: Vec<String>) -> Result<()> {
         let string_key = key.to_string_key();
+        let response = response.into();
         
-        // Calculate response size
-        let response_json = serde_json::to_string(&response)
-            .map_err(|e| Error::Validation(format!("Failed to serialize response: {}", e)))?;
-        let size_bytes = response_json.len();
+        // Calculate response size; the encoding is kept for whoever sends it
+        let size_bytes = response.to_bytes()?.len();
         
         // Check if response is too large
         if size_bytes > self.config.max_response_size {
@@ -207,10 +412,15 @@ impl CommandCache {
             return Ok(());
         }
         
+        let ttl = match &self.config.adaptive_ttl {
+            Some(adaptive) => self.learn_ttl(&string_key, response.value(), adaptive).await,
+            None => self.config.default_ttl,
+        };
+        
         let cached_result = CachedResult {
             response,
             cached_at: Instant::now(),
-            ttl: self.config.default_ttl,
+            ttl,
             size_bytes,
             hit_count: 0,
             tags,
@@ -235,19 +445,45 @@ impl CommandCache {
         
         stats.total_size_bytes += size_bytes;
         self.update_access_order(&string_key).await;
+        self.dirty.store(true, Ordering::Relaxed);
         
         debug!("Cached result for {} ({} bytes)", key.tool_name, size_bytes);
         Ok(())
     }
     
+    /// Update what is known about how often `key`'s response changes, returning its next TTL
+    async fn learn_ttl(&self, key: &str, response: &Value, adaptive: &AdaptiveTtlConfig) -> Duration {
+        let digest = content_digest(response);
+        let mut learned = self.learned.write().await;
+        let now = Instant::now();
+        let learning = learned.entry(key.to_string()).or_insert_with(|| TtlLearning {
+            ttl: self.config.default_ttl.clamp(adaptive.min_ttl, adaptive.max_ttl),
+            content_digest: digest.clone(),
+            observations: 0,
+            changes: 0,
+            last_seen: now,
+        });
+        if learning.observations > 0 {
+            learning.ttl = if learning.content_digest == digest {
+                learning.ttl.saturating_mul(2).min(adaptive.max_ttl)
+            } else {
+                learning.changes += 1;
+                (learning.ttl / 2).max(adaptive.min_ttl)
+            };
+        }
+        learning.observations += 1;
+        learning.content_digest = digest;
+        learning.last_seen = now;
+        learning.ttl
+    }
+    
     /// Store a result with custom TTL
-    pub async fn put_with_ttl(&self, key: &CacheKey, response: Value, ttl: Duration, tags: Vec<String>) -> Result<()> {
+    pub async fn put_with_ttl(&self, key: &CacheKey, response: impl Into<SharedResponse>, ttl: Duration, tags: Vec<String>) -> Result<()> {
         let string_key = key.to_string_key();
+        let response = response.into();
         
-        // Calculate response size
-        let response_json = serde_json::to_string(&response)
-            .map_err(|e| Error::Validation(format!("Failed to serialize response: {}", e)))?;
-        let size_bytes = response_json.len();
+        // Calculate response size; the encoding is kept for whoever sends it
+        let size_bytes = response.to_bytes()?.len();
         
         // Check if response is too large
         if size_bytes > self.config.max_response_size {
@@ -284,6 +520,7 @@ impl CommandCache {
         
         stats.total_size_bytes += size_bytes;
         self.update_access_order(&string_key).await;
+        self.dirty.store(true, Ordering::Relaxed);
         
         debug!("Cached result for {} with custom TTL {:?} ({} bytes)", key.tool_name, ttl, size_bytes);
         Ok(())
@@ -291,13 +528,29 @@ impl CommandCache {
     
     /// Invalidate cache entries by tag
     pub async fn invalidate_by_tag(&self, tag: &str) -> usize {
+        let count = self.invalidate_tags(&[tag.to_string()]).await;
+        info!("Invalidated {} cache entries with tag '{}'", count, tag);
+        count
+    }
+    
+    /// Invalidate the entries an entity mutation may have made stale
+    pub async fn invalidate_mutation(&self, mutation: &EntityMutation) -> usize {
+        let count = self.invalidate_tags(&mutation_tags(mutation)).await;
+        if count > 0 {
+            debug!("Invalidated {} cache entries after {:?} of entity {:?}", count, mutation.kind, mutation.entity);
+        }
+        count
+    }
+    
+    /// Invalidate cache entries carrying any of `tags`
+    async fn invalidate_tags(&self, tags: &[String]) -> usize {
         let mut cache = self.cache.write().await;
         let mut stats = self.stats.write().await;
         let mut access_order = self.access_order.write().await;
         
         let mut to_remove = Vec::new();
         for (key, cached_result) in cache.iter() {
-            if cached_result.tags.contains(&tag.to_string()) {
+            if cached_result.tags.iter().any(|tag| tags.contains(tag)) {
                 to_remove.push(key.clone());
             }
         }
@@ -311,8 +564,10 @@ impl CommandCache {
             }
             access_order.retain(|k| k != &key);
         }
+        if count > 0 {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
         
-        info!("Invalidated {} cache entries with tag '{}'", count, tag);
         count
     }
     
@@ -330,9 +585,34 @@ impl CommandCache {
         stats.total_size_bytes = 0;
         stats.evicted_entries += count as u64;
         
+        // A cleared cache must not come back on the next restart
+        if let Some(path) = &self.persist_path {
+            match std::fs::remove_file(path) {
+                Ok(()) => self.dirty.store(false, Ordering::Relaxed),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!("Failed to remove command cache snapshot {}: {}", path.display(), e),
+            }
+        }
+        
         info!("Cleared {} cache entries", count);
     }
     
+    /// Write the cache to its snapshot file now, returning how many entries were saved
+    pub async fn save(&self) -> Result<usize> {
+        let Some(path) = &self.persist_path else {
+            return Ok(0);
+        };
+        let cache = self.cache.read().await;
+        let saved = write_snapshot(path, &cache);
+        self.dirty.store(saved.is_err(), Ordering::Relaxed);
+        saved
+    }
+    
+    /// Whether entries survive restarts
+    pub fn is_persistent(&self) -> bool {
+        self.persist_path.is_some()
+    }
+    
     /// Get cache statistics
     pub async fn get_statistics(&self) -> CacheStatistics {
         let cache = self.cache.read().await;
@@ -369,6 +649,19 @@ impl CommandCache {
             newest_age.as_secs_f64()
         };
         
+        let mut learned_ttls: Vec<LearnedTtl> = self.learned.read().await
+            .iter()
+            .map(|(key, learning)| LearnedTtl {
+                tool_name: key.split(':').next().unwrap_or_default().to_string(),
+                key: key.clone(),
+                ttl_ms: learning.ttl.as_millis() as u64,
+                observations: learning.observations,
+                changes: learning.changes,
+            })
+            .collect();
+        learned_ttls.sort_by(|a, b| a.tool_name.cmp(&b.tool_name).then_with(|| a.key.cmp(&b.key)));
+        stats.learned_ttls = learned_ttls;
+        
         stats.clone()
     }
     
@@ -418,6 +711,12 @@ impl CommandCache {
         let stats = self.stats.clone();
         let access_order = self.access_order.clone();
         let cleanup_interval = self.config.cleanup_interval;
+        let persist_path = self.persist_path.clone();
+        let dirty = self.dirty.clone();
+        let learned = self.learned.clone();
+        // Forget keys not stored again within twice the longest TTL
+        let learning_horizon = self.config.adaptive_ttl.as_ref()
+            .map(|adaptive| adaptive.max_ttl.saturating_mul(2));
         
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(cleanup_interval);
@@ -449,6 +748,19 @@ impl CommandCache {
                 
                 stats_guard.cleanup_runs += 1;
                 
+                if let Some(horizon) = learning_horizon {
+                    learned.write().await.retain(|_, learning| learning.last_seen.elapsed() < horizon);
+                }
+                
+                if let Some(path) = &persist_path {
+                    if dirty.swap(false, Ordering::Relaxed) || expired_count > 0 {
+                        if let Err(e) = write_snapshot(path, &cache_guard) {
+                            dirty.store(true, Ordering::Relaxed);
+                            warn!("Failed to save command cache snapshot: {}", e);
+                        }
+                    }
+                }
+                
                 if expired_count > 0 {
                     debug!("Cache cleanup removed {} expired entries", expired_count);
                 }
@@ -457,6 +769,123 @@ impl CommandCache {
     }
 }
 
+#[async_trait]
+impl MemoryConsumer for CommandCache {
+    fn name(&self) -> &'static str {
+        "command_cache"
+    }
+    
+    /// Cached responses can always be fetched again, so they go first
+    fn eviction_priority(&self) -> u8 {
+        10
+    }
+    
+    async fn memory_usage(&self) -> usize {
+        self.cache.read().await.values().map(|entry| entry.size_bytes).sum()
+    }
+    
+    async fn evict(&self, bytes: usize) -> usize {
+        let mut cache = self.cache.write().await;
+        let mut stats = self.stats.write().await;
+        let mut access_order = self.access_order.write().await;
+        
+        let mut freed = 0;
+        while freed < bytes {
+            let Some(key) = access_order.pop() else {
+                break;
+            };
+            if let Some(removed) = cache.remove(&key) {
+                freed += removed.size_bytes;
+                stats.total_size_bytes = stats.total_size_bytes.saturating_sub(removed.size_bytes);
+                stats.total_entries = stats.total_entries.saturating_sub(1);
+                stats.evicted_entries += 1;
+            }
+        }
+        if freed > 0 {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        freed
+    }
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+fn snapshot_checksum(entries: &[PersistedEntry]) -> Result<String> {
+    let entries_json = serde_json::to_vec(entries)?;
+    Ok(format!("{:x}", Sha256::digest(&entries_json)))
+}
+
+/// Write unexpired entries to `path`, replacing the previous snapshot atomically
+fn write_snapshot(path: &Path, cache: &HashMap<String, CachedResult>) -> Result<usize> {
+    let now = SystemTime::now();
+    let entries: Vec<PersistedEntry> = cache
+        .iter()
+        .filter(|(_, entry)| !entry.is_expired())
+        .map(|(key, entry)| PersistedEntry {
+            key: key.clone(),
+            response: entry.response.value().clone(),
+            expires_at_ms: unix_millis(now + entry.ttl.saturating_sub(entry.age())),
+            size_bytes: entry.size_bytes,
+            hit_count: entry.hit_count,
+            tags: entry.tags.clone(),
+        })
+        .collect();
+    let snapshot = CacheSnapshot {
+        version: SNAPSHOT_VERSION,
+        checksum: snapshot_checksum(&entries)?,
+        entries,
+    };
+    
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, serde_json::to_vec(&snapshot)?)?;
+    std::fs::rename(&temp_path, path)?;
+    debug!("Saved {} command cache entries to {}", snapshot.entries.len(), path.display());
+    Ok(snapshot.entries.len())
+}
+
+/// Read the unexpired entries of the snapshot at `path`, most used first
+fn load_snapshot(path: &Path) -> Result<Vec<(String, CachedResult)>> {
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let snapshot: CacheSnapshot = serde_json::from_slice(&contents)
+        .map_err(|e| Error::Validation(format!("Unreadable cache snapshot: {}", e)))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(Error::Validation(format!("Unsupported cache snapshot version {}", snapshot.version)));
+    }
+    if snapshot_checksum(&snapshot.entries)? != snapshot.checksum {
+        return Err(Error::Validation("Cache snapshot failed its integrity check".to_string()));
+    }
+    
+    let now_ms = unix_millis(SystemTime::now());
+    let mut entries: Vec<PersistedEntry> = snapshot.entries
+        .into_iter()
+        .filter(|entry| entry.expires_at_ms > now_ms)
+        .collect();
+    entries.sort_by_key(|e| Reverse(e.hit_count));
+    Ok(entries
+        .into_iter()
+        .map(|entry| {
+            let cached = CachedResult {
+                response: SharedResponse::new(entry.response),
+                cached_at: Instant::now(),
+                ttl: Duration::from_millis(entry.expires_at_ms - now_ms),
+                size_bytes: entry.size_bytes,
+                hit_count: entry.hit_count,
+                tags: entry.tags,
+            };
+            (entry.key, cached)
+        })
+        .collect())
+}
+
 /// Get active feature flags as a string for cache key generation
 fn get_active_feature_flags() -> String {
     let mut flags = Vec::new();
@@ -518,6 +947,7 @@ mod tests {
             default_ttl: Duration::from_secs(1),
             cleanup_interval: Duration::from_secs(10),
             max_response_size: 1024,
+            adaptive_ttl: None,
         };
         let cache = CommandCache::new(config);
         
@@ -545,6 +975,7 @@ mod tests {
             default_ttl: Duration::from_millis(50),
             cleanup_interval: Duration::from_secs(10),
             max_response_size: 1024,
+            adaptive_ttl: None,
         };
         let cache = CommandCache::new(config);
         
@@ -582,4 +1013,115 @@ mod tests {
         assert!(cache.get(&key1).await.is_none());
         assert_eq!(cache.get(&key2).await.unwrap(), response2);
     }
+    
+    #[tokio::test]
+    async fn test_mutation_invalidation() {
+        use crate::brp_messages::BrpRequest;
+        
+        let cache = CommandCache::new(CacheConfig::default());
+        let player = json!({"result": {"type": "Entities", "data": [
+            {"id": 1, "components": {"Transform": {}, "Player": {}}}
+        ]}});
+        let enemies = json!({"result": {"type": "Entities", "data": [
+            {"id": 7, "components": {"Transform": {}, "Enemy": {}}},
+            {"id": 8, "components": {"Enemy": {}}}
+        ]}});
+        let health = json!({"status": "ok"});
+        
+        let keys: Vec<CacheKey> = ["player", "enemies", "empty", "health"].iter()
+            .map(|query| CacheKey::new("observe", &json!({"query": query})).unwrap())
+            .collect();
+        let entity_tags = |response: &Value| {
+            let mut tags = coverage_tags(response);
+            tags.push(ENTITY_DATA_TAG.to_string());
+            tags
+        };
+        cache.put(&keys[0], player.clone(), entity_tags(&player)).await.unwrap();
+        cache.put(&keys[1], enemies.clone(), entity_tags(&enemies)).await.unwrap();
+        cache.put(&keys[2], json!({"result": []}), entity_tags(&json!({"result": []}))).await.unwrap();
+        cache.put(&keys[3], health.clone(), vec!["system_health".to_string()]).await.unwrap();
+        
+        // Changing the player's Player component leaves enemy queries alone
+        let set = BrpRequest::Set {
+            entity: 1,
+            components: [("Player".to_string(), json!({}))].into_iter().collect(),
+        };
+        assert_eq!(cache.invalidate_mutation(&set.mutation().unwrap()).await, 2);
+        assert!(cache.get(&keys[0]).await.is_none());
+        assert_eq!(cache.get(&keys[1]).await.unwrap(), enemies);
+        
+        // Destroying an enemy only drops responses that included it
+        let destroy = BrpRequest::Destroy { entity: 8 };
+        assert_eq!(cache.invalidate_mutation(&destroy.mutation().unwrap()).await, 1);
+        
+        // Spawns may join any query, so all entity data goes
+        cache.put(&keys[0], player.clone(), entity_tags(&player)).await.unwrap();
+        let spawn = BrpRequest::Spawn { components: HashMap::new() };
+        assert_eq!(cache.invalidate_mutation(&spawn.mutation().unwrap()).await, 1);
+        assert_eq!(cache.get(&keys[3]).await.unwrap(), health);
+        assert!(BrpRequest::ListComponents.mutation().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_adaptive_ttl() {
+        let config = CacheConfig {
+            default_ttl: Duration::from_secs(4),
+            adaptive_ttl: Some(AdaptiveTtlConfig {
+                min_ttl: Duration::from_secs(1),
+                max_ttl: Duration::from_secs(10),
+            }),
+            ..CacheConfig::default()
+        };
+        let cache = CommandCache::new(config);
+        let scene = CacheKey::new("observe", &json!({"query": "list components"})).unwrap();
+        let positions = CacheKey::new("observe", &json!({"query": "find entities with Transform"})).unwrap();
+        
+        for step in 0..3 {
+            // Only the metadata timestamp changes for the scene query
+            let listing = json!({"result": ["Transform"], "metadata": {"timestamp": step}});
+            cache.put(&scene, listing, vec![]).await.unwrap();
+            cache.put(&positions, json!({"result": [{"x": step}]}), vec![]).await.unwrap();
+        }
+        
+        let stats = cache.get_statistics().await;
+        let learned = |key: &CacheKey| stats.learned_ttls.iter()
+            .find(|learned| learned.key == key.to_string_key())
+            .unwrap()
+            .clone();
+        let scene_ttl = learned(&scene);
+        assert_eq!(scene_ttl.ttl_ms, 10_000);
+        assert_eq!((scene_ttl.observations, scene_ttl.changes), (3, 0));
+        let positions_ttl = learned(&positions);
+        assert_eq!(positions_ttl.ttl_ms, 1_000);
+        assert_eq!(positions_ttl.changes, 2);
+        assert_eq!(positions_ttl.tool_name, "observe");
+    }
+    
+    #[tokio::test]
+    async fn test_persistence_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("commands.json");
+        let key = CacheKey::new("observe", &json!({"query": "list all entities"})).unwrap();
+        let schema = json!({"result": ["Transform", "Velocity"]});
+        
+        let cache = CommandCache::with_persistence(CacheConfig::default(), &path);
+        cache.put(&key, schema.clone(), vec!["schema".to_string()]).await.unwrap();
+        assert_eq!(cache.save().await.unwrap(), 1);
+        
+        let restarted = CommandCache::with_persistence(CacheConfig::default(), &path);
+        assert_eq!(restarted.get(&key).await.unwrap(), schema);
+        assert_eq!(restarted.get_statistics().await.total_entries, 1);
+        
+        // Clearing removes the snapshot too
+        restarted.clear().await;
+        assert!(!path.exists());
+        
+        // A tampered snapshot is discarded instead of served
+        cache.save().await.unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, contents.replace("Velocity", "Teleport")).unwrap();
+        let restarted = CommandCache::with_persistence(CacheConfig::default(), &path);
+        assert!(restarted.get(&key).await.is_none());
+        assert!(!path.exists());
+    }
 }
\ No newline at end of file
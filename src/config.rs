@@ -105,6 +105,8 @@ pub struct ObservabilityConfig {
     pub health_check_port: u16,
     pub sample_rate: f64,
     pub environment: String,
+    /// `service.name` reported on exported traces
+    pub service_name: String,
 }
 
 impl Default for ResilienceConfig {
@@ -132,6 +134,7 @@ impl Default for ObservabilityConfig {
             health_check_port: 8080,
             sample_rate: 1.0,
             environment: "development".to_string(),
+            service_name: "bevy-debugger-mcp".to_string(),
         }
     }
 }
@@ -268,6 +271,10 @@ impl Config {
             observability.environment = val;
         }
 
+        if let Ok(val) = env::var("OTEL_SERVICE_NAME") {
+            observability.service_name = val;
+        }
+
         Ok(Config {
             bevy_brp_host,
             bevy_brp_port,
@@ -0,0 +1,225 @@
+/*
+ * Bevy Debugger MCP Server - Crash Reports
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Diagnostic bundles written when the server panics
+//!
+//! [`install`] adds a panic hook that writes a bundle directory holding
+//! `crash.json` (panic message, location, process metrics), `backtrace.txt`
+//! and `recent.log` from the in-memory log buffer. The next start-up reports
+//! bundles nobody has seen yet via [`announce_previous`], so they can be
+//! attached to an issue.
+//!
+//! Only panics are caught. Fatal signals such as SIGSEGV can't safely run
+//! this code, and they shouldn't happen in safe Rust.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+// Rust 1.70 has no `PanicHookInfo`; later releases keep `PanicInfo` as a deprecated alias
+#[allow(deprecated)]
+use std::panic::PanicInfo;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::error::Result;
+use crate::log_buffer::{self, LogQuery};
+use crate::process_metrics::{ProcessMetrics, ProcessSampler};
+
+/// Directory bundles are written to unless `BEVY_MCP_CRASH_DIR` is set
+pub const DEFAULT_CRASH_DIR: &str = "./crashes";
+
+/// Written into a bundle once it has been announced at start-up
+const REPORTED_MARKER: &str = ".reported";
+
+/// Log lines kept in a bundle
+const CRASH_LOG_LINES: usize = 500;
+
+/// Contents of a bundle's `crash.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub crashed_at: DateTime<Utc>,
+    pub crate_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line:column` of the panic
+    pub location: Option<String>,
+    pub process: ProcessMetrics,
+}
+
+impl CrashReport {
+    #[allow(deprecated)]
+    pub fn from_panic(info: &PanicInfo<'_>) -> Self {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic payload".to_string());
+
+        Self {
+            crashed_at: Utc::now(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: std::thread::current().name().map(str::to_string),
+            message,
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            // A fresh sampler; the shared one may be locked by the panicking thread
+            process: ProcessSampler::new().sample(),
+        }
+    }
+}
+
+/// Crash directory from `BEVY_MCP_CRASH_DIR`, or [`DEFAULT_CRASH_DIR`]
+pub fn crash_dir() -> PathBuf {
+    std::env::var("BEVY_MCP_CRASH_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_CRASH_DIR))
+}
+
+/// Write a bundle under `crash_dir` on every panic, then run the previous hook
+pub fn install(crash_dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match write_bundle(
+            &crash_dir,
+            &CrashReport::from_panic(info),
+            &Backtrace::force_capture(),
+        ) {
+            Ok(bundle) => eprintln!("Crash report written to {}", bundle.display()),
+            Err(e) => eprintln!("Failed to write crash report: {e}"),
+        }
+        previous(info);
+    }));
+}
+
+/// Write a bundle directory for `report`, returning its path
+pub fn write_bundle(
+    crash_dir: &Path,
+    report: &CrashReport,
+    backtrace: &Backtrace,
+) -> Result<PathBuf> {
+    let bundle = crash_dir.join(format!(
+        "crash-{}-{}",
+        report.crashed_at.format("%Y%m%dT%H%M%S%.3fZ"),
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&bundle)?;
+
+    std::fs::write(
+        bundle.join("crash.json"),
+        serde_json::to_vec_pretty(report)?,
+    )?;
+    std::fs::write(bundle.join("backtrace.txt"), backtrace.to_string())?;
+
+    // The panicking thread may hold the buffer's lock; skip the logs rather than deadlock
+    let logs = log_buffer::global()
+        .try_recent(LogQuery {
+            min_level: tracing::Level::TRACE,
+            limit: CRASH_LOG_LINES,
+        })
+        .map(|records| {
+            records
+                .iter()
+                .map(|record| format!("{record}\n"))
+                .collect::<String>()
+        })
+        .unwrap_or_else(|| "Log buffer was locked when the crash happened\n".to_string());
+    std::fs::write(bundle.join("recent.log"), logs)?;
+
+    Ok(bundle)
+}
+
+/// Bundles in `crash_dir` that haven't been announced yet, oldest first
+pub fn unreported_bundles(crash_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(crash_dir) else {
+        return Vec::new();
+    };
+    let mut bundles: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.join("crash.json").is_file())
+        .filter(|path| !path.join(REPORTED_MARKER).exists())
+        .collect();
+    // Names start with the crash time, so they sort chronologically
+    bundles.sort();
+    bundles
+}
+
+/// The newest bundle announced by [`announce_previous`] in this run
+pub fn previous_crash() -> Option<&'static Path> {
+    PREVIOUS_CRASH.get().map(PathBuf::as_path)
+}
+
+static PREVIOUS_CRASH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Warn about crashes from earlier runs, once per bundle
+pub fn announce_previous(crash_dir: &Path) {
+    let bundles = unreported_bundles(crash_dir);
+    for bundle in &bundles {
+        warn!(
+            "A previous run crashed; attach {} when reporting the issue",
+            bundle.display()
+        );
+        if let Err(e) = std::fs::write(bundle.join(REPORTED_MARKER), b"") {
+            warn!(
+                "Failed to mark crash report {} as seen: {}",
+                bundle.display(),
+                e
+            );
+        }
+    }
+    if let Some(latest) = bundles.last() {
+        let _ = PREVIOUS_CRASH.set(latest.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_is_announced_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = CrashReport {
+            crashed_at: Utc::now(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            thread: Some("main".to_string()),
+            message: "index out of bounds".to_string(),
+            location: Some("src/replay.rs:10:5".to_string()),
+            process: ProcessMetrics::default(),
+        };
+
+        let bundle = write_bundle(dir.path(), &report, &Backtrace::disabled()).unwrap();
+        let saved: CrashReport =
+            serde_json::from_slice(&std::fs::read(bundle.join("crash.json")).unwrap()).unwrap();
+        assert_eq!(saved.message, "index out of bounds");
+        assert!(bundle.join("backtrace.txt").is_file());
+        assert!(bundle.join("recent.log").is_file());
+
+        assert_eq!(unreported_bundles(dir.path()), vec![bundle.clone()]);
+        announce_previous(dir.path());
+        assert!(unreported_bundles(dir.path()).is_empty());
+    }
+}
@@ -1,11 +1,97 @@
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
-use crate::error::{ErrorContext, Result};
+use crate::error::{Error, ErrorContext, Result};
+use crate::memory_budget::MemoryConsumer;
+
+/// How often the retrier looks for operations due for another attempt
+pub const RETRY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+tokio::task_local! {
+    static RETRYING: ();
+}
+
+/// Run `future` as an automatic retry, so failures inside it aren't queued again
+pub async fn with_retry_scope<F: Future>(future: F) -> F::Output {
+    RETRYING.scope((), future).await
+}
+
+/// Whether the current task is an automatic retry
+pub fn is_retry() -> bool {
+    RETRYING.try_with(|_| ()).is_ok()
+}
+
+/// How a failed operation is retried automatically
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Automatic attempts before the operation is left for manual handling
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Growth of the backoff between attempts
+    pub multiplier: f64,
+    /// Fraction of each delay that is randomized away, from 0 to 1
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn validate(&self) -> Result<()> {
+        if !self.multiplier.is_finite() || self.multiplier < 1.0 {
+            return Err(Error::Validation(
+                "Retry multiplier must be at least 1".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(Error::Validation(
+                "Retry jitter must be between 0 and 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delay before automatic attempt `attempt` (1-based), without jitter
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(64) as i32;
+        let backoff = self.initial_backoff_ms as f64 * self.multiplier.powi(exponent);
+        Duration::from_millis(backoff.min(self.max_backoff_ms as f64) as u64)
+    }
+
+    /// [`backoff`](Self::backoff) shortened by a random share of up to `jitter`
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff(attempt)
+            .mul_f64(1.0 - self.jitter * rand::random::<f64>())
+    }
+}
+
+/// Result of one automatic retry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryOutcome {
+    pub attempt: u32,
+    /// Seconds since the UNIX epoch
+    pub timestamp: u64,
+    pub succeeded: bool,
+    pub error: Option<String>,
+}
 
 /// Failed operation record for dead letter queue
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +116,12 @@ pub struct FailedOperation {
     pub failure_reason: String,
     /// Whether this operation can still be retried manually
     pub can_retry: bool,
+    /// When the next automatic retry is due, in milliseconds since the UNIX epoch
+    #[serde(default)]
+    pub next_retry_at_ms: Option<u64>,
+    /// Automatic retries made so far
+    #[serde(default)]
+    pub retry_outcomes: Vec<RetryOutcome>,
 }
 
 impl FailedOperation {
@@ -60,6 +152,8 @@ impl FailedOperation {
             request_data,
             failure_reason: failure_reason.to_string(),
             can_retry: true,
+            next_retry_at_ms: None,
+            retry_outcomes: Vec::new(),
         }
     }
 }
@@ -91,6 +185,51 @@ impl Default for DeadLetterConfig {
     }
 }
 
+/// Which failed operations a query returns
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeadLetterFilter {
+    pub component: Option<String>,
+    pub operation: Option<String>,
+    /// Earliest failure time, in seconds since the epoch
+    pub since: Option<u64>,
+    /// Latest failure time, in seconds since the epoch
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl DeadLetterFilter {
+    pub fn matches(&self, operation: &FailedOperation) -> bool {
+        !self
+            .component
+            .as_ref()
+            .is_some_and(|component| operation.component != *component)
+            && !self
+                .operation
+                .as_ref()
+                .is_some_and(|name| operation.operation != *name)
+            && !self
+                .since
+                .is_some_and(|since| operation.failed_timestamp < since)
+            && !self
+                .until
+                .is_some_and(|until| operation.failed_timestamp > until)
+    }
+}
+
+/// Durable storage mirroring the queue
+///
+/// Calls block, so the queue makes them from `spawn_blocking`.
+pub trait DeadLetterStore: Send + Sync + std::fmt::Debug {
+    /// Insert or replace `operation`
+    fn save(&self, operation: &FailedOperation) -> Result<()>;
+    fn remove(&self, id: &str) -> Result<()>;
+    /// Delete operations that failed at or before `cutoff`, returning how many
+    fn purge_before(&self, cutoff: u64) -> Result<usize>;
+    /// Matching operations, oldest failure first
+    fn query(&self, filter: &DeadLetterFilter) -> Result<Vec<FailedOperation>>;
+}
+
 /// Dead letter queue for managing permanently failed operations
 #[derive(Debug)]
 pub struct DeadLetterQueue {
@@ -98,6 +237,12 @@ pub struct DeadLetterQueue {
     queue: Arc<RwLock<VecDeque<FailedOperation>>>,
     cleanup_handle: Option<tokio::task::JoinHandle<()>>,
     shutdown_tx: Option<mpsc::Sender<()>>,
+    /// Retry policies by operation; operations without one are only retried manually
+    retry_policies: Arc<RwLock<HashMap<String, RetryPolicy>>>,
+    retries_succeeded: Arc<AtomicU64>,
+    retries_failed: Arc<AtomicU64>,
+    /// Durable copy of the queue, written alongside every change
+    store: Option<Arc<dyn DeadLetterStore>>,
 }
 
 impl DeadLetterQueue {
@@ -107,7 +252,60 @@ impl DeadLetterQueue {
             queue: Arc::new(RwLock::new(VecDeque::new())),
             cleanup_handle: None,
             shutdown_tx: None,
+            retry_policies: Arc::new(RwLock::new(HashMap::new())),
+            retries_succeeded: Arc::new(AtomicU64::new(0)),
+            retries_failed: Arc::new(AtomicU64::new(0)),
+            store: None,
+        }
+    }
+
+    /// Mirror the queue into `store`, which replaces the JSON file as its persistence
+    pub fn with_store(mut self, store: Arc<dyn DeadLetterStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Retry failures of `operation` automatically under `policy`, or stop with `None`
+    pub async fn set_retry_policy(
+        &self,
+        operation: &str,
+        policy: Option<RetryPolicy>,
+    ) -> Result<()> {
+        let mut policies = self.retry_policies.write().await;
+        match policy {
+            Some(policy) => {
+                policy.validate()?;
+                policies.insert(operation.to_string(), policy);
+            }
+            None => {
+                policies.remove(operation);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn retry_policy(&self, operation: &str) -> Option<RetryPolicy> {
+        self.retry_policies.read().await.get(operation).cloned()
+    }
+
+    pub async fn retry_policies(&self) -> HashMap<String, RetryPolicy> {
+        self.retry_policies.read().await.clone()
+    }
+
+    /// Add the policies in the JSON file named by `BEVY_MCP_DLQ_RETRY_POLICIES`,
+    /// an object keyed by operation, returning how many
+    pub async fn load_retry_policies_from_env(&self) -> Result<usize> {
+        let Ok(path) = std::env::var("BEVY_MCP_DLQ_RETRY_POLICIES") else {
+            return Ok(0);
+        };
+        let data = tokio::fs::read_to_string(&path).await?;
+        let policies: HashMap<String, RetryPolicy> = serde_json::from_str(&data)
+            .map_err(|e| Error::Config(format!("Invalid retry policies in {path}: {e}")))?;
+        let count = policies.len();
+        for (operation, policy) in policies {
+            self.set_retry_policy(&operation, Some(policy)).await?;
         }
+        Ok(count)
     }
 
     /// Start the dead letter queue with automatic cleanup
@@ -117,6 +315,7 @@ impl DeadLetterQueue {
 
         let queue = self.queue.clone();
         let config = self.config.clone();
+        let store = self.store.clone();
 
         let handle = tokio::spawn(async move {
             let mut interval =
@@ -125,7 +324,7 @@ impl DeadLetterQueue {
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
-                        Self::cleanup_expired(&queue, &config).await;
+                        Self::cleanup_expired(&queue, &config, store.as_ref()).await;
                     }
                     _ = shutdown_rx.recv() => {
                         info!("Dead letter queue cleanup shutting down");
@@ -144,16 +343,24 @@ impl DeadLetterQueue {
     }
 
     /// Add a failed operation to the dead letter queue
-    pub async fn add_failed_operation(&self, failed_operation: FailedOperation) -> Result<()> {
+    pub async fn add_failed_operation(&self, mut failed_operation: FailedOperation) -> Result<()> {
+        if failed_operation.can_retry && failed_operation.next_retry_at_ms.is_none() {
+            if let Some(policy) = self.retry_policy(&failed_operation.operation).await {
+                schedule_retry(&mut failed_operation, &policy);
+            }
+        }
+
         let mut queue = self.queue.write().await;
 
         // Check if we need to make room
+        let mut dropped = Vec::new();
         while queue.len() >= self.config.max_size {
             if let Some(oldest) = queue.pop_front() {
                 warn!(
                     "Dead letter queue full, dropping oldest operation: {}",
                     oldest.id
                 );
+                dropped.push(oldest.id);
             }
         }
 
@@ -162,7 +369,16 @@ impl DeadLetterQueue {
             failed_operation.operation, failed_operation.retry_count
         );
 
-        queue.push_back(failed_operation);
+        queue.push_back(failed_operation.clone());
+        drop(queue);
+
+        self.write_to_store(move |store| {
+            for id in &dropped {
+                store.remove(id)?;
+            }
+            store.save(&failed_operation)
+        })
+        .await;
 
         // Persist to disk if configured
         if self.config.persist_to_disk {
@@ -210,13 +426,94 @@ impl DeadLetterQueue {
 
         if let Some(pos) = queue.iter().position(|op| op.id == id) {
             let operation = queue.remove(pos).unwrap();
+            drop(queue);
             info!("Removed failed operation from dead letter queue: {}", id);
+            let id = id.to_string();
+            self.write_to_store(move |store| store.remove(&id)).await;
             Ok(Some(operation))
         } else {
             Ok(None)
         }
     }
 
+    /// Operations whose automatic retry is due at `now_ms`
+    ///
+    /// They stay queued but aren't returned again until their outcome is recorded.
+    pub async fn take_due_retries(&self, now_ms: u64) -> Vec<FailedOperation> {
+        let mut queue = self.queue.write().await;
+        queue
+            .iter_mut()
+            .filter(|op| op.can_retry && op.next_retry_at_ms.is_some_and(|at| at <= now_ms))
+            .map(|op| {
+                op.next_retry_at_ms = None;
+                op.clone()
+            })
+            .collect()
+    }
+
+    /// Record an automatic retry of `id`
+    ///
+    /// A success removes the operation. A failure schedules the next attempt,
+    /// or leaves the operation for manual handling once the policy is used up.
+    pub async fn record_retry_outcome(&self, id: &str, outcome: std::result::Result<(), String>) {
+        let policies = self.retry_policies.read().await;
+        let mut queue = self.queue.write().await;
+        let Some(pos) = queue.iter().position(|op| op.id == id) else {
+            return;
+        };
+
+        let operation = &mut queue[pos];
+        let attempt = operation.retry_outcomes.len() as u32 + 1;
+        operation.retry_outcomes.push(RetryOutcome {
+            attempt,
+            timestamp: now_secs(),
+            succeeded: outcome.is_ok(),
+            error: outcome.as_ref().err().cloned(),
+        });
+
+        match outcome {
+            Ok(()) => {
+                self.retries_succeeded.fetch_add(1, Ordering::Relaxed);
+                info!(
+                    "Retry {} of {} succeeded; removing it from the dead letter queue",
+                    attempt, operation.operation
+                );
+                queue.remove(pos);
+                drop(queue);
+                let id = id.to_string();
+                self.write_to_store(move |store| store.remove(&id)).await;
+            }
+            Err(reason) => {
+                self.retries_failed.fetch_add(1, Ordering::Relaxed);
+                operation.retry_count += 1;
+                operation.failed_timestamp = now_secs();
+                operation.failure_reason = reason;
+                match policies.get(&operation.operation) {
+                    Some(policy) if attempt < policy.max_attempts => {
+                        schedule_retry(operation, policy);
+                    }
+                    _ => {
+                        warn!(
+                            "Giving up automatic retries of {} after {} attempts",
+                            operation.operation, attempt
+                        );
+                    }
+                }
+                let operation = operation.clone();
+                drop(queue);
+                self.write_to_store(move |store| store.save(&operation))
+                    .await;
+            }
+        }
+    }
+
+    /// The queue as seen by the process memory budget
+    pub fn memory_consumer(&self) -> Arc<dyn MemoryConsumer> {
+        Arc::new(DeadLetterMemory {
+            queue: self.queue.clone(),
+        })
+    }
+
     /// Get statistics about the dead letter queue
     pub async fn get_statistics(&self) -> DeadLetterStats {
         let queue = self.queue.read().await;
@@ -228,6 +525,9 @@ impl DeadLetterQueue {
             oldest_timestamp: None,
             newest_timestamp: None,
             total_retry_attempts: 0,
+            pending_retries: 0,
+            retries_succeeded: self.retries_succeeded.load(Ordering::Relaxed),
+            retries_failed: self.retries_failed.load(Ordering::Relaxed),
         };
 
         for operation in queue.iter() {
@@ -256,6 +556,9 @@ impl DeadLetterQueue {
             }
 
             stats.total_retry_attempts += operation.retry_count;
+            if operation.next_retry_at_ms.is_some() {
+                stats.pending_retries += 1;
+            }
         }
 
         stats
@@ -265,6 +568,7 @@ impl DeadLetterQueue {
     async fn cleanup_expired(
         queue: &Arc<RwLock<VecDeque<FailedOperation>>>,
         config: &DeadLetterConfig,
+        store: Option<&Arc<dyn DeadLetterStore>>,
     ) {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -294,6 +598,44 @@ impl DeadLetterQueue {
             "Dead letter queue cleanup complete. Current size: {}",
             queue_guard.len()
         );
+        drop(queue_guard);
+
+        if let Some(store) = store {
+            let purged = run_blocking(store.clone(), move |store| store.purge_before(cutoff)).await;
+            if let Err(e) = purged {
+                error!(
+                    "Failed to purge expired operations from dead letter store: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    /// Failed operations matching `filter`, oldest failure first
+    ///
+    /// With a store this includes operations no longer held in memory.
+    pub async fn query(&self, filter: DeadLetterFilter) -> Result<Vec<FailedOperation>> {
+        if let Some(store) = &self.store {
+            return run_blocking(store.clone(), move |store| store.query(&filter)).await;
+        }
+        let queue = self.queue.read().await;
+        let matching = queue.iter().filter(|op| filter.matches(op)).cloned();
+        Ok(match filter.limit {
+            Some(limit) => matching.take(limit).collect(),
+            None => matching.collect(),
+        })
+    }
+
+    /// Apply `write` to the store, if there is one, logging failures
+    async fn write_to_store<F>(&self, write: F)
+    where
+        F: FnOnce(&dyn DeadLetterStore) -> Result<()> + Send + 'static,
+    {
+        if let Some(store) = &self.store {
+            if let Err(e) = run_blocking(store.clone(), write).await {
+                error!("Failed to update dead letter store: {}", e);
+            }
+        }
     }
 
     /// Persist the queue to disk (if configured)
@@ -307,8 +649,22 @@ impl DeadLetterQueue {
         Ok(())
     }
 
-    /// Load the queue from disk (if configured and file exists)
+    /// Load the queue from the store, or from disk (if configured and file exists)
     pub async fn load_from_disk(&self) -> Result<()> {
+        if let Some(store) = &self.store {
+            let mut operations = run_blocking(store.clone(), |store| {
+                store.query(&DeadLetterFilter::default())
+            })
+            .await?;
+            // Keep the newest when the store holds more than fits in memory
+            let excess = operations.len().saturating_sub(self.config.max_size);
+            operations.drain(..excess);
+
+            let mut queue = self.queue.write().await;
+            *queue = operations.into();
+            info!("Loaded {} operations from dead letter store", queue.len());
+            return Ok(());
+        }
         if let Some(ref path) = self.config.persistence_path {
             if tokio::fs::metadata(path).await.is_ok() {
                 let data = tokio::fs::read_to_string(path).await?;
@@ -349,6 +705,126 @@ impl DeadLetterQueue {
     }
 }
 
+/// The store named by `BEVY_MCP_DLQ_SQLITE`, a SQLite database path, if set
+pub fn store_from_env() -> Result<Option<Arc<dyn DeadLetterStore>>> {
+    let Ok(path) = std::env::var("BEVY_MCP_DLQ_SQLITE") else {
+        return Ok(None);
+    };
+    #[cfg(feature = "sqlite-dlq")]
+    {
+        let store = crate::dead_letter_store::SqliteDeadLetterStore::open(&path)?;
+        Ok(Some(Arc::new(store)))
+    }
+    #[cfg(not(feature = "sqlite-dlq"))]
+    {
+        Err(Error::Config(format!(
+            "Dead letter store {} requires the sqlite-dlq feature",
+            path
+        )))
+    }
+}
+
+/// Run `f` against `store` on the blocking thread pool
+async fn run_blocking<T, F>(store: Arc<dyn DeadLetterStore>, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn DeadLetterStore) -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(store.as_ref()))
+        .await
+        .map_err(|e| Error::Internal(format!("Dead letter store task failed: {e}")))?
+}
+
+/// Schedule the next automatic attempt of `operation` under `policy`, if any are left
+fn schedule_retry(operation: &mut FailedOperation, policy: &RetryPolicy) {
+    let attempt = operation.retry_outcomes.len() as u32 + 1;
+    if attempt > policy.max_attempts {
+        return;
+    }
+    operation.next_retry_at_ms = Some(now_ms() + policy.delay(attempt).as_millis() as u64);
+}
+
+/// Re-run due operations with `execute` in the background, recording each outcome
+///
+/// `execute` runs inside [`with_retry_scope`] and returns the failure reason
+/// when the operation fails again.
+pub fn spawn_retrier<F, Fut>(queue: Arc<RwLock<DeadLetterQueue>>, execute: F)
+where
+    F: Fn(FailedOperation) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = std::result::Result<(), String>> + Send,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RETRY_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let due = queue.read().await.take_due_retries(now_ms()).await;
+            for operation in due {
+                debug!(
+                    "Retrying failed operation {} ({})",
+                    operation.id, operation.operation
+                );
+                let outcome = with_retry_scope(execute(operation.clone())).await;
+                queue
+                    .read()
+                    .await
+                    .record_retry_outcome(&operation.id, outcome)
+                    .await;
+            }
+        }
+    });
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn now_secs() -> u64 {
+    now_ms() / 1000
+}
+
+/// Approximate size of a failed operation once serialized
+fn operation_size(operation: &FailedOperation) -> usize {
+    serde_json::to_vec(operation).map(|v| v.len()).unwrap_or(0)
+}
+
+struct DeadLetterMemory {
+    queue: Arc<RwLock<VecDeque<FailedOperation>>>,
+}
+
+#[async_trait]
+impl MemoryConsumer for DeadLetterMemory {
+    fn name(&self) -> &'static str {
+        "dead_letter_queue"
+    }
+
+    fn eviction_priority(&self) -> u8 {
+        40
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.queue.read().await.iter().map(operation_size).sum()
+    }
+
+    /// Oldest failures go first, as they do when the queue is full
+    async fn evict(&self, bytes: usize) -> usize {
+        let mut queue = self.queue.write().await;
+        let mut freed = 0;
+        while freed < bytes {
+            let Some(oldest) = queue.pop_front() else {
+                break;
+            };
+            freed += operation_size(&oldest);
+        }
+        if freed > 0 {
+            warn!("Memory budget evicted {} bytes of failed operations", freed);
+        }
+        freed
+    }
+}
+
 /// Statistics about the dead letter queue
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeadLetterStats {
@@ -358,6 +834,10 @@ pub struct DeadLetterStats {
     pub oldest_timestamp: Option<u64>,
     pub newest_timestamp: Option<u64>,
     pub total_retry_attempts: u32,
+    /// Operations with an automatic retry scheduled
+    pub pending_retries: usize,
+    pub retries_succeeded: u64,
+    pub retries_failed: u64,
 }
 
 impl Drop for DeadLetterQueue {
@@ -0,0 +1,208 @@
+/*
+ * Bevy Debugger MCP Server - SQLite Dead Letter Queue Store
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! SQLite persistence for the dead letter queue
+//!
+//! Each failed operation is one row, written in its own transaction, so a
+//! crash loses at most the operation being written rather than the whole
+//! file. Component, operation and failure time are indexed columns; the rest
+//! of the record is stored as JSON.
+
+use rusqlite::{params, params_from_iter, Connection};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::dead_letter_queue::{DeadLetterFilter, DeadLetterStore, FailedOperation};
+use crate::error::{Error, Result};
+
+// WAL lets readers run alongside the writer and recovers cleanly from torn writes
+const SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    CREATE TABLE IF NOT EXISTS failed_operations (
+        id TEXT PRIMARY KEY,
+        component TEXT NOT NULL,
+        operation TEXT NOT NULL,
+        failed_timestamp INTEGER NOT NULL,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS failed_operations_component
+        ON failed_operations (component, failed_timestamp);
+    CREATE INDEX IF NOT EXISTS failed_operations_operation
+        ON failed_operations (operation, failed_timestamp);
+    CREATE INDEX IF NOT EXISTS failed_operations_failed_timestamp
+        ON failed_operations (failed_timestamp);
+";
+
+/// Failed operations kept in a SQLite database
+#[derive(Debug)]
+pub struct SqliteDeadLetterStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteDeadLetterStore {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let connection = Connection::open(path).map_err(sqlite_error)?;
+        connection.execute_batch(SCHEMA).map_err(sqlite_error)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.connection
+            .lock()
+            .map_err(|_| Error::Internal("Dead letter store lock poisoned".to_string()))
+    }
+}
+
+impl DeadLetterStore for SqliteDeadLetterStore {
+    fn save(&self, operation: &FailedOperation) -> Result<()> {
+        let record = serde_json::to_string(operation)?;
+        let mut connection = self.connection()?;
+        let transaction = connection.transaction().map_err(sqlite_error)?;
+        transaction
+            .execute(
+                "INSERT OR REPLACE INTO failed_operations
+                     (id, component, operation, failed_timestamp, record)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    operation.id,
+                    operation.component,
+                    operation.operation,
+                    operation.failed_timestamp as i64,
+                    record
+                ],
+            )
+            .map_err(sqlite_error)?;
+        transaction.commit().map_err(sqlite_error)
+    }
+
+    fn remove(&self, id: &str) -> Result<()> {
+        self.connection()?
+            .execute("DELETE FROM failed_operations WHERE id = ?1", params![id])
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn purge_before(&self, cutoff: u64) -> Result<usize> {
+        self.connection()?
+            .execute(
+                "DELETE FROM failed_operations WHERE failed_timestamp <= ?1",
+                params![cutoff as i64],
+            )
+            .map_err(sqlite_error)
+    }
+
+    fn query(&self, filter: &DeadLetterFilter) -> Result<Vec<FailedOperation>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+        if let Some(component) = &filter.component {
+            values.push(component.clone().into());
+            conditions.push(format!("component = ?{}", values.len()));
+        }
+        if let Some(operation) = &filter.operation {
+            values.push(operation.clone().into());
+            conditions.push(format!("operation = ?{}", values.len()));
+        }
+        if let Some(since) = filter.since {
+            values.push((since as i64).into());
+            conditions.push(format!("failed_timestamp >= ?{}", values.len()));
+        }
+        if let Some(until) = filter.until {
+            values.push((until as i64).into());
+            conditions.push(format!("failed_timestamp <= ?{}", values.len()));
+        }
+
+        let mut sql = "SELECT record FROM failed_operations".to_string();
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY failed_timestamp, rowid");
+        if let Some(limit) = filter.limit {
+            sql.push_str(&format!(" LIMIT {limit}"));
+        }
+
+        let connection = self.connection()?;
+        let mut statement = connection.prepare(&sql).map_err(sqlite_error)?;
+        let records = statement
+            .query_map(params_from_iter(values), |row| row.get::<_, String>(0))
+            .map_err(sqlite_error)?;
+        let operations = records
+            .map(|record| Ok(serde_json::from_str(&record.map_err(sqlite_error)?)?))
+            .collect();
+        operations
+    }
+}
+
+fn sqlite_error(e: rusqlite::Error) -> Error {
+    Error::Internal(format!("Dead letter store error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorContext;
+    use serde_json::json;
+
+    fn failed(operation: &str, component: &str, failed_timestamp: u64) -> FailedOperation {
+        let mut failed = FailedOperation::new(
+            operation,
+            component,
+            1,
+            ErrorContext::new(operation, component),
+            json!({}),
+            "Connection error",
+        );
+        failed.failed_timestamp = failed_timestamp;
+        failed
+    }
+
+    #[test]
+    fn test_store_survives_reopen_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dead_letters.db");
+        {
+            let store = SqliteDeadLetterStore::open(&path).unwrap();
+            store.save(&failed("observe", "mcp_server", 100)).unwrap();
+            store.save(&failed("replay", "mcp_server", 200)).unwrap();
+            store.save(&failed("observe", "brp_client", 300)).unwrap();
+        }
+
+        let store = SqliteDeadLetterStore::open(&path).unwrap();
+        assert_eq!(store.query(&DeadLetterFilter::default()).unwrap().len(), 3);
+
+        let observe = store
+            .query(&DeadLetterFilter {
+                operation: Some("observe".to_string()),
+                since: Some(150),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(observe.len(), 1);
+        assert_eq!(observe[0].component, "brp_client");
+
+        assert_eq!(store.purge_before(200).unwrap(), 2);
+        store.remove(&observe[0].id).unwrap();
+        assert!(store
+            .query(&DeadLetterFilter::default())
+            .unwrap()
+            .is_empty());
+    }
+}
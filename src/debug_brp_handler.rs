@@ -50,7 +50,18 @@ impl BrpCommandHandler for DebugBrpHandler {
                 "GetSystemInfo".to_string(),
                 "ProfileSystem".to_string(),
                 "SetVisualDebug".to_string(),
+                "PlaceMarker".to_string(),
+                "RemoveMarker".to_string(),
+                "ClearMarkers".to_string(),
+                "ListMarkers".to_string(),
+                "DefineOverlay".to_string(),
+                "RemoveCustomOverlay".to_string(),
+                "ManipulateTransform".to_string(),
+                "GetOverlayState".to_string(),
+                "ResolveAssetHandles".to_string(),
+                "SetTheme".to_string(),
                 "ValidateQuery".to_string(),
+                "QuerySuggest".to_string(),
                 "ProfileMemory".to_string(),
                 "CreateSession".to_string(),
                 "StartIssueDetection".to_string(),
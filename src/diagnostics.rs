@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
 use crate::dead_letter_queue::{DeadLetterQueue, DeadLetterStats};
 use crate::error::{ErrorContext, Result};
+use crate::log_buffer::{self, LogQuery};
+use crate::process_metrics;
+use crate::redaction::REDACTED;
+use crate::report_redaction::ReportRedactionPolicy;
 
 /// System information for diagnostic reports
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +22,12 @@ pub struct SystemInfo {
     pub uptime_seconds: u64,
     pub memory_usage_bytes: u64,
     pub cpu_usage_percent: f32,
+    /// Only reported on Linux
+    #[serde(default)]
+    pub thread_count: Option<u64>,
+    /// Only reported on Linux
+    #[serde(default)]
+    pub open_file_descriptors: Option<u64>,
 }
 
 /// Environment information for debugging
@@ -70,6 +81,8 @@ pub struct DiagnosticCollector {
     recent_errors: std::sync::Arc<std::sync::RwLock<Vec<ErrorContext>>>,
     max_errors: usize,
     start_time: SystemTime,
+    log_query: LogQuery,
+    redaction: ReportRedactionPolicy,
 }
 
 impl DiagnosticCollector {
@@ -78,9 +91,23 @@ impl DiagnosticCollector {
             recent_errors: std::sync::Arc::new(std::sync::RwLock::new(Vec::new())),
             max_errors,
             start_time: SystemTime::now(),
+            log_query: LogQuery::default(),
+            redaction: ReportRedactionPolicy::default(),
         }
     }
 
+    /// Choose which buffered log lines reports include by default
+    pub fn with_log_query(mut self, log_query: LogQuery) -> Self {
+        self.log_query = log_query;
+        self
+    }
+
+    /// Redact exported reports and bug reports with `policy`
+    pub fn with_redaction(mut self, redaction: ReportRedactionPolicy) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
     /// Record an error for diagnostic purposes
     pub fn record_error(&self, error_context: ErrorContext) {
         let mut errors = self.recent_errors.write().unwrap();
@@ -104,6 +131,16 @@ impl DiagnosticCollector {
     pub async fn generate_report(
         &self,
         dead_letter_queue: Option<&DeadLetterQueue>,
+    ) -> Result<DiagnosticReport> {
+        self.generate_report_with_logs(dead_letter_queue, self.log_query)
+            .await
+    }
+
+    /// Generate a report including the buffered log lines selected by `log_query`
+    pub async fn generate_report_with_logs(
+        &self,
+        dead_letter_queue: Option<&DeadLetterQueue>,
+        log_query: LogQuery,
     ) -> Result<DiagnosticReport> {
         let report_id = uuid::Uuid::new_v4().to_string();
         let generated_at = SystemTime::now()
@@ -117,7 +154,7 @@ impl DiagnosticCollector {
         let environment_info = self.collect_environment_info().await?;
         let performance_snapshot = self.collect_performance_snapshot().await?;
         let error_summary = self.collect_error_summary(dead_letter_queue).await?;
-        let recent_logs = self.collect_recent_logs().await?;
+        let recent_logs = self.collect_recent_logs(log_query).await?;
         let configuration_dump = self.collect_configuration_dump().await?;
         let health_checks = self.collect_health_checks().await?;
 
@@ -140,9 +177,20 @@ impl DiagnosticCollector {
         Ok(report)
     }
 
-    /// Export diagnostic report to JSON
+    /// Export diagnostic report to JSON, redacted for sharing
     pub async fn export_report_json(&self, report: &DiagnosticReport) -> Result<String> {
-        serde_json::to_string_pretty(report).map_err(Into::into)
+        let report = self.redaction.apply(serde_json::to_value(report)?);
+        serde_json::to_string_pretty(&report).map_err(Into::into)
+    }
+
+    /// Bug report markdown for `report`, redacted for sharing
+    pub fn bug_report(
+        &self,
+        report: &DiagnosticReport,
+        description: &str,
+        steps_to_reproduce: &str,
+    ) -> String {
+        create_redacted_bug_report(report, description, steps_to_reproduce, &self.redaction)
     }
 
     /// Save diagnostic report to file
@@ -160,8 +208,7 @@ impl DiagnosticCollector {
     async fn collect_system_info(&self) -> Result<SystemInfo> {
         let uptime = self.start_time.elapsed().unwrap_or_default().as_secs();
 
-        // Try to get system information
-        let (memory_usage, cpu_usage) = self.get_system_metrics().await;
+        let process = process_metrics::sample();
 
         Ok(SystemInfo {
             os: std::env::consts::OS.to_string(),
@@ -173,8 +220,10 @@ impl DiagnosticCollector {
             rust_version: rustc_version_runtime::version().to_string(),
             crate_version: env!("CARGO_PKG_VERSION").to_string(),
             uptime_seconds: uptime,
-            memory_usage_bytes: memory_usage,
-            cpu_usage_percent: cpu_usage,
+            memory_usage_bytes: process.rss_bytes,
+            cpu_usage_percent: process.cpu_percent,
+            thread_count: process.thread_count,
+            open_file_descriptors: process.open_fds,
         })
     }
 
@@ -203,15 +252,15 @@ impl DiagnosticCollector {
     }
 
     async fn collect_performance_snapshot(&self) -> Result<PerformanceSnapshot> {
-        let (memory_usage, cpu_usage) = self.get_system_metrics().await;
+        let process = process_metrics::sample();
 
         Ok(PerformanceSnapshot {
             timestamp: SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
-            memory_usage_bytes: memory_usage,
-            cpu_usage_percent: cpu_usage,
+            memory_usage_bytes: process.rss_bytes,
+            cpu_usage_percent: process.cpu_percent,
             active_connections: 0,        // TODO: Get from connection manager
             request_count_last_minute: 0, // TODO: Get from metrics
             error_count_last_minute: 0,   // TODO: Get from metrics
@@ -254,9 +303,12 @@ impl DiagnosticCollector {
         })
     }
 
-    async fn collect_recent_logs(&self) -> Result<Vec<String>> {
-        // TODO: Implement log collection from tracing subscriber
-        Ok(vec!["Log collection not yet implemented".to_string()])
+    async fn collect_recent_logs(&self, log_query: LogQuery) -> Result<Vec<String>> {
+        Ok(log_buffer::global()
+            .recent(log_query)
+            .iter()
+            .map(ToString::to_string)
+            .collect())
     }
 
     async fn collect_configuration_dump(&self) -> Result<HashMap<String, String>> {
@@ -291,15 +343,6 @@ impl DiagnosticCollector {
         Ok(checks)
     }
 
-    async fn get_system_metrics(&self) -> (u64, f32) {
-        // Try to get current process memory usage
-        let memory = std::process::id() as u64 * 1024; // Placeholder
-        let cpu = 1.0; // Placeholder
-
-        // TODO: Use sysinfo or similar for actual metrics
-        (memory, cpu)
-    }
-
     fn is_safe_env_var(key: &str) -> bool {
         // Only include safe environment variables
         let safe_prefixes = ["RUST_", "CARGO_", "PATH"];
@@ -337,6 +380,26 @@ pub fn create_bug_report(
     description: &str,
     steps_to_reproduce: &str,
 ) -> String {
+    create_redacted_bug_report(
+        report,
+        description,
+        steps_to_reproduce,
+        &ReportRedactionPolicy::default(),
+    )
+}
+
+/// Create a bug report showing only what `policy` lets through
+pub fn create_redacted_bug_report(
+    report: &DiagnosticReport,
+    description: &str,
+    steps_to_reproduce: &str,
+    policy: &ReportRedactionPolicy,
+) -> String {
+    let report = policy.apply(serde_json::to_value(report).unwrap_or_default());
+    let field = |path: &str| report_field(&report, path);
+    // Sections are only shown when they survived redaction intact
+    let section = |key: &str| report.get(key).cloned().unwrap_or_default();
+
     format!(
         r#"# Bug Report
 
@@ -356,6 +419,8 @@ pub fn create_bug_report(
 ## Performance at Time of Issue
 - Memory Usage: {} bytes
 - CPU Usage: {:.2}%
+- Threads: {}
+- Open File Descriptors: {}
 - Recent Errors: {}
 
 ## Error Summary
@@ -364,6 +429,11 @@ pub fn create_bug_report(
 ## System Health
 {}
 
+## Recent Logs
+```text
+{}
+```
+
 ## Report ID
 {}
 
@@ -375,24 +445,55 @@ pub fn create_bug_report(
 "#,
         description,
         steps_to_reproduce,
-        report.system_info.os,
-        report.system_info.arch,
-        report.system_info.rust_version,
-        report.system_info.crate_version,
-        report.system_info.hostname,
-        report.system_info.uptime_seconds,
-        report.performance_snapshot.memory_usage_bytes,
-        report.performance_snapshot.cpu_usage_percent,
-        report.error_summary.total_errors,
-        format_error_summary(&report.error_summary),
-        format_health_checks(&report.health_checks),
-        report.report_id,
-        chrono::DateTime::from_timestamp(report.generated_at as i64, 0)
+        field("system_info.os"),
+        field("system_info.arch"),
+        field("system_info.rust_version"),
+        field("system_info.crate_version"),
+        field("system_info.hostname"),
+        field("system_info.uptime_seconds"),
+        field("performance_snapshot.memory_usage_bytes"),
+        report
+            .pointer("/performance_snapshot/cpu_usage_percent")
+            .and_then(Value::as_f64)
+            .map_or_else(|| REDACTED.to_string(), |cpu| format!("{cpu:.2}")),
+        field("system_info.thread_count"),
+        field("system_info.open_file_descriptors"),
+        field("error_summary.total_errors"),
+        serde_json::from_value(section("error_summary"))
+            .map_or_else(|_| REDACTED.to_string(), |s| format_error_summary(&s)),
+        serde_json::from_value(section("health_checks"))
+            .map_or_else(|_| REDACTED.to_string(), |c| format_health_checks(&c)),
+        serde_json::from_value::<Vec<String>>(section("recent_logs"))
+            .map_or_else(|_| REDACTED.to_string(), |logs| format_recent_logs(&logs)),
+        field("report_id"),
+        report
+            .get("generated_at")
+            .and_then(Value::as_i64)
+            .and_then(|at| chrono::DateTime::from_timestamp(at, 0))
             .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
-            .unwrap_or_else(|| "Unknown".to_string())
+            .unwrap_or_else(|| REDACTED.to_string())
     )
 }
 
+/// A report field for display; [`REDACTED`] when the policy removed it
+fn report_field(report: &Value, path: &str) -> String {
+    match report.pointer(&format!("/{}", path.replace('.', "/"))) {
+        None => REDACTED.to_string(),
+        Some(Value::Null) => "unknown".to_string(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// The last lines of the log, enough for context without burying the report
+fn format_recent_logs(logs: &[String]) -> String {
+    const MAX_LINES: usize = 50;
+    if logs.is_empty() {
+        return "No log lines captured".to_string();
+    }
+    logs[logs.len().saturating_sub(MAX_LINES)..].join("\n")
+}
+
 fn format_error_summary(summary: &ErrorSummary) -> String {
     let mut result = String::new();
 
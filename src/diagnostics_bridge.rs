@@ -0,0 +1,224 @@
+//! Bridge to Bevy's built-in diagnostics plugins
+//!
+//! Reads the values published by `FrameTimeDiagnosticsPlugin` and
+//! `EntityCountDiagnosticsPlugin` (and any other registered diagnostic) over
+//! BRP, so the profiler, performance budgets and anomaly detectors can work
+//! from the game's own measurements instead of estimates.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse, BrpResult, DiagnosticMeasurement};
+use crate::error::{Error, Result};
+use crate::performance_budget::PerformanceMetrics;
+
+/// Diagnostic paths registered by Bevy's built-in plugins
+pub mod paths {
+    /// Frame time in milliseconds (`FrameTimeDiagnosticsPlugin`)
+    pub const FRAME_TIME: &str = "frame_time";
+    /// Frames per second (`FrameTimeDiagnosticsPlugin`)
+    pub const FPS: &str = "fps";
+    /// Total frame count (`FrameTimeDiagnosticsPlugin`)
+    pub const FRAME_COUNT: &str = "frame_count";
+    /// Number of entities (`EntityCountDiagnosticsPlugin`)
+    pub const ENTITY_COUNT: &str = "entity_count";
+}
+
+/// Default maximum age of a cached snapshot before it is refetched
+pub const DEFAULT_SNAPSHOT_MAX_AGE: Duration = Duration::from_millis(250);
+
+/// Diagnostics read from the game at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsSnapshot {
+    pub measurements: HashMap<String, DiagnosticMeasurement>,
+    pub collected_at: DateTime<Utc>,
+}
+
+impl DiagnosticsSnapshot {
+    pub fn new(measurements: Vec<DiagnosticMeasurement>) -> Self {
+        Self {
+            measurements: measurements
+                .into_iter()
+                .map(|m| (m.path.clone(), m))
+                .collect(),
+            collected_at: Utc::now(),
+        }
+    }
+
+    /// Best available value for a diagnostic: smoothed, then latest, then average
+    pub fn value(&self, path: &str) -> Option<f64> {
+        let measurement = self.measurements.get(path)?;
+        measurement
+            .smoothed
+            .or(measurement.value)
+            .or(measurement.average)
+    }
+
+    pub fn frame_time_ms(&self) -> Option<f32> {
+        self.value(paths::FRAME_TIME).map(|v| v as f32)
+    }
+
+    pub fn fps(&self) -> Option<f32> {
+        self.value(paths::FPS).map(|v| v as f32)
+    }
+
+    pub fn frame_count(&self) -> Option<u64> {
+        self.value(paths::FRAME_COUNT).map(|v| v as u64)
+    }
+
+    pub fn entity_count(&self) -> Option<usize> {
+        self.value(paths::ENTITY_COUNT).map(|v| v as usize)
+    }
+
+    /// Convert to budget metrics; values without a diagnostic source are zero
+    pub fn to_performance_metrics(&self) -> PerformanceMetrics {
+        PerformanceMetrics {
+            frame_time_ms: self
+                .frame_time_ms()
+                .or_else(|| self.fps().filter(|fps| *fps > 0.0).map(|fps| 1000.0 / fps))
+                .unwrap_or(0.0),
+            memory_mb: 0.0,
+            system_times: HashMap::new(),
+            cpu_percent: 0.0,
+            gpu_time_ms: 0.0,
+            entity_count: self.entity_count().unwrap_or(0),
+            draw_calls: 0,
+            network_bandwidth_kbps: 0.0,
+            timestamp: self.collected_at,
+        }
+    }
+}
+
+/// Read diagnostics from the game over BRP
+pub async fn fetch_diagnostics(
+    brp_client: &Arc<RwLock<BrpClient>>,
+    paths: Option<Vec<String>>,
+) -> Result<DiagnosticsSnapshot> {
+    let mut client = brp_client.write().await;
+    if !client.is_connected() {
+        return Err(Error::Connection("BRP client not connected".to_string()));
+    }
+
+    match client
+        .send_request(&BrpRequest::GetDiagnostics { paths })
+        .await?
+    {
+        BrpResponse::Success(boxed_result) => match *boxed_result {
+            BrpResult::Diagnostics(measurements) => {
+                debug!("Read {} diagnostics from game", measurements.len());
+                Ok(DiagnosticsSnapshot::new(measurements))
+            }
+            _ => Err(Error::Brp(
+                "Expected diagnostics response from BRP".to_string(),
+            )),
+        },
+        BrpResponse::Error(error) => Err(Error::Brp(format!(
+            "Diagnostics request failed: {}",
+            error.message
+        ))),
+    }
+}
+
+/// Shared diagnostics source that caches the latest snapshot.
+///
+/// Several consumers poll at high frequency; the cache keeps them from each
+/// issuing their own BRP request for the same frame.
+pub struct DiagnosticsBridge {
+    brp_client: Arc<RwLock<BrpClient>>,
+    latest: RwLock<Option<(Instant, DiagnosticsSnapshot)>>,
+    max_age: Duration,
+}
+
+impl DiagnosticsBridge {
+    pub fn new(brp_client: Arc<RwLock<BrpClient>>) -> Self {
+        Self::with_max_age(brp_client, DEFAULT_SNAPSHOT_MAX_AGE)
+    }
+
+    pub fn with_max_age(brp_client: Arc<RwLock<BrpClient>>, max_age: Duration) -> Self {
+        Self {
+            brp_client,
+            latest: RwLock::new(None),
+            max_age,
+        }
+    }
+
+    /// Fetch a fresh snapshot from the game, bypassing the cache
+    pub async fn fetch(&self) -> Result<DiagnosticsSnapshot> {
+        let snapshot = fetch_diagnostics(&self.brp_client, None).await?;
+        *self.latest.write().await = Some((Instant::now(), snapshot.clone()));
+        Ok(snapshot)
+    }
+
+    /// Get the cached snapshot if it is fresh enough, otherwise fetch a new one
+    pub async fn snapshot(&self) -> Result<DiagnosticsSnapshot> {
+        if let Some((fetched_at, snapshot)) = self.latest.read().await.as_ref() {
+            if fetched_at.elapsed() <= self.max_age {
+                return Ok(snapshot.clone());
+            }
+        }
+        self.fetch().await
+    }
+
+    /// Last snapshot fetched, regardless of age
+    pub async fn latest(&self) -> Option<DiagnosticsSnapshot> {
+        self.latest
+            .read()
+            .await
+            .as_ref()
+            .map(|(_, snapshot)| snapshot.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn measurement(path: &str, value: Option<f64>, smoothed: Option<f64>) -> DiagnosticMeasurement {
+        DiagnosticMeasurement {
+            path: path.to_string(),
+            value,
+            average: None,
+            smoothed,
+            suffix: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_values() {
+        let snapshot = DiagnosticsSnapshot::new(vec![
+            measurement(paths::FRAME_TIME, Some(20.0), Some(16.5)),
+            measurement(paths::ENTITY_COUNT, Some(1234.0), None),
+        ]);
+
+        assert_eq!(snapshot.frame_time_ms(), Some(16.5));
+        assert_eq!(snapshot.entity_count(), Some(1234));
+        assert_eq!(snapshot.fps(), None);
+
+        let metrics = snapshot.to_performance_metrics();
+        assert_eq!(metrics.frame_time_ms, 16.5);
+        assert_eq!(metrics.entity_count, 1234);
+    }
+
+    #[test]
+    fn test_frame_time_from_fps() {
+        let snapshot = DiagnosticsSnapshot::new(vec![measurement(paths::FPS, Some(50.0), None)]);
+        assert_eq!(snapshot.to_performance_metrics().frame_time_ms, 20.0);
+    }
+
+    #[tokio::test]
+    async fn test_bridge_requires_connection() {
+        let config = Config::default();
+        let brp_client = Arc::new(RwLock::new(BrpClient::new(&config)));
+        let bridge = DiagnosticsBridge::new(brp_client);
+
+        assert!(bridge.snapshot().await.is_err());
+        assert!(bridge.latest().await.is_none());
+    }
+}
@@ -0,0 +1,407 @@
+/*
+ * Bevy Debugger MCP Server - Disk-Backed Recording Ring Buffer
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::recording_system::{DeltaFrame, Frame};
+
+/// Configuration for spooling recorded frames to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskRingConfig {
+    /// Directory segments are written under; each buffer uses its own subdirectory
+    pub directory: PathBuf,
+    /// How much recent gameplay to keep
+    pub window: Duration,
+    /// Disk space ceiling, enforced on top of the window
+    pub max_bytes: u64,
+    /// Gameplay per segment file; also how often a full frame is written
+    pub segment_duration: Duration,
+    /// How often expired segments are removed in the background
+    pub compaction_interval: Duration,
+}
+
+impl Default for DiskRingConfig {
+    fn default() -> Self {
+        Self {
+            directory: std::env::temp_dir().join("bevy_debugger_recordings"),
+            window: Duration::from_secs(300),
+            max_bytes: 1024 * 1024 * 1024, // 1GB
+            segment_duration: Duration::from_secs(10),
+            compaction_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// One line of a segment file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "frame", rename_all = "snake_case")]
+pub enum SpoolEntry {
+    Full(Frame),
+    Delta(DeltaFrame),
+}
+
+impl SpoolEntry {
+    fn frame_number(&self) -> usize {
+        match self {
+            SpoolEntry::Full(frame) => frame.frame_number,
+            SpoolEntry::Delta(delta) => delta.frame_number,
+        }
+    }
+
+    fn timestamp(&self) -> Duration {
+        match self {
+            SpoolEntry::Full(frame) => frame.timestamp,
+            SpoolEntry::Delta(delta) => delta.timestamp,
+        }
+    }
+}
+
+/// Disk usage and coverage of the ring buffer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskRingStats {
+    pub directory: PathBuf,
+    pub segments: usize,
+    pub bytes: u64,
+    pub first_frame: Option<usize>,
+    pub last_frame: Option<usize>,
+    /// Gameplay time currently available to rewind
+    pub covered: Duration,
+    pub window: Duration,
+    pub compactions: u64,
+    pub segments_dropped: u64,
+}
+
+#[derive(Debug)]
+struct Segment {
+    path: PathBuf,
+    first_frame: usize,
+    last_frame: usize,
+    first_timestamp: Duration,
+    last_timestamp: Duration,
+    bytes: u64,
+}
+
+#[derive(Debug, Default)]
+struct RingState {
+    segments: VecDeque<Segment>,
+    writer: Option<BufWriter<File>>,
+    next_segment: u64,
+    compactions: u64,
+    segments_dropped: u64,
+}
+
+/// Recorded frames spooled to segment files, keeping only a recent window
+///
+/// Every segment opens with a full frame followed by deltas, so whole
+/// segments can be dropped from the front without breaking replay of the
+/// rest. Compaction drops segments that fall outside the window or the disk
+/// ceiling; the newest segment is always kept.
+#[derive(Debug)]
+pub struct DiskRingBuffer {
+    config: DiskRingConfig,
+    directory: PathBuf,
+    state: Mutex<RingState>,
+}
+
+impl DiskRingBuffer {
+    pub fn open(config: DiskRingConfig) -> Result<Arc<Self>> {
+        let directory = config.directory.join(Uuid::new_v4().to_string());
+        fs::create_dir_all(&directory)?;
+        debug!("Spooling recording to {:?}", directory);
+        Ok(Arc::new(Self {
+            config,
+            directory,
+            state: Mutex::new(RingState::default()),
+        }))
+    }
+
+    pub fn config(&self) -> &DiskRingConfig {
+        &self.config
+    }
+
+    /// Whether the next frame should be full, starting a new segment
+    pub fn needs_keyframe(&self, timestamp: Duration) -> bool {
+        let state = self.lock();
+        match state.segments.back() {
+            Some(segment) if state.writer.is_some() => {
+                timestamp.saturating_sub(segment.first_timestamp) >= self.config.segment_duration
+            }
+            _ => true,
+        }
+    }
+
+    /// Append a frame; full frames start a new segment
+    pub fn append(&self, entry: &SpoolEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut state = self.lock();
+        if let SpoolEntry::Full(_) = entry {
+            if let Some(mut writer) = state.writer.take() {
+                writer.flush()?;
+            }
+            let path = self
+                .directory
+                .join(format!("segment_{:08}.jsonl", state.next_segment));
+            state.next_segment += 1;
+            state.writer = Some(BufWriter::new(
+                OpenOptions::new().create(true).append(true).open(&path)?,
+            ));
+            state.segments.push_back(Segment {
+                path,
+                first_frame: entry.frame_number(),
+                last_frame: entry.frame_number(),
+                first_timestamp: entry.timestamp(),
+                last_timestamp: entry.timestamp(),
+                bytes: 0,
+            });
+        }
+
+        let Some(writer) = state.writer.as_mut() else {
+            return Err(Error::Validation(
+                "Disk ring buffer segments must start with a full frame".to_string(),
+            ));
+        };
+        writer.write_all(&line)?;
+        if let Some(segment) = state.segments.back_mut() {
+            segment.last_frame = entry.frame_number();
+            segment.last_timestamp = entry.timestamp();
+            segment.bytes += line.len() as u64;
+        }
+        Ok(())
+    }
+
+    /// Remove segments outside the window or over the disk ceiling, returning how many
+    pub fn compact(&self) -> usize {
+        let mut state = self.lock();
+        let Some(newest) = state.segments.back().map(|s| s.last_timestamp) else {
+            return 0;
+        };
+        let mut total: u64 = state.segments.iter().map(|s| s.bytes).sum();
+
+        let mut dropped = 0;
+        while state.segments.len() > 1 {
+            let oldest = &state.segments[0];
+            let expired = oldest.last_timestamp + self.config.window < newest;
+            if !expired && total <= self.config.max_bytes {
+                break;
+            }
+            if let Some(segment) = state.segments.pop_front() {
+                total -= segment.bytes;
+                if let Err(e) = fs::remove_file(&segment.path) {
+                    warn!(
+                        "Failed to remove recording segment {:?}: {}",
+                        segment.path, e
+                    );
+                }
+                dropped += 1;
+            }
+        }
+
+        state.compactions += 1;
+        state.segments_dropped += dropped as u64;
+        dropped
+    }
+
+    /// Everything still in the window, as full frames and deltas
+    pub fn read_window(&self) -> Result<(Vec<Frame>, Vec<DeltaFrame>)> {
+        let mut state = self.lock();
+        if let Some(writer) = state.writer.as_mut() {
+            writer.flush()?;
+        }
+
+        let mut frames = Vec::new();
+        let mut deltas = Vec::new();
+        for segment in &state.segments {
+            for line in BufReader::new(File::open(&segment.path)?).lines() {
+                // A crash can leave the last line half written
+                match serde_json::from_str(&line?) {
+                    Ok(SpoolEntry::Full(frame)) => frames.push(frame),
+                    Ok(SpoolEntry::Delta(delta)) => deltas.push(delta),
+                    Err(e) => warn!("Skipping unreadable entry in {:?}: {}", segment.path, e),
+                }
+            }
+        }
+        Ok((frames, deltas))
+    }
+
+    /// Drop every segment, e.g. when a new recording starts
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.writer = None;
+        for segment in state.segments.drain(..) {
+            let _ = fs::remove_file(&segment.path);
+        }
+    }
+
+    pub fn stats(&self) -> DiskRingStats {
+        let state = self.lock();
+        let (first, last) = (state.segments.front(), state.segments.back());
+        DiskRingStats {
+            directory: self.directory.clone(),
+            segments: state.segments.len(),
+            bytes: state.segments.iter().map(|s| s.bytes).sum(),
+            first_frame: first.map(|s| s.first_frame),
+            last_frame: last.map(|s| s.last_frame),
+            covered: match (first, last) {
+                (Some(first), Some(last)) => {
+                    last.last_timestamp.saturating_sub(first.first_timestamp)
+                }
+                _ => Duration::ZERO,
+            },
+            window: self.config.window,
+            compactions: state.compactions,
+            segments_dropped: state.segments_dropped,
+        }
+    }
+
+    /// Compact every `compaction_interval` until the buffer is dropped
+    pub fn start_compaction(self: &Arc<Self>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No async runtime; disk ring buffer will only be compacted on demand");
+            return;
+        };
+        let buffer = Arc::downgrade(self);
+        let period = self.config.compaction_interval;
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let Some(buffer) = buffer.upgrade() else {
+                    break;
+                };
+                let dropped = buffer.compact();
+                if dropped > 0 {
+                    debug!("Compacted {} expired recording segments", dropped);
+                }
+            }
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RingState> {
+        // Segment bookkeeping stays consistent even if a writer panicked
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for DiskRingBuffer {
+    fn drop(&mut self) {
+        self.clear();
+        let _ = fs::remove_dir(&self.directory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn frame(frame_number: usize) -> Frame {
+        Frame {
+            frame_number,
+            timestamp: Duration::from_secs(frame_number as u64),
+            entities: HashMap::new(),
+            events: Vec::new(),
+            checksum: None,
+        }
+    }
+
+    fn delta(frame_number: usize) -> DeltaFrame {
+        DeltaFrame {
+            frame_number,
+            timestamp: Duration::from_secs(frame_number as u64),
+            added_entities: HashMap::new(),
+            removed_entities: vec![frame_number as u64],
+            changed_components: HashMap::new(),
+            events: Vec::new(),
+        }
+    }
+
+    fn buffer(dir: &tempfile::TempDir, window_secs: u64) -> Arc<DiskRingBuffer> {
+        DiskRingBuffer::open(DiskRingConfig {
+            directory: dir.path().to_path_buf(),
+            window: Duration::from_secs(window_secs),
+            segment_duration: Duration::from_secs(10),
+            ..DiskRingConfig::default()
+        })
+        .unwrap()
+    }
+
+    /// Record one frame per second, keyframing whenever the buffer asks
+    fn record(buffer: &DiskRingBuffer, frames: std::ops::Range<usize>) {
+        for n in frames {
+            let entry = if buffer.needs_keyframe(Duration::from_secs(n as u64)) {
+                SpoolEntry::Full(frame(n))
+            } else {
+                SpoolEntry::Delta(delta(n))
+            };
+            buffer.append(&entry).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_compaction_keeps_the_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = buffer(&dir, 30);
+        record(&buffer, 0..100);
+        assert_eq!(buffer.stats().segments, 10);
+
+        // Segments ending more than 30s before frame 99 go
+        assert_eq!(buffer.compact(), 6);
+        let stats = buffer.stats();
+        assert_eq!((stats.first_frame, stats.last_frame), (Some(60), Some(99)));
+
+        let (frames, deltas) = buffer.read_window().unwrap();
+        let keyframes: Vec<_> = frames.iter().map(|f| f.frame_number).collect();
+        assert_eq!(keyframes, vec![60, 70, 80, 90]);
+        assert_eq!(deltas.len(), 36);
+        assert_eq!(fs::read_dir(&stats.directory).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn test_disk_ceiling_and_cleanup() {
+        let dir = tempfile::tempdir().unwrap();
+        let buffer = buffer(&dir, 3600);
+        assert!(buffer.append(&SpoolEntry::Delta(delta(0))).is_err());
+
+        record(&buffer, 0..50);
+        let per_segment = buffer.stats().bytes / 5;
+        let buffer = {
+            let mut config = buffer.config().clone();
+            config.max_bytes = per_segment * 2;
+            drop(buffer);
+            // Dropping the buffer removes its segments
+            assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+            DiskRingBuffer::open(config).unwrap()
+        };
+        record(&buffer, 0..50);
+        buffer.compact();
+        assert!(buffer.stats().bytes <= per_segment * 2 + per_segment / 2);
+        assert!(buffer.stats().segments >= 1);
+    }
+}
@@ -2,7 +2,8 @@
 use crate::brp_messages::{
     BrpRequest, BrpResponse, BrpResult, EntityData, EntityId, EntityMetadata, 
     EntityRelationships, EntityInspectionResult, DetailedComponentTypeInfo, 
-    EntityLocationInfo, DebugResponse, ComponentValue,
+    EntityLocationInfo, DebugResponse, ComponentValue, AssetHandleInfo, AssetHandleRef,
+    DebugCommand,
 };
 use crate::brp_client::BrpClient;
 use crate::error::{Error, Result};
@@ -273,6 +274,7 @@ impl EntityInspector {
         let generation = self.get_entity_generation(entity_id).await.unwrap_or(0);
         let archetype_id = self.get_entity_archetype(entity_id).await;
         let location_info = self.get_entity_location(entity_id).await;
+        let asset_handles = self.resolve_asset_handles(entity_id, entity_data).await;
 
         Ok(EntityMetadata {
             component_count,
@@ -284,9 +286,58 @@ impl EntityInspector {
             modified_components,
             archetype_id,
             location_info,
+            asset_handles,
         })
     }
 
+    /// Ask the game what the entity's asset handles point at
+    ///
+    /// Resolution is best effort: games without the debug plugin's asset
+    /// support still get the rest of the metadata.
+    async fn resolve_asset_handles(&self, entity_id: EntityId, entity_data: &EntityData) -> Vec<AssetHandleInfo> {
+        let handles = find_asset_handles(entity_data);
+        if handles.is_empty() {
+            return Vec::new();
+        }
+
+        let request = BrpRequest::Debug {
+            command: DebugCommand::ResolveAssetHandles {
+                entity: entity_id,
+                handles,
+            },
+            correlation_id: uuid::Uuid::new_v4().to_string(),
+            priority: Some(5),
+        };
+        let response = {
+            let mut brp_client = self.brp_client.write().await;
+            brp_client.send_request(&request).await
+        };
+
+        match response {
+            Ok(BrpResponse::Success(result)) => match *result {
+                BrpResult::Debug(response) => match *response {
+                    DebugResponse::AssetHandles(handles) => handles,
+                    other => {
+                        warn!("Unexpected response resolving asset handles: {:?}", other);
+                        Vec::new()
+                    }
+                },
+                other => {
+                    warn!("Unexpected response resolving asset handles: {:?}", other);
+                    Vec::new()
+                }
+            },
+            Ok(BrpResponse::Error(err)) => {
+                warn!("Could not resolve asset handles of entity {}: {}", entity_id, err.message);
+                Vec::new()
+            }
+            Err(e) => {
+                warn!("Could not resolve asset handles of entity {}: {}", entity_id, e);
+                Vec::new()
+            }
+        }
+    }
+
     /// Build entity relationship information
     async fn build_entity_relationships(&self, entity_data: &EntityData, _entity_id: EntityId) -> Result<EntityRelationships> {
         let mut parent = None;
@@ -350,6 +401,7 @@ impl EntityInspector {
                 modified_components: Vec::new(),
                 archetype_id: None,
                 location_info: None,
+                asset_handles: Vec::new(),
             }),
             relationships: relationships.cloned(),
             cached_at: Instant::now(),
@@ -569,6 +621,50 @@ impl Clone for EntityInspector {
     }
 }
 
+/// Asset handles in an entity's components, ordered by component and path
+///
+/// Bevy reflects `Handle<T>` as an enum, so a handle is any single-key
+/// object keyed `Strong` or `Weak`.
+fn find_asset_handles(entity_data: &EntityData) -> Vec<AssetHandleRef> {
+    const MAX_DEPTH: usize = 32;
+
+    fn walk(value: &Value, component: &str, path: &mut Vec<String>, found: &mut Vec<AssetHandleRef>) {
+        if path.len() > MAX_DEPTH {
+            return;
+        }
+        match value {
+            Value::Object(obj) if obj.len() == 1 && (obj.contains_key("Strong") || obj.contains_key("Weak")) => {
+                found.push(AssetHandleRef {
+                    component: component.to_string(),
+                    path: path.join("."),
+                });
+            }
+            Value::Object(obj) => {
+                for (key, child) in obj {
+                    path.push(key.clone());
+                    walk(child, component, path, found);
+                    path.pop();
+                }
+            }
+            Value::Array(arr) => {
+                for (index, child) in arr.iter().enumerate() {
+                    path.push(index.to_string());
+                    walk(child, component, path, found);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut found = Vec::new();
+    for (component, value) in &entity_data.components {
+        walk(value, component, &mut Vec::new(), &mut found);
+    }
+    found.sort_by(|a, b| (&a.component, &a.path).cmp(&(&b.component, &b.path)));
+    found
+}
+
 impl CachedEntityData {
     /// Check if cached data has expired
     fn ttl_expired(&self) -> bool {
@@ -630,6 +726,47 @@ mod tests {
         assert!(!inspector.has_reflection_data("custom::unknown::Component"));
     }
 
+    #[test]
+    fn test_find_asset_handles() {
+        let mut components = HashMap::new();
+        components.insert(
+            "bevy_sprite::sprite::Sprite".to_string(),
+            json!({"image": {"Strong": {"id": 3}}, "color": {"Srgba": {"red": 1.0}}}),
+        );
+        components.insert(
+            "game::Inventory".to_string(),
+            json!({"icons": [{"Weak": {"Index": {"index": 1}}}, null]}),
+        );
+        components.insert("game::Sound".to_string(), json!({"Strong": {"id": 9}}));
+        let entity = EntityData { id: 7, components };
+
+        let handles: Vec<(String, String)> = find_asset_handles(&entity)
+            .into_iter()
+            .map(|handle| (handle.component, handle.path))
+            .collect();
+        assert_eq!(
+            handles,
+            vec![
+                ("bevy_sprite::sprite::Sprite".to_string(), "image".to_string()),
+                ("game::Inventory".to_string(), "icons.0".to_string()),
+                ("game::Sound".to_string(), String::new()),
+            ]
+        );
+
+        let info = AssetHandleInfo {
+            handle: AssetHandleRef {
+                component: "bevy_sprite::sprite::Sprite".to_string(),
+                path: "image".to_string(),
+            },
+            asset_type: Some("bevy_image::image::Image".to_string()),
+            asset_path: Some("textures/player.png".to_string()),
+            asset_id: None,
+            load_state: crate::brp_messages::AssetLoadState::Loaded,
+            strong: true,
+        };
+        assert_eq!(info.describe(), "textures/player.png (Loaded)");
+    }
+
     #[tokio::test]
     async fn test_cache_expiry() {
         let mut config = Config::default();
@@ -653,6 +790,7 @@ mod tests {
                 modified_components: Vec::new(),
                 archetype_id: None,
                 location_info: None,
+                asset_handles: Vec::new(),
             },
             relationships: None,
             cached_at: Instant::now() - std::time::Duration::from_secs(10),
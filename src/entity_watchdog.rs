@@ -0,0 +1,548 @@
+//! Entity-count watchdog
+//!
+//! A lightweight monitor that polls entity counts over BRP and compares the
+//! total and per-tag counts against configured limits. Unlike the anomaly
+//! detector it keeps no statistical model: it only answers "did a count cross
+//! its limit or grow too fast since the last check", which makes it cheap
+//! enough to leave running for the whole session.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse, BrpResult, EntityData};
+use crate::error::{Error, Result};
+
+/// Maximum number of alerts kept in history
+pub const MAX_WATCHDOG_ALERTS: usize = 500;
+
+/// Minimum polling interval to keep BRP load reasonable
+pub const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Capacity of the alert notification channel
+const ALERT_CHANNEL_CAPACITY: usize = 64;
+
+/// Watchdog limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    /// Maximum total entity count
+    #[serde(default)]
+    pub total_limit: Option<usize>,
+    /// Maximum entity count per tag (component type name)
+    #[serde(default)]
+    pub tag_limits: HashMap<String, usize>,
+    /// Maximum growth ratio between two checks (e.g. 2.0 = doubling)
+    #[serde(default)]
+    pub max_growth_ratio: Option<f32>,
+    /// Counts below this are never reported as explosive growth
+    #[serde(default = "default_min_growth_count")]
+    pub min_growth_count: usize,
+    /// Polling interval in milliseconds
+    #[serde(default = "default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Minimum time between repeated alerts for the same subject, in milliseconds
+    #[serde(default = "default_cooldown_ms")]
+    pub cooldown_ms: u64,
+}
+
+fn default_min_growth_count() -> usize {
+    100
+}
+
+fn default_check_interval_ms() -> u64 {
+    1000
+}
+
+fn default_cooldown_ms() -> u64 {
+    30_000
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            total_limit: Some(50_000),
+            tag_limits: HashMap::new(),
+            max_growth_ratio: Some(2.0),
+            min_growth_count: default_min_growth_count(),
+            check_interval_ms: default_check_interval_ms(),
+            cooldown_ms: default_cooldown_ms(),
+        }
+    }
+}
+
+impl WatchdogConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.check_interval_ms < MIN_CHECK_INTERVAL.as_millis() as u64 {
+            return Err(Error::Validation(format!(
+                "check_interval_ms must be at least {}",
+                MIN_CHECK_INTERVAL.as_millis()
+            )));
+        }
+        if let Some(ratio) = self.max_growth_ratio {
+            if !ratio.is_finite() || ratio <= 1.0 {
+                return Err(Error::Validation(
+                    "max_growth_ratio must be greater than 1.0".to_string(),
+                ));
+            }
+        }
+        if self.tag_limits.keys().any(|tag| tag.trim().is_empty()) {
+            return Err(Error::Validation("Tag names must not be empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// What triggered a watchdog alert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchdogAlertKind {
+    /// Total entity count exceeded its limit
+    TotalLimitExceeded { limit: usize },
+    /// Entity count for a tag exceeded its limit
+    TagLimitExceeded { tag: String, limit: usize },
+    /// A count grew faster than the configured ratio between two checks
+    RapidGrowth {
+        tag: Option<String>,
+        previous: usize,
+        ratio: f32,
+    },
+}
+
+/// A raised watchdog alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogAlert {
+    pub kind: WatchdogAlertKind,
+    pub count: usize,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Entity counts observed in a single check
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityCounts {
+    pub total: usize,
+    pub by_tag: HashMap<String, usize>,
+}
+
+impl EntityCounts {
+    /// Count entities in total and for each configured tag
+    pub fn from_entities<'a>(
+        entities: &[EntityData],
+        tags: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        let by_tag = tags
+            .into_iter()
+            .map(|tag| {
+                let count = entities
+                    .iter()
+                    .filter(|e| e.components.keys().any(|c| tag_matches(c, tag)))
+                    .count();
+                (tag.clone(), count)
+            })
+            .collect();
+
+        Self {
+            total: entities.len(),
+            by_tag,
+        }
+    }
+}
+
+/// Whether a component type path matches a tag (full path or short type name)
+fn tag_matches(component: &str, tag: &str) -> bool {
+    component == tag
+        || component
+            .rsplit("::")
+            .next()
+            .is_some_and(|short| short == tag)
+}
+
+/// Watchdog state without any I/O, so checks can be driven from any source
+#[derive(Debug)]
+pub struct EntityWatchdog {
+    config: WatchdogConfig,
+    last_counts: Option<EntityCounts>,
+    last_alerted: HashMap<String, Instant>,
+    alerts: VecDeque<WatchdogAlert>,
+    checks: u64,
+}
+
+impl EntityWatchdog {
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            last_counts: None,
+            last_alerted: HashMap::new(),
+            alerts: VecDeque::new(),
+            checks: 0,
+        }
+    }
+
+    pub fn config(&self) -> &WatchdogConfig {
+        &self.config
+    }
+
+    /// Replace the configuration, keeping the count history
+    pub fn set_config(&mut self, config: WatchdogConfig) -> Result<()> {
+        config.validate()?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Compare counts against the limits and return newly raised alerts
+    pub fn check(&mut self, counts: EntityCounts) -> Vec<WatchdogAlert> {
+        self.checks += 1;
+        let mut raised = Vec::new();
+
+        if let Some(limit) = self.config.total_limit {
+            if counts.total > limit {
+                raised.push(self.alert(
+                    "total",
+                    WatchdogAlertKind::TotalLimitExceeded { limit },
+                    counts.total,
+                    format!("Entity count {} exceeds limit {}", counts.total, limit),
+                ));
+            }
+        }
+
+        let mut tag_limits: Vec<(String, usize)> = self
+            .config
+            .tag_limits
+            .iter()
+            .map(|(tag, limit)| (tag.clone(), *limit))
+            .collect();
+        tag_limits.sort();
+        for (tag, limit) in tag_limits {
+            let count = counts.by_tag.get(&tag).copied().unwrap_or(0);
+            if count > limit {
+                raised.push(self.alert(
+                    &format!("tag:{tag}"),
+                    WatchdogAlertKind::TagLimitExceeded {
+                        tag: tag.clone(),
+                        limit,
+                    },
+                    count,
+                    format!("'{tag}' entity count {count} exceeds limit {limit}"),
+                ));
+            }
+        }
+
+        let previous_counts = self.last_counts.take();
+        if let (Some(max_ratio), Some(previous)) =
+            (self.config.max_growth_ratio, previous_counts.as_ref())
+        {
+            let mut growth = vec![(None, previous.total, counts.total)];
+            let mut tag_growth: Vec<_> = counts
+                .by_tag
+                .iter()
+                .filter_map(|(tag, &count)| {
+                    previous
+                        .by_tag
+                        .get(tag)
+                        .map(|&before| (Some(tag.clone()), before, count))
+                })
+                .collect();
+            tag_growth.sort_by(|a, b| a.0.cmp(&b.0));
+            growth.extend(tag_growth);
+
+            for (tag, before, now) in growth {
+                if now < self.config.min_growth_count || before == 0 {
+                    continue;
+                }
+                let ratio = now as f32 / before as f32;
+                if ratio > max_ratio {
+                    let subject = tag.as_deref().unwrap_or("total");
+                    raised.push(self.alert(
+                        &format!("growth:{subject}"),
+                        WatchdogAlertKind::RapidGrowth {
+                            tag: tag.clone(),
+                            previous: before,
+                            ratio,
+                        },
+                        now,
+                        format!(
+                            "{subject} entity count grew {ratio:.1}x ({before} -> {now}) since last check"
+                        ),
+                    ));
+                }
+            }
+        }
+
+        self.last_counts = Some(counts);
+
+        // Drop alerts suppressed by the cooldown
+        let raised: Vec<WatchdogAlert> = raised.into_iter().flatten().collect();
+        for alert in &raised {
+            warn!("Entity watchdog: {}", alert.message);
+            if self.alerts.len() >= MAX_WATCHDOG_ALERTS {
+                self.alerts.pop_front();
+            }
+            self.alerts.push_back(alert.clone());
+        }
+        raised
+    }
+
+    /// Build an alert unless the subject is still in its cooldown window
+    fn alert(
+        &mut self,
+        subject: &str,
+        kind: WatchdogAlertKind,
+        count: usize,
+        message: String,
+    ) -> Option<WatchdogAlert> {
+        let cooldown = Duration::from_millis(self.config.cooldown_ms);
+        if let Some(last) = self.last_alerted.get(subject) {
+            if last.elapsed() < cooldown {
+                debug!("Suppressing watchdog alert for {} (cooldown)", subject);
+                return None;
+            }
+        }
+        self.last_alerted
+            .insert(subject.to_string(), Instant::now());
+
+        Some(WatchdogAlert {
+            kind,
+            count,
+            message,
+            timestamp: Utc::now(),
+        })
+    }
+
+    /// Recent alerts, newest last
+    pub fn alerts(&self, limit: usize) -> Vec<WatchdogAlert> {
+        let skip = self.alerts.len().saturating_sub(limit);
+        self.alerts.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn last_counts(&self) -> Option<&EntityCounts> {
+        self.last_counts.as_ref()
+    }
+
+    pub fn checks(&self) -> u64 {
+        self.checks
+    }
+
+    pub fn clear_alerts(&mut self) {
+        self.alerts.clear();
+        self.last_alerted.clear();
+    }
+}
+
+/// Polls BRP in the background and publishes watchdog alerts
+pub struct EntityWatchdogService {
+    brp_client: Arc<RwLock<BrpClient>>,
+    watchdog: Arc<RwLock<EntityWatchdog>>,
+    alert_sender: broadcast::Sender<WatchdogAlert>,
+    task: RwLock<Option<JoinHandle<()>>>,
+}
+
+impl EntityWatchdogService {
+    pub fn new(brp_client: Arc<RwLock<BrpClient>>, config: WatchdogConfig) -> Self {
+        let (alert_sender, _) = broadcast::channel(ALERT_CHANNEL_CAPACITY);
+        Self {
+            brp_client,
+            watchdog: Arc::new(RwLock::new(EntityWatchdog::new(config))),
+            alert_sender,
+            task: RwLock::new(None),
+        }
+    }
+
+    /// Subscribe to alert notifications
+    pub fn subscribe(&self) -> broadcast::Receiver<WatchdogAlert> {
+        self.alert_sender.subscribe()
+    }
+
+    pub fn watchdog(&self) -> Arc<RwLock<EntityWatchdog>> {
+        Arc::clone(&self.watchdog)
+    }
+
+    pub async fn is_running(&self) -> bool {
+        self.task
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+    }
+
+    /// Start background polling; restarts the task if already running
+    pub async fn start(&self) -> Result<()> {
+        let interval = {
+            let watchdog = self.watchdog.read().await;
+            watchdog.config().validate()?;
+            Duration::from_millis(watchdog.config().check_interval_ms)
+        };
+
+        let brp_client = Arc::clone(&self.brp_client);
+        let watchdog = Arc::clone(&self.watchdog);
+        let sender = self.alert_sender.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = Self::check_once(&brp_client, &watchdog, &sender).await {
+                    debug!("Entity watchdog check skipped: {}", e);
+                }
+            }
+        });
+
+        if let Some(previous) = self.task.write().await.replace(task) {
+            previous.abort();
+        }
+        info!("Entity watchdog started (interval {:?})", interval);
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(task) = self.task.write().await.take() {
+            task.abort();
+            info!("Entity watchdog stopped");
+        }
+    }
+
+    /// Run a single check immediately
+    pub async fn check_now(&self) -> Result<Vec<WatchdogAlert>> {
+        Self::check_once(&self.brp_client, &self.watchdog, &self.alert_sender).await
+    }
+
+    async fn check_once(
+        brp_client: &Arc<RwLock<BrpClient>>,
+        watchdog: &Arc<RwLock<EntityWatchdog>>,
+        sender: &broadcast::Sender<WatchdogAlert>,
+    ) -> Result<Vec<WatchdogAlert>> {
+        let entities = Self::fetch_entities(brp_client).await?;
+
+        let alerts = {
+            let mut watchdog = watchdog.write().await;
+            let counts =
+                EntityCounts::from_entities(&entities, watchdog.config().tag_limits.keys());
+            watchdog.check(counts)
+        };
+
+        for alert in &alerts {
+            // No subscribers is not an error
+            let _ = sender.send(alert.clone());
+        }
+        Ok(alerts)
+    }
+
+    async fn fetch_entities(brp_client: &Arc<RwLock<BrpClient>>) -> Result<Vec<EntityData>> {
+        let mut client = brp_client.write().await;
+        if !client.is_connected() {
+            return Err(Error::Connection("BRP client not connected".to_string()));
+        }
+
+        match client
+            .send_request(&BrpRequest::ListEntities { filter: None })
+            .await?
+        {
+            BrpResponse::Success(boxed_result) => match *boxed_result {
+                BrpResult::Entities(entities) => Ok(entities),
+                _ => Err(Error::Brp("Expected entities list from BRP".to_string())),
+            },
+            BrpResponse::Error(error) => Err(Error::Brp(error.message)),
+        }
+    }
+}
+
+impl Drop for EntityWatchdogService {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.get_mut().take() {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(total: usize, tags: &[(&str, usize)]) -> EntityCounts {
+        EntityCounts {
+            total,
+            by_tag: tags.iter().map(|(t, c)| (t.to_string(), *c)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_tag_matching() {
+        assert!(tag_matches("game::enemy::Enemy", "Enemy"));
+        assert!(tag_matches("game::enemy::Enemy", "game::enemy::Enemy"));
+        assert!(!tag_matches("game::enemy::EnemySpawner", "Enemy"));
+    }
+
+    #[test]
+    fn test_limits_and_cooldown() {
+        let mut watchdog = EntityWatchdog::new(WatchdogConfig {
+            total_limit: Some(1000),
+            tag_limits: HashMap::from([("Bullet".to_string(), 200)]),
+            max_growth_ratio: None,
+            ..WatchdogConfig::default()
+        });
+
+        assert!(watchdog.check(counts(500, &[("Bullet", 100)])).is_empty());
+
+        let alerts = watchdog.check(counts(1500, &[("Bullet", 300)]));
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(
+            alerts[1].kind,
+            WatchdogAlertKind::TagLimitExceeded {
+                tag: "Bullet".to_string(),
+                limit: 200
+            }
+        );
+
+        // Same subjects are suppressed during the cooldown
+        assert!(watchdog.check(counts(1600, &[("Bullet", 400)])).is_empty());
+        assert_eq!(watchdog.alerts(10).len(), 2);
+        assert_eq!(watchdog.checks(), 3);
+    }
+
+    #[test]
+    fn test_rapid_growth() {
+        let mut watchdog = EntityWatchdog::new(WatchdogConfig {
+            total_limit: None,
+            max_growth_ratio: Some(2.0),
+            min_growth_count: 100,
+            ..WatchdogConfig::default()
+        });
+
+        watchdog.check(counts(40, &[]));
+        // Below min_growth_count, not reported even though it tripled
+        assert!(watchdog.check(counts(90, &[])).is_empty());
+
+        let alerts = watchdog.check(counts(400, &[]));
+        assert_eq!(alerts.len(), 1);
+        match &alerts[0].kind {
+            WatchdogAlertKind::RapidGrowth {
+                previous, ratio, ..
+            } => {
+                assert_eq!(*previous, 90);
+                assert!(*ratio > 4.0);
+            }
+            other => panic!("Unexpected alert: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = WatchdogConfig {
+            check_interval_ms: 10,
+            ..WatchdogConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = WatchdogConfig {
+            max_growth_ratio: Some(0.5),
+            ..WatchdogConfig::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(WatchdogConfig::default().validate().is_ok());
+    }
+}
@@ -218,6 +218,10 @@ pub enum Error {
     #[error("Security error: {0}")]
     SecurityError(String),
 
+    /// A rate limit, quota, IP ban or account lockout that expires on its own
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
     /// Rich error with full context
     #[error("Error: {context}")]
     WithContext {
@@ -226,3 +230,20 @@ pub enum Error {
         source: Option<Box<Error>>,
     },
 }
+
+impl Error {
+    /// Whether the same request might succeed later, such as after the game reconnects
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::WebSocket(_)
+            | Error::Connection(_)
+            | Error::Brp(_)
+            | Error::Timeout(_)
+            | Error::RateLimited(_) => true,
+            Error::WithContext { context, source } => {
+                context.is_retryable || source.as_deref().is_some_and(Error::is_transient)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+/*
+ * Bevy Debugger MCP Server - Error Codes
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Stable, machine-readable codes for tool errors
+//!
+//! Every tool error response carries the code of its [`Error`] as its
+//! `data`, so clients can branch on `code` or `retryable` instead of parsing
+//! messages. Codes are never renamed once published; `list_error_codes`
+//! returns the full table.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::Error;
+
+/// Broad kind of failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Configuration,
+    Connection,
+    Protocol,
+    Validation,
+    Data,
+    Storage,
+    Debugging,
+    Security,
+    Internal,
+}
+
+/// A documented error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    ConfigInvalid,
+    ConnectionFailed,
+    WebsocketFailed,
+    BrpRequestFailed,
+    Timeout,
+    McpProtocol,
+    InvalidInput,
+    ValidationFailed,
+    SerializationFailed,
+    IoFailed,
+    DebugCommandFailed,
+    CheckpointFailed,
+    AuthenticationRequired,
+    AccessDenied,
+    RateLimited,
+    Internal,
+}
+
+/// An error code with everything a client needs to handle it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorCodeInfo {
+    pub code: ErrorCode,
+    pub category: ErrorCategory,
+    /// Whether the same request may succeed later unchanged
+    pub retryable: bool,
+    pub suggested_action: String,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 16] = [
+        Self::ConfigInvalid,
+        Self::ConnectionFailed,
+        Self::WebsocketFailed,
+        Self::BrpRequestFailed,
+        Self::Timeout,
+        Self::McpProtocol,
+        Self::InvalidInput,
+        Self::ValidationFailed,
+        Self::SerializationFailed,
+        Self::IoFailed,
+        Self::DebugCommandFailed,
+        Self::CheckpointFailed,
+        Self::AuthenticationRequired,
+        Self::AccessDenied,
+        Self::RateLimited,
+        Self::Internal,
+    ];
+
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            Self::ConfigInvalid => ErrorCategory::Configuration,
+            Self::ConnectionFailed
+            | Self::WebsocketFailed
+            | Self::BrpRequestFailed
+            | Self::Timeout => ErrorCategory::Connection,
+            Self::McpProtocol => ErrorCategory::Protocol,
+            Self::InvalidInput | Self::ValidationFailed => ErrorCategory::Validation,
+            Self::SerializationFailed => ErrorCategory::Data,
+            Self::IoFailed => ErrorCategory::Storage,
+            Self::DebugCommandFailed | Self::CheckpointFailed => ErrorCategory::Debugging,
+            Self::AuthenticationRequired | Self::AccessDenied | Self::RateLimited => {
+                ErrorCategory::Security
+            }
+            Self::Internal => ErrorCategory::Internal,
+        }
+    }
+
+    /// Matches [`Error::is_transient`] for the errors behind the code
+    pub fn retryable(self) -> bool {
+        self.category() == ErrorCategory::Connection || self == Self::RateLimited
+    }
+
+    pub fn suggested_action(self) -> &'static str {
+        match self {
+            Self::ConfigInvalid => "Fix the server configuration or environment variables named in the message and restart",
+            Self::ConnectionFailed => "Start the game with RemotePlugin enabled and check BEVY_BRP_HOST and BEVY_BRP_PORT",
+            Self::WebsocketFailed => "Check that the game is still running; the connection is re-established automatically",
+            Self::BrpRequestFailed => "Check the game log for the failing BRP method, then retry",
+            Self::Timeout => "Retry later, or narrow the request if the game is under heavy load",
+            Self::McpProtocol => "Check the tool name and arguments against the tool list",
+            Self::InvalidInput | Self::ValidationFailed => "Correct the arguments described in the message; retrying unchanged will fail again",
+            Self::SerializationFailed => "Check that the data matches the expected format, such as component values for the game's types",
+            Self::IoFailed => "Check that the path exists and the server can read and write it",
+            Self::DebugCommandFailed => "Check the command's preconditions in the message, such as an active session or recording",
+            Self::CheckpointFailed => "List checkpoints to confirm the one requested exists and is intact",
+            Self::AuthenticationRequired => "Authenticate and pass the returned token as auth_token",
+            Self::AccessDenied => "Use an account or API key whose role and scopes allow this operation",
+            Self::RateLimited => "Wait and retry; the message says how long where known, and rate_limit_status shows your remaining quota",
+            Self::Internal => "Export a bug report with the bug_report tool and file an issue",
+        }
+    }
+
+    pub fn info(self) -> ErrorCodeInfo {
+        ErrorCodeInfo {
+            code: self,
+            category: self.category(),
+            retryable: self.retryable(),
+            suggested_action: self.suggested_action().to_string(),
+        }
+    }
+
+    /// The `data` of a tool error response with this code
+    pub fn data(self) -> Value {
+        json!(self.info())
+    }
+}
+
+/// Every code, or those in `category`, for `list_error_codes`
+pub fn list(category: Option<ErrorCategory>) -> Vec<ErrorCodeInfo> {
+    ErrorCode::ALL
+        .iter()
+        .filter(|code| category.is_none() || Some(code.category()) == category)
+        .map(|code| code.info())
+        .collect()
+}
+
+impl Error {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Config(_) => ErrorCode::ConfigInvalid,
+            Error::WebSocket(_) => ErrorCode::WebsocketFailed,
+            Error::Io(_) => ErrorCode::IoFailed,
+            Error::Json(_) | Error::Serialization(_) | Error::Uuid(_) => {
+                ErrorCode::SerializationFailed
+            }
+            Error::Connection(_) => ErrorCode::ConnectionFailed,
+            Error::Mcp(_) => ErrorCode::McpProtocol,
+            Error::Brp(_) => ErrorCode::BrpRequestFailed,
+            Error::Validation(_) => ErrorCode::ValidationFailed,
+            Error::DebugError(_) => ErrorCode::DebugCommandFailed,
+            Error::Checkpoint(_) => ErrorCode::CheckpointFailed,
+            Error::InvalidInput(_) => ErrorCode::InvalidInput,
+            Error::Timeout(_) => ErrorCode::Timeout,
+            Error::Internal(_) => ErrorCode::Internal,
+            Error::SecurityError(_) => ErrorCode::AccessDenied,
+            Error::RateLimited(_) => ErrorCode::RateLimited,
+            Error::WithContext { source, .. } => {
+                source.as_deref().map_or(ErrorCode::Internal, Error::code)
+            }
+        }
+    }
+
+    /// The `data` of a tool error response for this error, with its message
+    pub fn data(&self) -> Value {
+        let mut data = self.code().data();
+        data["message"] = json!(self.to_string());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_match_transient_errors() {
+        let errors = [
+            Error::Connection("refused".to_string()),
+            Error::Timeout("no reply".to_string()),
+            Error::Validation("bad query".to_string()),
+            Error::SecurityError("forbidden".to_string()),
+            Error::RateLimited("too many requests".to_string()),
+        ];
+        for error in &errors {
+            assert_eq!(error.code().retryable(), error.is_transient(), "{error}");
+        }
+
+        let data = Error::Brp("component not registered".to_string()).data();
+        assert_eq!(data["code"], "BRP_REQUEST_FAILED");
+        assert_eq!(data["category"], "connection");
+        assert_eq!(data["retryable"], true);
+        assert!(data["message"]
+            .as_str()
+            .unwrap()
+            .contains("component not registered"));
+
+        let codes: Vec<Value> = ErrorCode::ALL.iter().map(|code| json!(code)).collect();
+        let unique: std::collections::HashSet<String> =
+            codes.iter().map(Value::to_string).collect();
+        assert_eq!(unique.len(), ErrorCode::ALL.len());
+    }
+}
@@ -0,0 +1,195 @@
+/*
+ * Bevy Debugger MCP Server - Flight Recorder
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Always-on record of what the debugger itself did recently
+//!
+//! Tool calls, BRP requests and state transitions (BRP connection, circuit
+//! breaker) from the last few minutes are kept in memory, so an intermittent
+//! server-side problem can be examined after it happened with the
+//! `flight_recorder` tool. Recording an event is a short mutex-guarded push.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// How long events are kept unless `BEVY_MCP_FLIGHT_RECORDER_MINUTES` says otherwise
+pub const DEFAULT_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// Upper bound on kept events, whatever the retention
+pub const DEFAULT_FLIGHT_CAPACITY: usize = 20_000;
+
+/// What happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FlightEventKind {
+    ToolCall {
+        tool: String,
+        status: String,
+        duration_ms: u64,
+    },
+    BrpRequest {
+        method: String,
+        status: String,
+        duration_ms: u64,
+    },
+    StateTransition {
+        component: String,
+        state: String,
+    },
+}
+
+impl FlightEventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ToolCall { .. } => "tool_call",
+            Self::BrpRequest { .. } => "brp_request",
+            Self::StateTransition { .. } => "state_transition",
+        }
+    }
+}
+
+/// `"ok"` or `"error"`, as used in event statuses
+pub fn status(failed: bool) -> String {
+    if failed { "error" } else { "ok" }.to_string()
+}
+
+/// A recorded event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlightEvent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: FlightEventKind,
+}
+
+/// Events from the last `retention`, oldest first
+#[derive(Debug)]
+pub struct FlightRecorder {
+    events: Mutex<VecDeque<FlightEvent>>,
+    retention: Duration,
+    capacity: usize,
+}
+
+impl FlightRecorder {
+    pub fn new(retention: Duration, capacity: usize) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::new()),
+            retention,
+            capacity,
+        }
+    }
+
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    pub fn record(&self, kind: FlightEventKind) {
+        let now = Utc::now();
+        let Ok(mut events) = self.events.lock() else {
+            return;
+        };
+        events.push_back(FlightEvent { at: now, kind });
+        let cutoff = cutoff(now, self.retention);
+        while events.len() > self.capacity
+            || matches!((events.front(), cutoff), (Some(event), Some(cutoff)) if event.at < cutoff)
+        {
+            events.pop_front();
+        }
+    }
+
+    /// Kept events from the last `within`, or all of them
+    pub fn snapshot(&self, within: Option<Duration>) -> Vec<FlightEvent> {
+        let Ok(events) = self.events.lock() else {
+            return Vec::new();
+        };
+        let cutoff = within.and_then(|within| cutoff(Utc::now(), within));
+        events
+            .iter()
+            .filter(|event| !cutoff.is_some_and(|cutoff| event.at < cutoff))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut events) = self.events.lock() {
+            events.clear();
+        }
+    }
+}
+
+/// `age` before `now`, if representable
+fn cutoff(now: DateTime<Utc>, age: Duration) -> Option<DateTime<Utc>> {
+    now.checked_sub_signed(chrono::Duration::from_std(age).ok()?)
+}
+
+/// The recorder the server records into
+pub fn global() -> Arc<FlightRecorder> {
+    static GLOBAL: OnceLock<Arc<FlightRecorder>> = OnceLock::new();
+    GLOBAL
+        .get_or_init(|| {
+            let retention = std::env::var("BEVY_MCP_FLIGHT_RECORDER_MINUTES")
+                .ok()
+                .and_then(|minutes| minutes.parse::<u64>().ok())
+                .map(|minutes| Duration::from_secs(minutes * 60))
+                .unwrap_or(DEFAULT_RETENTION);
+            Arc::new(FlightRecorder::new(retention, DEFAULT_FLIGHT_CAPACITY))
+        })
+        .clone()
+}
+
+/// Record `kind` in the global recorder
+pub fn record(kind: FlightEventKind) {
+    global().record(kind);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recorder_keeps_most_recent_events() {
+        let recorder = FlightRecorder::new(Duration::from_secs(60), 2);
+        recorder.record(FlightEventKind::StateTransition {
+            component: "brp_connection".to_string(),
+            state: "connected".to_string(),
+        });
+        recorder.record(FlightEventKind::BrpRequest {
+            method: "bevy/query".to_string(),
+            status: status(false),
+            duration_ms: 3,
+        });
+        recorder.record(FlightEventKind::ToolCall {
+            tool: "observe".to_string(),
+            status: status(true),
+            duration_ms: 7,
+        });
+
+        let events = recorder.snapshot(None);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind.name(), "brp_request");
+
+        let json = serde_json::to_value(&events[1]).unwrap();
+        assert_eq!(json["kind"], "tool_call");
+        assert_eq!(json["tool"], "observe");
+        assert_eq!(json["status"], "error");
+
+        recorder.clear();
+        assert!(recorder.snapshot(Some(Duration::from_secs(60))).is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+/*
+ * Bevy Debugger MCP Server - HTTP Health Endpoints
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! `/healthz` and `/readyz` for process supervisors
+//!
+//! These are plain HTTP endpoints, served apart from MCP so systemd,
+//! Kubernetes and similar can probe the server without speaking MCP.
+//!
+//! - `/healthz` fails when the process has grown past the memory budget
+//!   ceiling, which eviction should have prevented; restarting is the fix.
+//! - `/readyz` also fails while the game isn't connected, the circuit
+//!   breaker is open or the memory budget is over its high watermark. These
+//!   usually clear up on their own, so they don't warrant a restart.
+//!
+//! Both answer 200 or 503 with the same JSON body describing why.
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+use crate::brp_client::BrpClient;
+use crate::error::{Error, Result};
+use crate::memory_budget::{global_memory_budget, MemoryBudget};
+use crate::process_metrics;
+use crate::resource_manager::ResourceManager;
+
+/// How long a probe waits for the BRP client, which is locked while reconnecting
+const BRP_LOCK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// What the probes are decided from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthSignals {
+    pub brp_connected: bool,
+    pub circuit_breaker_open: bool,
+    pub rss_bytes: u64,
+    pub cpu_percent: f32,
+    pub memory_ceiling_bytes: u64,
+    /// Share of the memory budget held by registered subsystems
+    pub memory_budget_utilization: f64,
+}
+
+/// Probe result, returned as the body of both endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthStatus {
+    pub live: bool,
+    pub ready: bool,
+    /// Why the server isn't live or ready
+    pub problems: Vec<String>,
+    #[serde(flatten)]
+    pub signals: HealthSignals,
+}
+
+impl HealthStatus {
+    /// Judge `signals`, treating budget utilization above `high_watermark` as pressure
+    pub fn evaluate(signals: HealthSignals, high_watermark: f64) -> Self {
+        let mut problems = Vec::new();
+
+        let live = signals.rss_bytes <= signals.memory_ceiling_bytes;
+        if !live {
+            problems.push(format!(
+                "Process uses {} MB, over the {} MB memory ceiling",
+                signals.rss_bytes / (1024 * 1024),
+                signals.memory_ceiling_bytes / (1024 * 1024)
+            ));
+        }
+        if !signals.brp_connected {
+            problems.push("Not connected to the Bevy game".to_string());
+        }
+        if signals.circuit_breaker_open {
+            problems.push("Circuit breaker is open".to_string());
+        }
+        if signals.memory_budget_utilization > high_watermark {
+            problems.push(format!(
+                "Memory budget is {:.0}% used",
+                signals.memory_budget_utilization * 100.0
+            ));
+        }
+
+        Self {
+            live,
+            ready: problems.is_empty(),
+            problems,
+            signals,
+        }
+    }
+}
+
+/// Gathers [`HealthSignals`] from the running server
+#[derive(Clone)]
+pub struct HealthProbe {
+    brp_client: Arc<RwLock<BrpClient>>,
+    resource_manager: Option<Arc<RwLock<ResourceManager>>>,
+    memory_budget: Arc<MemoryBudget>,
+}
+
+impl HealthProbe {
+    pub fn new(brp_client: Arc<RwLock<BrpClient>>) -> Self {
+        Self {
+            brp_client,
+            resource_manager: None,
+            memory_budget: global_memory_budget(),
+        }
+    }
+
+    /// Report this resource manager's circuit breaker
+    pub fn with_resource_manager(mut self, resource_manager: Arc<RwLock<ResourceManager>>) -> Self {
+        self.resource_manager = Some(resource_manager);
+        self
+    }
+
+    pub async fn check(&self) -> HealthStatus {
+        // A client stuck reconnecting counts as disconnected rather than stalling the probe
+        let brp_connected = tokio::time::timeout(BRP_LOCK_TIMEOUT, self.brp_client.read())
+            .await
+            .map(|client| client.is_connected())
+            .unwrap_or(false);
+
+        let circuit_breaker_open = match &self.resource_manager {
+            Some(rm) => rm.read().await.get_metrics().await.circuit_breaker_open,
+            None => false,
+        };
+
+        let process = process_metrics::sample();
+        let budget = self.memory_budget.report().await;
+
+        HealthStatus::evaluate(
+            HealthSignals {
+                brp_connected,
+                circuit_breaker_open,
+                rss_bytes: process.rss_bytes,
+                cpu_percent: process.cpu_percent,
+                memory_ceiling_bytes: budget.ceiling_bytes as u64,
+                memory_budget_utilization: budget.utilization,
+            },
+            self.memory_budget.config().high_watermark,
+        )
+    }
+}
+
+/// Router serving `/healthz` and `/readyz`
+pub fn router(probe: HealthProbe) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .with_state(probe)
+}
+
+/// Serve the endpoints on `addr` in the background, returning the bound address
+pub async fn spawn(addr: &str, probe: HealthProbe) -> Result<SocketAddr> {
+    let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+        Error::Connection(format!("Failed to bind health endpoints to {addr}: {e}"))
+    })?;
+    let local_addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router(probe)).await {
+            error!("Health endpoint server failed: {}", e);
+        }
+    });
+    info!(
+        "Health endpoints available at http://{}/healthz and /readyz",
+        local_addr
+    );
+    Ok(local_addr)
+}
+
+async fn healthz(State(probe): State<HealthProbe>) -> (StatusCode, Json<HealthStatus>) {
+    let status = probe.check().await;
+    (status_code(status.live), Json(status))
+}
+
+async fn readyz(State(probe): State<HealthProbe>) -> (StatusCode, Json<HealthStatus>) {
+    let status = probe.check().await;
+    (status_code(status.ready), Json(status))
+}
+
+fn status_code(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_disconnected_server_is_live_but_not_ready() {
+        let brp_client = Arc::new(RwLock::new(BrpClient::new(&Config::default())));
+        let app = router(HealthProbe::new(brp_client));
+
+        let healthz = app
+            .clone()
+            .oneshot(Request::get("/healthz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(healthz.status(), StatusCode::OK);
+
+        let readyz = app
+            .oneshot(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(readyz.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_memory_over_ceiling_fails_liveness() {
+        let status = HealthStatus::evaluate(
+            HealthSignals {
+                brp_connected: true,
+                circuit_breaker_open: false,
+                rss_bytes: 300 * 1024 * 1024,
+                cpu_percent: 5.0,
+                memory_ceiling_bytes: 256 * 1024 * 1024,
+                memory_budget_utilization: 0.5,
+            },
+            0.9,
+        );
+        assert!(!status.live);
+        assert!(!status.ready);
+        assert_eq!(
+            status.problems,
+            vec!["Process uses 300 MB, over the 256 MB memory ceiling"]
+        );
+    }
+}
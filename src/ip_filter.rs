@@ -0,0 +1,205 @@
+/*
+ * Bevy Debugger MCP Server - IP Allow and Deny Lists
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// An address range in CIDR notation; a bare address is a single host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IpNet {
+    address: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // Dual-stack listeners report IPv4 clients as IPv4-mapped IPv6
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        match (self.address, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(net) as u128,
+                u32::from(ip) as u128,
+                32,
+                self.prefix,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(net: u128, ip: u128, bits: u8, prefix: u8) -> bool {
+    let host_bits = (bits - prefix) as u32;
+    host_bits >= 128 || (net >> host_bits) == (ip >> host_bits)
+}
+
+impl FromStr for IpNet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Validation(format!("Invalid IP address or CIDR range: {}", s));
+        let (address, prefix) = match s.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s.trim(), None),
+        };
+        let address: IpAddr = address.parse().map_err(|_| invalid())?;
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse().map_err(|_| invalid())?,
+            None => max_prefix,
+        };
+        if prefix > max_prefix {
+            return Err(invalid());
+        }
+        Ok(Self { address, prefix })
+    }
+}
+
+impl TryFrom<String> for IpNet {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<IpNet> for String {
+    fn from(net: IpNet) -> Self {
+        net.to_string()
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+/// Parse a comma separated list of addresses and CIDR ranges
+pub fn parse_ip_list(list: &str) -> Result<Vec<IpNet>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Network-level access control applied before any authentication
+///
+/// The deny list always wins. When the allow list is empty every address not
+/// denied is allowed; otherwise an address must be in the allow list.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IpFilter {
+    pub allow: Vec<IpNet>,
+    pub deny: Vec<IpNet>,
+}
+
+impl IpFilter {
+    /// From `BEVY_MCP_IP_ALLOWLIST` and `BEVY_MCP_IP_DENYLIST`
+    pub fn from_env() -> Result<Self> {
+        let list = |name: &str| {
+            env::var(name)
+                .map(|list| parse_ip_list(&list))
+                .unwrap_or_else(|_| Ok(Vec::new()))
+        };
+        Ok(Self {
+            allow: list("BEVY_MCP_IP_ALLOWLIST")?,
+            deny: list("BEVY_MCP_IP_DENYLIST")?,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.allow.is_empty() || !self.deny.is_empty()
+    }
+
+    /// Why `ip` is rejected, or `Ok` when it may connect
+    pub fn check(&self, ip: IpAddr) -> std::result::Result<(), String> {
+        if let Some(net) = self.deny.iter().find(|net| net.contains(ip)) {
+            return Err(format!("{} is in denied range {}", ip, net));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|net| net.contains(ip)) {
+            return Err(format!("{} is not in the allow list", ip));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cidr_matching() {
+        let net: IpNet = "10.20.0.0/16".parse().unwrap();
+        assert!(net.contains(ip("10.20.255.1")));
+        assert!(!net.contains(ip("10.21.0.1")));
+        assert!(net.contains(ip("::ffff:10.20.1.1")));
+        assert!(!net.contains(ip("fd00::1")));
+
+        let v6: IpNet = "fd00:abcd::/32".parse().unwrap();
+        assert!(v6.contains(ip("fd00:abcd:1::5")));
+        assert!(!v6.contains(ip("fd00:abce::5")));
+
+        let any: IpNet = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(ip("203.0.113.9")));
+        assert_eq!(
+            "192.168.1.5".parse::<IpNet>().unwrap().to_string(),
+            "192.168.1.5/32"
+        );
+
+        assert!("10.0.0.0/33".parse::<IpNet>().is_err());
+        assert!("not-an-ip".parse::<IpNet>().is_err());
+    }
+
+    #[test]
+    fn test_deny_list_overrides_allow_list() {
+        let filter = IpFilter {
+            allow: parse_ip_list("10.0.0.0/8, 127.0.0.1").unwrap(),
+            deny: parse_ip_list("10.66.0.0/16").unwrap(),
+        };
+        assert!(filter.check(ip("10.1.2.3")).is_ok());
+        assert!(filter.check(ip("127.0.0.1")).is_ok());
+        assert!(filter.check(ip("10.66.0.7")).is_err());
+        assert!(filter.check(ip("192.168.0.10")).is_err());
+
+        // Without an allow list only the deny list applies
+        let filter = IpFilter {
+            allow: Vec::new(),
+            deny: parse_ip_list("192.0.2.0/24").unwrap(),
+        };
+        assert!(filter.check(ip("198.51.100.1")).is_ok());
+        assert!(filter.check(ip("192.0.2.200")).is_err());
+        assert!(!IpFilter::default().is_enabled());
+    }
+}
@@ -0,0 +1,265 @@
+/*
+ * Bevy Debugger MCP Server - JWT Signing Key Rotation
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::sync::RwLock;
+
+use base64::Engine as _;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Public details of a signing key; the secret itself is never exposed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningKeyInfo {
+    /// Carried in the `kid` header of every token the key signs
+    pub kid: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    /// When a retired key stops validating tokens
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+struct SigningKey {
+    info: SigningKeyInfo,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+}
+
+impl SigningKey {
+    fn new(secret: &str) -> Self {
+        Self {
+            info: SigningKeyInfo {
+                kid: key_id(secret),
+                active: true,
+                created_at: Utc::now(),
+                expires_at: None,
+            },
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+        }
+    }
+
+    fn is_valid(&self, now: DateTime<Utc>) -> bool {
+        !matches!(self.info.expires_at, Some(expiry) if now >= expiry)
+    }
+}
+
+/// Stable key ID for `secret`, so restarts keep the same `kid`
+pub fn key_id(secret: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, secret.as_bytes());
+    digest.as_ref()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// HS256 signing keys: one active key signs, retired keys keep validating
+///
+/// Rotating retires the active key for a grace window rather than dropping
+/// it, so tokens issued just before a rotation stay valid until they would
+/// have expired anyway.
+pub struct JwtKeyRing {
+    keys: RwLock<Vec<SigningKey>>,
+    grace: Duration,
+}
+
+impl std::fmt::Debug for JwtKeyRing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtKeyRing")
+            .field("keys", &self.keys())
+            .field("grace", &self.grace)
+            .finish()
+    }
+}
+
+impl JwtKeyRing {
+    pub fn new(secret: &str, grace: Duration) -> Self {
+        Self {
+            keys: RwLock::new(vec![SigningKey::new(secret)]),
+            grace,
+        }
+    }
+
+    /// Sign `claims` with the active key
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String> {
+        let keys = self.keys.read().unwrap();
+        let key = keys
+            .iter()
+            .find(|key| key.info.active)
+            .ok_or_else(|| Error::SecurityError("No active signing key".to_string()))?;
+        let header = Header {
+            kid: Some(key.info.kid.clone()),
+            ..Header::default()
+        };
+        encode(&header, claims, &key.encoding)
+            .map_err(|e| Error::SecurityError(format!("Token generation failed: {}", e)))
+    }
+
+    /// Decode `token` with the key named by its `kid` header
+    ///
+    /// Tokens without a `kid` were issued before key IDs existed and are
+    /// checked against the active key only.
+    pub fn verify<T: DeserializeOwned>(&self, token: &str, validation: &Validation) -> Result<T> {
+        let header = decode_header(token)
+            .map_err(|e| Error::SecurityError(format!("Invalid token: {}", e)))?;
+        let now = Utc::now();
+        let keys = self.keys.read().unwrap();
+        let key = match &header.kid {
+            Some(kid) => keys.iter().find(|key| &key.info.kid == kid),
+            None => keys.iter().find(|key| key.info.active),
+        }
+        .filter(|key| key.is_valid(now))
+        .ok_or_else(|| {
+            Error::SecurityError("Invalid token: unknown or expired signing key".to_string())
+        })?;
+
+        decode::<T>(token, &key.decoding, validation)
+            .map(|data| data.claims)
+            .map_err(|e| Error::SecurityError(format!("Invalid token: {}", e)))
+    }
+
+    /// Make a new key active, generating its secret when none is given
+    ///
+    /// The previous active key keeps validating for the grace window.
+    pub fn rotate(&self, secret: Option<&str>) -> Result<SigningKeyInfo> {
+        let secret = match secret {
+            Some(secret) => secret.to_string(),
+            None => generate_secret()?,
+        };
+        let new_key = SigningKey::new(&secret);
+        let info = new_key.info.clone();
+
+        let mut keys = self.keys.write().unwrap();
+        if keys.iter().any(|key| key.info.kid == info.kid) {
+            return Err(Error::SecurityError(
+                "Signing key is already in use".to_string(),
+            ));
+        }
+        let retire_at = info.created_at + self.grace;
+        for key in keys.iter_mut().filter(|key| key.info.active) {
+            key.info.active = false;
+            key.info.expires_at = Some(retire_at);
+        }
+        keys.push(new_key);
+        Ok(info)
+    }
+
+    /// Every key still able to validate tokens, oldest first
+    pub fn keys(&self) -> Vec<SigningKeyInfo> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .map(|key| key.info.clone())
+            .collect()
+    }
+
+    /// Drop retired keys past their grace window, returning how many
+    pub fn prune(&self) -> usize {
+        let now = Utc::now();
+        let mut keys = self.keys.write().unwrap();
+        let before = keys.len();
+        keys.retain(|key| key.is_valid(now));
+        before - keys.len()
+    }
+}
+
+fn generate_secret() -> Result<String> {
+    let mut bytes = [0u8; 64];
+    ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut bytes)
+        .map_err(|_| Error::SecurityError("Failed to generate signing key".to_string()))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::Algorithm;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestClaims {
+        sub: String,
+        exp: u64,
+    }
+
+    fn claims() -> TestClaims {
+        TestClaims {
+            sub: "alice".to_string(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp() as u64,
+        }
+    }
+
+    fn validation() -> Validation {
+        Validation::new(Algorithm::HS256)
+    }
+
+    #[test]
+    fn test_old_tokens_validate_during_grace_window() {
+        let ring = JwtKeyRing::new("first-secret", Duration::hours(1));
+        let old_token = ring.sign(&claims()).unwrap();
+        assert_eq!(
+            decode_header(&old_token).unwrap().kid,
+            Some(key_id("first-secret"))
+        );
+
+        let rotated = ring.rotate(None).unwrap();
+        let new_token = ring.sign(&claims()).unwrap();
+        assert_eq!(decode_header(&new_token).unwrap().kid, Some(rotated.kid));
+        assert_eq!(
+            ring.verify::<TestClaims>(&old_token, &validation())
+                .unwrap()
+                .sub,
+            "alice"
+        );
+        assert!(ring.verify::<TestClaims>(&new_token, &validation()).is_ok());
+
+        let keys = ring.keys();
+        assert_eq!(keys.len(), 2);
+        assert!(!keys[0].active && keys[0].expires_at.is_some());
+        assert_eq!(ring.prune(), 0);
+        assert!(ring.rotate(Some("first-secret")).is_err());
+    }
+
+    #[test]
+    fn test_retired_keys_expire_after_grace_window() {
+        let ring = JwtKeyRing::new("first-secret", Duration::zero());
+        let old_token = ring.sign(&claims()).unwrap();
+        ring.rotate(Some("second-secret")).unwrap();
+
+        assert!(ring
+            .verify::<TestClaims>(&old_token, &validation())
+            .is_err());
+        assert_eq!(ring.prune(), 1);
+        assert_eq!(ring.keys().len(), 1);
+
+        // A token signed by an unrelated secret with a known kid still fails
+        let forged = encode(
+            &Header {
+                kid: Some(key_id("second-secret")),
+                ..Header::default()
+            },
+            &claims(),
+            &EncodingKey::from_secret(b"attacker"),
+        )
+        .unwrap();
+        assert!(ring.verify::<TestClaims>(&forged, &validation()).is_err());
+    }
+}
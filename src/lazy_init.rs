@@ -1,6 +1,10 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Weak};
+use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLock, Mutex, OnceCell};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::brp_client::BrpClient;
 use crate::config::Config;
@@ -14,7 +18,7 @@ use crate::memory_profiler_processor::MemoryProfilerProcessor;
 use crate::session_processor::SessionProcessor;
 use crate::issue_detector_processor::IssueDetectorProcessor;
 use crate::performance_budget_processor::PerformanceBudgetProcessor;
-use crate::pattern_learning::PatternLearningSystem;
+use crate::pattern_learning::{patterns_file_from_env, PatternLearningSystem};
 use crate::suggestion_engine::SuggestionEngine;
 use crate::workflow_automation::WorkflowAutomation;
 use crate::hot_reload::{HotReloadSystem, HotReloadConfig};
@@ -52,6 +56,87 @@ pub struct LazyComponents {
     
     // Initialization mutex to prevent race conditions
     init_mutex: Mutex<()>,
+    
+    // Components used by this process, and what earlier sessions used
+    used_components: std::sync::Mutex<BTreeSet<String>>,
+    usage_profile: UsageProfile,
+    usage_profile_path: Option<PathBuf>,
+    preloading: AtomicBool,
+}
+
+/// Environment variable overriding where the usage profile is stored
+pub const USAGE_PROFILE_PATH_ENV: &str = "BEVY_DEBUGGER_USAGE_PROFILE";
+
+/// Default usage profile location, relative to the working directory so each project has its own
+pub const DEFAULT_USAGE_PROFILE_PATH: &str = ".bevy_debugger/usage_profile.json";
+
+/// Number of past sessions the usage profile remembers
+const MAX_PROFILE_SESSIONS: usize = 20;
+
+/// Share of remembered sessions a component must be used in to be preloaded
+const PRELOAD_SESSION_SHARE: f64 = 0.25;
+
+/// Which lazy components recent sessions of a project used
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageProfile {
+    /// Components used in each session, oldest first
+    pub sessions: VecDeque<BTreeSet<String>>,
+}
+
+impl UsageProfile {
+    /// Profile location from `BEVY_DEBUGGER_USAGE_PROFILE`, or the default
+    pub fn default_path() -> PathBuf {
+        std::env::var(USAGE_PROFILE_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_USAGE_PROFILE_PATH))
+    }
+
+    /// Load a saved profile; a missing file yields an empty profile
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the profile, writing to a temporary file first so a crash never leaves a partial file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Add a session's components, forgetting the oldest sessions past the limit
+    pub fn record_session(&mut self, used: BTreeSet<String>) {
+        if used.is_empty() {
+            return;
+        }
+        self.sessions.push_back(used);
+        while self.sessions.len() > MAX_PROFILE_SESSIONS {
+            self.sessions.pop_front();
+        }
+    }
+
+    /// Components used in enough recent sessions to be worth preloading
+    pub fn frequently_used(&self) -> Vec<String> {
+        let threshold = (self.sessions.len() as f64 * PRELOAD_SESSION_SHARE).max(1.0);
+        let mut counts: std::collections::BTreeMap<&str, usize> = Default::default();
+        for session in &self.sessions {
+            for name in session {
+                *counts.entry(name.as_str()).or_default() += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count as f64 >= threshold)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
 }
 
 impl LazyComponents {
@@ -76,11 +161,76 @@ impl LazyComponents {
             workflow_automation: OnceCell::new(),
             hot_reload_system: OnceCell::new(),
             init_mutex: Mutex::new(()),
+            used_components: std::sync::Mutex::new(BTreeSet::new()),
+            usage_profile: UsageProfile::default(),
+            usage_profile_path: None,
+            preloading: AtomicBool::new(false),
+        }
+    }
+    
+    /// Track component usage in the profile at `path`, loading what earlier sessions recorded
+    pub fn with_usage_profile(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.usage_profile = UsageProfile::load(&path).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable usage profile {}: {}", path.display(), e);
+            UsageProfile::default()
+        });
+        self.usage_profile_path = Some(path);
+        self
+    }
+    
+    /// Save earlier sessions plus this one to the usage profile, if one is configured
+    pub fn save_usage_profile(&self) -> Result<()> {
+        let Some(path) = &self.usage_profile_path else {
+            return Ok(());
+        };
+        let mut profile = self.usage_profile.clone();
+        profile.record_session(self.used_components());
+        profile.save(path)
+    }
+    
+    /// Components this process has used so far
+    pub fn used_components(&self) -> BTreeSet<String> {
+        self.used_components.lock().unwrap().clone()
+    }
+    
+    fn record_use(&self, component: &str) {
+        // Preloading isn't use; a component that is really needed gets asked for again
+        if self.preloading.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut used = self.used_components.lock().unwrap();
+        if !used.contains(component) {
+            used.insert(component.to_string());
+        }
+    }
+    
+    /// Initialize a component by name, returning false for unknown names
+    async fn preload(&self, component: &str) -> bool {
+        match component {
+            "entity_inspector" => drop(self.get_entity_inspector().await),
+            "system_profiler" => drop(self.get_system_profiler().await),
+            "entity_processor" => drop(self.get_entity_processor().await),
+            "profiler_processor" => drop(self.get_profiler_processor().await),
+            "visual_overlay_processor" => drop(self.get_visual_overlay_processor().await),
+            "query_builder_processor" => drop(self.get_query_builder_processor().await),
+            "memory_profiler_processor" => drop(self.get_memory_profiler_processor().await),
+            "session_processor" => drop(self.get_session_processor().await),
+            "issue_detector_processor" => drop(self.get_issue_detector_processor().await),
+            "performance_budget_processor" => drop(self.get_performance_budget_processor().await),
+            "debug_command_router" => drop(self.get_debug_command_router().await),
+            "pattern_learning_system" => drop(self.get_pattern_learning_system().await),
+            "suggestion_engine" => drop(self.get_suggestion_engine().await),
+            "workflow_automation" => drop(self.get_workflow_automation().await),
+            "hot_reload_system" => drop(self.get_hot_reload_system().await),
+            _ => return false,
         }
+        true
     }
     
     /// Get or initialize entity inspector
     pub async fn get_entity_inspector(&self) -> Arc<EntityInspector> {
+        self.record_use("entity_inspector");
         // Try to get existing without cloning Arc immediately
         if let Some(inspector) = self.entity_inspector.get() {
             return Arc::clone(inspector);
@@ -105,6 +255,7 @@ impl LazyComponents {
     
     /// Get or initialize system profiler
     pub async fn get_system_profiler(&self) -> Arc<SystemProfiler> {
+        self.record_use("system_profiler");
         if let Some(profiler) = self.system_profiler.get() {
             return Arc::clone(profiler);
         }
@@ -127,6 +278,7 @@ impl LazyComponents {
     
     /// Get or initialize entity inspection processor
     pub async fn get_entity_processor(&self) -> Arc<EntityInspectionProcessor> {
+        self.record_use("entity_processor");
         if let Some(processor) = self.entity_processor.get() {
             return Arc::clone(processor);
         }
@@ -150,6 +302,7 @@ impl LazyComponents {
     
     /// Get or initialize system profiler processor
     pub async fn get_profiler_processor(&self) -> Arc<SystemProfilerProcessor> {
+        self.record_use("profiler_processor");
         if let Some(processor) = self.profiler_processor.get() {
             return Arc::clone(processor);
         }
@@ -173,6 +326,7 @@ impl LazyComponents {
     
     /// Get or initialize visual debug overlay processor
     pub async fn get_visual_overlay_processor(&self) -> Arc<VisualDebugOverlayProcessor> {
+        self.record_use("visual_overlay_processor");
         if let Some(processor) = self.visual_overlay_processor.get() {
             return Arc::clone(processor);
         }
@@ -195,6 +349,7 @@ impl LazyComponents {
     
     /// Get or initialize query builder processor
     pub async fn get_query_builder_processor(&self) -> Arc<QueryBuilderProcessor> {
+        self.record_use("query_builder_processor");
         if let Some(processor) = self.query_builder_processor.get() {
             return Arc::clone(processor);
         }
@@ -217,6 +372,7 @@ impl LazyComponents {
     
     /// Get or initialize memory profiler processor
     pub async fn get_memory_profiler_processor(&self) -> Arc<MemoryProfilerProcessor> {
+        self.record_use("memory_profiler_processor");
         if let Some(processor) = self.memory_profiler_processor.get() {
             return Arc::clone(processor);
         }
@@ -239,6 +395,7 @@ impl LazyComponents {
     
     /// Get or initialize session processor
     pub async fn get_session_processor(&self) -> Arc<SessionProcessor> {
+        self.record_use("session_processor");
         if let Some(processor) = self.session_processor.get() {
             return Arc::clone(processor);
         }
@@ -278,6 +435,7 @@ impl LazyComponents {
     
     /// Get or initialize issue detector processor
     pub async fn get_issue_detector_processor(&self) -> Arc<IssueDetectorProcessor> {
+        self.record_use("issue_detector_processor");
         if let Some(processor) = self.issue_detector_processor.get() {
             return Arc::clone(processor);
         }
@@ -300,6 +458,7 @@ impl LazyComponents {
     
     /// Get or initialize performance budget processor
     pub async fn get_performance_budget_processor(&self) -> Arc<PerformanceBudgetProcessor> {
+        self.record_use("performance_budget_processor");
         if let Some(processor) = self.performance_budget_processor.get() {
             return Arc::clone(processor);
         }
@@ -322,6 +481,7 @@ impl LazyComponents {
     
     /// Get or initialize debug command router with all processors
     pub async fn get_debug_command_router(&self) -> Arc<DebugCommandRouter> {
+        self.record_use("debug_command_router");
         if let Some(router) = self.debug_command_router.get() {
             return Arc::clone(router);
         }
@@ -367,6 +527,7 @@ impl LazyComponents {
     
     /// Get or initialize pattern learning system
     pub async fn get_pattern_learning_system(&self) -> Arc<PatternLearningSystem> {
+        self.record_use("pattern_learning_system");
         if let Some(system) = self.pattern_learning_system.get() {
             return Arc::clone(system);
         }
@@ -379,7 +540,10 @@ impl LazyComponents {
         }
         
         debug!("Lazy initializing PatternLearningSystem");
-        let system = Arc::new(PatternLearningSystem::new());
+        let system = Arc::new(match patterns_file_from_env() {
+            Some(path) => PatternLearningSystem::with_store(path).await,
+            None => PatternLearningSystem::new(),
+        });
         
         let _ = self.pattern_learning_system.set(Arc::clone(&system));
         
@@ -389,6 +553,7 @@ impl LazyComponents {
     
     /// Get or initialize suggestion engine
     pub async fn get_suggestion_engine(&self) -> Arc<SuggestionEngine> {
+        self.record_use("suggestion_engine");
         if let Some(engine) = self.suggestion_engine.get() {
             return Arc::clone(engine);
         }
@@ -412,6 +577,7 @@ impl LazyComponents {
     
     /// Get or initialize workflow automation
     pub async fn get_workflow_automation(&self) -> Arc<WorkflowAutomation> {
+        self.record_use("workflow_automation");
         if let Some(automation) = self.workflow_automation.get() {
             return Arc::clone(automation);
         }
@@ -436,6 +602,7 @@ impl LazyComponents {
     
     /// Get or initialize hot reload system
     pub async fn get_hot_reload_system(&self) -> Arc<HotReloadSystem> {
+        self.record_use("hot_reload_system");
         if let Some(system) = self.hot_reload_system.get() {
             return Arc::clone(system);
         }
@@ -528,7 +695,20 @@ pub async fn preload_critical_components(_components: &LazyComponents) -> Result
         let _ = _components.get_session_processor().await;
     }
     
-    info!("Critical components preloaded based on enabled features");
+    // Then whatever this project's recent sessions kept reaching for
+    let frequently_used = _components.usage_profile.frequently_used();
+    _components.preloading.store(true, Ordering::Relaxed);
+    for component in &frequently_used {
+        if !_components.preload(component).await {
+            debug!("Usage profile names unknown component {}", component);
+        }
+    }
+    _components.preloading.store(false, Ordering::Relaxed);
+    
+    info!(
+        "Critical components preloaded based on enabled features and usage profile ({:?})",
+        frequently_used
+    );
     Ok(())
 }
 
@@ -570,4 +750,42 @@ mod tests {
         
         assert!(Arc::ptr_eq(&inspector1, &inspector2));
     }
+    
+    #[tokio::test]
+    async fn test_usage_profile_drives_preloading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("usage_profile.json");
+        let brp_client = Arc::new(RwLock::new(BrpClient::new(&Config::default())));
+        
+        // A session that used the system profiler saves it to the profile
+        let first = LazyComponents::new(Arc::clone(&brp_client)).with_usage_profile(&path);
+        let _ = first.get_system_profiler().await;
+        first.save_usage_profile().unwrap();
+        assert_eq!(
+            UsageProfile::load(&path).unwrap().frequently_used(),
+            vec!["system_profiler".to_string()]
+        );
+        
+        // The next session preloads it without counting the preload as use
+        let second = LazyComponents::new(brp_client).with_usage_profile(&path);
+        preload_critical_components(&second).await.unwrap();
+        assert!(second.system_profiler.get().is_some());
+        assert!(second.used_components().is_empty());
+    }
+    
+    #[test]
+    fn test_usage_profile_forgets_rare_components() {
+        let mut profile = UsageProfile::default();
+        for i in 0..MAX_PROFILE_SESSIONS + 5 {
+            let mut used: BTreeSet<String> = ["entity_inspector".to_string()].into();
+            if i == 0 {
+                used.insert("hot_reload_system".to_string());
+            }
+            profile.record_session(used);
+        }
+        profile.record_session(BTreeSet::new());
+        
+        assert_eq!(profile.sessions.len(), MAX_PROFILE_SESSIONS);
+        assert_eq!(profile.frequently_used(), vec!["entity_inspector".to_string()]);
+    }
 }
\ No newline at end of file
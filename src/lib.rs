@@ -92,6 +92,7 @@
 //! - [`playback_system`] - Deterministic replay capabilities
 //! - [`timeline_branching`] - Multi-timeline debugging with branching
 //! - [`checkpoint`] - Save/restore debugging sessions
+//! - [`world_snapshot`] - Capture and restore live game world state
 //!
 //! ### Analysis and Monitoring
 //! - [`anomaly_detector`] - Pattern recognition for unusual behavior
@@ -164,6 +165,7 @@ pub mod prelude {
 
 // Core functionality
 pub mod error;
+pub mod error_codes;
 pub mod config;
 pub mod circuit_breaker;
 pub mod connection_pool;
@@ -181,6 +183,7 @@ pub mod debug_command_processor;
 pub mod entity_inspector;
 pub mod mcp_server;
 pub mod mcp_server_v2;
+pub mod wire_encoding;
 pub mod mcp_tools;
 pub mod query_builder_processor;
 
@@ -191,6 +194,8 @@ pub mod memory_profiler;
 pub mod memory_profiler_processor;
 pub mod visual_debug_overlay;
 pub mod visual_debug_overlay_processor;
+pub mod transform_manipulation;
+pub mod overlay_theme;
 
 // Issue detection
 pub mod issue_detector;
@@ -215,6 +220,12 @@ pub mod stress_test_system;
 
 // State management
 pub mod recording_system;
+pub mod recording_format;
+pub mod disk_ring_buffer;
+pub mod session_archive;
+pub mod recording_diff;
+pub mod world_snapshot;
+pub mod replay_assertions;
 pub mod playback_system;
 pub mod timeline_branching;
 pub mod checkpoint;
@@ -224,16 +235,33 @@ pub mod session_processor;
 pub mod replay_actor;
 
 // Analysis and monitoring
+pub mod alerting;
+pub mod workflow_scheduler;
 pub mod anomaly_detector;
+pub mod entity_watchdog;
+pub mod flight_recorder;
+pub mod crash_report;
 pub mod diagnostics;
+pub mod diagnostics_bridge;
+pub mod health_endpoints;
+pub mod log_buffer;
+pub mod log_format;
+pub mod metrics_store;
+pub mod schema_history;
+pub mod process_metrics;
 pub mod resource_manager;
 
 // Infrastructure
 pub mod tool_orchestration;
+pub mod pipeline_persistence;
+pub mod pipeline_templates;
 pub mod dead_letter_queue;
+#[cfg(feature = "sqlite-dlq")]
+pub mod dead_letter_store;
 pub mod lazy_init;
 pub mod command_cache;
 pub mod response_pool;
+pub mod memory_budget;
 pub mod profiling;
 pub mod compile_opts;
 pub mod memory_optimization_tracker;
@@ -252,6 +280,8 @@ pub mod panic_prevention_tests;
 pub mod pattern_learning;
 pub mod suggestion_engine;
 pub mod workflow_automation;
+#[cfg(feature = "scripting")]
+pub mod workflow_scripts;
 pub mod hot_reload;
 
 // Bevy reflection integration (Epic BEVDBG-012)
@@ -264,9 +294,26 @@ pub mod query_optimization_guide;
 
 // Production features
 pub mod security_config;
+pub mod secrets;
+pub mod password_policy;
 pub mod security;
+pub mod jwt_keys;
+pub mod login_throttle;
+pub mod audit_log;
+pub mod audit_chain;
+pub mod audit_sinks;
+pub mod user_store;
+pub mod totp;
+pub mod oidc;
+pub mod mtls;
+pub mod ip_filter;
+pub mod rbac_policy;
+pub mod rate_limit;
+pub mod redaction;
+pub mod report_redaction;
 pub mod secure_mcp_tools;
 pub mod bevy_observability_integration;
+pub mod otel_export;
 
 // Epic 6: Production features - Observability stack
 #[cfg(feature = "observability")]
@@ -0,0 +1,259 @@
+/*
+ * Bevy Debugger MCP Server - Recent Log Buffer
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Keeps the most recent log events in memory for diagnostic reports
+//!
+//! [`layer`] is installed alongside the normal log output and copies every
+//! event into a bounded ring buffer, so a bug report can include what the
+//! server logged just before the failure even when logs weren't being kept.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Events kept by the global buffer
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+
+/// A captured log event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    /// The message followed by the event's other fields as `name=value`
+    pub message: String,
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:>5} {}: {}",
+            self.timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// Which buffered events to return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogQuery {
+    /// Least severe level included
+    pub min_level: Level,
+    /// Most recent events to return
+    pub limit: usize,
+}
+
+impl Default for LogQuery {
+    fn default() -> Self {
+        Self {
+            min_level: Level::INFO,
+            limit: 200,
+        }
+    }
+}
+
+/// Bounded buffer of recent log events; the oldest are dropped when full
+#[derive(Debug)]
+pub struct LogBuffer {
+    records: Mutex<VecDeque<(Level, LogRecord)>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(capacity.min(DEFAULT_LOG_CAPACITY))),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, level: Level, record: LogRecord) {
+        let Ok(mut records) = self.records.lock() else {
+            return;
+        };
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back((level, record));
+    }
+
+    /// The most recent events matching `query`, oldest first
+    pub fn recent(&self, query: LogQuery) -> Vec<LogRecord> {
+        match self.records.lock() {
+            Ok(records) => select(&records, query),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Like [`recent`](Self::recent), but `None` instead of waiting for the lock
+    ///
+    /// For panic hooks, where the panicking thread may already hold it.
+    pub fn try_recent(&self, query: LogQuery) -> Option<Vec<LogRecord>> {
+        self.records
+            .try_lock()
+            .ok()
+            .map(|records| select(&records, query))
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().map(|r| r.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn select(records: &VecDeque<(Level, LogRecord)>, query: LogQuery) -> Vec<LogRecord> {
+    // More verbose levels compare greater
+    let mut selected: Vec<LogRecord> = records
+        .iter()
+        .rev()
+        .filter(|(level, _)| *level <= query.min_level)
+        .take(query.limit)
+        .map(|(_, record)| record.clone())
+        .collect();
+    selected.reverse();
+    selected
+}
+
+/// The buffer [`layer`] writes to and diagnostic reports read from
+pub fn global() -> Arc<LogBuffer> {
+    static GLOBAL: OnceLock<Arc<LogBuffer>> = OnceLock::new();
+    GLOBAL
+        .get_or_init(|| Arc::new(LogBuffer::new(DEFAULT_LOG_CAPACITY)))
+        .clone()
+}
+
+/// A layer copying events into the global buffer
+pub fn layer() -> LogBufferLayer {
+    LogBufferLayer::new(global())
+}
+
+/// Most verbose level worth buffering, from `BEVY_MCP_LOG_BUFFER_LEVEL`
+///
+/// Defaults to INFO; buffering DEBUG or TRACE makes every such event in
+/// every dependency do work.
+pub fn capture_level() -> LevelFilter {
+    std::env::var("BEVY_MCP_LOG_BUFFER_LEVEL")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::INFO)
+}
+
+/// Copies every event it sees into a [`LogBuffer`]
+#[derive(Debug, Clone)]
+pub struct LogBufferLayer {
+    buffer: Arc<LogBuffer>,
+}
+
+impl LogBufferLayer {
+    pub fn new(buffer: Arc<LogBuffer>) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBufferLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let mut message = visitor.message;
+        message.push_str(&visitor.fields);
+        self.buffer.push(
+            *metadata.level(),
+            LogRecord {
+                timestamp: Utc::now(),
+                level: metadata.level().to_string(),
+                target: metadata.target().to_string(),
+                message,
+            },
+        );
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_layer_keeps_recent_events() {
+        let buffer = Arc::new(LogBuffer::new(3));
+        let subscriber =
+            tracing_subscriber::registry().with(LogBufferLayer::new(Arc::clone(&buffer)));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("connecting");
+            tracing::info!(port = 15702, "connected");
+            tracing::warn!("slow frame");
+            tracing::error!(entity = 42, "component missing");
+        });
+
+        // The first event was dropped to stay within capacity
+        assert_eq!(buffer.len(), 3);
+        let all = buffer.recent(LogQuery {
+            min_level: Level::TRACE,
+            limit: 10,
+        });
+        assert_eq!(all[0].message, "connected port=15702");
+        assert_eq!(all[2].level, "ERROR");
+
+        let warnings = buffer.recent(LogQuery {
+            min_level: Level::WARN,
+            limit: 1,
+        });
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "component missing entity=42");
+        assert!(warnings[0]
+            .to_string()
+            .ends_with("ERROR bevy_debugger_mcp::log_buffer::tests: component missing entity=42"));
+    }
+}
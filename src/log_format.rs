@@ -0,0 +1,166 @@
+/*
+ * Bevy Debugger MCP Server - Log Output Format
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Human-readable or JSON log lines
+//!
+//! JSON output writes one object per line with the event's fields at the top
+//! level next to `timestamp`, `level`, `target` and `message`, and the
+//! innermost span under `span`. Events worth ingesting use these field names:
+//!
+//! | Field | Meaning |
+//! |-------|---------|
+//! | `tool` | MCP tool name |
+//! | `session` | Authenticated session id |
+//! | `user` | Authenticated user |
+//! | `status` | `ok` or `error` |
+//! | `duration_ms` | How long the operation took |
+//! | `query` | Observe query text |
+//! | `entity_count` | Entities an observe query returned |
+
+use std::str::FromStr;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::error::{Error, Result};
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(Error::Config(format!(
+                "Unknown log format '{other}' (expected text or json)"
+            ))),
+        }
+    }
+}
+
+impl LogFormat {
+    /// From `--log-format <format>` on the command line, else `BEVY_MCP_LOG_FORMAT`
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if let Some(format) = arg.strip_prefix("--log-format=") {
+                return format.parse();
+            }
+            if arg == "--log-format" {
+                return args
+                    .next()
+                    .ok_or_else(|| Error::Config("--log-format needs a value".to_string()))?
+                    .parse();
+            }
+        }
+        match std::env::var("BEVY_MCP_LOG_FORMAT") {
+            Ok(format) => format.parse(),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// A layer writing log lines in this format to `writer`
+    ///
+    /// `ansi` only affects text output.
+    pub fn layer<S, W>(self, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+        W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+    {
+        match self {
+            Self::Text => tracing_subscriber::fmt::layer()
+                .with_writer(writer)
+                .with_ansi(ansi)
+                .boxed(),
+            Self::Json => tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_writer(writer)
+                .boxed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::prelude::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_have_stable_fields() {
+        let args = vec![
+            "bevy-debugger-mcp".to_string(),
+            "--log-format=json".to_string(),
+        ];
+        let format = LogFormat::from_args(&args).unwrap();
+        assert_eq!(format, LogFormat::Json);
+        assert!("yaml".parse::<LogFormat>().is_err());
+
+        let buffer = SharedBuffer::default();
+        let writer = buffer.clone();
+        let subscriber =
+            tracing_subscriber::registry().with(format.layer(move || writer.clone(), false));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("mcp.tool_call", tool = "observe").in_scope(|| {
+                tracing::info!(
+                    tool = "observe",
+                    status = "ok",
+                    duration_ms = 12u64,
+                    "Tool call completed"
+                );
+            });
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["message"], "Tool call completed");
+        assert_eq!(line["level"], "INFO");
+        assert_eq!(line["tool"], "observe");
+        assert_eq!(line["status"], "ok");
+        assert_eq!(line["duration_ms"], 12);
+        assert_eq!(line["span"]["name"], "mcp.tool_call");
+        assert!(line["timestamp"].is_string());
+    }
+}
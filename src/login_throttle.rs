@@ -0,0 +1,189 @@
+/*
+ * Bevy Debugger MCP Server - Per-IP Login Throttling
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// Longest delay before a login attempt from a failing address is answered
+pub const MAX_FAILURE_DELAY: Duration = Duration::from_secs(10);
+
+/// Failed logins from one source address, across every username tried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpFailures {
+    pub count: u32,
+    pub first_failure: DateTime<Utc>,
+    pub last_failure: DateTime<Utc>,
+    pub banned_until: Option<DateTime<Utc>>,
+}
+
+/// Slows down and then bans addresses that keep failing to log in
+///
+/// Account lockout alone lets an attacker try one password against many
+/// usernames. Here every failure from an address doubles the delay before
+/// its next attempt is checked, and `max_failures` failures ban the address
+/// for `ban_duration`. Failures are forgotten after `ban_duration` without one.
+#[derive(Debug, Clone)]
+pub struct LoginThrottle {
+    max_failures: u32,
+    ban_duration: chrono::Duration,
+    base_delay: Duration,
+    failures: Arc<DashMap<IpAddr, IpFailures>>,
+}
+
+impl LoginThrottle {
+    pub fn new(max_failures: u32, ban_minutes: u64, base_delay: Duration) -> Self {
+        Self {
+            max_failures,
+            ban_duration: chrono::Duration::minutes(ban_minutes as i64),
+            base_delay,
+            failures: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Delay to apply before checking a login from `ip`, or when its ban ends
+    pub fn check(&self, ip: IpAddr, now: DateTime<Utc>) -> Result<Duration, DateTime<Utc>> {
+        let failures = match self.failures.get(&ip) {
+            Some(failures) if !self.is_stale(&failures, now) => failures,
+            _ => return Ok(Duration::ZERO),
+        };
+        if let Some(until) = failures.banned_until.filter(|until| now < *until) {
+            return Err(until);
+        }
+        let doublings = failures.count.saturating_sub(1).min(16);
+        Ok(self
+            .base_delay
+            .saturating_mul(1 << doublings)
+            .min(MAX_FAILURE_DELAY))
+    }
+
+    /// Count a failure from `ip`, returning the ban end if this failure started a ban
+    pub fn record_failure(&self, ip: IpAddr, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut failures = self.failures.entry(ip).or_insert_with(|| IpFailures {
+            count: 0,
+            first_failure: now,
+            last_failure: now,
+            banned_until: None,
+        });
+        if self.is_stale(&failures, now) {
+            *failures = IpFailures {
+                count: 0,
+                first_failure: now,
+                last_failure: now,
+                banned_until: None,
+            };
+        }
+        failures.count += 1;
+        failures.last_failure = now;
+        let already_banned = matches!(failures.banned_until, Some(until) if now < until);
+        if self.max_failures > 0 && failures.count >= self.max_failures && !already_banned {
+            let until = now + self.ban_duration;
+            failures.banned_until = Some(until);
+            return Some(until);
+        }
+        None
+    }
+
+    /// A successful login clears the address's failures
+    pub fn record_success(&self, ip: IpAddr) {
+        self.failures.remove(&ip);
+    }
+
+    /// Addresses currently banned, with when each ban ends
+    pub fn banned(&self, now: DateTime<Utc>) -> Vec<(IpAddr, DateTime<Utc>)> {
+        self.failures
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .banned_until
+                    .filter(|until| now < *until)
+                    .map(|until| (*entry.key(), until))
+            })
+            .collect()
+    }
+
+    /// Forget addresses with no recent failures and no active ban
+    pub fn prune(&self, now: DateTime<Utc>) {
+        self.failures
+            .retain(|_, failures| !self.is_stale(failures, now));
+    }
+
+    fn is_stale(&self, failures: &IpFailures, now: DateTime<Utc>) -> bool {
+        let banned = matches!(failures.banned_until, Some(until) if now < until);
+        !banned && now - failures.last_failure >= self.ban_duration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_progressive_delay_then_ban() {
+        let throttle = LoginThrottle::new(4, 30, Duration::from_millis(100));
+        let attacker = ip("203.0.113.7");
+        let now = Utc::now();
+
+        assert_eq!(throttle.check(attacker, now), Ok(Duration::ZERO));
+        assert_eq!(throttle.record_failure(attacker, now), None);
+        assert_eq!(
+            throttle.check(attacker, now),
+            Ok(Duration::from_millis(100))
+        );
+        throttle.record_failure(attacker, now);
+        throttle.record_failure(attacker, now);
+        assert_eq!(
+            throttle.check(attacker, now),
+            Ok(Duration::from_millis(400))
+        );
+
+        let until = throttle.record_failure(attacker, now).unwrap();
+        assert_eq!(until, now + chrono::Duration::minutes(30));
+        assert_eq!(throttle.check(attacker, now), Err(until));
+        assert_eq!(throttle.banned(now), vec![(attacker, until)]);
+
+        // Other addresses are unaffected and the ban lifts on its own
+        assert_eq!(throttle.check(ip("198.51.100.2"), now), Ok(Duration::ZERO));
+        let later = until + chrono::Duration::minutes(31);
+        assert_eq!(throttle.check(attacker, later), Ok(Duration::ZERO));
+        throttle.prune(later);
+        assert!(throttle.banned(later).is_empty());
+    }
+
+    #[test]
+    fn test_delay_is_capped_and_success_resets() {
+        let throttle = LoginThrottle::new(0, 30, Duration::from_secs(1));
+        let client = ip("10.0.0.5");
+        let now = Utc::now();
+        for _ in 0..40 {
+            assert_eq!(throttle.record_failure(client, now), None);
+        }
+        assert_eq!(throttle.check(client, now), Ok(MAX_FAILURE_DELAY));
+
+        throttle.record_success(client);
+        assert_eq!(throttle.check(client, now), Ok(Duration::ZERO));
+    }
+}
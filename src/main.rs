@@ -19,7 +19,9 @@
 use std::sync::Arc;
 use tokio::signal;
 use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tracing::info;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 use is_terminal::IsTerminal;
 
 // Modules are defined in lib.rs, no need to redeclare them here
@@ -27,7 +29,9 @@ use is_terminal::IsTerminal;
 use bevy_debugger_mcp::brp_client::BrpClient;
 use bevy_debugger_mcp::config::Config;
 use bevy_debugger_mcp::error::Result;
-use bevy_debugger_mcp::{mcp_server, mcp_server_v2};
+use bevy_debugger_mcp::health_endpoints::HealthProbe;
+use bevy_debugger_mcp::log_format::LogFormat;
+use bevy_debugger_mcp::{alerting, crash_report, health_endpoints, log_buffer, mcp_server_v2, otel_export};
 
 #[cfg(feature = "observability")]
 use bevy_debugger_mcp::observability::ObservabilityService;
@@ -57,6 +61,9 @@ async fn main() -> Result<()> {
         // Ensure logs are flushed
         std::io::Write::flush(&mut std::io::stderr()).unwrap_or(());
     }));
+    // Write a crash bundle before the message above is printed
+    let crash_dir = crash_report::crash_dir();
+    crash_report::install(crash_dir.clone());
     let args: Vec<String> = std::env::args().collect();
     
     // Check for help flag
@@ -65,13 +72,30 @@ async fn main() -> Result<()> {
         println!("\nUsage: {} [OPTIONS]", args[0]);
         println!("\nOptions:");
         println!("  --stdio              Run in stdio mode (default for Claude Code)");
-        println!("  --tcp, --server      Run as TCP server on port {}", Config::from_env().unwrap_or_default().mcp_port);
+        println!("  --tcp, --server      Run as mutual TLS server on port {} (requires the mtls feature)", Config::from_env().unwrap_or_default().mcp_port);
+        println!("  --log-format FORMAT  Log as text or json (default: text)");
         println!("  --help, -h           Show this help message");
         println!("\nEnvironment variables:");
         println!("  BEVY_BRP_HOST        Bevy Remote Protocol host (default: localhost)");
         println!("  BEVY_BRP_PORT        Bevy Remote Protocol port (default: 15702)");
         println!("  MCP_PORT             MCP server port for TCP mode (default: 3001)");
+        println!("  BEVY_MCP_TLS_CERT    Server certificate for TCP mode, with BEVY_MCP_TLS_KEY and BEVY_MCP_TLS_CLIENT_CA");
+        println!("  BEVY_MCP_IP_ALLOWLIST  Only accept clients from these addresses/CIDR ranges");
+        println!("  BEVY_MCP_IP_DENYLIST   Reject clients from these addresses/CIDR ranges");
+        println!("  OTEL_EXPORTER_OTLP_ENDPOINT  Export traces to this OTLP/gRPC collector");
+        println!("  OTEL_TRACES_SAMPLER_ARG      Fraction of traces to export (default: 1.0)");
         println!("  RUST_LOG             Logging level (default: info)");
+        println!("  BEVY_MCP_LOG_FORMAT  Log as text or json, unless --log-format is given");
+        println!("  BEVY_MCP_LOG_BUFFER_LEVEL  Most verbose level kept for diagnostic reports (default: info)");
+        println!("  BEVY_MCP_CRASH_DIR   Where crash reports are written (default: ./crashes)");
+        println!("  BEVY_MCP_FLIGHT_RECORDER_MINUTES  How long flight_recorder keeps events (default: 10)");
+        println!("  BEVY_MCP_HEALTH_ADDR  Serve /healthz and /readyz on this address (e.g. 127.0.0.1:8081)");
+        println!("  BEVY_MCP_ALERT_RULES  JSON file of alert rules to evaluate");
+        println!("  BEVY_MCP_SCHEDULES   JSON file of workflows to run on a schedule");
+        println!("  BEVY_MCP_DLQ_SQLITE  Keep the dead letter queue in this SQLite database (requires the sqlite-dlq feature)");
+        println!("  BEVY_MCP_SCHEMA_DIR  Where schema_history keeps component schemas per build (default: ./schemas)");
+        println!("  BEVY_MCP_SCRIPTS_DIR  Where workflow_script finds .rhai scripts (default: ./.bevy_debugger/scripts, requires the scripting feature)");
+        println!("  BEVY_MCP_PATTERNS_FILE  Where learned debugging patterns are saved (default: ./.bevy_debugger/patterns.json, empty to disable)");
         return Ok(());
     }
     
@@ -79,21 +103,26 @@ async fn main() -> Result<()> {
     let is_stdio_mode = args.iter().any(|arg| arg == "--stdio") || 
                         (!args.iter().any(|arg| arg == "--tcp" || arg == "--server") && !std::io::stdout().is_terminal());
     
+    let config = Config::from_env()?;
+    let log_format = LogFormat::from_args(&args)?;
+
     // Initialize tracing to stderr when in stdio mode (stdout is reserved for MCP protocol)
     // This prevents log output from contaminating the JSON-RPC stream
-    if is_stdio_mode {
-        tracing_subscriber::fmt()
-            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-            .with_writer(std::io::stderr)
-            .with_ansi(false)  // Disable ANSI color codes in stdio mode
-            .init();
+    let log_layer = if is_stdio_mode {
+        // Disable ANSI color codes in stdio mode
+        log_format.layer(std::io::stderr, false)
     } else {
-        tracing_subscriber::fmt()
-            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-            .init();
-    }
+        log_format.layer(std::io::stdout, true)
+    };
+    // RUST_LOG only filters log output; spans are exported whenever OTLP is configured
+    tracing_subscriber::registry()
+        .with(log_layer.with_filter(tracing_subscriber::EnvFilter::from_default_env()))
+        .with(otel_export::layer(&config.observability)?.with_filter(LevelFilter::INFO))
+        // Recent events are kept in memory for diagnostic reports
+        .with(log_buffer::layer().with_filter(log_buffer::capture_level()))
+        .init();
 
-    let config = Config::from_env()?;
+    crash_report::announce_previous(&crash_dir);
 
     // Check if we should run in stdio mode (for Claude Code) or TCP mode
     let use_tcp = args.iter().any(|arg| arg == "--tcp" || arg == "--server");
@@ -105,7 +134,7 @@ async fn main() -> Result<()> {
             .unwrap_or(false)
     );
 
-    if use_stdio {
+    let result = if use_stdio {
         info!("Starting Bevy Debugger MCP Server in stdio mode for Claude Code");
         run_stdio_mode(config).await
     } else {
@@ -114,7 +143,11 @@ async fn main() -> Result<()> {
             config.mcp_port
         );
         run_tcp_mode(config).await
-    }
+    };
+
+    // Export spans still waiting in the batch
+    otel_export::shutdown();
+    result
 }
 
 async fn run_stdio_mode(config: Config) -> Result<()> {
@@ -134,10 +167,32 @@ async fn run_stdio_mode(config: Config) -> Result<()> {
         None
     };
     
-    let server = mcp_server_v2::McpServerV2::new(config, brp_client)?;
+    start_health_endpoints(HealthProbe::new(brp_client.clone())).await?;
+    start_alerting(HealthProbe::new(brp_client.clone()))?;
+
+    let server = mcp_server_v2::McpServerV2::new(config, brp_client).await?;
     server.run_stdio().await
 }
 
+/// Serve `/healthz` and `/readyz` when `BEVY_MCP_HEALTH_ADDR` is set
+async fn start_health_endpoints(probe: HealthProbe) -> Result<()> {
+    if let Ok(addr) = std::env::var("BEVY_MCP_HEALTH_ADDR") {
+        health_endpoints::spawn(&addr, probe).await?;
+    }
+    Ok(())
+}
+
+/// Load alert rules from `BEVY_MCP_ALERT_RULES` and start evaluating them
+fn start_alerting(probe: HealthProbe) -> Result<()> {
+    let manager = alerting::global();
+    let count = manager.load_rules_from_env()?;
+    if count > 0 {
+        info!("Loaded {} alert rules", count);
+    }
+    alerting::spawn_evaluator(manager, probe);
+    Ok(())
+}
+
 async fn run_tcp_mode(config: Config) -> Result<()> {
     let brp_client = Arc::new(RwLock::new(BrpClient::new(&config)));
     {
@@ -172,32 +227,21 @@ async fn run_tcp_mode(config: Config) -> Result<()> {
         None
     };
     
-    let mcp_server = mcp_server::McpServer::new(config.clone(), brp_client);
-    
-    // Start TCP server
-    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", config.mcp_port))
-        .await
-        .map_err(|e| bevy_debugger_mcp::error::Error::Connection(format!("Failed to bind TCP: {}", e)))?;
-    
-    info!("MCP server listening on 127.0.0.1:{}", config.mcp_port);
-    
-    let server_handle = tokio::spawn(async move {
-        if let Err(e) = mcp_server.run(listener).await {
-            error!("MCP Server error: {}", e);
-        }
-    });
+    start_health_endpoints(HealthProbe::new(brp_client.clone())).await?;
+    start_alerting(HealthProbe::new(brp_client.clone()))?;
+
+    // TCP clients are only served over mutual TLS, through the same authorized tools as stdio
+    let server = mcp_server_v2::McpServerV2::new(config, brp_client).await?;
 
     tokio::select! {
-        _ = server_handle => {
-            warn!("MCP Server task completed");
-        }
+        result = server.run_tcp() => result?,
         _ = signal::ctrl_c() => {
             info!("Received SIGINT, shutting down gracefully");
             
             #[cfg(feature = "observability")]
             if let Some(obs) = observability {
                 if let Err(e) = obs.shutdown().await {
-                    warn!("Error shutting down observability: {}", e);
+                    tracing::warn!("Error shutting down observability: {}", e);
                 }
             }
         }
@@ -1,22 +1,31 @@
 use serde_json::{json, Value};
+use std::path::Path;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 use crate::brp_client::BrpClient;
 use crate::brp_messages::DebugCommand;
-use crate::suggestion_engine::{SuggestionContext, SystemState};
+use crate::suggestion_engine::{SuggestionContext, SuggestionFeedback, SystemState};
 use crate::workflow_automation::UserPreferences;
 use crate::checkpoint::{CheckpointConfig, CheckpointManager};
 use crate::config::Config;
-use crate::dead_letter_queue::{DeadLetterConfig, DeadLetterQueue};
+use crate::dead_letter_queue::{
+    self, DeadLetterConfig, DeadLetterFilter, DeadLetterQueue, FailedOperation, RetryPolicy,
+};
+use crate::error_codes::{self, ErrorCategory};
+use crate::entity_watchdog::{EntityWatchdogService, WatchdogAlert, WatchdogConfig};
+use crate::alerting::{self, AlertRule};
+use crate::workflow_scheduler::{self, ScheduledWorkflow};
+use crate::flight_recorder::{self, FlightEventKind};
+use crate::metrics_store::{self, Aggregation};
+use crate::process_metrics;
 use crate::debug_command_processor::{
     DebugCommandRequest, DebugCommandRouter, 
     EntityInspectionProcessor, DebugMetrics,
 };
-use crate::visual_debug_overlay_processor::VisualDebugOverlayProcessor;
+use crate::visual_debug_overlay_processor::parse_capture_overlays;
 use crate::query_builder_processor::QueryBuilderProcessor;
 use crate::memory_profiler_processor::MemoryProfilerProcessor;
 use crate::session_processor::SessionProcessor;
@@ -25,18 +34,41 @@ use crate::performance_budget_processor::PerformanceBudgetProcessor;
 use crate::entity_inspector::EntityInspector;
 use crate::system_profiler::SystemProfiler;
 use crate::system_profiler_processor::SystemProfilerProcessor;
-use crate::diagnostics::{create_bug_report, DiagnosticCollector};
+use crate::diagnostics::DiagnosticCollector;
 use crate::error::{Error, ErrorContext, ErrorSeverity, Result};
-use crate::resource_manager::{ResourceConfig, ResourceManager};
-use crate::tool_orchestration::{ToolContext, ToolOrchestrator, ToolPipeline};
-use crate::tools::{anomaly, experiment, hypothesis, observe, orchestration, replay, stress};
-use crate::lazy_init::{LazyComponents, preload_critical_components};
-use crate::command_cache::{CommandCache, CacheConfig, CacheKey};
-use crate::response_pool::{ResponsePool, ResponsePoolConfig};
+use crate::log_buffer::LogQuery;
+use crate::recording_diff::{compare_recordings, AlignMode, RecordingDiffConfig};
+use crate::recording_format::read_frame_at;
+use crate::recording_system::{RecordingBuffer, RecordingPoint};
+use crate::session_archive::{
+    resolve_relative_path, ArchivedFile, SessionArchive, RECORDING_FILE_NAME, SESSIONS_DIR,
+};
+use crate::world_snapshot::{SnapshotFilter, WorldSnapshot, WORLD_SNAPSHOT_KEY};
+use crate::health_endpoints::HealthProbe;
+use crate::report_redaction::ReportRedactionPolicy;
+use crate::resource_manager::{with_tool_scope, ResourceConfig, ResourceManager, SamplingPolicy};
+use crate::pipeline_persistence::PipelinePersistence;
+use crate::pipeline_templates::{validate_pipeline_definition, PipelineTemplateStore};
+use crate::tool_orchestration::{ExecutionId, ToolContext, ToolOrchestrator, ToolPipeline};
+use crate::tools::{
+    anomaly, benchmark, experiment, hypothesis, mutate, observe, orchestration, perf_timeline, replay, schema_history, search_world, stress, types,
+};
+use crate::lazy_init::{LazyComponents, UsageProfile, preload_critical_components};
+use crate::command_cache::{coverage_tags, AdaptiveTtlConfig, CommandCache, CacheConfig, CacheKey, ENTITY_DATA_TAG};
+use crate::query_parser::NOT_FOUND_TTL;
+use crate::response_pool::{ResponsePool, ResponsePoolConfig, SharedResponse};
+use crate::memory_budget::{global_memory_budget, MemoryBudget, MemoryConsumer};
 use crate::profiling::{init_profiler, get_profiler, PerfMeasurement};
 use crate::{profile_block, profile_async_block};
 use crate::compile_opts::{CompileConfig, inline_hot_path, cold_path};
 
+/// Minimum wait after enabling overlays for a screenshot, so they are drawn
+const OVERLAY_CAPTURE_FRAME_MS: u64 = 50;
+
+/// Runs debugging tools by name
+///
+/// It does no authorization of its own: clients reach it only through
+/// [`SecureMcpTools`](crate::secure_mcp_tools::SecureMcpTools).
 pub struct McpServer {
     config: Config,
     brp_client: Arc<RwLock<BrpClient>>,
@@ -45,24 +77,54 @@ pub struct McpServer {
     dead_letter_queue: Arc<RwLock<DeadLetterQueue>>,
     diagnostic_collector: Arc<DiagnosticCollector>,
     checkpoint_manager: Arc<RwLock<CheckpointManager>>,
+    pipeline_persistence: PipelinePersistence,
+    pipeline_templates: PipelineTemplateStore,
+    entity_watchdog: Arc<EntityWatchdogService>,
     lazy_components: Arc<LazyComponents>,
     command_cache: Arc<CommandCache>,
     response_pool: Arc<ResponsePool>,
+    memory_budget: Arc<MemoryBudget>,
     debug_mode: bool,
 }
 
 impl McpServer {
     pub fn new(config: Config, brp_client: Arc<RwLock<BrpClient>>) -> Self {
-        let orchestrator = orchestration::create_orchestrator(Arc::clone(&brp_client));
-        let resource_manager = ResourceManager::new(ResourceConfig::default());
+        let mut orchestrator = orchestration::create_orchestrator(Arc::clone(&brp_client));
+        let resource_manager = ResourceManager::new(ResourceConfig {
+            sampling_policy: SamplingPolicy::from_env(),
+            ..ResourceConfig::default()
+        });
 
         // Initialize error recovery and diagnostic systems
-        let dead_letter_queue = DeadLetterQueue::new(DeadLetterConfig::default());
-        let diagnostic_collector = Arc::new(DiagnosticCollector::new(100)); // Keep 100 recent errors
-        let checkpoint_manager = CheckpointManager::new(CheckpointConfig::default());
+        let mut dead_letter_queue = DeadLetterQueue::new(DeadLetterConfig::default());
+        match dead_letter_queue::store_from_env() {
+            Ok(Some(store)) => dead_letter_queue = dead_letter_queue.with_store(store),
+            Ok(None) => {}
+            Err(e) => warn!("Dead letter queue kept in memory only: {}", e),
+        }
+        // Keep 100 recent errors; exports are redacted for sharing outside the team
+        let diagnostic_collector = Arc::new(
+            DiagnosticCollector::new(100).with_redaction(ReportRedactionPolicy::from_env()),
+        );
+        let checkpoint_manager = Arc::new(RwLock::new(CheckpointManager::new(
+            CheckpointConfig::default(),
+        )));
+
+        // Checkpoint pipeline progress so long pipelines survive restarts
+        let pipeline_persistence = PipelinePersistence::new(Arc::clone(&checkpoint_manager));
+        orchestrator.set_pipeline_persistence(pipeline_persistence.clone());
+        let pipeline_templates = orchestrator.pipeline_templates();
+
+        let entity_watchdog = Arc::new(EntityWatchdogService::new(
+            Arc::clone(&brp_client),
+            WatchdogConfig::default(),
+        ));
 
         // Initialize lazy components manager for optimized startup
-        let lazy_components = Arc::new(LazyComponents::new(Arc::clone(&brp_client)));
+        let lazy_components = Arc::new(
+            LazyComponents::new(Arc::clone(&brp_client))
+                .with_usage_profile(UsageProfile::default_path()),
+        );
 
         // Initialize command result cache for performance optimization
         let cache_config = CacheConfig {
@@ -70,8 +132,15 @@ impl McpServer {
             default_ttl: Duration::from_secs(300), // 5 minutes
             cleanup_interval: Duration::from_secs(60), // 1 minute
             max_response_size: 512 * 1024, // 512KB per response
+            adaptive_ttl: Some(AdaptiveTtlConfig::default()),
         };
-        let command_cache = Arc::new(CommandCache::new(cache_config));
+        // BEVY_MCP_CACHE_PATH keeps cached results across restarts
+        let command_cache = Arc::new(match std::env::var("BEVY_MCP_CACHE_PATH") {
+            Ok(path) => CommandCache::with_persistence(cache_config, path),
+            Err(_) => CommandCache::new(cache_config),
+        });
+        spawn_cache_invalidation(Arc::clone(&brp_client), Arc::clone(&command_cache));
+        observe::spawn_query_cache_invalidation(Arc::clone(&brp_client));
 
         // Initialize response pool for memory optimization
         let response_pool_config = ResponsePoolConfig {
@@ -95,12 +164,45 @@ impl McpServer {
             info!("Debug mode enabled - verbose logging and diagnostics active");
         }
 
-        // Optionally preload critical components based on feature flags
+        // Preload critical components based on feature flags and past usage
         let lazy_components_for_preload = Arc::clone(&lazy_components);
         tokio::spawn(async move {
             if let Err(e) = preload_critical_components(&lazy_components_for_preload).await {
                 error!("Failed to preload critical components: {}", e);
             }
+            // Keep the usage profile current so the next start preloads what this one used
+            let mut interval = tokio::time::interval(Duration::from_secs(300));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = lazy_components_for_preload.save_usage_profile() {
+                    warn!("Failed to save component usage profile: {}", e);
+                }
+            }
+        });
+
+        // Keep a memory series for metrics_query
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(metrics_store::MEMORY_SAMPLE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let rss_bytes = process_metrics::sample().rss_bytes;
+                metrics_store::record("memory_rss_bytes", rss_bytes as f64);
+            }
+        });
+
+        // Count caches, recordings and failed operations against one memory ceiling
+        let memory_budget = global_memory_budget();
+        let budget_consumers: Vec<Arc<dyn MemoryConsumer>> = vec![
+            Arc::clone(&command_cache) as Arc<dyn MemoryConsumer>,
+            dead_letter_queue.memory_consumer(),
+            replay::recording_memory_consumer(),
+        ];
+        let budget = Arc::clone(&memory_budget);
+        tokio::spawn(async move {
+            for consumer in budget_consumers {
+                budget.register(consumer).await;
+            }
         });
 
         // Initialize performance profiler
@@ -115,14 +217,44 @@ impl McpServer {
             resource_manager: Arc::new(RwLock::new(resource_manager)),
             dead_letter_queue: Arc::new(RwLock::new(dead_letter_queue)),
             diagnostic_collector,
-            checkpoint_manager: Arc::new(RwLock::new(checkpoint_manager)),
+            checkpoint_manager,
+            pipeline_persistence,
+            pipeline_templates,
+            entity_watchdog,
             lazy_components,
             command_cache,
             response_pool,
+            memory_budget,
             debug_mode,
         }
     }
 
+    pub fn brp_client(&self) -> Arc<RwLock<BrpClient>> {
+        Arc::clone(&self.brp_client)
+    }
+
+    /// Alerts raised by the entity watchdog
+    pub fn subscribe_watchdog_alerts(&self) -> tokio::sync::broadcast::Receiver<WatchdogAlert> {
+        self.entity_watchdog.subscribe()
+    }
+
+    /// Probe backing the `/healthz` and `/readyz` endpoints
+    pub fn health_probe(&self) -> HealthProbe {
+        HealthProbe::new(Arc::clone(&self.brp_client))
+            .with_resource_manager(Arc::clone(&self.resource_manager))
+    }
+
+    /// Run scheduled workflows through this server's tools
+    ///
+    /// Only the first server started runs them.
+    pub fn start_workflow_scheduler(&self) {
+        let server = self.clone();
+        workflow_scheduler::global().spawn_runner(move |tool, arguments| {
+            let server = server.clone();
+            async move { server.handle_tool_call(&tool, arguments).await }
+        });
+    }
+
     pub async fn start(&self) -> Result<()> {
         // Start all systems
         {
@@ -133,13 +265,57 @@ impl McpServer {
         {
             let mut dlq = self.dead_letter_queue.write().await;
             dlq.start().await?;
+            dlq.load_from_disk().await?;
+            let policies = dlq.load_retry_policies_from_env().await?;
+            if policies > 0 {
+                info!("Loaded {} dead letter queue retry policies", policies);
+            }
         }
 
+        // Re-run queued failures through the normal tool path as their policies allow
+        let server = self.clone();
+        dead_letter_queue::spawn_retrier(Arc::clone(&self.dead_letter_queue), move |operation| {
+            let server = server.clone();
+            async move {
+                match server
+                    .handle_tool_call(&operation.operation, operation.request_data)
+                    .await
+                {
+                    Ok(response) => match response.get("error") {
+                        Some(error) => Err(error.to_string()),
+                        None => Ok(()),
+                    },
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+        });
+
         {
             let mut cm = self.checkpoint_manager.write().await;
             cm.start().await?;
         }
 
+        perf_timeline::spawn_sampling(Arc::clone(&self.brp_client));
+
+        // Prefetch common queries each time the game connects
+        let server = self.clone();
+        observe::spawn_cache_warming(Arc::clone(&self.brp_client), move |arguments| {
+            let server = server.clone();
+            async move { server.handle_tool_call("observe", arguments).await }
+        });
+
+        // Load user-defined pipeline templates
+        let template_dir = std::env::var("PIPELINE_TEMPLATE_DIR")
+            .unwrap_or_else(|_| "./pipeline_templates".to_string());
+        let known_tools = self.orchestrator.read().await.tool_names();
+        if let Err(e) = self
+            .pipeline_templates
+            .load_directory(std::path::Path::new(&template_dir), &known_tools)
+            .await
+        {
+            warn!("Failed to load pipeline templates from {}: {}", template_dir, e);
+        }
+
         info!("MCP Server started with error recovery and diagnostic systems");
         if self.debug_mode {
             info!("Debug mode active - enhanced logging enabled");
@@ -147,50 +323,20 @@ impl McpServer {
         Ok(())
     }
 
-    pub async fn run(&self, listener: TcpListener) -> Result<()> {
-        loop {
-            match listener.accept().await {
-                Ok((stream, addr)) => {
-                    info!("New MCP connection from: {}", addr);
-                    let server = self.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = server.handle_connection(stream).await {
-                            error!("Error handling MCP connection: {}", e);
-                        }
-                    });
-                }
-                Err(e) => {
-                    error!("Failed to accept connection: {}", e);
-                }
-            }
-        }
-    }
-
-    async fn handle_connection(&self, _stream: TcpStream) -> Result<()> {
-        debug!("Handling MCP connection - performing handshake");
-
-        let capabilities = json!({
-            "capabilities": {
-                "tools": {
-                    "listChanged": false
-                }
-            },
-            "serverInfo": {
-                "name": "bevy-debugger-mcp",
-                "version": "0.1.0"
-            }
-        });
-
-        debug!(
-            "MCP handshake completed with capabilities: {}",
-            capabilities
-        );
-
-        Ok(())
+    pub async fn handle_tool_call(&self, tool_name: &str, arguments: Value) -> Result<Value> {
+        self.handle_tool_call_shared(tool_name, arguments)
+            .await
+            .map(SharedResponse::into_value)
     }
 
-    pub async fn handle_tool_call(&self, tool_name: &str, arguments: Value) -> Result<Value> {
-        profile_async_block!(format!("handle_tool_call_{}", tool_name), async {
+    /// Handle a tool call, sharing the response with the cache instead of copying it
+    ///
+    /// Transports should send `to_bytes()` of the result: cached responses
+    /// were already serialized when they were stored.
+    #[instrument(name = "mcp.tool_call", skip(self, arguments), fields(mcp.tool = tool_name), err)]
+    pub async fn handle_tool_call_shared(&self, tool_name: &str, arguments: Value) -> Result<SharedResponse> {
+        let started = Instant::now();
+        let result = profile_async_block!(format!("handle_tool_call_{}", tool_name), async {
             debug!("Handling tool call: {} with args: {}", tool_name, arguments);
 
             // Try to get cached result first (for cacheable tools)
@@ -198,7 +344,7 @@ impl McpServer {
                 match CacheKey::new(tool_name, &arguments) {
                     Ok(key) => {
                         if let Some(cached_result) = profile_async_block!("cache_lookup", async {
-                            self.command_cache.get(&key).await
+                            self.command_cache.get_shared(&key).await
                         }) {
                             debug!("Returning cached result for tool: {}", tool_name);
                             return Ok(cached_result);
@@ -220,45 +366,76 @@ impl McpServer {
             // Use shared Arc reference for all tool handlers
             let brp_client_ref = Arc::clone(&self.brp_client);
 
-            let result: Result<serde_json::Value> = profile_async_block!(format!("tool_execution_{}", tool_name), async {
+            // Scoped so adaptive sampling can honour per-tool exemptions
+            let result: Result<serde_json::Value> = profile_async_block!(format!("tool_execution_{}", tool_name), with_tool_scope(tool_name, async {
                 match tool_name {
                     "observe" => observe::handle(arguments, brp_client_ref).await,
                     "experiment" => experiment::handle(arguments, Arc::clone(&brp_client_ref)).await,
+                    "mutate" => mutate::handle(arguments, Arc::clone(&brp_client_ref)).await,
+                    "types" => types::handle(arguments, Arc::clone(&brp_client_ref)).await,
+                    "schema_history" => schema_history::handle(arguments, Arc::clone(&brp_client_ref)).await,
+                    "search_world" => search_world::handle(arguments, Arc::clone(&brp_client_ref)).await,
                     "screenshot" => self.handle_screenshot(arguments).await,
                     "hypothesis" => hypothesis::handle(arguments, Arc::clone(&brp_client_ref)).await,
                     "stress" => stress::handle(arguments, Arc::clone(&brp_client_ref)).await,
                     "replay" => replay::handle(arguments, Arc::clone(&brp_client_ref)).await,
                     "anomaly" => anomaly::handle(arguments, Arc::clone(&brp_client_ref)).await,
+                    "entity_watchdog" => self.handle_entity_watchdog(arguments).await,
+                    "perf_timeline" => perf_timeline::handle(arguments).await,
+                    "benchmark" => benchmark::handle(arguments, Arc::clone(&brp_client_ref)).await,
                     "orchestrate" => self.handle_orchestration(arguments).await,
                     "pipeline" => self.handle_pipeline_execution(arguments).await,
                     "resource_metrics" => self.handle_resource_metrics(arguments).await,
                     "performance_dashboard" => self.handle_performance_dashboard(arguments).await,
                     "health_check" => self.handle_health_check(arguments).await,
+                    "sampling" => self.handle_sampling(arguments).await,
                     // New diagnostic and error recovery endpoints
                     "dead_letter_queue" => self.handle_dead_letter_queue(arguments).await,
                     "diagnostic_report" => self.handle_diagnostic_report(arguments).await,
                     "checkpoint" => self.handle_checkpoint(arguments).await,
                     "bug_report" => self.handle_bug_report(arguments).await,
+                    "flight_recorder" => self.handle_flight_recorder(arguments).await,
+                    "metrics_query" => self.handle_metrics_query(arguments).await,
+                    "alerts" => self.handle_alerts(arguments).await,
+                    "schedules" => self.handle_schedules(arguments).await,
+                    "list_error_codes" => self.handle_list_error_codes(arguments).await,
+                    "export_session" => self.handle_export_session(arguments).await,
+                    "import_session" => self.handle_import_session(arguments).await,
+                    "compare_recordings" => self.handle_compare_recordings(arguments).await,
+                    "state_at" => self.handle_state_at(arguments).await,
                     "debug" => self.handle_debug_command(arguments).await,
+                    "cache" => self.handle_cache(arguments).await,
                     // Machine learning and automation endpoints
                     "get_suggestions" => self.handle_get_suggestions(arguments).await,
                     "track_suggestion" => self.handle_track_suggestion(arguments).await,
+                    "suggestion_feedback" => self.handle_suggestion_feedback(arguments).await,
                     "get_patterns" => self.handle_get_patterns(arguments).await,
                     "execute_workflow" => self.handle_execute_workflow(arguments).await,
                     "approve_workflow" => self.handle_approve_workflow(arguments).await,
                     "get_workflows" => self.handle_get_workflows(arguments).await,
+                    "workflow_script" => self.handle_workflow_script(arguments).await,
                     // Hot reload endpoints
                     "hot_reload" => self.handle_hot_reload(arguments).await,
                     "get_model_versions" => self.handle_get_model_versions(arguments).await,
                     _ => Err(Error::Mcp(format!("Unknown tool: {tool_name}"))),
                 }
-            });
+            }));
+
+            let result = result.map(SharedResponse::new);
 
-            // Cache successful results for cacheable tools
+            // Cache successful results for cacheable tools; "not found" answers
+            // only briefly, since a spawn or registration can change them
             if let (Ok(ref response), Some(cache_key)) = (&result, cache_key) {
                 profile_async_block!("cache_store", async {
-                    let tags = self.get_cache_tags_for_tool(tool_name);
-                    if let Err(e) = self.command_cache.put(&cache_key, response.clone(), tags).await {
+                    let tags = self.cache_tags_for_response(tool_name, response.value());
+                    let stored = if observe::is_not_found(response.value()) {
+                        self.command_cache.put_with_ttl(&cache_key, response.clone(), NOT_FOUND_TTL, tags).await
+                    } else if response.value().get("error").is_some() {
+                        Ok(())
+                    } else {
+                        self.command_cache.put(&cache_key, response.clone(), tags).await
+                    };
+                    if let Err(e) = stored {
                         warn!("Failed to cache result for {}: {}", tool_name, e);
                     }
                 });
@@ -280,10 +457,54 @@ impl McpServer {
                         warn!("Tool call failed: {} - {}", tool_name, error);
                     }
                 });
+
+                // Park failures that may pass later, where a retry policy can pick them up
+                if error.is_transient() && !dead_letter_queue::is_retry() {
+                    let failed_operation = FailedOperation::new(
+                        tool_name,
+                        "mcp_server",
+                        0,
+                        ErrorContext::new(tool_name, "mcp_server").add_cause(&error.to_string()),
+                        args_for_error,
+                        &error.to_string(),
+                    );
+                    let dlq = self.dead_letter_queue.read().await;
+                    if let Err(e) = dlq.add_failed_operation(failed_operation).await {
+                        warn!("Failed to queue failed {} call: {}", tool_name, e);
+                    }
+                }
             }
 
             result
-        })
+        });
+
+        // Cache hits count too; they're what the client waited for
+        let failed = match &result {
+            Ok(response) => response.value().get("error").is_some(),
+            Err(_) => true,
+        };
+        let duration = started.elapsed();
+        self.resource_manager
+            .read()
+            .await
+            .record_tool_call(tool_name, duration, failed)
+            .await;
+        info!(
+            tool = tool_name,
+            status = if failed { "error" } else { "ok" },
+            duration_ms = duration.as_millis() as u64,
+            "Tool call completed"
+        );
+        flight_recorder::record(FlightEventKind::ToolCall {
+            tool: tool_name.to_string(),
+            status: flight_recorder::status(failed),
+            duration_ms: duration.as_millis() as u64,
+        });
+        metrics_store::record(
+            &format!("tool_latency_ms.{tool_name}"),
+            duration.as_micros() as f64 / 1000.0,
+        );
+        result
     }
 
     /// Handle orchestration tool calls
@@ -339,25 +560,70 @@ impl McpServer {
 
     /// Handle pipeline execution
     async fn handle_pipeline_execution(&self, arguments: Value) -> Result<Value> {
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("run");
+
+        match action {
+            "run" => {}
+            "list_running" => {
+                let executions = self.pipeline_persistence.list().await?;
+                return Ok(json!({
+                    "executions": executions,
+                    "total_count": executions.len()
+                }));
+            }
+            "resume" => {
+                let execution_id = Self::pipeline_execution_id(&arguments)?;
+                let mut orchestrator = self.orchestrator.write().await;
+                let result = orchestrator.resume_pipeline(execution_id).await?;
+                return Ok(json!({
+                    "pipeline_result": result
+                }));
+            }
+            "abort" => {
+                let execution_id = Self::pipeline_execution_id(&arguments)?;
+                let was_active = self.pipeline_persistence.abort(&execution_id).await?;
+                return Ok(json!({
+                    "aborted": true,
+                    "execution_id": execution_id,
+                    "was_active": was_active
+                }));
+            }
+            "list_templates" => {
+                let templates = self.pipeline_templates.list();
+                return Ok(json!({
+                    "templates": templates,
+                    "total_count": templates.len(),
+                    "load_errors": self.pipeline_templates.load_errors()
+                }));
+            }
+            "describe_template" => {
+                let name = arguments
+                    .get("template")
+                    .and_then(|t| t.as_str())
+                    .ok_or_else(|| Error::Validation("Missing 'template' field".to_string()))?;
+                return self.pipeline_templates.describe(name).ok_or_else(|| {
+                    Error::Validation(format!("Unknown pipeline template: {name}"))
+                });
+            }
+            _ => {
+                return Err(Error::Validation(format!(
+                    "Unknown pipeline action: {action}"
+                )))
+            }
+        }
+
         let context = ToolContext::new();
 
         // Check if this is a template pipeline or custom pipeline
         if let Some(template_name) = arguments.get("template").and_then(|t| t.as_str()) {
-            let mut orchestrator = self.orchestrator.write().await;
-
-            // Get pipeline template (this would need to be implemented in orchestrator)
-            let pipeline = match template_name {
-                "observe_experiment_replay" => {
-                    crate::tool_orchestration::WorkflowDSL::observe_experiment_replay()
-                }
-                "debug_performance" => crate::tool_orchestration::WorkflowDSL::debug_performance(),
-                _ => {
-                    return Err(Error::Validation(format!(
-                        "Unknown pipeline template: {template_name}"
-                    )))
-                }
-            };
+            let pipeline = self.pipeline_templates.get(template_name).ok_or_else(|| {
+                Error::Validation(format!("Unknown pipeline template: {template_name}"))
+            })?;
 
+            let mut orchestrator = self.orchestrator.write().await;
             let result = orchestrator.execute_pipeline(pipeline, context).await?;
 
             Ok(json!({
@@ -368,40 +634,8 @@ impl McpServer {
             let pipeline: ToolPipeline = serde_json::from_value(pipeline_data.clone())
                 .map_err(|e| Error::Validation(format!("Invalid pipeline format: {e}")))?;
 
-            // Validate pipeline constraints
-            if pipeline.steps.len() > 50 {
-                return Err(Error::Validation(
-                    "Pipeline too complex: maximum 50 steps allowed".to_string(),
-                ));
-            }
-
-            for step in &pipeline.steps {
-                if step.timeout.unwrap_or(Duration::from_secs(300)) > Duration::from_secs(600) {
-                    return Err(Error::Validation(format!(
-                        "Step '{}' timeout too long: maximum 10 minutes allowed",
-                        step.name
-                    )));
-                }
-
-                // Validate tool names against known tools
-                if ![
-                    "observe",
-                    "experiment",
-                    "hypothesis",
-                    "stress",
-                    "replay",
-                    "anomaly",
-                ]
-                .contains(&step.tool.as_str())
-                {
-                    return Err(Error::Validation(format!(
-                        "Unknown tool '{}' in step '{}'",
-                        step.tool, step.name
-                    )));
-                }
-            }
-
             let mut orchestrator = self.orchestrator.write().await;
+            validate_pipeline_definition(&pipeline, &orchestrator.tool_names())?;
             let result = orchestrator.execute_pipeline(pipeline, context).await?;
 
             Ok(json!({
@@ -414,13 +648,26 @@ impl McpServer {
         }
     }
 
+    /// Extract the 'execution_id' field for pipeline resume/abort actions
+    fn pipeline_execution_id(arguments: &Value) -> Result<ExecutionId> {
+        let id = arguments
+            .get("execution_id")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| Error::Validation("Missing 'execution_id' field".to_string()))?;
+
+        ExecutionId::parse(id)
+            .map_err(|_| Error::Validation(format!("Invalid execution_id: {id}")))
+    }
+
     /// Handle resource metrics requests
     async fn handle_resource_metrics(&self, _arguments: Value) -> Result<Value> {
         let resource_manager = self.resource_manager.read().await;
         let metrics = resource_manager.get_metrics().await;
 
-        serde_json::to_value(metrics)
-            .map_err(|e| Error::Validation(format!("Failed to serialize metrics: {e}")))
+        let mut response = serde_json::to_value(metrics)
+            .map_err(|e| Error::Validation(format!("Failed to serialize metrics: {e}")))?;
+        response["memory_budget"] = json!(self.memory_budget.report().await);
+        Ok(response)
     }
 
     /// Handle performance dashboard requests
@@ -431,6 +678,35 @@ impl McpServer {
         Ok(dashboard)
     }
 
+    /// Handle adaptive sampling requests: current rates per tool, or a policy update
+    async fn handle_sampling(&self, arguments: Value) -> Result<Value> {
+        let resource_manager = self.resource_manager.read().await;
+        let action = arguments.get("action").and_then(|a| a.as_str()).unwrap_or("status");
+
+        match action {
+            "status" => Ok(json!(resource_manager.sampling_report().await)),
+            "set_policy" => {
+                // Fields left out keep their current values
+                let mut policy = serde_json::to_value(resource_manager.sampling_report().await.policy)?;
+                let Some(updates) = arguments.get("policy").and_then(|p| p.as_object()) else {
+                    return Err(Error::Validation("Missing 'policy' object".to_string()));
+                };
+                for (field, value) in updates {
+                    policy[field] = value.clone();
+                }
+                let policy: SamplingPolicy = serde_json::from_value(policy)
+                    .map_err(|e| Error::Validation(format!("Invalid sampling policy: {e}")))?;
+                resource_manager.set_sampling_policy(policy).await?;
+                Ok(json!(resource_manager.sampling_report().await))
+            }
+            _ => Ok(json!({
+                "error": "Invalid action",
+                "message": format!("Unknown action: {}. Available actions: status, set_policy", action),
+                "available_actions": ["status", "set_policy"]
+            })),
+        }
+    }
+
     /// Handle health check requests
     async fn handle_health_check(&self, _arguments: Value) -> Result<Value> {
         let resource_manager = self.resource_manager.read().await;
@@ -464,13 +740,19 @@ impl McpServer {
                     "value_mb": metrics.memory_bytes / (1024 * 1024),
                     "threshold_mb": 100
                 },
+                "process": {
+                    "threads": metrics.thread_count,
+                    "open_file_descriptors": metrics.open_fds
+                },
                 "circuit_breaker": {
                     "status": if circuit_ok { "ok" } else { "error" },
                     "open": metrics.circuit_breaker_open
                 }
             },
             "uptime_seconds": metrics.timestamp.duration_since(UNIX_EPOCH)
-                .unwrap_or_default().as_secs()
+                .unwrap_or_default().as_secs(),
+            // Crash bundle from an earlier run, for attaching to an issue
+            "previous_crash": crate::crash_report::previous_crash().map(|p| p.display().to_string())
         }))
     }
 
@@ -522,6 +804,11 @@ impl McpServer {
             .and_then(|d| d.as_str())
             .map(|s| s.to_string());
 
+        let capture_overlays = match arguments.get("overlays") {
+            Some(overlays) => parse_capture_overlays(overlays)?,
+            None => Vec::new(),
+        };
+
         // Log the screenshot request for debugging
         if let Some(ref desc) = description {
             info!("Taking screenshot: {} -> {}", desc, path);
@@ -535,12 +822,51 @@ impl McpServer {
             tokio::time::sleep(tokio::time::Duration::from_millis(warmup_duration)).await;
         }
 
-        // Apply capture delay (for animation timing, etc.)
+        // Enable requested overlays for the capture, remembering what to restore
+        let overlay_capture = if capture_overlays.is_empty() {
+            None
+        } else {
+            let overlay_state = self
+                .lazy_components
+                .get_visual_overlay_processor()
+                .await
+                .get_state();
+            let snapshot = match overlay_state
+                .write()
+                .await
+                .apply_temporary_overlays(&capture_overlays)
+                .await
+            {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    warn!("Failed to enable overlays for screenshot: {}", e);
+                    return Ok(json!({
+                        "success": false,
+                        "error": "Overlay setup failed",
+                        "message": format!("Failed to enable overlays for screenshot: {e}")
+                    }));
+                }
+            };
+            Some((overlay_state, snapshot))
+        };
+
+        // Apply capture delay (for animation timing, etc.), waiting at least a
+        // frame when overlays were just enabled so they are drawn
+        let capture_delay = if overlay_capture.is_some() {
+            capture_delay.max(OVERLAY_CAPTURE_FRAME_MS)
+        } else {
+            capture_delay
+        };
         if capture_delay > 0 {
             debug!("Waiting {}ms capture delay", capture_delay);
             tokio::time::sleep(tokio::time::Duration::from_millis(capture_delay)).await;
         }
 
+        let annotations = match &overlay_capture {
+            Some((overlay_state, _)) => Some(overlay_state.read().await.active_overlay_annotations()),
+            None => None,
+        };
+
         // Send BRP screenshot request
         let request = crate::brp_messages::BrpRequest::Screenshot {
             path: Some(path.clone()),
@@ -550,8 +876,31 @@ impl McpServer {
             description: description.clone(),
         };
 
+        let result = self.send_screenshot_request(&request).await;
+
+        // Restore overlays whether or not the capture succeeded
+        if let Some((overlay_state, snapshot)) = overlay_capture {
+            if let Err(e) = overlay_state.write().await.restore_overlays(snapshot).await {
+                warn!("Failed to restore overlays after screenshot: {}", e);
+            }
+        }
+
+        let mut result = result?;
+        if let Some(annotations) = annotations {
+            if result["success"] == true {
+                annotate_screenshot(&mut result, annotations);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Send a screenshot request to the game and describe the outcome
+    async fn send_screenshot_request(
+        &self,
+        request: &crate::brp_messages::BrpRequest,
+    ) -> Result<Value> {
         let mut client = self.brp_client.write().await;
-        match client.send_request(&request).await {
+        match client.send_request(request).await {
             Ok(response) => match response {
                 crate::brp_messages::BrpResponse::Success(
                     boxed_result
@@ -600,6 +949,82 @@ impl McpServer {
         }
     }
 
+    /// Handle entity-count watchdog operations
+    async fn handle_entity_watchdog(&self, arguments: Value) -> Result<Value> {
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("status");
+
+        let watchdog = self.entity_watchdog.watchdog();
+        match action {
+            "status" => {
+                let running = self.entity_watchdog.is_running().await;
+                let wd = watchdog.read().await;
+                Ok(json!({
+                    "running": running,
+                    "config": wd.config(),
+                    "last_counts": wd.last_counts(),
+                    "checks": wd.checks(),
+                    "recent_alerts": wd.alerts(10)
+                }))
+            }
+            "configure" => {
+                let config: WatchdogConfig = serde_json::from_value(
+                    arguments
+                        .get("config")
+                        .cloned()
+                        .ok_or_else(|| Error::Validation("Missing 'config' field".to_string()))?,
+                )
+                .map_err(|e| Error::Validation(format!("Invalid watchdog config: {e}")))?;
+
+                watchdog.write().await.set_config(config.clone())?;
+                // Restart so a new check interval takes effect
+                if self.entity_watchdog.is_running().await {
+                    self.entity_watchdog.start().await?;
+                }
+                Ok(json!({
+                    "message": "Watchdog configuration updated",
+                    "config": config
+                }))
+            }
+            "start" => {
+                self.entity_watchdog.start().await?;
+                Ok(json!({ "running": true }))
+            }
+            "stop" => {
+                self.entity_watchdog.stop().await;
+                Ok(json!({ "running": false }))
+            }
+            "check" => {
+                let alerts = self.entity_watchdog.check_now().await?;
+                let wd = watchdog.read().await;
+                Ok(json!({
+                    "counts": wd.last_counts(),
+                    "alerts": alerts
+                }))
+            }
+            "alerts" => {
+                let limit = arguments
+                    .get("limit")
+                    .and_then(|l| l.as_u64())
+                    .unwrap_or(50) as usize;
+                let alerts = watchdog.read().await.alerts(limit);
+                Ok(json!({
+                    "alerts": alerts,
+                    "count": alerts.len()
+                }))
+            }
+            "clear" => {
+                watchdog.write().await.clear_alerts();
+                Ok(json!({ "message": "Watchdog alerts cleared" }))
+            }
+            _ => Err(Error::Validation(format!(
+                "Unknown entity watchdog action: {action}"
+            ))),
+        }
+    }
+
     /// Handle dead letter queue operations
     async fn handle_dead_letter_queue(&self, arguments: Value) -> Result<Value> {
         let action = arguments
@@ -609,8 +1034,15 @@ impl McpServer {
 
         match action {
             "list" => {
+                // Optional component, operation, since, until and limit
+                let filter: DeadLetterFilter = if arguments.is_object() {
+                    serde_json::from_value(arguments.clone())
+                        .map_err(|e| Error::Validation(format!("Invalid filter: {e}")))?
+                } else {
+                    DeadLetterFilter::default()
+                };
                 let dlq = self.dead_letter_queue.read().await;
-                let operations = dlq.get_failed_operations().await;
+                let operations = dlq.query(filter).await?;
                 Ok(json!({
                     "failed_operations": operations,
                     "total_count": operations.len()
@@ -635,12 +1067,68 @@ impl McpServer {
                     "operation": removed
                 }))
             }
+            "retry_policies" => {
+                let dlq = self.dead_letter_queue.read().await;
+                Ok(json!({ "retry_policies": dlq.retry_policies().await }))
+            }
+            "set_retry_policy" => {
+                let operation = arguments
+                    .get("operation")
+                    .and_then(|o| o.as_str())
+                    .ok_or_else(|| Error::Validation("Missing 'operation' field".to_string()))?;
+                // Without a policy, failures of the operation are no longer retried
+                let policy = match arguments.get("policy") {
+                    Some(policy) => Some(
+                        serde_json::from_value::<RetryPolicy>(policy.clone())
+                            .map_err(|e| Error::Validation(format!("Invalid retry policy: {e}")))?,
+                    ),
+                    None => None,
+                };
+
+                let dlq = self.dead_letter_queue.read().await;
+                dlq.set_retry_policy(operation, policy.clone()).await?;
+                Ok(json!({
+                    "operation": operation,
+                    "retry_policy": policy
+                }))
+            }
             _ => Err(Error::Validation(format!(
                 "Unknown dead letter queue action: {action}"
             ))),
         }
     }
 
+    /// Inspect and manage the command result cache
+    async fn handle_cache(&self, arguments: Value) -> Result<Value> {
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("stats");
+
+        match action {
+            "stats" => Ok(json!({
+                "statistics": self.get_cache_statistics().await,
+                "persistent": self.command_cache.is_persistent(),
+            })),
+            "clear" => {
+                self.clear_all_cache().await;
+                observe::clear_query_results().await;
+                Ok(json!({ "cleared": true }))
+            }
+            "invalidate" => {
+                let tag = arguments
+                    .get("tag")
+                    .and_then(|t| t.as_str())
+                    .ok_or_else(|| Error::Validation("Missing 'tag' field".to_string()))?;
+                Ok(json!({ "invalidated": self.clear_cache_by_tag(tag).await }))
+            }
+            "save" => Ok(json!({ "saved": self.command_cache.save().await? })),
+            _ => Err(Error::Validation(format!(
+                "Unknown cache action: {action}. Use stats, clear, invalidate or save"
+            ))),
+        }
+    }
+
     /// Handle diagnostic report generation
     async fn handle_diagnostic_report(&self, arguments: Value) -> Result<Value> {
         let action = arguments
@@ -648,12 +1136,24 @@ impl McpServer {
             .and_then(|a| a.as_str())
             .unwrap_or("generate");
 
+        let mut log_query = LogQuery::default();
+        if let Some(level) = arguments.get("log_level").and_then(|l| l.as_str()) {
+            log_query.min_level = level.parse().map_err(|_| {
+                Error::Validation(format!(
+                    "Invalid log_level '{level}': use error, warn, info, debug or trace"
+                ))
+            })?;
+        }
+        if let Some(lines) = arguments.get("max_log_lines").and_then(|l| l.as_u64()) {
+            log_query.limit = lines as usize;
+        }
+
         match action {
             "generate" => {
                 let dlq = self.dead_letter_queue.read().await;
                 let report = self
                     .diagnostic_collector
-                    .generate_report(Some(&*dlq))
+                    .generate_report_with_logs(Some(&*dlq), log_query)
                     .await?;
                 Ok(serde_json::to_value(report)?)
             }
@@ -661,7 +1161,7 @@ impl McpServer {
                 let dlq = self.dead_letter_queue.read().await;
                 let report = self
                     .diagnostic_collector
-                    .generate_report(Some(&*dlq))
+                    .generate_report_with_logs(Some(&*dlq), log_query)
                     .await?;
                 let json_export = self
                     .diagnostic_collector
@@ -703,7 +1203,31 @@ impl McpServer {
                     .and_then(|o| o.as_str())
                     .unwrap_or("manual");
 
-                let state_data = arguments.get("state_data").cloned().unwrap_or(json!({}));
+                let mut state_data = arguments.get("state_data").cloned().unwrap_or(json!({}));
+
+                // Snapshot the live game when a world filter is given
+                let mut world_summary = Value::Null;
+                if let Some(world) = arguments.get("world") {
+                    let filter: SnapshotFilter = serde_json::from_value(world.clone())
+                        .map_err(|e| Error::Validation(format!("Invalid 'world' filter: {e}")))?;
+                    let snapshot = WorldSnapshot::capture(&self.brp_client, filter).await?;
+                    world_summary = json!({
+                        "entity_count": snapshot.entities.len(),
+                        "resource_count": snapshot.resources.len()
+                    });
+                    state_data
+                        .as_object_mut()
+                        .ok_or_else(|| {
+                            Error::Validation(
+                                "'state_data' must be an object when capturing world state"
+                                    .to_string(),
+                            )
+                        })?
+                        .insert(
+                            WORLD_SNAPSHOT_KEY.to_string(),
+                            serde_json::to_value(snapshot)?,
+                        );
+                }
 
                 let checkpoint = crate::checkpoint::Checkpoint::new(
                     name,
@@ -718,7 +1242,8 @@ impl McpServer {
 
                 Ok(json!({
                     "checkpoint_id": checkpoint_id,
-                    "created": true
+                    "created": true,
+                    "world": world_summary
                 }))
             }
             "restore" => {
@@ -731,8 +1256,32 @@ impl McpServer {
 
                 let cm = self.checkpoint_manager.read().await;
                 let checkpoint = cm.restore_checkpoint(checkpoint_id).await?;
+                drop(cm);
+
+                let apply_world = arguments
+                    .get("apply_world")
+                    .and_then(|a| a.as_bool())
+                    .unwrap_or(true);
+                let world_restore = match WorldSnapshot::from_state_data(&checkpoint.state_data)? {
+                    Some(snapshot) if apply_world => {
+                        let report = snapshot.restore(&self.brp_client).await?;
+                        info!(
+                            "Restored world state from checkpoint {}: {} updated, {} despawned, {} respawned",
+                            checkpoint_id,
+                            report.updated,
+                            report.despawned,
+                            report.respawned.len()
+                        );
+                        Some(report)
+                    }
+                    _ => None,
+                };
 
-                Ok(serde_json::to_value(checkpoint)?)
+                let mut response = serde_json::to_value(checkpoint)?;
+                if let (Some(report), Some(obj)) = (world_restore, response.as_object_mut()) {
+                    obj.insert("world_restore".to_string(), serde_json::to_value(report)?);
+                }
+                Ok(response)
             }
             "list" => {
                 let cm = self.checkpoint_manager.read().await;
@@ -795,7 +1344,9 @@ impl McpServer {
             .generate_report(Some(&*dlq))
             .await?;
 
-        let bug_report = create_bug_report(&diagnostic_report, description, steps_to_reproduce);
+        let bug_report = self
+            .diagnostic_collector
+            .bug_report(&diagnostic_report, description, steps_to_reproduce);
 
         // Optionally save to file (with path validation)
         if let Some(file_path) = arguments.get("save_to_file").and_then(|f| f.as_str()) {
@@ -822,6 +1373,473 @@ impl McpServer {
         }))
     }
 
+    /// Dump or clear the server's own recent activity
+    async fn handle_flight_recorder(&self, arguments: Value) -> Result<Value> {
+        let recorder = flight_recorder::global();
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("dump");
+
+        match action {
+            "dump" => {
+                let within = arguments
+                    .get("minutes")
+                    .and_then(|m| m.as_f64())
+                    .filter(|m| *m > 0.0)
+                    .map(|m| Duration::from_secs_f64(m * 60.0));
+                let kinds: Option<Vec<&str>> = arguments
+                    .get("kinds")
+                    .and_then(|k| k.as_array())
+                    .map(|kinds| kinds.iter().filter_map(|k| k.as_str()).collect());
+
+                let events: Vec<_> = recorder
+                    .snapshot(within)
+                    .into_iter()
+                    .filter(|event| match &kinds {
+                        Some(kinds) => kinds.contains(&event.kind.name()),
+                        None => true,
+                    })
+                    .collect();
+                let mut counts = std::collections::BTreeMap::new();
+                for event in &events {
+                    *counts.entry(event.kind.name()).or_insert(0usize) += 1;
+                }
+
+                Ok(json!({
+                    "retention_minutes": recorder.retention().as_secs() / 60,
+                    "event_count": events.len(),
+                    "counts": counts,
+                    "events": events,
+                }))
+            }
+            "clear" => {
+                recorder.clear();
+                Ok(json!({ "cleared": true }))
+            }
+            _ => Err(Error::Validation(format!(
+                "Unknown flight_recorder action: {action}"
+            ))),
+        }
+    }
+
+    async fn handle_list_error_codes(&self, arguments: Value) -> Result<Value> {
+        let category = match arguments.get("category") {
+            Some(category) => Some(
+                serde_json::from_value::<ErrorCategory>(category.clone())
+                    .map_err(|e| Error::Validation(format!("Invalid category: {e}")))?,
+            ),
+            None => None,
+        };
+        Ok(json!({ "error_codes": error_codes::list(category) }))
+    }
+
+    /// List, add or remove threshold alert rules
+    async fn handle_alerts(&self, arguments: Value) -> Result<Value> {
+        let manager = alerting::global();
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("list");
+
+        match action {
+            "list" => Ok(json!({
+                "rules": manager.rules(),
+                "firing": manager.firing(),
+                "history": manager.history(),
+            })),
+            "add" => {
+                let rule: AlertRule = serde_json::from_value(
+                    arguments
+                        .get("rule")
+                        .cloned()
+                        .ok_or_else(|| Error::Validation("Missing 'rule' field".to_string()))?,
+                )
+                .map_err(|e| Error::Validation(format!("Invalid alert rule: {e}")))?;
+                let name = rule.name.clone();
+                manager.add_rule(rule)?;
+                Ok(json!({ "added": name }))
+            }
+            "remove" => {
+                let name = arguments
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .ok_or_else(|| Error::Validation("Missing 'name' field".to_string()))?;
+                Ok(json!({ "removed": manager.remove_rule(name) }))
+            }
+            _ => Err(Error::Validation(format!("Unknown alerts action: {action}"))),
+        }
+    }
+
+    /// List, add or remove scheduled workflows and show their run history
+    async fn handle_schedules(&self, arguments: Value) -> Result<Value> {
+        let scheduler = workflow_scheduler::global();
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("list");
+
+        match action {
+            "list" => Ok(json!({ "schedules": scheduler.list() })),
+            "add" => {
+                let workflow: ScheduledWorkflow = serde_json::from_value(
+                    arguments
+                        .get("schedule")
+                        .cloned()
+                        .ok_or_else(|| Error::Validation("Missing 'schedule' field".to_string()))?,
+                )
+                .map_err(|e| Error::Validation(format!("Invalid schedule: {e}")))?;
+                let name = workflow.name.clone();
+                let next_run = scheduler.add(workflow, chrono::Utc::now())?;
+                self.start_workflow_scheduler();
+                Ok(json!({ "added": name, "next_run": next_run }))
+            }
+            "remove" => {
+                let name = arguments
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .ok_or_else(|| Error::Validation("Missing 'name' field".to_string()))?;
+                Ok(json!({ "removed": scheduler.remove(name) }))
+            }
+            "history" => {
+                let name = arguments.get("name").and_then(|n| n.as_str());
+                Ok(json!({ "runs": scheduler.history(name) }))
+            }
+            _ => Err(Error::Validation(format!("Unknown schedules action: {action}"))),
+        }
+    }
+
+    /// Downsampled metric series over a window, shaped for Grafana
+    async fn handle_metrics_query(&self, arguments: Value) -> Result<Value> {
+        let store = metrics_store::global();
+        if arguments.get("action").and_then(|a| a.as_str()) == Some("list") {
+            return Ok(json!({ "series": store.names() }));
+        }
+
+        let to_ms = arguments
+            .get("to_ms")
+            .and_then(|t| t.as_u64())
+            .unwrap_or_else(metrics_store::now_ms);
+        let from_ms = match arguments.get("from_ms").and_then(|f| f.as_u64()) {
+            Some(from_ms) => from_ms,
+            None => {
+                let window_ms = arguments
+                    .get("window_ms")
+                    .and_then(|w| w.as_u64())
+                    .unwrap_or(metrics_store::DEFAULT_QUERY_WINDOW_MS);
+                to_ms.saturating_sub(window_ms)
+            }
+        };
+        if from_ms > to_ms {
+            return Err(Error::Validation(
+                "'from_ms' must not be after 'to_ms'".to_string(),
+            ));
+        }
+
+        let max_points = arguments
+            .get("max_points")
+            .and_then(|m| m.as_u64())
+            .map(|m| m as usize)
+            .unwrap_or(perf_timeline::DEFAULT_MAX_POINTS)
+            .clamp(1, perf_timeline::MAX_POINTS);
+        let aggregation: Aggregation = match arguments.get("aggregation").and_then(|a| a.as_str()) {
+            Some(aggregation) => aggregation.parse()?,
+            None => Aggregation::default(),
+        };
+        let selectors: Vec<String> = arguments
+            .get("series")
+            .and_then(|s| s.as_array())
+            .map(|series| {
+                series
+                    .iter()
+                    .filter_map(|s| s.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let series = store.query(&selectors, from_ms, to_ms, max_points, aggregation);
+        Ok(json!({
+            "from_ms": from_ms,
+            "to_ms": to_ms,
+            "interval_ms": (to_ms - from_ms + max_points as u64) / max_points as u64,
+            "series": series,
+        }))
+    }
+
+    /// Bundle a recording, checkpoints, screenshots and a diagnostic report into one archive
+    async fn handle_export_session(&self, arguments: Value) -> Result<Value> {
+        let archive_path = arguments
+            .get("path")
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| Error::Validation("Missing 'path' field".to_string()))?;
+        let archive_path = resolve_relative_path(Path::new(SESSIONS_DIR), archive_path)?;
+        let description = arguments
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(String::from);
+        let mut archive = SessionArchive::new(description);
+
+        // A saved recording file, or whatever the recording buffer holds
+        archive.recording = match arguments.get("recording").and_then(|r| r.as_str()) {
+            Some(file) => Some(ArchivedFile::from_path(&resolve_relative_path(
+                Path::new("."),
+                file,
+            )?)?),
+            None => {
+                let recording = replay::buffered_recording().await?;
+                if recording.frames.is_empty() {
+                    None
+                } else {
+                    let mut bytes = Vec::new();
+                    crate::recording_format::write_recording_to(&mut bytes, &recording)?;
+                    Some(ArchivedFile::new(RECORDING_FILE_NAME, &bytes))
+                }
+            }
+        };
+
+        if let Some(screenshots) = arguments.get("screenshots").and_then(|s| s.as_array()) {
+            for screenshot in screenshots.iter().filter_map(|s| s.as_str()) {
+                let path = resolve_relative_path(Path::new("."), screenshot)?;
+                archive.screenshots.push(ArchivedFile::from_path(&path)?);
+            }
+        }
+
+        if arguments
+            .get("include_checkpoints")
+            .and_then(|c| c.as_bool())
+            .unwrap_or(true)
+        {
+            archive.checkpoints = self
+                .checkpoint_manager
+                .read()
+                .await
+                .list_checkpoints()
+                .await?;
+        }
+
+        if arguments
+            .get("include_diagnostics")
+            .and_then(|d| d.as_bool())
+            .unwrap_or(true)
+        {
+            let dlq = self.dead_letter_queue.read().await;
+            archive.diagnostic_report = Some(
+                self.diagnostic_collector
+                    .generate_report(Some(&*dlq))
+                    .await?,
+            );
+        }
+
+        let write_path = archive_path.clone();
+        let archive =
+            tokio::task::spawn_blocking(move || archive.write(&write_path).map(|()| archive))
+                .await
+                .map_err(|e| Error::Validation(format!("Session export task failed: {e}")))??;
+
+        Ok(json!({
+            "exported": true,
+            "path": archive_path,
+            "recording": archive.recording.is_some(),
+            "checkpoint_count": archive.checkpoints.len(),
+            "screenshot_count": archive.screenshots.len(),
+            "diagnostic_report": archive.diagnostic_report.is_some(),
+        }))
+    }
+
+    /// Unpack a session archive, restoring its checkpoints and loading its recording for replay
+    async fn handle_import_session(&self, arguments: Value) -> Result<Value> {
+        let archive_path = arguments
+            .get("path")
+            .and_then(|p| p.as_str())
+            .ok_or_else(|| Error::Validation("Missing 'path' field".to_string()))?;
+        let archive_path = resolve_relative_path(Path::new(SESSIONS_DIR), archive_path)?;
+        let unpack_dir = archive_path.with_extension("");
+
+        let (archive, unpacked) = tokio::task::spawn_blocking(move || {
+            let archive = SessionArchive::read(&archive_path)?;
+            let unpacked = archive.unpack(&unpack_dir)?;
+            Ok::<_, Error>((archive, unpacked))
+        })
+        .await
+        .map_err(|e| Error::Validation(format!("Session import task failed: {e}")))??;
+
+        let cm = self.checkpoint_manager.read().await;
+        for checkpoint in &archive.checkpoints {
+            cm.create_checkpoint(checkpoint.clone()).await?;
+        }
+        drop(cm);
+
+        let load_recording = arguments
+            .get("load_recording")
+            .and_then(|l| l.as_bool())
+            .unwrap_or(true);
+        let recording_loaded = match (&unpacked.recording, load_recording) {
+            (Some(path), true) => {
+                replay::load_for_playback(RecordingBuffer::load_from_file(path)?).await?;
+                true
+            }
+            _ => false,
+        };
+
+        Ok(json!({
+            "imported": true,
+            "description": archive.description,
+            "created_at": archive.created_at,
+            "exported_by_version": archive.crate_version,
+            "unpacked": unpacked,
+            "recording_loaded": recording_loaded,
+            "checkpoints_restored": archive
+                .checkpoints
+                .iter()
+                .map(|c| &c.id)
+                .collect::<Vec<_>>(),
+            "diagnostic_report": archive.diagnostic_report,
+        }))
+    }
+
+    /// Diff two recordings, e.g. a run on main against the same run on a branch
+    async fn handle_compare_recordings(&self, arguments: Value) -> Result<Value> {
+        let recording_a = arguments
+            .get("recording_a")
+            .and_then(|r| r.as_str())
+            .ok_or_else(|| Error::Validation("Missing 'recording_a' field".to_string()))?;
+        let path_a = resolve_relative_path(Path::new("."), recording_a)?;
+        // Without a second file, compare against what is being recorded now
+        let path_b = match arguments.get("recording_b").and_then(|r| r.as_str()) {
+            Some(recording_b) => Some(resolve_relative_path(Path::new("."), recording_b)?),
+            None => None,
+        };
+
+        let mut config = RecordingDiffConfig::default();
+        if let Some(align) = arguments.get("align") {
+            config.align = serde_json::from_value::<AlignMode>(align.clone()).map_err(|_| {
+                Error::Validation("'align' must be 'frame' or 'timestamp'".to_string())
+            })?;
+        }
+        if let Some(filter) = arguments.get("components").and_then(|c| c.as_array()) {
+            config.component_filter = Some(
+                filter
+                    .iter()
+                    .filter_map(|c| c.as_str().map(String::from))
+                    .collect(),
+            );
+        }
+        if let Some(epsilon) = arguments.get("tolerance").and_then(|t| t.as_f64()) {
+            config.fuzzy.epsilon = epsilon;
+        }
+        if let Some(max) = arguments.get("max_frames").and_then(|m| m.as_u64()) {
+            config.max_reported_frames = max as usize;
+        }
+
+        let loaded_a = RecordingBuffer::load_from_file(&path_a)?;
+        let (loaded_b, label_b) = match &path_b {
+            Some(path) => (
+                RecordingBuffer::load_from_file(path)?,
+                path.display().to_string(),
+            ),
+            None => (
+                replay::buffered_recording().await?,
+                "recording buffer".to_string(),
+            ),
+        };
+        let diff =
+            tokio::task::spawn_blocking(move || compare_recordings(&loaded_a, &loaded_b, &config))
+                .await
+                .map_err(|e| Error::Validation(format!("Recording comparison task failed: {e}")))?;
+
+        Ok(json!({
+            "recording_a": recording_a,
+            "recording_b": label_b,
+            "diff": diff,
+        }))
+    }
+
+    /// Entity state at one moment of a recording, without playing it back
+    async fn handle_state_at(&self, arguments: Value) -> Result<Value> {
+        let point = match (
+            arguments.get("frame").and_then(|f| f.as_u64()),
+            arguments.get("seconds").and_then(|s| s.as_f64()),
+        ) {
+            (Some(frame), None) => RecordingPoint::Frame(frame as usize),
+            (None, Some(seconds)) if seconds.is_finite() && seconds >= 0.0 => {
+                RecordingPoint::Timestamp(Duration::from_secs_f64(seconds))
+            }
+            (None, Some(_)) => {
+                return Err(Error::Validation(
+                    "'seconds' must be a non-negative number".to_string(),
+                ))
+            }
+            _ => {
+                return Err(Error::Validation(
+                    "Provide exactly one of 'frame' or 'seconds'".to_string(),
+                ))
+            }
+        };
+        let entity_ids: Option<Vec<u64>> = arguments
+            .get("entities")
+            .and_then(|e| e.as_array())
+            .map(|ids| ids.iter().filter_map(|id| id.as_u64()).collect());
+        let components: Option<Vec<String>> = arguments
+            .get("components")
+            .and_then(|c| c.as_array())
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|c| c.as_str().map(String::from))
+                    .collect()
+            });
+
+        // Without a file, query what is being recorded now
+        let (frame, label) = match arguments.get("recording").and_then(|r| r.as_str()) {
+            Some(recording) => {
+                let path = resolve_relative_path(Path::new("."), recording)?;
+                let frame = tokio::task::spawn_blocking(move || read_frame_at(&path, point))
+                    .await
+                    .map_err(|e| Error::Validation(format!("State lookup task failed: {e}")))??;
+                (frame, recording.to_string())
+            }
+            None => (
+                replay::buffered_recording().await?.state_at(point),
+                "recording buffer".to_string(),
+            ),
+        };
+        let Some(frame) = frame else {
+            return Ok(json!({
+                "error": "No recorded state",
+                "message": format!("{label} has no full frame at or before the requested point"),
+            }));
+        };
+
+        let ids = entity_ids.unwrap_or_else(|| {
+            let mut ids: Vec<u64> = frame.entities.keys().copied().collect();
+            ids.sort_unstable();
+            ids
+        });
+        let mut entities = serde_json::Map::new();
+        let mut missing = Vec::new();
+        for id in ids {
+            let Some(state) = frame.entities.get(&id) else {
+                missing.push(id);
+                continue;
+            };
+            let mut state = state.clone();
+            if let Some(components) = &components {
+                state.components.retain(|name, _| components.contains(name));
+            }
+            entities.insert(id.to_string(), serde_json::to_value(state)?);
+        }
+
+        Ok(json!({
+            "recording": label,
+            "frame_number": frame.frame_number,
+            "timestamp_ms": frame.timestamp.as_millis() as u64,
+            "entities": entities,
+            "missing_entities": missing,
+            "events": frame.events,
+        }))
+    }
+
     /// Handle debug command execution
     async fn handle_debug_command(&self, arguments: Value) -> Result<Value> {
         // Extract command from arguments
@@ -874,6 +1892,7 @@ impl McpServer {
                 Ok(json!({
                     "correlation_id": correlation_id,
                     "error": e.to_string(),
+                    "error_code": e.data(),
                     "status": "error"
                 }))
             }
@@ -917,9 +1936,9 @@ impl McpServer {
                 "diagnostic_report" | "anomaly" | "debug" => true,
                 
                 // Non-cacheable tools (stateful or time-sensitive operations)
-                "experiment" | "screenshot" | "hypothesis" | "stress" | "replay" |
-                "orchestrate" | "pipeline" | "performance_dashboard" | 
-                "dead_letter_queue" | "checkpoint" | "bug_report" => false,
+                "experiment" | "mutate" | "types" | "schema_history" | "search_world" | "screenshot" | "hypothesis" | "stress" | "replay" |
+                "orchestrate" | "pipeline" | "performance_dashboard" | "perf_timeline" | "benchmark" | "sampling" | "export_session" | "import_session" | "compare_recordings" | "state_at" |
+                "entity_watchdog" | "dead_letter_queue" | "checkpoint" | "bug_report" | "flight_recorder" | "metrics_query" | "alerts" | "cache" => false,
                 
                 _ => false,
             }
@@ -958,6 +1977,21 @@ impl McpServer {
         }
     }
     
+    /// Cache tags for a tool's response
+    ///
+    /// Entity responses also record the entities and components they cover,
+    /// so mutations only invalidate the entries they affect.
+    fn cache_tags_for_response(&self, tool_name: &str, response: &Value) -> Vec<String> {
+        let mut tags = self.get_cache_tags_for_tool(tool_name);
+        if matches!(tool_name, "observe" | "debug") {
+            if !tags.iter().any(|tag| tag == ENTITY_DATA_TAG) {
+                tags.push(ENTITY_DATA_TAG.to_string());
+            }
+            tags.extend(coverage_tags(response));
+        }
+        tags
+    }
+    
     /// Get cache statistics
     pub async fn get_cache_statistics(&self) -> serde_json::Value {
         let stats = self.command_cache.get_statistics().await;
@@ -1095,6 +2129,36 @@ impl McpServer {
         }))
     }
     
+    /// Handle feedback on a suggestion, reweighting future suggestions
+    async fn handle_suggestion_feedback(&self, arguments: Value) -> Result<Value> {
+        let suggestion_engine = self.lazy_components.get_suggestion_engine().await;
+        
+        let suggestion_id = arguments
+            .get("suggestion_id")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| Error::Validation("Missing 'suggestion_id' field".to_string()))?;
+            
+        let feedback: SuggestionFeedback = arguments
+            .get("feedback")
+            .cloned()
+            .ok_or_else(|| Error::Validation("Missing 'feedback' field".to_string()))
+            .and_then(|f| {
+                serde_json::from_value(f).map_err(|_| {
+                    Error::Validation("'feedback' must be accepted, rejected or ignored".to_string())
+                })
+            })?;
+            
+        let success = arguments.get("success").and_then(|s| s.as_bool());
+        
+        let weight = suggestion_engine.record_feedback(suggestion_id, feedback, success).await?;
+        
+        Ok(json!({
+            "suggestion_id": suggestion_id,
+            "feedback": feedback,
+            "weight": weight
+        }))
+    }
+    
     /// Handle get learned patterns request
     async fn handle_get_patterns(&self, arguments: Value) -> Result<Value> {
         let pattern_system = self.lazy_components.get_pattern_learning_system().await;
@@ -1127,10 +2191,24 @@ impl McpServer {
                     .and_then(|p| p.as_str())
                     .ok_or_else(|| Error::Validation("Missing 'patterns_json' field".to_string()))?;
                     
-                pattern_system.import_patterns(patterns_json).await?;
+                let imported = pattern_system.import_patterns(patterns_json).await?;
+                let saved_to = match pattern_system.store_path() {
+                    Some(_) => Some(pattern_system.save().await?.display().to_string()),
+                    None => None,
+                };
                 
                 Ok(json!({
-                    "imported": true
+                    "imported": imported,
+                    "total_count": pattern_system.pattern_count(),
+                    "saved_to": saved_to
+                }))
+            }
+            "save" => {
+                let path = pattern_system.save().await?;
+                
+                Ok(json!({
+                    "saved_to": path.display().to_string(),
+                    "total_count": pattern_system.pattern_count()
                 }))
             }
             _ => Err(Error::Validation(format!("Unknown patterns action: {}", action)))
@@ -1214,6 +2292,47 @@ impl McpServer {
         }
     }
 
+    /// Handle listing and running Rhai workflow scripts
+    #[cfg(feature = "scripting")]
+    async fn handle_workflow_script(&self, arguments: Value) -> Result<Value> {
+        let workflow_automation = self.lazy_components.get_workflow_automation().await;
+        
+        let action = arguments
+            .get("action")
+            .and_then(|a| a.as_str())
+            .unwrap_or("list");
+            
+        match action {
+            "list" => {
+                let scripts = workflow_automation.list_scripts()?;
+                Ok(json!({
+                    "scripts": scripts,
+                    "total_count": scripts.len()
+                }))
+            }
+            "run" => {
+                let name = arguments
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .ok_or_else(|| Error::Validation("Missing 'name' field".to_string()))?;
+                let args = arguments.get("args").cloned().unwrap_or_else(|| json!({}));
+                
+                let run = workflow_automation
+                    .run_script(name, args, Arc::clone(&self.brp_client))
+                    .await?;
+                Ok(serde_json::to_value(run)?)
+            }
+            _ => Err(Error::Validation(format!("Unknown workflow_script action: {}", action)))
+        }
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    async fn handle_workflow_script(&self, _arguments: Value) -> Result<Value> {
+        Err(Error::Config(
+            "Workflow scripts require the scripting feature".to_string(),
+        ))
+    }
+
     /// Handle hot reload operations
     async fn handle_hot_reload(&self, arguments: Value) -> Result<Value> {
         let hot_reload_system = self.lazy_components.get_hot_reload_system().await;
@@ -1280,10 +2399,60 @@ impl Clone for McpServer {
             dead_letter_queue: Arc::clone(&self.dead_letter_queue),
             diagnostic_collector: Arc::clone(&self.diagnostic_collector),
             checkpoint_manager: Arc::clone(&self.checkpoint_manager),
+            pipeline_persistence: self.pipeline_persistence.clone(),
+            pipeline_templates: self.pipeline_templates.clone(),
+            entity_watchdog: Arc::clone(&self.entity_watchdog),
             lazy_components: Arc::clone(&self.lazy_components),
             command_cache: Arc::clone(&self.command_cache),
             response_pool: Arc::clone(&self.response_pool),
+            memory_budget: Arc::clone(&self.memory_budget),
             debug_mode: self.debug_mode,
         }
     }
 }
+
+/// Drop cached entity data as soon as the BRP client changes the world
+///
+/// If mutations arrive faster than they can be handled, every entity entry is
+/// dropped rather than guessing which ones were missed.
+fn spawn_cache_invalidation(brp_client: Arc<RwLock<BrpClient>>, command_cache: Arc<CommandCache>) {
+    tokio::spawn(async move {
+        let mut mutations = brp_client.read().await.subscribe_mutations();
+        loop {
+            match mutations.recv().await {
+                Ok(mutation) => {
+                    command_cache.invalidate_mutation(&mutation).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Missed {} entity mutations; dropping all cached entity data", missed);
+                    command_cache.invalidate_by_tag(ENTITY_DATA_TAG).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Attach the overlays visible in a screenshot, writing them next to the image when it is local
+fn annotate_screenshot(result: &mut Value, annotations: Vec<Value>) {
+    let image_path = result["path"].as_str().map(std::path::PathBuf::from);
+    result["overlays"] = json!(annotations);
+
+    let Some(image_path) = image_path.filter(|path| path.exists()) else {
+        return;
+    };
+    let annotation_path = image_path.with_extension("overlays.json");
+    let annotation = json!({
+        "image": image_path,
+        "overlays": result["overlays"],
+        "timestamp": SystemTime::now().duration_since(UNIX_EPOCH)
+            .unwrap_or_default().as_secs()
+    });
+    match serde_json::to_string_pretty(&annotation)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| std::fs::write(&annotation_path, contents).map_err(|e| e.to_string()))
+    {
+        Ok(()) => result["annotation_path"] = json!(annotation_path),
+        Err(e) => warn!("Failed to write screenshot annotations: {}", e),
+    }
+}
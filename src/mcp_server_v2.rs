@@ -28,41 +28,69 @@ use tracing::{error, info};
 use crate::brp_client::BrpClient;
 use crate::config::Config;
 use crate::error::Result;
-use crate::mcp_tools::BevyDebuggerTools;
+use crate::mcp_server::McpServer;
+#[cfg(feature = "mtls")]
+use crate::mtls::MtlsAcceptor;
 use crate::secure_mcp_tools::SecureMcpTools;
-use crate::security::{SecurityManager, SecurityConfig};
+use crate::security::{SecurityAudit, SecurityManager, SecurityConfig};
+use crate::memory_budget::global_memory_budget;
+use crate::tools::{observe, replay};
+use crate::workflow_scheduler;
+#[cfg(feature = "mtls")]
+use crate::wire_encoding;
 
 /// Proper MCP server implementation using the official SDK
 pub struct McpServerV2 {
     config: Config,
     brp_client: Arc<RwLock<BrpClient>>,
-    tools: Arc<BevyDebuggerTools>,
+    /// The only tool handler served; every debugging tool is authorized by the security middleware
     secure_tools: Arc<SecureMcpTools>,
+    /// Runs tools for `secure_tools`, and scheduled workflows
+    tool_server: McpServer,
     security_manager: Arc<SecurityManager>,
 }
 
 impl McpServerV2 {
-    pub fn new(config: Config, brp_client: Arc<RwLock<BrpClient>>) -> Result<Self> {
-        let tools = Arc::new(BevyDebuggerTools::new(brp_client.clone()));
-        
+    pub async fn new(config: Config, brp_client: Arc<RwLock<BrpClient>>) -> Result<Self> {
         // Initialize production-ready security system
         let security_config = SecurityConfig::new()?;
         security_config.print_security_summary();
-        let security_manager = Arc::new(SecurityManager::new(security_config)?);
-        let secure_tools = Arc::new(SecureMcpTools::new(brp_client.clone(), security_manager.clone()));
+        let security_manager = Arc::new(SecurityManager::new(security_config).await?);
+        let tool_server = McpServer::new(config.clone(), brp_client.clone());
+        let secure_tools = Arc::new(SecureMcpTools::new(tool_server.clone(), security_manager.clone()));
         
         Ok(Self {
             config,
             brp_client,
-            tools,
             secure_tools,
+            tool_server,
             security_manager,
         })
     }
+
+    /// Count the in-memory audit log and recordings against the process memory budget
+    async fn register_memory_consumers(&self) {
+        let budget = global_memory_budget();
+        budget.register(self.security_manager.audit_memory_consumer()).await;
+        budget.register(replay::recording_memory_consumer()).await;
+    }
+
+    /// Start the tool server's background systems and the workflows in `BEVY_MCP_SCHEDULES`
+    async fn start_tool_server(&self) -> Result<()> {
+        self.tool_server.start().await?;
+        let count = workflow_scheduler::global().load_from_env()?;
+        if count > 0 {
+            info!("Loaded {} scheduled workflows", count);
+            self.tool_server.start_workflow_scheduler();
+        }
+        Ok(())
+    }
     
     /// Run the server in stdio mode for Claude Code
     pub async fn run_stdio(self) -> Result<()> {
         info!("Starting MCP server in stdio mode for Claude Code integration");
+        self.register_memory_consumers().await;
+        self.start_tool_server().await?;
         
         // Initialize BRP connection
         {
@@ -73,6 +101,13 @@ impl McpServerV2 {
             }
         }
         
+        // Keep cached query results in step with the game
+        observe::spawn_query_cache_invalidation(self.brp_client.clone());
+        
+        // Prefetch common queries each time the game connects
+        let warm_client = self.brp_client.clone();
+        observe::spawn_cache_warming(self.brp_client.clone(), move |arguments| observe::handle(arguments, warm_client.clone()));
+        
         // Start BRP connection heartbeat in background
         let brp_client = self.brp_client.clone();
         tokio::spawn(async move {
@@ -133,6 +168,9 @@ impl McpServerV2 {
             }
         });
 
+        // Scan for configuration weaknesses on a schedule, when enabled
+        SecurityAudit::new(self.security_manager.clone()).start_scheduled_scans();
+
         // Run the server using the secure tools handler with proper error handling
         tokio::select! {
             result = serve_server(Arc::try_unwrap(self.secure_tools).unwrap_or_else(|arc| (*arc).clone()), (stdin, stdout)) => {
@@ -155,15 +193,121 @@ impl McpServerV2 {
     }
     
     /// Run the server in TCP mode for background operation
+    ///
+    /// Connections must use mutual TLS. Unless `BEVY_MCP_TLS_REQUIRE_JWT` is
+    /// set, each connection is authenticated as its client certificate's
+    /// mapped identity and calls need no JWT.
+    #[cfg(feature = "mtls")]
+    pub async fn run_tcp(self) -> Result<()> {
+        let mtls = self.security_manager.mtls_config().cloned().ok_or_else(|| {
+            crate::error::Error::SecurityError(
+                "TCP mode requires BEVY_MCP_TLS_CERT, BEVY_MCP_TLS_KEY and BEVY_MCP_TLS_CLIENT_CA".to_string(),
+            )
+        })?;
+        let acceptor = MtlsAcceptor::new(&mtls)?;
+        let address = format!("{}:{}", mtls.bind_address, self.config.mcp_port);
+        let listener = tokio::net::TcpListener::bind(&address)
+            .await
+            .map_err(|e| crate::error::Error::Connection(format!("Failed to bind TCP: {}", e)))?;
+        info!("MCP server listening with mutual TLS on {}", address);
+        self.register_memory_consumers().await;
+        self.start_tool_server().await?;
+
+        // Start security cleanup task
+        let security_manager = self.security_manager.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(300));
+            loop {
+                interval.tick().await;
+                security_manager.cleanup().await;
+            }
+        });
+
+        // Scan for configuration weaknesses on a schedule, when enabled
+        SecurityAudit::new(self.security_manager.clone()).start_scheduled_scans();
+
+        loop {
+            let (stream, addr) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    error!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            // Dropping the stream closes connections from rejected addresses before the TLS handshake
+            if self.security_manager.check_client_address(addr.ip(), "tcp").await.is_err() {
+                continue;
+            }
+            let acceptor = acceptor.clone();
+            let security_manager = self.security_manager.clone();
+            let secure_tools = self.secure_tools.clone();
+            let require_jwt = mtls.require_jwt;
+            tokio::spawn(async move {
+                if let Err(e) = serve_tls_connection(acceptor, stream, addr, security_manager, secure_tools, require_jwt).await {
+                    tracing::warn!("MCP connection from {} closed: {}", addr, e);
+                }
+            });
+        }
+    }
+
+    /// Run the server in TCP mode for background operation
+    #[cfg(not(feature = "mtls"))]
     pub async fn run_tcp(self) -> Result<()> {
         info!("Starting MCP server in TCP mode on port {}", self.config.mcp_port);
-        
-        // For now, TCP mode is not implemented
-        // You can use stdio mode instead
-        error!("TCP mode not implemented, use stdio mode");
-        Err(crate::error::Error::DebugError("TCP mode not implemented".to_string()))
+
+        // TCP mode is only served over mutual TLS
+        error!("TCP mode requires the mtls feature, use stdio mode");
+        Err(crate::error::Error::DebugError("TCP mode requires the mtls feature".to_string()))
+    }
+}
+
+/// Serve MCP on one TLS connection, authenticated by its client certificate
+#[cfg(feature = "mtls")]
+async fn serve_tls_connection(
+    acceptor: MtlsAcceptor,
+    stream: tokio::net::TcpStream,
+    addr: std::net::SocketAddr,
+    security_manager: Arc<SecurityManager>,
+    secure_tools: Arc<SecureMcpTools>,
+    require_jwt: bool,
+) -> Result<()> {
+    let (tls_stream, common_name) = acceptor.accept(stream).await?;
+    info!("MCP client '{}' connected from {}", common_name, addr);
+
+    let session_token = if require_jwt {
+        None
+    } else {
+        Some(security_manager.authenticate_client_certificate(&common_name, Some(addr.ip().to_string())).await?)
+    };
+    let tools = secure_tools.with_client_address(addr.ip());
+    let tools = match &session_token {
+        Some(token) => tools.with_session_token(token.clone()),
+        None => tools,
+    };
+
+    // Clients may ask for MessagePack or CBOR, and for chunked responses, before their first message
+    let (options, stream) = wire_encoding::negotiate(tls_stream).await?;
+    let service = if options.needs_bridge() {
+        serve_server(tools, tokio::io::split(wire_encoding::bridge(stream, options))).await
+    } else {
+        serve_server(tools, tokio::io::split(stream)).await
+    };
+    let result = match service {
+        Ok(service) => {
+            let _ = service.waiting().await;
+            Ok(())
+        }
+        Err(e) => Err(crate::error::Error::Connection(format!("MCP session failed: {}", e))),
+    };
+
+    // The certificate session ends with the connection
+    if let Some(token) = session_token {
+        if let Err(e) = security_manager.revoke_token(&token).await {
+            tracing::warn!("Failed to end certificate session for '{}': {}", common_name, e);
+        }
     }
+    result
 }
 
-// McpServerV2 acts as a coordinator - the actual MCP handling is done by BevyDebuggerTools
+// McpServerV2 acts as a coordinator - the actual MCP handling is done by SecureMcpTools
 // No ServerHandler implementation needed here since tools handle the MCP protocol directly
\ No newline at end of file
@@ -37,6 +37,12 @@ pub struct ObserveRequest {
     pub detailed: bool,
     #[serde(default)]
     pub reflection: bool,
+    /// Return only entities added, removed or changed since the previous poll
+    #[serde(default)]
+    pub delta: bool,
+    /// Sub-queries to run alongside the query: "hierarchy", "components", "schemas"
+    #[serde(default)]
+    pub include: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -120,6 +126,8 @@ impl BevyDebuggerTools {
             "diff": req.diff,
             "detailed": req.detailed,
             "reflection": req.reflection,
+            "include": req.include,
+            "delta": req.delta,
         });
         
         match observe::handle(arguments, self.brp_client.clone()).await {
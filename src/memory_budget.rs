@@ -0,0 +1,328 @@
+/*
+ * Bevy Debugger MCP Server - Process Memory Budget
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// A subsystem whose buffers count against the memory budget
+#[async_trait]
+pub trait MemoryConsumer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Consumers with lower priorities are evicted from first
+    fn eviction_priority(&self) -> u8 {
+        50
+    }
+
+    /// Approximate bytes currently held
+    async fn memory_usage(&self) -> usize;
+
+    /// Drop the least valuable data until about `bytes` are freed, returning how many were
+    async fn evict(&self, bytes: usize) -> usize;
+}
+
+/// Ceiling for the memory held by all registered subsystems together
+#[derive(Debug, Clone)]
+pub struct MemoryBudgetConfig {
+    pub ceiling_bytes: usize,
+    /// Share of the ceiling that triggers eviction
+    pub high_watermark: f64,
+    /// Share of the ceiling eviction brings usage back down to
+    pub low_watermark: f64,
+    pub check_interval: Duration,
+}
+
+impl Default for MemoryBudgetConfig {
+    fn default() -> Self {
+        Self {
+            ceiling_bytes: 256 * 1024 * 1024, // 256MB
+            high_watermark: 0.9,
+            low_watermark: 0.7,
+            check_interval: Duration::from_secs(15),
+        }
+    }
+}
+
+impl MemoryBudgetConfig {
+    /// Defaults with the ceiling taken from `BEVY_MCP_MEMORY_CEILING_MB`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            ceiling_bytes: env::var("BEVY_MCP_MEMORY_CEILING_MB")
+                .ok()
+                .and_then(|v| v.parse::<usize>().ok())
+                .map(|mb| mb * 1024 * 1024)
+                .unwrap_or(defaults.ceiling_bytes),
+            ..defaults
+        }
+    }
+}
+
+/// Memory held by one subsystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemUsage {
+    pub name: String,
+    pub bytes: usize,
+    pub eviction_priority: u8,
+}
+
+/// Budget usage across every registered subsystem
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryBudgetReport {
+    pub ceiling_bytes: usize,
+    pub used_bytes: usize,
+    pub utilization: f64,
+    pub subsystems: Vec<SubsystemUsage>,
+    pub eviction_runs: u64,
+    pub bytes_evicted: u64,
+}
+
+/// Shared memory ceiling for caches, recordings, the audit log and the dead letter queue
+///
+/// Each subsystem keeps its own limits; the budget only steps in when their
+/// combined usage passes the high watermark, evicting from the lowest
+/// priority subsystems first until usage is back at the low watermark.
+pub struct MemoryBudget {
+    config: MemoryBudgetConfig,
+    consumers: RwLock<Vec<Arc<dyn MemoryConsumer>>>,
+    eviction_runs: AtomicU64,
+    bytes_evicted: AtomicU64,
+}
+
+impl std::fmt::Debug for MemoryBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("config", &self.config)
+            .field("eviction_runs", &self.eviction_runs)
+            .finish()
+    }
+}
+
+static GLOBAL_BUDGET: OnceLock<Arc<MemoryBudget>> = OnceLock::new();
+
+/// The process-wide budget, configured from the environment and checked periodically
+pub fn global_memory_budget() -> Arc<MemoryBudget> {
+    GLOBAL_BUDGET
+        .get_or_init(|| {
+            let budget = Arc::new(MemoryBudget::new(MemoryBudgetConfig::from_env()));
+            budget.clone().start();
+            budget
+        })
+        .clone()
+}
+
+impl MemoryBudget {
+    pub fn new(config: MemoryBudgetConfig) -> Self {
+        Self {
+            config,
+            consumers: RwLock::new(Vec::new()),
+            eviction_runs: AtomicU64::new(0),
+            bytes_evicted: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &MemoryBudgetConfig {
+        &self.config
+    }
+
+    /// Count `consumer` against the budget, replacing any consumer with the same name
+    pub async fn register(&self, consumer: Arc<dyn MemoryConsumer>) {
+        let mut consumers = self.consumers.write().await;
+        consumers.retain(|existing| existing.name() != consumer.name());
+        consumers.push(consumer);
+    }
+
+    pub async fn report(&self) -> MemoryBudgetReport {
+        let subsystems = self.usages().await;
+        let used_bytes = subsystems.iter().map(|(_, usage)| usage.bytes).sum();
+        MemoryBudgetReport {
+            ceiling_bytes: self.config.ceiling_bytes,
+            used_bytes,
+            utilization: if self.config.ceiling_bytes > 0 {
+                used_bytes as f64 / self.config.ceiling_bytes as f64
+            } else {
+                0.0
+            },
+            subsystems: subsystems.into_iter().map(|(_, usage)| usage).collect(),
+            eviction_runs: self.eviction_runs.load(Ordering::Relaxed),
+            bytes_evicted: self.bytes_evicted.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Evict if usage is over the high watermark, returning the bytes freed
+    pub async fn enforce(&self) -> usize {
+        let mut usages = self.usages().await;
+        let used: usize = usages.iter().map(|(_, usage)| usage.bytes).sum();
+        let ceiling = self.config.ceiling_bytes as f64;
+        if (used as f64) <= ceiling * self.config.high_watermark {
+            return 0;
+        }
+
+        let target = (ceiling * self.config.low_watermark) as usize;
+        let mut remaining = used.saturating_sub(target);
+        warn!(
+            "Memory budget exceeded ({} of {} bytes); evicting {} bytes",
+            used, self.config.ceiling_bytes, remaining
+        );
+        usages.sort_by(|(_, a), (_, b)| {
+            a.eviction_priority
+                .cmp(&b.eviction_priority)
+                .then(b.bytes.cmp(&a.bytes))
+        });
+
+        let mut freed = 0;
+        for (consumer, usage) in usages {
+            if remaining == 0 {
+                break;
+            }
+            if usage.bytes == 0 {
+                continue;
+            }
+            let evicted = consumer.evict(remaining.min(usage.bytes)).await;
+            debug!("Evicted {} bytes from {}", evicted, usage.name);
+            freed += evicted;
+            remaining = remaining.saturating_sub(evicted);
+        }
+
+        self.eviction_runs.fetch_add(1, Ordering::Relaxed);
+        self.bytes_evicted
+            .fetch_add(freed as u64, Ordering::Relaxed);
+        freed
+    }
+
+    /// Check the budget every `check_interval` in the background
+    pub fn start(self: Arc<Self>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No async runtime; memory budget will only be enforced on demand");
+            return;
+        };
+        runtime.spawn(async move {
+            let mut interval = tokio::time::interval(self.config.check_interval);
+            loop {
+                interval.tick().await;
+                self.enforce().await;
+            }
+        });
+    }
+
+    async fn usages(&self) -> Vec<(Arc<dyn MemoryConsumer>, SubsystemUsage)> {
+        let consumers = self.consumers.read().await.clone();
+        let mut usages = Vec::with_capacity(consumers.len());
+        for consumer in consumers {
+            let usage = SubsystemUsage {
+                name: consumer.name().to_string(),
+                bytes: consumer.memory_usage().await,
+                eviction_priority: consumer.eviction_priority(),
+            };
+            usages.push((consumer, usage));
+        }
+        usages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct Buffer {
+        name: &'static str,
+        priority: u8,
+        bytes: AtomicUsize,
+    }
+
+    impl Buffer {
+        fn new(name: &'static str, priority: u8, bytes: usize) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                priority,
+                bytes: AtomicUsize::new(bytes),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl MemoryConsumer for Buffer {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn eviction_priority(&self) -> u8 {
+            self.priority
+        }
+
+        async fn memory_usage(&self) -> usize {
+            self.bytes.load(Ordering::Relaxed)
+        }
+
+        async fn evict(&self, bytes: usize) -> usize {
+            let held = self.bytes.load(Ordering::Relaxed);
+            let freed = bytes.min(held);
+            self.bytes.store(held - freed, Ordering::Relaxed);
+            freed
+        }
+    }
+
+    fn budget() -> MemoryBudget {
+        MemoryBudget::new(MemoryBudgetConfig {
+            ceiling_bytes: 1000,
+            ..MemoryBudgetConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_evicts_lowest_priority_first() {
+        let budget = budget();
+        let cache = Buffer::new("cache", 10, 600);
+        let audit = Buffer::new("audit", 90, 350);
+        budget.register(cache.clone()).await;
+        budget.register(audit.clone()).await;
+
+        // 950 bytes is over the 900 byte high watermark; back to 700
+        assert_eq!(budget.enforce().await, 250);
+        assert_eq!(cache.memory_usage().await, 350);
+        assert_eq!(audit.memory_usage().await, 350);
+
+        let report = budget.report().await;
+        assert_eq!(report.used_bytes, 700);
+        assert_eq!((report.eviction_runs, report.bytes_evicted), (1, 250));
+        assert_eq!(budget.enforce().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_spills_into_next_subsystem() {
+        let budget = budget();
+        let dlq = Buffer::new("dead_letter_queue", 40, 100);
+        let recording = Buffer::new("recording", 20, 500);
+        budget.register(dlq.clone()).await;
+        budget.register(recording.clone()).await;
+        budget.register(Buffer::new("recording", 20, 900)).await;
+
+        // The re-registered recording replaces the first one
+        assert_eq!(budget.report().await.subsystems.len(), 2);
+        assert_eq!(budget.enforce().await, 300);
+        assert_eq!(dlq.memory_usage().await, 100);
+    }
+}
@@ -0,0 +1,292 @@
+/*
+ * Bevy Debugger MCP Server - Metrics Time Series Store
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Recent metric values kept for external dashboards
+//!
+//! Each named series is a ring buffer of timestamped values. The
+//! `metrics_query` tool downsamples them over a window into the
+//! `{"target", "datapoints": [[value, timestamp_ms], ...]}` shape Grafana's
+//! JSON data sources expect. Series recorded by the server:
+//!
+//! | Series | Source |
+//! |--------|--------|
+//! | `frame_time_ms` | Frames recorded with `perf_timeline` |
+//! | `entity_count` | Frames recorded with `perf_timeline` |
+//! | `memory_rss_bytes` | Sampled every [`MEMORY_SAMPLE_INTERVAL`] |
+//! | `tool_latency_ms.<tool>` | Every MCP tool call |
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{Error, Result};
+
+/// Values kept per series; about 14 hours of memory samples
+pub const DEFAULT_SERIES_CAPACITY: usize = 10_000;
+
+/// Window queried when a request names no start
+pub const DEFAULT_QUERY_WINDOW_MS: u64 = 5 * 60 * 1000;
+
+/// How often process memory is recorded
+pub const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A recorded value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricPoint {
+    pub timestamp_ms: u64,
+    pub value: f64,
+}
+
+/// How the values falling into one downsampled point are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Avg,
+    Min,
+    Max,
+}
+
+impl FromStr for Aggregation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "avg" => Ok(Self::Avg),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            other => Err(Error::Validation(format!(
+                "Unknown aggregation '{other}' (expected avg, min or max)"
+            ))),
+        }
+    }
+}
+
+/// A downsampled series in Grafana's JSON data source shape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesResponse {
+    pub target: String,
+    /// `[value, timestamp_ms]` pairs, oldest first
+    pub datapoints: Vec<[f64; 2]>,
+}
+
+/// Named ring buffers of metric values
+#[derive(Debug)]
+pub struct MetricsStore {
+    series: Mutex<BTreeMap<String, VecDeque<MetricPoint>>>,
+    capacity: usize,
+}
+
+impl MetricsStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            series: Mutex::new(BTreeMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record `value` for `name` now
+    pub fn record(&self, name: &str, value: f64) {
+        self.record_at(name, now_ms(), value);
+    }
+
+    /// Record `value` for `name` at `timestamp_ms`, evicting the oldest value when full
+    pub fn record_at(&self, name: &str, timestamp_ms: u64, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        let Ok(mut series) = self.series.lock() else {
+            return;
+        };
+        let points = series.entry(name.to_string()).or_default();
+        if points.len() >= self.capacity {
+            points.pop_front();
+        }
+        points.push_back(MetricPoint {
+            timestamp_ms,
+            value,
+        });
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.series
+            .lock()
+            .map(|series| series.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The most recent value of every series
+    pub fn latest(&self) -> BTreeMap<String, f64> {
+        self.series
+            .lock()
+            .map(|series| {
+                series
+                    .iter()
+                    .filter_map(|(name, points)| Some((name.clone(), points.back()?.value)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Series matching `selectors` downsampled to at most `max_points` over `[from_ms, to_ms]`
+    ///
+    /// A selector matches the series of that name and, for `tool_latency_ms`
+    /// style prefixes, every `<selector>.<label>` series. No selectors means
+    /// every series.
+    pub fn query(
+        &self,
+        selectors: &[String],
+        from_ms: u64,
+        to_ms: u64,
+        max_points: usize,
+        aggregation: Aggregation,
+    ) -> Vec<SeriesResponse> {
+        let Ok(series) = self.series.lock() else {
+            return Vec::new();
+        };
+        series
+            .iter()
+            .filter(|(name, _)| {
+                selectors.is_empty()
+                    || selectors.iter().any(|selector| {
+                        *name == selector
+                            || name
+                                .strip_prefix(selector.as_str())
+                                .is_some_and(|rest| rest.starts_with('.'))
+                    })
+            })
+            .map(|(name, points)| {
+                let in_window: Vec<MetricPoint> = points
+                    .iter()
+                    .filter(|p| p.timestamp_ms >= from_ms && p.timestamp_ms <= to_ms)
+                    .copied()
+                    .collect();
+                SeriesResponse {
+                    target: name.clone(),
+                    datapoints: downsample(&in_window, from_ms, to_ms, max_points, aggregation),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for MetricsStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_SERIES_CAPACITY)
+    }
+}
+
+/// Combine `points` into at most `max_points` equal-width buckets
+///
+/// Each bucket becomes one `[value, timestamp_ms]` pair stamped with the
+/// bucket start; empty buckets are omitted.
+pub fn downsample(
+    points: &[MetricPoint],
+    from_ms: u64,
+    to_ms: u64,
+    max_points: usize,
+    aggregation: Aggregation,
+) -> Vec<[f64; 2]> {
+    if points.is_empty() || max_points == 0 {
+        return Vec::new();
+    }
+
+    let span = to_ms.saturating_sub(from_ms) + 1;
+    let bucket_width = ((span + max_points as u64 - 1) / max_points as u64).max(1);
+
+    let mut buckets: Vec<Vec<f64>> = vec![Vec::new(); max_points];
+    for point in points {
+        let index = (point.timestamp_ms.saturating_sub(from_ms) / bucket_width) as usize;
+        buckets[index.min(max_points - 1)].push(point.value);
+    }
+
+    buckets
+        .into_iter()
+        .enumerate()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(i, values)| {
+            let value = match aggregation {
+                Aggregation::Avg => values.iter().sum::<f64>() / values.len() as f64,
+                Aggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+                Aggregation::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            };
+            [value, (from_ms + i as u64 * bucket_width) as f64]
+        })
+        .collect()
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The store the server records into
+pub fn global() -> Arc<MetricsStore> {
+    static GLOBAL: OnceLock<Arc<MetricsStore>> = OnceLock::new();
+    GLOBAL.get_or_init(Default::default).clone()
+}
+
+/// Record `value` for `name` in the global store
+pub fn record(name: &str, value: f64) {
+    global().record(name, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_downsamples_matching_series() {
+        let store = MetricsStore::new(100);
+        for i in 0..10u64 {
+            store.record_at("frame_time_ms", 1_000 + i * 100, i as f64);
+        }
+        store.record_at("tool_latency_ms.observe", 1_500, 12.0);
+        store.record_at("tool_latency_ms.replay", 1_500, 40.0);
+        store.record_at("tool_latency_msx", 1_500, 1.0);
+
+        let frames = store.query(
+            &["frame_time_ms".to_string()],
+            1_000,
+            1_999,
+            2,
+            Aggregation::Avg,
+        );
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].datapoints, vec![[2.0, 1_000.0], [7.0, 1_500.0]]);
+
+        let peaks = store.query(&[], 1_000, 1_999, 1, Aggregation::Max);
+        assert_eq!(peaks.len(), 4);
+
+        let latency = store.query(
+            &["tool_latency_ms".to_string()],
+            1_000,
+            1_999,
+            10,
+            Aggregation::Avg,
+        );
+        let targets: Vec<&str> = latency.iter().map(|s| s.target.as_str()).collect();
+        assert_eq!(
+            targets,
+            vec!["tool_latency_ms.observe", "tool_latency_ms.replay"]
+        );
+    }
+}
@@ -0,0 +1,276 @@
+/*
+ * Bevy Debugger MCP Server - Mutual TLS Client Authentication
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::oidc::parse_role;
+use crate::security::Role;
+
+/// Subject prefix of sessions whose certificate maps straight to a role
+pub const CERT_SUBJECT_PREFIX: &str = "cert:";
+
+/// Who a client certificate authenticates as
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CertIdentity {
+    /// An existing local user; the user's role applies
+    User(String),
+    /// No local user, just a role
+    Role(Role),
+}
+
+/// TLS settings for the TCP transport
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MtlsConfig {
+    /// PEM server certificate chain
+    pub cert_path: String,
+    /// PEM server private key
+    pub key_path: String,
+    /// PEM CA certificates client certificates must chain to
+    pub client_ca_path: String,
+    /// Address the TLS listener binds to
+    pub bind_address: String,
+    /// Client certificate CNs and who they authenticate as
+    pub identity_mapping: Vec<(String, CertIdentity)>,
+    /// Still require a JWT on every call; the certificate only gates the connection
+    pub require_jwt: bool,
+}
+
+impl MtlsConfig {
+    /// Read settings from `BEVY_MCP_TLS_*`; `None` when no server certificate is configured
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(cert_path) = env::var("BEVY_MCP_TLS_CERT") else {
+            return Ok(None);
+        };
+        let required = |name: &str| {
+            env::var(name).map_err(|_| {
+                Error::SecurityError(format!(
+                    "{} is required when BEVY_MCP_TLS_CERT is set",
+                    name
+                ))
+            })
+        };
+        let identity_mapping = env::var("BEVY_MCP_TLS_CN_MAP")
+            .map(|map| parse_cn_mapping(&map))
+            .unwrap_or_else(|_| Ok(Vec::new()))?;
+
+        Ok(Some(Self {
+            cert_path,
+            key_path: required("BEVY_MCP_TLS_KEY")?,
+            client_ca_path: required("BEVY_MCP_TLS_CLIENT_CA")?,
+            bind_address: env::var("BEVY_MCP_TLS_BIND").unwrap_or_else(|_| "127.0.0.1".to_string()),
+            identity_mapping,
+            require_jwt: env::var("BEVY_MCP_TLS_REQUIRE_JWT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(false),
+        }))
+    }
+
+    /// Identity of a verified client certificate's CN
+    pub fn identity_for(&self, common_name: &str) -> Result<&CertIdentity> {
+        self.identity_mapping
+            .iter()
+            .find(|(cn, _)| cn == common_name)
+            .map(|(_, identity)| identity)
+            .ok_or_else(|| {
+                Error::SecurityError(format!(
+                    "No identity mapped for client certificate CN '{}'",
+                    common_name
+                ))
+            })
+    }
+}
+
+/// Parse `CN=identity` pairs separated by commas
+///
+/// The identity is a role name (`Viewer`, `Developer`, `Admin`) or else the
+/// username of a local user.
+pub fn parse_cn_mapping(map: &str) -> Result<Vec<(String, CertIdentity)>> {
+    map.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (cn, identity) = entry.rsplit_once('=').ok_or_else(|| {
+                Error::SecurityError(format!("Invalid certificate mapping entry: {}", entry))
+            })?;
+            let (cn, identity) = (cn.trim(), identity.trim());
+            if cn.is_empty() || identity.is_empty() {
+                return Err(Error::SecurityError(format!(
+                    "Invalid certificate mapping entry: {}",
+                    entry
+                )));
+            }
+            let identity = parse_role(identity)
+                .map(CertIdentity::Role)
+                .unwrap_or_else(|_| CertIdentity::User(identity.to_string()));
+            Ok((cn.to_string(), identity))
+        })
+        .collect()
+}
+
+#[cfg(feature = "mtls")]
+pub use acceptor::MtlsAcceptor;
+
+#[cfg(feature = "mtls")]
+mod acceptor {
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::sync::Arc;
+
+    use tokio::net::TcpStream;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+    use tokio_rustls::rustls::server::WebPkiClientVerifier;
+    use tokio_rustls::rustls::{RootCertStore, ServerConfig};
+    use tokio_rustls::server::TlsStream;
+    use tokio_rustls::TlsAcceptor;
+
+    use super::MtlsConfig;
+    use crate::error::{Error, Result};
+
+    /// Accepts TLS connections that present a certificate signed by the client CA
+    #[derive(Clone)]
+    pub struct MtlsAcceptor {
+        acceptor: TlsAcceptor,
+    }
+
+    impl MtlsAcceptor {
+        pub fn new(config: &MtlsConfig) -> Result<Self> {
+            let certs = load_certs(&config.cert_path)?;
+            let key = load_key(&config.key_path)?;
+
+            let mut roots = RootCertStore::empty();
+            for ca in load_certs(&config.client_ca_path)? {
+                roots
+                    .add(ca)
+                    .map_err(|e| Error::SecurityError(format!("Invalid client CA: {}", e)))?;
+            }
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .map_err(|e| Error::SecurityError(format!("Invalid client CA: {}", e)))?;
+
+            let server_config = ServerConfig::builder()
+                .with_client_cert_verifier(verifier)
+                .with_single_cert(certs, key)
+                .map_err(|e| Error::SecurityError(format!("Invalid server certificate: {}", e)))?;
+
+            Ok(Self {
+                acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            })
+        }
+
+        /// Complete the handshake and return the stream with the client certificate's CN
+        pub async fn accept(&self, stream: TcpStream) -> Result<(TlsStream<TcpStream>, String)> {
+            let tls_stream = self
+                .acceptor
+                .accept(stream)
+                .await
+                .map_err(|e| Error::SecurityError(format!("TLS handshake failed: {}", e)))?;
+
+            let common_name = {
+                let (_, connection) = tls_stream.get_ref();
+                let leaf = connection
+                    .peer_certificates()
+                    .and_then(|certs| certs.first())
+                    .ok_or_else(|| {
+                        Error::SecurityError("Client presented no certificate".to_string())
+                    })?;
+                common_name(leaf)?
+            };
+            Ok((tls_stream, common_name))
+        }
+    }
+
+    fn common_name(cert: &CertificateDer<'_>) -> Result<String> {
+        let (_, parsed) = x509_parser::parse_x509_certificate(cert.as_ref())
+            .map_err(|e| Error::SecurityError(format!("Invalid client certificate: {}", e)))?;
+        let cn = parsed
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| Error::SecurityError("Client certificate has no CN".to_string()));
+        cn
+    }
+
+    fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let certs = rustls_pemfile::certs(&mut reader).collect::<std::io::Result<Vec<_>>>()?;
+        if certs.is_empty() {
+            return Err(Error::SecurityError(format!(
+                "No certificates found in {}",
+                path
+            )));
+        }
+        Ok(certs)
+    }
+
+    fn load_key(path: &str) -> Result<PrivateKeyDer<'static>> {
+        let mut reader = BufReader::new(File::open(path)?);
+        rustls_pemfile::private_key(&mut reader)?
+            .ok_or_else(|| Error::SecurityError(format!("No private key found in {}", path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cn_mapping() {
+        let mapping =
+            parse_cn_mapping("ci-runner.studio=Developer, alice.studio = alice ,qa=viewer")
+                .unwrap();
+        assert_eq!(
+            mapping,
+            vec![
+                (
+                    "ci-runner.studio".to_string(),
+                    CertIdentity::Role(Role::Developer)
+                ),
+                (
+                    "alice.studio".to_string(),
+                    CertIdentity::User("alice".to_string())
+                ),
+                ("qa".to_string(), CertIdentity::Role(Role::Viewer)),
+            ]
+        );
+        assert!(parse_cn_mapping("no-identity").is_err());
+        assert!(parse_cn_mapping("=Admin").is_err());
+    }
+
+    #[test]
+    fn test_identity_for_unknown_cn_is_rejected() {
+        let config = MtlsConfig {
+            cert_path: "server.pem".to_string(),
+            key_path: "server.key".to_string(),
+            client_ca_path: "ca.pem".to_string(),
+            bind_address: "127.0.0.1".to_string(),
+            identity_mapping: parse_cn_mapping("build-farm=Admin").unwrap(),
+            require_jwt: false,
+        };
+        assert_eq!(
+            config.identity_for("build-farm").unwrap(),
+            &CertIdentity::Role(Role::Admin)
+        );
+        assert!(config.identity_for("Build-Farm").is_err());
+    }
+}
@@ -0,0 +1,434 @@
+/*
+ * Bevy Debugger MCP Server - OAuth2 / OIDC Token Validation
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::error::{Error, Result};
+use crate::security::{Claims, Role};
+
+/// How long fetched signing keys are trusted before they are fetched again
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Minimum time between refetches triggered by an unknown key id
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Signature algorithms accepted from the identity provider
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+    Algorithm::EdDSA,
+];
+
+/// External identity provider settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcConfig {
+    /// Expected `iss` claim; also used for discovery when `jwks_url` is unset
+    pub issuer: String,
+    /// Where the provider publishes its signing keys
+    pub jwks_url: Option<String>,
+    /// Expected `aud` claim
+    pub audience: String,
+    /// Claim holding the user's roles or groups; dots address nested claims
+    pub role_claim: String,
+    /// Claim values and the role they grant; the highest matching role wins
+    pub role_mapping: Vec<(String, Role)>,
+    /// Role for authenticated users without a mapped claim value; rejected when `None`
+    pub default_role: Option<Role>,
+}
+
+impl OidcConfig {
+    /// Read settings from `BEVY_MCP_OIDC_*`; `None` when no issuer is configured
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(issuer) = env::var("BEVY_MCP_OIDC_ISSUER") else {
+            return Ok(None);
+        };
+        let audience = env::var("BEVY_MCP_OIDC_AUDIENCE").map_err(|_| {
+            Error::SecurityError(
+                "BEVY_MCP_OIDC_AUDIENCE is required when BEVY_MCP_OIDC_ISSUER is set".to_string(),
+            )
+        })?;
+        let role_mapping = env::var("BEVY_MCP_OIDC_ROLE_MAP")
+            .map(|map| parse_role_mapping(&map))
+            .unwrap_or_else(|_| Ok(Vec::new()))?;
+        let default_role = env::var("BEVY_MCP_OIDC_DEFAULT_ROLE")
+            .ok()
+            .map(|role| parse_role(&role))
+            .transpose()?;
+
+        Ok(Some(Self {
+            issuer,
+            jwks_url: env::var("BEVY_MCP_OIDC_JWKS_URL").ok(),
+            audience,
+            role_claim: env::var("BEVY_MCP_OIDC_ROLE_CLAIM")
+                .unwrap_or_else(|_| "roles".to_string()),
+            role_mapping,
+            default_role,
+        }))
+    }
+
+    /// Role granted by a token's claims
+    pub fn map_role(&self, claims: &Value) -> Result<Role> {
+        let values = claim_values(claims, &self.role_claim);
+        self.role_mapping
+            .iter()
+            .filter(|(value, _)| values.iter().any(|v| v == value))
+            .map(|(_, role)| role.clone())
+            .max_by_key(Role::level)
+            .or_else(|| self.default_role.clone())
+            .ok_or_else(|| {
+                Error::SecurityError(format!(
+                    "No role mapped from claim '{}' in identity provider token",
+                    self.role_claim
+                ))
+            })
+    }
+}
+
+/// Parse `value=Role` pairs separated by commas
+pub fn parse_role_mapping(map: &str) -> Result<Vec<(String, Role)>> {
+    map.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (value, role) = entry.split_once('=').ok_or_else(|| {
+                Error::SecurityError(format!("Invalid role mapping entry: {}", entry))
+            })?;
+            Ok((value.trim().to_string(), parse_role(role.trim())?))
+        })
+        .collect()
+}
+
+pub(crate) fn parse_role(role: &str) -> Result<Role> {
+    match role.to_lowercase().as_str() {
+        "guest" => Ok(Role::Guest),
+        "viewer" => Ok(Role::Viewer),
+        "developer" => Ok(Role::Developer),
+        "admin" => Ok(Role::Admin),
+        _ => Err(Error::SecurityError(format!("Unknown role: {}", role))),
+    }
+}
+
+/// String values of a (possibly nested) claim; single strings count as one value
+fn claim_values(claims: &Value, path: &str) -> Vec<String> {
+    let claim = path
+        .split('.')
+        .try_fold(claims, |value, key| value.get(key));
+    match claim {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Registered claims read from provider tokens
+#[derive(Debug, Deserialize)]
+struct ProviderClaims {
+    sub: String,
+    exp: u64,
+    #[serde(default)]
+    iat: u64,
+    jti: Option<String>,
+    sid: Option<String>,
+}
+
+#[derive(Debug)]
+struct CachedKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Validates tokens issued by an external identity provider
+#[derive(Debug)]
+pub struct OidcValidator {
+    config: OidcConfig,
+    keys: RwLock<Option<CachedKeys>>,
+    /// Keys given at construction are never refetched
+    static_keys: bool,
+}
+
+impl OidcValidator {
+    /// Validator that fetches the provider's signing keys on demand
+    pub fn new(config: OidcConfig) -> Self {
+        Self {
+            config,
+            keys: RwLock::new(None),
+            static_keys: false,
+        }
+    }
+
+    /// Validator with a fixed key set, for offline providers and tests
+    pub fn with_keys(config: OidcConfig, keys: JwkSet) -> Self {
+        Self {
+            config,
+            keys: RwLock::new(Some(CachedKeys {
+                keys,
+                fetched_at: Instant::now(),
+            })),
+            static_keys: true,
+        }
+    }
+
+    pub fn config(&self) -> &OidcConfig {
+        &self.config
+    }
+
+    /// Whether a token should be validated by the provider rather than locally
+    pub fn handles(token: &str) -> bool {
+        decode_header(token).is_ok_and(|header| ALLOWED_ALGORITHMS.contains(&header.alg))
+    }
+
+    /// Validate a provider token and translate it into local claims
+    pub async fn validate(&self, token: &str) -> Result<Claims> {
+        let header = decode_header(token)
+            .map_err(|e| Error::SecurityError(format!("Invalid token: {}", e)))?;
+        if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+            return Err(Error::SecurityError(format!(
+                "Token algorithm {:?} is not accepted from the identity provider",
+                header.alg
+            )));
+        }
+        let kid = header
+            .kid
+            .ok_or_else(|| Error::SecurityError("Token has no key id".to_string()))?;
+        let key = self.decoding_key(&kid).await?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = 30;
+        validation.set_issuer(&[&self.config.issuer]);
+        validation.set_audience(&[&self.config.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let raw = decode::<Value>(token, &key, &validation)
+            .map_err(|e| Error::SecurityError(format!("Invalid identity provider token: {}", e)))?
+            .claims;
+        let role = self.config.map_role(&raw)?;
+        let provider: ProviderClaims = serde_json::from_value(raw)?;
+
+        // Provider tokens have no local session; the provider's session id
+        // (or the subject) groups them for auditing
+        let jti = provider
+            .jti
+            .unwrap_or_else(|| format!("oidc:{}:{}", provider.sub, provider.iat));
+        let session_id = format!(
+            "oidc:{}",
+            provider.sid.unwrap_or_else(|| provider.sub.clone())
+        );
+        Ok(Claims {
+            sub: provider.sub,
+            role,
+            exp: provider.exp,
+            iat: provider.iat,
+            jti,
+            session_id,
+        })
+    }
+
+    async fn decoding_key(&self, kid: &str) -> Result<DecodingKey> {
+        {
+            let cached = self.keys.read().await;
+            if let Some(cached) = cached.as_ref() {
+                let fresh = self.static_keys || cached.fetched_at.elapsed() < JWKS_CACHE_TTL;
+                if let (true, Some(jwk)) = (fresh, cached.keys.find(kid)) {
+                    return DecodingKey::from_jwk(jwk)
+                        .map_err(|e| Error::SecurityError(format!("Invalid signing key: {}", e)));
+                }
+                // Unknown key ids may mean the provider rotated keys, but do not
+                // let arbitrary tokens trigger a fetch every time
+                if self.static_keys || cached.fetched_at.elapsed() < JWKS_MIN_REFRESH_INTERVAL {
+                    return Err(Error::SecurityError(format!(
+                        "Unknown signing key: {}",
+                        kid
+                    )));
+                }
+            }
+        }
+
+        let keys = self.fetch_keys().await?;
+        let key = keys
+            .find(kid)
+            .map(DecodingKey::from_jwk)
+            .transpose()
+            .map_err(|e| Error::SecurityError(format!("Invalid signing key: {}", e)))?;
+        *self.keys.write().await = Some(CachedKeys {
+            keys,
+            fetched_at: Instant::now(),
+        });
+        key.ok_or_else(|| Error::SecurityError(format!("Unknown signing key: {}", kid)))
+    }
+
+    async fn fetch_keys(&self) -> Result<JwkSet> {
+        let jwks_url = match &self.config.jwks_url {
+            Some(url) => url.clone(),
+            None => {
+                let discovery_url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let discovery = fetch_json(&discovery_url).await?;
+                discovery
+                    .get("jwks_uri")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| {
+                        Error::SecurityError("OIDC discovery document has no jwks_uri".to_string())
+                    })?
+            }
+        };
+
+        debug!("Fetching identity provider keys from {}", jwks_url);
+        let keys: JwkSet = serde_json::from_value(fetch_json(&jwks_url).await?)?;
+        info!("Loaded {} identity provider signing keys", keys.keys.len());
+        Ok(keys)
+    }
+}
+
+#[cfg(feature = "oidc")]
+async fn fetch_json(url: &str) -> Result<Value> {
+    let response = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| Error::SecurityError(format!("Failed to fetch {}: {}", url, e)))?;
+    response
+        .json()
+        .await
+        .map_err(|e| Error::SecurityError(format!("Invalid JSON from {}: {}", url, e)))
+}
+
+#[cfg(not(feature = "oidc"))]
+async fn fetch_json(url: &str) -> Result<Value> {
+    tracing::warn!("Cannot fetch {}: built without the 'oidc' feature", url);
+    Err(Error::SecurityError(
+        "Fetching identity provider keys requires the 'oidc' feature".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use serde_json::json;
+
+    fn test_config() -> OidcConfig {
+        OidcConfig {
+            issuer: "https://sso.example.com".to_string(),
+            jwks_url: None,
+            audience: "bevy-debugger".to_string(),
+            role_claim: "realm_access.roles".to_string(),
+            role_mapping: parse_role_mapping("debug-viewers=Viewer, debug-admins=admin").unwrap(),
+            default_role: None,
+        }
+    }
+
+    /// A provider key pair and a validator trusting it
+    fn test_provider() -> (EncodingKey, OidcValidator) {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let x = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(pair.public_key().as_ref());
+        let keys: JwkSet = serde_json::from_value(json!({
+            "keys": [{"kty": "OKP", "crv": "Ed25519", "x": x, "kid": "key-1", "alg": "EdDSA"}]
+        }))
+        .unwrap();
+        (
+            EncodingKey::from_ed_der(pkcs8.as_ref()),
+            OidcValidator::with_keys(test_config(), keys),
+        )
+    }
+
+    fn sign(key: &EncodingKey, kid: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(kid.to_string());
+        encode(&header, &claims, key).unwrap()
+    }
+
+    fn exp() -> u64 {
+        chrono::Utc::now().timestamp() as u64 + 600
+    }
+
+    #[tokio::test]
+    async fn test_validates_provider_token_and_maps_role() {
+        let (key, validator) = test_provider();
+        let token = sign(
+            &key,
+            "key-1",
+            json!({
+                "iss": "https://sso.example.com",
+                "aud": "bevy-debugger",
+                "sub": "jdoe",
+                "exp": exp(),
+                "sid": "abc",
+                "realm_access": {"roles": ["debug-viewers", "debug-admins"]}
+            }),
+        );
+        assert!(OidcValidator::handles(&token));
+
+        let claims = validator.validate(&token).await.unwrap();
+        assert_eq!(claims.sub, "jdoe");
+        assert_eq!(claims.role, Role::Admin);
+        assert_eq!(claims.session_id, "oidc:abc");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_audience_unknown_key_and_unmapped_role() {
+        let (key, validator) = test_provider();
+        let claims = |aud: &str, roles: Value| {
+            json!({
+                "iss": "https://sso.example.com",
+                "aud": aud,
+                "sub": "jdoe",
+                "exp": exp(),
+                "realm_access": {"roles": roles}
+            })
+        };
+
+        let wrong_audience = sign(&key, "key-1", claims("other-app", json!(["debug-admins"])));
+        assert!(validator.validate(&wrong_audience).await.is_err());
+
+        let unknown_key = sign(
+            &key,
+            "key-2",
+            claims("bevy-debugger", json!(["debug-admins"])),
+        );
+        assert!(validator.validate(&unknown_key).await.is_err());
+
+        let unmapped = sign(&key, "key-1", claims("bevy-debugger", json!(["artists"])));
+        assert!(validator.validate(&unmapped).await.is_err());
+
+        assert!(parse_role_mapping("admins:Admin").is_err());
+        assert!(parse_role_mapping("admins=Root").is_err());
+    }
+}
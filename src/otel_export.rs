@@ -0,0 +1,113 @@
+/*
+ * Bevy Debugger MCP Server - OpenTelemetry Trace Export
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Export `tracing` spans to an OpenTelemetry collector over OTLP
+//!
+//! Tool calls, orchestrator pipelines and steps, and BRP round trips are
+//! instrumented with ordinary `tracing` spans. When an OTLP endpoint is
+//! configured, [`layer`] turns those spans into OpenTelemetry spans, so one
+//! tool call shows up as a single trace from MCP dispatch down to each BRP
+//! request it made.
+
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{self, Sampler, Tracer};
+use opentelemetry_sdk::Resource;
+use std::time::Duration;
+use tracing::{info, Subscriber};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::ObservabilityConfig;
+use crate::error::{Error, Result};
+
+/// How long the exporter waits for the collector before dropping a batch
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A layer exporting spans to the configured OTLP endpoint
+///
+/// `None` when tracing is disabled or no endpoint is configured. Must be
+/// called from within a Tokio runtime, which runs the batch exporter.
+pub fn layer<S>(config: &ObservabilityConfig) -> Result<Option<OpenTelemetryLayer<S, Tracer>>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let endpoint = match &config.otlp_endpoint {
+        Some(endpoint) if config.tracing_enabled => endpoint,
+        _ => return Ok(None),
+    };
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint)
+                .with_timeout(EXPORT_TIMEOUT),
+        )
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler(config.sample_rate))
+                .with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", config.service_name.clone()),
+                    KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+                    KeyValue::new("deployment.environment", config.environment.clone()),
+                ])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .map_err(|e| Error::Config(format!("Failed to set up OTLP trace export: {e}")))?;
+
+    info!(
+        "Exporting traces to {} (sample rate {})",
+        endpoint, config.sample_rate
+    );
+    Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+/// Flush spans that haven't been exported yet
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Sample a fraction of new traces, following the caller's decision for the rest
+fn sampler(sample_rate: f64) -> Sampler {
+    let root = if sample_rate >= 1.0 {
+        Sampler::AlwaysOn
+    } else if sample_rate <= 0.0 {
+        Sampler::AlwaysOff
+    } else {
+        Sampler::TraceIdRatioBased(sample_rate)
+    };
+    Sampler::ParentBased(Box::new(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::Registry;
+
+    #[test]
+    fn test_no_layer_without_endpoint() {
+        let mut config = ObservabilityConfig::default();
+        assert!(layer::<Registry>(&config).unwrap().is_none());
+
+        config.otlp_endpoint = Some("http://localhost:4317".to_string());
+        config.tracing_enabled = false;
+        assert!(layer::<Registry>(&config).unwrap().is_none());
+    }
+}
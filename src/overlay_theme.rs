@@ -0,0 +1,281 @@
+/// Overlay theming
+///
+/// Resolves a `SetTheme` request into a palette and into per-overlay color
+/// configs. Presets include color-blind safe sets based on the Okabe-Ito
+/// palette; when a background is given, colors are pushed toward black or
+/// white until they reach the requested contrast ratio against it.
+use crate::brp_messages::{DebugOverlayType, OverlayTheme, ThemePalette, ThemePreset};
+use crate::error::{Error, Result};
+use serde_json::{json, Value};
+
+/// Contrast ratio used when the theme does not set one (WCAG graphics minimum)
+pub const DEFAULT_MIN_CONTRAST: f32 = 3.0;
+
+/// Largest possible contrast ratio (white on black)
+pub const MAX_CONTRAST: f32 = 21.0;
+
+/// Colors of a palette preset before any contrast adjustment
+pub fn preset_palette(preset: ThemePreset) -> ThemePalette {
+    match preset {
+        ThemePreset::Default => ThemePalette {
+            primary: [0.0, 1.0, 1.0, 1.0],
+            secondary: [0.3, 0.6, 1.0, 1.0],
+            accent: [1.0, 1.0, 0.0, 1.0],
+            positive: [0.2, 1.0, 0.2, 1.0],
+            warning: [1.0, 0.8, 0.0, 1.0],
+            critical: [1.0, 0.2, 0.2, 1.0],
+            neutral: [0.8, 0.8, 0.8, 1.0],
+            text: [1.0, 1.0, 1.0, 1.0],
+            heat: [
+                [0.0, 0.0, 1.0, 1.0],
+                [0.0, 1.0, 0.0, 1.0],
+                [1.0, 0.0, 0.0, 1.0],
+            ],
+        },
+        ThemePreset::HighContrast => ThemePalette {
+            primary: [0.0, 1.0, 1.0, 1.0],
+            secondary: [1.0, 0.0, 1.0, 1.0],
+            accent: [1.0, 1.0, 0.0, 1.0],
+            positive: [0.0, 1.0, 0.0, 1.0],
+            warning: [1.0, 0.5, 0.0, 1.0],
+            critical: [1.0, 0.0, 0.0, 1.0],
+            neutral: [1.0, 1.0, 1.0, 1.0],
+            text: [1.0, 1.0, 1.0, 1.0],
+            heat: [
+                [0.0, 0.0, 1.0, 1.0],
+                [1.0, 1.0, 1.0, 1.0],
+                [1.0, 0.0, 0.0, 1.0],
+            ],
+        },
+        // Okabe-Ito colors; status roles avoid relying on red vs green
+        ThemePreset::Deuteranopia => ThemePalette {
+            primary: [0.337, 0.706, 0.914, 1.0],
+            secondary: [0.8, 0.475, 0.655, 1.0],
+            accent: [0.941, 0.894, 0.259, 1.0],
+            positive: [0.0, 0.447, 0.698, 1.0],
+            warning: [0.902, 0.624, 0.0, 1.0],
+            critical: [0.835, 0.369, 0.0, 1.0],
+            neutral: [0.8, 0.8, 0.8, 1.0],
+            text: [1.0, 1.0, 1.0, 1.0],
+            heat: CIVIDIS,
+        },
+        // Reds look dark with protanopia, so critical is orange rather than vermillion
+        ThemePreset::Protanopia => ThemePalette {
+            primary: [0.337, 0.706, 0.914, 1.0],
+            secondary: [0.0, 0.62, 0.451, 1.0],
+            accent: [0.8, 0.475, 0.655, 1.0],
+            positive: [0.0, 0.447, 0.698, 1.0],
+            warning: [0.941, 0.894, 0.259, 1.0],
+            critical: [0.902, 0.624, 0.0, 1.0],
+            neutral: [0.8, 0.8, 0.8, 1.0],
+            text: [1.0, 1.0, 1.0, 1.0],
+            heat: CIVIDIS,
+        },
+    }
+}
+
+/// Blue → gray → yellow gradient that stays ordered for red-green color blindness
+const CIVIDIS: [[f32; 4]; 3] = [
+    [0.0, 0.125, 0.302, 1.0],
+    [0.486, 0.482, 0.471, 1.0],
+    [1.0, 0.914, 0.271, 1.0],
+];
+
+/// Check the background and contrast ratio of a theme
+pub fn validate_theme(theme: &OverlayTheme) -> Result<()> {
+    if let Some(background) = theme.background {
+        if !background.iter().all(|c| (0.0..=1.0).contains(c)) {
+            return Err(Error::Validation(
+                "Theme background components must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+    }
+    if let Some(ratio) = theme.min_contrast {
+        if !(1.0..=MAX_CONTRAST).contains(&ratio) {
+            return Err(Error::Validation(format!(
+                "min_contrast must be between 1.0 and {}",
+                MAX_CONTRAST
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Palette of a theme, adjusted for contrast when a background is set
+pub fn resolve_palette(theme: &OverlayTheme) -> Result<ThemePalette> {
+    validate_theme(theme)?;
+    let mut palette = preset_palette(theme.preset);
+    let Some(background) = theme.background else {
+        return Ok(palette);
+    };
+
+    let min_ratio = theme.min_contrast.unwrap_or(DEFAULT_MIN_CONTRAST);
+    let adjust = |color: &mut [f32; 4]| *color = ensure_contrast(*color, background, min_ratio);
+    for color in [
+        &mut palette.primary,
+        &mut palette.secondary,
+        &mut palette.accent,
+        &mut palette.positive,
+        &mut palette.warning,
+        &mut palette.critical,
+        &mut palette.neutral,
+        &mut palette.text,
+    ] {
+        adjust(color);
+    }
+    palette.heat.iter_mut().for_each(adjust);
+    Ok(palette)
+}
+
+/// Relative luminance of an sRGB color
+pub fn relative_luminance(rgb: [f32; 3]) -> f32 {
+    let linear = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(rgb[0]) + 0.7152 * linear(rgb[1]) + 0.0722 * linear(rgb[2])
+}
+
+/// Contrast ratio between two sRGB colors, from 1 to 21
+pub fn contrast_ratio(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Blend a color toward black or white, whichever stands out more from the
+/// background, until it reaches `min_ratio`; alpha is kept
+pub fn ensure_contrast(color: [f32; 4], background: [f32; 3], min_ratio: f32) -> [f32; 4] {
+    let rgb = [color[0], color[1], color[2]];
+    if contrast_ratio(rgb, background) >= min_ratio {
+        return color;
+    }
+
+    let target = if contrast_ratio([1.0; 3], background) >= contrast_ratio([0.0; 3], background) {
+        1.0
+    } else {
+        0.0
+    };
+    for step in 1..=10 {
+        let k = step as f32 / 10.0;
+        let mixed = rgb.map(|c| c + (target - c) * k);
+        if contrast_ratio(mixed, background) >= min_ratio {
+            return [mixed[0], mixed[1], mixed[2], color[3]];
+        }
+    }
+    [target, target, target, color[3]]
+}
+
+/// Color config for each themed overlay, using the overlays' own config keys
+pub fn overlay_theme_configs(palette: &ThemePalette) -> Vec<(DebugOverlayType, Value)> {
+    let with_alpha = |color: [f32; 4], alpha: f32| [color[0], color[1], color[2], color[3] * alpha];
+    vec![
+        (
+            DebugOverlayType::EntityHighlight,
+            json!({ "default_color": palette.accent }),
+        ),
+        (
+            DebugOverlayType::BoundingBoxes,
+            json!({ "default_color": palette.primary }),
+        ),
+        (
+            DebugOverlayType::Colliders,
+            json!({
+                "solid_color": palette.positive,
+                "sensor_color": with_alpha(palette.warning, 0.8),
+            }),
+        ),
+        (
+            DebugOverlayType::Transforms,
+            json!({
+                "hierarchy_color": with_alpha(palette.neutral, 0.5),
+                "manipulation_color": palette.warning,
+            }),
+        ),
+        (
+            DebugOverlayType::Frustum,
+            json!({
+                "frustum_color": palette.accent,
+                "culled_color": palette.critical,
+            }),
+        ),
+        (
+            DebugOverlayType::Heatmap,
+            json!({ "gradient": palette.heat }),
+        ),
+        (
+            DebugOverlayType::Labels,
+            json!({ "text_color": palette.text }),
+        ),
+        (
+            DebugOverlayType::SystemFlow,
+            json!({
+                "bar_color": palette.secondary,
+                "over_budget_color": palette.critical,
+            }),
+        ),
+        (
+            DebugOverlayType::PerformanceMetrics,
+            json!({
+                "graph_color": palette.positive,
+                "spike_color": palette.critical,
+                "budget_color": palette.warning,
+            }),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_ratio_bounds() {
+        assert!((contrast_ratio([1.0; 3], [0.0; 3]) - MAX_CONTRAST).abs() < 0.01);
+        assert!((contrast_ratio([0.5; 3], [0.5; 3]) - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_background_aware_contrast() {
+        // Yellow disappears on a white background and must be darkened
+        let theme = OverlayTheme {
+            preset: ThemePreset::Default,
+            background: Some([1.0, 1.0, 1.0]),
+            min_contrast: Some(4.5),
+        };
+        let palette = resolve_palette(&theme).unwrap();
+        for color in [palette.accent, palette.text, palette.heat[2]] {
+            assert!(contrast_ratio([color[0], color[1], color[2]], [1.0; 3]) >= 4.5);
+        }
+
+        // Without a background the preset is used unchanged
+        let plain = resolve_palette(&OverlayTheme::default()).unwrap();
+        assert_eq!(plain, preset_palette(ThemePreset::Default));
+
+        let invalid = OverlayTheme {
+            min_contrast: Some(30.0),
+            ..OverlayTheme::default()
+        };
+        assert!(resolve_palette(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_color_blind_presets_avoid_red_green_status() {
+        for preset in [ThemePreset::Deuteranopia, ThemePreset::Protanopia] {
+            let palette = preset_palette(preset);
+            // Positive is blue so it is never confused with warning or critical
+            assert!(palette.positive[2] > palette.positive[0]);
+            assert!(palette.critical[0] > palette.critical[2]);
+        }
+
+        let configs = overlay_theme_configs(&preset_palette(ThemePreset::Protanopia));
+        assert_eq!(configs.len(), 9);
+        let (_, heatmap) = configs
+            .iter()
+            .find(|(overlay, _)| *overlay == DebugOverlayType::Heatmap)
+            .unwrap();
+        assert_eq!(heatmap["gradient"].as_array().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,222 @@
+/*
+ * Bevy Debugger MCP Server - Password Policy
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Most common passwords, rejected when `reject_common` is set
+const COMMON_PASSWORDS: &[&str] = &[
+    "password",
+    "123456",
+    "123456789",
+    "12345678",
+    "12345",
+    "1234567890",
+    "qwerty",
+    "abc123",
+    "password123",
+    "admin",
+    "admin123",
+    "root",
+    "user",
+    "guest",
+    "test",
+    "demo",
+    "welcome",
+    "login",
+    "passw0rd",
+    "p@ssword",
+    "p@ssw0rd",
+];
+
+/// A kind of character a password can be required to contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharacterClass {
+    Upper,
+    Lower,
+    Digit,
+    Symbol,
+}
+
+impl CharacterClass {
+    pub const ALL: [CharacterClass; 4] = [Self::Upper, Self::Lower, Self::Digit, Self::Symbol];
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Self::Upper => c.is_uppercase(),
+            Self::Lower => c.is_lowercase(),
+            Self::Digit => c.is_ascii_digit(),
+            Self::Symbol => !c.is_alphanumeric(),
+        }
+    }
+}
+
+impl fmt::Display for CharacterClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Upper => "uppercase",
+            Self::Lower => "lowercase",
+            Self::Digit => "number",
+            Self::Symbol => "symbol",
+        })
+    }
+}
+
+impl FromStr for CharacterClass {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "upper" | "uppercase" => Ok(Self::Upper),
+            "lower" | "lowercase" => Ok(Self::Lower),
+            "digit" | "number" => Ok(Self::Digit),
+            "symbol" => Ok(Self::Symbol),
+            other => Err(Error::Validation(format!(
+                "Unknown password character class: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Parse a comma separated list such as "upper,lower,digit"
+pub fn parse_character_classes(list: &str) -> Result<Vec<CharacterClass>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|class| !class.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Rules a new password must satisfy, and how long a password stays valid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub required_classes: Vec<CharacterClass>,
+    /// Reject common passwords and passwords containing the username
+    pub reject_common: bool,
+    /// Previous passwords that cannot be reused; the current one never can
+    pub history_size: usize,
+    /// Days until a password must be changed; 0 never expires
+    pub max_age_days: u64,
+}
+
+impl PasswordPolicy {
+    /// Check `password` for `username`, listing every rule it breaks
+    pub fn check(&self, password: &str, username: &str) -> Result<()> {
+        let mut problems = Vec::new();
+        if password.chars().count() < self.min_length {
+            problems.push(format!("be at least {} characters long", self.min_length));
+        }
+        let missing: Vec<String> = self
+            .required_classes
+            .iter()
+            .filter(|class| !password.chars().any(|c| class.matches(c)))
+            .map(|class| class.to_string())
+            .collect();
+        if !missing.is_empty() {
+            problems.push(format!("contain {} characters", missing.join(", ")));
+        }
+        if self.reject_common {
+            if is_common_password(password) {
+                problems.push("not be a common password".to_string());
+            }
+            if !username.is_empty() && password.to_lowercase().contains(&username.to_lowercase()) {
+                problems.push("not contain the username".to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::SecurityError(format!(
+                "Password must {}",
+                problems.join("; ")
+            )))
+        }
+    }
+
+    /// When a password set at `changed_at` expires, if it does
+    pub fn expires_at(&self, changed_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (self.max_age_days > 0).then(|| changed_at + Duration::days(self.max_age_days as i64))
+    }
+
+    pub fn is_expired(&self, changed_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        matches!(self.expires_at(changed_at), Some(expiry) if now >= expiry)
+    }
+}
+
+/// Whether `password` is one of the most common passwords
+pub fn is_common_password(password: &str) -> bool {
+    let lowered = password.to_lowercase();
+    COMMON_PASSWORDS.iter().any(|&common| lowered == common)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            required_classes: CharacterClass::ALL.to_vec(),
+            reject_common: true,
+            history_size: 3,
+            max_age_days: 90,
+        }
+    }
+
+    #[test]
+    fn test_complexity_rules() {
+        let policy = policy();
+        assert!(policy.check("Tr0ub4dor&3x", "alice").is_ok());
+
+        let error = policy.check("short", "alice").unwrap_err().to_string();
+        assert!(error.contains("at least 10 characters"));
+        assert!(error.contains("uppercase, number, symbol"));
+
+        assert!(policy.check("Alice-2025-Rocks", "alice").is_err());
+
+        let relaxed = PasswordPolicy {
+            required_classes: parse_character_classes("lower, digit").unwrap(),
+            ..policy
+        };
+        assert!(relaxed.check("plain words 42", "alice").is_ok());
+        assert!(parse_character_classes("upper,emoji").is_err());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let changed = Utc::now() - Duration::days(91);
+        assert!(policy().is_expired(changed, Utc::now()));
+        assert!(!policy().is_expired(Utc::now() - Duration::days(5), Utc::now()));
+
+        let never = PasswordPolicy {
+            max_age_days: 0,
+            ..policy()
+        };
+        assert_eq!(never.expires_at(changed), None);
+        assert!(!never.is_expired(changed, Utc::now()));
+    }
+}
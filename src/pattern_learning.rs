@@ -19,13 +19,14 @@
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::brp_messages::DebugCommand;
-use crate::error::Result;
+use crate::error::{Error, Result};
 
 /// Maximum number of patterns to store
 const MAX_PATTERNS: usize = 1000;
@@ -45,6 +46,26 @@ const DIFFERENTIAL_PRIVACY_EPSILON: f64 = 1.0;
 /// Pattern similarity threshold for matching
 const SIMILARITY_THRESHOLD: f64 = 0.8;
 
+/// Lowest weight suggestion feedback can push a pattern down to
+pub const MIN_FEEDBACK_WEIGHT: f64 = 0.2;
+
+/// Highest weight suggestion feedback can push a pattern up to
+pub const MAX_FEEDBACK_WEIGHT: f64 = 1.5;
+
+/// File learned patterns are kept in unless `BEVY_MCP_PATTERNS_FILE` is set
+pub const DEFAULT_PATTERNS_FILE: &str = "./.bevy_debugger/patterns.json";
+
+/// Patterns file from `BEVY_MCP_PATTERNS_FILE`, or [`DEFAULT_PATTERNS_FILE`]
+///
+/// An empty `BEVY_MCP_PATTERNS_FILE` keeps patterns in memory only.
+pub fn patterns_file_from_env() -> Option<PathBuf> {
+    match std::env::var("BEVY_MCP_PATTERNS_FILE") {
+        Ok(path) if path.is_empty() => None,
+        Ok(path) => Some(PathBuf::from(path)),
+        Err(_) => Some(PathBuf::from(DEFAULT_PATTERNS_FILE)),
+    }
+}
+
 /// Privacy-preserving command representation
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct AnonymizedCommand {
@@ -94,6 +115,14 @@ pub struct DebugPattern {
     pub last_seen: Instant,
     /// Context tags (e.g., "performance", "entity_inspection")
     pub tags: Vec<String>,
+    /// Weight from suggestion feedback, multiplied into the confidence of
+    /// suggestions made from this pattern
+    #[serde(default = "default_feedback_weight")]
+    pub feedback_weight: f64,
+}
+
+fn default_feedback_weight() -> f64 {
+    1.0
 }
 
 /// Pattern mining using PrefixSpan algorithm
@@ -210,6 +239,8 @@ pub struct PatternLearningSystem {
     noise_scale: f64,
     /// Session buffer for k-anonymity
     session_buffer: Arc<RwLock<VecDeque<Vec<AnonymizedCommand>>>>,
+    /// File patterns are saved to after learning, if any
+    store_path: Option<PathBuf>,
 }
 
 impl PatternLearningSystem {
@@ -220,9 +251,60 @@ impl PatternLearningSystem {
             miner: PatternMiner::new(MIN_PATTERN_FREQUENCY, MAX_SEQUENCE_LENGTH),
             noise_scale: 1.0 / DIFFERENTIAL_PRIVACY_EPSILON,
             session_buffer: Arc::new(RwLock::new(VecDeque::new())),
+            store_path: None,
         }
     }
     
+    /// System that starts from the patterns saved in `path` and saves
+    /// there again whenever it learns new ones
+    ///
+    /// A missing file starts empty; an unreadable one is logged and ignored
+    /// so a bad file never stops the server from starting.
+    pub async fn with_store(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let system = Self {
+            store_path: Some(path.clone()),
+            ..Self::new()
+        };
+        match tokio::fs::read_to_string(&path).await {
+            Ok(json) => match system.import_patterns(&json).await {
+                Ok(count) => info!("Loaded {} learned patterns from {}", count, path.display()),
+                Err(e) => warn!("Ignoring unreadable patterns file {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Could not read patterns file {}: {}", path.display(), e),
+        }
+        system
+    }
+    
+    /// File patterns are saved to, if any
+    pub fn store_path(&self) -> Option<&Path> {
+        self.store_path.as_deref()
+    }
+    
+    /// Number of learned patterns
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+    
+    /// Write all patterns to the store file, returning its path
+    ///
+    /// The file is replaced atomically so a teammate copying it never gets
+    /// half a file.
+    pub async fn save(&self) -> Result<&Path> {
+        let path = self.store_path.as_deref().ok_or_else(|| {
+            Error::Validation("Pattern persistence is disabled (BEVY_MCP_PATTERNS_FILE is empty)".to_string())
+        })?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, self.export_patterns().await?).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        debug!("Saved {} learned patterns to {}", self.patterns.len(), path.display());
+        Ok(path)
+    }
+    
     /// Start tracking a new debug session
     pub async fn start_session(&self, session_id: String) {
         let mut sessions = self.active_sessions.write().await;
@@ -252,9 +334,9 @@ impl PatternLearningSystem {
     
     /// End a session and learn patterns
     pub async fn end_session(&self, session_id: &str, success: bool) -> Result<()> {
-        let mut sessions = self.active_sessions.write().await;
+        let sequence = self.active_sessions.write().await.remove(session_id);
         
-        if let Some(sequence) = sessions.remove(session_id) {
+        if let Some(sequence) = sequence {
             if sequence.is_empty() {
                 return Ok(());
             }
@@ -278,6 +360,12 @@ impl PatternLearningSystem {
                 
                 // Prune old patterns
                 self.prune_patterns().await;
+                
+                if self.store_path.is_some() {
+                    if let Err(e) = self.save().await {
+                        warn!("Could not save learned patterns: {}", e);
+                    }
+                }
             }
         }
         
@@ -347,6 +435,7 @@ impl PatternLearningSystem {
                 confidence: 0.1,
                 last_seen: Instant::now(),
                 tags: Vec::new(),
+                feedback_weight: default_feedback_weight(),
             });
     }
     
@@ -393,6 +482,25 @@ impl PatternLearningSystem {
         }
     }
     
+    /// Shift a pattern's feedback weight by `delta`, returning the new weight
+    ///
+    /// Returns `None` when no pattern has `pattern_id`, e.g. after pruning.
+    pub async fn apply_feedback(&self, pattern_id: &str, delta: f64) -> Option<f64> {
+        let weight = {
+            let mut pattern = self.patterns.get_mut(pattern_id)?;
+            pattern.feedback_weight =
+                (pattern.feedback_weight + delta).clamp(MIN_FEEDBACK_WEIGHT, MAX_FEEDBACK_WEIGHT);
+            pattern.feedback_weight
+        };
+        
+        if self.store_path.is_some() {
+            if let Err(e) = self.save().await {
+                warn!("Could not save learned patterns: {}", e);
+            }
+        }
+        Some(weight)
+    }
+    
     /// Find matching patterns for a given sequence
     pub async fn find_matching_patterns(
         &self,
@@ -450,24 +558,57 @@ impl PatternLearningSystem {
         matches as f64 / seq1.len().max(seq2.len()) as f64
     }
     
-    /// Export learned patterns
+    /// Export learned patterns, ordered by ID so shared files diff cleanly
     pub async fn export_patterns(&self) -> Result<String> {
-        let patterns: Vec<DebugPattern> = self.patterns
+        let mut patterns: Vec<DebugPattern> = self.patterns
             .iter()
             .map(|entry| entry.value().clone())
             .collect();
+        patterns.sort_by(|a, b| a.id.cmp(&b.id));
         
         Ok(serde_json::to_string_pretty(&patterns)?)
     }
     
-    /// Import patterns
-    pub async fn import_patterns(&self, json: &str) -> Result<()> {
+    /// Import patterns, returning how many were read
+    ///
+    /// Patterns already known keep whichever copy has seen more sessions, so
+    /// importing the same file twice changes nothing.
+    pub async fn import_patterns(&self, json: &str) -> Result<usize> {
         let patterns: Vec<DebugPattern> = serde_json::from_str(json)?;
+        if let Some(invalid) = patterns
+            .iter()
+            .find(|p| {
+                p.id.is_empty()
+                    || !(0.0..=1.0).contains(&p.success_rate)
+                    || !(MIN_FEEDBACK_WEIGHT..=MAX_FEEDBACK_WEIGHT).contains(&p.feedback_weight)
+            })
+        {
+            return Err(Error::Validation(format!(
+                "Invalid pattern '{}': needs an ID, a success rate between 0 and 1 \
+                 and a feedback weight between {} and {}",
+                invalid.id, MIN_FEEDBACK_WEIGHT, MAX_FEEDBACK_WEIGHT
+            )));
+        }
         
+        let count = patterns.len();
         for pattern in patterns {
-            self.patterns.insert(pattern.id.clone(), pattern);
+            self.patterns
+                .entry(pattern.id.clone())
+                .and_modify(|existing| {
+                    for tag in &pattern.tags {
+                        if !existing.tags.contains(tag) {
+                            existing.tags.push(tag.clone());
+                        }
+                    }
+                    if pattern.frequency > existing.frequency {
+                        let tags = std::mem::take(&mut existing.tags);
+                        *existing = DebugPattern { tags, ..pattern.clone() };
+                    }
+                })
+                .or_insert(pattern);
         }
+        self.prune_patterns().await;
         
-        Ok(())
+        Ok(count)
     }
-}
\ No newline at end of file
+}
@@ -6,6 +6,7 @@
 use crate::brp_messages::{DebugCommand, DebugResponse};
 use crate::brp_client::BrpClient;
 use crate::debug_command_processor::DebugCommandProcessor;
+use crate::diagnostics_bridge::DiagnosticsBridge;
 use crate::performance_budget::{
     PerformanceBudgetMonitor, BudgetConfig, PerformanceMetrics, Platform,
     BudgetViolation, ComplianceReport, BudgetRecommendation
@@ -26,8 +27,8 @@ pub struct PerformanceBudgetProcessor {
     /// Budget monitor instance
     monitor: Arc<PerformanceBudgetMonitor>,
     
-    /// BRP client for Bevy interaction
-    brp_client: Arc<RwLock<BrpClient>>,
+    /// Frame time and entity count measurements from the game over BRP
+    diagnostics: Arc<DiagnosticsBridge>,
     
     /// Background monitoring task handle
     monitoring_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
@@ -66,7 +67,7 @@ impl PerformanceBudgetProcessor {
         
         Self {
             monitor,
-            brp_client,
+            diagnostics: Arc::new(DiagnosticsBridge::new(brp_client)),
             monitoring_handle: Arc::new(RwLock::new(None)),
             monitoring_state: Arc::new(RwLock::new(MonitoringState::default())),
             config_path: Some("config/performance_budgets.toml".to_string()),
@@ -85,7 +86,7 @@ impl PerformanceBudgetProcessor {
         self.monitor.start_monitoring().await?;
         
         let monitor = Arc::clone(&self.monitor);
-        let brp_client = Arc::clone(&self.brp_client);
+        let diagnostics = Arc::clone(&self.diagnostics);
         let monitoring_state = Arc::clone(&self.monitoring_state);
         
         let handle = tokio::spawn(async move {
@@ -96,7 +97,7 @@ impl PerformanceBudgetProcessor {
                 tokio::select! {
                     _ = check_interval.tick() => {
                         // Perform budget checks
-                        if let Ok(metrics) = Self::collect_metrics(&brp_client).await {
+                        if let Ok(metrics) = Self::collect_metrics(&diagnostics).await {
                             if let Ok(violations) = monitor.check_violations(metrics).await {
                                 Self::handle_violations(&violations, &monitoring_state).await;
                             }
@@ -144,18 +145,14 @@ impl PerformanceBudgetProcessor {
     }
     
     /// Collect current performance metrics
-    async fn collect_metrics(brp_client: &Arc<RwLock<BrpClient>>) -> Result<PerformanceMetrics> {
-        // In a real implementation, this would query actual metrics from Bevy
-        // For now, we'll simulate metrics collection
-        
-        // This would normally query BRP for actual metrics
-        // Example queries would include:
-        // - Frame time from diagnostics
-        // - Memory usage from system info
-        // - Entity count from world stats
-        // - System execution times from profiler
+    async fn collect_metrics(diagnostics: &DiagnosticsBridge) -> Result<PerformanceMetrics> {
+        // Prefer the game's own diagnostics (frame time, entity count)
+        match diagnostics.snapshot().await {
+            Ok(snapshot) => return Ok(snapshot.to_performance_metrics()),
+            Err(e) => debug!("Diagnostics unavailable, using simulated metrics: {}", e),
+        }
         
-        // Simulated metrics for now
+        // Simulated metrics when the game doesn't expose diagnostics
         Ok(PerformanceMetrics {
             frame_time_ms: 16.0 + (rand::random::<f32>() * 5.0),
             memory_mb: 450.0 + (rand::random::<f32>() * 100.0),
@@ -284,7 +281,7 @@ impl DebugCommandProcessor for PerformanceBudgetProcessor {
                 debug!("Checking for budget violations");
                 
                 // Collect current metrics
-                let metrics = Self::collect_metrics(&self.brp_client).await?;
+                let metrics = Self::collect_metrics(&self.diagnostics).await?;
                 
                 // Check for violations
                 let violations = self.monitor.check_violations(metrics).await?;
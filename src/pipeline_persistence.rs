@@ -0,0 +1,363 @@
+//! Pipeline persistence and resumption
+//!
+//! Stores the execution state of running pipelines through the checkpoint
+//! subsystem so that a crashed or restarted server can resume a long pipeline
+//! from its next pending step instead of starting over.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::checkpoint::{Checkpoint, CheckpointManager};
+use crate::error::{Error, Result};
+use crate::tool_orchestration::{ExecutionId, StepResult, ToolContext, ToolPipeline};
+
+/// Checkpoint operation type used for pipeline execution state
+pub const PIPELINE_CHECKPOINT_OPERATION: &str = "pipeline_execution";
+
+/// Checkpoint component name used for pipeline execution state
+const PIPELINE_CHECKPOINT_COMPONENT: &str = "tool_orchestrator";
+
+/// Status of a persisted pipeline execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PipelineStatus {
+    /// Steps remain to be executed
+    Running,
+    /// Abort was requested; no further steps will run
+    Aborted,
+}
+
+/// Snapshot of a pipeline execution that can be resumed later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineExecutionState {
+    pub execution_id: ExecutionId,
+    pub pipeline: ToolPipeline,
+    /// Index of the next step to execute
+    pub next_step: usize,
+    /// Results of the steps already executed
+    pub step_results: Vec<StepResult>,
+    /// Shared context including partial results
+    pub context: ToolContext,
+    pub status: PipelineStatus,
+    /// Execution time accumulated across runs
+    pub elapsed: Duration,
+    pub started_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl PipelineExecutionState {
+    pub fn new(pipeline: ToolPipeline, context: ToolContext) -> Self {
+        let now = SystemTime::now();
+        Self {
+            execution_id: ExecutionId::new(),
+            pipeline,
+            next_step: 0,
+            step_results: Vec::new(),
+            context,
+            status: PipelineStatus::Running,
+            elapsed: Duration::ZERO,
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether all steps have been executed
+    pub fn is_complete(&self) -> bool {
+        self.next_step >= self.pipeline.steps.len()
+    }
+
+    /// Summary suitable for listing without the full context
+    pub fn summary(&self, active: bool) -> PipelineExecutionSummary {
+        PipelineExecutionSummary {
+            execution_id: self.execution_id.to_string(),
+            pipeline_name: self.pipeline.name.clone(),
+            status: self.status,
+            active,
+            next_step: self.next_step,
+            total_steps: self.pipeline.steps.len(),
+            completed_steps: self.step_results.iter().filter(|r| r.success).count(),
+            failed_steps: self.step_results.iter().filter(|r| !r.success).count(),
+            started_at: self
+                .started_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            updated_at: self
+                .updated_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        }
+    }
+}
+
+/// Lightweight view of a persisted pipeline execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineExecutionSummary {
+    pub execution_id: String,
+    pub pipeline_name: String,
+    pub status: PipelineStatus,
+    /// Whether the pipeline is currently executing in this process
+    pub active: bool,
+    pub next_step: usize,
+    pub total_steps: usize,
+    pub completed_steps: usize,
+    pub failed_steps: usize,
+    pub started_at: u64,
+    pub updated_at: u64,
+}
+
+/// Persists pipeline execution state via the checkpoint manager
+#[derive(Clone)]
+pub struct PipelinePersistence {
+    checkpoint_manager: Arc<RwLock<CheckpointManager>>,
+    /// Executions running in this process
+    active: Arc<Mutex<HashSet<ExecutionId>>>,
+    /// Executions with a pending abort request
+    abort_requested: Arc<Mutex<HashSet<ExecutionId>>>,
+}
+
+impl PipelinePersistence {
+    pub fn new(checkpoint_manager: Arc<RwLock<CheckpointManager>>) -> Self {
+        Self {
+            checkpoint_manager,
+            active: Arc::new(Mutex::new(HashSet::new())),
+            abort_requested: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// Save the current execution state, replacing any previous snapshot
+    pub async fn save(&self, state: &mut PipelineExecutionState) -> Result<()> {
+        state.updated_at = SystemTime::now();
+
+        let mut checkpoint = Checkpoint::new(
+            &state.pipeline.name,
+            &format!(
+                "Pipeline '{}' at step {}/{}",
+                state.pipeline.name,
+                state.next_step,
+                state.pipeline.steps.len()
+            ),
+            PIPELINE_CHECKPOINT_OPERATION,
+            PIPELINE_CHECKPOINT_COMPONENT,
+            serde_json::to_value(&*state)?,
+        )
+        .with_metadata("pipeline_name", &state.pipeline.name);
+        // Key the checkpoint by execution ID so each save overwrites the last one
+        checkpoint.id = state.execution_id.to_string();
+
+        let cm = self.checkpoint_manager.read().await;
+        cm.create_checkpoint(checkpoint).await?;
+        debug!(
+            "Persisted pipeline {} at step {}",
+            state.execution_id.to_string(),
+            state.next_step
+        );
+        Ok(())
+    }
+
+    /// Load a persisted execution state
+    pub async fn load(&self, execution_id: &ExecutionId) -> Result<PipelineExecutionState> {
+        let cm = self.checkpoint_manager.read().await;
+        let checkpoint = cm
+            .restore_checkpoint(&execution_id.to_string())
+            .await
+            .map_err(|_| {
+                Error::Validation(format!(
+                    "No persisted pipeline execution: {}",
+                    execution_id.to_string()
+                ))
+            })?;
+
+        if checkpoint.operation_type != PIPELINE_CHECKPOINT_OPERATION {
+            return Err(Error::Validation(format!(
+                "Checkpoint {} is not a pipeline execution",
+                checkpoint.id
+            )));
+        }
+
+        Ok(serde_json::from_value(checkpoint.state_data)?)
+    }
+
+    /// List all persisted pipeline executions
+    pub async fn list(&self) -> Result<Vec<PipelineExecutionSummary>> {
+        let checkpoints = {
+            let cm = self.checkpoint_manager.read().await;
+            cm.list_checkpoints_by_operation(PIPELINE_CHECKPOINT_OPERATION)
+                .await?
+        };
+
+        let mut summaries = Vec::with_capacity(checkpoints.len());
+        for checkpoint in checkpoints {
+            match serde_json::from_value::<PipelineExecutionState>(checkpoint.state_data) {
+                Ok(state) => {
+                    let active = self.is_active(&state.execution_id);
+                    summaries.push(state.summary(active));
+                }
+                Err(e) => warn!(
+                    "Skipping unreadable pipeline checkpoint {}: {}",
+                    checkpoint.id, e
+                ),
+            }
+        }
+
+        summaries.sort_by_key(|s| s.started_at);
+        Ok(summaries)
+    }
+
+    /// Remove the persisted state of a finished or aborted execution
+    pub async fn remove(&self, execution_id: &ExecutionId) {
+        let cm = self.checkpoint_manager.read().await;
+        if let Err(e) = cm.delete_checkpoint(&execution_id.to_string()).await {
+            debug!("No pipeline checkpoint to remove: {}", e);
+        }
+    }
+
+    /// Request that an execution stop.
+    ///
+    /// Active executions stop before their next step; interrupted executions
+    /// are discarded immediately. Returns `true` if the execution was active.
+    pub async fn abort(&self, execution_id: &ExecutionId) -> Result<bool> {
+        if self.is_active(execution_id) {
+            self.abort_requested
+                .lock()
+                .map_err(|_| Error::Internal("Pipeline abort set poisoned".to_string()))?
+                .insert(*execution_id);
+            return Ok(true);
+        }
+
+        // Validate the execution exists before discarding it
+        self.load(execution_id).await?;
+        self.remove(execution_id).await;
+        Ok(false)
+    }
+
+    pub fn mark_active(&self, execution_id: ExecutionId) {
+        if let Ok(mut active) = self.active.lock() {
+            active.insert(execution_id);
+        }
+    }
+
+    pub fn mark_inactive(&self, execution_id: &ExecutionId) {
+        if let Ok(mut active) = self.active.lock() {
+            active.remove(execution_id);
+        }
+        if let Ok(mut aborts) = self.abort_requested.lock() {
+            aborts.remove(execution_id);
+        }
+    }
+
+    pub fn is_active(&self, execution_id: &ExecutionId) -> bool {
+        self.active
+            .lock()
+            .map(|active| active.contains(execution_id))
+            .unwrap_or(false)
+    }
+
+    pub fn is_abort_requested(&self, execution_id: &ExecutionId) -> bool {
+        self.abort_requested
+            .lock()
+            .map(|aborts| aborts.contains(execution_id))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::CheckpointConfig;
+
+    fn in_memory_persistence() -> PipelinePersistence {
+        let config = CheckpointConfig {
+            persist_to_disk: false,
+            ..CheckpointConfig::default()
+        };
+        PipelinePersistence::new(Arc::new(RwLock::new(CheckpointManager::new(config))))
+    }
+
+    #[tokio::test]
+    async fn test_save_load_and_list() {
+        let persistence = in_memory_persistence();
+        let mut state =
+            PipelineExecutionState::new(ToolPipeline::new("long".to_string()), ToolContext::new());
+        state.next_step = 2;
+
+        persistence.save(&mut state).await.unwrap();
+        // Saving again overwrites rather than duplicating
+        persistence.save(&mut state).await.unwrap();
+
+        let loaded = persistence.load(&state.execution_id).await.unwrap();
+        assert_eq!(loaded.next_step, 2);
+        assert_eq!(loaded.pipeline.name, "long");
+
+        let listed = persistence.list().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(!listed[0].active);
+    }
+
+    #[tokio::test]
+    async fn test_resave_does_not_evict_other_checkpoints() {
+        let config = CheckpointConfig {
+            persist_to_disk: false,
+            max_checkpoints: 3,
+            ..CheckpointConfig::default()
+        };
+        let manager = Arc::new(RwLock::new(CheckpointManager::new(config)));
+        let persistence = PipelinePersistence::new(Arc::clone(&manager));
+        // Older than the pipeline's checkpoint, so they'd be evicted first
+        for name in ["manual-1", "manual-2"] {
+            let mut checkpoint =
+                Checkpoint::new(name, "manual", "manual", "test", serde_json::json!({}));
+            checkpoint.timestamp -= 60;
+            manager.read().await.create_checkpoint(checkpoint).await.unwrap();
+        }
+
+        let mut state =
+            PipelineExecutionState::new(ToolPipeline::new("long".to_string()), ToolContext::new());
+        for step in 0..3 {
+            state.next_step = step;
+            persistence.save(&mut state).await.unwrap();
+        }
+
+        let names: Vec<String> = manager
+            .read()
+            .await
+            .list_checkpoints()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|checkpoint| checkpoint.name)
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert!(names.contains(&"manual-1".to_string()));
+        assert!(names.contains(&"manual-2".to_string()));
+        assert_eq!(persistence.load(&state.execution_id).await.unwrap().next_step, 2);
+    }
+
+    #[tokio::test]
+    async fn test_abort_inactive_discards_state() {
+        let persistence = in_memory_persistence();
+        let mut state =
+            PipelineExecutionState::new(ToolPipeline::new("long".to_string()), ToolContext::new());
+        persistence.save(&mut state).await.unwrap();
+
+        assert!(!persistence.abort(&state.execution_id).await.unwrap());
+        assert!(persistence.load(&state.execution_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_abort_active_sets_flag() {
+        let persistence = in_memory_persistence();
+        let id = ExecutionId::new();
+        persistence.mark_active(id);
+
+        assert!(persistence.abort(&id).await.unwrap());
+        assert!(persistence.is_abort_requested(&id));
+
+        persistence.mark_inactive(&id);
+        assert!(!persistence.is_abort_requested(&id));
+    }
+}
@@ -0,0 +1,354 @@
+//! User-defined pipeline template registry
+//!
+//! Built-in templates are registered by the orchestrator at startup; additional
+//! templates are loaded from a directory of JSON or TOML pipeline files. Every
+//! template is validated when it is loaded so that broken definitions are
+//! reported up front instead of failing halfway through an execution.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::fs;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::tool_orchestration::ToolPipeline;
+
+/// Maximum number of steps in a user-supplied pipeline
+pub const MAX_PIPELINE_STEPS: usize = 50;
+
+/// Maximum timeout for a single user-supplied pipeline step
+pub const MAX_STEP_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Validate a user-supplied pipeline definition against the available tools
+pub fn validate_pipeline_definition(pipeline: &ToolPipeline, known_tools: &[String]) -> Result<()> {
+    if pipeline.name.trim().is_empty() {
+        return Err(Error::Validation(
+            "Pipeline name must not be empty".to_string(),
+        ));
+    }
+
+    if pipeline.steps.is_empty() {
+        return Err(Error::Validation(format!(
+            "Pipeline '{}' has no steps",
+            pipeline.name
+        )));
+    }
+
+    if pipeline.steps.len() > MAX_PIPELINE_STEPS {
+        return Err(Error::Validation(format!(
+            "Pipeline too complex: maximum {MAX_PIPELINE_STEPS} steps allowed"
+        )));
+    }
+
+    for step in &pipeline.steps {
+        if step.timeout.unwrap_or(Duration::from_secs(300)) > MAX_STEP_TIMEOUT {
+            return Err(Error::Validation(format!(
+                "Step '{}' timeout too long: maximum 10 minutes allowed",
+                step.name
+            )));
+        }
+
+        // Validate tool names against known tools
+        if !known_tools.iter().any(|tool| tool == &step.tool) {
+            return Err(Error::Validation(format!(
+                "Unknown tool '{}' in step '{}'",
+                step.tool, step.name
+            )));
+        }
+    }
+
+    pipeline.validate()
+}
+
+/// Where a template was defined
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateSource {
+    Builtin,
+    File { path: PathBuf },
+}
+
+/// A registered pipeline template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineTemplate {
+    pub pipeline: ToolPipeline,
+    pub source: TemplateSource,
+}
+
+/// Summary of a template for listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSummary {
+    pub name: String,
+    pub description: Option<String>,
+    pub step_count: usize,
+    pub source: TemplateSource,
+}
+
+/// A template file that failed to load or validate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateLoadError {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+#[derive(Debug, Default)]
+struct TemplateRegistry {
+    templates: HashMap<String, PipelineTemplate>,
+    load_errors: Vec<TemplateLoadError>,
+}
+
+/// Shared, cheaply cloneable registry of pipeline templates
+#[derive(Debug, Clone, Default)]
+pub struct PipelineTemplateStore {
+    inner: Arc<RwLock<TemplateRegistry>>,
+}
+
+impl PipelineTemplateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a template, replacing any template with the same name
+    pub fn insert(&self, pipeline: ToolPipeline, source: TemplateSource) {
+        match self.inner.write() {
+            Ok(mut registry) => {
+                if let Some(existing) = registry.templates.get(&pipeline.name) {
+                    warn!(
+                        "Pipeline template '{}' from {:?} overrides {:?}",
+                        pipeline.name, source, existing.source
+                    );
+                }
+                registry
+                    .templates
+                    .insert(pipeline.name.clone(), PipelineTemplate { pipeline, source });
+            }
+            Err(_) => warn!("Pipeline template registry lock poisoned; template not registered"),
+        }
+    }
+
+    /// Get a copy of a template's pipeline by name
+    pub fn get(&self, name: &str) -> Option<ToolPipeline> {
+        self.inner
+            .read()
+            .ok()
+            .and_then(|registry| registry.templates.get(name).map(|t| t.pipeline.clone()))
+    }
+
+    /// List all templates sorted by name
+    pub fn list(&self) -> Vec<TemplateSummary> {
+        let Ok(registry) = self.inner.read() else {
+            return Vec::new();
+        };
+
+        let mut summaries: Vec<TemplateSummary> = registry
+            .templates
+            .values()
+            .map(|t| TemplateSummary {
+                name: t.pipeline.name.clone(),
+                description: t.pipeline.description.clone(),
+                step_count: t.pipeline.steps.len(),
+                source: t.source.clone(),
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+        summaries
+    }
+
+    /// Full description of a template including its steps
+    pub fn describe(&self, name: &str) -> Option<Value> {
+        let registry = self.inner.read().ok()?;
+        let template = registry.templates.get(name)?;
+
+        let steps: Vec<Value> = template
+            .pipeline
+            .steps
+            .iter()
+            .map(|step| {
+                json!({
+                    "name": step.name,
+                    "tool": step.tool,
+                    "arguments": step.arguments,
+                    "condition": step.condition,
+                    "retry_config": step.retry_config,
+                    "repeat_until": step.repeat_until,
+                    "timeout_ms": step.timeout.map(|t| t.as_millis() as u64),
+                })
+            })
+            .collect();
+
+        Some(json!({
+            "name": template.pipeline.name,
+            "description": template.pipeline.description,
+            "parallel_execution": template.pipeline.parallel_execution,
+            "fail_fast": template.pipeline.fail_fast,
+            "source": template.source,
+            "steps": steps,
+        }))
+    }
+
+    /// Errors from the most recent directory load
+    pub fn load_errors(&self) -> Vec<TemplateLoadError> {
+        self.inner
+            .read()
+            .map(|registry| registry.load_errors.clone())
+            .unwrap_or_default()
+    }
+
+    /// Load and validate every `.json` and `.toml` template in a directory.
+    ///
+    /// Invalid files are skipped and recorded in [`Self::load_errors`].
+    /// Returns the number of templates loaded.
+    pub async fn load_directory(&self, dir: &Path, known_tools: &[String]) -> Result<usize> {
+        if !dir.exists() {
+            return Ok(0);
+        }
+
+        let mut entries = fs::read_dir(dir).await?;
+        let mut paths = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("json") | Some("toml")
+            ) {
+                paths.push(path);
+            }
+        }
+        // Deterministic override order when two files define the same name
+        paths.sort();
+
+        let mut loaded = 0;
+        let mut load_errors = Vec::new();
+
+        for path in paths {
+            match Self::load_file(&path, known_tools).await {
+                Ok(pipeline) => {
+                    self.insert(pipeline, TemplateSource::File { path: path.clone() });
+                    loaded += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        "Skipping invalid pipeline template {}: {}",
+                        path.display(),
+                        e
+                    );
+                    load_errors.push(TemplateLoadError {
+                        path,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        if let Ok(mut registry) = self.inner.write() {
+            registry.load_errors = load_errors;
+        }
+
+        info!(
+            "Loaded {} pipeline templates from {}",
+            loaded,
+            dir.display()
+        );
+        Ok(loaded)
+    }
+
+    /// Parse and validate a single template file
+    async fn load_file(path: &Path, known_tools: &[String]) -> Result<ToolPipeline> {
+        let contents = fs::read_to_string(path).await?;
+        let pipeline = parse_template(path, &contents)?;
+        validate_pipeline_definition(&pipeline, known_tools)?;
+        Ok(pipeline)
+    }
+}
+
+/// Parse a template file based on its extension
+fn parse_template(path: &Path, contents: &str) -> Result<ToolPipeline> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid TOML pipeline: {e}"))),
+        _ => serde_json::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid JSON pipeline: {e}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_orchestration::WorkflowDSL;
+
+    fn known_tools() -> Vec<String> {
+        ["observe", "experiment", "stress", "replay", "anomaly"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_tool() {
+        let mut pipeline = WorkflowDSL::debug_performance();
+        pipeline.steps[0].tool = "format_disk".to_string();
+
+        let err = validate_pipeline_definition(&pipeline, &known_tools()).unwrap_err();
+        assert!(err.to_string().contains("Unknown tool"));
+    }
+
+    #[test]
+    fn test_parse_toml_and_json() {
+        let toml_src = r#"
+            name = "poll_entities"
+            description = "Poll until entity count settles"
+
+            [[steps]]
+            name = "count"
+            tool = "observe"
+            arguments = { query = "count entities" }
+        "#;
+        let pipeline = parse_template(Path::new("poll.toml"), toml_src).unwrap();
+        assert_eq!(pipeline.name, "poll_entities");
+        assert!(pipeline.fail_fast);
+        assert_eq!(pipeline.steps[0].arguments["query"], "count entities");
+
+        let json_src = r#"{"name": "observe_once", "description": null,
+            "steps": [{"name": "o", "tool": "observe", "condition": null, "timeout": null}]}"#;
+        let pipeline = parse_template(Path::new("observe.json"), json_src).unwrap();
+        assert_eq!(pipeline.steps.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_directory_records_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("good.json"),
+            r#"{"name": "good", "description": null,
+                "steps": [{"name": "o", "tool": "observe", "condition": null, "timeout": null}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("bad.json"),
+            r#"{"name": "bad", "description": null,
+                "steps": [{"name": "x", "tool": "unknown", "condition": null, "timeout": null}]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let store = PipelineTemplateStore::new();
+        store.insert(WorkflowDSL::debug_performance(), TemplateSource::Builtin);
+
+        let loaded = store
+            .load_directory(dir.path(), &known_tools())
+            .await
+            .unwrap();
+        assert_eq!(loaded, 1);
+        assert!(store.get("good").is_some());
+        assert!(store.get("bad").is_none());
+        assert_eq!(store.load_errors().len(), 1);
+
+        let names: Vec<String> = store.list().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["debug_performance", "good"]);
+        assert!(store.describe("good").unwrap()["steps"].is_array());
+    }
+}
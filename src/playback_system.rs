@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use futures_util::stream::Stream;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex, RwLock};
@@ -9,7 +10,8 @@ use tracing::{debug, error, info, warn};
 
 use crate::brp_client::BrpClient;
 use crate::error::{Error, Result};
-use crate::recording_system::{Frame, Recording, Timeline};
+use crate::recording_system::{Frame, Marker, Recording, Timeline};
+use crate::replay_assertions::{AssertionChecker, AssertionReport, ReplayAssertion};
 
 /// Playback state machine states
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,15 +24,20 @@ pub enum PlaybackState {
     Error,
 }
 
+/// Slowest supported playback speed multiplier
+pub const MIN_PLAYBACK_SPEED: f32 = 0.1;
+/// Fastest supported playback speed multiplier
+pub const MAX_PLAYBACK_SPEED: f32 = 10.0;
+
 /// Playback speed multiplier
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PlaybackSpeed(f32);
 
 impl PlaybackSpeed {
     pub fn new(speed: f32) -> Result<Self> {
-        if speed <= 0.0 || speed > 100.0 {
+        if !(MIN_PLAYBACK_SPEED..=MAX_PLAYBACK_SPEED).contains(&speed) {
             return Err(Error::Validation(format!(
-                "Invalid playback speed: {speed}"
+                "Invalid playback speed: {speed} (must be between {MIN_PLAYBACK_SPEED}x and {MAX_PLAYBACK_SPEED}x)"
             )));
         }
         Ok(Self(speed))
@@ -146,6 +153,7 @@ pub struct PlaybackController {
     playback_time: Arc<RwLock<Duration>>,
     version: RecordingVersion,
     playback_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    assertions: Arc<RwLock<Option<AssertionChecker>>>,
 }
 
 impl PlaybackController {
@@ -162,7 +170,68 @@ impl PlaybackController {
             playback_time: Arc::new(RwLock::new(Duration::ZERO)),
             version: RecordingVersion::current(),
             playback_task: Arc::new(Mutex::new(None)),
+            assertions: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Check `assertions` against every frame played from now on
+    ///
+    /// Replaces earlier assertions and their results; an empty list stops checking.
+    pub async fn set_assertions(&self, assertions: Vec<ReplayAssertion>) {
+        *self.assertions.write().await =
+            (!assertions.is_empty()).then(|| AssertionChecker::new(assertions));
+    }
+
+    /// Add a marker `before` earlier than the current playback position
+    ///
+    /// Returns `None` when no recording is loaded.
+    pub async fn annotate(
+        &self,
+        name: String,
+        description: Option<String>,
+        metadata: HashMap<String, serde_json::Value>,
+        before: Duration,
+    ) -> Option<Marker> {
+        let mut timeline = self.timeline.write().await;
+        let current = timeline.current()?;
+        let timestamp = current.timestamp.saturating_sub(before);
+        let marker = Marker {
+            name,
+            frame_number: timeline.recording.as_ref()?.frame_at(timestamp)?,
+            timestamp,
+            description,
+            metadata,
+        };
+        timeline.add_marker(marker.clone());
+        Some(marker)
+    }
+
+    /// Results of the assertions set for playback, if any
+    pub async fn assertion_report(&self) -> Option<AssertionReport> {
+        self.assertions
+            .read()
+            .await
+            .as_ref()
+            .map(AssertionChecker::report)
+    }
+
+    /// Check `assertions` against the whole recording without playing it back
+    pub async fn check_assertions(&self, assertions: Vec<ReplayAssertion>) -> AssertionReport {
+        let mut checker = AssertionChecker::new(assertions);
+        let mut timeline = self.timeline.write().await;
+        let total_frames = timeline
+            .recording
+            .as_ref()
+            .map(|r| r.total_frames)
+            .unwrap_or(0);
+        for frame_number in 0..total_frames {
+            // Frames before the first keyframe of a windowed recording aren't held
+            match timeline.get_frame(frame_number) {
+                Some(frame) if frame.frame_number == frame_number => checker.check_frame(&frame),
+                _ => {}
+            }
         }
+        checker.report()
     }
 
     /// Load a recording for playback
@@ -345,6 +414,43 @@ impl PlaybackController {
         Ok(())
     }
 
+    /// Move `frames` frames forward or backward and sync only the frame landed on
+    ///
+    /// Stops at either end of the recording. Backward steps are reconstructed
+    /// from the nearest keyframe, so their cost is bounded by the recording's
+    /// keyframe interval. Returns the frame landed on.
+    pub async fn step_by(
+        &self,
+        frames: usize,
+        backward: bool,
+        brp_client: &mut BrpClient,
+    ) -> Result<usize> {
+        if *self.state.read().await == PlaybackState::Playing {
+            return Err(Error::Validation("Cannot step while playing".to_string()));
+        }
+
+        let mut timeline = self.timeline.write().await;
+        let total_frames = timeline
+            .recording
+            .as_ref()
+            .map(|r| r.total_frames)
+            .ok_or_else(|| Error::Validation("No recording loaded".to_string()))?;
+        let target = if backward {
+            timeline.current_frame.saturating_sub(frames)
+        } else {
+            (timeline.current_frame + frames).min(total_frames.saturating_sub(1))
+        };
+
+        timeline.seek(target);
+        let frame = timeline
+            .current()
+            .ok_or_else(|| Error::Validation(format!("Frame {target} is not available")))?;
+        self.sync_strategy.sync_frame(&frame, brp_client).await?;
+        *self.playback_time.write().await = frame.timestamp;
+        debug!("Stepped to frame {}", frame.frame_number);
+        Ok(target)
+    }
+
     /// Set playback speed
     pub async fn set_speed(&self, speed: f32) -> Result<()> {
         let new_speed = PlaybackSpeed::new(speed)?;
@@ -401,6 +507,7 @@ impl PlaybackController {
         let speed = self.speed.clone();
         let sync_strategy = self.sync_strategy.clone();
         let playback_time = self.playback_time.clone();
+        let assertions = self.assertions.clone();
 
         // Spawn playback task and store handle
         let task = tokio::spawn(async move {
@@ -439,6 +546,9 @@ impl PlaybackController {
                                 if let Err(e) = sync_strategy.sync_frame(&frame, &mut client).await {
                                     error!("Failed to sync frame: {}", e);
                                 }
+                                if let Some(checker) = assertions.write().await.as_mut() {
+                                    checker.check_frame(&frame);
+                                }
 
                                 // Move to next frame
                                 if timeline.next().is_none() {
@@ -685,6 +795,10 @@ mod tests {
         assert!(PlaybackSpeed::new(0.0).is_err());
         assert!(PlaybackSpeed::new(-1.0).is_err());
         assert!(PlaybackSpeed::new(101.0).is_err());
+        assert!(PlaybackSpeed::new(0.1).is_ok());
+        assert!(PlaybackSpeed::new(10.0).is_ok());
+        assert!(PlaybackSpeed::new(0.05).is_err());
+        assert!(PlaybackSpeed::new(10.5).is_err());
     }
 
     #[test]
@@ -0,0 +1,142 @@
+/*
+ * Bevy Debugger MCP Server - Process Metrics
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Resource usage of the debugger's own process
+//!
+//! Memory and CPU come from sysinfo on every platform. Thread and file
+//! descriptor counts are read from `/proc` and are only available on Linux.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+use sysinfo::{Pid, System};
+
+/// A sample of this process's resource usage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+    /// Resident set size
+    pub rss_bytes: u64,
+    pub virtual_bytes: u64,
+    /// Share of the machine's total CPU time since the previous sample
+    pub cpu_percent: f32,
+    pub thread_count: Option<u64>,
+    pub open_fds: Option<u64>,
+}
+
+/// Samples [`ProcessMetrics`] for the current process
+///
+/// CPU usage is measured between consecutive samples, so the first sample
+/// reports 0%.
+#[derive(Debug)]
+pub struct ProcessSampler {
+    system: System,
+    pid: Pid,
+    cpu_count: usize,
+}
+
+impl ProcessSampler {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            pid: Pid::from_u32(std::process::id()),
+            cpu_count: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    pub fn sample(&mut self) -> ProcessMetrics {
+        let mut metrics = ProcessMetrics {
+            thread_count: thread_count(),
+            open_fds: open_fds(),
+            ..ProcessMetrics::default()
+        };
+        // Only this process is refreshed; refreshing every process is far slower
+        if self.system.refresh_process(self.pid) {
+            if let Some(process) = self.system.process(self.pid) {
+                metrics.rss_bytes = process.memory();
+                metrics.virtual_bytes = process.virtual_memory();
+                // sysinfo reports 100% per fully used core
+                metrics.cpu_percent = (process.cpu_usage() / self.cpu_count as f32).min(100.0);
+            }
+        }
+        metrics
+    }
+}
+
+impl Default for ProcessSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sample the current process with a sampler shared across callers
+///
+/// Sharing one sampler gives one-off callers, like diagnostic reports, a CPU
+/// reading over the time since anyone last sampled.
+pub fn sample() -> ProcessMetrics {
+    static SAMPLER: OnceLock<Mutex<ProcessSampler>> = OnceLock::new();
+    SAMPLER
+        .get_or_init(|| Mutex::new(ProcessSampler::new()))
+        .lock()
+        .map(|mut sampler| sampler.sample())
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "linux")]
+fn thread_count() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Threads:"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_count() -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn open_fds() -> Option<u64> {
+    Some(std::fs::read_dir("/proc/self/fd").ok()?.count() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_fds() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_reports_real_usage() {
+        let mut sampler = ProcessSampler::new();
+        let metrics = sampler.sample();
+        assert!(metrics.rss_bytes > 0);
+        assert!(metrics.virtual_bytes >= metrics.rss_bytes);
+        assert!((0.0..=100.0).contains(&sampler.sample().cpu_percent));
+
+        if cfg!(target_os = "linux") {
+            // The test harness runs tests on their own threads
+            assert!(metrics.thread_count.unwrap() >= 1);
+            assert!(metrics.open_fds.unwrap() >= 1);
+        }
+    }
+}
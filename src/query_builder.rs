@@ -2,11 +2,14 @@
 /// 
 /// Provides a fluent interface for building complex ECS queries with validation,
 /// optimization suggestions, and performance estimation.
+use crate::bevy_reflection::type_registry_tools::fuzzy_match_score;
 use crate::brp_messages::{
     ValidatedQuery, QueryFilter, QueryCost, ComponentFilter, 
-    DebugCommand
+    DebugCommand, QueryCompletion, QueryCompletionKind
 };
 use crate::error::{Error, Result};
+use crate::tools::mutate::resolve as resolve_schema;
+use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
@@ -576,6 +579,321 @@ impl QueryOptimizer {
     }
 }
 
+/// Completions returned when the request sets no limit
+pub const DEFAULT_COMPLETION_LIMIT: usize = 20;
+
+/// Operators for numeric fields, with what they test
+const NUMERIC_OPERATORS: &[(&str, &str)] = &[
+    ("eq", "equal to"),
+    ("ne", "not equal to"),
+    ("gt", "greater than"),
+    ("gte", "greater than or equal to"),
+    ("lt", "less than"),
+    ("lte", "less than or equal to"),
+];
+
+/// Operators for string fields
+const STRING_OPERATORS: &[(&str, &str)] = &[
+    ("eq", "equal to"),
+    ("ne", "not equal to"),
+    ("contains", "contains the text"),
+    ("regex", "matches the regular expression"),
+];
+
+/// Operators for lists
+const LIST_OPERATORS: &[(&str, &str)] = &[
+    ("eq", "equal to"),
+    ("ne", "not equal to"),
+    ("contains", "contains the element"),
+];
+
+/// Operators for everything else
+const EQUALITY_OPERATORS: &[(&str, &str)] = &[("eq", "equal to"), ("ne", "not equal to")];
+
+/// Complete a partially typed filter `<component>[.<field>...] [<op> [<value>]]`
+///
+/// Component names and field paths come from `schemas` (component schemas by
+/// type path, as cached by the `mutate` tool), operators from the field's
+/// type and values from its enum variants.
+pub fn suggest_completions(
+    partial: &str,
+    schemas: &HashMap<String, Value>,
+    limit: usize,
+) -> (QueryCompletionKind, Vec<QueryCompletion>) {
+    let trailing_space = partial.ends_with(char::is_whitespace);
+    let tokens: Vec<&str> = partial.split_whitespace().collect();
+    // Everything before the token being completed
+    let head = if trailing_space || tokens.is_empty() {
+        partial
+    } else {
+        partial
+            .trim_end()
+            .strip_suffix(tokens[tokens.len() - 1])
+            .unwrap_or("")
+    };
+
+    let (kind, candidates) = match (tokens.as_slice(), trailing_space) {
+        ([], _) => (
+            QueryCompletionKind::Component,
+            complete_component("", schemas),
+        ),
+        ([path], false) => match path.split_once('.') {
+            None => (
+                QueryCompletionKind::Component,
+                complete_component(path, schemas),
+            ),
+            Some((component, fields)) => (
+                QueryCompletionKind::Field,
+                complete_field(component, fields, schemas),
+            ),
+        },
+        ([path], true) => (
+            QueryCompletionKind::Operator,
+            complete_operator(path, "", schemas),
+        ),
+        ([path, op], false) => (
+            QueryCompletionKind::Operator,
+            complete_operator(path, op, schemas),
+        ),
+        ([path, _], true) => (
+            QueryCompletionKind::Value,
+            complete_value(path, "", schemas),
+        ),
+        ([path, _, value], false) => (
+            QueryCompletionKind::Value,
+            complete_value(path, value, schemas),
+        ),
+        _ => (QueryCompletionKind::Value, Vec::new()),
+    };
+
+    let completions = candidates
+        .into_iter()
+        .take(limit)
+        .map(|(token, label, detail)| QueryCompletion {
+            text: format!("{head}{token}"),
+            label,
+            detail,
+        })
+        .collect();
+    (kind, completions)
+}
+
+/// A completion as (token, label, detail)
+type Candidate = (String, String, Option<String>);
+
+/// Whether `schema` describes a component rather than a type used inside one
+fn is_component_schema(type_path: &str, schema: &Value) -> bool {
+    match schema.get("reflectTypes").and_then(Value::as_array) {
+        Some(reflect_types) => reflect_types.iter().any(|t| t == "Component"),
+        None => type_path.contains("::"),
+    }
+}
+
+fn short_name(type_path: &str) -> &str {
+    let base = type_path.split('<').next().unwrap_or(type_path);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+fn complete_component(prefix: &str, schemas: &HashMap<String, Value>) -> Vec<Candidate> {
+    let needle = prefix.to_lowercase();
+    let components: Vec<&String> = schemas
+        .iter()
+        .filter(|(path, schema)| is_component_schema(path, schema))
+        .map(|(path, _)| path)
+        .collect();
+
+    let mut scored: Vec<(f64, &String)> = components
+        .iter()
+        .filter_map(|path| {
+            let short = short_name(path).to_lowercase();
+            let score = if needle.is_empty() || short.starts_with(&needle) {
+                3.0
+            } else if path.to_lowercase().starts_with(&needle) {
+                2.5
+            } else if short.contains(&needle) {
+                2.0
+            } else {
+                fuzzy_match_score(&needle, &short).filter(|score| *score >= 0.3)?
+            };
+            Some((score, *path))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    scored
+        .into_iter()
+        .map(|(_, path)| {
+            let short = short_name(path);
+            let unique = components.iter().filter(|p| short_name(p) == short).count() == 1;
+            let token = if unique { short } else { path.as_str() };
+            (token.to_string(), short.to_string(), Some(path.clone()))
+        })
+        .collect()
+}
+
+/// The component `name` refers to, by full path or unique short name
+fn resolve_component_name<'a>(
+    name: &str,
+    schemas: &'a HashMap<String, Value>,
+) -> Option<&'a String> {
+    if let Some((path, _)) = schemas.get_key_value(name) {
+        return Some(path);
+    }
+    let mut matches = schemas
+        .iter()
+        .filter(|(path, schema)| is_component_schema(path, schema))
+        .map(|(path, _)| path)
+        .filter(|path| short_name(path).eq_ignore_ascii_case(name));
+    let first = matches.next()?;
+    matches.next().is_none().then_some(first)
+}
+
+/// Resolved schema at `segments` inside `component`
+fn schema_at_path(
+    component: &str,
+    segments: &[&str],
+    schemas: &HashMap<String, Value>,
+) -> Option<Value> {
+    let mut current = resolve_schema(
+        schemas.get(resolve_component_name(component, schemas)?)?,
+        schemas,
+    );
+    for segment in segments {
+        current = if let Some(field) = current.get("properties").and_then(|p| p.get(*segment)) {
+            resolve_schema(field, schemas)
+        } else if let (Some(items), Ok(_)) = (current.get("items"), segment.parse::<usize>()) {
+            resolve_schema(items, schemas)
+        } else {
+            return None;
+        };
+    }
+    Some(current)
+}
+
+/// Declared type of a field schema, e.g. `f32` for `{"type": {"$ref": "#/$defs/f32"}}`
+fn field_type_name(field: &Value) -> Option<String> {
+    field
+        .get("$ref")
+        .or_else(|| field.get("type").and_then(|t| t.get("$ref")))
+        .and_then(Value::as_str)
+        .map(|r| r.trim_start_matches("#/$defs/").to_string())
+        .or_else(|| {
+            field
+                .get("type")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+}
+
+fn complete_field(
+    component: &str,
+    fields: &str,
+    schemas: &HashMap<String, Value>,
+) -> Vec<Candidate> {
+    let segments: Vec<&str> = fields.split('.').collect();
+    let Some((prefix, parents)) = segments.split_last() else {
+        return Vec::new();
+    };
+    let Some(parent) = schema_at_path(component, parents, schemas) else {
+        return Vec::new();
+    };
+    let Some(properties) = parent.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let needle = prefix.to_lowercase();
+    let mut names: Vec<&String> = properties
+        .keys()
+        .filter(|name| name.to_lowercase().starts_with(&needle))
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let path: Vec<&str> = parents.iter().copied().chain([name.as_str()]).collect();
+            (
+                format!("{component}.{}", path.join(".")),
+                name.clone(),
+                field_type_name(&properties[name]),
+            )
+        })
+        .collect()
+}
+
+fn type_names(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn field_schema(path: &str, schemas: &HashMap<String, Value>) -> Option<Value> {
+    let (component, fields) = path.split_once('.').unwrap_or((path, ""));
+    let segments: Vec<&str> = fields.split('.').filter(|s| !s.is_empty()).collect();
+    schema_at_path(component, &segments, schemas)
+}
+
+fn complete_operator(path: &str, prefix: &str, schemas: &HashMap<String, Value>) -> Vec<Candidate> {
+    let schema = field_schema(path, schemas);
+    let types = schema.as_ref().map(type_names).unwrap_or_default();
+    let operators: Vec<(&str, &str)> = if schema.is_none() {
+        // Unknown field: offer everything rather than guess wrong
+        NUMERIC_OPERATORS
+            .iter()
+            .chain(&STRING_OPERATORS[2..])
+            .copied()
+            .collect()
+    } else if types.iter().any(|t| *t == "number" || *t == "integer") {
+        NUMERIC_OPERATORS.to_vec()
+    } else if types.contains(&"string") && schema.as_ref().is_some_and(|s| s.get("enum").is_none())
+    {
+        STRING_OPERATORS.to_vec()
+    } else if types.contains(&"array") {
+        LIST_OPERATORS.to_vec()
+    } else {
+        EQUALITY_OPERATORS.to_vec()
+    };
+    operators
+        .into_iter()
+        .filter(|(op, _)| op.starts_with(prefix))
+        .map(|(op, description)| {
+            (
+                op.to_string(),
+                op.to_string(),
+                Some(description.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn complete_value(path: &str, prefix: &str, schemas: &HashMap<String, Value>) -> Vec<Candidate> {
+    let Some(schema) = field_schema(path, schemas) else {
+        return Vec::new();
+    };
+    let mut values: Vec<Value> = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+        values.extend(variants.iter().filter_map(|v| v.get("const").cloned()));
+    }
+    if type_names(&schema).contains(&"boolean") {
+        values.extend([Value::Bool(true), Value::Bool(false)]);
+    }
+    values
+        .into_iter()
+        .map(|value| value.to_string())
+        .filter(|token| {
+            token
+                .trim_matches('"')
+                .starts_with(prefix.trim_matches('"'))
+        })
+        .map(|token| (token.clone(), token, None))
+        .collect()
+}
+
 /// Simple hash function for cache keys (simplified version)
 fn sha256_hash(input: &str) -> String {
     // In a real implementation, you'd use a proper hash function
@@ -591,6 +909,55 @@ fn sha256_hash(input: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_suggest_completions_from_schemas() {
+        use serde_json::json;
+
+        let f32_ref = json!({"type": {"$ref": "#/$defs/f32"}});
+        let schemas = HashMap::from([
+            (
+                "bevy_transform::components::transform::Transform".to_string(),
+                json!({"reflectTypes": ["Component"], "properties": {
+                    "translation": {"type": {"$ref": "#/$defs/glam::Vec3"}},
+                    "scale": {"type": {"$ref": "#/$defs/glam::Vec3"}},
+                }}),
+            ),
+            (
+                "glam::Vec3".to_string(),
+                json!({"reflectTypes": ["Default"], "properties": {"x": f32_ref, "y": f32_ref, "z": f32_ref}}),
+            ),
+            (
+                "game::State".to_string(),
+                json!({"reflectTypes": ["Component"], "properties": {"mode": {"type": {"$ref": "#/$defs/game::Mode"}}}}),
+            ),
+            (
+                "game::Mode".to_string(),
+                json!({"type": "string", "enum": ["Idle", "Chasing"]}),
+            ),
+        ]);
+        let suggest =
+            |partial: &str| suggest_completions(partial, &schemas, DEFAULT_COMPLETION_LIMIT);
+
+        let (kind, completions) = suggest("Tra");
+        assert_eq!(kind, QueryCompletionKind::Component);
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].text, "Transform");
+
+        let (kind, completions) = suggest("Transform.tr");
+        assert_eq!(kind, QueryCompletionKind::Field);
+        assert_eq!(completions[0].text, "Transform.translation");
+        assert_eq!(completions[0].detail.as_deref(), Some("glam::Vec3"));
+
+        let (kind, completions) = suggest("Transform.translation.x ");
+        assert_eq!(kind, QueryCompletionKind::Operator);
+        assert!(completions.iter().any(|c| c.label == "gte"));
+        assert!(!completions.iter().any(|c| c.label == "contains"));
+
+        let (kind, completions) = suggest("State.mode eq C");
+        assert_eq!(kind, QueryCompletionKind::Value);
+        assert_eq!(completions[0].text, "State.mode eq \"Chasing\"");
+    }
+
     #[test]
     fn test_query_builder_basic() {
         let query = QueryBuilder::new()
@@ -5,7 +5,10 @@
 use crate::brp_messages::{DebugCommand, DebugResponse, ValidatedQuery};
 use crate::brp_client::BrpClient;
 use crate::debug_command_processor::DebugCommandProcessor;
-use crate::query_builder::{QueryBuilder, QueryValidator, QueryOptimizer};
+use crate::query_builder::{
+    suggest_completions, QueryBuilder, QueryValidator, QueryOptimizer, DEFAULT_COMPLETION_LIMIT,
+};
+use crate::tools::mutate;
 use crate::error::{Error, Result};
 use async_trait::async_trait;
 use serde_json::Value;
@@ -15,6 +18,9 @@ use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Longest partial query `QuerySuggest` accepts
+const MAX_PARTIAL_QUERY_LEN: usize = 1024;
+
 /// Cache for query results and validations
 #[derive(Debug, Clone)]
 struct QueryCache {
@@ -325,6 +331,23 @@ impl QueryBuilderProcessor {
         })
     }
 
+    /// Complete a partially typed filter from the cached component schemas
+    async fn handle_query_suggest(&self, partial: String, limit: Option<usize>) -> Result<DebugResponse> {
+        debug!("Handling query suggest request: {:?}", partial);
+
+        let mut schemas = mutate::cached_schemas();
+        if schemas.is_empty() && self.brp_client.read().await.is_connected() {
+            match mutate::refresh_schemas(&self.brp_client).await {
+                Ok(refreshed) => schemas = refreshed,
+                Err(e) => warn!("Could not read component schemas from the game: {}", e),
+            }
+        }
+
+        let (kind, completions) =
+            suggest_completions(&partial, &schemas, limit.unwrap_or(DEFAULT_COMPLETION_LIMIT));
+        Ok(DebugResponse::QueryCompletions { kind, completions })
+    }
+
     /// Build and execute a query using the QueryBuilder
     async fn handle_build_and_execute(&self, params: Value) -> Result<DebugResponse> {
         debug!("Handling build and execute request: {:?}", params);
@@ -456,6 +479,9 @@ impl DebugCommandProcessor for QueryBuilderProcessor {
             DebugCommand::BuildAndExecuteQuery { params } => {
                 self.handle_build_and_execute(params).await
             }
+            DebugCommand::QuerySuggest { partial, limit } => {
+                self.handle_query_suggest(partial, limit).await
+            }
             _ => Err(Error::DebugError(
                 "Unsupported command for query builder processor".to_string(),
             )),
@@ -488,6 +514,15 @@ impl DebugCommandProcessor for QueryBuilderProcessor {
 
                 Ok(())
             }
+            DebugCommand::QuerySuggest { partial, .. } => {
+                if partial.len() > MAX_PARTIAL_QUERY_LEN {
+                    return Err(Error::Validation(format!(
+                        "Partial query is longer than {} characters",
+                        MAX_PARTIAL_QUERY_LEN
+                    )));
+                }
+                Ok(())
+            }
             _ => Err(Error::DebugError("Command not supported by query builder processor".to_string())),
         }
     }
@@ -497,6 +532,7 @@ impl DebugCommandProcessor for QueryBuilderProcessor {
             DebugCommand::ValidateQuery { .. } => Duration::from_millis(10),
             DebugCommand::EstimateCost { .. } => Duration::from_millis(5),
             DebugCommand::GetQuerySuggestions { .. } => Duration::from_millis(15),
+            DebugCommand::QuerySuggest { .. } => Duration::from_millis(5),
             DebugCommand::BuildAndExecuteQuery { .. } => Duration::from_millis(100), // Actual BRP execution
             _ => Duration::from_millis(1),
         }
@@ -508,7 +544,8 @@ impl DebugCommandProcessor for QueryBuilderProcessor {
             DebugCommand::ValidateQuery { .. } |
             DebugCommand::EstimateCost { .. } |
             DebugCommand::GetQuerySuggestions { .. } |
-            DebugCommand::BuildAndExecuteQuery { .. }
+            DebugCommand::BuildAndExecuteQuery { .. } |
+            DebugCommand::QuerySuggest { .. }
         )
     }
 }
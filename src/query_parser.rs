@@ -276,10 +276,18 @@ struct CachedResult {
     entity_count: usize,
 }
 
+/// How long a "not found" answer is reused by default
+pub const NOT_FOUND_TTL: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Query cache with TTL support
+///
+/// "Not found" answers are kept separately with a much shorter TTL, so a
+/// missing entity or component that later appears is noticed quickly.
 pub struct QueryCache {
     cache: dashmap::DashMap<String, CachedResult>,
     ttl_seconds: u64,
+    not_found: dashmap::DashMap<String, (serde_json::Value, std::time::Instant)>,
+    not_found_ttl: std::time::Duration,
 }
 
 impl QueryCache {
@@ -289,9 +297,40 @@ impl QueryCache {
         Self {
             cache: dashmap::DashMap::new(),
             ttl_seconds,
+            not_found: dashmap::DashMap::new(),
+            not_found_ttl: NOT_FOUND_TTL,
         }
     }
 
+    /// Reuse "not found" answers for `ttl` instead of [`NOT_FOUND_TTL`]
+    #[must_use]
+    pub fn with_not_found_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.not_found_ttl = ttl;
+        self
+    }
+
+    /// Get a cached "not found" answer for `query` if it is still fresh
+    pub fn get_not_found(&self, query: &str) -> Option<serde_json::Value> {
+        let entry = self.not_found.get(query)?;
+        if entry.1.elapsed() > self.not_found_ttl {
+            drop(entry);
+            self.not_found.remove(query);
+            return None;
+        }
+        Some(entry.0.clone())
+    }
+
+    /// Remember that `query` found nothing
+    pub fn set_not_found(&self, query: String, answer: serde_json::Value) {
+        self.not_found
+            .insert(query, (answer, std::time::Instant::now()));
+    }
+
+    /// Forget every "not found" answer, e.g. after a spawn or new component registration
+    pub fn clear_not_found(&self) {
+        self.not_found.clear();
+    }
+
     /// Get cached result if available and not expired
     pub fn get(&self, query: &str) -> Option<(serde_json::Value, usize)> {
         let entry = self.cache.get(query)?;
@@ -317,6 +356,12 @@ impl QueryCache {
         );
     }
 
+    /// Drop every cached result, including "not found" answers
+    pub fn clear(&self) {
+        self.cache.clear();
+        self.not_found.clear();
+    }
+
     /// Clear expired entries from cache
     pub fn cleanup(&self) {
         let cutoff = std::time::Instant::now() - std::time::Duration::from_secs(self.ttl_seconds);
@@ -334,6 +379,7 @@ impl QueryCache {
             .filter(|entry| entry.timestamp.elapsed().as_secs() > self.ttl_seconds)
             .count();
         stats.insert("expired_entries".to_string(), expired_count);
+        stats.insert("not_found_entries".to_string(), self.not_found.len());
 
         stats
     }
@@ -411,6 +457,26 @@ mod tests {
         assert_eq!(cached.1, 0);
     }
 
+    #[test]
+    fn test_not_found_cache() {
+        let cache = QueryCache::new(300).with_not_found_ttl(std::time::Duration::from_millis(50));
+        let query = "show entity 999";
+        let answer = serde_json::json!({"error": "BRP error", "code": "entity_not_found"});
+
+        cache.set_not_found(query.to_string(), answer.clone());
+        assert_eq!(cache.get_not_found(query), Some(answer.clone()));
+        assert!(cache.get(query).is_none());
+
+        // Short TTL expiry
+        std::thread::sleep(std::time::Duration::from_millis(80));
+        assert!(cache.get_not_found(query).is_none());
+
+        // A spawn or registration clears it straight away
+        cache.set_not_found(query.to_string(), answer);
+        cache.clear_not_found();
+        assert!(cache.get_not_found(query).is_none());
+    }
+
     #[test]
     fn test_multiple_components_query() {
         let parser = RegexQueryParser::new().unwrap();
@@ -0,0 +1,449 @@
+/*
+ * Bevy Debugger MCP Server - Per-Role and Per-Tool Rate Limits
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use dashmap::DashMap;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::error::{Error, Result};
+use crate::security::Role;
+
+/// A token bucket: `burst` calls at once, refilled at `per_minute`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub per_minute: u32,
+    #[serde(default)]
+    pub burst: Option<u32>,
+}
+
+impl RateLimit {
+    pub fn new(per_minute: u32, burst: u32) -> Self {
+        Self {
+            per_minute,
+            burst: Some(burst),
+        }
+    }
+
+    /// Bucket size; a limit without a burst allows a minute's worth at once
+    fn capacity(&self) -> f64 {
+        self.burst.unwrap_or(self.per_minute).max(1) as f64
+    }
+
+    fn refill_per_second(&self) -> f64 {
+        self.per_minute as f64 / 60.0
+    }
+}
+
+/// Limits for one tool, optionally different per role
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolRateLimit {
+    #[serde(flatten)]
+    pub limit: RateLimit,
+    #[serde(default)]
+    pub roles: HashMap<Role, RateLimit>,
+}
+
+impl ToolRateLimit {
+    fn for_role(&self, role: &Role) -> RateLimit {
+        self.roles.get(role).copied().unwrap_or(self.limit)
+    }
+}
+
+/// Per-user limits by role, plus per-user limits on individual tools
+///
+/// Every call counts against the caller's role limit; calls to a listed tool
+/// also count against that tool's limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Limit for roles not listed in `roles`
+    pub default: RateLimit,
+    #[serde(default)]
+    pub roles: HashMap<Role, RateLimit>,
+    #[serde(default)]
+    pub tools: HashMap<String, ToolRateLimit>,
+}
+
+impl RateLimitConfig {
+    /// The same limit for everyone and no tool limits
+    pub fn uniform(limit: RateLimit) -> Self {
+        Self {
+            default: limit,
+            roles: HashMap::new(),
+            tools: HashMap::new(),
+        }
+    }
+
+    pub fn role_limit(&self, role: &Role) -> RateLimit {
+        self.roles.get(role).copied().unwrap_or(self.default)
+    }
+
+    /// Reject limits that would block every call
+    pub fn validate(&self) -> Result<()> {
+        let limits = std::iter::once(("default".to_string(), &self.default))
+            .chain(
+                self.roles
+                    .iter()
+                    .map(|(role, limit)| (format!("{:?}", role), limit)),
+            )
+            .chain(self.tools.iter().flat_map(|(tool, rule)| {
+                std::iter::once((tool.clone(), &rule.limit)).chain(
+                    rule.roles
+                        .iter()
+                        .map(move |(role, limit)| (format!("{}.{:?}", tool, role), limit)),
+                )
+            }));
+        for (name, limit) in limits {
+            if limit.per_minute == 0 {
+                return Err(Error::Validation(format!(
+                    "Rate limit '{}' needs per_minute above 0",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse a rate limit file based on its extension
+pub fn parse_rate_limits(path: &Path, contents: &str) -> Result<RateLimitConfig> {
+    let config: RateLimitConfig = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid TOML rate limits: {e}")))?,
+        _ => serde_json::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid JSON rate limits: {e}")))?,
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+/// Remaining quota in one bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaStatus {
+    /// `*` for the role limit, else the tool name
+    pub scope: String,
+    pub per_minute: u32,
+    pub burst: u32,
+    /// Calls that can be made right now
+    pub remaining: u32,
+}
+
+/// A user's quotas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    pub user_id: String,
+    pub role: Role,
+    pub quotas: Vec<QuotaStatus>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// Limit the bucket was last used under
+    limit: RateLimit,
+}
+
+impl Bucket {
+    /// Refill for the time passed, never above the current capacity
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.refill_per_second()).min(limit.capacity());
+        self.updated = now;
+        self.limit = *limit;
+    }
+}
+
+const ROLE_SCOPE: &str = "*";
+
+/// Per-user token buckets under limits reloaded when their file changes
+#[derive(Clone)]
+pub struct UserRateLimiter {
+    path: Option<PathBuf>,
+    config: Arc<RwLock<Arc<RateLimitConfig>>>,
+    buckets: Arc<DashMap<(String, String), Bucket>>,
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+}
+
+impl std::fmt::Debug for UserRateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserRateLimiter")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UserRateLimiter {
+    /// Load limits from `path`, or use `fallback` when `None`
+    pub fn new(path: Option<&str>, fallback: RateLimitConfig) -> Result<Self> {
+        let path = path.map(PathBuf::from);
+        let config = match &path {
+            Some(path) => Self::read(path)?,
+            None => fallback,
+        };
+        Ok(Self {
+            path,
+            config: Arc::new(RwLock::new(Arc::new(config))),
+            buckets: Arc::new(DashMap::new()),
+            watcher: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    fn read(path: &Path) -> Result<RateLimitConfig> {
+        parse_rate_limits(path, &std::fs::read_to_string(path)?)
+    }
+
+    pub fn current(&self) -> Arc<RateLimitConfig> {
+        self.config
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Re-read the limits file; an invalid file leaves the current limits in place
+    pub fn reload(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let config = Self::read(path)?;
+        *self
+            .config
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(config);
+        info!("Reloaded rate limits from {}", path.display());
+        Ok(())
+    }
+
+    /// Reload the limits whenever their file is written
+    pub fn watch(&self) -> Result<()> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        // The watcher only holds the limits, so dropping the limiter stops it
+        let limiter = Self {
+            path: Some(path.clone()),
+            config: self.config.clone(),
+            buckets: self.buckets.clone(),
+            watcher: Arc::new(Mutex::new(None)),
+        };
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|changed| {
+                    changed.file_name().map(|name| name.to_os_string()) == file_name
+                });
+            if relevant {
+                if let Err(e) = limiter.reload() {
+                    error!("Keeping previous rate limits: {}", e);
+                }
+            }
+        })
+        .map_err(|e| Error::Validation(format!("Failed to create rate limit watcher: {}", e)))?;
+
+        let directory = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::Validation(format!("Failed to watch rate limits: {}", e)))?;
+        *self
+            .watcher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(watcher);
+        Ok(())
+    }
+
+    /// Take one call from the user's role bucket and, for limited tools, the tool bucket
+    pub fn check(&self, user_id: &str, role: &Role, tool: &str) -> Result<()> {
+        let config = self.current();
+        let mut scopes = vec![(ROLE_SCOPE.to_string(), config.role_limit(role))];
+        if let Some(rule) = config.tools.get(tool) {
+            scopes.push((tool.to_string(), rule.for_role(role)));
+        }
+
+        let now = Instant::now();
+        let mut buckets = Vec::with_capacity(scopes.len());
+        for (scope, limit) in &scopes {
+            let mut bucket = self
+                .buckets
+                .entry((user_id.to_string(), scope.clone()))
+                .or_insert(Bucket {
+                    tokens: limit.capacity(),
+                    updated: now,
+                    limit: *limit,
+                });
+            bucket.refill(limit, now);
+            if bucket.tokens < 1.0 {
+                let wait = (1.0 - bucket.tokens) / limit.refill_per_second();
+                let scope = if scope == ROLE_SCOPE {
+                    "all tools".to_string()
+                } else {
+                    scope.clone()
+                };
+                return Err(Error::RateLimited(format!(
+                    "Rate limit exceeded for {}; retry in {:.0} seconds",
+                    scope,
+                    wait.ceil()
+                )));
+            }
+            buckets.push((user_id.to_string(), scope.clone()));
+        }
+
+        // Only spend once every bucket has room, so a rejected call costs nothing
+        for key in buckets {
+            if let Some(mut bucket) = self.buckets.get_mut(&key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remaining quota of the role limit and each tool limit that applies to `role`
+    pub fn status(&self, user_id: &str, role: &Role) -> RateLimitStatus {
+        let config = self.current();
+        let now = Instant::now();
+        let mut scopes = vec![(ROLE_SCOPE.to_string(), config.role_limit(role))];
+        let mut tools: Vec<_> = config
+            .tools
+            .iter()
+            .map(|(tool, rule)| (tool.clone(), rule.for_role(role)))
+            .collect();
+        tools.sort_by(|a, b| a.0.cmp(&b.0));
+        scopes.extend(tools);
+
+        let quotas = scopes
+            .into_iter()
+            .map(|(scope, limit)| {
+                let remaining = match self.buckets.get(&(user_id.to_string(), scope.clone())) {
+                    Some(bucket) => {
+                        let mut bucket = *bucket;
+                        bucket.refill(&limit, now);
+                        bucket.tokens.floor() as u32
+                    }
+                    None => limit.capacity() as u32,
+                };
+                QuotaStatus {
+                    scope,
+                    per_minute: limit.per_minute,
+                    burst: limit.capacity() as u32,
+                    remaining,
+                }
+            })
+            .collect();
+
+        RateLimitStatus {
+            user_id: user_id.to_string(),
+            role: role.clone(),
+            quotas,
+        }
+    }
+
+    /// Drop buckets that have refilled completely; a new bucket starts full anyway
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.buckets.retain(|_, bucket| {
+            let limit = bucket.limit;
+            bucket.refill(&limit, now);
+            bucket.tokens < limit.capacity()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_and_tool_limits() {
+        let config = parse_rate_limits(
+            Path::new("limits.toml"),
+            r#"
+                default = { per_minute = 60, burst = 3 }
+
+                [roles.Admin]
+                per_minute = 600
+                burst = 5
+
+                [tools.stress_test]
+                per_minute = 1
+                burst = 1
+
+                [tools.stress_test.roles.Admin]
+                per_minute = 2
+                burst = 2
+            "#,
+        )
+        .unwrap();
+        let limiter = UserRateLimiter::new(None, config).unwrap();
+
+        for _ in 0..3 {
+            limiter.check("viewer", &Role::Viewer, "observe").unwrap();
+        }
+        assert!(limiter.check("viewer", &Role::Viewer, "observe").is_err());
+        // Buckets are per user
+        limiter.check("other", &Role::Viewer, "observe").unwrap();
+
+        limiter
+            .check("dev", &Role::Developer, "stress_test")
+            .unwrap();
+        let error = limiter
+            .check("dev", &Role::Developer, "stress_test")
+            .unwrap_err();
+        assert!(error.to_string().contains("stress_test"));
+        // The rejected tool call did not spend from the role bucket
+        let status = limiter.status("dev", &Role::Developer);
+        assert_eq!(status.quotas[0].scope, "*");
+        assert_eq!(status.quotas[0].remaining, 2);
+        assert_eq!(status.quotas[1].remaining, 0);
+
+        limiter.check("admin", &Role::Admin, "stress_test").unwrap();
+        limiter.check("admin", &Role::Admin, "stress_test").unwrap();
+        assert_eq!(limiter.status("admin", &Role::Admin).quotas[0].remaining, 3);
+    }
+
+    #[test]
+    fn test_reload_changes_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("limits.json");
+        std::fs::write(&path, r#"{"default": {"per_minute": 60, "burst": 1}}"#).unwrap();
+
+        let limiter = UserRateLimiter::new(
+            path.to_str(),
+            RateLimitConfig::uniform(RateLimit::new(1, 1)),
+        )
+        .unwrap();
+        limiter.check("u", &Role::Viewer, "observe").unwrap();
+        assert!(limiter.check("u", &Role::Viewer, "observe").is_err());
+
+        std::fs::write(&path, r#"{"default": {"per_minute": 0}}"#).unwrap();
+        assert!(limiter.reload().is_err());
+
+        std::fs::write(&path, r#"{"default": {"per_minute": 60, "burst": 10}}"#).unwrap();
+        limiter.reload().unwrap();
+        assert_eq!(limiter.current().role_limit(&Role::Viewer).capacity(), 10.0);
+    }
+}
@@ -0,0 +1,355 @@
+/*
+ * Bevy Debugger MCP Server - Tool Access Policy
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::error::{Error, Result};
+use crate::security::Role;
+
+/// Who may call one tool
+///
+/// A caller is allowed when their role meets `min_role`, their role is listed
+/// in `roles`, or their user id is listed in `users`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolRule {
+    #[serde(default)]
+    pub min_role: Option<Role>,
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+impl ToolRule {
+    fn min_role(role: Role) -> Self {
+        Self {
+            min_role: Some(role),
+            ..Self::default()
+        }
+    }
+
+    fn allows(&self, role: &Role, subject: &str) -> bool {
+        self.min_role
+            .as_ref()
+            .is_some_and(|min_role| role.has_permission(min_role))
+            || self.roles.contains(role)
+            || self.users.iter().any(|user| user == subject)
+    }
+}
+
+/// Tool to rule mapping, with a minimum role for tools it does not list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RbacPolicy {
+    #[serde(default = "default_min_role")]
+    pub default_min_role: Role,
+    #[serde(default)]
+    pub tools: HashMap<String, ToolRule>,
+}
+
+fn default_min_role() -> Role {
+    Role::Developer
+}
+
+impl Default for RbacPolicy {
+    /// The built-in policy used when no policy file is configured
+    fn default() -> Self {
+        let mut tools = HashMap::new();
+        for tool in ["observe", "detect_anomaly", "types", "search_world"] {
+            tools.insert(tool.to_string(), ToolRule::min_role(Role::Guest));
+        }
+        for tool in [
+            "hypothesis",
+            "resource_metrics",
+            "performance_dashboard",
+            "health_check",
+            "diagnostic_report",
+            "metrics_query",
+            "compare_recordings",
+            "state_at",
+            "get_suggestions",
+            "get_workflows",
+            "get_model_versions",
+        ] {
+            tools.insert(tool.to_string(), ToolRule::min_role(Role::Viewer));
+        }
+        for tool in ["experiment", "mutate", "stress_test", "time_travel_replay"] {
+            tools.insert(tool.to_string(), ToolRule::min_role(Role::Developer));
+        }
+        for tool in [
+            "user_management",
+            "audit_log_access",
+            "session_management",
+            "api_key_management",
+            "signing_key_management",
+            "policy_management",
+            "security_scan",
+            "schedules",
+            "hot_reload",
+        ] {
+            tools.insert(tool.to_string(), ToolRule::min_role(Role::Admin));
+        }
+        Self {
+            default_min_role: default_min_role(),
+            tools,
+        }
+    }
+}
+
+/// Outcome of checking one call against the policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub operation: String,
+    pub allowed: bool,
+    /// Which part of the policy decided: `tools.<name>` or `default`
+    pub rule: String,
+}
+
+impl RbacPolicy {
+    /// Reject rules that could never allow anyone
+    pub fn validate(&self) -> Result<()> {
+        for (tool, rule) in &self.tools {
+            if rule.min_role.is_none() && rule.roles.is_empty() && rule.users.is_empty() {
+                return Err(Error::Validation(format!(
+                    "Policy rule for '{}' needs min_role, roles or users",
+                    tool
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `subject` with `role` may call `operation`
+    pub fn decide(&self, operation: &str, role: &Role, subject: &str) -> PolicyDecision {
+        let (allowed, rule) = match self.tools.get(operation) {
+            Some(rule) => (rule.allows(role, subject), format!("tools.{}", operation)),
+            None => (
+                role.has_permission(&self.default_min_role),
+                "default".to_string(),
+            ),
+        };
+        PolicyDecision {
+            operation: operation.to_string(),
+            allowed,
+            rule,
+        }
+    }
+}
+
+/// Parse a policy file based on its extension
+pub fn parse_policy(path: &Path, contents: &str) -> Result<RbacPolicy> {
+    let policy: RbacPolicy = match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => toml::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid TOML policy: {e}")))?,
+        _ => serde_json::from_str(contents)
+            .map_err(|e| Error::Serialization(format!("Invalid JSON policy: {e}")))?,
+    };
+    policy.validate()?;
+    Ok(policy)
+}
+
+/// The active policy, reloaded when its file changes
+#[derive(Clone)]
+pub struct PolicyStore {
+    path: Option<PathBuf>,
+    policy: Arc<RwLock<Arc<RbacPolicy>>>,
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+}
+
+impl std::fmt::Debug for PolicyStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PolicyStore")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PolicyStore {
+    /// Load the policy at `path`, or use the built-in policy when `None`
+    pub fn new(path: Option<&str>) -> Result<Self> {
+        let path = path.map(PathBuf::from);
+        let policy = match &path {
+            Some(path) => Self::read(path)?,
+            None => RbacPolicy::default(),
+        };
+        Ok(Self {
+            path,
+            policy: Arc::new(RwLock::new(Arc::new(policy))),
+            watcher: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    fn read(path: &Path) -> Result<RbacPolicy> {
+        parse_policy(path, &std::fs::read_to_string(path)?)
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn current(&self) -> Arc<RbacPolicy> {
+        self.policy
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Re-read the policy file; an invalid file leaves the current policy in place
+    pub fn reload(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let policy = Self::read(path)?;
+        *self
+            .policy
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(policy);
+        info!("Reloaded tool policy from {}", path.display());
+        Ok(())
+    }
+
+    /// Reload the policy whenever its file is written
+    pub fn watch(&self) -> Result<()> {
+        let Some(path) = self.path.clone() else {
+            return Ok(());
+        };
+        // The watcher only holds the policy, so dropping the store stops it
+        let store = Self {
+            path: Some(path.clone()),
+            policy: self.policy.clone(),
+            watcher: Arc::new(Mutex::new(None)),
+        };
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                && event.paths.iter().any(|changed| {
+                    changed.file_name().map(|name| name.to_os_string()) == file_name
+                });
+            if relevant {
+                if let Err(e) = store.reload() {
+                    error!("Keeping previous tool policy: {}", e);
+                }
+            }
+        })
+        .map_err(|e| Error::Validation(format!("Failed to create policy watcher: {}", e)))?;
+
+        // Watch the directory so editors that replace the file are noticed
+        let directory = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::Validation(format!("Failed to watch tool policy: {}", e)))?;
+        *self
+            .watcher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(watcher);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_matches_builtin_roles() {
+        let policy = RbacPolicy::default();
+        assert!(policy.decide("observe", &Role::Viewer, "viewer").allowed);
+        assert!(!policy.decide("experiment", &Role::Viewer, "viewer").allowed);
+        assert!(policy.decide("experiment", &Role::Developer, "dev").allowed);
+        assert!(
+            !policy
+                .decide("user_management", &Role::Developer, "dev")
+                .allowed
+        );
+
+        let decision = policy.decide("unlisted_tool", &Role::Viewer, "viewer");
+        assert!(!decision.allowed);
+        assert_eq!(decision.rule, "default");
+    }
+
+    #[test]
+    fn test_toml_policy_with_explicit_permissions() {
+        let policy = parse_policy(
+            Path::new("policy.toml"),
+            r#"
+                default_min_role = "Admin"
+
+                [tools.observe]
+                min_role = "Viewer"
+
+                [tools.stress_test]
+                roles = ["Admin"]
+                users = ["perf-bot"]
+            "#,
+        )
+        .unwrap();
+
+        assert!(policy.decide("observe", &Role::Viewer, "v").allowed);
+        assert!(!policy.decide("experiment", &Role::Developer, "d").allowed);
+        assert!(!policy.decide("stress_test", &Role::Developer, "d").allowed);
+        let decision = policy.decide("stress_test", &Role::Viewer, "perf-bot");
+        assert!(decision.allowed);
+        assert_eq!(decision.rule, "tools.stress_test");
+
+        assert!(parse_policy(Path::new("policy.json"), r#"{"tools": {"observe": {}}}"#).is_err());
+    }
+
+    #[test]
+    fn test_invalid_reload_keeps_previous_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.json");
+        std::fs::write(&path, r#"{"tools": {"observe": {"min_role": "Admin"}}}"#).unwrap();
+
+        let store = PolicyStore::new(path.to_str()).unwrap();
+        assert!(
+            !store
+                .current()
+                .decide("observe", &Role::Viewer, "v")
+                .allowed
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(store.reload().is_err());
+        assert!(
+            !store
+                .current()
+                .decide("observe", &Role::Viewer, "v")
+                .allowed
+        );
+
+        std::fs::write(&path, r#"{"tools": {"observe": {"min_role": "Viewer"}}}"#).unwrap();
+        store.reload().unwrap();
+        assert!(
+            store
+                .current()
+                .decide("observe", &Role::Viewer, "v")
+                .allowed
+        );
+    }
+}
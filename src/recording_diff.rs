@@ -0,0 +1,568 @@
+/*
+ * Bevy Debugger MCP Server - Recording Comparison
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::recording_system::{Frame, Recording, Timeline};
+use crate::state_diff::{FuzzyCompareConfig, FuzzyPartialEq};
+
+/// How frames of two recordings are paired up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlignMode {
+    /// Same frame number
+    Frame,
+    /// Nearest timestamp, for runs recorded at different rates
+    Timestamp,
+}
+
+/// Options for [`compare_recordings`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingDiffConfig {
+    pub align: AlignMode,
+    /// Tolerance for numeric component values
+    pub fuzzy: FuzzyCompareConfig,
+    /// Only compare these components
+    pub component_filter: Option<Vec<String>>,
+    /// How many divergent frames to report in detail
+    pub max_reported_frames: usize,
+}
+
+impl Default for RecordingDiffConfig {
+    fn default() -> Self {
+        Self {
+            align: AlignMode::Frame,
+            fuzzy: FuzzyCompareConfig::default(),
+            component_filter: None,
+            max_reported_frames: 50,
+        }
+    }
+}
+
+/// A component whose value differs between the runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentDivergence {
+    pub entity_id: u64,
+    pub component: String,
+    pub a: Option<Value>,
+    pub b: Option<Value>,
+}
+
+/// Differences between one aligned pair of frames
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameDivergence {
+    pub frame_a: usize,
+    pub frame_b: usize,
+    pub timestamp_a: Duration,
+    pub timestamp_b: Duration,
+    pub only_in_a: Vec<u64>,
+    pub only_in_b: Vec<u64>,
+    pub components: Vec<ComponentDivergence>,
+}
+
+impl FrameDivergence {
+    fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.components.is_empty()
+    }
+}
+
+/// A run of consecutive divergent frames, numbered as in recording A
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivergenceRange {
+    pub start_frame: usize,
+    pub end_frame: usize,
+}
+
+/// Aggregate numbers for one recording
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordingMetrics {
+    pub frames: usize,
+    pub duration: Duration,
+    pub markers: usize,
+    pub avg_entities: f64,
+    pub max_entities: usize,
+    pub events: usize,
+    pub events_by_type: BTreeMap<String, usize>,
+}
+
+/// One metric side by side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub a: f64,
+    pub b: f64,
+    /// Change from A to B in percent, when A is non-zero
+    pub change_pct: Option<f64>,
+}
+
+/// Result of comparing two recordings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingDiff {
+    pub align: AlignMode,
+    pub aligned_frames: usize,
+    pub unmatched_a: usize,
+    pub unmatched_b: usize,
+    pub divergent_frames: usize,
+    pub first_divergence: Option<FrameDivergence>,
+    pub divergence_ranges: Vec<DivergenceRange>,
+    /// Divergent frame count per component, most divergent first
+    pub components: Vec<(String, usize)>,
+    /// Divergent frame count per entity, most divergent first
+    pub entities: Vec<(u64, usize)>,
+    /// The first `max_reported_frames` divergent frames
+    pub frames: Vec<FrameDivergence>,
+    pub metrics_a: RecordingMetrics,
+    pub metrics_b: RecordingMetrics,
+    pub metric_deltas: Vec<MetricDelta>,
+    pub summary: String,
+}
+
+/// Align two recordings and report where their entity and component streams diverge
+pub fn compare_recordings(
+    a: &Recording,
+    b: &Recording,
+    config: &RecordingDiffConfig,
+) -> RecordingDiff {
+    let index_a = frame_index(a);
+    let index_b = frame_index(b);
+    let pairs = align(&index_a, &index_b, config.align);
+
+    let mut timeline_a = Timeline::new();
+    timeline_a.load_recording(a.clone());
+    let mut timeline_b = Timeline::new();
+    timeline_b.load_recording(b.clone());
+
+    let mut divergent_frames = 0;
+    let mut first_divergence = None;
+    let mut divergence_ranges: Vec<DivergenceRange> = Vec::new();
+    let mut component_counts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut entity_counts: BTreeMap<u64, usize> = BTreeMap::new();
+    let mut frames = Vec::new();
+    let mut previous_divergent: Option<usize> = None;
+
+    for (pair_index, &(frame_a, frame_b)) in pairs.iter().enumerate() {
+        let (Some(a_frame), Some(b_frame)) =
+            (timeline_a.get_frame(frame_a), timeline_b.get_frame(frame_b))
+        else {
+            continue;
+        };
+        let divergence = diff_frames(&a_frame, &b_frame, config);
+        if divergence.is_empty() {
+            continue;
+        }
+
+        divergent_frames += 1;
+        match divergence_ranges.last_mut() {
+            Some(range) if previous_divergent.map(|p| p + 1) == Some(pair_index) => {
+                range.end_frame = frame_a;
+            }
+            _ => divergence_ranges.push(DivergenceRange {
+                start_frame: frame_a,
+                end_frame: frame_a,
+            }),
+        }
+        previous_divergent = Some(pair_index);
+
+        let components: BTreeSet<&str> = divergence
+            .components
+            .iter()
+            .map(|c| c.component.as_str())
+            .collect();
+        for component in components {
+            *component_counts.entry(component.to_string()).or_default() += 1;
+        }
+        let entities: BTreeSet<u64> = divergence
+            .components
+            .iter()
+            .map(|c| c.entity_id)
+            .chain(divergence.only_in_a.iter().copied())
+            .chain(divergence.only_in_b.iter().copied())
+            .collect();
+        for entity in entities {
+            *entity_counts.entry(entity).or_default() += 1;
+        }
+
+        if first_divergence.is_none() {
+            first_divergence = Some(divergence.clone());
+        }
+        if frames.len() < config.max_reported_frames {
+            frames.push(divergence);
+        }
+    }
+
+    let metrics_a = recording_metrics(a, &index_a, &mut timeline_a);
+    let metrics_b = recording_metrics(b, &index_b, &mut timeline_b);
+    let metric_deltas = metric_deltas(&metrics_a, &metrics_b);
+
+    let mut diff = RecordingDiff {
+        align: config.align,
+        aligned_frames: pairs.len(),
+        unmatched_a: index_a.len().saturating_sub(pairs.len()),
+        unmatched_b: index_b.len().saturating_sub(pairs.len()),
+        divergent_frames,
+        first_divergence,
+        divergence_ranges,
+        components: sorted_counts(component_counts),
+        entities: sorted_counts(entity_counts),
+        frames,
+        metrics_a,
+        metrics_b,
+        metric_deltas,
+        summary: String::new(),
+    };
+    diff.summary = summarize(&diff);
+    diff
+}
+
+/// Frame numbers and timestamps available in a recording, in frame order
+fn frame_index(recording: &Recording) -> Vec<(usize, Duration)> {
+    let index: BTreeMap<usize, Duration> = recording
+        .frames
+        .iter()
+        .map(|f| (f.frame_number, f.timestamp))
+        .chain(
+            recording
+                .delta_frames
+                .iter()
+                .map(|d| (d.frame_number, d.timestamp)),
+        )
+        .collect();
+    index.into_iter().collect()
+}
+
+/// Pair frame numbers of A with frame numbers of B
+fn align(
+    index_a: &[(usize, Duration)],
+    index_b: &[(usize, Duration)],
+    mode: AlignMode,
+) -> Vec<(usize, usize)> {
+    match mode {
+        AlignMode::Frame => {
+            let numbers_b: BTreeSet<usize> = index_b.iter().map(|(n, _)| *n).collect();
+            index_a
+                .iter()
+                .filter(|(n, _)| numbers_b.contains(n))
+                .map(|(n, _)| (*n, *n))
+                .collect()
+        }
+        AlignMode::Timestamp => {
+            // (frame A, frame B, distance), each B frame paired at most once
+            let mut pairs: Vec<(usize, usize, Duration)> = Vec::new();
+            let mut j = 0;
+            for &(frame_a, time_a) in index_a {
+                if index_b.is_empty() {
+                    break;
+                }
+                // Timestamps grow with frame number, so the nearest B frame only moves forward
+                while j + 1 < index_b.len()
+                    && distance(index_b[j + 1].1, time_a) < distance(index_b[j].1, time_a)
+                {
+                    j += 1;
+                }
+                let (frame_b, time_b) = index_b[j];
+                let gap = distance(time_b, time_a);
+                match pairs.last_mut() {
+                    // Several A frames share the nearest B frame; keep the closest
+                    Some(last) if last.1 == frame_b => {
+                        if gap < last.2 {
+                            *last = (frame_a, frame_b, gap);
+                        }
+                    }
+                    _ => pairs.push((frame_a, frame_b, gap)),
+                }
+            }
+            pairs.into_iter().map(|(a, b, _)| (a, b)).collect()
+        }
+    }
+}
+
+fn distance(a: Duration, b: Duration) -> Duration {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+fn diff_frames(a: &Frame, b: &Frame, config: &RecordingDiffConfig) -> FrameDivergence {
+    let included = |component: &str| match &config.component_filter {
+        Some(filter) => filter.iter().any(|c| c == component),
+        None => true,
+    };
+
+    let mut divergence = FrameDivergence {
+        frame_a: a.frame_number,
+        frame_b: b.frame_number,
+        timestamp_a: a.timestamp,
+        timestamp_b: b.timestamp,
+        only_in_a: Vec::new(),
+        only_in_b: Vec::new(),
+        components: Vec::new(),
+    };
+
+    let entity_ids: BTreeSet<u64> = a
+        .entities
+        .keys()
+        .chain(b.entities.keys())
+        .copied()
+        .collect();
+    for entity_id in entity_ids {
+        let (entity_a, entity_b) = match (a.entities.get(&entity_id), b.entities.get(&entity_id)) {
+            (Some(entity_a), Some(entity_b)) => (entity_a, entity_b),
+            (Some(_), None) => {
+                divergence.only_in_a.push(entity_id);
+                continue;
+            }
+            (None, _) => {
+                divergence.only_in_b.push(entity_id);
+                continue;
+            }
+        };
+
+        let names: BTreeSet<&String> = entity_a
+            .components
+            .keys()
+            .chain(entity_b.components.keys())
+            .filter(|name| included(name))
+            .collect();
+        for name in names {
+            let value_a = entity_a.components.get(name);
+            let value_b = entity_b.components.get(name);
+            let equal = match (value_a, value_b) {
+                (Some(value_a), Some(value_b)) => value_a.fuzzy_eq(value_b, &config.fuzzy),
+                _ => false,
+            };
+            if !equal {
+                divergence.components.push(ComponentDivergence {
+                    entity_id,
+                    component: name.clone(),
+                    a: value_a.cloned(),
+                    b: value_b.cloned(),
+                });
+            }
+        }
+    }
+    divergence
+}
+
+fn recording_metrics(
+    recording: &Recording,
+    index: &[(usize, Duration)],
+    timeline: &mut Timeline,
+) -> RecordingMetrics {
+    let mut metrics = RecordingMetrics {
+        frames: index.len(),
+        duration: recording.duration,
+        markers: recording.markers.len(),
+        ..RecordingMetrics::default()
+    };
+
+    let mut entity_total = 0;
+    let mut sampled = 0;
+    for &(frame_number, _) in index {
+        let Some(frame) = timeline.get_frame(frame_number) else {
+            continue;
+        };
+        sampled += 1;
+        entity_total += frame.entities.len();
+        metrics.max_entities = metrics.max_entities.max(frame.entities.len());
+        metrics.events += frame.events.len();
+        for event in &frame.events {
+            *metrics
+                .events_by_type
+                .entry(event.event_type.clone())
+                .or_default() += 1;
+        }
+    }
+    if sampled > 0 {
+        metrics.avg_entities = entity_total as f64 / sampled as f64;
+    }
+    metrics
+}
+
+fn metric_deltas(a: &RecordingMetrics, b: &RecordingMetrics) -> Vec<MetricDelta> {
+    let delta = |metric: String, a: f64, b: f64| MetricDelta {
+        metric,
+        a,
+        b,
+        change_pct: (a != 0.0).then(|| (b - a) / a * 100.0),
+    };
+
+    let mut deltas = vec![
+        delta("frames".to_string(), a.frames as f64, b.frames as f64),
+        delta(
+            "duration_seconds".to_string(),
+            a.duration.as_secs_f64(),
+            b.duration.as_secs_f64(),
+        ),
+        delta("avg_entities".to_string(), a.avg_entities, b.avg_entities),
+        delta(
+            "max_entities".to_string(),
+            a.max_entities as f64,
+            b.max_entities as f64,
+        ),
+        delta("events".to_string(), a.events as f64, b.events as f64),
+    ];
+    let event_types: BTreeSet<&String> = a
+        .events_by_type
+        .keys()
+        .chain(b.events_by_type.keys())
+        .collect();
+    for event_type in event_types {
+        let count = |m: &RecordingMetrics| m.events_by_type.get(event_type).copied().unwrap_or(0);
+        deltas.push(delta(
+            format!("events.{event_type}"),
+            count(a) as f64,
+            count(b) as f64,
+        ));
+    }
+    deltas
+}
+
+fn sorted_counts<K: Ord>(counts: BTreeMap<K, usize>) -> Vec<(K, usize)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    // Stable, so ties keep key order
+    counts.sort_by_key(|c| Reverse(c.1));
+    counts
+}
+
+fn summarize(diff: &RecordingDiff) -> String {
+    let Some(first) = &diff.first_divergence else {
+        return format!(
+            "Recordings match across {} aligned frames",
+            diff.aligned_frames
+        );
+    };
+
+    let mut what: Vec<String> = first
+        .components
+        .iter()
+        .take(3)
+        .map(|c| format!("entity {} {}", c.entity_id, c.component))
+        .collect();
+    if !first.only_in_a.is_empty() {
+        what.push(format!("{} entities only in A", first.only_in_a.len()));
+    }
+    if !first.only_in_b.is_empty() {
+        what.push(format!("{} entities only in B", first.only_in_b.len()));
+    }
+
+    let mut summary = format!(
+        "Runs diverge at frame {} ({:.2}s): {}. {} of {} aligned frames differ",
+        first.frame_a,
+        first.timestamp_a.as_secs_f64(),
+        what.join(", "),
+        diff.divergent_frames,
+        diff.aligned_frames
+    );
+    if !diff.components.is_empty() {
+        let top: Vec<String> = diff
+            .components
+            .iter()
+            .take(3)
+            .map(|(name, count)| format!("{name} ({count})"))
+            .collect();
+        summary.push_str(&format!("; most divergent components: {}", top.join(", ")));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording_system::{EntityState, RecordingConfig};
+    use std::collections::HashMap;
+
+    fn recording(frame_ms: u64, health: impl Fn(usize) -> f64) -> Recording {
+        let frames: Vec<Frame> = (0..10)
+            .map(|n| Frame {
+                frame_number: n,
+                timestamp: Duration::from_millis(n as u64 * frame_ms),
+                entities: HashMap::from([(
+                    1,
+                    EntityState {
+                        entity_id: 1,
+                        components: HashMap::from([
+                            ("Health".to_string(), serde_json::json!(health(n))),
+                            ("Name".to_string(), serde_json::json!("player")),
+                        ]),
+                        active: true,
+                    },
+                )]),
+                events: Vec::new(),
+                checksum: None,
+            })
+            .collect();
+        Recording {
+            config: RecordingConfig::default(),
+            total_frames: frames.len(),
+            duration: Duration::from_millis(10 * frame_ms),
+            frames,
+            delta_frames: Vec::new(),
+            markers: Vec::new(),
+            version: crate::playback_system::RecordingVersion::current(),
+        }
+    }
+
+    #[test]
+    fn test_frame_aligned_divergence() {
+        let main = recording(33, |_| 100.0);
+        let branch = recording(33, |n| if n < 6 { 100.0 + 1e-9 } else { 50.0 });
+
+        let diff = compare_recordings(&main, &branch, &RecordingDiffConfig::default());
+        assert_eq!(diff.aligned_frames, 10);
+        assert_eq!(diff.divergent_frames, 4);
+        let first = diff.first_divergence.as_ref().unwrap();
+        assert_eq!(first.frame_a, 6);
+        assert_eq!(first.components[0].component, "Health");
+        assert_eq!(diff.divergence_ranges.len(), 1);
+        assert_eq!(diff.divergence_ranges[0].end_frame, 9);
+        assert_eq!(diff.components, vec![("Health".to_string(), 4)]);
+        assert!(diff.summary.starts_with("Runs diverge at frame 6"));
+
+        let same = compare_recordings(&main, &main, &RecordingDiffConfig::default());
+        assert!(same.first_divergence.is_none());
+        assert_eq!(same.summary, "Recordings match across 10 aligned frames");
+    }
+
+    #[test]
+    fn test_timestamp_alignment() {
+        // B samples half as often as A
+        let a = recording(33, |n| n as f64);
+        let b = recording(66, |n| (n * 2) as f64);
+
+        let config = RecordingDiffConfig {
+            align: AlignMode::Timestamp,
+            ..RecordingDiffConfig::default()
+        };
+        let diff = compare_recordings(&a, &b, &config);
+        // Even A frames line up with B; B frames past A's last timestamp go unmatched
+        assert_eq!(diff.aligned_frames, 5);
+        assert_eq!(diff.divergent_frames, 0);
+        assert_eq!((diff.unmatched_a, diff.unmatched_b), (5, 5));
+
+        // Without a timestamp match the same runs look completely different
+        let by_frame = compare_recordings(&a, &b, &RecordingDiffConfig::default());
+        assert_eq!(by_frame.divergent_frames, 9);
+    }
+}
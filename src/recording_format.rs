@@ -0,0 +1,735 @@
+/*
+ * Bevy Debugger MCP Server - Recording File Format
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Versioned on-disk format for replay recordings
+//!
+//! A recording file is laid out as follows, with integers little endian:
+//!
+//! ```text
+//! "BEVYREC\0"                  8 byte magic
+//! header length: u32, header   JSON `RecordingHeader`
+//! chunk length: u32, chunk     repeated; JSON array of frames or delta frames
+//! index                        JSON `RecordingIndex`
+//! index offset: u64            where the index starts
+//! "BEVYIDX\0"                  8 byte magic
+//! ```
+//!
+//! Chunks are compressed as the header says; the header and index never
+//! are, so a file can be inspected without decoding any frames.
+//!
+//! Format versions:
+//! - 1: a bare bincode `Recording`, optionally gzipped, with no header.
+//!   Written before this format existed; read by converting it on load.
+//! - 2: the layout above, with gzip compressed chunks.
+//! - 3: chunks may also be zstd compressed, which compressing recordings now uses.
+//!
+//! Changing what gets written means bumping [`RECORDING_FORMAT_VERSION`] and
+//! adding an entry to `MIGRATIONS` that rewrites the previous version's header
+//! and chunks, so older files keep loading. [`migrate_file`] rewrites a file
+//! in the current version.
+
+use chrono::{DateTime, Utc};
+use flate2::read::{GzDecoder, GzEncoder};
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+use crate::error::{Error, Result};
+use crate::playback_system::RecordingVersion;
+use crate::recording_system::{
+    DeltaFrame, Frame, Marker, Recording, RecordingConfig, RecordingPoint,
+};
+
+/// Version of the container layout written by [`write_recording`]
+pub const RECORDING_FORMAT_VERSION: u32 = 3;
+
+/// Bevy release the recorded component data is expected to come from
+pub const BEVY_VERSION: &str = "0.16";
+
+/// Magic bytes opening every recording file
+pub const FILE_MAGIC: &[u8; 8] = b"BEVYREC\0";
+
+/// Magic bytes closing every recording file, after the index offset
+pub const INDEX_MAGIC: &[u8; 8] = b"BEVYIDX\0";
+
+/// Frames per chunk; smaller chunks make seeking cheaper at some size cost
+pub const FRAMES_PER_CHUNK: usize = 256;
+
+/// Migrations between format versions; entry `i` upgrades version `i + 2` to `i + 3`
+///
+/// Version 1 files have no header to migrate and are converted by `read_legacy`.
+const MIGRATIONS: &[fn(RecordingDocument) -> Result<RecordingDocument>] = &[migrate_v2_to_v3];
+
+/// Version 3 only added a compression option, so version 2 contents carry over
+fn migrate_v2_to_v3(mut document: RecordingDocument) -> Result<RecordingDocument> {
+    document.header["format_version"] = 3.into();
+    Ok(document)
+}
+
+/// How chunk payloads are compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkCompression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+/// Metadata at the start of a recording file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingHeader {
+    pub format_version: u32,
+    /// Version of this crate that wrote the file
+    pub crate_version: String,
+    pub bevy_version: String,
+    pub recording_version: RecordingVersion,
+    pub created_at: DateTime<Utc>,
+    pub compression: ChunkCompression,
+    pub config: RecordingConfig,
+    pub total_frames: usize,
+    pub duration: Duration,
+    pub markers: Vec<Marker>,
+}
+
+impl RecordingHeader {
+    fn for_recording(recording: &Recording) -> Self {
+        Self {
+            format_version: RECORDING_FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            bevy_version: BEVY_VERSION.to_string(),
+            recording_version: recording.version.clone(),
+            created_at: Utc::now(),
+            compression: if recording.config.compression {
+                ChunkCompression::Zstd
+            } else {
+                ChunkCompression::None
+            },
+            config: recording.config.clone(),
+            total_frames: recording.total_frames,
+            duration: recording.duration,
+            markers: recording.markers.clone(),
+        }
+    }
+}
+
+/// What a chunk holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkKind {
+    Frames,
+    DeltaFrames,
+}
+
+/// Location of one chunk in the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkIndexEntry {
+    pub kind: ChunkKind,
+    /// Offset of the chunk payload, after its length prefix
+    pub offset: u64,
+    pub length: u64,
+    pub first_frame: usize,
+    pub last_frame: usize,
+    pub count: usize,
+}
+
+/// Chunk locations, written after the last chunk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordingIndex {
+    pub chunks: Vec<ChunkIndexEntry>,
+}
+
+/// A file's header and chunks before they are decoded, as migrations see them
+struct RecordingDocument {
+    header: Value,
+    chunks: Vec<(ChunkKind, Value)>,
+}
+
+/// Write `recording` to `path` in the current format
+pub fn write_recording(path: &Path, recording: &Recording) -> Result<()> {
+    let temp_path = path.with_extension("tmp");
+    let mut out = BufWriter::new(File::create(&temp_path)?);
+    let chunks = write_recording_to(&mut out, recording)?;
+    out.into_inner()
+        .map_err(|e| Error::Io(e.into_error()))?
+        .sync_all()?;
+    std::fs::rename(&temp_path, path)?;
+
+    info!(
+        "Wrote recording format v{} with {} chunks",
+        RECORDING_FORMAT_VERSION, chunks
+    );
+    Ok(())
+}
+
+/// Encode `recording` in the current format, returning the number of chunks
+pub fn write_recording_to(out: &mut impl Write, recording: &Recording) -> Result<usize> {
+    write_with_header(out, recording, &RecordingHeader::for_recording(recording))
+}
+
+fn write_with_header(
+    out: &mut impl Write,
+    recording: &Recording,
+    header: &RecordingHeader,
+) -> Result<usize> {
+    out.write_all(FILE_MAGIC)?;
+    let header_bytes = serde_json::to_vec(header)?;
+    write_block(out, &header_bytes)?;
+    let mut offset = (FILE_MAGIC.len() + 4 + header_bytes.len()) as u64;
+
+    let mut index = RecordingIndex::default();
+    for frames in recording.frames.chunks(FRAMES_PER_CHUNK) {
+        let numbers = frames.iter().map(|f| f.frame_number);
+        let entry = write_chunk(out, &mut offset, header, ChunkKind::Frames, frames, numbers)?;
+        index.chunks.push(entry);
+    }
+    for deltas in recording.delta_frames.chunks(FRAMES_PER_CHUNK) {
+        let numbers = deltas.iter().map(|d| d.frame_number);
+        let entry = write_chunk(
+            out,
+            &mut offset,
+            header,
+            ChunkKind::DeltaFrames,
+            deltas,
+            numbers,
+        )?;
+        index.chunks.push(entry);
+    }
+
+    out.write_all(&serde_json::to_vec(&index)?)?;
+    out.write_all(&offset.to_le_bytes())?;
+    out.write_all(INDEX_MAGIC)?;
+    Ok(index.chunks.len())
+}
+
+/// Read a recording of any supported format version
+pub fn read_recording(path: &Path) -> Result<Recording> {
+    match ChunkReader::open(path)? {
+        Some(mut reader) => {
+            let entries = reader.index.chunks.clone();
+            reader.load(&entries)
+        }
+        None => read_legacy(path),
+    }
+}
+
+/// Rebuild the state at `point` from a recording file
+///
+/// Uses the index to decode only the chunks holding the nearest earlier full
+/// frame and the deltas after it, rather than the whole recording.
+pub fn read_frame_at(path: &Path, point: RecordingPoint) -> Result<Option<Frame>> {
+    let Some(mut reader) = ChunkReader::open(path)? else {
+        return Ok(read_legacy(path)?.state_at(point));
+    };
+    let (full_chunks, delta_chunks): (Vec<ChunkIndexEntry>, Vec<ChunkIndexEntry>) = reader
+        .index
+        .chunks
+        .iter()
+        .cloned()
+        .partition(|entry| entry.kind == ChunkKind::Frames);
+
+    // The index only has frame numbers, so a timestamp needs every full-frame
+    // chunk; with the default keyframe interval that's one frame in thirty
+    let full_chunks = match point {
+        RecordingPoint::Frame(n) => full_chunks
+            .iter()
+            .rev()
+            .find(|entry| entry.first_frame <= n)
+            .cloned()
+            .into_iter()
+            .collect(),
+        RecordingPoint::Timestamp(_) => full_chunks,
+    };
+    let mut partial = reader.load(&full_chunks)?;
+    let Some(start) = partial
+        .frames
+        .iter()
+        .rev()
+        .find(|f| !point.is_before(f.frame_number, f.timestamp))
+        .map(|f| f.frame_number)
+    else {
+        return Ok(None);
+    };
+
+    for entry in delta_chunks.iter().filter(|entry| entry.last_frame > start) {
+        if matches!(point, RecordingPoint::Frame(n) if entry.first_frame > n) {
+            break;
+        }
+        let chunk = reader.load(std::slice::from_ref(entry))?;
+        let past_point = chunk
+            .delta_frames
+            .last()
+            .is_some_and(|d| point.is_before(d.frame_number, d.timestamp));
+        partial.delta_frames.extend(chunk.delta_frames);
+        if past_point {
+            break;
+        }
+    }
+    Ok(partial.state_at(point))
+}
+
+/// An open recording file whose chunks are decoded on demand
+struct ChunkReader {
+    file: File,
+    header: Value,
+    version: u32,
+    compression: ChunkCompression,
+    index: RecordingIndex,
+}
+
+impl ChunkReader {
+    /// `None` for format version 1 files, which have no header or index
+    fn open(path: &Path) -> Result<Option<Self>> {
+        let mut file = File::open(path)?;
+        if !has_magic(&mut file) {
+            return Ok(None);
+        }
+
+        let header: Value = serde_json::from_slice(&read_block(&mut file)?)?;
+        let version = header_version(&header)?;
+        let compression = serde_json::from_value(header["compression"].clone())?;
+        let index = read_index(&mut file)?;
+        Ok(Some(Self {
+            file,
+            header,
+            version,
+            compression,
+            index,
+        }))
+    }
+
+    /// Decode `entries` into a recording holding just their frames
+    fn load(&mut self, entries: &[ChunkIndexEntry]) -> Result<Recording> {
+        let mut chunks = Vec::with_capacity(entries.len());
+        for entry in entries {
+            self.file.seek(SeekFrom::Start(entry.offset))?;
+            let mut payload = vec![0; entry.length as usize];
+            self.file.read_exact(&mut payload)?;
+            match self.compression {
+                ChunkCompression::Gzip => {
+                    let mut decoded = Vec::new();
+                    GzDecoder::new(payload.as_slice()).read_to_end(&mut decoded)?;
+                    payload = decoded;
+                }
+                ChunkCompression::Zstd => payload = zstd::stream::decode_all(payload.as_slice())?,
+                ChunkCompression::None => {}
+            }
+            chunks.push((entry.kind, serde_json::from_slice(&payload)?));
+        }
+
+        let mut document = RecordingDocument {
+            header: self.header.clone(),
+            chunks,
+        };
+        for migration in &MIGRATIONS[(self.version - 2) as usize..] {
+            document = migration(document)?;
+        }
+        decode(document)
+    }
+}
+
+/// Read only the header, converting legacy files to get one
+pub fn read_header(path: &Path) -> Result<RecordingHeader> {
+    let mut file = File::open(path)?;
+    if !has_magic(&mut file) {
+        let mut header = RecordingHeader::for_recording(&read_legacy(path)?);
+        header.format_version = 1;
+        header.crate_version = "unknown".to_string();
+        return Ok(header);
+    }
+    let header: Value = serde_json::from_slice(&read_block(&mut file)?)?;
+    header_version(&header)?;
+    Ok(serde_json::from_value(header)?)
+}
+
+/// Rewrite the recording at `path` in the current format, returning the version it had
+pub fn migrate_file(path: &Path) -> Result<u32> {
+    let from_version = read_header(path)?.format_version;
+    if from_version < RECORDING_FORMAT_VERSION {
+        write_recording(path, &read_recording(path)?)?;
+        info!(
+            "Migrated recording {:?} from format v{} to v{}",
+            path, from_version, RECORDING_FORMAT_VERSION
+        );
+    }
+    Ok(from_version)
+}
+
+fn write_block(out: &mut impl Write, bytes: &[u8]) -> Result<()> {
+    let length = u32::try_from(bytes.len())
+        .map_err(|_| Error::Serialization("Recording block exceeds 4GB".to_string()))?;
+    out.write_all(&length.to_le_bytes())?;
+    out.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_block(input: &mut impl Read) -> Result<Vec<u8>> {
+    let mut length = [0; 4];
+    input.read_exact(&mut length)?;
+    let mut bytes = vec![0; u32::from_le_bytes(length) as usize];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn write_chunk<T: Serialize>(
+    out: &mut impl Write,
+    offset: &mut u64,
+    header: &RecordingHeader,
+    kind: ChunkKind,
+    items: &[T],
+    mut frame_numbers: impl Iterator<Item = usize>,
+) -> Result<ChunkIndexEntry> {
+    let json = serde_json::to_vec(items)?;
+    let payload = match header.compression {
+        ChunkCompression::Gzip => {
+            let mut compressed = Vec::new();
+            GzEncoder::new(json.as_slice(), Compression::default()).read_to_end(&mut compressed)?;
+            compressed
+        }
+        ChunkCompression::Zstd => {
+            zstd::stream::encode_all(json.as_slice(), header.config.compression_level)?
+        }
+        ChunkCompression::None => json,
+    };
+    write_block(out, &payload)?;
+
+    let first_frame = frame_numbers.next().unwrap_or_default();
+    let entry = ChunkIndexEntry {
+        kind,
+        offset: *offset + 4,
+        length: payload.len() as u64,
+        first_frame,
+        last_frame: frame_numbers.last().unwrap_or(first_frame),
+        count: items.len(),
+    };
+    *offset += 4 + payload.len() as u64;
+    Ok(entry)
+}
+
+fn has_magic(file: &mut File) -> bool {
+    let mut magic = [0; 8];
+    file.read_exact(&mut magic).is_ok() && &magic == FILE_MAGIC
+}
+
+fn header_version(header: &Value) -> Result<u32> {
+    let version = header
+        .get("format_version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Error::Serialization("Recording header has no format_version".to_string()))?
+        as u32;
+    if version > RECORDING_FORMAT_VERSION {
+        return Err(Error::Validation(format!(
+            "Recording format version {} is newer than supported version {}; upgrade the debugger to load it",
+            version, RECORDING_FORMAT_VERSION
+        )));
+    }
+    if version < 2 {
+        return Err(Error::Serialization(format!(
+            "Invalid recording format version {version}"
+        )));
+    }
+    Ok(version)
+}
+
+fn read_index(file: &mut File) -> Result<RecordingIndex> {
+    let end = file.seek(SeekFrom::End(-16))?;
+    let mut footer = [0; 16];
+    file.read_exact(&mut footer)?;
+    if &footer[8..] != INDEX_MAGIC {
+        return Err(Error::Serialization(
+            "Recording is truncated: index footer missing".to_string(),
+        ));
+    }
+    let mut index_offset = [0; 8];
+    index_offset.copy_from_slice(&footer[..8]);
+    let index_offset = u64::from_le_bytes(index_offset);
+
+    file.seek(SeekFrom::Start(index_offset))?;
+    let mut index = vec![0; end.saturating_sub(index_offset) as usize];
+    file.read_exact(&mut index)?;
+    Ok(serde_json::from_slice(&index)?)
+}
+
+fn decode(document: RecordingDocument) -> Result<Recording> {
+    let header: RecordingHeader = serde_json::from_value(document.header)?;
+    let mut frames = Vec::new();
+    let mut delta_frames = Vec::new();
+    for (kind, chunk) in document.chunks {
+        match kind {
+            ChunkKind::Frames => frames.extend(serde_json::from_value::<Vec<Frame>>(chunk)?),
+            ChunkKind::DeltaFrames => {
+                delta_frames.extend(serde_json::from_value::<Vec<DeltaFrame>>(chunk)?)
+            }
+        }
+    }
+    Ok(Recording {
+        config: header.config,
+        frames,
+        delta_frames,
+        markers: header.markers,
+        total_frames: header.total_frames,
+        duration: header.duration,
+        version: header.recording_version,
+    })
+}
+
+/// `Recording` as format version 1 files laid it out; bincode is positional,
+/// so config fields added since then can't be read from those files
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyRecording {
+    config: LegacyRecordingConfig,
+    frames: Vec<Frame>,
+    delta_frames: Vec<DeltaFrame>,
+    markers: Vec<Marker>,
+    total_frames: usize,
+    duration: Duration,
+    version: RecordingVersion,
+}
+
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize))]
+struct LegacyRecordingConfig {
+    sample_rate: f32,
+    max_buffer_size: usize,
+    compression: bool,
+    checksums: bool,
+    component_filter: Option<Vec<String>>,
+    event_filter: Option<Vec<String>>,
+}
+
+impl From<LegacyRecording> for Recording {
+    fn from(legacy: LegacyRecording) -> Self {
+        let config = legacy.config;
+        Self {
+            config: RecordingConfig {
+                sample_rate: config.sample_rate,
+                max_buffer_size: config.max_buffer_size,
+                compression: config.compression,
+                checksums: config.checksums,
+                component_filter: config.component_filter,
+                event_filter: config.event_filter,
+                ..RecordingConfig::default()
+            },
+            frames: legacy.frames,
+            delta_frames: legacy.delta_frames,
+            markers: legacy.markers,
+            total_frames: legacy.total_frames,
+            duration: legacy.duration,
+            version: legacy.version,
+        }
+    }
+}
+
+/// Format version 1: a bincode `Recording`, gzipped or not
+fn read_legacy(path: &Path) -> Result<Recording> {
+    let compressed = bincode::deserialize_from(BufReader::new(GzDecoder::new(File::open(path)?)));
+    let recording: LegacyRecording = match compressed {
+        Ok(recording) => recording,
+        Err(_) => bincode::deserialize_from(BufReader::new(File::open(path)?))
+            .map_err(|e| Error::Serialization(format!("Failed to deserialize recording: {e}")))?,
+    };
+    info!(
+        "Loaded format v1 recording {:?}; save it again or migrate it to upgrade",
+        path
+    );
+    Ok(recording.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording_system::EntityState;
+    use std::collections::HashMap;
+
+    fn recording(compression: bool, frame_count: usize) -> Recording {
+        let frames = (0..frame_count)
+            .map(|frame_number| Frame {
+                frame_number,
+                timestamp: Duration::from_millis(frame_number as u64 * 33),
+                entities: HashMap::from([(
+                    7,
+                    EntityState {
+                        entity_id: 7,
+                        components: HashMap::from([(
+                            "Transform".to_string(),
+                            serde_json::json!({"x": frame_number}),
+                        )]),
+                        active: true,
+                    },
+                )]),
+                events: Vec::new(),
+                checksum: None,
+            })
+            .collect();
+        Recording {
+            config: RecordingConfig {
+                compression,
+                ..RecordingConfig::default()
+            },
+            frames,
+            delta_frames: Vec::new(),
+            markers: vec![Marker {
+                name: "spawn".to_string(),
+                frame_number: 3,
+                timestamp: Duration::from_millis(99),
+                description: None,
+                metadata: HashMap::new(),
+            }],
+            total_frames: frame_count,
+            duration: Duration::from_secs(10),
+            version: RecordingVersion::current(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_with_index() {
+        let dir = tempfile::tempdir().unwrap();
+        for compression in [true, false] {
+            let path = dir.path().join(format!("session_{compression}.bevy"));
+            write_recording(&path, &recording(compression, FRAMES_PER_CHUNK + 10)).unwrap();
+
+            let header = read_header(&path).unwrap();
+            assert_eq!(header.format_version, RECORDING_FORMAT_VERSION);
+            assert_eq!(header.crate_version, env!("CARGO_PKG_VERSION"));
+            assert_eq!(header.markers[0].name, "spawn");
+
+            let mut file = File::open(&path).unwrap();
+            let index = read_index(&mut file).unwrap();
+            assert_eq!(index.chunks.len(), 2);
+            assert_eq!(index.chunks[1].first_frame, FRAMES_PER_CHUNK);
+            assert_eq!(index.chunks[1].count, 10);
+
+            let loaded = read_recording(&path).unwrap();
+            assert_eq!(loaded.frames.len(), FRAMES_PER_CHUNK + 10);
+            assert_eq!(
+                loaded.frames[42].entities[&7].components["Transform"],
+                serde_json::json!({"x": 42})
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_frame_at_matches_full_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deltas.bevy");
+        let mut recording = recording(true, FRAMES_PER_CHUNK * 3);
+        // Keep a full frame every 30 frames and store the rest as deltas
+        let (frames, deltas): (Vec<Frame>, Vec<Frame>) = recording
+            .frames
+            .drain(..)
+            .partition(|f| f.frame_number % 30 == 0);
+        recording.frames = frames;
+        recording.delta_frames = deltas
+            .into_iter()
+            .map(|f| DeltaFrame {
+                frame_number: f.frame_number,
+                timestamp: f.timestamp,
+                added_entities: HashMap::new(),
+                removed_entities: Vec::new(),
+                changed_components: HashMap::from([(7, f.entities[&7].components.clone())]),
+                events: Vec::new(),
+            })
+            .collect();
+        write_recording(&path, &recording).unwrap();
+
+        for frame_number in [0, 29, 300, 611, 10_000] {
+            let point = RecordingPoint::Frame(frame_number);
+            let frame = read_frame_at(&path, point).unwrap().unwrap();
+            let expected = recording.state_at(point).unwrap();
+            assert_eq!(frame.frame_number, expected.frame_number);
+            assert_eq!(frame.entities[&7], expected.entities[&7]);
+        }
+        let frame = read_frame_at(
+            &path,
+            RecordingPoint::Timestamp(Duration::from_millis(3400)),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(frame.frame_number, 103);
+        assert_eq!(
+            frame.entities[&7].components["Transform"],
+            serde_json::json!({"x": 103})
+        );
+    }
+
+    #[test]
+    fn test_version_2_gzip_recordings_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("v2.bevy");
+        let recording = recording(true, 20);
+        let mut header = RecordingHeader::for_recording(&recording);
+        header.format_version = 2;
+        header.compression = ChunkCompression::Gzip;
+        write_with_header(&mut File::create(&path).unwrap(), &recording, &header).unwrap();
+
+        assert_eq!(read_recording(&path).unwrap().frames.len(), 20);
+        assert_eq!(migrate_file(&path).unwrap(), 2);
+        let header = read_header(&path).unwrap();
+        assert_eq!(header.format_version, RECORDING_FORMAT_VERSION);
+        assert_eq!(header.compression, ChunkCompression::Zstd);
+        assert_eq!(read_recording(&path).unwrap().frames.len(), 20);
+    }
+
+    #[test]
+    fn test_legacy_recordings_migrate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.bevy");
+        let current = recording(false, 0);
+        let legacy = LegacyRecording {
+            config: LegacyRecordingConfig {
+                sample_rate: current.config.sample_rate,
+                max_buffer_size: current.config.max_buffer_size,
+                compression: false,
+                checksums: current.config.checksums,
+                component_filter: None,
+                event_filter: None,
+            },
+            frames: current.frames,
+            delta_frames: current.delta_frames,
+            markers: current.markers,
+            total_frames: current.total_frames,
+            duration: current.duration,
+            version: current.version,
+        };
+        bincode::serialize_into(File::create(&path).unwrap(), &legacy).unwrap();
+
+        assert_eq!(read_header(&path).unwrap().format_version, 1);
+        assert_eq!(read_recording(&path).unwrap().markers[0].name, "spawn");
+
+        assert_eq!(migrate_file(&path).unwrap(), 1);
+        assert_eq!(
+            read_header(&path).unwrap().format_version,
+            RECORDING_FORMAT_VERSION
+        );
+        assert_eq!(migrate_file(&path).unwrap(), RECORDING_FORMAT_VERSION);
+
+        // Files from a newer debugger are refused rather than misread
+        let mut header = serde_json::to_value(read_header(&path).unwrap()).unwrap();
+        header["format_version"] = (RECORDING_FORMAT_VERSION + 1).into();
+        let newer = dir.path().join("newer.bevy");
+        let mut out = File::create(&newer).unwrap();
+        out.write_all(FILE_MAGIC).unwrap();
+        write_block(&mut out, &serde_json::to_vec(&header).unwrap()).unwrap();
+        assert!(matches!(read_recording(&newer), Err(Error::Validation(_))));
+    }
+}
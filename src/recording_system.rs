@@ -1,11 +1,7 @@
-use flate2::read::GzDecoder;
-use flate2::write::GzEncoder;
-use flate2::Compression;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, VecDeque};
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -13,7 +9,14 @@ use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::brp_client::BrpClient;
-use crate::error::{Error, Result};
+use crate::disk_ring_buffer::{DiskRingBuffer, DiskRingConfig, DiskRingStats, SpoolEntry};
+use crate::error::Result;
+use crate::memory_budget::MemoryConsumer;
+
+/// Event type a game sends, e.g. from a debug hotkey, to drop a marker on the recording
+///
+/// The event's `name` and `description` fields name the marker.
+pub const ANNOTATION_EVENT_TYPE: &str = "bevy_debugger::Annotate";
 
 /// A frame of recorded game state
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +75,54 @@ pub struct DeltaFrame {
     pub events: Vec<RecordedEvent>,
 }
 
+impl DeltaFrame {
+    /// Bring `frame`, the state at the previous frame, up to this frame
+    pub fn apply_to(&self, frame: &mut Frame) {
+        // Remove entities
+        for id in &self.removed_entities {
+            frame.entities.remove(id);
+        }
+
+        // Add entities
+        for (id, state) in &self.added_entities {
+            frame.entities.insert(*id, state.clone());
+        }
+
+        // Apply component changes
+        for (id, changes) in &self.changed_components {
+            if let Some(entity) = frame.entities.get_mut(id) {
+                for (comp_name, comp_value) in changes {
+                    entity
+                        .components
+                        .insert(comp_name.clone(), comp_value.clone());
+                }
+            }
+        }
+
+        // Update frame metadata
+        frame.frame_number = self.frame_number;
+        frame.timestamp = self.timestamp;
+        frame.events = self.events.clone();
+    }
+}
+
+/// A moment in a recording, by frame number or by time since it started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingPoint {
+    Frame(usize),
+    Timestamp(Duration),
+}
+
+impl RecordingPoint {
+    /// Whether a frame recorded at `frame_number`/`timestamp` comes after this point
+    pub fn is_before(self, frame_number: usize, timestamp: Duration) -> bool {
+        match self {
+            Self::Frame(n) => frame_number > n,
+            Self::Timestamp(t) => timestamp > t,
+        }
+    }
+}
+
 /// Recording configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingConfig {
@@ -87,6 +138,24 @@ pub struct RecordingConfig {
     pub component_filter: Option<Vec<String>>,
     /// Event types to record
     pub event_filter: Option<Vec<String>>,
+    /// Spool frames to disk, keeping a bounded window instead of holding them in memory
+    #[serde(default)]
+    pub disk_buffer: Option<DiskRingConfig>,
+    /// Store a full frame every this many frames, bounding how many deltas a
+    /// seek has to replay
+    #[serde(default = "default_keyframe_interval")]
+    pub keyframe_interval: usize,
+    /// zstd level used when `compression` is on; higher is smaller but slower
+    #[serde(default = "default_compression_level")]
+    pub compression_level: i32,
+}
+
+fn default_keyframe_interval() -> usize {
+    30
+}
+
+fn default_compression_level() -> i32 {
+    3
 }
 
 impl Default for RecordingConfig {
@@ -98,6 +167,9 @@ impl Default for RecordingConfig {
             checksums: true,
             component_filter: None,
             event_filter: None,
+            disk_buffer: None,
+            keyframe_interval: default_keyframe_interval(),
+            compression_level: default_compression_level(),
         }
     }
 }
@@ -113,11 +185,25 @@ pub struct RecordingBuffer {
     frame_counter: usize,
     recording: bool,
     last_full_frame: Option<Frame>,
+    disk: Option<Arc<DiskRingBuffer>>,
 }
 
 impl RecordingBuffer {
     /// Create a new recording buffer
     pub fn new(config: RecordingConfig) -> Self {
+        let disk = config.disk_buffer.clone().and_then(|disk_config| {
+            match DiskRingBuffer::open(disk_config) {
+                Ok(disk) => {
+                    disk.start_compaction();
+                    Some(disk)
+                }
+                Err(e) => {
+                    warn!("Disk ring buffer unavailable, recording in memory: {}", e);
+                    None
+                }
+            }
+        });
+
         Self {
             frames: VecDeque::with_capacity(config.max_buffer_size),
             delta_frames: VecDeque::with_capacity(config.max_buffer_size),
@@ -127,6 +213,7 @@ impl RecordingBuffer {
             frame_counter: 0,
             recording: false,
             last_full_frame: None,
+            disk,
             config,
         }
     }
@@ -142,6 +229,9 @@ impl RecordingBuffer {
         self.delta_frames.clear();
         self.markers.clear();
         self.last_full_frame = None;
+        if let Some(disk) = &self.disk {
+            disk.clear();
+        }
     }
 
     /// Stop recording
@@ -155,6 +245,31 @@ impl RecordingBuffer {
         self.recording
     }
 
+    /// Capture the current game state as a frame without storing it
+    pub async fn capture_frame(
+        &self,
+        brp_client: &mut BrpClient,
+        frame_number: usize,
+        timestamp: Duration,
+    ) -> Result<Frame> {
+        let entities = self.fetch_entities(brp_client).await?;
+        let events = self.fetch_events(brp_client).await?;
+
+        let mut frame = Frame {
+            frame_number,
+            timestamp,
+            entities,
+            events,
+            checksum: None,
+        };
+
+        // Calculate checksum if enabled
+        if self.config.checksums {
+            frame.checksum = Some(self.calculate_checksum(&frame));
+        }
+        Ok(frame)
+    }
+
     /// Record a frame
     pub async fn record_frame(&mut self, brp_client: &mut BrpClient) -> Result<()> {
         if !self.recording {
@@ -173,31 +288,44 @@ impl RecordingBuffer {
             }
         }
 
-        // Get current game state
-        let entities = self.fetch_entities(brp_client).await?;
-        let events = self.fetch_events(brp_client).await?;
-
         let timestamp = self
             .start_time
             .map(|start| now.duration_since(start))
             .unwrap_or_default();
+        let frame = self
+            .capture_frame(brp_client, self.frame_counter, timestamp)
+            .await?;
 
-        // Create frame
-        let mut frame = Frame {
-            frame_number: self.frame_counter,
-            timestamp,
-            entities,
-            events,
-            checksum: None,
-        };
-
-        // Calculate checksum if enabled
-        if self.config.checksums {
-            frame.checksum = Some(self.calculate_checksum(&frame));
+        for event in frame
+            .events
+            .iter()
+            .filter(|e| e.event_type == ANNOTATION_EVENT_TYPE)
+        {
+            let name = event.data.get("name").and_then(|n| n.as_str());
+            let description = event.data.get("description").and_then(|d| d.as_str());
+            let mut metadata = HashMap::from([("source".to_string(), serde_json::json!("game"))]);
+            if let Some(entity_id) = event.entity_id {
+                metadata.insert("entity_id".to_string(), serde_json::json!(entity_id));
+            }
+            self.add_marker_with_metadata(
+                name.unwrap_or("annotation").to_string(),
+                description.map(String::from),
+                metadata,
+            );
         }
 
+        if let Some(disk) = &self.disk {
+            // Only the latest frame stays in memory; the disk keeps the window
+            let entry = match &self.last_full_frame {
+                Some(last_frame) if !disk.needs_keyframe(timestamp) => {
+                    SpoolEntry::Delta(self.create_delta_frame(last_frame, &frame))
+                }
+                _ => SpoolEntry::Full(frame.clone()),
+            };
+            disk.append(&entry)?;
+        }
         // Store as delta frame if we have a previous frame
-        if let Some(ref last_frame) = self.last_full_frame {
+        else if let Some(ref last_frame) = self.last_full_frame {
             let delta = self.create_delta_frame(last_frame, &frame);
 
             // Add to circular buffer
@@ -214,8 +342,9 @@ impl RecordingBuffer {
         }
 
         // Store every Nth frame as full frame for seeking
-        if self.frame_counter % 30 == 0 {
-            if self.frames.len() >= self.config.max_buffer_size / 30 {
+        let keyframe_interval = self.config.keyframe_interval.max(1);
+        if self.disk.is_none() && self.frame_counter % keyframe_interval == 0 {
+            if self.frames.len() >= self.config.max_buffer_size / keyframe_interval {
                 self.frames.pop_front();
             }
             self.frames.push_back(frame.clone());
@@ -230,39 +359,108 @@ impl RecordingBuffer {
 
     /// Add a marker at the current position
     pub fn add_marker(&mut self, name: String, description: Option<String>) {
+        self.add_marker_with_metadata(name, description, HashMap::new());
+    }
+
+    /// Add a marker carrying extra metadata, returning it if recording
+    pub fn add_marker_with_metadata(
+        &mut self,
+        name: String,
+        description: Option<String>,
+        metadata: HashMap<String, serde_json::Value>,
+    ) -> Option<Marker> {
+        self.annotate(name, description, metadata, Duration::ZERO)
+    }
+
+    /// Add a marker `before` earlier than now, e.g. just before a bug reproduced
+    ///
+    /// The marker goes on the last frame recorded by then. Returns it if recording.
+    pub fn annotate(
+        &mut self,
+        name: String,
+        description: Option<String>,
+        metadata: HashMap<String, serde_json::Value>,
+        before: Duration,
+    ) -> Option<Marker> {
         if !self.recording {
             warn!("Cannot add marker when not recording");
-            return;
+            return None;
         }
 
-        let timestamp = self
+        let now = self
             .start_time
             .map(|start| Instant::now().duration_since(start))
             .unwrap_or_default();
+        let timestamp = now.saturating_sub(before);
+        let frame_number = if before.is_zero() {
+            self.frame_counter
+        } else {
+            self.frame_at(timestamp, before)
+        };
 
         let marker = Marker {
             name,
-            frame_number: self.frame_counter,
+            frame_number,
             timestamp,
             description,
-            metadata: HashMap::new(),
+            metadata,
         };
 
         info!(
             "Added marker '{}' at frame {}",
             marker.name, marker.frame_number
         );
-        self.markers.push(marker);
+        let index = self
+            .markers
+            .partition_point(|m| m.frame_number <= marker.frame_number);
+        self.markers.insert(index, marker.clone());
+        Some(marker)
+    }
+
+    /// Last frame recorded at or before `timestamp`, which is `before` ago
+    fn frame_at(&self, timestamp: Duration, before: Duration) -> usize {
+        let delta = self
+            .delta_frames
+            .iter()
+            .rev()
+            .find(|d| d.timestamp <= timestamp)
+            .map(|d| d.frame_number);
+        let full = self
+            .frames
+            .iter()
+            .rev()
+            .find(|f| f.timestamp <= timestamp)
+            .map(|f| f.frame_number);
+        match delta.max(full) {
+            Some(frame_number) => frame_number,
+            // Spooled to disk or older than the buffer; estimate from the sample rate
+            None => {
+                let frames_back = (before.as_secs_f32() * self.config.sample_rate) as usize;
+                self.frame_counter.saturating_sub(frames_back)
+            }
+        }
     }
 
     /// Save recording to file
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
         info!("Saving recording to {:?}", path);
 
-        let recording = Recording {
+        crate::recording_format::write_recording(path, &self.to_recording()?)?;
+
+        info!("Recording saved successfully");
+        Ok(())
+    }
+
+    /// Everything recorded so far, or the window still on disk when spooling
+    pub fn to_recording(&self) -> Result<Recording> {
+        let (frames, delta_frames) = match &self.disk {
+            Some(disk) => disk.read_window()?,
+            None => (self.frames.clone().into(), self.delta_frames.clone().into()),
+        };
+        Ok(Recording {
             config: self.config.clone(),
-            frames: self.frames.clone().into(),
-            delta_frames: self.delta_frames.clone().into(),
+            frames,
+            delta_frames,
             markers: self.markers.clone(),
             total_frames: self.frame_counter,
             duration: self
@@ -270,42 +468,14 @@ impl RecordingBuffer {
                 .map(|start| Instant::now().duration_since(start))
                 .unwrap_or_default(),
             version: crate::playback_system::RecordingVersion::current(),
-        };
-
-        let file = File::create(path)?;
-
-        if self.config.compression {
-            let encoder = GzEncoder::new(file, Compression::default());
-            let writer = BufWriter::new(encoder);
-            bincode::serialize_into(writer, &recording)
-                .map_err(|e| Error::Serialization(format!("Failed to serialize recording: {e}")))?;
-        } else {
-            let writer = BufWriter::new(file);
-            bincode::serialize_into(writer, &recording)
-                .map_err(|e| Error::Serialization(format!("Failed to serialize recording: {e}")))?;
-        }
-
-        info!("Recording saved successfully");
-        Ok(())
+        })
     }
 
-    /// Load recording from file
+    /// Load recording from file, migrating older format versions
     pub fn load_from_file(path: &Path) -> Result<Recording> {
         info!("Loading recording from {:?}", path);
 
-        let file = File::open(path)?;
-
-        // Try to load as compressed first
-        let recording: Recording = match Self::try_load_compressed(&file) {
-            Ok(rec) => rec,
-            Err(_) => {
-                // Try uncompressed
-                let reader = BufReader::new(file);
-                bincode::deserialize_from(reader).map_err(|e| {
-                    Error::Serialization(format!("Failed to deserialize recording: {e}"))
-                })?
-            }
-        };
+        let recording = crate::recording_format::read_recording(path)?;
 
         info!(
             "Recording loaded: {} frames, {} duration",
@@ -316,14 +486,6 @@ impl RecordingBuffer {
         Ok(recording)
     }
 
-    fn try_load_compressed(file: &File) -> Result<Recording> {
-        let decoder = GzDecoder::new(file);
-        let reader = BufReader::new(decoder);
-        bincode::deserialize_from(reader).map_err(|e| {
-            Error::Serialization(format!("Failed to deserialize compressed recording: {e}"))
-        })
-    }
-
     /// Fetch current entity states from the game
     async fn fetch_entities(
         &self,
@@ -415,6 +577,14 @@ impl RecordingBuffer {
 
     /// Get current recording statistics
     pub fn get_stats(&self) -> RecordingStats {
+        let disk = self.disk.as_ref().map(|disk| disk.stats());
+        let buffer_usage = match &disk {
+            Some(disk) => disk.covered.as_secs_f32() / disk.window.as_secs_f32().max(1.0),
+            None => {
+                (self.delta_frames.len() + self.frames.len()) as f32
+                    / self.config.max_buffer_size as f32
+            }
+        };
         RecordingStats {
             frame_count: self.frame_counter,
             delta_frame_count: self.delta_frames.len(),
@@ -425,12 +595,87 @@ impl RecordingBuffer {
                 .map(|start| Instant::now().duration_since(start))
                 .unwrap_or_default(),
             is_recording: self.recording,
-            buffer_usage: (self.delta_frames.len() + self.frames.len()) as f32
-                / self.config.max_buffer_size as f32,
+            buffer_usage,
+            disk,
+            compression: self.compression_stats(),
+        }
+    }
+
+    /// Space saved by storing deltas and compressing them, for in-memory buffers
+    fn compression_stats(&self) -> Option<CompressionStats> {
+        if self.disk.is_some() || self.frames.is_empty() {
+            return None;
+        }
+
+        let keyframe_bytes: usize = self.frames.iter().map(encoded_size).sum();
+        let stored_bytes =
+            keyframe_bytes + self.delta_frames.iter().map(encoded_size).sum::<usize>();
+        // Every frame after the first is held as a delta
+        let full_snapshot_bytes =
+            keyframe_bytes / self.frames.len() * (self.delta_frames.len() + 1);
+
+        let compressed_bytes = if self.config.compression {
+            let compress = || -> std::io::Result<usize> {
+                let mut encoder =
+                    zstd::stream::Encoder::new(Vec::new(), self.config.compression_level)?;
+                let encoded = self
+                    .frames
+                    .iter()
+                    .map(bincode::serialize)
+                    .chain(self.delta_frames.iter().map(bincode::serialize));
+                for bytes in encoded {
+                    std::io::Write::write_all(&mut encoder, &bytes.unwrap_or_default())?;
+                }
+                Ok(encoder.finish()?.len())
+            };
+            compress().unwrap_or(stored_bytes)
+        } else {
+            stored_bytes
+        };
+
+        let ratio = |bytes: usize| full_snapshot_bytes as f32 / bytes.max(1) as f32;
+        Some(CompressionStats {
+            full_snapshot_bytes,
+            stored_bytes,
+            compressed_bytes,
+            delta_ratio: ratio(stored_bytes),
+            compression_ratio: ratio(compressed_bytes),
+        })
+    }
+
+    /// Approximate bytes held by the buffered frames
+    pub fn memory_usage(&self) -> usize {
+        self.frames.iter().map(encoded_size).sum::<usize>()
+            + self.delta_frames.iter().map(encoded_size).sum::<usize>()
+    }
+
+    /// Drop the oldest frames until about `bytes` are freed, returning how many were
+    ///
+    /// Delta frames only replay on top of an earlier full frame, so the oldest
+    /// full frame goes together with the deltas that follow it. The newest
+    /// full frame is always kept.
+    pub fn evict_oldest(&mut self, bytes: usize) -> usize {
+        let mut freed = 0;
+        while freed < bytes && self.frames.len() > 1 {
+            if let Some(frame) = self.frames.pop_front() {
+                freed += encoded_size(&frame);
+            }
+            let keep_from = self.frames.front().map_or(0, |frame| frame.frame_number);
+            while matches!(self.delta_frames.front(), Some(delta) if delta.frame_number < keep_from)
+            {
+                if let Some(delta) = self.delta_frames.pop_front() {
+                    freed += encoded_size(&delta);
+                }
+            }
         }
+        freed
     }
 }
 
+fn encoded_size<T: Serialize>(value: &T) -> usize {
+    bincode::serialized_size(value).unwrap_or(0) as usize
+}
+
 /// Complete recording with all data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recording {
@@ -443,6 +688,97 @@ pub struct Recording {
     pub version: crate::playback_system::RecordingVersion,
 }
 
+impl Recording {
+    /// Last frame recorded at or before `timestamp`, or the first frame if it is earlier
+    pub fn frame_at(&self, timestamp: Duration) -> Option<usize> {
+        let full = self.frames.partition_point(|f| f.timestamp <= timestamp);
+        let delta = self
+            .delta_frames
+            .partition_point(|d| d.timestamp <= timestamp);
+        let last_full = full.checked_sub(1).map(|i| self.frames[i].frame_number);
+        let last_delta = delta
+            .checked_sub(1)
+            .map(|i| self.delta_frames[i].frame_number);
+        last_full
+            .max(last_delta)
+            .or_else(|| self.frames.first().map(|f| f.frame_number))
+    }
+
+    /// State of the recording at `point`, rebuilt from the nearest full frame
+    ///
+    /// Only the deltas between that full frame and `point` are applied. A
+    /// point past the end gives the last recorded state; `None` means the
+    /// point is before the first full frame.
+    pub fn state_at(&self, point: RecordingPoint) -> Option<Frame> {
+        let full_index = self
+            .frames
+            .partition_point(|f| !point.is_before(f.frame_number, f.timestamp));
+        let mut frame = self.frames.get(full_index.checked_sub(1)?)?.clone();
+
+        let first_delta = self
+            .delta_frames
+            .partition_point(|d| d.frame_number <= frame.frame_number);
+        for delta in self.delta_frames[first_delta..]
+            .iter()
+            .take_while(|d| !point.is_before(d.frame_number, d.timestamp))
+        {
+            delta.apply_to(&mut frame);
+        }
+        Some(frame)
+    }
+
+    /// The part of the recording between `start` and `end`
+    ///
+    /// Starts at the last full frame at or before `start` so every kept delta
+    /// can still be replayed. Frame numbers are kept as recorded.
+    pub fn window(&self, start: Duration, end: Duration) -> Recording {
+        let first_frame = self
+            .frames
+            .iter()
+            .filter(|f| f.timestamp <= start)
+            .map(|f| f.frame_number)
+            .max()
+            .or_else(|| self.frames.iter().map(|f| f.frame_number).min())
+            .unwrap_or(0);
+        let in_window = |frame_number: usize, timestamp: Duration| {
+            frame_number >= first_frame && timestamp <= end
+        };
+
+        let frames: Vec<Frame> = self
+            .frames
+            .iter()
+            .filter(|f| in_window(f.frame_number, f.timestamp))
+            .cloned()
+            .collect();
+        let delta_frames: Vec<DeltaFrame> = self
+            .delta_frames
+            .iter()
+            .filter(|d| in_window(d.frame_number, d.timestamp))
+            .cloned()
+            .collect();
+        let last_frame = frames
+            .iter()
+            .map(|f| f.frame_number)
+            .chain(delta_frames.iter().map(|d| d.frame_number))
+            .max();
+
+        Recording {
+            config: self.config.clone(),
+            markers: self
+                .markers
+                .iter()
+                .filter(|m| m.timestamp >= start && m.timestamp <= end)
+                .cloned()
+                .collect(),
+            total_frames: last_frame.map_or(0, |n| n + 1),
+            duration: end.min(self.duration),
+            frames,
+            delta_frames,
+            version: self.version.clone(),
+        }
+    }
+}
+
 /// Recording statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordingStats {
@@ -453,6 +789,26 @@ pub struct RecordingStats {
     pub duration: Duration,
     pub is_recording: bool,
     pub buffer_usage: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk: Option<DiskRingStats>,
+    /// Not tracked while spooling to disk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression: Option<CompressionStats>,
+}
+
+/// How much smaller the buffered frames are than full snapshots of every frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionStats {
+    /// Estimated size had every frame been kept as a full snapshot
+    pub full_snapshot_bytes: usize,
+    /// Size as held, keyframes plus deltas
+    pub stored_bytes: usize,
+    /// Size after zstd compression, or `stored_bytes` with compression off
+    pub compressed_bytes: usize,
+    /// `full_snapshot_bytes / stored_bytes`
+    pub delta_ratio: f32,
+    /// `full_snapshot_bytes / compressed_bytes`
+    pub compression_ratio: f32,
 }
 
 /// Timeline for navigating recordings
@@ -491,20 +847,24 @@ impl Timeline {
 
         let recording = self.recording.as_ref()?;
 
-        // Find nearest full frame
-        let full_frame = recording
+        // Frames and deltas are stored in frame order, so the nearest full
+        // frame and the deltas after it can be found by binary search
+        let full_index = recording
             .frames
-            .iter()
-            .filter(|f| f.frame_number <= frame_number)
-            .max_by_key(|f| f.frame_number)?;
+            .partition_point(|f| f.frame_number <= frame_number);
+        let full_frame = recording.frames.get(full_index.checked_sub(1)?)?;
 
         let mut reconstructed = full_frame.clone();
 
         // Apply delta frames
-        for delta in &recording.delta_frames {
-            if delta.frame_number <= frame_number && delta.frame_number > full_frame.frame_number {
-                self.apply_delta(&mut reconstructed, delta);
-            }
+        let first_delta = recording
+            .delta_frames
+            .partition_point(|d| d.frame_number <= full_frame.frame_number);
+        let last_delta = recording
+            .delta_frames
+            .partition_point(|d| d.frame_number <= frame_number);
+        for delta in &recording.delta_frames[first_delta..last_delta.max(first_delta)] {
+            delta.apply_to(&mut reconstructed);
         }
 
         // Cache the reconstructed frame with size limit
@@ -520,35 +880,6 @@ impl Timeline {
         Some(reconstructed)
     }
 
-    /// Apply a delta frame to a full frame
-    fn apply_delta(&self, frame: &mut Frame, delta: &DeltaFrame) {
-        // Remove entities
-        for id in &delta.removed_entities {
-            frame.entities.remove(id);
-        }
-
-        // Add entities
-        for (id, state) in &delta.added_entities {
-            frame.entities.insert(*id, state.clone());
-        }
-
-        // Apply component changes
-        for (id, changes) in &delta.changed_components {
-            if let Some(entity) = frame.entities.get_mut(id) {
-                for (comp_name, comp_value) in changes {
-                    entity
-                        .components
-                        .insert(comp_name.clone(), comp_value.clone());
-                }
-            }
-        }
-
-        // Update frame metadata
-        frame.frame_number = delta.frame_number;
-        frame.timestamp = delta.timestamp;
-        frame.events = delta.events.clone();
-    }
-
     /// Seek to a specific frame
     pub fn seek(&mut self, frame_number: usize) -> bool {
         if let Some(recording) = &self.recording {
@@ -596,6 +927,18 @@ impl Timeline {
         None
     }
 
+    /// Add a marker to the loaded recording, returning false if none is loaded
+    pub fn add_marker(&mut self, marker: Marker) -> bool {
+        let Some(recording) = self.recording.as_mut() else {
+            return false;
+        };
+        let index = recording
+            .markers
+            .partition_point(|m| m.frame_number <= marker.frame_number);
+        recording.markers.insert(index, marker);
+        true
+    }
+
     /// Get all markers
     pub fn markers(&self) -> Vec<Marker> {
         self.recording
@@ -624,6 +967,43 @@ impl RecordingState {
             timeline: Arc::new(RwLock::new(Timeline::new())),
         }
     }
+
+    /// The recording buffer as seen by the process memory budget
+    pub fn memory_consumer(&self) -> Arc<dyn MemoryConsumer> {
+        Arc::new(RecordingMemory {
+            buffer: self.buffer.clone(),
+        })
+    }
+}
+
+struct RecordingMemory {
+    buffer: Arc<RwLock<RecordingBuffer>>,
+}
+
+#[async_trait]
+impl MemoryConsumer for RecordingMemory {
+    fn name(&self) -> &'static str {
+        "recording"
+    }
+
+    fn eviction_priority(&self) -> u8 {
+        30
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.buffer.read().await.memory_usage()
+    }
+
+    async fn evict(&self, bytes: usize) -> usize {
+        let freed = self.buffer.write().await.evict_oldest(bytes);
+        if freed > 0 {
+            warn!(
+                "Memory budget dropped {} bytes of the oldest recorded frames",
+                freed
+            );
+        }
+        freed
+    }
 }
 
 // bincode is now a direct dependency, no fallback needed
@@ -728,6 +1108,39 @@ mod tests {
         assert!(delta.changed_components.contains_key(&1));
     }
 
+    #[test]
+    fn test_evict_oldest_keeps_replayable_frames() {
+        let mut buffer = RecordingBuffer::new(RecordingConfig::default());
+        let frame = |frame_number| Frame {
+            frame_number,
+            timestamp: Duration::from_secs(frame_number as u64),
+            entities: HashMap::new(),
+            events: Vec::new(),
+            checksum: None,
+        };
+        let delta = |frame_number| DeltaFrame {
+            frame_number,
+            timestamp: Duration::from_secs(frame_number as u64),
+            added_entities: HashMap::new(),
+            removed_entities: vec![frame_number as u64],
+            changed_components: HashMap::new(),
+            events: Vec::new(),
+        };
+        buffer.frames.extend([frame(0), frame(30), frame(60)]);
+        buffer.delta_frames.extend((1..70).map(delta));
+        let before = buffer.memory_usage();
+
+        let freed = buffer.evict_oldest(1);
+        assert_eq!(buffer.frames.front().unwrap().frame_number, 30);
+        assert_eq!(buffer.delta_frames.front().unwrap().frame_number, 30);
+        assert_eq!(buffer.memory_usage(), before - freed);
+
+        // The newest full frame and its deltas survive any request
+        buffer.evict_oldest(usize::MAX);
+        assert_eq!(buffer.frames.len(), 1);
+        assert_eq!(buffer.delta_frames.len(), 9);
+    }
+
     #[test]
     fn test_timeline_creation() {
         let timeline = Timeline::new();
@@ -769,6 +1182,55 @@ mod tests {
         assert!(!timeline.seek_to_marker("nonexistent"));
     }
 
+    #[test]
+    fn test_timeline_steps_backward_from_keyframes() {
+        let state = |x: usize| EntityState {
+            entity_id: 1,
+            components: HashMap::from([("x".to_string(), serde_json::json!(x))]),
+            active: true,
+        };
+        let frame = |n: usize| Frame {
+            frame_number: n,
+            timestamp: Duration::from_millis(n as u64 * 33),
+            entities: HashMap::from([(1, state(n))]),
+            events: Vec::new(),
+            checksum: None,
+        };
+        let delta = |n: usize| DeltaFrame {
+            frame_number: n,
+            timestamp: Duration::from_millis(n as u64 * 33),
+            added_entities: HashMap::new(),
+            removed_entities: Vec::new(),
+            changed_components: HashMap::from([(
+                1,
+                HashMap::from([("x".to_string(), serde_json::json!(n))]),
+            )]),
+            events: Vec::new(),
+        };
+
+        let mut timeline = Timeline::new();
+        timeline.load_recording(Recording {
+            config: RecordingConfig {
+                keyframe_interval: 10,
+                ..RecordingConfig::default()
+            },
+            frames: vec![frame(0), frame(10)],
+            delta_frames: (1..20).filter(|n| n % 10 != 0).map(delta).collect(),
+            markers: Vec::new(),
+            total_frames: 20,
+            duration: Duration::from_millis(660),
+            version: crate::playback_system::RecordingVersion::current(),
+        });
+
+        assert!(timeline.seek(19));
+        for expected in (0..19).rev() {
+            let frame = timeline.previous().unwrap();
+            assert_eq!(frame.frame_number, expected);
+            assert_eq!(frame.entities[&1], state(expected));
+        }
+        assert!(timeline.previous().is_none());
+    }
+
     #[test]
     fn test_recording_stats() {
         let config = RecordingConfig::default();
@@ -781,6 +1243,95 @@ mod tests {
         assert!(!stats.is_recording);
     }
 
+    #[test]
+    fn test_annotate_before_now() {
+        let frame = |n: usize| Frame {
+            frame_number: n,
+            timestamp: Duration::from_millis(n as u64 * 500),
+            entities: HashMap::new(),
+            events: Vec::new(),
+            checksum: None,
+        };
+
+        let mut buffer = RecordingBuffer::new(RecordingConfig::default());
+        assert!(buffer
+            .annotate("early".to_string(), None, HashMap::new(), Duration::ZERO)
+            .is_none());
+
+        buffer.start_recording();
+        // Ten frames half a second apart, the last one just now
+        buffer.start_time = Some(Instant::now() - Duration::from_millis(4500));
+        buffer.frames.push_back(frame(0));
+        for n in 1..10 {
+            let delta = buffer.create_delta_frame(&frame(n - 1), &frame(n));
+            buffer.delta_frames.push_back(delta);
+        }
+        buffer.frame_counter = 10;
+
+        let bug = buffer
+            .annotate("bug".to_string(), None, HashMap::new(), Duration::ZERO)
+            .unwrap();
+        assert_eq!(bug.frame_number, 10);
+        let before = buffer
+            .annotate(
+                "before_bug".to_string(),
+                Some("Player still on the ledge".to_string()),
+                HashMap::new(),
+                Duration::from_millis(2200),
+            )
+            .unwrap();
+        assert_eq!(before.frame_number, 4);
+
+        // Markers stay in frame order
+        let recording = buffer.to_recording().unwrap();
+        assert_eq!(recording.markers[0].name, "before_bug");
+        assert_eq!(recording.frame_at(Duration::from_millis(2300)), Some(4));
+
+        let mut timeline = Timeline::new();
+        timeline.load_recording(recording);
+        assert!(timeline.add_marker(bug));
+        assert!(timeline.seek_to_marker("before_bug"));
+        assert_eq!(timeline.current_frame, 4);
+    }
+
+    #[test]
+    fn test_compression_stats() {
+        let frame = |n: usize| Frame {
+            frame_number: n,
+            timestamp: Duration::from_millis(n as u64 * 33),
+            entities: (0..50)
+                .map(|id| {
+                    let state = EntityState {
+                        entity_id: id,
+                        components: HashMap::from([(
+                            "Transform".to_string(),
+                            // Only entity 0 moves
+                            serde_json::json!({"x": if id == 0 { n as u64 } else { id }, "y": 0}),
+                        )]),
+                        active: true,
+                    };
+                    (id, state)
+                })
+                .collect(),
+            events: Vec::new(),
+            checksum: None,
+        };
+
+        let mut buffer = RecordingBuffer::new(RecordingConfig::default());
+        assert!(buffer.get_stats().compression.is_none());
+
+        buffer.frames.push_back(frame(0));
+        for n in 1..30 {
+            let delta = buffer.create_delta_frame(&frame(n - 1), &frame(n));
+            buffer.delta_frames.push_back(delta);
+        }
+
+        let compression = buffer.get_stats().compression.unwrap();
+        assert!(compression.delta_ratio > 5.0);
+        assert!(compression.compressed_bytes < compression.stored_bytes);
+        assert!(compression.compression_ratio > compression.delta_ratio);
+    }
+
     #[test]
     fn test_recording_config_default() {
         let config = RecordingConfig::default();
@@ -0,0 +1,172 @@
+/*
+ * Bevy Debugger MCP Server - Guest Response Redaction
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Replaces every redacted value
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Component data hidden from guests
+///
+/// Each rule is either `Component.field`, `Component.*` for a whole component,
+/// or a bare name that matches any component or field with that name.
+/// Components match by full type path or by their short name, so
+/// `PlayerName.*` covers `game::player::PlayerName`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedactionRules {
+    pub rules: Vec<String>,
+}
+
+impl RedactionRules {
+    pub fn new(rules: Vec<String>) -> Self {
+        Self { rules }
+    }
+
+    /// From the comma separated `BEVY_MCP_GUEST_REDACT`
+    pub fn from_env() -> Self {
+        let rules = env::var("BEVY_MCP_GUEST_REDACT")
+            .map(|list| {
+                list.split(',')
+                    .map(str::trim)
+                    .filter(|rule| !rule.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::new(rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// `value` with every matching component or field replaced by [`REDACTED`]
+    pub fn redact(&self, mut value: Value) -> Value {
+        if !self.is_empty() {
+            self.redact_in_place(&mut value, None);
+        }
+        value
+    }
+
+    /// The value of `component` with the parts these rules hide replaced
+    pub fn redact_component(&self, component: &str, mut value: Value) -> Value {
+        if self.matches(None, component) {
+            return Value::String(REDACTED.to_string());
+        }
+        if !self.is_empty() {
+            self.redact_in_place(&mut value, Some(component));
+        }
+        value
+    }
+
+    fn redact_in_place(&self, value: &mut Value, parent: Option<&str>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    if self.matches(parent, key) {
+                        *child = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_in_place(child, Some(key));
+                    }
+                }
+            }
+            // Array elements belong to the same component as the array
+            Value::Array(items) => {
+                for item in items {
+                    self.redact_in_place(item, parent);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn matches(&self, parent: Option<&str>, key: &str) -> bool {
+        self.rules.iter().any(|rule| match rule.split_once('.') {
+            Some((component, "*")) => names_type(key, component),
+            Some((component, field)) => {
+                key == field && matches!(parent, Some(parent) if names_type(parent, component))
+            }
+            None => key == rule || names_type(key, rule),
+        })
+    }
+}
+
+/// Whether `key` is the type `name`, given either as a full path or a short name
+fn names_type(key: &str, name: &str) -> bool {
+    key == name || key.rsplit("::").next() == Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_component_and_field_rules() {
+        let rules = RedactionRules::new(vec![
+            "PlayerName.*".to_string(),
+            "ChatMessage.text".to_string(),
+            "email".to_string(),
+        ]);
+        let observed = json!({
+            "entities": [{
+                "id": 12,
+                "components": {
+                    "game::player::PlayerName": "alice",
+                    "game::chat::ChatMessage": {"text": "meet at spawn", "channel": "team"},
+                    "game::ui::Label": {"text": "Score"},
+                    "bevy_transform::components::transform::Transform": {"translation": [1.0, 2.0, 0.0]},
+                    "game::account::Account": {"profile": {"email": "a@example.com"}},
+                }
+            }]
+        });
+
+        let redacted = rules.redact(observed);
+        let components = &redacted["entities"][0]["components"];
+        assert_eq!(components["game::player::PlayerName"], REDACTED);
+        assert_eq!(components["game::chat::ChatMessage"]["text"], REDACTED);
+        assert_eq!(components["game::chat::ChatMessage"]["channel"], "team");
+        assert_eq!(components["game::ui::Label"]["text"], "Score");
+        assert_eq!(
+            components["game::account::Account"]["profile"]["email"],
+            REDACTED
+        );
+        assert_eq!(
+            components["bevy_transform::components::transform::Transform"]["translation"][1],
+            2.0
+        );
+    }
+
+    #[test]
+    fn test_field_rules_apply_inside_arrays() {
+        let rules = RedactionRules::new(vec!["ChatLog.lines".to_string()]);
+        let value = json!({"game::ChatLog": [{"lines": ["hi"]}, {"lines": ["bye"]}]});
+        let redacted = rules.redact(value);
+        assert_eq!(redacted["game::ChatLog"][0]["lines"], REDACTED);
+        assert_eq!(redacted["game::ChatLog"][1]["lines"], REDACTED);
+
+        let untouched = json!({"game::PlayerName": "bob"});
+        assert_eq!(
+            RedactionRules::default().redact(untouched.clone()),
+            untouched
+        );
+    }
+}
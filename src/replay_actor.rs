@@ -13,7 +13,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::brp_client::BrpClient;
 use crate::error::{Error, Result};
-use crate::playback_system::{DirectSync, PlaybackController};
+use crate::playback_system::{DirectSync, PlaybackController, PlaybackSpeed};
 use crate::recording_system::{RecordingBuffer, RecordingConfig, RecordingState};
 use crate::timeline_branching::{
     BranchId, MergeStrategy, Modification, ModificationLayer, TimelineBranchManager,
@@ -436,6 +436,7 @@ impl ReplayActor {
     }
 
     async fn set_speed(&mut self, speed: f64) -> Result<()> {
+        PlaybackSpeed::new(speed as f32)?;
         self.state.playback_status.playback_speed = speed;
         Ok(())
     }
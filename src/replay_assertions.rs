@@ -0,0 +1,474 @@
+/*
+ * Bevy Debugger MCP Server - Replay Assertions
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Assertions checked against recorded frames as they play back
+//!
+//! Assertions can be written as short sentences:
+//!
+//! ```text
+//! entity 42 never has Health < 0
+//! entity 7 always has Transform.translation.y >= -100
+//! every entity never has Velocity.x > 50
+//! frame time < 20ms
+//! ```
+//!
+//! Component values are read from the recorded component JSON, following the
+//! dotted path after the component name. Frame time is the time between
+//! consecutive recorded frames.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::recording_system::Frame;
+
+/// Violations kept per assertion; the rest are only counted
+pub const MAX_REPORTED_VIOLATIONS: usize = 20;
+
+/// Comparison between an observed and an expected number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+}
+
+impl CompareOp {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "<" => Some(Self::Lt),
+            "<=" => Some(Self::Le),
+            ">" => Some(Self::Gt),
+            ">=" => Some(Self::Ge),
+            "==" | "=" => Some(Self::Eq),
+            "!=" => Some(Self::Ne),
+            _ => None,
+        }
+    }
+
+    pub fn holds(self, actual: f64, expected: f64) -> bool {
+        match self {
+            Self::Lt => actual < expected,
+            Self::Le => actual <= expected,
+            Self::Gt => actual > expected,
+            Self::Ge => actual >= expected,
+            Self::Eq => (actual - expected).abs() < f64::EPSILON,
+            Self::Ne => (actual - expected).abs() >= f64::EPSILON,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
+}
+
+/// Whether a component condition must always or never hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expectation {
+    Always,
+    Never,
+}
+
+/// A condition checked on every frame of a replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayAssertion {
+    Component {
+        /// Entity to check; every entity with the component when absent
+        #[serde(default)]
+        entity: Option<u64>,
+        component: String,
+        /// Dotted path into the component value, e.g. `translation.y`
+        #[serde(default)]
+        field: Option<String>,
+        expectation: Expectation,
+        op: CompareOp,
+        value: f64,
+    },
+    FrameTime {
+        op: CompareOp,
+        millis: f64,
+    },
+}
+
+impl fmt::Display for ReplayAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Component {
+                entity,
+                component,
+                field,
+                expectation,
+                op,
+                value,
+            } => {
+                match entity {
+                    Some(id) => write!(f, "entity {id} ")?,
+                    None => write!(f, "every entity ")?,
+                }
+                let expectation = match expectation {
+                    Expectation::Always => "always",
+                    Expectation::Never => "never",
+                };
+                write!(f, "{expectation} has {component}")?;
+                if let Some(field) = field {
+                    write!(f, ".{field}")?;
+                }
+                write!(f, " {} {value}", op.symbol())
+            }
+            Self::FrameTime { op, millis } => write!(f, "frame time {} {millis}ms", op.symbol()),
+        }
+    }
+}
+
+impl FromStr for ReplayAssertion {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| Error::Validation(format!("Invalid assertion '{expression}': {reason}"));
+        let tokens: Vec<&str> = expression.split_whitespace().collect();
+
+        if let ["frame", "time", op, limit] = tokens.as_slice() {
+            let op = CompareOp::parse(op).ok_or_else(|| invalid("unknown comparison"))?;
+            let (number, scale) = match limit.strip_suffix("ms") {
+                Some(number) => (number, 1.0),
+                None => (limit.strip_suffix('s').unwrap_or(limit), 1000.0),
+            };
+            let millis = number
+                .parse::<f64>()
+                .map_err(|_| invalid("frame time limit must be a number"))?;
+            return Ok(Self::FrameTime {
+                op,
+                millis: millis * scale,
+            });
+        }
+
+        let (entity, rest) = match tokens.as_slice() {
+            ["entity", "*", rest @ ..] | ["every" | "any", "entity", rest @ ..] => (None, rest),
+            ["entity", id, rest @ ..] => (
+                Some(
+                    id.parse::<u64>()
+                        .map_err(|_| invalid("entity must be an ID or '*'"))?,
+                ),
+                rest,
+            ),
+            _ => {
+                return Err(invalid(
+                    "expected 'entity <id> always|never has ...' or 'frame time <op> <ms>'",
+                ))
+            }
+        };
+        let [expectation, "has", target, op, value] = rest else {
+            return Err(invalid(
+                "expected 'always|never has <Component[.field]> <op> <value>'",
+            ));
+        };
+        let expectation = match *expectation {
+            "always" => Expectation::Always,
+            "never" => Expectation::Never,
+            _ => return Err(invalid("expected 'always' or 'never'")),
+        };
+        let (component, field) = match target.split_once('.') {
+            Some((component, field)) => (component, Some(field.to_string())),
+            None => (*target, None),
+        };
+
+        Ok(Self::Component {
+            entity,
+            component: component.to_string(),
+            field,
+            expectation,
+            op: CompareOp::parse(op).ok_or_else(|| invalid("unknown comparison"))?,
+            value: value
+                .parse()
+                .map_err(|_| invalid("value must be a number"))?,
+        })
+    }
+}
+
+impl ReplayAssertion {
+    /// Parse an assertion given as a sentence or as its JSON form
+    pub fn from_value(value: &Value) -> Result<Self> {
+        match value {
+            Value::String(expression) => expression.parse(),
+            _ => serde_json::from_value(value.clone())
+                .map_err(|e| Error::Validation(format!("Invalid assertion {value}: {e}"))),
+        }
+    }
+}
+
+/// Where and how an assertion failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Violation {
+    pub frame_number: usize,
+    pub timestamp: Duration,
+    pub entity: Option<u64>,
+    /// The value the assertion compared
+    pub actual: f64,
+    /// The entity's recorded components at that frame
+    pub state: Option<Value>,
+}
+
+/// Outcome of one assertion over the frames checked so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionResult {
+    pub assertion: String,
+    pub passed: bool,
+    /// How many values were compared
+    pub evaluations: usize,
+    pub violation_count: usize,
+    /// The first [`MAX_REPORTED_VIOLATIONS`] violations
+    pub violations: Vec<Violation>,
+}
+
+/// Outcome of every assertion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertionReport {
+    pub passed: bool,
+    pub frames_checked: usize,
+    pub results: Vec<AssertionResult>,
+}
+
+/// Checks assertions against frames fed to it in playback order
+#[derive(Debug, Clone)]
+pub struct AssertionChecker {
+    assertions: Vec<ReplayAssertion>,
+    results: Vec<AssertionResult>,
+    frames_checked: usize,
+    previous_timestamp: Option<Duration>,
+}
+
+impl AssertionChecker {
+    pub fn new(assertions: Vec<ReplayAssertion>) -> Self {
+        let results = assertions
+            .iter()
+            .map(|assertion| AssertionResult {
+                assertion: assertion.to_string(),
+                passed: true,
+                evaluations: 0,
+                violation_count: 0,
+                violations: Vec::new(),
+            })
+            .collect();
+        Self {
+            assertions,
+            results,
+            frames_checked: 0,
+            previous_timestamp: None,
+        }
+    }
+
+    pub fn check_frame(&mut self, frame: &Frame) {
+        let frame_time = self
+            .previous_timestamp
+            .map(|previous| frame.timestamp.saturating_sub(previous));
+        self.previous_timestamp = Some(frame.timestamp);
+        self.frames_checked += 1;
+
+        for (assertion, result) in self.assertions.iter().zip(&mut self.results) {
+            let mut record = |entity: Option<u64>, actual: f64, violated: bool| {
+                result.evaluations += 1;
+                if !violated {
+                    return;
+                }
+                result.passed = false;
+                result.violation_count += 1;
+                if result.violations.len() < MAX_REPORTED_VIOLATIONS {
+                    result.violations.push(Violation {
+                        frame_number: frame.frame_number,
+                        timestamp: frame.timestamp,
+                        entity,
+                        actual,
+                        state: entity
+                            .and_then(|id| frame.entities.get(&id))
+                            .and_then(|state| serde_json::to_value(&state.components).ok()),
+                    });
+                }
+            };
+
+            match assertion {
+                ReplayAssertion::FrameTime { op, millis } => {
+                    if let Some(frame_time) = frame_time {
+                        let actual = frame_time.as_secs_f64() * 1000.0;
+                        record(None, actual, !op.holds(actual, *millis));
+                    }
+                }
+                ReplayAssertion::Component {
+                    entity,
+                    component,
+                    field,
+                    expectation,
+                    op,
+                    value,
+                } => {
+                    let entities = frame
+                        .entities
+                        .iter()
+                        .filter(|(id, _)| entity.is_none() || *entity == Some(**id));
+                    for (id, state) in entities {
+                        let Some(actual) = state
+                            .components
+                            .get(component)
+                            .and_then(|v| read_number(v, field.as_deref()))
+                        else {
+                            continue;
+                        };
+                        let holds = op.holds(actual, *value);
+                        let violated = match expectation {
+                            Expectation::Always => !holds,
+                            Expectation::Never => holds,
+                        };
+                        record(Some(*id), actual, violated);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn report(&self) -> AssertionReport {
+        AssertionReport {
+            passed: self.results.iter().all(|r| r.passed),
+            frames_checked: self.frames_checked,
+            results: self.results.clone(),
+        }
+    }
+}
+
+/// Number at `field` (a dotted path) inside a component value
+fn read_number(value: &Value, field: Option<&str>) -> Option<f64> {
+    let mut current = value;
+    for key in field.into_iter().flat_map(|f| f.split('.')) {
+        current = match current {
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => current.get(key)?,
+        };
+    }
+    current.as_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording_system::EntityState;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn frame(frame_number: usize, millis: u64, health: i64) -> Frame {
+        Frame {
+            frame_number,
+            timestamp: Duration::from_millis(millis),
+            entities: HashMap::from([(
+                42,
+                EntityState {
+                    entity_id: 42,
+                    components: HashMap::from([("Health".to_string(), json!({"current": health}))]),
+                    active: true,
+                },
+            )]),
+            events: Vec::new(),
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_assertions() {
+        let assertion: ReplayAssertion = "entity 42 never has Health.current < 0".parse().unwrap();
+        assert_eq!(
+            assertion,
+            ReplayAssertion::Component {
+                entity: Some(42),
+                component: "Health".to_string(),
+                field: Some("current".to_string()),
+                expectation: Expectation::Never,
+                op: CompareOp::Lt,
+                value: 0.0,
+            }
+        );
+        assert_eq!(
+            assertion.to_string(),
+            "entity 42 never has Health.current < 0"
+        );
+        assert_eq!(
+            "frame time < 0.5s".parse::<ReplayAssertion>().unwrap(),
+            ReplayAssertion::FrameTime {
+                op: CompareOp::Lt,
+                millis: 500.0
+            }
+        );
+        assert!("entity 42 sometimes has Health < 0"
+            .parse::<ReplayAssertion>()
+            .is_err());
+        assert!(ReplayAssertion::from_value(&json!({
+            "kind": "frame_time", "op": "<=", "millis": 16.7
+        }))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_checker_reports_violations() {
+        let mut checker = AssertionChecker::new(vec![
+            "entity 42 never has Health.current < 0".parse().unwrap(),
+            "frame time < 20ms".parse().unwrap(),
+            "every entity always has Health.current <= 100"
+                .parse()
+                .unwrap(),
+        ]);
+        for (n, (millis, health)) in [(0, 100), (16, 50), (50, -5), (66, 10)].iter().enumerate() {
+            checker.check_frame(&frame(n, *millis, *health));
+        }
+
+        let report = checker.report();
+        assert!(!report.passed);
+        assert_eq!(report.frames_checked, 4);
+
+        let health = &report.results[0];
+        assert_eq!(health.violation_count, 1);
+        assert_eq!(health.violations[0].frame_number, 2);
+        assert_eq!(health.violations[0].actual, -5.0);
+        assert_eq!(
+            health.violations[0].state.as_ref().unwrap()["Health"],
+            json!({"current": -5})
+        );
+
+        let frame_time = &report.results[1];
+        assert_eq!(frame_time.evaluations, 3);
+        assert_eq!(frame_time.violations[0].frame_number, 2);
+        assert!(report.results[2].passed);
+    }
+}
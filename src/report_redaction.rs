@@ -0,0 +1,216 @@
+/*
+ * Bevy Debugger MCP Server - Diagnostic Report Redaction
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Redaction applied to diagnostic reports before they leave the machine
+//!
+//! Fields are named by their dotted path in the report's JSON, such as
+//! `system_info.hostname` or `environment_info.environment_variables.HOME`.
+//! A name without a dot matches that key at any depth.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::env;
+
+use crate::redaction::REDACTED;
+
+/// Which report fields are exported, hidden or hashed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportRedactionPolicy {
+    /// When non-empty, only these fields (with their parents and children) are exported
+    pub allow: Vec<String>,
+    /// Fields replaced by [`REDACTED`]
+    pub deny: Vec<String>,
+    /// Fields replaced by a salted hash, so reports from one machine can still be correlated
+    pub hash: Vec<String>,
+    pub hash_salt: String,
+}
+
+impl ReportRedactionPolicy {
+    /// From the comma separated `BEVY_MCP_REPORT_ALLOW`, `BEVY_MCP_REPORT_DENY`
+    /// and `BEVY_MCP_REPORT_HASH`, salted with `BEVY_MCP_REPORT_HASH_SALT`
+    pub fn from_env() -> Self {
+        let list = |name: &str| {
+            env::var(name)
+                .map(|list| {
+                    list.split(',')
+                        .map(str::trim)
+                        .filter(|field| !field.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            allow: list("BEVY_MCP_REPORT_ALLOW"),
+            deny: list("BEVY_MCP_REPORT_DENY"),
+            hash: list("BEVY_MCP_REPORT_HASH"),
+            hash_salt: env::var("BEVY_MCP_REPORT_HASH_SALT").unwrap_or_default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty() && self.hash.is_empty()
+    }
+
+    /// `report` with the policy applied
+    pub fn apply(&self, mut report: Value) -> Value {
+        if !self.is_empty() {
+            if let Value::Object(map) = &mut report {
+                self.apply_to_object(map, "");
+            }
+        }
+        report
+    }
+
+    fn apply_to_object(&self, map: &mut Map<String, Value>, path: &str) {
+        let keys: Vec<String> = map.keys().cloned().collect();
+        for key in keys {
+            let child_path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            if !self.allows(&child_path) {
+                map.remove(&key);
+                continue;
+            }
+            let Some(child) = map.get_mut(&key) else {
+                continue;
+            };
+            if matches(&self.deny, &child_path, &key) {
+                *child = Value::String(REDACTED.to_string());
+            } else if matches(&self.hash, &child_path, &key) {
+                *child = Value::String(self.hashed(child));
+            } else {
+                self.apply_to_value(child, &child_path);
+            }
+        }
+    }
+
+    // Array elements share the array's path
+    fn apply_to_value(&self, value: &mut Value, path: &str) {
+        match value {
+            Value::Object(map) => self.apply_to_object(map, path),
+            Value::Array(items) => {
+                for item in items {
+                    self.apply_to_value(item, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn allows(&self, path: &str) -> bool {
+        self.allow.is_empty()
+            || self.allow.iter().any(|rule| {
+                rule == path
+                    || path.starts_with(&format!("{rule}."))
+                    || rule.starts_with(&format!("{path}."))
+            })
+    }
+
+    fn hashed(&self, value: &Value) -> String {
+        let text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let mut hasher = Sha256::new();
+        hasher.update(self.hash_salt.as_bytes());
+        hasher.update(text.as_bytes());
+        let digest = hasher.finalize();
+        let hex: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+        format!("hash:{hex}")
+    }
+}
+
+fn matches(rules: &[String], path: &str, key: &str) -> bool {
+    rules.iter().any(|rule| {
+        if rule.contains('.') {
+            rule == path
+        } else {
+            rule == key
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report() -> Value {
+        json!({
+            "report_id": "r1",
+            "system_info": {"os": "linux", "hostname": "build-07"},
+            "environment_info": {
+                "working_directory": "/home/alice/game",
+                "environment_variables": {"HOME": "/home/alice", "RUST_LOG": "info"}
+            },
+            "recent_logs": ["connected to build-07"]
+        })
+    }
+
+    #[test]
+    fn test_deny_and_hash_rules() {
+        let policy = ReportRedactionPolicy {
+            deny: vec!["working_directory".to_string(), "recent_logs".to_string()],
+            hash: vec!["system_info.hostname".to_string()],
+            hash_salt: "team".to_string(),
+            ..Default::default()
+        };
+        let redacted = policy.apply(report());
+        assert_eq!(redacted["environment_info"]["working_directory"], REDACTED);
+        assert_eq!(redacted["recent_logs"], REDACTED);
+        assert_eq!(redacted["system_info"]["os"], "linux");
+
+        // Stable for a salt, different across salts
+        let hostname = redacted["system_info"]["hostname"].as_str().unwrap();
+        assert!(hostname.starts_with("hash:"));
+        assert_eq!(policy.apply(report())["system_info"]["hostname"], hostname);
+        let other_salt = ReportRedactionPolicy {
+            hash_salt: "other".to_string(),
+            ..policy
+        };
+        assert_ne!(
+            other_salt.apply(report())["system_info"]["hostname"],
+            hostname
+        );
+    }
+
+    #[test]
+    fn test_allow_list_keeps_only_named_fields() {
+        let policy = ReportRedactionPolicy {
+            allow: vec![
+                "report_id".to_string(),
+                "system_info.os".to_string(),
+                "environment_info.environment_variables.RUST_LOG".to_string(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.apply(report()),
+            json!({
+                "report_id": "r1",
+                "system_info": {"os": "linux"},
+                "environment_info": {"environment_variables": {"RUST_LOG": "info"}}
+            })
+        );
+    }
+}
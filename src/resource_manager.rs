@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use sysinfo::System;
 use tokio::sync::{mpsc, RwLock, Semaphore};
 use tokio::time::interval;
 use tracing::{info, warn};
@@ -11,6 +12,8 @@ use uuid::Uuid;
 // For now, we'll implement our own lightweight monitoring
 
 use crate::error::{Error, Result};
+use crate::flight_recorder::{self, FlightEventKind};
+use crate::process_metrics::ProcessSampler;
 
 /// Unique identifier for resource tracking
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -53,6 +56,9 @@ pub struct ResourceConfig {
     pub circuit_breaker_threshold: usize,
     /// Circuit breaker reset timeout
     pub circuit_breaker_reset_timeout: Duration,
+    /// How adaptive sampling reacts to load
+    #[serde(default)]
+    pub sampling_policy: SamplingPolicy,
 }
 
 impl Default for ResourceConfig {
@@ -67,16 +73,130 @@ impl Default for ResourceConfig {
             object_pooling_enabled: true,
             circuit_breaker_threshold: 5,
             circuit_breaker_reset_timeout: Duration::from_secs(30),
+            sampling_policy: SamplingPolicy::default(),
         }
     }
 }
 
+/// Tunable policy for adaptive sampling
+///
+/// The sampling rate drops while average CPU usage is above half again the
+/// target (or memory above its target) and recovers while CPU is below half
+/// the target. Requests made on behalf of exempt tools are always sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingPolicy {
+    /// CPU usage percentage the sampler steers towards
+    pub target_cpu_percent: f32,
+    /// Memory usage in bytes above which sampling is reduced
+    pub target_memory_bytes: u64,
+    /// Lowest sampling rate the sampler will drop to (0-1)
+    pub min_sample_rate: f32,
+    /// Tools whose requests are never dropped
+    pub exempt_tools: BTreeSet<String>,
+}
+
+impl Default for SamplingPolicy {
+    fn default() -> Self {
+        Self {
+            target_cpu_percent: 10.0,
+            target_memory_bytes: 80 * 1024 * 1024, // 80MB
+            min_sample_rate: 0.01,                 // 1% minimum sampling
+            exempt_tools: BTreeSet::new(),
+        }
+    }
+}
+
+impl SamplingPolicy {
+    /// Defaults overridden by `BEVY_MCP_SAMPLING_TARGET_CPU`,
+    /// `BEVY_MCP_SAMPLING_MIN_RATE` and `BEVY_MCP_SAMPLING_EXEMPT_TOOLS`
+    /// (comma separated)
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            target_cpu_percent: std::env::var("BEVY_MCP_SAMPLING_TARGET_CPU")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.target_cpu_percent),
+            min_sample_rate: std::env::var("BEVY_MCP_SAMPLING_MIN_RATE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.min_sample_rate),
+            exempt_tools: std::env::var("BEVY_MCP_SAMPLING_EXEMPT_TOOLS")
+                .map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|tool| !tool.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or(defaults.exempt_tools),
+            ..defaults
+        }
+    }
+
+    /// Reject values the sampler can't work with
+    pub fn validate(&self) -> Result<()> {
+        if !(self.target_cpu_percent > 0.0 && self.target_cpu_percent <= 100.0) {
+            return Err(Error::Validation(
+                "target_cpu_percent must be in (0, 100]".to_string(),
+            ));
+        }
+        if !(self.min_sample_rate > 0.0 && self.min_sample_rate <= 1.0) {
+            return Err(Error::Validation(
+                "min_sample_rate must be in (0, 1]".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_TOOL: String;
+}
+
+/// Run `future` on behalf of `tool`, so sampling decisions made inside it
+/// can honour per-tool exemptions
+pub async fn with_tool_scope<F: Future>(tool: &str, future: F) -> F::Output {
+    CURRENT_TOOL.scope(tool.to_string(), future).await
+}
+
+/// The tool the current task is running on behalf of, if any
+pub fn current_tool() -> Option<String> {
+    CURRENT_TOOL.try_with(|tool| tool.clone()).ok()
+}
+
+/// Sampling decisions made for one tool
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolSamplingStats {
+    /// Rate currently applied to the tool's requests
+    pub rate: f32,
+    pub exempt: bool,
+    pub sampled: u64,
+    pub dropped: u64,
+}
+
+/// Current sampling state, as reported by the `sampling` tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingReport {
+    pub enabled: bool,
+    pub current_rate: f32,
+    pub policy: SamplingPolicy,
+    pub recent_cpu_percent: Option<f32>,
+    pub recent_memory_bytes: Option<u64>,
+    pub tools: BTreeMap<String, ToolSamplingStats>,
+}
+
 /// Current resource usage metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceMetrics {
     pub timestamp: SystemTime,
     pub cpu_percent: f32,
     pub memory_bytes: u64,
+    /// Only reported on Linux
+    pub thread_count: Option<u64>,
+    /// Only reported on Linux
+    pub open_fds: Option<u64>,
     pub concurrent_operations: usize,
     pub brp_requests_per_second: u32,
     pub circuit_breaker_open: bool,
@@ -204,23 +324,33 @@ impl CircuitBreaker {
 
     pub async fn record_success(&self) {
         self.failure_count.store(0, Ordering::Relaxed);
-        self.is_open.store(false, Ordering::Relaxed);
+        self.set_open(false);
     }
 
     pub async fn record_failure(&self) {
         let count = self.failure_count.fetch_add(1, Ordering::Relaxed) + 1;
 
         if count >= self.threshold {
-            self.is_open.store(true, Ordering::Relaxed);
+            self.set_open(true);
             *self.last_failure_time.write().await = Some(Instant::now());
         }
     }
 
     pub async fn reset(&self) {
         self.failure_count.store(0, Ordering::Relaxed);
-        self.is_open.store(false, Ordering::Relaxed);
+        self.set_open(false);
         *self.last_failure_time.write().await = None;
     }
+
+    /// Open or close the breaker, noting changes in the flight recorder
+    fn set_open(&self, open: bool) {
+        if self.is_open.swap(open, Ordering::Relaxed) != open {
+            flight_recorder::record(FlightEventKind::StateTransition {
+                component: "circuit_breaker".to_string(),
+                state: if open { "open" } else { "closed" }.to_string(),
+            });
+        }
+    }
 }
 
 /// Adaptive sampler for high-frequency data
@@ -229,24 +359,108 @@ pub struct AdaptiveSampler {
     samples: Arc<RwLock<Vec<ResourceSample>>>,
     sample_rate: Arc<RwLock<f32>>,
     max_samples: usize,
-    min_rate: f32,
     max_rate: f32,
+    policy: RwLock<SamplingPolicy>,
+    /// Sampled and dropped counts per tool, with "unscoped" for requests outside a tool call
+    decisions: RwLock<HashMap<String, (u64, u64)>>,
 }
 
 impl AdaptiveSampler {
     pub fn new(max_samples: usize) -> Self {
+        Self::with_policy(max_samples, SamplingPolicy::default())
+    }
+
+    pub fn with_policy(max_samples: usize, policy: SamplingPolicy) -> Self {
         Self {
             samples: Arc::new(RwLock::new(Vec::with_capacity(max_samples))),
             sample_rate: Arc::new(RwLock::new(1.0)),
             max_samples,
-            min_rate: 0.01, // 1% minimum sampling
-            max_rate: 1.0,  // 100% maximum sampling
+            max_rate: 1.0, // 100% maximum sampling
+            policy: RwLock::new(policy),
+            decisions: RwLock::new(HashMap::new()),
         }
     }
 
+    /// Decide whether to send a request made by the current tool
     pub async fn should_sample(&self) -> bool {
+        self.should_sample_tool(current_tool().as_deref()).await
+    }
+
+    /// Decide whether to send a request made on behalf of `tool`
+    pub async fn should_sample_tool(&self, tool: Option<&str>) -> bool {
+        let exempt = match tool {
+            Some(tool) => self.policy.read().await.exempt_tools.contains(tool),
+            None => false,
+        };
+        let sampled = exempt || rand::random::<f32>() < *self.sample_rate.read().await;
+
+        let mut decisions = self.decisions.write().await;
+        let counts = decisions
+            .entry(tool.unwrap_or("unscoped").to_string())
+            .or_default();
+        if sampled {
+            counts.0 += 1;
+        } else {
+            counts.1 += 1;
+        }
+        sampled
+    }
+
+    pub async fn policy(&self) -> SamplingPolicy {
+        self.policy.read().await.clone()
+    }
+
+    /// Replace the policy, clamping the current rate to its minimum
+    pub async fn set_policy(&self, policy: SamplingPolicy) -> Result<()> {
+        policy.validate()?;
+        let mut rate = self.sample_rate.write().await;
+        *rate = rate.max(policy.min_sample_rate);
+        info!("Adaptive sampling policy updated: {:?}", policy);
+        *self.policy.write().await = policy;
+        Ok(())
+    }
+
+    /// Current rate for every tool that has made requests or is exempt
+    pub async fn tool_rates(&self) -> BTreeMap<String, ToolSamplingStats> {
         let rate = *self.sample_rate.read().await;
-        rand::random::<f32>() < rate
+        let policy = self.policy.read().await;
+        let decisions = self.decisions.read().await;
+
+        let mut tools: BTreeMap<String, ToolSamplingStats> = decisions
+            .iter()
+            .map(|(tool, &(sampled, dropped))| {
+                let exempt = policy.exempt_tools.contains(tool);
+                let stats = ToolSamplingStats {
+                    rate: if exempt { 1.0 } else { rate },
+                    exempt,
+                    sampled,
+                    dropped,
+                };
+                (tool.clone(), stats)
+            })
+            .collect();
+        for tool in &policy.exempt_tools {
+            tools.entry(tool.clone()).or_insert(ToolSamplingStats {
+                rate: 1.0,
+                exempt: true,
+                ..Default::default()
+            });
+        }
+        tools
+    }
+
+    /// Average CPU and memory usage over the samples the rate is based on
+    async fn recent_usage(&self) -> Option<(f32, u64)> {
+        let samples = self.samples.read().await;
+        let recent_samples = &samples[samples.len().saturating_sub(10)..];
+        if recent_samples.is_empty() {
+            return None;
+        }
+        let count = recent_samples.len();
+        Some((
+            recent_samples.iter().map(|s| s.cpu_percent).sum::<f32>() / count as f32,
+            recent_samples.iter().map(|s| s.memory_bytes).sum::<u64>() / count as u64,
+        ))
     }
 
     pub async fn add_sample(&self, cpu_percent: f32, memory_bytes: u64, request_count: u32) {
@@ -282,14 +496,19 @@ impl AdaptiveSampler {
             / recent_samples.len() as u64;
 
         let mut new_rate = *self.sample_rate.read().await;
+        let policy = self.policy.read().await;
 
         // Increase sampling rate if resources are low
-        if avg_cpu < 5.0 && avg_memory < 50 * 1024 * 1024 {
+        if avg_cpu < policy.target_cpu_percent * 0.5
+            && avg_memory < policy.target_memory_bytes / 8 * 5
+        {
             new_rate = (new_rate * 1.1).min(self.max_rate);
         }
         // Decrease sampling rate if resources are high
-        else if avg_cpu > 15.0 || avg_memory > 80 * 1024 * 1024 {
-            new_rate = (new_rate * 0.9).max(self.min_rate);
+        else if avg_cpu > policy.target_cpu_percent * 1.5
+            || avg_memory > policy.target_memory_bytes
+        {
+            new_rate = (new_rate * 0.9).max(policy.min_sample_rate);
         }
 
         *self.sample_rate.write().await = new_rate;
@@ -342,19 +561,130 @@ impl RateLimiter {
     }
 }
 
+/// Rolling windows tool latency is reported over, by label
+pub const TOOL_LATENCY_WINDOWS: [(&str, Duration); 3] = [
+    ("1m", Duration::from_secs(60)),
+    ("5m", Duration::from_secs(5 * 60)),
+    ("15m", Duration::from_secs(15 * 60)),
+];
+
+/// Calls kept per tool; beyond this the longer windows only cover the most recent calls
+const MAX_TOOL_SAMPLES: usize = 10_000;
+
+/// Calls, error rate and latency percentiles of one tool over one window
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolLatencyStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ToolCallSample {
+    at: Instant,
+    duration: Duration,
+    failed: bool,
+}
+
+/// Per-tool call latencies over [`TOOL_LATENCY_WINDOWS`]
+#[derive(Debug, Default)]
+pub struct ToolLatencyTracker {
+    calls: RwLock<HashMap<String, VecDeque<ToolCallSample>>>,
+}
+
+impl ToolLatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record(&self, tool: &str, duration: Duration, failed: bool) {
+        let now = Instant::now();
+        let longest = TOOL_LATENCY_WINDOWS[TOOL_LATENCY_WINDOWS.len() - 1].1;
+        let mut calls = self.calls.write().await;
+        let samples = calls.entry(tool.to_string()).or_default();
+        samples.push_back(ToolCallSample {
+            at: now,
+            duration,
+            failed,
+        });
+        while samples
+            .front()
+            .is_some_and(|s| now.duration_since(s.at) > longest)
+            || samples.len() > MAX_TOOL_SAMPLES
+        {
+            samples.pop_front();
+        }
+    }
+
+    /// Stats for every tool called within the longest window, keyed by tool then window label
+    pub async fn report(&self) -> BTreeMap<String, BTreeMap<String, ToolLatencyStats>> {
+        let now = Instant::now();
+        let calls = self.calls.read().await;
+        calls
+            .iter()
+            .filter_map(|(tool, samples)| {
+                let windows: BTreeMap<String, ToolLatencyStats> = TOOL_LATENCY_WINDOWS
+                    .iter()
+                    .map(|(label, window)| {
+                        let recent = samples
+                            .iter()
+                            .filter(|s| now.duration_since(s.at) <= *window);
+                        (label.to_string(), Self::window_stats(recent))
+                    })
+                    .collect();
+                windows
+                    .values()
+                    .any(|stats| stats.calls > 0)
+                    .then(|| (tool.clone(), windows))
+            })
+            .collect()
+    }
+
+    fn window_stats<'a>(samples: impl Iterator<Item = &'a ToolCallSample>) -> ToolLatencyStats {
+        let mut errors = 0;
+        let mut durations: Vec<f64> = samples
+            .map(|s| {
+                errors += u64::from(s.failed);
+                s.duration.as_micros() as f64 / 1000.0
+            })
+            .collect();
+        if durations.is_empty() {
+            return ToolLatencyStats::default();
+        }
+        durations.sort_by(|a, b| a.total_cmp(b));
+
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * durations.len() as f64).ceil() as usize;
+            durations[rank.clamp(1, durations.len()) - 1]
+        };
+        ToolLatencyStats {
+            calls: durations.len() as u64,
+            errors,
+            error_rate: errors as f64 / durations.len() as f64,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+        }
+    }
+}
+
 /// Main resource manager
 #[derive(Debug)]
 pub struct ResourceManager {
     config: ResourceConfig,
     metrics: Arc<RwLock<ResourceMetrics>>,
-    system: Arc<RwLock<System>>,
-    pid: usize,
+    process: Arc<RwLock<ProcessSampler>>,
 
     // Resource controls
     operation_semaphore: Arc<Semaphore>,
     circuit_breaker: Arc<CircuitBreaker>,
     adaptive_sampler: Arc<AdaptiveSampler>,
     rate_limiter: Arc<RateLimiter>,
+    tool_latency: Arc<ToolLatencyTracker>,
 
     // Object pools
     string_pool: Arc<ObjectPool<String>>,
@@ -371,16 +701,19 @@ pub struct ResourceManager {
 
 impl ResourceManager {
     pub fn new(config: ResourceConfig) -> Self {
-        let mut system = System::new_all();
-        system.refresh_all();
-        let pid = std::process::id() as usize;
+        let mut process = ProcessSampler::new();
+        // Start the CPU measurement window
+        process.sample();
 
         let operation_semaphore = Arc::new(Semaphore::new(config.max_concurrent_operations));
         let circuit_breaker = Arc::new(CircuitBreaker::new(
             config.circuit_breaker_threshold,
             config.circuit_breaker_reset_timeout,
         ));
-        let adaptive_sampler = Arc::new(AdaptiveSampler::new(1000));
+        let adaptive_sampler = Arc::new(AdaptiveSampler::with_policy(
+            1000,
+            config.sampling_policy.clone(),
+        ));
         let rate_limiter = Arc::new(RateLimiter::new(config.max_brp_requests_per_second));
 
         // Create object pools
@@ -391,6 +724,8 @@ impl ResourceManager {
             timestamp: SystemTime::now(),
             cpu_percent: 0.0,
             memory_bytes: 0,
+            thread_count: None,
+            open_fds: None,
             concurrent_operations: 0,
             brp_requests_per_second: 0,
             circuit_breaker_open: false,
@@ -403,12 +738,12 @@ impl ResourceManager {
         Self {
             config,
             metrics: Arc::new(RwLock::new(initial_metrics)),
-            system: Arc::new(RwLock::new(system)),
-            pid,
+            process: Arc::new(RwLock::new(process)),
             operation_semaphore,
             circuit_breaker,
             adaptive_sampler,
             rate_limiter,
+            tool_latency: Arc::new(ToolLatencyTracker::new()),
             string_pool,
             vec_pool,
             monitoring_handle: None,
@@ -423,8 +758,7 @@ impl ResourceManager {
         self.shutdown_tx = Some(shutdown_tx);
 
         let metrics = self.metrics.clone();
-        let system = self.system.clone();
-        let _pid = self.pid;
+        let process = self.process.clone();
         let config = self.config.clone();
         let circuit_breaker = self.circuit_breaker.clone();
         let adaptive_sampler = self.adaptive_sampler.clone();
@@ -442,7 +776,7 @@ impl ResourceManager {
                     _ = interval.tick() => {
                         Self::update_metrics(
                             &metrics,
-                            &system,
+                            &process,
                             &circuit_breaker,
                             &adaptive_sampler,
                             &rate_limiter,
@@ -467,7 +801,7 @@ impl ResourceManager {
 
     async fn update_metrics(
         metrics: &Arc<RwLock<ResourceMetrics>>,
-        system: &Arc<RwLock<System>>,
+        process: &Arc<RwLock<ProcessSampler>>,
         circuit_breaker: &Arc<CircuitBreaker>,
         adaptive_sampler: &Arc<AdaptiveSampler>,
         rate_limiter: &Arc<RateLimiter>,
@@ -476,39 +810,8 @@ impl ResourceManager {
         total_allocations: &Arc<AtomicU64>,
         total_deallocations: &Arc<AtomicU64>,
     ) {
-        let mut sys = system.write().await;
-        sys.refresh_all();
-
-        // Get current process info using simplified approach
-        let (cpu_percent, memory_bytes) = {
-            // Get basic system info - fallback to process-based approach
-            let _pid = std::process::id();
-            let mut memory = 0u64;
-            let mut cpu = 0.0f32;
-
-            // Try to read from /proc/self/stat on Unix systems for better reliability
-            #[cfg(unix)]
-            {
-                if let Ok(stat) = std::fs::read_to_string("/proc/self/stat") {
-                    let fields: Vec<&str> = stat.split_whitespace().collect();
-                    if fields.len() > 23 {
-                        // Field 22 is memory in pages, convert to bytes (assume 4KB pages)
-                        if let Ok(mem_pages) = fields[22].parse::<u64>() {
-                            memory = mem_pages * 4096;
-                        }
-                        // For CPU, we'll use a simple approximation
-                        cpu = 1.0; // Placeholder for actual CPU calculation
-                    }
-                }
-            }
-
-            // Fallback values if unable to read system info
-            if memory == 0 {
-                memory = 10 * 1024 * 1024; // 10MB default
-            }
-
-            (cpu, memory)
-        };
+        let usage = process.write().await.sample();
+        let (cpu_percent, memory_bytes) = (usage.cpu_percent, usage.rss_bytes);
 
         let current_rate = rate_limiter.get_current_rate().await;
         let sampling_rate = adaptive_sampler.get_sampling_rate().await;
@@ -523,6 +826,8 @@ impl ResourceManager {
             timestamp: SystemTime::now(),
             cpu_percent,
             memory_bytes,
+            thread_count: usage.thread_count,
+            open_fds: usage.open_fds,
             concurrent_operations: 0, // This would be updated by operations
             brp_requests_per_second: current_rate,
             circuit_breaker_open: circuit_breaker.is_open().await,
@@ -567,6 +872,23 @@ impl ResourceManager {
         self.adaptive_sampler.should_sample().await
     }
 
+    /// Current sampling rates, per tool, and the policy producing them
+    pub async fn sampling_report(&self) -> SamplingReport {
+        let recent_usage = self.adaptive_sampler.recent_usage().await;
+        SamplingReport {
+            enabled: self.config.adaptive_sampling_enabled,
+            current_rate: self.adaptive_sampler.get_sampling_rate().await,
+            policy: self.adaptive_sampler.policy().await,
+            recent_cpu_percent: recent_usage.map(|(cpu, _)| cpu),
+            recent_memory_bytes: recent_usage.map(|(_, memory)| memory),
+            tools: self.adaptive_sampler.tool_rates().await,
+        }
+    }
+
+    pub async fn set_sampling_policy(&self, policy: SamplingPolicy) -> Result<()> {
+        self.adaptive_sampler.set_policy(policy).await
+    }
+
     pub async fn acquire_string(&self) -> String {
         if self.config.object_pooling_enabled {
             self.total_allocations.fetch_add(1, Ordering::Relaxed);
@@ -609,6 +931,11 @@ impl ResourceManager {
         self.circuit_breaker.record_failure().await;
     }
 
+    /// Count a finished tool call towards the dashboard's per-tool latencies
+    pub async fn record_tool_call(&self, tool: &str, duration: Duration, failed: bool) {
+        self.tool_latency.record(tool, duration, failed).await;
+    }
+
     pub async fn get_metrics(&self) -> ResourceMetrics {
         let mut metrics = self.metrics.read().await.clone();
         // Update with current circuit breaker state
@@ -619,6 +946,20 @@ impl ResourceManager {
 
     pub async fn get_performance_dashboard(&self) -> serde_json::Value {
         let metrics = self.get_metrics().await;
+        let tools = self.tool_latency.report().await;
+
+        // Slowest first by p95 over 5 minutes, the usual suspects for a slow session
+        let mut slowest: Vec<(&String, f64)> = tools
+            .iter()
+            .map(|(tool, windows)| (tool, windows.get("5m").map_or(0.0, |w| w.p95_ms)))
+            .filter(|(_, p95_ms)| *p95_ms > 0.0)
+            .collect();
+        slowest.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let slowest_tools: Vec<serde_json::Value> = slowest
+            .into_iter()
+            .take(5)
+            .map(|(tool, p95_ms)| serde_json::json!({ "tool": tool, "p95_ms": p95_ms }))
+            .collect();
 
         serde_json::json!({
             "timestamp": metrics.timestamp.duration_since(UNIX_EPOCH)
@@ -647,6 +988,10 @@ impl ResourceManager {
                     "OK"
                 }
             },
+            "process": {
+                "threads": metrics.thread_count,
+                "open_file_descriptors": metrics.open_fds
+            },
             "operations": {
                 "concurrent": metrics.concurrent_operations,
                 "limit": self.config.max_concurrent_operations,
@@ -665,7 +1010,9 @@ impl ResourceManager {
                 "pool_size": metrics.object_pool_size,
                 "total_allocations": metrics.total_allocations,
                 "total_deallocations": metrics.total_deallocations
-            }
+            },
+            "tools": tools,
+            "slowest_tools": slowest_tools
         })
     }
 
@@ -807,6 +1154,42 @@ mod tests {
         assert!(new_rate >= initial_rate);
     }
 
+    #[tokio::test]
+    async fn test_sampling_policy_floor_and_exemptions() {
+        let sampler = AdaptiveSampler::with_policy(
+            100,
+            SamplingPolicy {
+                target_cpu_percent: 5.0,
+                min_sample_rate: 0.5,
+                exempt_tools: ["observe".to_string()].into_iter().collect(),
+                ..Default::default()
+            },
+        );
+
+        // 10% CPU is well over a 5% target, so the rate falls to the floor
+        for _ in 0..100 {
+            sampler.add_sample(10.0, 10 * 1024 * 1024, 5).await;
+        }
+        assert_eq!(sampler.get_sampling_rate().await, 0.5);
+
+        for _ in 0..50 {
+            assert!(with_tool_scope("observe", sampler.should_sample()).await);
+            sampler.should_sample_tool(Some("stress")).await;
+        }
+        let rates = sampler.tool_rates().await;
+        assert_eq!((rates["observe"].rate, rates["observe"].dropped), (1.0, 0));
+        assert_eq!(rates["stress"].rate, 0.5);
+        assert_eq!(rates["stress"].sampled + rates["stress"].dropped, 50);
+
+        assert!(sampler
+            .set_policy(SamplingPolicy {
+                min_sample_rate: 0.0,
+                ..Default::default()
+            })
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_performance_dashboard() {
         let config = ResourceConfig::default();
@@ -820,6 +1203,30 @@ mod tests {
         assert!(dashboard.get("brp_requests").is_some());
     }
 
+    #[tokio::test]
+    async fn test_tool_latency_in_dashboard() {
+        let manager = ResourceManager::new(ResourceConfig::default());
+        for ms in 1..=100 {
+            manager
+                .record_tool_call("observe", Duration::from_millis(ms), ms % 10 == 0)
+                .await;
+        }
+        manager
+            .record_tool_call("health_check", Duration::from_millis(1), false)
+            .await;
+
+        let dashboard = manager.get_performance_dashboard().await;
+        let observe: ToolLatencyStats =
+            serde_json::from_value(dashboard["tools"]["observe"]["1m"].clone()).unwrap();
+        assert_eq!((observe.calls, observe.errors), (100, 10));
+        assert!((observe.error_rate - 0.1).abs() < 1e-9);
+        assert_eq!(
+            (observe.p50_ms, observe.p95_ms, observe.p99_ms),
+            (50.0, 95.0, 99.0)
+        );
+        assert_eq!(dashboard["slowest_tools"][0]["tool"], "observe");
+    }
+
     #[tokio::test]
     async fn test_resource_manager_monitoring() {
         let config = ResourceConfig {
@@ -1,5 +1,6 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::sync::atomic::{AtomicU64, Ordering};
+use bytes::Bytes;
 use tokio::sync::RwLock;
 use serde_json::Value;
 use tracing::{debug, info, warn};
@@ -67,6 +68,50 @@ impl ResponseBuffer {
     }
 }
 
+/// An immutable response shared by the cache and everyone it is handed to
+///
+/// Cloning only bumps reference counts, and the JSON encoding is produced at
+/// most once however many times the response is measured or sent.
+#[derive(Debug, Clone)]
+pub struct SharedResponse {
+    value: Arc<Value>,
+    json: Arc<OnceLock<Bytes>>,
+}
+
+impl SharedResponse {
+    pub fn new(value: Value) -> Self {
+        Self {
+            value: Arc::new(value),
+            json: Arc::new(OnceLock::new()),
+        }
+    }
+    
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+    
+    /// The JSON encoding, serialized on first use
+    pub fn to_bytes(&self) -> Result<Bytes> {
+        if let Some(json) = self.json.get() {
+            return Ok(json.clone());
+        }
+        let json = Bytes::from(serde_json::to_vec(self.value.as_ref())
+            .map_err(|e| crate::error::Error::Validation(format!("Failed to serialize JSON: {}", e)))?);
+        Ok(self.json.get_or_init(|| json).clone())
+    }
+    
+    /// The owned value, copied only if it is still shared
+    pub fn into_value(self) -> Value {
+        Arc::try_unwrap(self.value).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl From<Value> for SharedResponse {
+    fn from(value: Value) -> Self {
+        Self::new(value)
+    }
+}
+
 /// Configuration for response pooling
 #[derive(Debug, Clone)]
 pub struct ResponsePoolConfig {
@@ -439,6 +484,21 @@ mod tests {
         assert_eq!(stats.total_serializations, 11);
     }
     
+    #[test]
+    fn test_shared_response_serializes_once() {
+        let response = SharedResponse::new(json!({"entities": [{"id": 1}]}));
+        let copy = response.clone();
+        
+        let first = response.to_bytes().unwrap();
+        let second = copy.to_bytes().unwrap();
+        assert_eq!(first, Bytes::from_static(br#"{"entities":[{"id":1}]}"#));
+        // Both handles see the same encoded buffer rather than a second copy
+        assert_eq!(first.as_ptr(), second.as_ptr());
+        
+        drop(copy);
+        assert_eq!(response.into_value()["entities"][0]["id"], 1);
+    }
+    
     #[tokio::test]
     async fn test_buffer_type_selection() {
         let config = ResponsePoolConfig::default();
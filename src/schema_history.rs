@@ -0,0 +1,340 @@
+/*
+ * Bevy Debugger MCP Server - Schema History
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Component and resource schemas kept per game build
+//!
+//! A snapshot records the fields and field types of every reflected
+//! component and resource in one build of the game, as one JSON file per
+//! build under `BEVY_MCP_SCHEMA_DIR`. Diffing two snapshots lists the types
+//! and fields that were added, removed or retyped, which is usually why saves
+//! or recordings from an older build stop loading.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::bevy_reflection::type_registry_tools::{cached_type_from_schema, EcsRole};
+use crate::error::{Error, Result};
+
+/// Directory snapshots are kept in unless `BEVY_MCP_SCHEMA_DIR` is set
+pub const DEFAULT_SCHEMA_DIR: &str = "./schemas";
+
+/// Longest accepted build name
+const MAX_BUILD_NAME_LEN: usize = 100;
+
+/// Fields of one reflected type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeSchema {
+    pub role: EcsRole,
+    /// Field type by field name
+    pub fields: BTreeMap<String, String>,
+}
+
+/// The schemas of one build
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaSnapshot {
+    pub build: String,
+    pub captured_at: DateTime<Utc>,
+    pub types: BTreeMap<String, TypeSchema>,
+}
+
+impl SchemaSnapshot {
+    /// Components and resources from a `bevy/registry/schema` response
+    pub fn from_registry(build: &str, schemas: &HashMap<String, Value>) -> Self {
+        let types = schemas
+            .iter()
+            .filter_map(|(type_path, schema)| {
+                let info = cached_type_from_schema(type_path, schema);
+                let role = info.ecs_role.filter(|role| *role != EcsRole::Event)?;
+                let fields = info
+                    .metadata
+                    .fields
+                    .into_iter()
+                    .map(|field| (field.name, field.type_name))
+                    .collect();
+                Some((type_path.clone(), TypeSchema { role, fields }))
+            })
+            .collect();
+        Self {
+            build: build.to_string(),
+            captured_at: Utc::now(),
+            types,
+        }
+    }
+}
+
+/// A stored snapshot, without its types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotSummary {
+    pub build: String,
+    pub captured_at: DateTime<Utc>,
+    pub type_count: usize,
+}
+
+/// A field whose type changed between builds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetypedField {
+    pub field: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// How one type present in both builds changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeChange {
+    pub type_name: String,
+    /// Added fields with their types
+    pub added_fields: BTreeMap<String, String>,
+    /// Removed fields with their old types
+    pub removed_fields: BTreeMap<String, String>,
+    pub retyped_fields: Vec<RetypedField>,
+}
+
+/// Differences between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaDiff {
+    pub from: String,
+    pub to: String,
+    pub added_types: Vec<String>,
+    pub removed_types: Vec<String>,
+    pub changed_types: Vec<TypeChange>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_types.is_empty()
+            && self.removed_types.is_empty()
+            && self.changed_types.is_empty()
+    }
+}
+
+/// Types and fields added, removed or retyped going from `old` to `new`
+pub fn diff(old: &SchemaSnapshot, new: &SchemaSnapshot) -> SchemaDiff {
+    let added_types = new
+        .types
+        .keys()
+        .filter(|name| !old.types.contains_key(*name))
+        .cloned()
+        .collect();
+    let removed_types = old
+        .types
+        .keys()
+        .filter(|name| !new.types.contains_key(*name))
+        .cloned()
+        .collect();
+
+    let changed_types = old
+        .types
+        .iter()
+        .filter_map(|(name, before)| {
+            let after = new.types.get(name)?;
+            let field_diff = |from: &TypeSchema, to: &TypeSchema| -> BTreeMap<String, String> {
+                from.fields
+                    .iter()
+                    .filter(|(field, _)| !to.fields.contains_key(*field))
+                    .map(|(field, ty)| (field.clone(), ty.clone()))
+                    .collect()
+            };
+            let change = TypeChange {
+                type_name: name.clone(),
+                added_fields: field_diff(after, before),
+                removed_fields: field_diff(before, after),
+                retyped_fields: before
+                    .fields
+                    .iter()
+                    .filter_map(|(field, from)| {
+                        let to = after.fields.get(field)?;
+                        (to != from).then(|| RetypedField {
+                            field: field.clone(),
+                            from: from.clone(),
+                            to: to.clone(),
+                        })
+                    })
+                    .collect(),
+            };
+            let changed = !change.added_fields.is_empty()
+                || !change.removed_fields.is_empty()
+                || !change.retyped_fields.is_empty();
+            changed.then_some(change)
+        })
+        .collect();
+
+    SchemaDiff {
+        from: old.build.clone(),
+        to: new.build.clone(),
+        added_types,
+        removed_types,
+        changed_types,
+    }
+}
+
+/// Snapshot files in a directory, one per build
+#[derive(Debug, Clone)]
+pub struct SchemaStore {
+    dir: PathBuf,
+}
+
+impl SchemaStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store in `BEVY_MCP_SCHEMA_DIR`, or [`DEFAULT_SCHEMA_DIR`]
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("BEVY_MCP_SCHEMA_DIR").unwrap_or_else(|_| DEFAULT_SCHEMA_DIR.to_string()),
+        )
+    }
+
+    fn path(&self, build: &str) -> Result<PathBuf> {
+        let valid = !build.is_empty()
+            && build.len() <= MAX_BUILD_NAME_LEN
+            && !build.starts_with('.')
+            && build
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '+'));
+        if !valid {
+            return Err(Error::Validation(format!(
+                "Invalid build name '{build}': use up to {MAX_BUILD_NAME_LEN} letters, digits, '.', '_', '-' or '+'"
+            )));
+        }
+        Ok(self.dir.join(format!("{build}.json")))
+    }
+
+    /// Save `snapshot`, replacing any earlier snapshot of the same build
+    pub fn save(&self, snapshot: &SchemaSnapshot) -> Result<()> {
+        let path = self.path(&snapshot.build)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(path, serde_json::to_vec_pretty(snapshot)?)?;
+        Ok(())
+    }
+
+    pub fn load(&self, build: &str) -> Result<SchemaSnapshot> {
+        let path = self.path(build)?;
+        if !path.exists() {
+            return Err(Error::Validation(format!(
+                "No schema snapshot for build '{build}'"
+            )));
+        }
+        read_snapshot(&path)
+    }
+
+    /// Stored snapshots, oldest first
+    pub fn list(&self) -> Result<Vec<SnapshotSummary>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut summaries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match read_snapshot(&path) {
+                Ok(snapshot) => summaries.push(SnapshotSummary {
+                    build: snapshot.build,
+                    captured_at: snapshot.captured_at,
+                    type_count: snapshot.types.len(),
+                }),
+                Err(e) => warn!(
+                    "Skipping unreadable schema snapshot {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        summaries.sort_by_key(|summary| summary.captured_at);
+        Ok(summaries)
+    }
+}
+
+fn read_snapshot(path: &Path) -> Result<SchemaSnapshot> {
+    Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn registry(health_type: &str, extra_field: bool) -> HashMap<String, Value> {
+        let mut properties =
+            json!({"current": {"type": {"$ref": format!("#/$defs/{health_type}")}}});
+        if extra_field {
+            properties["regen"] = json!({"type": {"$ref": "#/$defs/f32"}});
+        }
+        HashMap::from([
+            (
+                "game::Health".to_string(),
+                json!({"kind": "Struct", "reflectTypes": ["Component"], "properties": properties}),
+            ),
+            (
+                "game::Score".to_string(),
+                json!({"kind": "Struct", "reflectTypes": ["Resource"], "properties": {}}),
+            ),
+            (
+                "glam::Vec3".to_string(),
+                json!({"kind": "Struct", "reflectTypes": ["Default"], "properties": {}}),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_snapshots_round_trip_and_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SchemaStore::new(dir.path());
+
+        let old = SchemaSnapshot::from_registry("1.0.0", &registry("u32", false));
+        assert_eq!(old.types.len(), 2);
+        store.save(&old).unwrap();
+        let mut new_registry = registry("f32", true);
+        new_registry.remove("game::Score");
+        new_registry.insert(
+            "game::Mana".to_string(),
+            json!({"kind": "Struct", "reflectTypes": ["Component"]}),
+        );
+        store
+            .save(&SchemaSnapshot::from_registry("1.1.0", &new_registry))
+            .unwrap();
+
+        assert_eq!(store.list().unwrap().len(), 2);
+        let changes = diff(&store.load("1.0.0").unwrap(), &store.load("1.1.0").unwrap());
+        assert_eq!(changes.added_types, vec!["game::Mana"]);
+        assert_eq!(changes.removed_types, vec!["game::Score"]);
+        assert_eq!(changes.changed_types.len(), 1);
+        let health = &changes.changed_types[0];
+        assert_eq!(
+            health.added_fields.get("regen").map(String::as_str),
+            Some("f32")
+        );
+        assert_eq!(
+            health.retyped_fields,
+            vec![RetypedField {
+                field: "current".to_string(),
+                from: "u32".to_string(),
+                to: "f32".to_string(),
+            }]
+        );
+
+        assert!(store.load("../etc/passwd").is_err());
+        assert!(diff(&old, &old).is_empty());
+    }
+}
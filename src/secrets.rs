@@ -0,0 +1,327 @@
+/*
+ * Bevy Debugger MCP Server - Secret Providers
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::env;
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+
+/// Keyring service name secrets are stored under
+pub const KEYRING_SERVICE: &str = "bevy-debugger-mcp";
+
+/// Placeholder secrets shipped in examples and old configs; never valid in production
+const DEFAULT_SECRET_MARKERS: &[&str] = &[
+    "change_in_production",
+    "changeme",
+    "change-me",
+    "your-secret",
+    "your_secret",
+    "default_secret",
+    "default-secret",
+];
+
+/// Whether `secret` is a known placeholder rather than a real secret
+pub fn is_default_secret(secret: &str) -> bool {
+    let lowered = secret.to_lowercase();
+    DEFAULT_SECRET_MARKERS
+        .iter()
+        .any(|marker| lowered.contains(marker))
+}
+
+/// A place secrets can be read from
+///
+/// Keys are upper case names such as `JWT_SECRET`; each provider maps them to
+/// its own naming scheme.
+pub trait SecretProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// The secret, or `None` when this provider does not have it
+    fn get(&self, key: &str) -> Result<Option<String>>;
+}
+
+/// `BEVY_MCP_<KEY>` environment variables
+#[derive(Debug, Default)]
+pub struct EnvSecretProvider;
+
+impl SecretProvider for EnvSecretProvider {
+    fn name(&self) -> &'static str {
+        "env"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(env::var(format!("BEVY_MCP_{}", key)).ok())
+    }
+}
+
+/// Files named by `BEVY_MCP_<KEY>_FILE`, as mounted by Docker and Kubernetes secrets
+#[derive(Debug, Default)]
+pub struct FileSecretProvider;
+
+impl SecretProvider for FileSecretProvider {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let Ok(path) = env::var(format!("BEVY_MCP_{}_FILE", key)) else {
+            return Ok(None);
+        };
+        let contents = std::fs::read_to_string(PathBuf::from(&path)).map_err(|e| {
+            Error::SecurityError(format!("Failed to read secret file {}: {}", path, e))
+        })?;
+        Ok(Some(contents.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+/// The OS keyring (macOS Keychain, Windows Credential Manager, Secret Service)
+///
+/// Entries live under the [`KEYRING_SERVICE`] service with the lower case key
+/// as the account name.
+#[cfg(feature = "os-keyring")]
+#[derive(Debug, Default)]
+pub struct KeyringSecretProvider;
+
+#[cfg(feature = "os-keyring")]
+impl SecretProvider for KeyringSecretProvider {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, &key.to_lowercase())
+            .map_err(|e| Error::SecurityError(format!("Keyring unavailable: {}", e)))?;
+        match entry.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(Error::SecurityError(format!(
+                "Failed to read {} from keyring: {}",
+                key, e
+            ))),
+        }
+    }
+}
+
+/// A HashiCorp Vault KV secret, read once per key over HTTP
+///
+/// Fields are the lower case key names, e.g. `jwt_secret`. Both KV v1 and v2
+/// response layouts are understood.
+#[derive(Clone)]
+pub struct VaultSecretProvider {
+    /// e.g. `https://vault.internal:8200`
+    pub address: String,
+    /// API path of the secret, e.g. `secret/data/bevy-debugger`
+    pub path: String,
+    pub token: String,
+}
+
+impl VaultSecretProvider {
+    /// From `BEVY_MCP_VAULT_ADDR`, `BEVY_MCP_VAULT_PATH` and `BEVY_MCP_VAULT_TOKEN`
+    pub fn from_env() -> Result<Self> {
+        let required = |name: &str| {
+            env::var(name).map_err(|_| {
+                Error::SecurityError(format!("{} is required for the vault provider", name))
+            })
+        };
+        Ok(Self {
+            address: required("BEVY_MCP_VAULT_ADDR")?
+                .trim_end_matches('/')
+                .to_string(),
+            path: required("BEVY_MCP_VAULT_PATH")?
+                .trim_matches('/')
+                .to_string(),
+            token: required("BEVY_MCP_VAULT_TOKEN")?,
+        })
+    }
+
+    /// `key` from a KV v1 or v2 read response
+    pub fn field(response: &serde_json::Value, key: &str) -> Option<String> {
+        let data = &response["data"];
+        // KV v2 nests the secret one level deeper
+        data["data"]
+            .get(key)
+            .or_else(|| data.get(key))
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+    }
+}
+
+#[cfg(feature = "vault")]
+impl SecretProvider for VaultSecretProvider {
+    fn name(&self) -> &'static str {
+        "vault"
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let url = format!("{}/v1/{}", self.address, self.path);
+        let token = self.token.clone();
+        // Configuration is loaded synchronously, possibly on a runtime thread,
+        // so the request runs on its own thread and runtime
+        let response = std::thread::spawn(move || -> Result<serde_json::Value> {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            runtime.block_on(async {
+                let response = reqwest::Client::new()
+                    .get(&url)
+                    .header("X-Vault-Token", token)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| Error::SecurityError(format!("Vault request failed: {}", e)))?;
+                response
+                    .json()
+                    .await
+                    .map_err(|e| Error::SecurityError(format!("Invalid Vault response: {}", e)))
+            })
+        })
+        .join()
+        .map_err(|_| Error::SecurityError("Vault request thread panicked".to_string()))??;
+
+        Ok(Self::field(&response, &key.to_lowercase()))
+    }
+}
+
+/// Providers consulted in order; the first one with a secret wins
+pub struct SecretResolver {
+    providers: Vec<Box<dyn SecretProvider>>,
+}
+
+impl std::fmt::Debug for SecretResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretResolver")
+            .field("providers", &self.provider_names())
+            .finish()
+    }
+}
+
+impl SecretResolver {
+    pub fn new(providers: Vec<Box<dyn SecretProvider>>) -> Self {
+        Self { providers }
+    }
+
+    /// Environment and files first, then `BEVY_MCP_SECRETS_PROVIDERS` (e.g. "keyring,vault")
+    pub fn from_env() -> Result<Self> {
+        let mut providers: Vec<Box<dyn SecretProvider>> =
+            vec![Box::new(EnvSecretProvider), Box::new(FileSecretProvider)];
+        if let Ok(extra) = env::var("BEVY_MCP_SECRETS_PROVIDERS") {
+            for name in extra.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                providers.push(external_provider(name)?);
+            }
+        }
+        Ok(Self::new(providers))
+    }
+
+    pub fn provider_names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    /// Look `key` up in each provider in turn
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        for provider in &self.providers {
+            if let Some(secret) = provider.get(key)? {
+                if !secret.is_empty() {
+                    return Ok(Some(secret));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn external_provider(name: &str) -> Result<Box<dyn SecretProvider>> {
+    match name.to_lowercase().as_str() {
+        "env" => Ok(Box::new(EnvSecretProvider)),
+        "file" => Ok(Box::new(FileSecretProvider)),
+        #[cfg(feature = "os-keyring")]
+        "keyring" => Ok(Box::new(KeyringSecretProvider)),
+        #[cfg(not(feature = "os-keyring"))]
+        "keyring" => Err(Error::SecurityError(
+            "The keyring secrets provider requires the os-keyring feature".to_string(),
+        )),
+        #[cfg(feature = "vault")]
+        "vault" => Ok(Box::new(VaultSecretProvider::from_env()?)),
+        #[cfg(not(feature = "vault"))]
+        "vault" => Err(Error::SecurityError(
+            "The vault secrets provider requires the vault feature".to_string(),
+        )),
+        other => Err(Error::SecurityError(format!(
+            "Unknown secrets provider: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MapProvider(&'static str, HashMap<&'static str, &'static str>);
+
+    impl SecretProvider for MapProvider {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn get(&self, key: &str) -> Result<Option<String>> {
+            Ok(self.1.get(key).map(|s| s.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_first_provider_with_secret_wins() {
+        let resolver = SecretResolver::new(vec![
+            Box::new(MapProvider("first", HashMap::from([("JWT_SECRET", "")]))),
+            Box::new(MapProvider(
+                "second",
+                HashMap::from([("JWT_SECRET", "from-second"), ("USER_STORE_KEY", "key")]),
+            )),
+            Box::new(MapProvider(
+                "third",
+                HashMap::from([("JWT_SECRET", "from-third")]),
+            )),
+        ]);
+        assert_eq!(
+            resolver.get("JWT_SECRET").unwrap().as_deref(),
+            Some("from-second")
+        );
+        assert_eq!(resolver.get("AUDIT_HTTP_TOKEN").unwrap(), None);
+        assert_eq!(resolver.provider_names(), vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn test_vault_kv_layouts_and_default_secrets() {
+        let v2 = serde_json::json!({"data": {"data": {"jwt_secret": "v2"}, "metadata": {}}});
+        let v1 = serde_json::json!({"data": {"jwt_secret": "v1"}});
+        assert_eq!(
+            VaultSecretProvider::field(&v2, "jwt_secret").as_deref(),
+            Some("v2")
+        );
+        assert_eq!(
+            VaultSecretProvider::field(&v1, "jwt_secret").as_deref(),
+            Some("v1")
+        );
+        assert_eq!(VaultSecretProvider::field(&v1, "user_store_key"), None);
+
+        assert!(is_default_secret(
+            "default_jwt_secret_change_in_production_0123456789"
+        ));
+        assert!(is_default_secret("CHANGEME-CHANGEME-CHANGEME-CHANGEME"));
+        assert!(!is_default_secret("kq3Zr8pW1vN5xT0yB7mC4hJ9sL2dF6gA"));
+    }
+}
@@ -16,18 +16,24 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use rmcp::{model::*, tool, tool_router, tool_handler, handler::server::{ServerHandler, router::tool::ToolRouter, tool::Parameters}, schemars, Error as McpError};
+use rmcp::{model::*, tool, tool_router, handler::server::{ServerHandler, router::tool::ToolRouter, tool::Parameters}, schemars, Error as McpError};
+use rmcp::handler::server::tool::ToolCallContext;
+use rmcp::service::{NotificationContext, RequestContext};
+use rmcp::RoleServer;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{sync::Arc, future::Future};
+use std::{net::IpAddr, sync::Arc, future::Future};
 use tokio::sync::RwLock;
 use tracing::{error, info, debug, warn};
 use schemars::JsonSchema;
 
+use crate::alerting::{self, AlertState};
 use crate::brp_client::BrpClient;
-use crate::tools::{observe, experiment, hypothesis, anomaly, stress, replay};
+use crate::mcp_server::McpServer;
+use crate::tools::{observe, experiment, hypothesis, mutate, anomaly, stress, replay, schema_history, search_world, types};
 use crate::security::{SecurityManager, SecurityMiddleware, Role, Claims, SecurityAudit};
 use crate::error::{Error, Result};
+use crate::error_codes::{self, ErrorCategory, ErrorCode};
 
 // Re-export parameter structures from the original tools
 pub use crate::mcp_tools::{
@@ -40,13 +46,22 @@ pub use crate::mcp_tools::{
 pub struct AuthRequest {
     pub username: String,
     pub password: String,
+    /// TOTP or recovery code, for accounts with two-factor authentication
+    pub otp_code: Option<String>,
+    /// Replace the password while logging in; required once it expired or must be changed
+    pub new_password: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenewTokenRequest {
+    pub refresh_token: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateUserRequest {
     pub username: String,
     pub password: String,
-    pub role: String, // "viewer", "developer", or "admin"
+    pub role: String, // "guest", "viewer", "developer", or "admin"
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -54,6 +69,98 @@ pub struct DeleteUserRequest {
     pub username: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub role: String, // "guest", "viewer", "developer", or "admin"
+    /// Operations the key may call; all operations allowed by the role when empty
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RevokeApiKeyRequest {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PolicyCheckRequest {
+    /// Tool operation to check, e.g. "experiment"
+    pub operation: String,
+    pub role: String, // "guest", "viewer", "developer", or "admin"
+    /// User id, for rules that allow specific users
+    pub user_id: Option<String>,
+    /// Re-read the policy file before checking
+    #[serde(default)]
+    pub reload: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConfirmTwoFactorRequest {
+    /// Current code from the authenticator app
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DisableTwoFactorRequest {
+    /// Defaults to your own account; disabling another user's requires Admin role
+    pub username: Option<String>,
+    /// Current authenticator or recovery code, required for your own account
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListSessionsRequest {
+    /// Only sessions of this user
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RevokeSessionRequest {
+    /// End this one session
+    pub session_id: Option<String>,
+    /// End every session of this user
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SecurityToolRequest {
+    /// "scan", "metrics" or "audit_events"
+    pub action: String,
+    /// Most recent audit events to return (default 50)
+    pub limit: Option<usize>,
+    /// Only audit events with this action, e.g. "authentication"
+    pub event_action: Option<String>,
+    /// Only failed audit events
+    #[serde(default)]
+    pub failures_only: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SigningKeysRequest {
+    /// "list" (default) or "rotate"
+    #[serde(default = "default_signing_keys_action")]
+    pub action: String,
+}
+
+fn default_signing_keys_action() -> String {
+    "list".to_string()
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RateLimitStatusRequest {
+    /// Re-read the rate limits file first (requires Admin role)
+    #[serde(default)]
+    pub reload: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListErrorCodesRequest {
+    /// Only codes in this category, such as "connection" or "security"
+    pub category: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AuditLogRequest {
     pub limit: Option<usize>,
@@ -63,7 +170,8 @@ pub struct AuditLogRequest {
 #[derive(Debug, Serialize)]
 pub struct AuthResponse {
     pub token: String,
-    pub role: String,
+    pub refresh_token: String,
+    pub role: Role,
     pub expires_in: u64,
 }
 
@@ -71,29 +179,58 @@ pub struct AuthResponse {
 #[derive(Clone)]
 pub struct SecureMcpTools {
     brp_client: Arc<RwLock<BrpClient>>,
+    /// Runs the tools that have no handler of their own here, once authorized
+    tool_server: McpServer,
     security_manager: Arc<SecurityManager>,
     security_middleware: SecurityMiddleware,
     security_audit: SecurityAudit,
+    /// Token of the connection's client certificate session, used when a call carries none
+    session_token: Option<String>,
+    /// Peer address of the connection, for IP allow lists and login throttling
+    client_address: Option<IpAddr>,
     tool_router: ToolRouter<Self>,
 }
 
 impl SecureMcpTools {
     pub fn new(
-        brp_client: Arc<RwLock<BrpClient>>, 
+        tool_server: McpServer,
         security_manager: Arc<SecurityManager>
     ) -> Self {
         let security_middleware = SecurityMiddleware::new(security_manager.clone());
         let security_audit = SecurityAudit::new(security_manager.clone());
         
         Self { 
-            brp_client,
+            brp_client: tool_server.brp_client(),
+            tool_server,
             security_manager: security_manager.clone(),
             security_middleware,
             security_audit,
+            session_token: None,
+            client_address: None,
             tool_router: Self::tool_router(),
         }
     }
 
+    /// Tools for a connection authenticated by client certificate
+    pub fn with_session_token(&self, token: String) -> Self {
+        Self {
+            session_token: Some(token),
+            ..self.clone()
+        }
+    }
+
+    /// Tools for a connection from `address`
+    pub fn with_client_address(&self, address: IpAddr) -> Self {
+        Self {
+            client_address: Some(address),
+            ..self.clone()
+        }
+    }
+
+    fn client_ip(&self) -> Option<String> {
+        self.client_address.map(|ip| ip.to_string())
+    }
+
     /// Extract JWT token from request headers or parameters
     fn extract_token_from_request(params: &Value) -> Option<String> {
         // Check if token is provided in parameters
@@ -111,44 +248,91 @@ impl SecureMcpTools {
         None
     }
 
+    /// Drop the auth parameters so they never reach the underlying tool
+    fn strip_auth(req: &mut Value) {
+        if let Some(obj) = req.as_object_mut() {
+            obj.remove("auth_token");
+            obj.remove("authorization");
+        }
+    }
+
+    /// Token for a call: the request's own, else the connection's certificate session
+    fn request_token(&self, params: &Value) -> Option<String> {
+        Self::extract_token_from_request(params).or_else(|| self.session_token.clone())
+    }
+
     /// Validate and authorize a tool call
     async fn authorize_tool_call(&self, operation: &str, params: &Value) -> Result<Claims> {
-        let token = Self::extract_token_from_request(params)
+        let token = self.request_token(params)
             .ok_or_else(|| Error::SecurityError("Authentication required".to_string()))?;
         
         self.security_middleware.authorize_tool_call(Some(&token), operation).await
     }
 
+    /// Authorize a call and run it on the tool server
+    async fn call_tool_server(&self, operation: &str, mut params: Value) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call(operation, &params).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure(operation, &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        Self::strip_auth(&mut params);
+
+        match self.tool_server.handle_tool_call(operation, params).await {
+            Ok(result) => {
+                self.log_tool_success(&claims, operation, None).await;
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
+            }
+            Err(e) => {
+                error!("{} tool error for user {}: {}", operation, claims.sub, e);
+                self.log_tool_failure(operation, &e.to_string()).await;
+                Err(McpError::internal_error(format!("{} tool error: {}", operation, e), Some(e.data())))
+            }
+        }
+    }
+
     /// Log a successful tool operation
     async fn log_tool_success(&self, claims: &Claims, operation: &str, resource: Option<&str>) {
         // This would typically be handled by the security manager's audit logging
-        debug!("Tool operation successful: {} by user {}", operation, claims.sub);
+        debug!(
+            tool = operation,
+            user = %claims.sub,
+            session = %claims.session_id,
+            status = "ok",
+            "Tool operation successful"
+        );
     }
 
     /// Log a failed tool operation
     async fn log_tool_failure(&self, operation: &str, error: &str) {
-        warn!("Tool operation failed: {} - {}", operation, error);
+        warn!(tool = operation, status = "error", error, "Tool operation failed");
     }
 }
 
 #[tool_router]
 impl SecureMcpTools {
     /// Authenticate user and return JWT token
-    #[tool(description = "Authenticate with username and password to get a JWT token for accessing debugging tools. Returns a token that must be included in subsequent requests.")]
+    #[tool(description = "Authenticate with username and password to get a JWT token for accessing debugging tools. Accounts with two-factor authentication must also pass otp_code (an authenticator or recovery code). Pass new_password to change the password; this is required when it expired or must be changed on first login. Returns a token that must be included in subsequent requests.")]
     pub async fn authenticate(&self, Parameters(req): Parameters<AuthRequest>) -> std::result::Result<CallToolResult, McpError> {
         info!("Authentication attempt for user: {}", req.username);
         
-        match self.security_manager.authenticate(
+        match self.security_manager.authenticate_with_refresh(
             &req.username, 
             &req.password,
-            None, // IP address - could be extracted from request context
+            req.otp_code.as_deref(),
+            req.new_password.as_deref(),
+            self.client_ip(),
             None, // User agent - could be extracted from request context
         ).await {
-            Ok(token) => {
+            Ok(pair) => {
                 let response = AuthResponse {
-                    token: token.clone(),
-                    role: "authenticated".to_string(), // Could decode role from token
-                    expires_in: 24 * 3600, // Default 24 hours
+                    token: pair.access_token,
+                    refresh_token: pair.refresh_token,
+                    role: pair.role,
+                    expires_in: pair.expires_in,
                 };
                 
                 Ok(CallToolResult::success(vec![
@@ -157,16 +341,53 @@ impl SecureMcpTools {
             }
             Err(e) => {
                 error!("Authentication failed for {}: {}", req.username, e);
-                Err(McpError::invalid_params(format!("Authentication failed: {}", e), None))
+                Err(McpError::invalid_params(format!("Authentication failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Exchange a refresh token for a new token pair
+    #[tool(description = "Renew an expiring JWT token using the refresh token returned by authenticate. Returns a new token and a new refresh token; each refresh token works only once.")]
+    pub async fn renew_token(&self, Parameters(req): Parameters<RenewTokenRequest>) -> std::result::Result<CallToolResult, McpError> {
+        match self.security_manager.renew_token(&req.refresh_token, self.client_ip()).await {
+            Ok(pair) => {
+                let response = AuthResponse {
+                    token: pair.access_token,
+                    refresh_token: pair.refresh_token,
+                    role: pair.role,
+                    expires_in: pair.expires_in,
+                };
+                
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string(&response).unwrap())
+                ]))
+            }
+            Err(e) => {
+                warn!("Token renewal failed: {}", e);
+                Err(McpError::invalid_params(format!("Token renewal failed: {}", e), Some(e.data())))
             }
         }
     }
 
+    /// Document the error codes carried by tool errors
+    #[tool(description = "List the machine-readable error codes found in the data of tool errors, with each code's category, whether retrying may succeed and a suggested action. Optionally filter by category. No authentication required.")]
+    pub async fn list_error_codes(&self, Parameters(req): Parameters<ListErrorCodesRequest>) -> std::result::Result<CallToolResult, McpError> {
+        let category = match req.category {
+            Some(category) => Some(
+                serde_json::from_value::<ErrorCategory>(Value::String(category))
+                    .map_err(|e| McpError::invalid_params(format!("Invalid category: {}", e), Some(ErrorCode::InvalidInput.data())))?,
+            ),
+            None => None,
+        };
+        let codes = serde_json::json!({ "error_codes": error_codes::list(category) });
+        Ok(CallToolResult::success(vec![Content::text(codes.to_string())]))
+    }
+
     /// Revoke JWT token (logout)
     #[tool(description = "Revoke your JWT token to log out. This will invalidate the token and end your session.")]
     pub async fn logout(&self, Parameters(params): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
-        let token = Self::extract_token_from_request(&params)
-            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), None))?;
+        let token = self.request_token(&params)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
         
         match self.security_manager.revoke_token(&token).await {
             Ok(_) => {
@@ -175,30 +396,120 @@ impl SecureMcpTools {
             }
             Err(e) => {
                 error!("Token revocation failed: {}", e);
-                Err(McpError::internal_error(format!("Logout failed: {}", e), None))
+                Err(McpError::internal_error(format!("Logout failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Start two-factor enrollment for your account (Admin and Developer only)
+    #[tool(description = "Start TOTP two-factor enrollment for your own account. Returns the secret, an otpauth:// URI for authenticator apps and single-use recovery codes, shown only once. Call confirm_two_factor with a code to turn it on. Admin and Developer accounts only.")]
+    pub async fn enroll_two_factor(&self, Parameters(params): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let token = self.request_token(&params)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        match self.security_manager.enroll_two_factor(&token).await {
+            Ok(enrollment) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&enrollment).unwrap())
+                ]))
+            }
+            Err(e) => {
+                self.log_tool_failure("enroll_two_factor", &e.to_string()).await;
+                Err(McpError::invalid_params(format!("Two-factor enrollment failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Turn on two-factor authentication after enrollment
+    #[tool(description = "Confirm two-factor enrollment with the current code from your authenticator app. From then on authenticate requires otp_code.")]
+    pub async fn confirm_two_factor(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let confirm_req: ConfirmTwoFactorRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid two-factor parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        match self.security_manager.confirm_two_factor(&token, &confirm_req.code).await {
+            Ok(_) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text("Two-factor authentication enabled".to_string())
+                ]))
+            }
+            Err(e) => {
+                self.log_tool_failure("confirm_two_factor", &e.to_string()).await;
+                Err(McpError::invalid_params(format!("Two-factor confirmation failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Turn off two-factor authentication
+    #[tool(description = "Disable two-factor authentication for your own account (pass a current code or recovery code as code), or for another user with Admin role (e.g. when they lost their authenticator and recovery codes).")]
+    pub async fn disable_two_factor(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+        let claims = self.security_manager.validate_token(&token).await
+            .map_err(|e| McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let disable_req: DisableTwoFactorRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid two-factor parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+        let username = disable_req.username.unwrap_or(claims.sub);
+
+        match self.security_manager.disable_two_factor(&token, &username, disable_req.code.as_deref()).await {
+            Ok(_) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(format!("Two-factor authentication disabled for {}", username))
+                ]))
+            }
+            Err(e) => {
+                self.log_tool_failure("disable_two_factor", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Disabling two-factor authentication failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Show the caller's remaining rate limit quota
+    #[tool(description = "Show your rate limits and remaining quota: the limit for your role across all tools (scope \"*\") and any per-tool limits. Admins can pass reload=true to re-read the rate limits file first.")]
+    pub async fn rate_limit_status(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let status_req: RateLimitStatusRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid rate limit status parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        match self.security_manager.rate_limit_status(&token, status_req.reload).await {
+            Ok(status) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&status).unwrap())
+                ]))
+            }
+            Err(e) => {
+                self.log_tool_failure("rate_limit_status", &e.to_string()).await;
+                Err(McpError::invalid_params(format!("Rate limit status failed: {}", e), Some(e.data())))
             }
         }
     }
 
-    /// Observe and query Bevy game state (requires Viewer role or higher)
-    #[tool(description = "Observe and query Bevy game state in real-time with optional reflection-based component inspection. Requires authentication token and Viewer role or higher.")]
+    /// Observe and query Bevy game state (requires Guest role or higher)
+    #[tool(description = "Observe and query Bevy game state in real-time with optional reflection-based component inspection. Requires authentication token and Guest role or higher; guests see configured component fields redacted.")]
     pub async fn observe(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
         let claims = match self.authorize_tool_call("observe", &req).await {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("observe", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        // Remove auth parameters before passing to the actual tool
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let observe_req: ObserveRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid observe parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid observe parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         debug!("User {} executing observe query: {}", claims.sub, observe_req.query);
         
@@ -207,17 +518,101 @@ impl SecureMcpTools {
             "diff": observe_req.diff,
             "detailed": observe_req.detailed,
             "reflection": observe_req.reflection,
+            "include": observe_req.include,
+            "delta": observe_req.delta,
+            "session_id": claims.session_id,
         });
         
         match observe::handle(arguments, self.brp_client.clone()).await {
             Ok(result) => {
                 self.log_tool_success(&claims, "observe", Some(&observe_req.query)).await;
-                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
             }
             Err(e) => {
                 error!("Observe tool error for user {}: {}", claims.sub, e);
                 self.log_tool_failure("observe", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Observe tool error: {}", e), None))
+                Err(McpError::internal_error(format!("Observe tool error: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Search reflected types in the game (requires Guest role or higher)
+    #[tool(description = "List and search the reflected types registered in the connected game, with each type's category (component, resource, event or type), reflection kind, fields and docs where available. search matches names by substring or fuzzily (e.g. \"gtrans\" finds GlobalTransform) and docs by substring; category filters by component, resource or event; limit defaults to 50. Pass refresh=true after the game registers new types. Requires authentication token and Guest role or higher.")]
+    pub async fn types(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("types", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("types", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        Self::strip_auth(&mut req);
+
+        match types::handle(req, self.brp_client.clone()).await {
+            Ok(result) => {
+                self.log_tool_success(&claims, "types", None).await;
+                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+            }
+            Err(e) => {
+                error!("Types tool error for user {}: {}", claims.sub, e);
+                self.log_tool_failure("types", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Types tool error: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Keep and diff component schemas per game build (requires Developer role or higher)
+    #[tool(description = "Track the game's component and resource schemas across builds. action=\"capture\" stores the running game's schemas under build (e.g. \"1.4.2\"); action=\"list\" shows stored builds; action=\"diff\" lists types and fields added, removed or retyped from build from to build to, or to the running game when to is omitted. Requires authentication token and Developer role or higher.")]
+    pub async fn schema_history(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("schema_history", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("schema_history", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        Self::strip_auth(&mut req);
+        let action = req.get("action").and_then(|a| a.as_str()).unwrap_or("list").to_string();
+
+        match schema_history::handle(req, self.brp_client.clone()).await {
+            Ok(result) => {
+                self.log_tool_success(&claims, "schema_history", Some(&action)).await;
+                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+            }
+            Err(e) => {
+                error!("Schema history tool error for user {}: {}", claims.sub, e);
+                self.log_tool_failure("schema_history", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Schema history tool error: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Find where a value appears across entity components (requires Guest role or higher)
+    #[tool(description = "Search every entity's component values for a value and return each entity, component and field path where it appears. pattern is text (case-insensitive substring, e.g. \"Goblin\"), /regex/, or a numeric comparison such as \"> 1e6\" or \"== 0\"; pass value instead to match an exact JSON value. components limits the search to component types containing any of the given names; limit caps matches (default 100) and max_entities caps entities scanned (default 1000), sampling evenly across larger worlds. Requires authentication token and Guest role or higher.")]
+    pub async fn search_world(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("search_world", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("search_world", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        Self::strip_auth(&mut req);
+
+        // Redact before searching: hidden values must not be returned or matched
+        let redaction = self.security_manager.redaction_for_role(&claims.role);
+        match search_world::handle_redacted(req, self.brp_client.clone(), redaction).await {
+            Ok(result) => {
+                self.log_tool_success(&claims, "search_world", None).await;
+                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+            }
+            Err(e) => {
+                error!("Search world tool error for user {}: {}", claims.sub, e);
+                self.log_tool_failure("search_world", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Search world tool error: {}", e), Some(e.data())))
             }
         }
     }
@@ -229,18 +624,14 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("experiment", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        // Remove auth parameters
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let exp_req: ExperimentRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid experiment parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid experiment parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         debug!("User {} running experiment: {}", claims.sub, exp_req.experiment_type);
         
@@ -253,12 +644,39 @@ impl SecureMcpTools {
         match experiment::handle(arguments, self.brp_client.clone()).await {
             Ok(result) => {
                 self.log_tool_success(&claims, "experiment", Some(&exp_req.experiment_type)).await;
-                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
             }
             Err(e) => {
                 error!("Experiment tool error for user {}: {}", claims.sub, e);
                 self.log_tool_failure("experiment", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Experiment tool error: {}", e), None))
+                Err(McpError::internal_error(format!("Experiment tool error: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Change a component field after schema validation (requires Developer role or higher)
+    #[tool(description = "Change one component field (path such as \"translation.x\") or a whole component on an entity. The value is validated against the component's reflected schema first; invalid field paths, types or out-of-range values are returned as errors with the expected value and nothing is sent to the game. Pass dry_run=true to validate only. Requires authentication token and Developer role or higher.")]
+    pub async fn mutate(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("mutate", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("mutate", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        Self::strip_auth(&mut req);
+        let component = req.get("component").and_then(|c| c.as_str()).unwrap_or_default().to_string();
+
+        match mutate::handle(req, self.brp_client.clone()).await {
+            Ok(result) => {
+                self.log_tool_success(&claims, "mutate", Some(&component)).await;
+                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+            }
+            Err(e) => {
+                error!("Mutate tool error for user {}: {}", claims.sub, e);
+                self.log_tool_failure("mutate", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Mutate tool error: {}", e), Some(e.data())))
             }
         }
     }
@@ -270,17 +688,14 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("hypothesis", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let hyp_req: HypothesisRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid hypothesis parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid hypothesis parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         debug!("User {} testing hypothesis: {}", claims.sub, hyp_req.hypothesis);
         
@@ -293,34 +708,31 @@ impl SecureMcpTools {
         match hypothesis::handle(arguments, self.brp_client.clone()).await {
             Ok(result) => {
                 self.log_tool_success(&claims, "hypothesis", Some(&hyp_req.hypothesis)).await;
-                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
             }
             Err(e) => {
                 error!("Hypothesis tool error for user {}: {}", claims.sub, e);
                 self.log_tool_failure("hypothesis", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Hypothesis tool error: {}", e), None))
+                Err(McpError::internal_error(format!("Hypothesis tool error: {}", e), Some(e.data())))
             }
         }
     }
 
-    /// Detect anomalies in game behavior (requires Viewer role or higher)
-    #[tool(description = "Detect anomalies in game behavior, performance, and state. Requires authentication token and Viewer role or higher.")]
+    /// Detect anomalies in game behavior (requires Guest role or higher)
+    #[tool(description = "Detect anomalies in game behavior, performance, and state. Requires authentication token and Guest role or higher.")]
     pub async fn detect_anomaly(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
         let claims = match self.authorize_tool_call("detect_anomaly", &req).await {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("detect_anomaly", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let anom_req: AnomalyRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid anomaly detection parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid anomaly detection parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         debug!("User {} running anomaly detection: {}", claims.sub, anom_req.detection_type);
         
@@ -333,12 +745,12 @@ impl SecureMcpTools {
         match anomaly::handle(arguments, self.brp_client.clone()).await {
             Ok(result) => {
                 self.log_tool_success(&claims, "detect_anomaly", Some(&anom_req.detection_type)).await;
-                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
             }
             Err(e) => {
                 error!("Anomaly detection error for user {}: {}", claims.sub, e);
                 self.log_tool_failure("detect_anomaly", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Anomaly detection error: {}", e), None))
+                Err(McpError::internal_error(format!("Anomaly detection error: {}", e), Some(e.data())))
             }
         }
     }
@@ -350,17 +762,14 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("stress_test", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let stress_req: StressTestRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid stress test parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid stress test parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         info!("User {} starting stress test: {} at intensity {}", claims.sub, stress_req.test_type, stress_req.intensity);
         
@@ -374,12 +783,12 @@ impl SecureMcpTools {
         match stress::handle(arguments, self.brp_client.clone()).await {
             Ok(result) => {
                 self.log_tool_success(&claims, "stress_test", Some(&stress_req.test_type)).await;
-                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
             }
             Err(e) => {
                 error!("Stress test error for user {}: {}", claims.sub, e);
                 self.log_tool_failure("stress_test", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Stress test error: {}", e), None))
+                Err(McpError::internal_error(format!("Stress test error: {}", e), Some(e.data())))
             }
         }
     }
@@ -391,17 +800,14 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("time_travel_replay", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let replay_req: ReplayRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid replay parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid replay parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         info!("User {} executing time travel replay: {}", claims.sub, replay_req.action);
         
@@ -414,16 +820,220 @@ impl SecureMcpTools {
         match replay::handle(arguments, self.brp_client.clone()).await {
             Ok(result) => {
                 self.log_tool_success(&claims, "time_travel_replay", Some(&replay_req.action)).await;
-                Ok(CallToolResult::success(vec![Content::text(result.to_string())]))
+                Ok(CallToolResult::success(vec![Content::text(self.security_manager.redact_for_role(&claims.role, result).to_string())]))
             }
             Err(e) => {
                 error!("Replay tool error for user {}: {}", claims.sub, e);
                 self.log_tool_failure("time_travel_replay", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Replay tool error: {}", e), None))
+                Err(McpError::internal_error(format!("Replay tool error: {}", e), Some(e.data())))
             }
         }
     }
 
+    /// Take a screenshot of the game window (requires Developer role or higher)
+    #[tool(description = "Capture a screenshot of the game window to path, optionally waiting for rendering (warmup_duration, capture_delay, wait_for_render) and with debug overlays enabled for the capture. Requires authentication token and Developer role or higher.")]
+    pub async fn screenshot(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("screenshot", req).await
+    }
+
+    /// Watch entity counts for leaks and spikes (requires Developer role or higher)
+    #[tool(description = "Watch entity counts for leaks, spikes and runaway spawning. action: start, stop, status, configure (with config), check, alerts (with limit) or clear. Requires authentication token and Developer role or higher.")]
+    pub async fn entity_watchdog(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("entity_watchdog", req).await
+    }
+
+    /// Query the frame time and system timeline (requires Developer role or higher)
+    #[tool(description = "Frame time, per-system time and entity count history sampled from the game. action: query, stats, mark, record, configure (capacity, sample_interval_ms; 0 pauses sampling) or clear. Requires authentication token and Developer role or higher.")]
+    pub async fn perf_timeline(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("perf_timeline", req).await
+    }
+
+    /// Run benchmarks against saved baselines (requires Developer role or higher)
+    #[tool(description = "Run a benchmark scenario and compare it with a saved baseline within tolerance_pct. action: save_baseline, baselines or delete_baseline manage baselines. Requires authentication token and Developer role or higher.")]
+    pub async fn benchmark(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("benchmark", req).await
+    }
+
+    /// Run a tool through the orchestrator (requires Developer role or higher)
+    #[tool(description = "Run one debugging tool (tool with arguments) through the orchestrator, which can record, experiment on and cache its results (config). Requires authentication token and Developer role or higher.")]
+    pub async fn orchestrate(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("orchestrate", req).await
+    }
+
+    /// Run pipelines and pipeline templates (requires Developer role or higher)
+    #[tool(description = "Run debugging pipelines. action: run (with pipeline or template), resume, abort, list_running, list_templates or describe_template. Requires authentication token and Developer role or higher.")]
+    pub async fn pipeline(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("pipeline", req).await
+    }
+
+    /// Report server resource usage (requires Viewer role or higher)
+    #[tool(description = "Report the server's CPU, memory and connection usage. Requires authentication token and Viewer role or higher.")]
+    pub async fn resource_metrics(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("resource_metrics", req).await
+    }
+
+    /// Report server performance (requires Viewer role or higher)
+    #[tool(description = "Report tool latencies, cache hit rates and other server performance figures. Requires authentication token and Viewer role or higher.")]
+    pub async fn performance_dashboard(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("performance_dashboard", req).await
+    }
+
+    /// Check server and game connection health (requires Viewer role or higher)
+    #[tool(description = "Check the health of the server and its connection to the game. Requires authentication token and Viewer role or higher.")]
+    pub async fn health_check(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("health_check", req).await
+    }
+
+    /// Show or set adaptive sampling (requires Developer role or higher)
+    #[tool(description = "Show (action: status) or change (action: set_policy with policy) adaptive sampling of high-volume data. Requires authentication token and Developer role or higher.")]
+    pub async fn sampling(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("sampling", req).await
+    }
+
+    /// Manage failed operations (requires Developer role or higher)
+    #[tool(description = "Inspect and retry failed operations. action: list, stats, remove (with id), retry_policies or set_retry_policy (with operation and policy). Requires authentication token and Developer role or higher.")]
+    pub async fn dead_letter_queue(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("dead_letter_queue", req).await
+    }
+
+    /// Generate a diagnostic report (requires Viewer role or higher)
+    #[tool(description = "Generate (action: generate) or export (action: export) a diagnostic report with recent errors and logs; log_level and max_log_lines limit the logs. Requires authentication token and Viewer role or higher.")]
+    pub async fn diagnostic_report(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("diagnostic_report", req).await
+    }
+
+    /// Manage state checkpoints (requires Developer role or higher)
+    #[tool(description = "Create, list, restore, delete and count state checkpoints. action: create, list, restore (with checkpoint_id), delete or stats. Requires authentication token and Developer role or higher.")]
+    pub async fn checkpoint(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("checkpoint", req).await
+    }
+
+    /// Generate a bug report (requires Developer role or higher)
+    #[tool(description = "Generate a bug report from the current session with description and steps_to_reproduce, optionally saved to a file. Requires authentication token and Developer role or higher.")]
+    pub async fn bug_report(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("bug_report", req).await
+    }
+
+    /// Dump or clear the flight recorder (requires Developer role or higher)
+    #[tool(description = "Dump the last minutes of recorded events (optionally only some kinds), or clear them. action: dump or clear. Requires authentication token and Developer role or higher.")]
+    pub async fn flight_recorder(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("flight_recorder", req).await
+    }
+
+    /// Query recorded metric time series (requires Viewer role or higher)
+    #[tool(description = "Query downsampled metric time series between from_ms and to_ms for dashboards, with max_points and aggregation. Requires authentication token and Viewer role or higher.")]
+    pub async fn metrics_query(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("metrics_query", req).await
+    }
+
+    /// Manage threshold alert rules (requires Developer role or higher)
+    #[tool(description = "List, add (with rule) or remove (with name) threshold alert rules. action: list, add or remove. Requires authentication token and Developer role or higher.")]
+    pub async fn alerts(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("alerts", req).await
+    }
+
+    /// Manage scheduled workflows (requires Admin role)
+    #[tool(description = "List, add (with schedule) or remove (with name) workflows that run on an interval or cron schedule, or show their run history. action: list, add, remove or history. Requires authentication token and Admin role.")]
+    pub async fn schedules(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("schedules", req).await
+    }
+
+    /// Export a debugging session (requires Developer role or higher)
+    #[tool(description = "Export the current session, a recording, screenshots and optionally checkpoints and diagnostics to an archive at path. Requires authentication token and Developer role or higher.")]
+    pub async fn export_session(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("export_session", req).await
+    }
+
+    /// Import a debugging session (requires Developer role or higher)
+    #[tool(description = "Import a session archive from path, optionally loading its recording. Requires authentication token and Developer role or higher.")]
+    pub async fn import_session(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("import_session", req).await
+    }
+
+    /// Compare two recordings (requires Viewer role or higher)
+    #[tool(description = "Compare two recordings frame by frame and report where their components diverge beyond tolerance. Requires authentication token and Viewer role or higher.")]
+    pub async fn compare_recordings(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("compare_recordings", req).await
+    }
+
+    /// Show recorded state at a frame or time (requires Viewer role or higher)
+    #[tool(description = "Show the recorded state of entities and components at a frame or at seconds into a recording. Requires authentication token and Viewer role or higher.")]
+    pub async fn state_at(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("state_at", req).await
+    }
+
+    /// Run a debug command (requires Developer role or higher)
+    #[tool(description = "Run a structured debug command (command) in the game, with optional priority and correlation_id. Requires authentication token and Developer role or higher.")]
+    pub async fn debug(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("debug", req).await
+    }
+
+    /// Manage the response cache (requires Developer role or higher)
+    #[tool(description = "Show statistics of (action: stats), clear, invalidate (with tag) or save the response cache. Requires authentication token and Developer role or higher.")]
+    pub async fn cache(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("cache", req).await
+    }
+
+    /// Suggest next debugging steps (requires Viewer role or higher)
+    #[tool(description = "Suggest next debugging steps from the current game state, recent commands and learned patterns. Requires authentication token and Viewer role or higher.")]
+    pub async fn get_suggestions(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("get_suggestions", req).await
+    }
+
+    /// Record the outcome of a suggestion (requires Developer role or higher)
+    #[tool(description = "Record whether a suggestion was accepted and whether it helped. Requires authentication token and Developer role or higher.")]
+    pub async fn track_suggestion(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("track_suggestion", req).await
+    }
+
+    /// Reweight suggestions with feedback (requires Developer role or higher)
+    #[tool(description = "Mark a suggestion as accepted, rejected or ignored so similar suggestions are ranked up or down. Requires authentication token and Developer role or higher.")]
+    pub async fn suggestion_feedback(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("suggestion_feedback", req).await
+    }
+
+    /// Manage learned debugging patterns (requires Developer role or higher)
+    #[tool(description = "List, save, export or import (with patterns_json) learned debugging patterns. action: list, save, export or import. Requires authentication token and Developer role or higher.")]
+    pub async fn get_patterns(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("get_patterns", req).await
+    }
+
+    /// Run a learned workflow (requires Developer role or higher)
+    #[tool(description = "Run a learned debugging workflow by workflow_id, with optional automation preferences. Requires authentication token and Developer role or higher.")]
+    pub async fn execute_workflow(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("execute_workflow", req).await
+    }
+
+    /// Approve a workflow (requires Developer role or higher)
+    #[tool(description = "Approve a workflow that requires confirmation before it runs. Requires authentication token and Developer role or higher.")]
+    pub async fn approve_workflow(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("approve_workflow", req).await
+    }
+
+    /// List learned workflows (requires Viewer role or higher)
+    #[tool(description = "List learned workflows (action: list) or analyze them for automation (action: analyze). Requires authentication token and Viewer role or higher.")]
+    pub async fn get_workflows(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("get_workflows", req).await
+    }
+
+    /// List or run workflow scripts (requires Developer role or higher)
+    #[tool(description = "List (action: list) or run (action: run with name and args) Rhai workflow scripts from the scripts directory. Requires the scripting feature. Requires authentication token and Developer role or higher.")]
+    pub async fn workflow_script(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("workflow_script", req).await
+    }
+
+    /// Control model hot reloading (requires Admin role)
+    #[tool(description = "Start, stop, force or check hot reloading of suggestion models. action: start, stop, force_reload or status. Requires authentication token and Admin role.")]
+    pub async fn hot_reload(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("hot_reload", req).await
+    }
+
+    /// List loaded model versions (requires Viewer role or higher)
+    #[tool(description = "List the versions of the loaded suggestion models. Requires authentication token and Viewer role or higher.")]
+    pub async fn get_model_versions(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        self.call_tool_server("get_model_versions", req).await
+    }
+
     /// Create a new user (requires Admin role)
     #[tool(description = "Create a new user with specified role. Requires Admin role. Roles: viewer (read-only), developer (full debugging), admin (user management).")]
     pub async fn create_user(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
@@ -431,28 +1041,26 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("create_user", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
         // Extract token first, then remove auth parameters
-        let token = Self::extract_token_from_request(&req)
-            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), None))?;
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let create_req: CreateUserRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid create user parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid create user parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         // Parse role
         let role = match create_req.role.to_lowercase().as_str() {
+            "guest" => Role::Guest,
             "viewer" => Role::Viewer,
             "developer" => Role::Developer,
             "admin" => Role::Admin,
-            _ => return Err(McpError::invalid_params("Invalid role. Use: viewer, developer, or admin".to_string(), None)),
+            _ => return Err(McpError::invalid_params("Invalid role. Use: guest, viewer, developer, or admin".to_string(), Some(ErrorCode::InvalidInput.data()))),
         };
 
         info!("Admin {} creating user: {} with role: {:?}", claims.sub, create_req.username, role);
@@ -466,7 +1074,7 @@ impl SecureMcpTools {
             Err(e) => {
                 error!("User creation failed: {}", e);
                 self.log_tool_failure("create_user", &e.to_string()).await;
-                Err(McpError::internal_error(format!("User creation failed: {}", e), None))
+                Err(McpError::internal_error(format!("User creation failed: {}", e), Some(e.data())))
             }
         }
     }
@@ -478,20 +1086,17 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("delete_user", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        let token = Self::extract_token_from_request(&req)
-            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), None))?;
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let delete_req: DeleteUserRequest = serde_json::from_value(req)
-            .map_err(|e| McpError::invalid_params(format!("Invalid delete user parameters: {}", e), None))?;
+            .map_err(|e| McpError::invalid_params(format!("Invalid delete user parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
 
         info!("Admin {} deleting user: {}", claims.sub, delete_req.username);
         
@@ -504,7 +1109,7 @@ impl SecureMcpTools {
             Err(e) => {
                 error!("User deletion failed: {}", e);
                 self.log_tool_failure("delete_user", &e.to_string()).await;
-                Err(McpError::internal_error(format!("User deletion failed: {}", e), None))
+                Err(McpError::internal_error(format!("User deletion failed: {}", e), Some(e.data())))
             }
         }
     }
@@ -516,12 +1121,12 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("list_users", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        let token = Self::extract_token_from_request(&req)
-            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), None))?;
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
 
         info!("Admin {} listing users", claims.sub);
         
@@ -534,7 +1139,10 @@ impl SecureMcpTools {
                         "role": u.role,
                         "created_at": u.created_at,
                         "last_login": u.last_login,
-                        "active": u.active
+                        "active": u.active,
+                        "two_factor_enabled": u.two_factor.as_ref().is_some_and(|tf| tf.enabled),
+                        "must_change_password": u.must_change_password,
+                        "password_changed_at": u.password_changed_at.unwrap_or(u.created_at)
                     }))
                     .collect::<Vec<_>>();
                 
@@ -545,7 +1153,269 @@ impl SecureMcpTools {
             Err(e) => {
                 error!("List users failed: {}", e);
                 self.log_tool_failure("list_users", &e.to_string()).await;
-                Err(McpError::internal_error(format!("List users failed: {}", e), None))
+                Err(McpError::internal_error(format!("List users failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Create an API key (requires Admin role)
+    #[tool(description = "Create a long-lived API key for CI jobs and scripts. Requires Admin role. The key is shown only once; pass it as auth_token. Optional scopes restrict the key to specific operations.")]
+    pub async fn create_api_key(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("api_key_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("create_api_key", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let create_req: CreateApiKeyRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid create API key parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        let role = match create_req.role.to_lowercase().as_str() {
+            "guest" => Role::Guest,
+            "viewer" => Role::Viewer,
+            "developer" => Role::Developer,
+            "admin" => Role::Admin,
+            _ => return Err(McpError::invalid_params("Invalid role. Use: guest, viewer, developer, or admin".to_string(), Some(ErrorCode::InvalidInput.data()))),
+        };
+
+        info!("Admin {} creating API key: {} with role: {:?}", claims.sub, create_req.name, role);
+
+        match self.security_manager.create_api_key(&token, &create_req.name, role, create_req.scopes, create_req.expires_in_days).await {
+            Ok((api_key, key)) => {
+                let response = serde_json::json!({
+                    "id": api_key.id,
+                    "name": api_key.name,
+                    "role": api_key.role,
+                    "scopes": api_key.scopes,
+                    "expires_at": api_key.expires_at,
+                    "key": key,
+                });
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&response).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("API key creation failed: {}", e);
+                self.log_tool_failure("create_api_key", &e.to_string()).await;
+                Err(McpError::internal_error(format!("API key creation failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Revoke an API key (requires Admin role)
+    #[tool(description = "Revoke an API key by id. Requires Admin role. Clients using the key are rejected immediately.")]
+    pub async fn revoke_api_key(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("api_key_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("revoke_api_key", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let revoke_req: RevokeApiKeyRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid revoke API key parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        info!("Admin {} revoking API key: {}", claims.sub, revoke_req.id);
+
+        match self.security_manager.revoke_api_key(&token, &revoke_req.id).await {
+            Ok(_) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(format!("API key {} revoked", revoke_req.id))
+                ]))
+            }
+            Err(e) => {
+                error!("API key revocation failed: {}", e);
+                self.log_tool_failure("revoke_api_key", &e.to_string()).await;
+                Err(McpError::internal_error(format!("API key revocation failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// List active sessions (requires Admin role)
+    #[tool(description = "List active sessions with their user, IP address, user agent, start time and last activity, most recently active first. Optionally filter by user_id. Requires Admin role.")]
+    pub async fn list_sessions(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("session_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("list_sessions", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let list_req: ListSessionsRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid list sessions parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        debug!("Admin {} listing sessions", claims.sub);
+
+        match self.security_manager.get_active_sessions(&token).await {
+            Ok(sessions) => {
+                let sessions = sessions.into_iter()
+                    .filter(|session| !matches!(&list_req.user_id, Some(user_id) if &session.user_id != user_id))
+                    .map(|session| serde_json::json!({
+                        "id": session.id,
+                        "user_id": session.user_id,
+                        "ip_address": session.ip_address,
+                        "user_agent": session.user_agent,
+                        "created_at": session.created_at,
+                        "last_activity": session.last_activity,
+                        "current": session.id == claims.session_id
+                    }))
+                    .collect::<Vec<_>>();
+
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&sessions).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("Listing sessions failed: {}", e);
+                Err(McpError::internal_error(format!("List sessions failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Force-end sessions (requires Admin role)
+    #[tool(description = "Force-revoke one session by session_id, or every session of a user by user_id. Tokens and refresh tokens of revoked sessions stop working immediately. Requires Admin role.")]
+    pub async fn revoke_session(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("session_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("revoke_session", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let revoke_req: RevokeSessionRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid revoke session parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        let result = match (&revoke_req.session_id, &revoke_req.user_id) {
+            (Some(session_id), None) => {
+                info!("Admin {} revoking session {}", claims.sub, session_id);
+                self.security_manager.revoke_session(&token, session_id).await
+                    .map(|session| format!("Session {} of user {} revoked", session.id, session.user_id))
+            }
+            (None, Some(user_id)) => {
+                info!("Admin {} revoking all sessions of {}", claims.sub, user_id);
+                self.security_manager.revoke_user_sessions(&token, user_id).await
+                    .map(|ended| format!("{} sessions of user {} revoked", ended, user_id))
+            }
+            _ => return Err(McpError::invalid_params("Provide exactly one of session_id or user_id".to_string(), Some(ErrorCode::InvalidInput.data()))),
+        };
+
+        match result {
+            Ok(message) => Ok(CallToolResult::success(vec![Content::text(message)])),
+            Err(e) => {
+                error!("Session revocation failed: {}", e);
+                self.log_tool_failure("revoke_session", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Session revocation failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Test the tool access policy (requires Admin role)
+    #[tool(description = "Check whether a role or user may call a tool under the current access policy, optionally reloading the policy file first. Requires Admin role.")]
+    pub async fn policy_check(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("policy_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("policy_check", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let check_req: PolicyCheckRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid policy check parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        let role = match check_req.role.to_lowercase().as_str() {
+            "guest" => Role::Guest,
+            "viewer" => Role::Viewer,
+            "developer" => Role::Developer,
+            "admin" => Role::Admin,
+            _ => return Err(McpError::invalid_params("Invalid role. Use: guest, viewer, developer, or admin".to_string(), Some(ErrorCode::InvalidInput.data()))),
+        };
+
+        debug!("Admin {} checking policy for {} as {:?}", claims.sub, check_req.operation, role);
+
+        match self.security_manager.check_policy(&token, &check_req.operation, role, check_req.user_id.as_deref(), check_req.reload).await {
+            Ok(decision) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&decision).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("Policy check failed: {}", e);
+                self.log_tool_failure("policy_check", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Policy check failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// List API keys (requires Admin role)
+    #[tool(description = "List API keys with their roles, scopes, expiry and last use. Requires Admin role. Key secrets are never shown.")]
+    pub async fn list_api_keys(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("api_key_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("list_api_keys", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        info!("Admin {} listing API keys", claims.sub);
+
+        match self.security_manager.list_api_keys(&token).await {
+            Ok(keys) => {
+                let key_list = keys.into_iter()
+                    .map(|k| serde_json::json!({
+                        "id": k.id,
+                        "name": k.name,
+                        "role": k.role,
+                        "scopes": k.scopes,
+                        "created_by": k.created_by,
+                        "created_at": k.created_at,
+                        "expires_at": k.expires_at,
+                        "last_used": k.last_used
+                    }))
+                    .collect::<Vec<_>>();
+
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&key_list).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("List API keys failed: {}", e);
+                self.log_tool_failure("list_api_keys", &e.to_string()).await;
+                Err(McpError::internal_error(format!("List API keys failed: {}", e), Some(e.data())))
             }
         }
     }
@@ -557,17 +1427,14 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("get_audit_log", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        let token = Self::extract_token_from_request(&req)
-            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), None))?;
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
 
-        req.as_object_mut().map(|obj| {
-            obj.remove("auth_token");
-            obj.remove("authorization");
-        });
+        Self::strip_auth(&mut req);
 
         let audit_req: AuditLogRequest = serde_json::from_value(req).unwrap_or(AuditLogRequest {
             limit: Some(100),
@@ -585,7 +1452,163 @@ impl SecureMcpTools {
             Err(e) => {
                 error!("Audit log access failed: {}", e);
                 self.log_tool_failure("get_audit_log", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Audit log access failed: {}", e), None))
+                Err(McpError::internal_error(format!("Audit log access failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Rotate the audit log file (requires Admin role)
+    #[tool(description = "Rotate the audit log file now, compressing the current file and deleting rotated files past retention. Requires Admin role.")]
+    pub async fn rotate_audit_log(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("audit_log_access", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("rotate_audit_log", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        info!("Admin {} rotating audit log", claims.sub);
+
+        match self.security_manager.rotate_audit_log(&token).await {
+            Ok(rotation) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&rotation).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("Audit log rotation failed: {}", e);
+                self.log_tool_failure("rotate_audit_log", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Audit log rotation failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Verify the audit log's hash chain (requires Admin role)
+    #[tool(description = "Verify the persisted audit log has not been edited: checks every entry's hash chain link and any periodic signatures, including rotated files. Reports the first broken entry. Requires Admin role and audit integrity mode.")]
+    pub async fn verify_audit_log(&self, Parameters(req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("audit_log_access", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("verify_audit_log", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        info!("Admin {} verifying audit log", claims.sub);
+
+        match self.security_manager.verify_audit_log(&token).await {
+            Ok(report) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&report).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("Audit log verification failed: {}", e);
+                self.log_tool_failure("verify_audit_log", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Audit log verification failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// List or rotate JWT signing keys (requires Admin role)
+    #[tool(description = "Manage the keys that sign access tokens. action=\"list\" shows key IDs, which key is active and when retired keys stop validating; action=\"rotate\" switches to a new random key while tokens signed by the old key stay valid for the grace window. Requires Admin role.")]
+    pub async fn signing_keys(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("signing_key_management", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("signing_keys", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let keys_req: SigningKeysRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid signing key parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        debug!("Admin {} running signing key action: {}", claims.sub, keys_req.action);
+
+        let result = match keys_req.action.as_str() {
+            "list" => self.security_manager.list_signing_keys(&token).await
+                .map(|keys| serde_json::to_value(keys).unwrap_or_default()),
+            "rotate" => self.security_manager.rotate_signing_key(&token).await
+                .map(|key| serde_json::to_value(key).unwrap_or_default()),
+            other => return Err(McpError::invalid_params(format!("Unknown signing key action: {}. Use list or rotate", other), Some(ErrorCode::InvalidInput.data()))),
+        };
+
+        match result {
+            Ok(value) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&value).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("Signing key action {} failed: {}", keys_req.action, e);
+                self.log_tool_failure("signing_keys", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Signing key action failed: {}", e), Some(e.data())))
+            }
+        }
+    }
+
+    /// Security scan, metrics and recent audit events (requires Admin role)
+    #[tool(description = "Security overview for admins. action=\"scan\" runs a vulnerability scan, action=\"metrics\" returns session, lockout and failure counters, action=\"audit_events\" returns the most recent audit events (filter with event_action, failures_only, limit). Requires Admin role.")]
+    pub async fn security(&self, Parameters(mut req): Parameters<Value>) -> std::result::Result<CallToolResult, McpError> {
+        let claims = match self.authorize_tool_call("security_scan", &req).await {
+            Ok(claims) => claims,
+            Err(e) => {
+                self.log_tool_failure("security", &e.to_string()).await;
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
+            }
+        };
+
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
+
+        Self::strip_auth(&mut req);
+
+        let security_req: SecurityToolRequest = serde_json::from_value(req)
+            .map_err(|e| McpError::invalid_params(format!("Invalid security parameters: {}", e), Some(ErrorCode::InvalidInput.data())))?;
+
+        debug!("Admin {} running security action: {}", claims.sub, security_req.action);
+
+        let result = match security_req.action.as_str() {
+            "scan" => self.security_audit.run_security_scan(&token).await
+                .map(|report| serde_json::to_value(report).unwrap_or_default()),
+            "metrics" => self.security_manager.security_metrics(&token).await
+                .map(|metrics| serde_json::to_value(metrics).unwrap_or_default()),
+            "audit_events" => self.security_manager.get_audit_log(&token, None, None).await
+                .map(|entries| {
+                    let events = entries.into_iter()
+                        .rev()
+                        .filter(|entry| !security_req.failures_only || !entry.success)
+                        .filter(|entry| !matches!(&security_req.event_action, Some(action) if &entry.action != action))
+                        .take(security_req.limit.unwrap_or(50))
+                        .collect::<Vec<_>>();
+                    serde_json::to_value(events).unwrap_or_default()
+                }),
+            other => return Err(McpError::invalid_params(format!("Unknown security action: {}. Use scan, metrics or audit_events", other), Some(ErrorCode::InvalidInput.data()))),
+        };
+
+        match result {
+            Ok(value) => {
+                Ok(CallToolResult::success(vec![
+                    Content::text(serde_json::to_string_pretty(&value).unwrap())
+                ]))
+            }
+            Err(e) => {
+                error!("Security action {} failed: {}", security_req.action, e);
+                self.log_tool_failure("security", &e.to_string()).await;
+                Err(McpError::internal_error(format!("Security action failed: {}", e), Some(e.data())))
             }
         }
     }
@@ -597,12 +1620,12 @@ impl SecureMcpTools {
             Ok(claims) => claims,
             Err(e) => {
                 self.log_tool_failure("security_scan", &e.to_string()).await;
-                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), None));
+                return Err(McpError::invalid_params(format!("Authorization failed: {}", e), Some(e.data())));
             }
         };
 
-        let token = Self::extract_token_from_request(&req)
-            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), None))?;
+        let token = self.request_token(&req)
+            .ok_or_else(|| McpError::invalid_params("Authentication token required".to_string(), Some(ErrorCode::AuthenticationRequired.data())))?;
 
         info!("Admin {} initiating security scan", claims.sub);
         
@@ -615,18 +1638,38 @@ impl SecureMcpTools {
             Err(e) => {
                 error!("Security scan failed: {}", e);
                 self.log_tool_failure("security_scan", &e.to_string()).await;
-                Err(McpError::internal_error(format!("Security scan failed: {}", e), None))
+                Err(McpError::internal_error(format!("Security scan failed: {}", e), Some(e.data())))
             }
         }
     }
 }
 
 // Implement ServerHandler for the secure tools
+//
+// `#[tool_handler]` would expand to this module's `Result` alias, so the two
+// router methods are written out.
 impl ServerHandler for SecureMcpTools {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let tcc = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult::with_all_items(self.tool_router.list_all()))
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder().enable_logging().enable_tools().build(),
             server_info: Implementation {
                 name: "bevy-debugger-mcp-secure".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -634,5 +1677,44 @@ impl ServerHandler for SecureMcpTools {
             instructions: Some("Security-enhanced AI-assisted debugging tools for Bevy games. All operations require JWT authentication with role-based permissions.".to_string()),
         }
     }
+
+    /// Forward alert firings and entity watchdog alerts to the client as log notifications
+    fn on_initialized(&self, context: NotificationContext<RoleServer>) -> impl Future<Output = ()> + Send + '_ {
+        let peer = context.peer;
+        let mut alerts = alerting::global().subscribe();
+        let mut watchdog_alerts = self.tool_server.subscribe_watchdog_alerts();
+        tokio::spawn(async move {
+            loop {
+                let notification = tokio::select! {
+                    event = alerts.recv() => match event {
+                        Ok(event) => LoggingMessageNotificationParam {
+                            level: match event.state {
+                                AlertState::Firing => LoggingLevel::Alert,
+                                AlertState::Resolved => LoggingLevel::Notice,
+                            },
+                            logger: Some("alerts".to_string()),
+                            data: serde_json::to_value(&event).unwrap_or_default(),
+                        },
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                    alert = watchdog_alerts.recv() => match alert {
+                        Ok(alert) => LoggingMessageNotificationParam {
+                            level: LoggingLevel::Warning,
+                            logger: Some("entity_watchdog".to_string()),
+                            data: serde_json::to_value(&alert).unwrap_or_default(),
+                        },
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    },
+                };
+                // The client has gone away
+                if peer.notify_logging_message(notification).await.is_err() {
+                    break;
+                }
+            }
+        });
+        std::future::ready(())
+    }
 }
 
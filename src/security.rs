@@ -16,26 +16,47 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{Algorithm, Validation};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
+use async_trait::async_trait;
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 use argon2::password_hash::{rand_core::OsRng, SaltString};
 use governor::{Quota, RateLimiter, state::{direct::NotKeyed, InMemoryState}, clock::DefaultClock, middleware::NoOpMiddleware};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
+use base64::Engine as _;
 
+use crate::audit_chain::{verify_chain, AuditChain, AuditVerification};
+use crate::audit_log::{AuditFileConfig, AuditFileLogger, AuditRotation};
+use crate::audit_sinks::{build_sink, AuditLogger};
 use crate::error::{Error, Result};
+use crate::ip_filter::IpFilter;
+use crate::jwt_keys::{JwtKeyRing, SigningKeyInfo};
+use crate::login_throttle::LoginThrottle;
+use crate::memory_budget::MemoryConsumer;
+use crate::mtls::{CertIdentity, MtlsConfig, CERT_SUBJECT_PREFIX};
+use crate::oidc::OidcValidator;
+use crate::rate_limit::{RateLimit, RateLimitConfig, RateLimitStatus, UserRateLimiter};
+use crate::rbac_policy::{PolicyDecision, PolicyStore, RbacPolicy};
+use crate::redaction::RedactionRules;
+use crate::secrets::is_default_secret;
+use crate::totp::{TotpEnrollment, TwoFactor};
+use crate::user_store::{EncryptedFileUserStore, InMemoryUserStore, UserStore, UserStoreSnapshot};
 
 /// User roles with hierarchical permissions
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Role {
+    /// Can only run observe and diagnostic reads, with configured component data redacted
+    Guest,
     /// Can only observe and query (read-only access)
     Viewer,
     /// Can observe, query, and modify state (full debugging)
@@ -49,8 +70,9 @@ impl Role {
     pub fn has_permission(&self, required_role: &Role) -> bool {
         match (self, required_role) {
             (Role::Admin, _) => true,
-            (Role::Developer, Role::Viewer | Role::Developer) => true,
-            (Role::Viewer, Role::Viewer) => true,
+            (Role::Developer, Role::Guest | Role::Viewer | Role::Developer) => true,
+            (Role::Viewer, Role::Guest | Role::Viewer) => true,
+            (Role::Guest, Role::Guest) => true,
             _ => false,
         }
     }
@@ -58,6 +80,7 @@ impl Role {
     /// Get the minimum role level as a number for comparisons
     pub fn level(&self) -> u8 {
         match self {
+            Role::Guest => 0,
             Role::Viewer => 1,
             Role::Developer => 2,
             Role::Admin => 3,
@@ -86,6 +109,65 @@ pub struct User {
     pub created_at: DateTime<Utc>,
     pub last_login: Option<DateTime<Utc>>,
     pub active: bool,
+    /// TOTP second factor, if the user enrolled
+    #[serde(default)]
+    pub two_factor: Option<TwoFactor>,
+    /// When the password was last set; `created_at` is used when unknown
+    #[serde(default)]
+    pub password_changed_at: Option<DateTime<Utc>>,
+    /// Hashes of previous passwords, newest first
+    #[serde(default)]
+    pub password_history: Vec<String>,
+    /// A new password must be chosen at the next login
+    #[serde(default)]
+    pub must_change_password: bool,
+}
+
+/// Issuer shown in authenticator apps
+const TOTP_ISSUER: &str = "Bevy Debugger MCP";
+
+/// Failed login counts kept in memory for usernames that don't exist
+const MAX_UNKNOWN_FAILED_LOGINS: usize = 10_000;
+
+/// Prefix identifying API keys among bearer tokens
+pub const API_KEY_PREFIX: &str = "bmcp_";
+
+/// Long-lived key for non-interactive clients; only a hash of the secret is kept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub key_hash: String,
+    pub role: Role,
+    /// Operations the key may call; empty allows everything its role allows
+    pub scopes: Vec<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Prefix identifying refresh tokens
+pub const REFRESH_TOKEN_PREFIX: &str = "bmcr_";
+
+/// Access token plus the single-use refresh token that renews it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub role: Role,
+    /// Seconds until the access token expires
+    pub expires_in: u64,
+}
+
+/// Refresh token state; tokens are keyed by the hash of their value
+#[derive(Debug, Clone)]
+struct RefreshToken {
+    session_id: String,
+    user_id: String,
+    expires_at: DateTime<Utc>,
+    /// Set once the token has been rotated; presenting it again revokes the session
+    used: bool,
 }
 
 /// Audit log entry for security tracking
@@ -102,6 +184,15 @@ pub struct AuditEntry {
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub session_id: Option<String>,
+    /// Hash of the previous entry, in integrity mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    /// Hash of this entry including `prev_hash`, in integrity mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Signature over `hash`, on every Nth entry when a signing key is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 // Re-export the production security configuration
@@ -119,7 +210,7 @@ pub struct Session {
 }
 
 /// Failed login attempt tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FailedLogin {
     pub count: u32,
     pub first_attempt: DateTime<Utc>,
@@ -130,21 +221,52 @@ pub struct FailedLogin {
 /// Main security manager
 pub struct SecurityManager {
     config: SecurityConfig,
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    jwt_keys: Arc<JwtKeyRing>,
     users: Arc<RwLock<HashMap<String, User>>>,
     revoked_tokens: Arc<DashMap<String, DateTime<Utc>>>,
     active_sessions: Arc<DashMap<String, Session>>,
     failed_logins: Arc<DashMap<String, FailedLogin>>,
+    login_throttle: LoginThrottle,
     audit_log: Arc<RwLock<Vec<AuditEntry>>>,
     rate_limiter: Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>>,
+    store: Arc<dyn UserStore>,
+    oidc: Option<Arc<OidcValidator>>,
+    api_keys: Arc<DashMap<String, ApiKey>>,
+    refresh_tokens: Arc<DashMap<String, RefreshToken>>,
+    policy: PolicyStore,
+    user_rate_limiter: UserRateLimiter,
+    audit_logger: Arc<AuditLogger>,
 }
 
 impl SecurityManager {
     /// Create a new security manager with configuration
-    pub fn new(config: SecurityConfig) -> Result<Self> {
-        let encoding_key = EncodingKey::from_secret(config.jwt_secret.as_ref());
-        let decoding_key = DecodingKey::from_secret(config.jwt_secret.as_ref());
+    ///
+    /// Users are kept in an encrypted file when `user_store_path` is set and
+    /// only in memory otherwise. Fails if the store can't be read or decrypted.
+    pub async fn new(config: SecurityConfig) -> Result<Self> {
+        let store: Arc<dyn UserStore> = match (&config.user_store_path, &config.user_store_key) {
+            (Some(path), Some(key)) => Arc::new(EncryptedFileUserStore::new(path, key)?),
+            (Some(_), None) => {
+                return Err(Error::SecurityError(
+                    "A user store key is required when a user store path is set".to_string(),
+                ))
+            }
+            (None, _) => Arc::new(InMemoryUserStore),
+        };
+        Self::with_store(config, store).await
+    }
+
+    /// Create a security manager that keeps users in `store`
+    ///
+    /// Stored users are loaded before this returns. A store that fails to load
+    /// is an error rather than an empty user set, so the next save can't
+    /// overwrite it.
+    pub async fn with_store(config: SecurityConfig, store: Arc<dyn UserStore>) -> Result<Self> {
+        if config.production_mode && is_default_secret(&config.jwt_secret) {
+            return Err(Error::SecurityError("Refusing to start in production mode with a default JWT secret".to_string()));
+        }
+
+        let jwt_keys = Arc::new(JwtKeyRing::new(&config.jwt_secret, chrono::Duration::hours(config.jwt_key_grace_hours as i64)));
 
         // Setup global rate limiter (will be supplemented with per-IP limiting)
         let quota = Quota::per_minute(
@@ -152,30 +274,100 @@ impl SecurityManager {
             )
             .allow_burst(std::num::NonZeroU32::new(config.rate_limit_burst.try_into().unwrap_or(10)).unwrap_or(std::num::NonZeroU32::new(10).unwrap()));
         let rate_limiter = Arc::new(RateLimiter::direct(quota));
+        let oidc = config.oidc.clone().map(|oidc| Arc::new(OidcValidator::new(oidc)));
+        let audit_file = config.audit_log_persistence.then(|| {
+            Arc::new(AuditFileLogger::new(AuditFileConfig {
+                path: config.audit_log_path.clone().into(),
+                max_bytes: config.audit_log_max_size_mb.saturating_mul(1024 * 1024),
+                rotate_interval_hours: Some(config.audit_log_rotate_hours).filter(|hours| *hours > 0),
+                retention_days: config.audit_log_retention_days,
+                max_files: Some(config.audit_log_max_files).filter(|max| *max > 0),
+            }))
+        });
+        let mut audit_logger = AuditLogger::new(audit_file);
+        for sink in &config.audit_sinks {
+            audit_logger = audit_logger.with_sink(build_sink(sink, config.audit_http_token.clone())?);
+        }
+        if config.audit_integrity {
+            audit_logger = audit_logger.with_chain(AuditChain::new(config.audit_signing_key.as_deref(), config.audit_signature_interval));
+        }
+        let policy = PolicyStore::new(config.tool_policy_path.as_deref())?;
+        if let Err(e) = policy.watch() {
+            warn!("Tool policy changes will need a manual reload: {}", e);
+        }
+        let user_rate_limiter = UserRateLimiter::new(
+            config.rate_limit_config_path.as_deref(),
+            RateLimitConfig::uniform(RateLimit::new(config.rate_limit_per_user, config.rate_limit_burst)),
+        )?;
+        if let Err(e) = user_rate_limiter.watch() {
+            warn!("Rate limit changes will need a manual reload: {}", e);
+        }
+
+        let login_throttle = LoginThrottle::new(config.ip_max_failed_logins, config.ip_ban_duration_minutes, Duration::from_millis(config.ip_failure_delay_ms));
 
         let manager = Self {
             config,
-            encoding_key,
-            decoding_key,
+            jwt_keys,
             users: Arc::new(RwLock::new(HashMap::new())),
             revoked_tokens: Arc::new(DashMap::new()),
             active_sessions: Arc::new(DashMap::new()),
             failed_logins: Arc::new(DashMap::new()),
+            login_throttle,
             audit_log: Arc::new(RwLock::new(Vec::new())),
             rate_limiter,
+            store,
+            oidc,
+            api_keys: Arc::new(DashMap::new()),
+            refresh_tokens: Arc::new(DashMap::new()),
+            policy,
+            user_rate_limiter,
+            audit_logger: Arc::new(audit_logger),
         };
 
-        // Create default admin user if none exists
-        tokio::spawn({
-            let manager = manager.clone();
-            async move {
-                if let Err(e) = manager.initialize_default_users().await {
-                    error!("Failed to initialize default users: {}", e);
+        // Load stored users, creating default users if there are none
+        manager.initialize_users().await?;
+
+        Ok(manager)
+    }
+
+    /// Restore users and lockouts from the store, or create default users
+    async fn initialize_users(&self) -> Result<()> {
+        if let Some(snapshot) = self.store.load().await? {
+            if !snapshot.users.is_empty() {
+                info!("Loaded {} users from the user store", snapshot.users.len());
+                *self.users.write().await = snapshot.users;
+                for (username, failed) in snapshot.lockouts {
+                    self.failed_logins.insert(username, failed);
+                }
+                for (id, api_key) in snapshot.api_keys {
+                    self.api_keys.insert(id, api_key);
                 }
+                return Ok(());
             }
-        });
+        }
 
-        Ok(manager)
+        self.initialize_default_users().await?;
+        self.persist().await
+    }
+
+    /// Save users and the lockout state of existing users to the store
+    async fn persist(&self) -> Result<()> {
+        let users = self.users.read().await.clone();
+        let snapshot = UserStoreSnapshot {
+            lockouts: self
+                .failed_logins
+                .iter()
+                .filter(|entry| users.contains_key(entry.key()))
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect(),
+            users,
+            api_keys: self
+                .api_keys
+                .iter()
+                .map(|entry| (entry.key().clone(), entry.value().clone()))
+                .collect(),
+        };
+        self.store.save(&snapshot).await
     }
 
     /// Initialize default users for first-time setup with secure passwords
@@ -204,6 +396,10 @@ impl SecurityManager {
                 created_at: Utc::now(),
                 last_login: None,
                 active: true,
+                two_factor: None,
+                password_changed_at: None,
+                password_history: Vec::new(),
+                must_change_password: self.config.force_initial_password_change,
             };
             
             let dev_user = User {
@@ -214,6 +410,10 @@ impl SecurityManager {
                 created_at: Utc::now(),
                 last_login: None,
                 active: true,
+                two_factor: None,
+                password_changed_at: None,
+                password_history: Vec::new(),
+                must_change_password: self.config.force_initial_password_change,
             };
             
             let viewer_user = User {
@@ -224,6 +424,10 @@ impl SecurityManager {
                 created_at: Utc::now(),
                 last_login: None,
                 active: true,
+                two_factor: None,
+                password_changed_at: None,
+                password_history: Vec::new(),
+                must_change_password: self.config.force_initial_password_change,
             };
             
             users.insert("admin".to_string(), admin_user);
@@ -266,12 +470,70 @@ impl SecurityManager {
         Ok(argon2.verify_password(password.as_bytes(), &parsed_hash).is_ok())
     }
 
+    /// Hash `new_password` for `user` after checking the policy and the password history
+    ///
+    /// Returns the new hash together with the history to store alongside it.
+    fn prepare_password_change(&self, user: &User, new_password: &str) -> Result<(String, Vec<String>)> {
+        let policy = self.config.password_policy();
+        policy.check(new_password, &user.username)?;
+
+        let recent = std::iter::once(&user.password_hash).chain(user.password_history.iter().take(policy.history_size));
+        for hash in recent {
+            if self.verify_password(new_password, hash).unwrap_or(false) {
+                return Err(Error::SecurityError(format!(
+                    "Password was used recently; it must differ from the last {} password(s)",
+                    policy.history_size + 1
+                )));
+            }
+        }
+
+        let password_hash = self.hash_password(new_password)?;
+        let mut history = user.password_history.clone();
+        history.insert(0, user.password_hash.clone());
+        history.truncate(policy.history_size);
+        Ok((password_hash, history))
+    }
+
     /// Authenticate user and return JWT token
     pub async fn authenticate(&self, username: &str, password: &str, ip_address: Option<String>, user_agent: Option<String>) -> Result<String> {
+        self.authenticate_with_second_factor(username, password, None, ip_address, user_agent).await
+    }
+
+    /// Authenticate user, checking a TOTP or recovery code when the account has two-factor enabled
+    pub async fn authenticate_with_second_factor(&self, username: &str, password: &str, second_factor: Option<&str>, ip_address: Option<String>, user_agent: Option<String>) -> Result<String> {
+        self.login(username, password, second_factor, None, ip_address, user_agent).await
+    }
+
+    /// Authenticate and replace the password in one step
+    ///
+    /// This is how users whose password expired or must be changed on first
+    /// login get in; anyone else may use it to change their password too.
+    pub async fn authenticate_with_password_change(&self, username: &str, password: &str, second_factor: Option<&str>, new_password: &str, ip_address: Option<String>, user_agent: Option<String>) -> Result<String> {
+        self.login(username, password, second_factor, Some(new_password), ip_address, user_agent).await
+    }
+
+    async fn login(&self, username: &str, password: &str, second_factor: Option<&str>, new_password: Option<&str>, ip_address: Option<String>, user_agent: Option<String>) -> Result<String> {
         // Check rate limiting first
         if self.rate_limiter.check().is_err() {
             self.log_audit("authentication", username, None, false, Some("Rate limit exceeded"), ip_address.as_deref(), user_agent.as_deref(), None).await;
-            return Err(Error::SecurityError("Rate limit exceeded".to_string()));
+            return Err(Error::RateLimited("Rate limit exceeded".to_string()));
+        }
+
+        // Addresses outside the IP allow list cannot authenticate on any transport
+        let client_ip: Option<IpAddr> = ip_address.as_deref().and_then(|ip| ip.parse().ok());
+        if let Some(ip) = client_ip {
+            self.check_client_address(ip, "authentication").await?;
+
+            // Addresses that keep failing are slowed down, then banned, whichever usernames they try
+            match self.login_throttle.check(ip, Utc::now()) {
+                Err(banned_until) => {
+                    let reason = format!("IP address banned until {}", banned_until.to_rfc3339());
+                    self.log_audit("authentication", username, None, false, Some(&reason), ip_address.as_deref(), user_agent.as_deref(), None).await;
+                    return Err(Error::RateLimited("Too many failed logins from this address; try again later".to_string()));
+                }
+                Ok(delay) if !delay.is_zero() => tokio::time::sleep(delay).await,
+                Ok(_) => {}
+            }
         }
 
         // Check for account lockout
@@ -279,7 +541,7 @@ impl SecurityManager {
             if let Some(locked_until) = failed.locked_until {
                 if Utc::now() < locked_until {
                     self.log_audit("authentication", username, None, false, Some("Account locked"), ip_address.as_deref(), user_agent.as_deref(), None).await;
-                    return Err(Error::SecurityError("Account is temporarily locked".to_string()));
+                    return Err(Error::RateLimited("Account is temporarily locked".to_string()));
                 }
             }
         }
@@ -292,7 +554,7 @@ impl SecurityManager {
                 let ip = ip_address.clone();
                 let ua = user_agent.clone();
                 async move {
-                    security.record_failed_login(&username).await;
+                    security.record_failed_login(&username, ip.as_deref()).await;
                     security.log_audit("authentication", &username, None, false, Some("User not found"), ip.as_deref(), ua.as_deref(), None).await;
                 }
             });
@@ -312,21 +574,80 @@ impl SecurityManager {
                 let ip = ip_address.clone();
                 let ua = user_agent.clone();
                 async move {
-                    security.record_failed_login(&username).await;
+                    security.record_failed_login(&username, ip.as_deref()).await;
                     security.log_audit("authentication", &username, None, false, Some("Invalid password"), ip.as_deref(), ua.as_deref(), None).await;
                 }
             });
             return Err(Error::SecurityError("Invalid credentials".to_string()));
         }
 
+        let (user_id, role) = (user.id.clone(), user.role.clone());
+        let password_expired = self.config.password_policy().is_expired(user.password_changed_at.unwrap_or(user.created_at), Utc::now());
+        let password_change_required = user.must_change_password || password_expired;
+        // Hash before taking the write lock; the new password is only stored once every factor checks out
+        let new_credentials = match new_password {
+            Some(new_password) => match self.prepare_password_change(user, new_password) {
+                Ok(credentials) => Some(credentials),
+                Err(e) => {
+                    drop(users);
+                    self.log_audit("password_change", username, None, false, Some(&e.to_string()), ip_address.as_deref(), user_agent.as_deref(), None).await;
+                    return Err(e);
+                }
+            },
+            None => None,
+        };
+        drop(users);
+
+        // Check the second factor and record the login under one lock, so a code is only accepted once
+        let mut users = self.users.write().await;
+        let second_factor_error = match users.get_mut(username).and_then(|user| user.two_factor.as_mut()).filter(|tf| tf.enabled) {
+            Some(two_factor) => match second_factor {
+                None => Some("Two-factor code required"),
+                Some(code) if !two_factor.verify(code, Utc::now().timestamp().max(0) as u64) => Some("Invalid two-factor code"),
+                Some(_) => None,
+            },
+            None => None,
+        };
+        if let Some(reason) = second_factor_error {
+            drop(users);
+            self.record_failed_login(username, ip_address.as_deref()).await;
+            self.log_audit("authentication", username, None, false, Some(reason), ip_address.as_deref(), user_agent.as_deref(), None).await;
+            return Err(Error::SecurityError(reason.to_string()));
+        }
+        if new_credentials.is_none() && password_change_required {
+            drop(users);
+            let reason = if password_expired { "Password expired" } else { "Password change required" };
+            self.log_audit("authentication", username, None, false, Some(reason), ip_address.as_deref(), user_agent.as_deref(), None).await;
+            return Err(Error::SecurityError(format!("{}: authenticate again with a new_password", reason)));
+        }
+        if let Some(user) = users.get_mut(username) {
+            user.last_login = Some(Utc::now());
+            if let Some((password_hash, history)) = new_credentials.clone() {
+                user.password_hash = password_hash;
+                user.password_history = history;
+                user.password_changed_at = Some(Utc::now());
+                user.must_change_password = false;
+            }
+        }
+        drop(users);
+        if let Err(e) = self.persist().await {
+            warn!("Failed to save user store after login: {}", e);
+        }
+        if new_credentials.is_some() {
+            self.log_audit("password_change", username, None, true, None, ip_address.as_deref(), user_agent.as_deref(), None).await;
+        }
+
         // Clear failed login attempts on successful login
         self.failed_logins.remove(username);
+        if let Some(ip) = client_ip {
+            self.login_throttle.record_success(ip);
+        }
 
         // Create session
         let session_id = Uuid::new_v4().to_string();
         let session = Session {
             id: session_id.clone(),
-            user_id: user.id.clone(),
+            user_id: user_id.clone(),
             created_at: Utc::now(),
             last_activity: Utc::now(),
             ip_address: ip_address.clone(),
@@ -335,59 +656,279 @@ impl SecurityManager {
         self.active_sessions.insert(session_id.clone(), session);
 
         // Generate JWT token
+        let token = self.issue_access_token(&user_id, &role, &session_id)?;
+
+        self.log_audit("authentication", username, None, true, None, ip_address.as_deref(), user_agent.as_deref(), Some(&session_id)).await;
+        info!("User {} authenticated successfully", username);
+
+        Ok(token)
+    }
+
+    /// Sign a JWT for an existing session
+    fn issue_access_token(&self, user_id: &str, role: &Role, session_id: &str) -> Result<String> {
         let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        let exp = now + (self.config.jwt_expiry_hours * 3600);
-        
         let claims = Claims {
-            sub: user.id.clone(),
-            role: user.role.clone(),
-            exp,
+            sub: user_id.to_string(),
+            role: role.clone(),
+            exp: now + (self.config.jwt_expiry_hours * 3600),
             iat: now,
             jti: Uuid::new_v4().to_string(),
-            session_id: session_id.clone(),
+            session_id: session_id.to_string(),
         };
 
-        let token = encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| Error::SecurityError(format!("Token generation failed: {}", e)))?;
+        self.jwt_keys.sign(&claims)
+    }
 
-        // Update user's last login
-        drop(users);
-        let mut users = self.users.write().await;
-        if let Some(user) = users.get_mut(username) {
-            user.last_login = Some(Utc::now());
+    /// Create a refresh token for a session; only its hash is kept
+    fn issue_refresh_token(&self, session_id: &str, user_id: &str) -> Result<String> {
+        let mut secret_bytes = [0u8; 32];
+        ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut secret_bytes)
+            .map_err(|_| Error::SecurityError("Failed to generate refresh token".to_string()))?;
+        let token = format!("{}{}", REFRESH_TOKEN_PREFIX, base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret_bytes));
+
+        self.refresh_tokens.insert(hash_api_key_secret(&token), RefreshToken {
+            session_id: session_id.to_string(),
+            user_id: user_id.to_string(),
+            expires_at: Utc::now() + chrono::Duration::hours(self.config.refresh_token_expiry_hours as i64),
+            used: false,
+        });
+        Ok(token)
+    }
+
+    /// Authenticate and return an access token together with a refresh token
+    ///
+    /// With `new_password` the password is replaced as part of logging in.
+    pub async fn authenticate_with_refresh(&self, username: &str, password: &str, second_factor: Option<&str>, new_password: Option<&str>, ip_address: Option<String>, user_agent: Option<String>) -> Result<TokenPair> {
+        let access_token = self.login(username, password, second_factor, new_password, ip_address, user_agent).await?;
+        let claims = self.validate_token(&access_token).await?;
+        let refresh_token = self.issue_refresh_token(&claims.session_id, &claims.sub)?;
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            role: claims.role,
+            expires_in: self.config.jwt_expiry_hours * 3600,
+        })
+    }
+
+    /// Exchange a refresh token for a new token pair
+    ///
+    /// Refresh tokens rotate: each one works once. Presenting a token that was
+    /// already rotated means it leaked, so the whole session is revoked. The
+    /// session must still be active, which ties renewal to sliding expiry.
+    pub async fn renew_token(&self, refresh_token: &str, ip_address: Option<String>) -> Result<TokenPair> {
+        let hash = hash_api_key_secret(refresh_token);
+        let (entry, already_used) = {
+            let mut entry = self.refresh_tokens
+                .get_mut(&hash)
+                .ok_or_else(|| Error::SecurityError("Invalid refresh token".to_string()))?;
+            let already_used = std::mem::replace(&mut entry.used, true);
+            (entry.clone(), already_used)
+        };
+
+        if already_used {
+            self.end_session(&entry.session_id);
+            self.log_audit("token_renewal", &entry.user_id, None, false, Some("Refresh token reuse detected"), ip_address.as_deref(), None, Some(&entry.session_id)).await;
+            warn!("Refresh token reuse for user {}; session {} revoked", entry.user_id, entry.session_id);
+            return Err(Error::SecurityError("Refresh token has already been used; session revoked".to_string()));
+        }
+        if Utc::now() >= entry.expires_at {
+            self.refresh_tokens.remove(&hash);
+            return Err(Error::SecurityError("Refresh token has expired".to_string()));
+        }
+        if !self.touch_session(&entry.session_id) {
+            return Err(Error::SecurityError("Session not found or expired".to_string()));
         }
 
-        self.log_audit("authentication", username, None, true, None, ip_address.as_deref(), user_agent.as_deref(), Some(&session_id)).await;
-        info!("User {} authenticated successfully", username);
+        let role = {
+            let users = self.users.read().await;
+            match users.get(&entry.user_id).filter(|user| user.active) {
+                Some(user) => user.role.clone(),
+                None => {
+                    drop(users);
+                    self.end_session(&entry.session_id);
+                    return Err(Error::SecurityError("User no longer exists or is disabled".to_string()));
+                }
+            }
+        };
+
+        let access_token = self.issue_access_token(&entry.user_id, &role, &entry.session_id)?;
+        let refresh_token = self.issue_refresh_token(&entry.session_id, &entry.user_id)?;
+
+        self.log_audit("token_renewal", &entry.user_id, None, true, None, ip_address.as_deref(), None, Some(&entry.session_id)).await;
+        debug!("Token renewed for user {}", entry.user_id);
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            role,
+            expires_in: self.config.jwt_expiry_hours * 3600,
+        })
+    }
+
+    /// Record activity on a session; idle sessions past the timeout are ended instead
+    fn touch_session(&self, session_id: &str) -> bool {
+        let idle_timeout = chrono::Duration::hours(self.config.session_timeout_hours as i64);
+        let now = Utc::now();
+        let Some(mut session) = self.active_sessions.get_mut(session_id) else {
+            return false;
+        };
+        if now.signed_duration_since(session.last_activity) <= idle_timeout {
+            session.last_activity = now;
+            return true;
+        }
+        drop(session);
+        self.end_session(session_id);
+        false
+    }
+
+    /// End a session along with its refresh tokens
+    fn end_session(&self, session_id: &str) {
+        self.active_sessions.remove(session_id);
+        self.refresh_tokens.retain(|_, token| token.session_id != session_id);
+    }
+
+    /// End every session of a user, returning how many were ended
+    fn end_user_sessions(&self, user_id: &str) -> usize {
+        let user_sessions: Vec<_> = self.active_sessions
+            .iter()
+            .filter(|entry| entry.user_id == user_id)
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for session_id in &user_sessions {
+            self.end_session(session_id);
+        }
+        user_sessions.len()
+    }
+
+    /// Mutual TLS settings, when the TCP transport authenticates by client certificate
+    pub fn mtls_config(&self) -> Option<&MtlsConfig> {
+        self.config.mtls.as_ref()
+    }
+
+    /// Client address allow and deny lists
+    pub fn ip_filter(&self) -> &IpFilter {
+        &self.config.ip_filter
+    }
+
+    /// Reject clients outside the IP allow list or inside the deny list, auditing the rejection
+    pub async fn check_client_address(&self, ip: IpAddr, transport: &str) -> Result<()> {
+        if let Err(reason) = self.config.ip_filter.check(ip) {
+            let ip = ip.to_string();
+            self.log_audit("connection_rejected", "anonymous", Some(transport), false, Some(&reason), Some(&ip), None, None).await;
+            warn!("Rejected {} client: {}", transport, reason);
+            return Err(Error::SecurityError("Client address is not allowed".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Tool output as `role` may see it: Guest responses have the configured component data redacted
+    pub fn redact_for_role(&self, role: &Role, output: serde_json::Value) -> serde_json::Value {
+        match self.redaction_for_role(role) {
+            Some(redaction) => redaction.redact(output),
+            None => output,
+        }
+    }
+
+    /// Component data hidden from `role`, if any
+    pub fn redaction_for_role(&self, role: &Role) -> Option<&RedactionRules> {
+        match role {
+            Role::Guest => Some(&self.config.guest_redaction),
+            _ => None,
+        }
+    }
+
+    /// Start a session for a verified client certificate and return its token
+    ///
+    /// The certificate's CN is mapped to a local user or directly to a role.
+    /// The session lasts until the token is revoked when the connection closes.
+    pub async fn authenticate_client_certificate(&self, common_name: &str, ip_address: Option<String>) -> Result<String> {
+        let mtls = self.config.mtls.as_ref().ok_or_else(|| {
+            Error::SecurityError("Client certificate authentication is not configured".to_string())
+        })?;
+        let identity = match mtls.identity_for(common_name) {
+            Ok(identity) => identity.clone(),
+            Err(e) => {
+                self.log_audit("certificate_authentication", common_name, None, false, Some("Unmapped certificate CN"), ip_address.as_deref(), None, None).await;
+                return Err(e);
+            }
+        };
+
+        let (subject, role) = match identity {
+            CertIdentity::Role(role) => (format!("{}{}", CERT_SUBJECT_PREFIX, common_name), role),
+            CertIdentity::User(username) => {
+                let users = self.users.read().await;
+                match users.get(&username).filter(|user| user.active) {
+                    Some(user) => (user.id.clone(), user.role.clone()),
+                    None => {
+                        drop(users);
+                        self.log_audit("certificate_authentication", &username, None, false, Some("Mapped user missing or disabled"), ip_address.as_deref(), None, None).await;
+                        return Err(Error::SecurityError(format!(
+                            "Certificate user '{}' does not exist or is disabled",
+                            username
+                        )));
+                    }
+                }
+            }
+        };
+
+        let session_id = Uuid::new_v4().to_string();
+        self.active_sessions.insert(session_id.clone(), Session {
+            id: session_id.clone(),
+            user_id: subject.clone(),
+            created_at: Utc::now(),
+            last_activity: Utc::now(),
+            ip_address: ip_address.clone(),
+            user_agent: Some(format!("mtls:{}", common_name)),
+        });
+
+        let token = self.issue_access_token(&subject, &role, &session_id)?;
+
+        self.log_audit("certificate_authentication", &subject, None, true, None, ip_address.as_deref(), None, Some(&session_id)).await;
+        info!("Client certificate '{}' authenticated as {}", common_name, subject);
 
         Ok(token)
     }
 
     /// Validate JWT token and return claims
     pub async fn validate_token(&self, token: &str) -> Result<Claims> {
+        if token.starts_with(API_KEY_PREFIX) {
+            return self.validate_api_key(token);
+        }
+
+        // Tokens signed by the identity provider have no local user or session
+        if let Some(oidc) = &self.oidc {
+            if OidcValidator::handles(token) {
+                let claims = oidc.validate(token).await?;
+                if self.revoked_tokens.contains_key(&claims.jti) {
+                    return Err(Error::SecurityError("Token has been revoked".to_string()));
+                }
+                return Ok(claims);
+            }
+        }
+
         // Check if token is revoked
         let mut validation = Validation::new(Algorithm::HS256);
         validation.leeway = 30; // Allow 30 seconds leeway for clock skew
         
-        let token_data = decode::<Claims>(token, &self.decoding_key, &validation)
-            .map_err(|e| Error::SecurityError(format!("Invalid token: {}", e)))?;
-
-        let claims = token_data.claims;
+        let claims: Claims = self.jwt_keys.verify(token, &validation)?;
 
         // Check if token is revoked
         if self.revoked_tokens.contains_key(&claims.jti) {
             return Err(Error::SecurityError("Token has been revoked".to_string()));
         }
 
-        // Check if session is still active
-        if let Some(session) = self.active_sessions.get(&claims.session_id) {
-            let mut session = session.clone();
-            session.last_activity = Utc::now();
-            self.active_sessions.insert(claims.session_id.clone(), session);
-        } else {
+        // Check if session is still active; activity slides its idle expiry
+        if !self.touch_session(&claims.session_id) {
             return Err(Error::SecurityError("Session not found or expired".to_string()));
         }
 
+        // Certificate identities mapped straight to a role have no local user
+        if claims.sub.starts_with(CERT_SUBJECT_PREFIX) {
+            return Ok(claims);
+        }
+
         // Verify user still exists and is active
         let users = self.users.read().await;
         let user = users.get(&claims.sub).ok_or_else(|| 
@@ -404,6 +945,7 @@ impl SecurityManager {
     /// Check if user has permission for a specific operation
     pub async fn check_permission(&self, token: &str, required_role: &Role, operation: &str) -> Result<Claims> {
         let claims = self.validate_token(token).await?;
+        self.check_api_key_scope(&claims, operation)?;
         
         if !claims.role.has_permission(required_role) {
             self.log_audit("authorization", &claims.sub, Some(operation), false, Some("Insufficient permissions"), None, None, Some(&claims.session_id)).await;
@@ -425,8 +967,8 @@ impl SecurityManager {
         // Add to revoked tokens
         self.revoked_tokens.insert(claims.jti.clone(), Utc::now());
         
-        // Remove active session
-        self.active_sessions.remove(&claims.session_id);
+        // Remove active session and its refresh tokens
+        self.end_session(&claims.session_id);
         
         self.log_audit("token_revocation", &claims.sub, None, true, None, None, None, Some(&claims.session_id)).await;
         info!("Token revoked for user {}", claims.sub);
@@ -434,10 +976,137 @@ impl SecurityManager {
         Ok(())
     }
 
-    /// Record a failed login attempt
-    async fn record_failed_login(&self, username: &str) {
+    /// Validate an API key and return claims for it
+    fn validate_api_key(&self, key: &str) -> Result<Claims> {
+        let invalid = || Error::SecurityError("Invalid API key".to_string());
+        let (id, secret) = key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('.'))
+            .ok_or_else(invalid)?;
+
+        let mut api_key = self.api_keys.get_mut(id).ok_or_else(invalid)?;
+        let expected = api_key.key_hash.as_bytes();
+        let actual = hash_api_key_secret(secret);
+        let matches = expected.len() == actual.len()
+            && expected
+                .iter()
+                .zip(actual.as_bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0;
+        if !matches {
+            return Err(invalid());
+        }
+
+        let now = Utc::now();
+        if api_key.expires_at.is_some_and(|expires_at| now >= expires_at) {
+            return Err(Error::SecurityError("API key has expired".to_string()));
+        }
+        api_key.last_used = Some(now);
+
+        Ok(Claims {
+            sub: format!("apikey:{}", id),
+            role: api_key.role.clone(),
+            exp: api_key
+                .expires_at
+                .map_or(u64::MAX, |expires_at| expires_at.timestamp().max(0) as u64),
+            iat: api_key.created_at.timestamp().max(0) as u64,
+            jti: format!("apikey:{}", id),
+            session_id: format!("apikey:{}", id),
+        })
+    }
+
+    /// Reject operations outside an API key's scopes
+    pub fn check_api_key_scope(&self, claims: &Claims, operation: &str) -> Result<()> {
+        let Some(id) = claims.sub.strip_prefix("apikey:") else {
+            return Ok(());
+        };
+        let api_key = self
+            .api_keys
+            .get(id)
+            .ok_or_else(|| Error::SecurityError("API key has been revoked".to_string()))?;
+        if !api_key.scopes.is_empty() && !api_key.scopes.iter().any(|scope| scope == operation) {
+            return Err(Error::SecurityError(format!(
+                "API key '{}' is not scoped for operation: {}",
+                api_key.name, operation
+            )));
+        }
+        Ok(())
+    }
+
+    /// Create an API key (admin only); the key is returned once and only its hash is kept
+    pub async fn create_api_key(
+        &self,
+        token: &str,
+        name: &str,
+        role: Role,
+        scopes: Vec<String>,
+        expires_in_days: Option<u32>,
+    ) -> Result<(ApiKey, String)> {
+        let claims = self.check_permission(token, &Role::Admin, "api_key_management").await?;
+        if name.trim().is_empty() {
+            return Err(Error::SecurityError("API key name must not be empty".to_string()));
+        }
+
+        let id = Uuid::new_v4().simple().to_string()[..12].to_string();
+        let mut secret_bytes = [0u8; 32];
+        ring::rand::SecureRandom::fill(&ring::rand::SystemRandom::new(), &mut secret_bytes)
+            .map_err(|_| Error::SecurityError("Failed to generate API key".to_string()))?;
+        let secret = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(secret_bytes);
+
+        let now = Utc::now();
+        let api_key = ApiKey {
+            id: id.clone(),
+            name: name.to_string(),
+            key_hash: hash_api_key_secret(&secret),
+            role,
+            scopes,
+            created_by: claims.sub.clone(),
+            created_at: now,
+            expires_at: expires_in_days.map(|days| now + chrono::Duration::days(days as i64)),
+            last_used: None,
+        };
+        self.api_keys.insert(id.clone(), api_key.clone());
+        self.persist().await?;
+
+        self.log_audit("api_key_created", &claims.sub, Some(id.as_str()), true, None, None, None, Some(&claims.session_id)).await;
+        info!("API key {} ({}) created by {}", id, name, claims.sub);
+        Ok((api_key, format!("{}{}.{}", API_KEY_PREFIX, id, secret)))
+    }
+
+    /// Revoke an API key (admin only)
+    pub async fn revoke_api_key(&self, token: &str, id: &str) -> Result<()> {
+        let claims = self.check_permission(token, &Role::Admin, "api_key_management").await?;
+        if self.api_keys.remove(id).is_none() {
+            return Err(Error::SecurityError("API key not found".to_string()));
+        }
+        self.persist().await?;
+
+        self.log_audit("api_key_revoked", &claims.sub, Some(id), true, None, None, None, Some(&claims.session_id)).await;
+        info!("API key {} revoked by {}", id, claims.sub);
+        Ok(())
+    }
+
+    /// List API keys (admin only)
+    pub async fn list_api_keys(&self, token: &str) -> Result<Vec<ApiKey>> {
+        self.check_permission(token, &Role::Admin, "api_key_management").await?;
+
+        let mut keys: Vec<ApiKey> = self.api_keys.iter().map(|entry| entry.value().clone()).collect();
+        keys.sort_by_key(|k| k.created_at);
+        Ok(keys)
+    }
+
+    /// Record a failed login attempt against the username and the source address
+    async fn record_failed_login(&self, username: &str, ip_address: Option<&str>) {
         let now = Utc::now();
         
+        if let Some(ip) = ip_address.and_then(|ip| ip.parse::<IpAddr>().ok()) {
+            if let Some(banned_until) = self.login_throttle.record_failure(ip, now) {
+                let reason = format!("Too many failed logins; banned until {}", banned_until.to_rfc3339());
+                self.log_audit("ip_ban", username, Some(&ip.to_string()), true, Some(&reason), ip_address, None, None).await;
+                warn!("IP address {} banned until {} after repeated failed logins", ip, banned_until);
+            }
+        }
+
         match self.failed_logins.get_mut(username) {
             Some(mut entry) => {
                 entry.count += 1;
@@ -458,11 +1127,40 @@ impl SecurityManager {
                 self.failed_logins.insert(username.to_string(), failed);
             }
         }
+
+        // Unknown usernames are counted the same way so they can't be told
+        // apart, but only in memory and only up to a limit
+        if !self.users.read().await.contains_key(username) {
+            self.forget_oldest_unknown_failed_logins().await;
+            return;
+        }
+        if let Err(e) = self.persist().await {
+            warn!("Failed to save lockout state: {}", e);
+        }
+    }
+
+    /// Drop the least recent failed logins of unknown usernames beyond [`MAX_UNKNOWN_FAILED_LOGINS`]
+    async fn forget_oldest_unknown_failed_logins(&self) {
+        let users = self.users.read().await;
+        let mut unknown: Vec<(String, DateTime<Utc>)> = self
+            .failed_logins
+            .iter()
+            .filter(|entry| !users.contains_key(entry.key()))
+            .map(|entry| (entry.key().clone(), entry.value().last_attempt))
+            .collect();
+        drop(users);
+        if unknown.len() <= MAX_UNKNOWN_FAILED_LOGINS {
+            return;
+        }
+        unknown.sort_by_key(|(_, last_attempt)| *last_attempt);
+        for (username, _) in unknown.iter().take(unknown.len() - MAX_UNKNOWN_FAILED_LOGINS) {
+            self.failed_logins.remove(username);
+        }
     }
 
     /// Log an audit entry
     async fn log_audit(&self, action: &str, user_id: &str, resource: Option<&str>, success: bool, error_message: Option<&str>, ip_address: Option<&str>, user_agent: Option<&str>, session_id: Option<&str>) {
-        let entry = AuditEntry {
+        let mut entry = AuditEntry {
             id: Uuid::new_v4().to_string(),
             user_id: user_id.to_string(),
             username: user_id.to_string(), // For simplicity, using user_id as username
@@ -474,8 +1172,13 @@ impl SecurityManager {
             ip_address: ip_address.map(|s| s.to_string()),
             user_agent: user_agent.map(|s| s.to_string()),
             session_id: session_id.map(|s| s.to_string()),
+            prev_hash: None,
+            hash: None,
+            signature: None,
         };
 
+        self.audit_logger.record(&mut entry).await;
+
         let mut audit_log = self.audit_log.write().await;
         audit_log.push(entry);
 
@@ -484,6 +1187,60 @@ impl SecurityManager {
         audit_log.retain(|entry| entry.timestamp > retention_cutoff);
     }
 
+    /// Rotate the audit log file now and apply retention (admin only)
+    pub async fn rotate_audit_log(&self, token: &str) -> Result<AuditRotation> {
+        let claims = self.check_permission(token, &Role::Admin, "audit_log_access").await?;
+        let audit_file = self.audit_logger.file().ok_or_else(|| {
+            Error::SecurityError("Audit log persistence is disabled".to_string())
+        })?;
+
+        let rotation = audit_file.rotate().await?;
+        let rotated_to = rotation.rotated_to.as_ref().map(|path| path.display().to_string());
+        self.log_audit("audit_log_rotation", &claims.sub, rotated_to.as_deref(), true, None, None, None, Some(&claims.session_id)).await;
+        info!("Audit log rotated by {}; {} old files removed", claims.sub, rotation.removed);
+        Ok(rotation)
+    }
+
+    /// Check the persisted audit log's hash chain and signatures (admin only)
+    pub async fn verify_audit_log(&self, token: &str) -> Result<AuditVerification> {
+        let claims = self.check_permission(token, &Role::Admin, "audit_log_access").await?;
+        let audit_file = self.audit_logger.file().ok_or_else(|| {
+            Error::SecurityError("Audit log persistence is disabled".to_string())
+        })?;
+
+        let entries = audit_file.read_entries().await?;
+        let report = verify_chain(&entries, self.config.audit_signing_key.as_deref());
+        let problem = report.first_break.as_ref().map(|b| format!("Entry {} ({}): {}", b.index, b.entry_id, b.reason));
+        self.log_audit("audit_log_verification", &claims.sub, None, report.valid, problem.as_deref(), None, None, Some(&claims.session_id)).await;
+        if !report.valid {
+            error!("Audit log verification failed: {}", problem.as_deref().unwrap_or("unknown"));
+        }
+        Ok(report)
+    }
+
+    /// Start signing tokens with a new random key (admin only)
+    ///
+    /// Tokens signed by the previous key stay valid for the configured grace
+    /// window, so live sessions are not cut off.
+    pub async fn rotate_signing_key(&self, token: &str) -> Result<SigningKeyInfo> {
+        let claims = self.check_permission(token, &Role::Admin, "signing_key_management").await?;
+
+        let result = self.jwt_keys.rotate(None);
+        let kid = result.as_ref().ok().map(|key| key.kid.clone());
+        let err = result.as_ref().err().map(|e| e.to_string());
+        self.log_audit("signing_key_rotation", &claims.sub, kid.as_deref(), result.is_ok(), err.as_deref(), None, None, Some(&claims.session_id)).await;
+        if let Ok(key) = &result {
+            info!("Admin {} rotated the JWT signing key to {}", claims.sub, key.kid);
+        }
+        result
+    }
+
+    /// JWT signing keys that still validate tokens (admin only)
+    pub async fn list_signing_keys(&self, token: &str) -> Result<Vec<SigningKeyInfo>> {
+        self.check_permission(token, &Role::Admin, "signing_key_management").await?;
+        Ok(self.jwt_keys.keys())
+    }
+
     /// Get audit log entries (admin only)
     pub async fn get_audit_log(&self, token: &str, limit: Option<usize>, offset: Option<usize>) -> Result<Vec<AuditEntry>> {
         self.check_permission(token, &Role::Admin, "audit_log_access").await?;
@@ -499,6 +1256,16 @@ impl SecurityManager {
         Ok(audit_log[start..end].to_vec())
     }
 
+    /// The in-memory audit log as seen by the process memory budget
+    ///
+    /// Eviction only trims the copy kept in memory; the audit file and any
+    /// configured sink keep every entry.
+    pub fn audit_memory_consumer(&self) -> Arc<dyn MemoryConsumer> {
+        Arc::new(AuditMemory {
+            audit_log: self.audit_log.clone(),
+        })
+    }
+
     /// Create a new user (admin only)
     pub async fn create_user(&self, token: &str, username: &str, password: &str, role: Role) -> Result<()> {
         self.check_permission(token, &Role::Admin, "user_management").await?;
@@ -512,6 +1279,10 @@ impl SecurityManager {
             created_at: Utc::now(),
             last_login: None,
             active: true,
+            two_factor: None,
+            password_changed_at: Some(Utc::now()),
+            password_history: Vec::new(),
+            must_change_password: self.config.force_initial_password_change,
         };
 
         let mut users = self.users.write().await;
@@ -520,11 +1291,131 @@ impl SecurityManager {
         }
         
         users.insert(username.to_string(), user);
+        drop(users);
+        self.persist().await?;
         info!("User {} created", username);
         
         Ok(())
     }
 
+    /// Change a user's password (the user themselves or an admin)
+    pub async fn change_password(&self, token: &str, username: &str, new_password: &str) -> Result<()> {
+        let claims = self.validate_token(token).await?;
+        if claims.sub != username {
+            self.check_permission(token, &Role::Admin, "user_management").await?;
+        }
+
+        let current = self.users.read().await.get(username).cloned()
+            .ok_or_else(|| Error::SecurityError("User not found".to_string()))?;
+        let (password_hash, history) = self.prepare_password_change(&current, new_password)?;
+        let mut users = self.users.write().await;
+        let user = users
+            .get_mut(username)
+            .ok_or_else(|| Error::SecurityError("User not found".to_string()))?;
+        user.password_hash = password_hash;
+        user.password_history = history;
+        user.password_changed_at = Some(Utc::now());
+        // A password set by an admin is temporary, like an initial password
+        user.must_change_password = claims.sub != username && self.config.force_initial_password_change;
+        drop(users);
+
+        // A new password lifts any lockout
+        self.failed_logins.remove(username);
+        self.persist().await?;
+
+        self.log_audit("password_change", username, None, true, None, None, None, Some(&claims.session_id)).await;
+        info!("Password changed for user {}", username);
+        Ok(())
+    }
+
+    /// Start two-factor enrollment for the caller's own account
+    ///
+    /// Only Admin and Developer accounts can enroll, since they can change live
+    /// game state. The returned secret and recovery codes are shown once; the
+    /// second factor is not enforced until `confirm_two_factor` succeeds.
+    pub async fn enroll_two_factor(&self, token: &str) -> Result<TotpEnrollment> {
+        let claims = self.validate_token(token).await?;
+        if !claims.role.has_permission(&Role::Developer) {
+            return Err(Error::SecurityError("Two-factor authentication is only available to Admin and Developer accounts".to_string()));
+        }
+
+        let mut users = self.users.write().await;
+        let user = users
+            .get_mut(&claims.sub)
+            .ok_or_else(|| Error::SecurityError("Two-factor authentication requires a local user account".to_string()))?;
+        if user.two_factor.as_ref().is_some_and(|tf| tf.enabled) {
+            return Err(Error::SecurityError("Two-factor authentication is already enabled".to_string()));
+        }
+        let (two_factor, enrollment) = TwoFactor::enroll(&user.username, TOTP_ISSUER)?;
+        user.two_factor = Some(two_factor);
+        drop(users);
+        self.persist().await?;
+
+        self.log_audit("two_factor_enroll", &claims.sub, None, true, None, None, None, Some(&claims.session_id)).await;
+        Ok(enrollment)
+    }
+
+    /// Enable two-factor authentication once the user proves their authenticator works
+    pub async fn confirm_two_factor(&self, token: &str, code: &str) -> Result<()> {
+        let claims = self.validate_token(token).await?;
+
+        let mut users = self.users.write().await;
+        let two_factor = users
+            .get_mut(&claims.sub)
+            .and_then(|user| user.two_factor.as_mut())
+            .ok_or_else(|| Error::SecurityError("No two-factor enrollment in progress".to_string()))?;
+        if two_factor.enabled {
+            return Err(Error::SecurityError("Two-factor authentication is already enabled".to_string()));
+        }
+        // Recovery codes are not accepted here; the authenticator itself must work
+        if !code.trim().chars().all(|c| c.is_ascii_digit()) || !two_factor.verify(code, Utc::now().timestamp().max(0) as u64) {
+            drop(users);
+            self.log_audit("two_factor_confirm", &claims.sub, None, false, Some("Invalid two-factor code"), None, None, Some(&claims.session_id)).await;
+            return Err(Error::SecurityError("Invalid two-factor code".to_string()));
+        }
+        two_factor.enabled = true;
+        drop(users);
+        self.persist().await?;
+
+        self.log_audit("two_factor_confirm", &claims.sub, None, true, None, None, None, Some(&claims.session_id)).await;
+        info!("Two-factor authentication enabled for user {}", claims.sub);
+        Ok(())
+    }
+
+    /// Remove a user's second factor (the user themselves or an admin)
+    ///
+    /// Users turning off their own need a current TOTP or recovery code, so a
+    /// stolen session alone can't remove it. Admins can turn off another
+    /// user's without one.
+    pub async fn disable_two_factor(&self, token: &str, username: &str, code: Option<&str>) -> Result<()> {
+        let claims = self.validate_token(token).await?;
+        let own_account = claims.sub == username;
+        if !own_account {
+            self.check_permission(token, &Role::Admin, "user_management").await?;
+        }
+
+        let mut users = self.users.write().await;
+        let user = users
+            .get_mut(username)
+            .ok_or_else(|| Error::SecurityError("User not found".to_string()))?;
+        let two_factor = user
+            .two_factor
+            .as_mut()
+            .ok_or_else(|| Error::SecurityError("Two-factor authentication is not enabled".to_string()))?;
+        if own_account && !code.is_some_and(|code| two_factor.verify(code, Utc::now().timestamp().max(0) as u64)) {
+            drop(users);
+            self.log_audit("two_factor_disable", username, None, false, Some("Invalid two-factor code"), None, None, Some(&claims.session_id)).await;
+            return Err(Error::SecurityError("A current two-factor or recovery code is required".to_string()));
+        }
+        user.two_factor = None;
+        drop(users);
+        self.persist().await?;
+
+        self.log_audit("two_factor_disable", username, None, true, None, None, None, Some(&claims.session_id)).await;
+        info!("Two-factor authentication disabled for user {}", username);
+        Ok(())
+    }
+
     /// Delete a user (admin only)
     pub async fn delete_user(&self, token: &str, username: &str) -> Result<()> {
         let claims = self.check_permission(token, &Role::Admin, "user_management").await?;
@@ -538,17 +1429,12 @@ impl SecurityManager {
         if users.remove(username).is_none() {
             return Err(Error::SecurityError("User not found".to_string()));
         }
+        drop(users);
+        self.failed_logins.remove(username);
+        self.persist().await?;
         
         // Revoke all sessions for this user
-        let user_sessions: Vec<_> = self.active_sessions
-            .iter()
-            .filter(|entry| entry.user_id == username)
-            .map(|entry| entry.key().clone())
-            .collect();
-            
-        for session_id in user_sessions {
-            self.active_sessions.remove(&session_id);
-        }
+        self.end_user_sessions(username);
         
         info!("User {} deleted", username);
         Ok(())
@@ -565,18 +1451,117 @@ impl SecurityManager {
         Ok(user_list)
     }
 
+    /// The tool access policy currently in force
+    pub fn tool_policy(&self) -> Arc<RbacPolicy> {
+        self.policy.current()
+    }
+
+    /// Spend one call of the caller's per-role and per-tool quota
+    pub async fn check_rate_limit(&self, claims: &Claims, operation: &str) -> Result<()> {
+        if let Err(e) = self.user_rate_limiter.check(&claims.sub, &claims.role, operation) {
+            self.log_audit("rate_limit", &claims.sub, Some(operation), false, Some(&e.to_string()), None, None, Some(&claims.session_id)).await;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// The caller's remaining quotas; reloading the limits file first requires Admin role
+    pub async fn rate_limit_status(&self, token: &str, reload: bool) -> Result<RateLimitStatus> {
+        let claims = if reload {
+            let claims = self.check_permission(token, &Role::Admin, "policy_management").await?;
+            self.user_rate_limiter.reload()?;
+            self.log_audit("rate_limit_reload", &claims.sub, None, true, None, None, None, Some(&claims.session_id)).await;
+            claims
+        } else {
+            self.validate_token(token).await?
+        };
+        Ok(self.user_rate_limiter.status(&claims.sub, &claims.role))
+    }
+
+    /// Test the tool policy for a role or user (admin only), optionally reloading it first
+    pub async fn check_policy(&self, token: &str, operation: &str, role: Role, user_id: Option<&str>, reload: bool) -> Result<PolicyDecision> {
+        let claims = self.check_permission(token, &Role::Admin, "policy_management").await?;
+        if reload {
+            self.policy.reload()?;
+            self.log_audit("policy_reload", &claims.sub, self.policy.path().and_then(|p| p.to_str()), true, None, None, None, Some(&claims.session_id)).await;
+        }
+        Ok(self.policy.current().decide(operation, &role, user_id.unwrap_or_default()))
+    }
+
     /// Get active sessions (admin only)
     pub async fn get_active_sessions(&self, token: &str) -> Result<Vec<Session>> {
         self.check_permission(token, &Role::Admin, "session_management").await?;
         
-        let sessions: Vec<Session> = self.active_sessions
+        let mut sessions: Vec<Session> = self.active_sessions
             .iter()
             .map(|entry| entry.value().clone())
             .collect();
+        sessions.sort_by_key(|s| Reverse(s.last_activity));
             
         Ok(sessions)
     }
 
+    /// Current security counters (admin only)
+    pub async fn security_metrics(&self, token: &str) -> Result<SecurityMetrics> {
+        self.check_permission(token, &Role::Admin, "security_scan").await?;
+
+        let now = Utc::now();
+        let hour_ago = now - chrono::Duration::hours(1);
+        let mut recent_failures = HashMap::new();
+        for entry in self.audit_log.read().await.iter().rev() {
+            if entry.timestamp < hour_ago {
+                break;
+            }
+            if !entry.success {
+                *recent_failures.entry(entry.action.clone()).or_insert(0) += 1;
+            }
+        }
+
+        Ok(SecurityMetrics {
+            users: self.users.read().await.len(),
+            active_sessions: self.active_sessions.len(),
+            api_keys: self.api_keys.len(),
+            revoked_tokens: self.revoked_tokens.len(),
+            locked_accounts: self.failed_logins
+                .iter()
+                .filter(|failed| failed.locked_until.is_some_and(|until| until > now))
+                .count(),
+            failed_login_attempts: self.failed_logins.iter().map(|failed| failed.count).sum(),
+            banned_ips: self.login_throttle.banned(now).len(),
+            audit_entries: self.audit_log.read().await.len(),
+            recent_failures,
+        })
+    }
+
+    /// Force-end one session (admin only)
+    ///
+    /// Every access token issued for the session stops validating and its
+    /// refresh tokens are dropped.
+    pub async fn revoke_session(&self, token: &str, session_id: &str) -> Result<Session> {
+        let claims = self.check_permission(token, &Role::Admin, "session_management").await?;
+
+        let session = self.active_sessions
+            .get(session_id)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| Error::SecurityError("Session not found".to_string()))?;
+        self.end_session(session_id);
+
+        self.log_audit("session_revocation", &session.user_id, Some(session_id), true, None, session.ip_address.as_deref(), None, Some(&claims.session_id)).await;
+        info!("Session {} of user {} revoked by {}", session_id, session.user_id, claims.sub);
+        Ok(session)
+    }
+
+    /// Force-end all sessions of a user (admin only), returning how many were ended
+    pub async fn revoke_user_sessions(&self, token: &str, user_id: &str) -> Result<usize> {
+        let claims = self.check_permission(token, &Role::Admin, "session_management").await?;
+
+        let ended = self.end_user_sessions(user_id);
+        let resource = format!("{} sessions", ended);
+        self.log_audit("session_revocation", user_id, Some(&resource), true, None, None, None, Some(&claims.session_id)).await;
+        info!("{} sessions of user {} revoked by {}", ended, user_id, claims.sub);
+        Ok(ended)
+    }
+
     /// Cleanup expired sessions and revoked tokens
     pub async fn cleanup(&self) {
         let now = Utc::now();
@@ -590,7 +1575,16 @@ impl SecurityManager {
             .collect();
             
         for session_id in expired_sessions {
-            self.active_sessions.remove(&session_id);
+            self.end_session(&session_id);
+        }
+
+        // Remove expired refresh tokens, including used ones kept for reuse detection
+        self.refresh_tokens.retain(|_, token| token.expires_at > now && self.active_sessions.contains_key(&token.session_id));
+        self.login_throttle.prune(now);
+
+        let retired_keys = self.jwt_keys.prune();
+        if retired_keys > 0 {
+            info!("Dropped {} JWT signing keys past their grace window", retired_keys);
         }
         
         // Remove old revoked tokens (keep for JWT expiry time)
@@ -598,6 +1592,14 @@ impl SecurityManager {
         let revoked_cutoff = now - token_retention;
         
         self.revoked_tokens.retain(|_, &mut revoked_at| revoked_at > revoked_cutoff);
+        self.user_rate_limiter.prune();
+
+        // Rotate an aged audit log file and drop rotated files past retention
+        if let Some(audit_file) = self.audit_logger.file() {
+            if let Err(e) = audit_file.maintain().await {
+                warn!("Audit log maintenance failed: {}", e);
+            }
+        }
         
         debug!("Security cleanup completed");
     }
@@ -607,18 +1609,75 @@ impl Clone for SecurityManager {
     fn clone(&self) -> Self {
         Self {
             config: self.config.clone(),
-            encoding_key: self.encoding_key.clone(),
-            decoding_key: self.decoding_key.clone(),
+            jwt_keys: self.jwt_keys.clone(),
             users: self.users.clone(),
             revoked_tokens: self.revoked_tokens.clone(),
             active_sessions: self.active_sessions.clone(),
             failed_logins: self.failed_logins.clone(),
+            login_throttle: self.login_throttle.clone(),
             audit_log: self.audit_log.clone(),
             rate_limiter: self.rate_limiter.clone(),
+            store: self.store.clone(),
+            oidc: self.oidc.clone(),
+            api_keys: self.api_keys.clone(),
+            refresh_tokens: self.refresh_tokens.clone(),
+            policy: self.policy.clone(),
+            user_rate_limiter: self.user_rate_limiter.clone(),
+            audit_logger: self.audit_logger.clone(),
+        }
+    }
+}
+
+struct AuditMemory {
+    audit_log: Arc<RwLock<Vec<AuditEntry>>>,
+}
+
+fn audit_entry_size(entry: &AuditEntry) -> usize {
+    serde_json::to_vec(entry).map(|v| v.len()).unwrap_or(0)
+}
+
+#[async_trait]
+impl MemoryConsumer for AuditMemory {
+    fn name(&self) -> &'static str {
+        "audit_log"
+    }
+
+    /// The audit trail is the last thing to give up
+    fn eviction_priority(&self) -> u8 {
+        90
+    }
+
+    async fn memory_usage(&self) -> usize {
+        self.audit_log.read().await.iter().map(audit_entry_size).sum()
+    }
+
+    async fn evict(&self, bytes: usize) -> usize {
+        let mut audit_log = self.audit_log.write().await;
+        let mut freed = 0;
+        let evicted = audit_log
+            .iter()
+            .take_while(|entry| {
+                let fits = freed < bytes;
+                if fits {
+                    freed += audit_entry_size(entry);
+                }
+                fits
+            })
+            .count();
+        audit_log.drain(..evicted);
+        if evicted > 0 {
+            warn!("Memory budget dropped the {} oldest in-memory audit entries", evicted);
         }
+        freed
     }
 }
 
+/// Hash of an API key secret as stored at rest
+fn hash_api_key_secret(secret: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, secret.as_bytes());
+    base64::engine::general_purpose::STANDARD_NO_PAD.encode(digest.as_ref())
+}
+
 /// Security middleware for tool access control
 #[derive(Clone)]
 pub struct SecurityMiddleware {
@@ -630,21 +1689,14 @@ impl SecurityMiddleware {
         Self { security_manager }
     }
 
-    /// Check if a tool operation is allowed for the given role
+    /// Check if a tool operation is allowed for the given role under the built-in policy
     pub fn check_tool_permission(operation: &str, role: &Role) -> bool {
-        match operation {
-            // Viewer permissions (read-only operations)
-            "observe" | "hypothesis" | "detect_anomaly" => role.level() >= 1,
-            
-            // Developer permissions (can modify state)
-            "experiment" | "stress_test" | "time_travel_replay" => role.level() >= 2,
-            
-            // Admin permissions (system management)
-            "user_management" | "audit_log_access" | "session_management" => role.level() >= 3,
-            
-            // Default to requiring developer role
-            _ => role.level() >= 2,
-        }
+        RbacPolicy::default().decide(operation, role, "").allowed
+    }
+
+    /// Check a connecting client's address against the IP allow and deny lists
+    pub async fn check_client_address(&self, ip: IpAddr, transport: &str) -> Result<()> {
+        self.security_manager.check_client_address(ip, transport).await
     }
 
     /// Validate token and check permissions for a tool operation
@@ -655,15 +1707,19 @@ impl SecurityMiddleware {
 
         // Validate token
         let claims = self.security_manager.validate_token(token).await?;
+        self.security_manager.check_api_key_scope(&claims, operation)?;
 
-        // Check tool-specific permissions
-        if !Self::check_tool_permission(operation, &claims.role) {
+        // Check tool-specific permissions against the configured policy
+        let policy = self.security_manager.tool_policy();
+        if !policy.decide(operation, &claims.role, &claims.sub).allowed {
             return Err(Error::SecurityError(format!(
                 "Insufficient permissions for operation: {}",
                 operation
             )));
         }
 
+        self.security_manager.check_rate_limit(&claims, operation).await?;
+
         Ok(claims)
     }
 }
@@ -681,8 +1737,44 @@ impl SecurityAudit {
 
     /// Run security vulnerability scan
     pub async fn run_security_scan(&self, token: &str) -> Result<SecurityScanReport> {
-        self.security_manager.check_permission(token, &Role::Admin, "security_scan").await?;
-        
+        let claims = self.security_manager.check_permission(token, &Role::Admin, "security_scan").await?;
+        let report = self.scan().await;
+        self.security_manager.log_audit("security_scan", &claims.sub, None, true, None, None, None, Some(&claims.session_id)).await;
+        Ok(report)
+    }
+
+    /// Scan and record every finding in the audit log; used by scheduled scans
+    pub async fn run_scheduled_scan(&self) -> SecurityScanReport {
+        let report = self.scan().await;
+        for (vulnerability, recommendation) in report.vulnerabilities.iter().zip(&report.recommendations) {
+            self.security_manager.log_audit("security_scan_finding", "system", Some(vulnerability), false, Some(recommendation), None, None, None).await;
+        }
+        let summary = format!("{} findings", report.vulnerabilities.len());
+        self.security_manager.log_audit("security_scan", "system", Some(&summary), true, None, None, None, None).await;
+        if !report.vulnerabilities.is_empty() {
+            warn!("Scheduled security scan found {} issues: {}", report.vulnerabilities.len(), report.vulnerabilities.join("; "));
+        }
+        report
+    }
+
+    /// Run scheduled scans at the configured interval; `None` when scheduling is disabled
+    pub fn start_scheduled_scans(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let minutes = self.security_manager.config.security_scan_interval_minutes;
+        if minutes == 0 {
+            return None;
+        }
+        let audit = self.clone();
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(minutes * 60));
+            loop {
+                interval.tick().await;
+                audit.run_scheduled_scan().await;
+            }
+        }))
+    }
+
+    /// Check the configuration and users for known weaknesses
+    pub async fn scan(&self) -> SecurityScanReport {
         let mut report = SecurityScanReport {
             scan_time: Utc::now(),
             vulnerabilities: Vec::new(),
@@ -699,7 +1791,7 @@ impl SecurityAudit {
         }
 
         // Check for weak JWT secret
-        if self.security_manager.config.jwt_secret.contains("change_in_production") {
+        if is_default_secret(&self.security_manager.config.jwt_secret) {
             report.vulnerabilities.push("Default JWT secret detected".to_string());
             report.recommendations.push("Configure a strong, random JWT secret".to_string());
         }
@@ -711,10 +1803,28 @@ impl SecurityAudit {
         }
 
         info!("Security scan completed, found {} vulnerabilities", report.vulnerabilities.len());
-        Ok(report)
+        report
     }
 }
 
+/// Counters describing the current security state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityMetrics {
+    pub users: usize,
+    pub active_sessions: usize,
+    pub api_keys: usize,
+    pub revoked_tokens: usize,
+    /// Accounts currently locked out after failed logins
+    pub locked_accounts: usize,
+    /// Failed login attempts tracked towards lockout
+    pub failed_login_attempts: u32,
+    /// Source addresses temporarily banned after repeated failed logins
+    pub banned_ips: usize,
+    pub audit_entries: usize,
+    /// Failed audit events in the last hour, by action
+    pub recent_failures: HashMap<String, usize>,
+}
+
 /// Security scan report
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SecurityScanReport {
@@ -23,14 +23,25 @@ use tracing::{info, warn, error};
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
+use crate::audit_sinks::{parse_audit_sinks, AuditSinkConfig};
+use crate::ip_filter::IpFilter;
+use crate::redaction::RedactionRules;
+use crate::mtls::MtlsConfig;
+use crate::password_policy::{parse_character_classes, CharacterClass, PasswordPolicy};
+use crate::oidc::OidcConfig;
+use crate::secrets::{is_default_secret, SecretResolver};
 
 /// Production-ready security configuration with environment variable support
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductionSecurityConfig {
-    /// JWT signing secret - MUST be set via environment variable in production
+    /// JWT signing secret - MUST come from a secrets provider in production
     pub jwt_secret: String,
     /// JWT token expiry time in hours
     pub jwt_expiry_hours: u64,
+    /// Hours a rotated-out JWT signing key keeps validating tokens
+    pub jwt_key_grace_hours: u64,
+    /// Refresh token lifetime in hours; renewal also needs the session to be active
+    pub refresh_token_expiry_hours: u64,
     /// Rate limiting: requests per minute per IP
     pub rate_limit_per_ip: u32,
     /// Rate limiting: requests per minute per user
@@ -43,22 +54,72 @@ pub struct ProductionSecurityConfig {
     pub password_require_complexity: bool,
     /// Password must not be in common password list
     pub password_blacklist_check: bool,
+    /// Character classes required when complexity is enabled
+    pub password_required_classes: Vec<CharacterClass>,
+    /// Previous passwords a user cannot reuse
+    pub password_history_size: usize,
+    /// Days until a password must be changed; 0 disables expiry
+    pub password_max_age_days: u64,
     /// Session timeout in hours
     pub session_timeout_hours: u64,
     /// Maximum failed login attempts before lockout
     pub max_failed_logins: u32,
     /// Account lockout duration in minutes
     pub lockout_duration_minutes: u64,
+    /// Failed logins from one IP, across all usernames, before it is banned; 0 disables bans
+    pub ip_max_failed_logins: u32,
+    /// IP ban duration in minutes; failures are also forgotten after this long
+    pub ip_ban_duration_minutes: u64,
+    /// Delay after the first failure from an IP, doubled for each further failure
+    pub ip_failure_delay_ms: u64,
     /// Audit log retention in days
     pub audit_log_retention_days: u64,
     /// Enable persistent audit logging
     pub audit_log_persistence: bool,
+    /// Audit log file written when persistence is enabled
+    pub audit_log_path: String,
+    /// Rotate the audit log file once it reaches this size
+    pub audit_log_max_size_mb: u64,
+    /// Rotate the audit log file after this many hours; 0 rotates on size only
+    pub audit_log_rotate_hours: u64,
+    /// Compressed audit log files to keep; 0 keeps all within retention
+    pub audit_log_max_files: usize,
+    /// Where audit entries are exported besides memory and the local file
+    pub audit_sinks: Vec<AuditSinkConfig>,
+    /// Bearer token for HTTP audit sinks
+    pub audit_http_token: Option<String>,
+    /// Chain audit entries by hash so edits to the log can be detected
+    pub audit_integrity: bool,
+    /// Key periodic audit signatures are made with
+    pub audit_signing_key: Option<String>,
+    /// Sign every this many audit entries when a signing key is set
+    pub audit_signature_interval: u64,
     /// Force password change on first login
     pub force_initial_password_change: bool,
     /// Enable account lockout recovery
     pub enable_lockout_recovery: bool,
     /// Production mode (stricter security)
     pub production_mode: bool,
+    /// File users are persisted to; users are kept in memory only when unset
+    pub user_store_path: Option<String>,
+    /// Key the user store file is encrypted with
+    pub user_store_key: Option<String>,
+    /// Accept tokens from an external identity provider alongside local JWTs
+    pub oidc: Option<OidcConfig>,
+    /// Tool access policy file (TOML or JSON); the built-in policy is used when unset
+    pub tool_policy_path: Option<String>,
+    /// Serve the TCP transport over TLS and authenticate clients by certificate
+    pub mtls: Option<MtlsConfig>,
+    /// Secret providers consulted, in order
+    pub secret_providers: Vec<String>,
+    /// Client address allow and deny lists for network transports and authentication
+    pub ip_filter: IpFilter,
+    /// Run a security scan this often and audit its findings; 0 disables scheduled scans
+    pub security_scan_interval_minutes: u64,
+    /// Per-role and per-tool rate limits file (TOML or JSON); `rate_limit_per_user` applies to everyone when unset
+    pub rate_limit_config_path: Option<String>,
+    /// Component data removed from responses to Guest users
+    pub guest_redaction: RedactionRules,
 }
 
 impl ProductionSecurityConfig {
@@ -68,15 +129,18 @@ impl ProductionSecurityConfig {
             .unwrap_or_else(|_| "development".to_string())
             .to_lowercase() == "production";
 
-        let jwt_secret = if production_mode {
-            // In production, JWT secret MUST be provided via environment variable
-            env::var("BEVY_MCP_JWT_SECRET")
-                .map_err(|_| Error::SecurityError(
-                    "BEVY_MCP_JWT_SECRET environment variable is required in production mode".to_string()
-                ))?
-        } else {
+        let secrets = SecretResolver::from_env()?;
+
+        let jwt_secret = match secrets.get("JWT_SECRET")? {
+            Some(secret) => secret,
+            // In production, JWT secret MUST be provided
+            None if production_mode => {
+                return Err(Error::SecurityError(
+                    "A JWT secret is required in production mode: set BEVY_MCP_JWT_SECRET, BEVY_MCP_JWT_SECRET_FILE or a secrets provider".to_string()
+                ));
+            }
             // In development, generate a secure random secret
-            Self::generate_secure_jwt_secret()?
+            None => Self::generate_secure_jwt_secret()?,
         };
 
         // Validate JWT secret strength
@@ -85,6 +149,14 @@ impl ProductionSecurityConfig {
                 "JWT secret must be at least 32 characters long".to_string()
             ));
         }
+        if is_default_secret(&jwt_secret) {
+            if production_mode {
+                return Err(Error::SecurityError(
+                    "Refusing to start in production mode with a default JWT secret".to_string()
+                ));
+            }
+            warn!("JWT secret is a known default value - DO NOT use it in production");
+        }
 
         let config = Self {
             jwt_secret,
@@ -92,6 +164,16 @@ impl ProductionSecurityConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(if production_mode { 4 } else { 24 }),
+
+            jwt_key_grace_hours: env::var("BEVY_MCP_JWT_KEY_GRACE_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(if production_mode { 4 } else { 24 }),
+
+            refresh_token_expiry_hours: env::var("BEVY_MCP_REFRESH_TOKEN_EXPIRY_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(if production_mode { 24 } else { 168 }),
             
             rate_limit_per_ip: env::var("BEVY_MCP_RATE_LIMIT_PER_IP")
                 .ok()
@@ -122,6 +204,20 @@ impl ProductionSecurityConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(production_mode),
+
+            password_required_classes: env::var("BEVY_MCP_PASSWORD_CLASSES")
+                .map(|classes| parse_character_classes(&classes))
+                .unwrap_or_else(|_| Ok(CharacterClass::ALL.to_vec()))?,
+
+            password_history_size: env::var("BEVY_MCP_PASSWORD_HISTORY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(if production_mode { 5 } else { 0 }),
+
+            password_max_age_days: env::var("BEVY_MCP_PASSWORD_MAX_AGE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(if production_mode { 90 } else { 0 }),
             
             session_timeout_hours: env::var("BEVY_MCP_SESSION_TIMEOUT")
                 .ok()
@@ -138,6 +234,21 @@ impl ProductionSecurityConfig {
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(30),
             
+            ip_max_failed_logins: env::var("BEVY_MCP_IP_MAX_FAILED_LOGINS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20),
+            
+            ip_ban_duration_minutes: env::var("BEVY_MCP_IP_BAN_DURATION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            
+            ip_failure_delay_ms: env::var("BEVY_MCP_IP_FAILURE_DELAY_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(if production_mode { 250 } else { 0 }),
+            
             audit_log_retention_days: env::var("BEVY_MCP_AUDIT_RETENTION")
                 .ok()
                 .and_then(|v| v.parse().ok())
@@ -147,6 +258,42 @@ impl ProductionSecurityConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(production_mode),
+
+            audit_log_path: env::var("BEVY_MCP_AUDIT_LOG_PATH")
+                .unwrap_or_else(|_| "logs/audit.log".to_string()),
+
+            audit_log_max_size_mb: env::var("BEVY_MCP_AUDIT_MAX_SIZE_MB")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+
+            audit_log_rotate_hours: env::var("BEVY_MCP_AUDIT_ROTATE_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(24),
+
+            audit_log_max_files: env::var("BEVY_MCP_AUDIT_MAX_FILES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+
+            audit_sinks: env::var("BEVY_MCP_AUDIT_SINKS")
+                .map(|sinks| parse_audit_sinks(&sinks))
+                .unwrap_or_else(|_| Ok(Vec::new()))?,
+
+            audit_http_token: secrets.get("AUDIT_HTTP_TOKEN")?,
+
+            audit_integrity: env::var("BEVY_MCP_AUDIT_INTEGRITY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(production_mode),
+
+            audit_signing_key: secrets.get("AUDIT_SIGNING_KEY")?,
+
+            audit_signature_interval: env::var("BEVY_MCP_AUDIT_SIGN_EVERY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
             
             force_initial_password_change: env::var("BEVY_MCP_FORCE_PASSWORD_CHANGE")
                 .ok()
@@ -159,8 +306,40 @@ impl ProductionSecurityConfig {
                 .unwrap_or(true),
             
             production_mode,
+
+            user_store_path: env::var("BEVY_MCP_USER_STORE").ok(),
+
+            user_store_key: secrets.get("USER_STORE_KEY")?,
+
+            oidc: OidcConfig::from_env()?,
+
+            tool_policy_path: env::var("BEVY_MCP_TOOL_POLICY").ok(),
+
+            mtls: MtlsConfig::from_env()?,
+
+            secret_providers: secrets.provider_names().into_iter().map(str::to_string).collect(),
+
+            ip_filter: IpFilter::from_env()?,
+
+            security_scan_interval_minutes: env::var("BEVY_MCP_SECURITY_SCAN_INTERVAL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+
+            rate_limit_config_path: env::var("BEVY_MCP_RATE_LIMITS").ok(),
+
+            guest_redaction: RedactionRules::from_env(),
         };
 
+        // The JWT secret is random in development, so the store needs its own key
+        if config.user_store_path.is_some()
+            && config.user_store_key.as_ref().map_or(true, |key| key.len() < 32)
+        {
+            return Err(Error::SecurityError(
+                "BEVY_MCP_USER_STORE_KEY (min 32 chars) is required when BEVY_MCP_USER_STORE is set".to_string()
+            ));
+        }
+
         // Log configuration warnings
         if production_mode {
             info!("Security configuration loaded in PRODUCTION mode");
@@ -193,51 +372,24 @@ impl ProductionSecurityConfig {
         Ok(secret)
     }
 
-    /// Validate password against security policy
-    pub fn validate_password(&self, password: &str) -> Result<()> {
-        if password.len() < self.password_min_length {
-            return Err(Error::SecurityError(format!(
-                "Password must be at least {} characters long",
-                self.password_min_length
-            )));
-        }
-
-        if self.password_require_complexity {
-            let has_uppercase = password.chars().any(|c| c.is_uppercase());
-            let has_lowercase = password.chars().any(|c| c.is_lowercase());
-            let has_number = password.chars().any(|c| c.is_ascii_digit());
-            let has_symbol = password.chars().any(|c| !c.is_alphanumeric());
-
-            if !(has_uppercase && has_lowercase && has_number && has_symbol) {
-                return Err(Error::SecurityError(
-                    "Password must contain uppercase, lowercase, number, and symbol characters".to_string()
-                ));
-            }
-        }
-
-        if self.password_blacklist_check {
-            if Self::is_common_password(password) {
-                return Err(Error::SecurityError(
-                    "Password is too common, please choose a more secure password".to_string()
-                ));
-            }
+    /// The password rules configured for this deployment
+    pub fn password_policy(&self) -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: self.password_min_length,
+            required_classes: if self.password_require_complexity {
+                self.password_required_classes.clone()
+            } else {
+                Vec::new()
+            },
+            reject_common: self.password_blacklist_check,
+            history_size: self.password_history_size,
+            max_age_days: self.password_max_age_days,
         }
-
-        Ok(())
     }
 
-    /// Check if password is in common password list
-    fn is_common_password(password: &str) -> bool {
-        // Simple check against most common passwords
-        const COMMON_PASSWORDS: &[&str] = &[
-            "password", "123456", "123456789", "12345678", "12345",
-            "1234567890", "qwerty", "abc123", "password123", "admin",
-            "admin123", "root", "user", "guest", "test", "demo",
-            "welcome", "login", "passw0rd", "p@ssword", "p@ssw0rd"
-        ];
-
-        let password_lower = password.to_lowercase();
-        COMMON_PASSWORDS.iter().any(|&common| password_lower == common)
+    /// Validate password against security policy
+    pub fn validate_password(&self, password: &str) -> Result<()> {
+        self.password_policy().check(password, "")
     }
 
     /// Generate a secure random password for initial setup
@@ -266,43 +418,66 @@ impl ProductionSecurityConfig {
         info!("=== Security Configuration Summary ===");
         info!("Production Mode: {}", self.production_mode);
         info!("JWT Expiry: {} hours", self.jwt_expiry_hours);
+        info!("JWT Key Rotation Grace: {} hours", self.jwt_key_grace_hours);
+        info!("Refresh Token Expiry: {} hours", self.refresh_token_expiry_hours);
         info!("Rate Limit (IP): {} req/min", self.rate_limit_per_ip);
-        info!("Rate Limit (User): {} req/min", self.rate_limit_per_user);
+        info!("Rate Limit (User): {}", self.rate_limit_config_path.as_ref().map_or_else(|| format!("{} req/min", self.rate_limit_per_user), |path| format!("per role from {}", path)));
         info!("Password Min Length: {} chars", self.password_min_length);
         info!("Password Complexity: {}", self.password_require_complexity);
+        info!("Password History: {} previous", self.password_history_size);
+        if self.password_max_age_days > 0 {
+            info!("Password Expiry: {} days", self.password_max_age_days);
+        }
         info!("Session Timeout: {} hours", self.session_timeout_hours);
         info!("Max Failed Logins: {}", self.max_failed_logins);
+        info!("IP Ban: after {} failures for {} minutes, delay starting at {} ms", self.ip_max_failed_logins, self.ip_ban_duration_minutes, self.ip_failure_delay_ms);
         info!("Audit Persistence: {}", self.audit_log_persistence);
+        if !self.audit_sinks.is_empty() {
+            info!("Audit Export: {} sink(s)", self.audit_sinks.len());
+        }
+        if self.audit_log_persistence {
+            info!("Audit Log File: {} (rotate at {} MB or {} hours)", self.audit_log_path, self.audit_log_max_size_mb, self.audit_log_rotate_hours);
+        }
+        if self.audit_integrity {
+            info!("Audit Integrity: hash chain{}", if self.audit_signing_key.is_some() { format!(", signed every {} entries", self.audit_signature_interval) } else { String::new() });
+        }
         info!("Force Password Change: {}", self.force_initial_password_change);
+        info!("User Store: {}", self.user_store_path.as_deref().unwrap_or("in-memory"));
+        info!("OIDC Issuer: {}", self.oidc.as_ref().map_or("disabled", |oidc| oidc.issuer.as_str()));
+        info!("Tool Policy: {}", self.tool_policy_path.as_deref().unwrap_or("built-in"));
+        info!("Secret Providers: {}", self.secret_providers.join(", "));
+        if self.ip_filter.is_enabled() {
+            info!("IP Filter: {} allowed, {} denied range(s)", self.ip_filter.allow.len(), self.ip_filter.deny.len());
+        }
+        if !self.guest_redaction.is_empty() {
+            info!("Guest Redaction: {}", self.guest_redaction.rules.join(", "));
+        }
+        if self.security_scan_interval_minutes > 0 {
+            info!("Scheduled Security Scan: every {} minutes", self.security_scan_interval_minutes);
+        }
+        info!("Mutual TLS: {}", self.mtls.as_ref().map_or("disabled", |mtls| if mtls.require_jwt { "enabled (JWT required)" } else { "enabled (certificate identity)" }));
         info!("=====================================");
     }
 
-    /// Validate environment variables for production deployment
+    /// Validate the secrets required for production deployment
     pub fn validate_production_environment() -> Result<()> {
-        let required_env_vars = [
-            "BEVY_MCP_JWT_SECRET",
-        ];
-
-        let mut missing_vars = Vec::new();
-
-        for var in &required_env_vars {
-            if env::var(var).is_err() {
-                missing_vars.push(*var);
-            }
-        }
-
-        if !missing_vars.is_empty() {
-            return Err(Error::SecurityError(format!(
-                "Missing required environment variables for production: {}",
-                missing_vars.join(", ")
-            )));
-        }
+        let secrets = SecretResolver::from_env()?;
+        let jwt_secret = secrets.get("JWT_SECRET")?.ok_or_else(|| {
+            Error::SecurityError(format!(
+                "Missing JWT secret for production (checked: {})",
+                secrets.provider_names().join(", ")
+            ))
+        })?;
 
         // Validate JWT secret strength
-        let jwt_secret = env::var("BEVY_MCP_JWT_SECRET").unwrap();
         if jwt_secret.len() < 32 {
             return Err(Error::SecurityError(
-                "BEVY_MCP_JWT_SECRET must be at least 32 characters long".to_string()
+                "JWT secret must be at least 32 characters long".to_string()
+            ));
+        }
+        if is_default_secret(&jwt_secret) {
+            return Err(Error::SecurityError(
+                "JWT secret is a known default value".to_string()
             ));
         }
 
@@ -323,23 +498,70 @@ pub const ENVIRONMENT_VARIABLES_HELP: &str = r#"
 
 REQUIRED FOR PRODUCTION:
   BEVY_MCP_ENV=production              # Enable production security mode
-  BEVY_MCP_JWT_SECRET=<secret>         # JWT signing secret (min 32 chars)
+  BEVY_MCP_JWT_SECRET=<secret>         # JWT signing secret (min 32 chars), or one of the sources below
+
+SECRETS:
+  Secrets (JWT_SECRET, USER_STORE_KEY, AUDIT_HTTP_TOKEN, AUDIT_SIGNING_KEY) are read from the first source that has them:
+  BEVY_MCP_<NAME>=<secret>             # Environment variable
+  BEVY_MCP_<NAME>_FILE=<path>          # File containing the secret, e.g. a mounted Docker/Kubernetes secret
+  BEVY_MCP_SECRETS_PROVIDERS=<list>    # Further providers in order: "keyring" (os-keyring feature), "vault" (vault feature)
+  BEVY_MCP_VAULT_ADDR=<url>            # Vault server, e.g. https://vault:8200 (required with vault)
+  BEVY_MCP_VAULT_PATH=<path>           # KV secret path with lower case fields, e.g. secret/data/bevy-debugger
+  BEVY_MCP_VAULT_TOKEN=<token>         # Vault token (required with vault)
+  Keyring entries use service "bevy-debugger-mcp" with the lower case name as account, e.g. jwt_secret
 
 OPTIONAL CONFIGURATION:
   BEVY_MCP_JWT_EXPIRY_HOURS=4          # JWT token expiry (default: 4 in prod, 24 in dev)
+  BEVY_MCP_JWT_KEY_GRACE_HOURS=4       # Hours a rotated signing key still validates tokens (default: 4 in prod, 24 in dev)
+  BEVY_MCP_REFRESH_TOKEN_EXPIRY_HOURS=24 # Refresh token lifetime (default: 24 in prod, 168 in dev)
   BEVY_MCP_RATE_LIMIT_PER_IP=60        # Rate limit per IP (default: 60 req/min)
   BEVY_MCP_RATE_LIMIT_PER_USER=100     # Rate limit per user (default: 100 req/min)
   BEVY_MCP_RATE_LIMIT_BURST=10         # Rate limit burst capacity (default: 10)
+  BEVY_MCP_RATE_LIMITS=<path>          # Per-role and per-tool rate limits, TOML or JSON, reloaded on change (default: RATE_LIMIT_PER_USER for all)
   BEVY_MCP_PASSWORD_MIN_LENGTH=12      # Minimum password length (default: 12 in prod, 8 in dev)
   BEVY_MCP_PASSWORD_COMPLEXITY=true    # Require password complexity (default: true in prod)
-  BEVY_MCP_PASSWORD_BLACKLIST=true     # Check against common passwords (default: true in prod)
-  BEVY_MCP_SESSION_TIMEOUT=4           # Session timeout in hours (default: 4 in prod, 8 in dev)
+  BEVY_MCP_PASSWORD_BLACKLIST=true     # Check against common passwords and the username (default: true in prod)
+  BEVY_MCP_PASSWORD_CLASSES=<list>     # Classes complexity requires, from upper,lower,digit,symbol (default: all)
+  BEVY_MCP_PASSWORD_HISTORY=5          # Previous passwords that cannot be reused (default: 5 in prod, 0 in dev)
+  BEVY_MCP_PASSWORD_MAX_AGE=90         # Days before a password must be changed, 0 to disable (default: 90 in prod, 0 in dev)
+  BEVY_MCP_SESSION_TIMEOUT=4           # Idle session timeout in hours, extended by activity (default: 4 in prod, 8 in dev)
   BEVY_MCP_MAX_FAILED_LOGINS=5         # Max failed logins before lockout (default: 5)
   BEVY_MCP_LOCKOUT_DURATION=30         # Lockout duration in minutes (default: 30)
+  BEVY_MCP_IP_MAX_FAILED_LOGINS=20     # Failed logins from one IP, any username, before a temporary ban; 0 to disable (default: 20)
+  BEVY_MCP_IP_BAN_DURATION=60          # IP ban duration in minutes (default: 60)
+  BEVY_MCP_IP_FAILURE_DELAY_MS=250     # Delay after an IP's first failure, doubling per failure up to 10s (default: 250 in prod, 0 in dev)
   BEVY_MCP_AUDIT_RETENTION=90          # Audit log retention in days (default: 90)
   BEVY_MCP_AUDIT_PERSISTENCE=true      # Enable persistent audit logging (default: true in prod)
+  BEVY_MCP_AUDIT_LOG_PATH=<path>       # Audit log file (default: logs/audit.log)
+  BEVY_MCP_AUDIT_MAX_SIZE_MB=10        # Rotate and gzip the audit log at this size (default: 10)
+  BEVY_MCP_AUDIT_ROTATE_HOURS=24       # Also rotate after this many hours, 0 to disable (default: 24)
+  BEVY_MCP_AUDIT_MAX_FILES=0           # Rotated files to keep, 0 for all within retention (default: 0)
+  BEVY_MCP_AUDIT_SINKS=<sinks>         # Export audit entries, e.g. "syslog://siem:514,journald,https://siem/bulk"
+  BEVY_MCP_AUDIT_HTTP_TOKEN=<token>    # Bearer token for HTTP audit sinks (requires the audit-http feature)
+  BEVY_MCP_AUDIT_INTEGRITY=true        # Hash-chain audit entries so tampering is detectable (default: true in prod)
+  BEVY_MCP_AUDIT_SIGNING_KEY=<secret>  # Also sign the chain periodically with this key (default: unsigned)
+  BEVY_MCP_AUDIT_SIGN_EVERY=100        # Entries between signatures (default: 100)
   BEVY_MCP_FORCE_PASSWORD_CHANGE=true  # Force initial password change (default: true in prod)
   BEVY_MCP_LOCKOUT_RECOVERY=true       # Enable lockout recovery (default: true)
+  BEVY_MCP_USER_STORE=<path>           # Persist users to an encrypted file (default: in-memory)
+  BEVY_MCP_USER_STORE_KEY=<secret>     # User store encryption key (min 32 chars, required with USER_STORE)
+  BEVY_MCP_OIDC_ISSUER=<url>           # Accept tokens from this OIDC issuer (default: disabled)
+  BEVY_MCP_OIDC_AUDIENCE=<aud>         # Required audience of provider tokens (required with OIDC_ISSUER)
+  BEVY_MCP_OIDC_JWKS_URL=<url>         # Provider signing keys (default: from issuer discovery)
+  BEVY_MCP_OIDC_ROLE_CLAIM=roles       # Claim with roles/groups, dots for nesting (default: roles)
+  BEVY_MCP_OIDC_ROLE_MAP=<map>         # Claim value to role, e.g. "debug-admins=Admin,devs=Developer"
+  BEVY_MCP_OIDC_DEFAULT_ROLE=Viewer    # Role for unmapped users (default: reject)
+  BEVY_MCP_TOOL_POLICY=<path>          # Tool to role policy file, TOML or JSON, reloaded on change (default: built-in)
+  BEVY_MCP_TLS_CERT=<path>             # Serve TCP mode over mutual TLS with this certificate (default: disabled)
+  BEVY_MCP_TLS_KEY=<path>              # Server private key (required with TLS_CERT)
+  BEVY_MCP_TLS_CLIENT_CA=<path>        # CA that client certificates must chain to (required with TLS_CERT)
+  BEVY_MCP_TLS_CN_MAP=<map>            # Client cert CN to role or user, e.g. "ci-runner=Developer,alice.studio=alice"
+  BEVY_MCP_TLS_REQUIRE_JWT=false       # Also require a JWT per call instead of the cert identity (default: false)
+  BEVY_MCP_TLS_BIND=127.0.0.1          # Address the TLS listener binds to (default: 127.0.0.1)
+  BEVY_MCP_IP_ALLOWLIST=<list>         # Only accept clients from these addresses/CIDR ranges, e.g. "10.0.0.0/8,::1" (default: all)
+  BEVY_MCP_IP_DENYLIST=<list>          # Reject clients from these addresses/CIDR ranges; overrides the allow list
+  BEVY_MCP_GUEST_REDACT=<rules>        # Hidden from Guest users, e.g. "PlayerName.*,ChatMessage.text" (default: none)
+  BEVY_MCP_SECURITY_SCAN_INTERVAL=0    # Scan every N minutes and write findings to the audit log (default: 0, disabled)
 
 EXAMPLE PRODUCTION CONFIGURATION:
   export BEVY_MCP_ENV=production
@@ -0,0 +1,247 @@
+/*
+ * Bevy Debugger MCP Server - Portable Session Archives
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::checkpoint::Checkpoint;
+use crate::diagnostics::DiagnosticReport;
+use crate::error::{Error, Result};
+
+/// Version of the archive document written by [`SessionArchive::write`]
+pub const SESSION_ARCHIVE_VERSION: u32 = 1;
+
+/// Directory archives are written to and unpacked under
+pub const SESSIONS_DIR: &str = "./sessions";
+
+/// File name an archived recording is unpacked as
+pub const RECORDING_FILE_NAME: &str = "recording.bevy";
+
+/// A file carried inside an archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    pub name: String,
+    /// Hex SHA-256 of the contents, checked on unpack
+    pub sha256: String,
+    /// Base64 contents
+    pub data: String,
+}
+
+impl ArchivedFile {
+    pub fn new(name: &str, bytes: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            sha256: format!("{:x}", Sha256::digest(bytes)),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+
+    /// Read `path` into an archive entry named after its file name
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| Error::Validation(format!("Invalid file name: {}", path.display())))?;
+        Ok(Self::new(name, &std::fs::read(path)?))
+    }
+
+    /// Decoded contents, verified against the stored digest
+    pub fn bytes(&self) -> Result<Vec<u8>> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&self.data)
+            .map_err(|e| {
+                Error::Serialization(format!("Corrupt archived file {}: {e}", self.name))
+            })?;
+        if format!("{:x}", Sha256::digest(&bytes)) != self.sha256 {
+            return Err(Error::Validation(format!(
+                "Archived file {} does not match its checksum",
+                self.name
+            )));
+        }
+        Ok(bytes)
+    }
+}
+
+/// Everything needed to reproduce a debugging session elsewhere
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionArchive {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Version of this crate that wrote the archive
+    pub crate_version: String,
+    pub description: Option<String>,
+    /// A recording file in the current recording format
+    pub recording: Option<ArchivedFile>,
+    pub checkpoints: Vec<Checkpoint>,
+    pub screenshots: Vec<ArchivedFile>,
+    pub diagnostic_report: Option<DiagnosticReport>,
+}
+
+/// Where an archive's files ended up after unpacking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpackedSession {
+    pub directory: PathBuf,
+    pub recording: Option<PathBuf>,
+    pub screenshots: Vec<PathBuf>,
+}
+
+impl SessionArchive {
+    pub fn new(description: Option<String>) -> Self {
+        Self {
+            format_version: SESSION_ARCHIVE_VERSION,
+            created_at: Utc::now(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            description,
+            recording: None,
+            checkpoints: Vec::new(),
+            screenshots: Vec::new(),
+            diagnostic_report: None,
+        }
+    }
+
+    /// Write the archive as gzipped JSON
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut encoder =
+            GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish()?.flush()?;
+        info!("Exported session archive to {:?}", path);
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let reader = GzDecoder::new(BufReader::new(File::open(path)?));
+        let archive: Self = serde_json::from_reader(reader)?;
+        if archive.format_version > SESSION_ARCHIVE_VERSION {
+            return Err(Error::Validation(format!(
+                "Session archive version {} is newer than supported version {}",
+                archive.format_version, SESSION_ARCHIVE_VERSION
+            )));
+        }
+        Ok(archive)
+    }
+
+    /// Write the recording and screenshots into `directory`
+    ///
+    /// File names are reduced to their last component, so an archive can't
+    /// write outside `directory`.
+    pub fn unpack(&self, directory: &Path) -> Result<UnpackedSession> {
+        std::fs::create_dir_all(directory)?;
+        let write = |file: &ArchivedFile, name: &str| -> Result<PathBuf> {
+            let name = Path::new(name)
+                .file_name()
+                .ok_or_else(|| Error::Validation(format!("Invalid archived file name: {name}")))?;
+            let path = directory.join(name);
+            std::fs::write(&path, file.bytes()?)?;
+            Ok(path)
+        };
+
+        let recording = self
+            .recording
+            .as_ref()
+            .map(|file| write(file, RECORDING_FILE_NAME))
+            .transpose()?;
+        let screenshots = self
+            .screenshots
+            .iter()
+            .map(|file| write(file, &file.name))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(UnpackedSession {
+            directory: directory.to_path_buf(),
+            recording,
+            screenshots,
+        })
+    }
+}
+
+/// Resolve a user supplied path, which must be relative and stay inside `base`
+pub fn resolve_relative_path(base: &Path, file_path: &str) -> Result<PathBuf> {
+    let path = Path::new(file_path);
+    if path.is_absolute() || path.to_string_lossy().contains("..") {
+        return Err(Error::Validation(
+            "Invalid file path: must be relative and not contain '..'".to_string(),
+        ));
+    }
+    Ok(base.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut archive = SessionArchive::new(Some("Player falls through floor".to_string()));
+        archive.recording = Some(ArchivedFile::new("session.bevy", b"BEVYREC\0frames"));
+        archive
+            .screenshots
+            .push(ArchivedFile::new("floor.png", &[0x89, b'P', b'N', b'G']));
+        archive.checkpoints.push(Checkpoint::new(
+            "before_jump",
+            "Standing on the ledge",
+            "manual",
+            "mcp_server",
+            serde_json::json!({"player": [0.0, 4.0, 0.0]}),
+        ));
+
+        let path = dir.path().join("bug.bevysession");
+        archive.write(&path).unwrap();
+        let loaded = SessionArchive::read(&path).unwrap();
+        assert_eq!(loaded.checkpoints[0].name, "before_jump");
+
+        let unpacked = loaded.unpack(&dir.path().join("unpacked")).unwrap();
+        let recording = unpacked.recording.unwrap();
+        assert!(recording.ends_with(RECORDING_FILE_NAME));
+        assert_eq!(std::fs::read(recording).unwrap(), b"BEVYREC\0frames");
+        assert_eq!(
+            std::fs::read(&unpacked.screenshots[0]).unwrap(),
+            vec![0x89, b'P', b'N', b'G']
+        );
+    }
+
+    #[test]
+    fn test_unpack_rejects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut archive = SessionArchive::new(None);
+
+        // Names can't escape the unpack directory
+        archive
+            .screenshots
+            .push(ArchivedFile::new("../../escape.png", b"png"));
+        let unpacked = archive.unpack(&dir.path().join("out")).unwrap();
+        assert_eq!(unpacked.screenshots[0], dir.path().join("out/escape.png"));
+
+        archive.screenshots[0].data = base64::engine::general_purpose::STANDARD.encode(b"other");
+        assert!(archive.unpack(&dir.path().join("out")).is_err());
+        assert!(resolve_relative_path(dir.path(), "../etc/passwd").is_err());
+    }
+}
@@ -23,8 +23,11 @@ use tokio::sync::RwLock;
 use tracing::{debug, info};
 
 use crate::brp_messages::DebugCommand;
-use crate::error::Result;
-use crate::pattern_learning::{AnonymizedCommand, DebugPattern, PatternLearningSystem};
+use crate::error::{Error, Result};
+use crate::pattern_learning::{
+    AnonymizedCommand, DebugPattern, PatternLearningSystem, MAX_FEEDBACK_WEIGHT,
+    MIN_FEEDBACK_WEIGHT,
+};
 
 /// Maximum number of suggestions to generate
 const MAX_SUGGESTIONS: usize = 5;
@@ -32,9 +35,20 @@ const MAX_SUGGESTIONS: usize = 5;
 /// Minimum confidence for suggestions
 const MIN_SUGGESTION_CONFIDENCE: f64 = 0.6;
 
+/// Weight change for one accepted or rejected suggestion
+const FEEDBACK_STEP: f64 = 0.1;
+
+/// Prefix of IDs of suggestions made from a learned pattern
+const PATTERN_SUGGESTION_PREFIX: &str = "pattern:";
+
+/// Prefix of IDs of suggestions made from a template or the system state
+const COMMAND_SUGGESTION_PREFIX: &str = "command:";
+
 /// A debug suggestion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebugSuggestion {
+    /// ID to pass back with feedback on this suggestion
+    pub id: String,
     /// Suggested command
     pub command: String,
     /// Confidence score (0.0 to 1.0)
@@ -49,6 +63,31 @@ pub struct DebugSuggestion {
     pub priority: i32,
 }
 
+/// What the user did with a suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionFeedback {
+    /// The suggestion was followed
+    Accepted,
+    /// The suggestion was judged unhelpful
+    Rejected,
+    /// The suggestion was shown but neither followed nor rejected
+    Ignored,
+}
+
+impl SuggestionFeedback {
+    /// Weight change for this feedback; accepted suggestions that did not
+    /// help count against the suggestion, but less than a rejection
+    fn weight_delta(self, success: Option<bool>) -> f64 {
+        match (self, success) {
+            (Self::Accepted, Some(false)) => -FEEDBACK_STEP / 2.0,
+            (Self::Accepted, _) => FEEDBACK_STEP,
+            (Self::Rejected, _) => -FEEDBACK_STEP,
+            (Self::Ignored, _) => -FEEDBACK_STEP / 4.0,
+        }
+    }
+}
+
 /// Context for generating suggestions
 #[derive(Debug, Clone)]
 pub struct SuggestionContext {
@@ -84,6 +123,9 @@ pub struct SuggestionEngine {
     suggestion_history: Arc<RwLock<HashMap<String, SuggestionMetrics>>>,
     /// Pre-computed suggestion templates
     templates: Arc<RwLock<Vec<SuggestionTemplate>>>,
+    /// Feedback weights of template and context suggestions, by command;
+    /// pattern suggestions keep theirs on the pattern
+    command_weights: Arc<RwLock<HashMap<String, f64>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -124,6 +166,7 @@ impl SuggestionEngine {
             pattern_system,
             suggestion_history: Arc::new(RwLock::new(HashMap::new())),
             templates: Arc::new(RwLock::new(Vec::new())),
+            command_weights: Arc::new(RwLock::new(HashMap::new())),
         };
         
         // Initialize with default templates
@@ -152,6 +195,21 @@ impl SuggestionEngine {
         let context_suggestions = self.generate_context_suggestions(context).await;
         suggestions.extend(context_suggestions);
         
+        // Weight by feedback on earlier suggestions
+        let command_weights = self.command_weights.read().await;
+        for suggestion in &mut suggestions {
+            suggestion.id = match &suggestion.pattern_id {
+                Some(pattern_id) => format!("{}{}", PATTERN_SUGGESTION_PREFIX, pattern_id),
+                None => {
+                    if let Some(weight) = command_weights.get(&suggestion.command) {
+                        suggestion.confidence = (suggestion.confidence * weight).min(1.0);
+                    }
+                    format!("{}{}", COMMAND_SUGGESTION_PREFIX, suggestion.command)
+                }
+            };
+        }
+        drop(command_weights);
+        
         // Sort by priority and confidence
         suggestions.sort_by(|a, b| {
             let priority_cmp = b.priority.cmp(&a.priority);
@@ -201,6 +259,7 @@ impl SuggestionEngine {
         for template in templates.iter() {
             if self.matches_condition(&template.condition, context) {
                 suggestions.push(DebugSuggestion {
+                    id: String::new(),
                     command: template.command_template.clone(),
                     confidence: 0.8,
                     reasoning: template.template.clone(),
@@ -221,6 +280,7 @@ impl SuggestionEngine {
         // Performance issues
         if context.system_state.fps < 30.0 {
             suggestions.push(DebugSuggestion {
+                id: String::new(),
                 command: "profile_system".to_string(),
                 confidence: 0.9,
                 reasoning: format!("FPS is low ({:.1}), profiling can identify bottlenecks", context.system_state.fps),
@@ -233,6 +293,7 @@ impl SuggestionEngine {
         // Memory issues
         if context.system_state.memory_mb > 500.0 {
             suggestions.push(DebugSuggestion {
+                id: String::new(),
                 command: "profile_memory".to_string(),
                 confidence: 0.85,
                 reasoning: format!("High memory usage ({:.1}MB)", context.system_state.memory_mb),
@@ -245,6 +306,7 @@ impl SuggestionEngine {
         // Entity explosion
         if context.system_state.entity_count > 10000 {
             suggestions.push(DebugSuggestion {
+                id: String::new(),
                 command: "observe entities --limit 100".to_string(),
                 confidence: 0.75,
                 reasoning: format!("High entity count ({})", context.system_state.entity_count),
@@ -257,6 +319,7 @@ impl SuggestionEngine {
         // Error state
         if context.system_state.has_errors {
             suggestions.push(DebugSuggestion {
+                id: String::new(),
                 command: "detect_issues".to_string(),
                 confidence: 0.95,
                 reasoning: "System has errors that need investigation".to_string(),
@@ -284,8 +347,9 @@ impl SuggestionEngine {
         let next_cmd = &pattern.sequence[current_len];
         
         Some(DebugSuggestion {
+            id: String::new(),
             command: self.anonymized_to_command_string(next_cmd),
-            confidence: pattern.confidence,
+            confidence: (pattern.confidence * pattern.feedback_weight).min(1.0),
             reasoning: format!(
                 "Based on pattern with {:.0}% success rate (seen {} times)",
                 pattern.success_rate * 100.0,
@@ -361,6 +425,45 @@ impl SuggestionEngine {
                suggestion_id, accepted, success);
     }
     
+    /// Record feedback on a suggestion, returning its new weight
+    ///
+    /// Feedback on a pattern suggestion reweights the learned pattern, so it
+    /// is saved and shared with the patterns; feedback on other suggestions
+    /// reweights every suggestion of the same command.
+    pub async fn record_feedback(
+        &self,
+        suggestion_id: &str,
+        feedback: SuggestionFeedback,
+        success: Option<bool>,
+    ) -> Result<f64> {
+        let delta = feedback.weight_delta(success);
+        let pattern_id = suggestion_id.strip_prefix(PATTERN_SUGGESTION_PREFIX);
+        let weight = if let Some(pattern_id) = pattern_id {
+            self.pattern_system
+                .apply_feedback(pattern_id, delta)
+                .await
+                .ok_or_else(|| {
+                    Error::Validation(format!("No learned pattern '{}' to reweight", pattern_id))
+                })?
+        } else if let Some(command) = suggestion_id.strip_prefix(COMMAND_SUGGESTION_PREFIX) {
+            let mut weights = self.command_weights.write().await;
+            let weight = weights.entry(command.to_string()).or_insert(1.0);
+            *weight = (*weight + delta).clamp(MIN_FEEDBACK_WEIGHT, MAX_FEEDBACK_WEIGHT);
+            *weight
+        } else {
+            return Err(Error::Validation(format!(
+                "Unknown suggestion ID '{}': pass the id returned by get_suggestions",
+                suggestion_id
+            )));
+        };
+        
+        let accepted = feedback == SuggestionFeedback::Accepted;
+        let helped = accepted && success != Some(false);
+        self.track_suggestion_acceptance(suggestion_id, accepted, helped).await;
+        
+        Ok(weight)
+    }
+    
     /// Get suggestion effectiveness metrics
     pub async fn get_suggestion_metrics(&self) -> HashMap<String, (f64, f64)> {
         let history = self.suggestion_history.read().await;
@@ -1,15 +1,18 @@
 /// System Performance Profiler for comprehensive ECS profiling
 use crate::brp_messages::{
-    DebugCommand, DebugResponse, SystemMetrics, ProfileSample, SystemProfile,
+    BrpRequest, BrpResponse, BrpResult, DebugCommand, DebugResponse, FrameBound, GpuFrameTiming,
+    GpuPassStats, GpuTimingAnalysis, SystemMetrics, ProfileSample, SystemProfile,
 };
 use crate::brp_client::BrpClient;
+use crate::diagnostics_bridge::DiagnosticsSnapshot;
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 /// Maximum number of frames to store in history
 pub const MAX_FRAME_HISTORY: usize = 1000;
@@ -23,6 +26,12 @@ pub const DEFAULT_PROFILE_DURATION_MS: u64 = 5000;
 /// Performance threshold for anomaly detection (150% of average)
 pub const ANOMALY_THRESHOLD_MULTIPLIER: f32 = 1.5;
 
+/// Default number of recent frames used for GPU analysis
+pub const DEFAULT_GPU_ANALYSIS_FRAMES: usize = 120;
+
+/// A frame is CPU or GPU bound when one side exceeds the other by this ratio
+pub const FRAME_BOUND_RATIO: f32 = 1.2;
+
 /// System profiler with comprehensive performance tracking
 pub struct SystemProfiler {
     /// BRP client for communication with Bevy
@@ -35,6 +44,8 @@ pub struct SystemProfiler {
     dependency_graph: Arc<RwLock<SystemDependencyGraph>>,
     /// Performance anomaly detector
     anomaly_detector: Arc<RwLock<AnomalyDetector>>,
+    /// GPU frame timings reported by the game
+    gpu_history: Arc<RwLock<VecDeque<GpuFrameTiming>>>,
     /// Profiling configuration
     config: ProfilerConfig,
 }
@@ -170,6 +181,7 @@ impl SystemProfiler {
             anomaly_detector: Arc::new(RwLock::new(AnomalyDetector::new(
                 ANOMALY_THRESHOLD_MULTIPLIER,
             ))),
+            gpu_history: Arc::new(RwLock::new(VecDeque::with_capacity(MAX_FRAME_HISTORY))),
             config,
         }
     }
@@ -258,6 +270,14 @@ impl SystemProfiler {
                     duration_ms,
                 })
             }
+            DebugCommand::ProfileGpu { frame_count } => {
+                if let Err(e) = self.fetch_gpu_timings(frame_count).await {
+                    warn!("Using buffered GPU timings, fetch failed: {}", e);
+                }
+                Ok(DebugResponse::GpuProfile(
+                    self.analyze_gpu_timings(frame_count).await,
+                ))
+            }
             _ => Err(Error::DebugError("Unsupported profiling command".to_string())),
         }
     }
@@ -442,6 +462,151 @@ impl SystemProfiler {
         samples
     }
 
+    /// Record the game's frame time diagnostic against the current frame
+    pub async fn record_diagnostics(&self, snapshot: &DiagnosticsSnapshot) {
+        let Some(frame_time_ms) = snapshot.frame_time_ms() else {
+            return;
+        };
+        if !frame_time_ms.is_finite() || frame_time_ms < 0.0 {
+            return;
+        }
+
+        let mut history = self.frame_history.write().await;
+        let frame = history.get_or_create_current_frame();
+        frame.duration = Duration::from_secs_f32(frame_time_ms / 1000.0);
+    }
+
+    /// Record GPU timings for a frame reported by the game
+    pub async fn record_gpu_frame(&self, timing: GpuFrameTiming) {
+        let mut history = self.gpu_history.write().await;
+        // Frames can be reported again when fetch windows overlap
+        if history
+            .iter()
+            .any(|f| f.frame_number == timing.frame_number)
+        {
+            return;
+        }
+        history.push_back(timing);
+        if history.len() > MAX_FRAME_HISTORY {
+            history.pop_front();
+        }
+    }
+
+    /// Fetch recent GPU pass timings from the game's debugger plugin.
+    ///
+    /// Returns the number of frames received.
+    pub async fn fetch_gpu_timings(&self, frame_count: Option<usize>) -> Result<usize> {
+        let request = BrpRequest::Debug {
+            command: DebugCommand::ProfileGpu { frame_count },
+            correlation_id: uuid::Uuid::new_v4().to_string(),
+            priority: Some(5),
+        };
+
+        let response = {
+            let mut client = self.brp_client.write().await;
+            if !client.is_connected() {
+                return Err(Error::Connection("BRP client not connected".to_string()));
+            }
+            client.send_request(&request).await?
+        };
+
+        let frames = match response {
+            BrpResponse::Success(boxed_result) => match *boxed_result {
+                BrpResult::Debug(debug_response) => match *debug_response {
+                    DebugResponse::GpuTimings { frames } => frames,
+                    other => {
+                        return Err(Error::Brp(format!(
+                            "Unexpected GPU timing response: {:?}",
+                            other
+                        )))
+                    }
+                },
+                _ => return Err(Error::Brp("Expected debug response".to_string())),
+            },
+            BrpResponse::Error(error) => {
+                return Err(Error::Brp(format!(
+                    "GPU timing request failed: {}",
+                    error.message
+                )))
+            }
+        };
+
+        let received = frames.len();
+        for frame in frames {
+            self.record_gpu_frame(frame).await;
+        }
+        debug!("Received GPU timings for {} frames", received);
+        Ok(received)
+    }
+
+    /// Analyze the most recent GPU frame timings
+    pub async fn analyze_gpu_timings(&self, frame_count: Option<usize>) -> GpuTimingAnalysis {
+        let history = self.gpu_history.read().await;
+        let count = frame_count
+            .unwrap_or(DEFAULT_GPU_ANALYSIS_FRAMES)
+            .min(history.len());
+        let frames: Vec<&GpuFrameTiming> = history.iter().skip(history.len() - count).collect();
+
+        let mut cpu_bound_frames = 0;
+        let mut gpu_bound_frames = 0;
+        let mut balanced_frames = 0;
+        let mut pass_samples: HashMap<&str, Vec<u64>> = HashMap::new();
+
+        for frame in &frames {
+            match classify_frame(frame.cpu_time_us, frame.gpu_time_us) {
+                FrameBound::Cpu => cpu_bound_frames += 1,
+                FrameBound::Gpu => gpu_bound_frames += 1,
+                FrameBound::Balanced => balanced_frames += 1,
+            }
+            for pass in &frame.passes {
+                pass_samples
+                    .entry(pass.pass_name.as_str())
+                    .or_default()
+                    .push(pass.duration_us);
+            }
+        }
+
+        let mut passes: Vec<GpuPassStats> = pass_samples
+            .into_iter()
+            .map(|(name, samples)| GpuPassStats {
+                pass_name: name.to_string(),
+                avg_time_us: samples.iter().sum::<u64>() / samples.len() as u64,
+                max_time_us: samples.iter().copied().max().unwrap_or(0),
+                sample_count: samples.len(),
+            })
+            .collect();
+        passes.sort_by_key(|p| Reverse(p.avg_time_us));
+
+        let dominant = [
+            (FrameBound::Cpu, cpu_bound_frames),
+            (FrameBound::Gpu, gpu_bound_frames),
+            (FrameBound::Balanced, balanced_frames),
+        ]
+        .into_iter()
+        .filter(|(_, n)| *n > 0)
+        .max_by_key(|(_, n)| *n)
+        .map(|(bound, _)| bound);
+
+        let average = |values: Vec<u64>| -> u64 {
+            if values.is_empty() {
+                0
+            } else {
+                values.iter().sum::<u64>() / values.len() as u64
+            }
+        };
+
+        GpuTimingAnalysis {
+            frame_count: frames.len(),
+            cpu_bound_frames,
+            gpu_bound_frames,
+            balanced_frames,
+            avg_cpu_time_us: average(frames.iter().map(|f| f.cpu_time_us).collect()),
+            avg_gpu_time_us: average(frames.iter().map(|f| f.gpu_time_us).collect()),
+            dominant,
+            passes,
+        }
+    }
+
     /// Get detected anomalies
     pub async fn get_anomalies(&self) -> Vec<PerformanceAnomaly> {
         let detector = self.anomaly_detector.read().await;
@@ -458,6 +623,9 @@ impl SystemProfiler {
 
         let mut detector = self.anomaly_detector.write().await;
         detector.anomalies.clear();
+
+        let mut gpu_history = self.gpu_history.write().await;
+        gpu_history.clear();
     }
 
     /// Export profiling data in various formats
@@ -538,11 +706,25 @@ impl Clone for SystemProfiler {
             frame_history: self.frame_history.clone(),
             dependency_graph: self.dependency_graph.clone(),
             anomaly_detector: self.anomaly_detector.clone(),
+            gpu_history: self.gpu_history.clone(),
             config: self.config.clone(),
         }
     }
 }
 
+/// Classify a frame as CPU or GPU bound from its CPU and GPU times
+pub fn classify_frame(cpu_time_us: u64, gpu_time_us: u64) -> FrameBound {
+    let cpu = cpu_time_us as f32;
+    let gpu = gpu_time_us as f32;
+    if gpu > cpu * FRAME_BOUND_RATIO {
+        FrameBound::Gpu
+    } else if cpu > gpu * FRAME_BOUND_RATIO {
+        FrameBound::Cpu
+    } else {
+        FrameBound::Balanced
+    }
+}
+
 impl FrameHistory {
     fn new(max_frames: usize) -> Self {
         Self {
@@ -760,6 +942,89 @@ mod tests {
         assert_eq!(metrics.total_allocations, 45);
     }
 
+    #[tokio::test]
+    async fn test_record_diagnostics_sets_frame_duration() {
+        let config = Config::default();
+        let brp_client = Arc::new(RwLock::new(BrpClient::new(&config)));
+        let profiler = SystemProfiler::new(brp_client);
+
+        let snapshot = DiagnosticsSnapshot::new(vec![crate::brp_messages::DiagnosticMeasurement {
+            path: crate::diagnostics_bridge::paths::FRAME_TIME.to_string(),
+            value: Some(25.0),
+            average: None,
+            smoothed: None,
+            suffix: Some("ms".to_string()),
+        }]);
+        profiler.record_diagnostics(&snapshot).await;
+
+        let history = profiler.frame_history.read().await;
+        assert_eq!(
+            history.frames.back().unwrap().duration,
+            Duration::from_millis(25)
+        );
+    }
+
+    #[test]
+    fn test_classify_frame() {
+        assert_eq!(classify_frame(16_000, 8_000), FrameBound::Cpu);
+        assert_eq!(classify_frame(8_000, 16_000), FrameBound::Gpu);
+        assert_eq!(classify_frame(16_000, 15_000), FrameBound::Balanced);
+    }
+
+    #[tokio::test]
+    async fn test_gpu_timing_analysis() {
+        let config = Config::default();
+        let brp_client = Arc::new(RwLock::new(BrpClient::new(&config)));
+        let profiler = SystemProfiler::new(brp_client);
+
+        for frame_number in 0..10 {
+            let gpu_time_us = if frame_number < 7 { 20_000 } else { 5_000 };
+            profiler
+                .record_gpu_frame(GpuFrameTiming {
+                    frame_number,
+                    cpu_time_us: 10_000,
+                    gpu_time_us,
+                    passes: vec![
+                        crate::brp_messages::GpuPassTiming {
+                            pass_name: "main_opaque".to_string(),
+                            duration_us: gpu_time_us - 1_000,
+                        },
+                        crate::brp_messages::GpuPassTiming {
+                            pass_name: "ui".to_string(),
+                            duration_us: 1_000,
+                        },
+                    ],
+                })
+                .await;
+        }
+        // Duplicate frames are ignored
+        profiler
+            .record_gpu_frame(GpuFrameTiming {
+                frame_number: 9,
+                cpu_time_us: 10_000,
+                gpu_time_us: 5_000,
+                passes: Vec::new(),
+            })
+            .await;
+
+        let analysis = profiler.analyze_gpu_timings(None).await;
+        assert_eq!(analysis.frame_count, 10);
+        assert_eq!(analysis.gpu_bound_frames, 7);
+        assert_eq!(analysis.cpu_bound_frames, 3);
+        assert_eq!(analysis.dominant, Some(FrameBound::Gpu));
+        assert_eq!(analysis.passes[0].pass_name, "main_opaque");
+        assert_eq!(analysis.passes[0].max_time_us, 19_000);
+
+        // Only the most recent frames are analyzed
+        let recent = profiler.analyze_gpu_timings(Some(3)).await;
+        assert_eq!(recent.frame_count, 3);
+        assert_eq!(recent.dominant, Some(FrameBound::Cpu));
+
+        // Not connected, so fetching fails without touching buffered data
+        assert!(profiler.fetch_gpu_timings(None).await.is_err());
+        assert_eq!(profiler.analyze_gpu_timings(None).await.frame_count, 10);
+    }
+
     #[test]
     fn test_moving_average() {
         let mut avg = MovingAverage::new(3);
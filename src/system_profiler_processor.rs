@@ -2,11 +2,11 @@
 use crate::brp_messages::{DebugCommand, DebugResponse};
 use crate::debug_command_processor::DebugCommandProcessor;
 use crate::error::{Error, Result};
-use crate::system_profiler::{SystemProfiler, ExportFormat};
+use crate::system_profiler::{SystemProfiler, ExportFormat, MAX_FRAME_HISTORY};
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::time::Duration;
-use tracing::info;
+use tracing::{info, warn};
 
 /// System profiler processor for debug commands
 pub struct SystemProfilerProcessor {
@@ -53,6 +53,17 @@ impl DebugCommandProcessor for SystemProfilerProcessor {
                     duration_ms,
                 })
             }
+            DebugCommand::ProfileGpu { frame_count } => {
+                info!("Processing ProfileGpu command");
+
+                // Fall back to already buffered timings if the game can't be reached
+                if let Err(e) = self.profiler.fetch_gpu_timings(frame_count).await {
+                    warn!("Failed to fetch GPU timings from game: {}", e);
+                }
+
+                let analysis = self.profiler.analyze_gpu_timings(frame_count).await;
+                Ok(DebugResponse::GpuProfile(analysis))
+            }
             _ => Err(Error::DebugError("Unsupported command for SystemProfilerProcessor".to_string())),
         }
     }
@@ -81,6 +92,20 @@ impl DebugCommandProcessor for SystemProfilerProcessor {
                 
                 Ok(())
             }
+            DebugCommand::ProfileGpu { frame_count } => {
+                if let Some(count) = frame_count {
+                    if *count == 0 {
+                        return Err(Error::DebugError("Frame count must be greater than 0".to_string()));
+                    }
+                    if *count > MAX_FRAME_HISTORY {
+                        return Err(Error::DebugError(format!(
+                            "Frame count too large (max {})",
+                            MAX_FRAME_HISTORY
+                        )));
+                    }
+                }
+                Ok(())
+            }
             _ => Ok(()),
         }
     }
@@ -91,12 +116,16 @@ impl DebugCommandProcessor for SystemProfilerProcessor {
                 // Processing time is roughly the profiling duration
                 Duration::from_millis(duration_ms.unwrap_or(5000))
             }
+            DebugCommand::ProfileGpu { .. } => Duration::from_millis(50),
             _ => Duration::from_millis(10),
         }
     }
     
     fn supports_command(&self, command: &DebugCommand) -> bool {
-        matches!(command, DebugCommand::ProfileSystem { .. })
+        matches!(
+            command,
+            DebugCommand::ProfileSystem { .. } | DebugCommand::ProfileGpu { .. }
+        )
     }
 }
 
@@ -246,4 +275,29 @@ mod tests {
         let estimated_time = processor.estimate_processing_time(&command);
         assert_eq!(estimated_time.as_millis(), 3000);
     }
+
+    #[tokio::test]
+    async fn test_gpu_profile_command() {
+        let config = Config::default();
+        let brp_client = Arc::new(RwLock::new(BrpClient::new(&config)));
+        let profiler = Arc::new(SystemProfiler::new(brp_client));
+        let processor = SystemProfilerProcessor::new(profiler);
+
+        let command = DebugCommand::ProfileGpu { frame_count: Some(60) };
+        assert!(processor.supports_command(&command));
+        assert!(processor.validate(&command).await.is_ok());
+        assert!(processor
+            .validate(&DebugCommand::ProfileGpu { frame_count: Some(0) })
+            .await
+            .is_err());
+
+        // Without a connected game the analysis is empty rather than an error
+        match processor.process(command).await.unwrap() {
+            DebugResponse::GpuProfile(analysis) => {
+                assert_eq!(analysis.frame_count, 0);
+                assert!(analysis.dominant.is_none());
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+    }
 }
\ No newline at end of file
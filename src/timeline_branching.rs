@@ -165,6 +165,10 @@ pub struct TimelineBranch {
     modified_frames: HashMap<usize, Frame>,
     /// Reference to the base recording
     base_recording_id: Option<String>,
+    /// Frames recorded from the live game after the branch point, which
+    /// take precedence over replayed base frames
+    #[serde(default)]
+    recorded_frames: BTreeMap<usize, Frame>,
 }
 
 impl TimelineBranch {
@@ -174,6 +178,7 @@ impl TimelineBranch {
             modification_layers: BTreeMap::new(),
             modified_frames: HashMap::new(),
             base_recording_id: None,
+            recorded_frames: BTreeMap::new(),
         }
     }
 
@@ -198,8 +203,35 @@ impl TimelineBranch {
         Ok(())
     }
 
+    /// Store a frame recorded from the live game after the branch point
+    pub fn record_frame(&mut self, frame: Frame) -> Result<()> {
+        if frame.frame_number <= self.metadata.branch_point_frame {
+            return Err(Error::Validation(format!(
+                "Frame {} is not after branch point {}",
+                frame.frame_number, self.metadata.branch_point_frame
+            )));
+        }
+        self.recorded_frames.insert(frame.frame_number, frame);
+        self.metadata.last_modified = SystemTime::now();
+        Ok(())
+    }
+
+    /// Get a frame recorded live in this branch
+    pub fn recorded_frame(&self, frame_number: usize) -> Option<&Frame> {
+        self.recorded_frames.get(&frame_number)
+    }
+
+    /// Number of frames recorded live in this branch
+    pub fn recorded_frame_count(&self) -> usize {
+        self.recorded_frames.len()
+    }
+
     /// Get a frame with all modifications applied up to that point
     pub fn get_frame(&mut self, base_frame: &Frame, frame_number: usize) -> Result<Frame> {
+        if let Some(recorded) = self.recorded_frames.get(&frame_number) {
+            return Ok(recorded.clone());
+        }
+
         // Check if we already have this frame cached
         if let Some(cached_frame) = self.modified_frames.get(&frame_number) {
             return Ok(cached_frame.clone());
@@ -357,6 +389,15 @@ impl TimelineBranchManager {
         branch_id: BranchId,
         frame_number: usize,
     ) -> Result<Frame> {
+        // Recorded branch frames may run past the end of the base recording
+        if let Some(recorded) = self
+            .branches
+            .get(&branch_id)
+            .and_then(|b| b.recorded_frame(frame_number))
+        {
+            return Ok(recorded.clone());
+        }
+
         let base_recording = self
             .base_recording
             .as_ref()
@@ -379,6 +420,16 @@ impl TimelineBranchManager {
         branch.get_frame(base_frame, frame_number)
     }
 
+    /// Store a frame recorded from the live game in a branch
+    pub fn record_branch_frame(&mut self, branch_id: BranchId, frame: Frame) -> Result<()> {
+        self.branches
+            .get_mut(&branch_id)
+            .ok_or_else(|| {
+                Error::Validation(format!("Branch {} not found", branch_id.to_string()))
+            })?
+            .record_frame(frame)
+    }
+
     /// Set the active branch for playback
     pub fn set_active_branch(&mut self, branch_id: BranchId) -> Result<()> {
         if !self.branches.contains_key(&branch_id) {
@@ -664,10 +715,25 @@ impl TimelineBranchManager {
         for (entity_id, entity_a) in &frame_a.entities {
             if let Some(entity_b) = frame_b.entities.get(entity_id) {
                 if entity_a != entity_b {
+                    let mut changed: Vec<String> = entity_a
+                        .components
+                        .keys()
+                        .chain(entity_b.components.keys())
+                        .filter(|name| {
+                            entity_a.components.get(*name) != entity_b.components.get(*name)
+                        })
+                        .cloned()
+                        .collect();
+                    changed.sort();
+                    changed.dedup();
+                    if entity_a.active != entity_b.active {
+                        changed.push("active".to_string());
+                    }
                     differences.push(EntityDifference {
                         entity_id: *entity_id,
                         difference_type: DifferenceType::Modified,
-                        details: format!("Entity {entity_id} modified"),
+                        details: format!("Entity {entity_id} modified: {}", changed.join(", ")),
+                        changed_components: changed,
                     });
                 }
             } else {
@@ -675,6 +741,7 @@ impl TimelineBranchManager {
                     entity_id: *entity_id,
                     difference_type: DifferenceType::OnlyInA,
                     details: format!("Entity {entity_id} only in branch A"),
+                    changed_components: Vec::new(),
                 });
             }
         }
@@ -686,6 +753,7 @@ impl TimelineBranchManager {
                     entity_id: *entity_id,
                     difference_type: DifferenceType::OnlyInB,
                     details: format!("Entity {entity_id} only in branch B"),
+                    changed_components: Vec::new(),
                 });
             }
         }
@@ -785,6 +853,13 @@ pub struct BranchComparison {
     pub frame_differences: Vec<FrameDifference>,
 }
 
+impl BranchComparison {
+    /// First frame where the branches' outcomes differ
+    pub fn first_divergence(&self) -> Option<usize> {
+        self.frame_differences.first().map(|d| d.frame_number)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FrameDifference {
     pub frame_number: usize,
@@ -796,6 +871,8 @@ pub struct EntityDifference {
     pub entity_id: u64,
     pub difference_type: DifferenceType,
     pub details: String,
+    /// Components whose values differ, for modified entities
+    pub changed_components: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -885,6 +962,58 @@ mod tests {
         assert_eq!(manager.list_branches().len(), 2); // master + test_branch
     }
 
+    #[test]
+    fn test_recorded_branch_diverges() {
+        let mut manager = TimelineBranchManager::new();
+        let entity = |health: i64| EntityState {
+            entity_id: 1,
+            components: [("Health".to_string(), serde_json::json!(health))]
+                .into_iter()
+                .collect(),
+            active: true,
+        };
+        let frame = |frame_number: usize, health: i64| Frame {
+            frame_number,
+            timestamp: Duration::from_millis(frame_number as u64 * 33),
+            entities: [(1, entity(health))].into_iter().collect(),
+            events: Vec::new(),
+            checksum: None,
+        };
+        manager.set_base_recording(Recording {
+            config: RecordingConfig::default(),
+            frames: (0..3).map(|n| frame(n, 100)).collect(),
+            delta_frames: Vec::new(),
+            markers: Vec::new(),
+            total_frames: 3,
+            duration: Duration::from_millis(100),
+            version: crate::playback_system::RecordingVersion::current(),
+        });
+
+        let master = manager.master_branch_id().unwrap();
+        let branch = manager
+            .create_branch("low_health".to_string(), Some(master), 1)
+            .unwrap();
+        assert!(manager.record_branch_frame(branch, frame(1, 10)).is_err());
+        // The branch keeps recording past the end of the base recording
+        manager.record_branch_frame(branch, frame(2, 10)).unwrap();
+        manager.record_branch_frame(branch, frame(3, 5)).unwrap();
+        assert_eq!(
+            manager.get_branch(branch).unwrap().recorded_frame_count(),
+            2
+        );
+
+        let comparison = manager.compare_branches(master, branch, 0..3).unwrap();
+        assert_eq!(comparison.first_divergence(), Some(2));
+        assert_eq!(
+            comparison.frame_differences[0].differences[0].changed_components,
+            vec!["Health".to_string()]
+        );
+        assert_eq!(
+            manager.get_frame_from_branch(branch, 3).unwrap().entities[&1],
+            entity(5)
+        );
+    }
+
     #[test]
     fn test_merge_conflict_detection() {
         let mod1 = Modification::EntityModification {
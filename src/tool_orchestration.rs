@@ -4,11 +4,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::{mpsc, oneshot, RwLock};
-use tracing::{debug, info};
+use tracing::{debug, info, instrument, warn, Instrument};
 use uuid::Uuid;
 
 use crate::brp_client::BrpClient;
 use crate::error::{Error, Result};
+use crate::pipeline_persistence::{PipelineExecutionState, PipelinePersistence, PipelineStatus};
+use crate::pipeline_templates::{PipelineTemplateStore, TemplateSource};
 
 /// Unique identifier for tool executions and results
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -22,6 +24,11 @@ impl ExecutionId {
     pub fn to_string(&self) -> String {
         self.0.to_string()
     }
+
+    /// Parse an execution ID from its string form
+    pub fn parse(id: &str) -> Result<Self> {
+        Ok(Self(Uuid::parse_str(id)?))
+    }
 }
 
 impl Default for ExecutionId {
@@ -146,15 +153,23 @@ pub struct ToolResult {
     pub cache_key: Option<String>,
 }
 
+/// Maximum number of iterations a `repeat_until` step may request
+pub const MAX_REPEAT_ITERATIONS: usize = 1000;
+
 /// A single step in a tool pipeline
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineStep {
     pub name: String,
     pub tool: String,
+    #[serde(default)]
     pub arguments: Value,
     pub condition: Option<StepCondition>,
+    #[serde(alias = "retry_with_backoff")]
     pub retry_config: Option<RetryConfig>,
     pub timeout: Option<Duration>,
+    /// Re-run the step until a condition holds (polling)
+    #[serde(default)]
+    pub repeat_until: Option<RepeatUntil>,
 }
 
 /// Condition for executing a pipeline step
@@ -206,17 +221,92 @@ impl Default for RetryConfig {
     }
 }
 
+/// Polling configuration for a step that is re-executed until a condition holds,
+/// e.g. "observe entity count every second until it stabilizes"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatUntil {
+    pub condition: RepeatCondition,
+    /// Delay between iterations
+    pub interval: Duration,
+    /// Iterations allowed before the step is reported as failed
+    pub max_iterations: usize,
+}
+
+/// Condition that ends a repeating step
+///
+/// Paths select a value from the tool output using either JSON pointer
+/// syntax (`/metrics/entity_count`) or dotted syntax (`metrics.entity_count`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RepeatCondition {
+    /// Stop once the tool succeeds
+    Succeeded,
+    /// Stop once the value at `path` equals `value`
+    Equals { path: String, value: Value },
+    /// Stop once the value at `path` (or the whole output) is identical for
+    /// `iterations` consecutive runs
+    Stable {
+        path: Option<String>,
+        iterations: usize,
+    },
+}
+
+impl RepeatCondition {
+    /// Select the value this condition observes from a tool output
+    fn observed<'a>(&self, output: &'a Value) -> Option<&'a Value> {
+        match self {
+            RepeatCondition::Succeeded => Some(output),
+            RepeatCondition::Equals { path, .. } => select_path(output, Some(path)),
+            RepeatCondition::Stable { path, .. } => select_path(output, path.as_deref()),
+        }
+    }
+
+    /// Check the condition given the latest result and how many consecutive
+    /// runs (including this one) produced the same observed value
+    fn is_met(&self, result: &ToolResult, identical_runs: usize) -> bool {
+        if !result.success {
+            return false;
+        }
+
+        match self {
+            RepeatCondition::Succeeded => true,
+            RepeatCondition::Equals { value, .. } => self.observed(&result.output) == Some(value),
+            RepeatCondition::Stable { iterations, .. } => identical_runs >= (*iterations).max(2),
+        }
+    }
+}
+
+/// Resolve a JSON pointer or dotted path against a value
+fn select_path<'a>(value: &'a Value, path: Option<&str>) -> Option<&'a Value> {
+    match path {
+        None | Some("") => Some(value),
+        Some(p) if p.starts_with('/') => value.pointer(p),
+        Some(p) => p
+            .split('.')
+            .try_fold(value, |current, segment| match current {
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => current.get(segment),
+            }),
+    }
+}
+
 /// A pipeline of tool executions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolPipeline {
     pub name: String,
     pub description: Option<String>,
     pub steps: Vec<PipelineStep>,
+    #[serde(default)]
     pub parallel_execution: bool,
+    #[serde(default = "default_fail_fast")]
     pub fail_fast: bool,
+    #[serde(default = "SystemTime::now")]
     pub created_at: SystemTime,
 }
 
+fn default_fail_fast() -> bool {
+    true
+}
+
 impl ToolPipeline {
     pub fn new(name: String) -> Self {
         Self {
@@ -242,6 +332,37 @@ impl ToolPipeline {
         self.fail_fast = fail_fast;
         self
     }
+
+    /// Validate loop and retry settings on the pipeline's steps
+    pub fn validate(&self) -> Result<()> {
+        for step in &self.steps {
+            if let Some(ref retry) = step.retry_config {
+                if retry.max_attempts == 0 || retry.max_attempts > 10 {
+                    return Err(Error::Validation(format!(
+                        "Step '{}' retry max_attempts must be between 1 and 10",
+                        step.name
+                    )));
+                }
+            }
+
+            if let Some(ref repeat) = step.repeat_until {
+                if self.parallel_execution {
+                    return Err(Error::Validation(format!(
+                        "Step '{}' uses repeat_until, which is not supported with parallel execution",
+                        step.name
+                    )));
+                }
+                if repeat.max_iterations == 0 || repeat.max_iterations > MAX_REPEAT_ITERATIONS {
+                    return Err(Error::Validation(format!(
+                        "Step '{}' repeat_until max_iterations must be between 1 and {}",
+                        step.name, MAX_REPEAT_ITERATIONS
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Result of pipeline execution
@@ -253,6 +374,9 @@ pub struct PipelineResult {
     pub step_results: Vec<StepResult>,
     pub total_execution_time: Duration,
     pub context: ToolContext,
+    /// Whether execution was stopped by an abort request
+    #[serde(default)]
+    pub aborted: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -263,6 +387,16 @@ pub struct StepResult {
     pub error: Option<String>,
     pub execution_time: Duration,
     pub retry_count: usize,
+    /// Number of times the step ran (greater than one for `repeat_until` steps)
+    #[serde(default)]
+    pub iterations: usize,
+}
+
+/// Outcome of running a step's tool, including any retries
+struct StepAttempt {
+    result: Option<ToolResult>,
+    error: Option<String>,
+    retry_count: usize,
 }
 
 /// Message for actor-based tool coordination
@@ -297,7 +431,9 @@ pub struct ToolOrchestrator {
     /// BRP client for tool execution
     brp_client: Arc<RwLock<BrpClient>>,
     /// Pipeline templates
-    pipeline_templates: HashMap<String, ToolPipeline>,
+    pipeline_templates: PipelineTemplateStore,
+    /// Checkpoint-backed storage for resumable pipeline state
+    persistence: Option<PipelinePersistence>,
 }
 
 impl ToolOrchestrator {
@@ -307,22 +443,41 @@ impl ToolOrchestrator {
             result_cache: HashMap::new(),
             dependency_graph: DependencyGraph::new(),
             brp_client,
-            pipeline_templates: HashMap::new(),
+            pipeline_templates: PipelineTemplateStore::new(),
+            persistence: None,
         }
     }
 
+    /// Persist sequential pipeline progress so it can be resumed after a restart
+    pub fn set_pipeline_persistence(&mut self, persistence: PipelinePersistence) {
+        self.persistence = Some(persistence);
+    }
+
     /// Register a tool executor
     pub fn register_tool(&mut self, name: String, executor: Arc<dyn ToolExecutor>) {
         self.tools.insert(name, executor);
     }
 
-    /// Register a pipeline template
+    /// Names of the registered tools
+    pub fn tool_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tools.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Register a built-in pipeline template
     pub fn register_pipeline_template(&mut self, pipeline: ToolPipeline) {
         self.pipeline_templates
-            .insert(pipeline.name.clone(), pipeline);
+            .insert(pipeline, TemplateSource::Builtin);
+    }
+
+    /// Shared handle to the pipeline template store
+    pub fn pipeline_templates(&self) -> PipelineTemplateStore {
+        self.pipeline_templates.clone()
     }
 
     /// Execute a single tool
+    #[instrument(name = "orchestrator.tool", skip(self, arguments, context), err)]
     pub async fn execute_tool(
         &mut self,
         tool: String,
@@ -411,67 +566,155 @@ impl ToolOrchestrator {
     pub async fn execute_pipeline(
         &mut self,
         pipeline: ToolPipeline,
-        mut context: ToolContext,
+        context: ToolContext,
     ) -> Result<PipelineResult> {
-        let start_time = Instant::now();
-        let execution_id = ExecutionId::new();
-        let step_results;
-
         // Enforce execution bounds
         if pipeline.steps.len() > 100 {
             return Err(Error::Validation(
                 "Pipeline too complex: maximum 100 steps allowed".to_string(),
             ));
         }
+        pipeline.validate()?;
+
+        self.run_pipeline(PipelineExecutionState::new(pipeline, context))
+            .await
+    }
+
+    /// Resume a persisted pipeline from its next pending step
+    pub async fn resume_pipeline(&mut self, execution_id: ExecutionId) -> Result<PipelineResult> {
+        let persistence = self
+            .persistence
+            .clone()
+            .ok_or_else(|| Error::Validation("Pipeline persistence is not enabled".to_string()))?;
+
+        if persistence.is_active(&execution_id) {
+            return Err(Error::Validation(format!(
+                "Pipeline {} is already running",
+                execution_id.to_string()
+            )));
+        }
+
+        let mut state = persistence.load(&execution_id).await?;
+        state.status = PipelineStatus::Running;
+        info!(
+            "Resuming pipeline '{}' ({}) at step {}/{}",
+            state.pipeline.name,
+            execution_id.to_string(),
+            state.next_step,
+            state.pipeline.steps.len()
+        );
+
+        self.run_pipeline(state).await
+    }
+
+    /// Run the remaining steps of a pipeline, checkpointing after each step
+    #[instrument(
+        name = "orchestrator.pipeline",
+        skip_all,
+        fields(
+            pipeline = %state.pipeline.name,
+            execution_id = %state.execution_id.to_string(),
+            steps = state.pipeline.steps.len()
+        ),
+        err
+    )]
+    async fn run_pipeline(&mut self, mut state: PipelineExecutionState) -> Result<PipelineResult> {
+        let start_time = Instant::now();
+        let execution_id = state.execution_id;
+        let persistence = self.persistence.clone();
+
+        if let Some(ref persistence) = persistence {
+            persistence.mark_active(execution_id);
+            if let Err(e) = persistence.save(&mut state).await {
+                warn!("Failed to persist pipeline state: {}", e);
+            }
+        }
 
         let max_pipeline_time = Duration::from_secs(1800); // 30 minutes max
         let pipeline_result = tokio::time::timeout(max_pipeline_time, async {
-            if pipeline.parallel_execution {
+            if state.pipeline.parallel_execution {
                 // Execute steps in parallel
-                self.execute_parallel_steps(&pipeline.steps, &mut context)
-                    .await
+                state.step_results = self
+                    .execute_parallel_steps(&state.pipeline.steps, &mut state.context)
+                    .await?;
+                state.next_step = state.pipeline.steps.len();
             } else {
                 // Execute steps sequentially
-                let mut results = Vec::new();
-                for step in &pipeline.steps {
-                    let step_result = self.execute_step(step, &mut context).await;
+                while !state.is_complete() {
+                    if persistence
+                        .as_ref()
+                        .map(|p| p.is_abort_requested(&execution_id))
+                        .unwrap_or(false)
+                    {
+                        info!("Pipeline {} aborted", execution_id.to_string());
+                        state.status = PipelineStatus::Aborted;
+                        break;
+                    }
+
+                    let step = state.pipeline.steps[state.next_step].clone();
+                    let step_result = self.execute_step(&step, &mut state.context).await;
                     let success = step_result.success;
-                    results.push(step_result);
+                    state.step_results.push(step_result);
+                    state.next_step += 1;
 
-                    if !success && pipeline.fail_fast {
+                    if !success && state.pipeline.fail_fast {
                         break;
                     }
+
+                    if let Some(ref persistence) = persistence {
+                        if let Err(e) = persistence.save(&mut state).await {
+                            warn!("Failed to persist pipeline state: {}", e);
+                        }
+                    }
                 }
-                Ok(results)
             }
+            Ok::<(), Error>(())
         })
         .await;
 
-        step_results = match pipeline_result {
-            Ok(Ok(results)) => results,
+        state.elapsed += start_time.elapsed();
+
+        if let Some(ref persistence) = persistence {
+            persistence.mark_inactive(&execution_id);
+            // Keep the checkpoint on timeout or error so the pipeline can be resumed
+            if matches!(pipeline_result, Ok(Ok(()))) {
+                persistence.remove(&execution_id).await;
+            } else if let Err(e) = persistence.save(&mut state).await {
+                warn!("Failed to persist pipeline state: {}", e);
+            }
+        }
+
+        match pipeline_result {
+            Ok(Ok(())) => {}
             Ok(Err(e)) => return Err(e),
             Err(_) => {
                 return Err(Error::Validation(
                     "Pipeline execution timed out".to_string(),
                 ))
             }
-        };
+        }
 
-        let total_execution_time = start_time.elapsed();
-        let pipeline_success = step_results.iter().all(|r| r.success);
+        let aborted = state.status == PipelineStatus::Aborted;
+        let pipeline_success = !aborted && state.step_results.iter().all(|r| r.success);
 
         Ok(PipelineResult {
-            pipeline_name: pipeline.name,
+            pipeline_name: state.pipeline.name,
             execution_id,
             success: pipeline_success,
-            step_results,
-            total_execution_time,
-            context,
+            step_results: state.step_results,
+            total_execution_time: state.elapsed,
+            context: state.context,
+            aborted,
         })
     }
 
     /// Execute a single pipeline step
-    pub(crate) async fn execute_step(&mut self, step: &PipelineStep, context: &mut ToolContext) -> StepResult {
+    #[instrument(name = "orchestrator.step", skip_all, fields(step = %step.name, tool = %step.tool))]
+    pub(crate) async fn execute_step(
+        &mut self,
+        step: &PipelineStep,
+        context: &mut ToolContext,
+    ) -> StepResult {
         let start_time = Instant::now();
 
         // Check step condition
@@ -483,52 +726,135 @@ impl ToolOrchestrator {
                 error: Some("Step condition not met".to_string()),
                 execution_time: start_time.elapsed(),
                 retry_count: 0,
+                iterations: 0,
             };
         }
 
+        let Some(ref repeat) = step.repeat_until else {
+            let attempt = self.execute_with_retry(step, context).await;
+            return StepResult {
+                step_name: step.name.clone(),
+                success: attempt.error.is_none(),
+                result: attempt.result,
+                error: attempt.error,
+                execution_time: start_time.elapsed(),
+                retry_count: attempt.retry_count,
+                iterations: 1,
+            };
+        };
+
+        // Polling must observe fresh state on every iteration
+        let cache_results = context.config.cache_results;
+        context.config.cache_results = false;
+
+        let mut iterations = 0;
+        let mut total_retries = 0;
+        let mut previous: Option<Value> = None;
+        let mut identical_runs = 0;
+
+        let step_result = loop {
+            let attempt = self.execute_with_retry(step, context).await;
+            iterations += 1;
+            total_retries += attempt.retry_count;
+
+            if let Some(ref tool_result) = attempt.result {
+                let observed = repeat.condition.observed(&tool_result.output).cloned();
+                identical_runs = if observed.is_some() && observed == previous {
+                    identical_runs + 1
+                } else {
+                    1
+                };
+                previous = observed;
+
+                if repeat.condition.is_met(tool_result, identical_runs) {
+                    break StepResult {
+                        step_name: step.name.clone(),
+                        success: true,
+                        result: attempt.result,
+                        error: None,
+                        execution_time: start_time.elapsed(),
+                        retry_count: total_retries,
+                        iterations,
+                    };
+                }
+            }
+
+            if iterations >= repeat.max_iterations {
+                break StepResult {
+                    step_name: step.name.clone(),
+                    success: false,
+                    error: Some(attempt.error.unwrap_or_else(|| {
+                        format!("Repeat condition not met after {iterations} iterations")
+                    })),
+                    result: attempt.result,
+                    execution_time: start_time.elapsed(),
+                    retry_count: total_retries,
+                    iterations,
+                };
+            }
+
+            debug!(
+                "Step '{}' repeat condition not met (iteration {}), waiting {:?}",
+                step.name, iterations, repeat.interval
+            );
+            tokio::time::sleep(repeat.interval).await;
+        };
+
+        context.config.cache_results = cache_results;
+        step_result
+    }
+
+    /// Run a step's tool, retrying failed attempts with the step's backoff policy
+    async fn execute_with_retry(
+        &mut self,
+        step: &PipelineStep,
+        context: &mut ToolContext,
+    ) -> StepAttempt {
         let mut retry_count = 0;
         let max_attempts = step
             .retry_config
             .as_ref()
-            .map(|r| r.max_attempts)
+            .map(|r| r.max_attempts.max(1))
             .unwrap_or(1);
 
         loop {
-            let result = self
+            let (result, error) = match self
                 .execute_tool(step.tool.clone(), step.arguments.clone(), context)
-                .await;
-
-            match result {
+                .await
+            {
+                Ok(tool_result) if tool_result.success => (Some(tool_result), None),
                 Ok(tool_result) => {
-                    return StepResult {
-                        step_name: step.name.clone(),
-                        success: true,
-                        result: Some(tool_result),
-                        error: None,
-                        execution_time: start_time.elapsed(),
-                        retry_count,
-                    };
+                    let error = tool_result
+                        .error
+                        .clone()
+                        .unwrap_or_else(|| format!("Tool '{}' failed", step.tool));
+                    (Some(tool_result), Some(error))
                 }
-                Err(e) => {
-                    retry_count += 1;
-
-                    if retry_count >= max_attempts {
-                        return StepResult {
-                            step_name: step.name.clone(),
-                            success: false,
-                            result: None,
-                            error: Some(e.to_string()),
-                            execution_time: start_time.elapsed(),
-                            retry_count,
-                        };
-                    }
+                Err(e) => (None, Some(e.to_string())),
+            };
 
-                    // Wait before retry
-                    if let Some(ref retry_config) = step.retry_config {
-                        let delay = self.calculate_retry_delay(retry_config, retry_count);
-                        tokio::time::sleep(delay).await;
-                    }
-                }
+            if error.is_none() {
+                return StepAttempt {
+                    result,
+                    error,
+                    retry_count,
+                };
+            }
+
+            retry_count += 1;
+
+            if retry_count >= max_attempts {
+                return StepAttempt {
+                    result,
+                    error,
+                    retry_count,
+                };
+            }
+
+            // Wait before retry
+            if let Some(ref retry_config) = step.retry_config {
+                let delay = self.calculate_retry_delay(retry_config, retry_count);
+                tokio::time::sleep(delay).await;
             }
         }
     }
@@ -549,29 +875,33 @@ impl ToolOrchestrator {
                 let tools = self.tools.clone();
                 let brp_client = self.brp_client.clone();
 
-                let handle = tokio::spawn(async move {
-                    // Use shared context for parallel execution
-                    let mut local_context = {
-                        let ctx = context_ref.read().await;
-                        ctx.clone()
-                    };
+                // Keep parallel steps under the pipeline's span
+                let handle = tokio::spawn(
+                    async move {
+                        // Use shared context for parallel execution
+                        let mut local_context = {
+                            let ctx = context_ref.read().await;
+                            ctx.clone()
+                        };
 
-                    let result = Self::execute_step_standalone(
-                        step_clone,
-                        &mut local_context,
-                        tools,
-                        brp_client,
-                    )
-                    .await;
-
-                    // Update shared context with results
-                    if let Some(ref tool_result) = result.result {
-                        let mut ctx = context_ref.write().await;
-                        ctx.add_result(tool_result.tool_name.clone(), tool_result.clone());
+                        let result = Self::execute_step_standalone(
+                            step_clone,
+                            &mut local_context,
+                            tools,
+                            brp_client,
+                        )
+                        .await;
+
+                        // Update shared context with results
+                        if let Some(ref tool_result) = result.result {
+                            let mut ctx = context_ref.write().await;
+                            ctx.add_result(tool_result.tool_name.clone(), tool_result.clone());
+                        }
+
+                        result
                     }
-
-                    result
-                });
+                    .in_current_span(),
+                );
 
                 handles.push(handle);
             }
@@ -593,6 +923,7 @@ impl ToolOrchestrator {
     }
 
     /// Standalone step execution for parallel processing
+    #[instrument(name = "orchestrator.step", skip_all, fields(step = %step.name, tool = %step.tool))]
     async fn execute_step_standalone(
         step: PipelineStep,
         context: &mut ToolContext,
@@ -611,6 +942,7 @@ impl ToolOrchestrator {
                     error: Some(format!("Tool '{}' not found", step.tool)),
                     execution_time: start_time.elapsed(),
                     retry_count: 0,
+                    iterations: 0,
                 };
             }
         };
@@ -635,6 +967,7 @@ impl ToolOrchestrator {
                     error: None,
                     execution_time: start_time.elapsed(),
                     retry_count: 0,
+                    iterations: 1,
                 }
             }
             Err(e) => StepResult {
@@ -644,6 +977,7 @@ impl ToolOrchestrator {
                 error: Some(e.to_string()),
                 execution_time: start_time.elapsed(),
                 retry_count: 0,
+                iterations: 1,
             },
         }
     }
@@ -698,11 +1032,12 @@ impl ToolOrchestrator {
         match retry_config.backoff_type {
             BackoffType::Fixed => retry_config.initial_delay,
             BackoffType::Linear => {
-                let delay = retry_config.initial_delay * attempt as u32;
+                let delay = retry_config.initial_delay.saturating_mul(attempt as u32);
                 std::cmp::min(delay, retry_config.max_delay)
             }
             BackoffType::Exponential => {
-                let delay = retry_config.initial_delay * (2_u32.pow(attempt as u32 - 1));
+                let factor = 2_u32.saturating_pow((attempt as u32).saturating_sub(1));
+                let delay = retry_config.initial_delay.saturating_mul(factor);
                 std::cmp::min(delay, retry_config.max_delay)
             }
         }
@@ -902,6 +1237,7 @@ impl WorkflowDSL {
             condition: None,
             retry_config: Some(RetryConfig::default()),
             timeout: Some(Duration::from_secs(30)),
+            repeat_until: None,
         });
 
         pipeline.add_step(PipelineStep {
@@ -915,6 +1251,7 @@ impl WorkflowDSL {
             }),
             retry_config: Some(RetryConfig::default()),
             timeout: Some(Duration::from_secs(60)),
+            repeat_until: None,
         });
 
         pipeline.add_step(PipelineStep {
@@ -928,6 +1265,7 @@ impl WorkflowDSL {
             }),
             retry_config: None,
             timeout: Some(Duration::from_secs(120)),
+            repeat_until: None,
         });
 
         pipeline
@@ -946,6 +1284,7 @@ impl WorkflowDSL {
             condition: None,
             retry_config: None,
             timeout: Some(Duration::from_secs(45)),
+            repeat_until: None,
         });
 
         pipeline.add_step(PipelineStep {
@@ -959,6 +1298,7 @@ impl WorkflowDSL {
             }),
             retry_config: Some(RetryConfig::default()),
             timeout: Some(Duration::from_secs(30)),
+            repeat_until: None,
         });
 
         pipeline
@@ -1015,6 +1355,7 @@ mod tests {
             condition: None,
             retry_config: None,
             timeout: None,
+            repeat_until: None,
         });
 
         assert_eq!(pipeline.steps.len(), 1);
@@ -1093,4 +1434,159 @@ mod tests {
         assert!(pipeline.steps[1].condition.is_some());
         assert!(pipeline.steps[2].condition.is_some());
     }
+
+    /// Tool whose output counts up until `settle_after` calls, then stays fixed
+    struct SettlingTool {
+        calls: std::sync::atomic::AtomicUsize,
+        settle_after: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl ToolExecutor for SettlingTool {
+        async fn execute(
+            &self,
+            _arguments: Value,
+            _brp_client: Arc<RwLock<BrpClient>>,
+            _context: &mut ToolContext,
+        ) -> Result<Value> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            Ok(json!({"metrics": {"entity_count": call.min(self.settle_after)}}))
+        }
+    }
+
+    fn test_orchestrator() -> ToolOrchestrator {
+        ToolOrchestrator::new(Arc::new(RwLock::new(crate::brp_client::BrpClient::new(
+            &crate::config::Config::default(),
+        ))))
+    }
+
+    fn polling_step(condition: RepeatCondition, max_iterations: usize) -> PipelineStep {
+        PipelineStep {
+            name: "poll".to_string(),
+            tool: "settling".to_string(),
+            arguments: json!({}),
+            condition: None,
+            retry_config: None,
+            timeout: None,
+            repeat_until: Some(RepeatUntil {
+                condition,
+                interval: Duration::from_millis(1),
+                max_iterations,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_select_path() {
+        let value = json!({"metrics": {"entity_count": 5, "samples": [1, 2]}});
+
+        assert_eq!(select_path(&value, None), Some(&value));
+        assert_eq!(
+            select_path(&value, Some("/metrics/entity_count")),
+            Some(&json!(5))
+        );
+        assert_eq!(
+            select_path(&value, Some("metrics.entity_count")),
+            Some(&json!(5))
+        );
+        assert_eq!(
+            select_path(&value, Some("metrics.samples.1")),
+            Some(&json!(2))
+        );
+        assert_eq!(select_path(&value, Some("metrics.missing")), None);
+    }
+
+    #[tokio::test]
+    async fn test_repeat_until_stable() {
+        let mut orchestrator = test_orchestrator();
+        orchestrator.register_tool(
+            "settling".to_string(),
+            Arc::new(SettlingTool {
+                calls: Default::default(),
+                settle_after: 3,
+            }),
+        );
+
+        let step = polling_step(
+            RepeatCondition::Stable {
+                path: Some("metrics.entity_count".to_string()),
+                iterations: 2,
+            },
+            10,
+        );
+
+        let mut context = ToolContext::new();
+        let result = orchestrator.execute_step(&step, &mut context).await;
+
+        assert!(result.success);
+        // Counts 1, 2, 3, 3 -> stable on the fourth run
+        assert_eq!(result.iterations, 4);
+        // Caching is restored after polling
+        assert!(context.config.cache_results);
+    }
+
+    #[tokio::test]
+    async fn test_repeat_until_exhausts_iterations() {
+        let mut orchestrator = test_orchestrator();
+        orchestrator.register_tool(
+            "settling".to_string(),
+            Arc::new(SettlingTool {
+                calls: Default::default(),
+                settle_after: 100,
+            }),
+        );
+
+        let step = polling_step(
+            RepeatCondition::Equals {
+                path: "/metrics/entity_count".to_string(),
+                value: json!(50),
+            },
+            3,
+        );
+
+        let mut context = ToolContext::new();
+        let result = orchestrator.execute_step(&step, &mut context).await;
+
+        assert!(!result.success);
+        assert_eq!(result.iterations, 3);
+        assert!(result.error.unwrap().contains("not met after 3 iterations"));
+    }
+
+    #[test]
+    fn test_pipeline_validation() {
+        let mut pipeline = ToolPipeline::new("poll".to_string());
+        pipeline.add_step(polling_step(RepeatCondition::Succeeded, 5));
+        assert!(pipeline.validate().is_ok());
+
+        let parallel = pipeline.clone().with_parallel_execution(true);
+        assert!(parallel.validate().is_err());
+
+        let mut unbounded = ToolPipeline::new("poll".to_string());
+        unbounded.add_step(polling_step(
+            RepeatCondition::Succeeded,
+            MAX_REPEAT_ITERATIONS + 1,
+        ));
+        assert!(unbounded.validate().is_err());
+    }
+
+    #[test]
+    fn test_retry_with_backoff_alias() {
+        let step: PipelineStep = serde_json::from_value(json!({
+            "name": "observe",
+            "tool": "observe",
+            "arguments": {},
+            "condition": null,
+            "timeout": null,
+            "retry_with_backoff": {
+                "max_attempts": 4,
+                "backoff_type": "Linear",
+                "initial_delay": {"secs": 0, "nanos": 1000000},
+                "max_delay": {"secs": 1, "nanos": 0}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(step.retry_config.unwrap().max_attempts, 4);
+        assert!(step.repeat_until.is_none());
+    }
 }
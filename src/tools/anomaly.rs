@@ -1,29 +1,57 @@
 use serde_json::{json, Value};
 /// Anomaly detection tool for automatic game state monitoring
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
 use crate::anomaly_detector::{Anomaly, AnomalyConfig, AnomalyDetectionSystem};
 use crate::brp_client::BrpClient;
 use crate::brp_messages::{BrpRequest, BrpResponse, BrpResult};
+use crate::diagnostics_bridge::fetch_diagnostics;
 use crate::error::Result;
+use crate::tools::replay;
+
+/// Saving recordings around high-severity anomalies
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AutoRecordConfig {
+    pub enabled: bool,
+    /// Anomalies at or above this severity trigger a capture
+    pub min_severity: f32,
+    /// Gameplay kept from before the anomaly, taken from the ring buffer
+    pub seconds_before: u64,
+    /// Gameplay recorded after the anomaly before the capture is saved
+    pub seconds_after: u64,
+    pub directory: PathBuf,
+}
+
+impl Default for AutoRecordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_severity: 0.8,
+            seconds_before: 10,
+            seconds_after: 5,
+            directory: PathBuf::from("./recordings/anomalies"),
+        }
+    }
+}
 
 /// Shared state for anomaly detection
 pub struct AnomalyState {
     detection_system: AnomalyDetectionSystem,
     is_monitoring: bool,
+    auto_record: AutoRecordConfig,
+    /// A capture is still recording its `seconds_after` until then
+    capture_pending_until: Option<Instant>,
 }
 
 impl AnomalyState {
     /// Create new anomaly detection state
     #[must_use]
     pub fn new() -> Self {
-        let config = AnomalyConfig::default();
-        Self {
-            detection_system: AnomalyDetectionSystem::new(config),
-            is_monitoring: false,
-        }
+        Self::with_config(AnomalyConfig::default())
     }
 
     /// Create with custom configuration
@@ -32,6 +60,61 @@ impl AnomalyState {
         Self {
             detection_system: AnomalyDetectionSystem::new(config),
             is_monitoring: false,
+            auto_record: AutoRecordConfig::default(),
+            capture_pending_until: None,
+        }
+    }
+
+    /// Capture recordings for anomalies severe enough to trigger one
+    ///
+    /// One capture at a time: anomalies firing while a capture is still
+    /// recording are already covered by it.
+    async fn auto_record(&mut self, anomalies: &[Anomaly]) -> Vec<Value> {
+        if !self.auto_record.enabled
+            || self
+                .capture_pending_until
+                .is_some_and(|until| Instant::now() < until)
+        {
+            return Vec::new();
+        }
+        let Some(anomaly) = anomalies
+            .iter()
+            .find(|a| a.severity >= self.auto_record.min_severity)
+        else {
+            return Vec::new();
+        };
+
+        let after = Duration::from_secs(self.auto_record.seconds_after);
+        match replay::capture_anomaly(
+            anomaly,
+            Duration::from_secs(self.auto_record.seconds_before),
+            after,
+            &self.auto_record.directory,
+        )
+        .await
+        {
+            Ok(path) => {
+                info!(
+                    "Capturing recording for anomaly {} to {:?}",
+                    anomaly.id, path
+                );
+                self.capture_pending_until = Some(Instant::now() + after);
+                vec![json!({
+                    "anomaly_id": anomaly.id,
+                    "path": path,
+                    "ready_in_seconds": self.auto_record.seconds_after,
+                })]
+            }
+            Err(e) => {
+                warn!(
+                    "Could not capture recording for anomaly {}: {}",
+                    anomaly.id, e
+                );
+                vec![json!({
+                    "anomaly_id": anomaly.id,
+                    "error": e.to_string(),
+                })]
+            }
         }
     }
 }
@@ -133,11 +216,19 @@ async fn handle_detect(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
         }
     };
 
+    let diagnostics = match fetch_diagnostics(&brp_client, None).await {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            debug!("Skipping diagnostics-based detection: {}", e);
+            None
+        }
+    };
+
     // Run anomaly detection
     let state = get_anomaly_state();
     let mut state_guard = state.write().await;
 
-    let anomalies = match state_guard.detection_system.detect_anomalies(&entities) {
+    let mut anomalies = match state_guard.detection_system.detect_anomalies(&entities) {
         Ok(anomalies) => anomalies,
         Err(e) => {
             error!("Anomaly detection failed: {}", e);
@@ -148,6 +239,15 @@ async fn handle_detect(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
         }
     };
 
+    // Frame time anomalies from the game's diagnostics plugins, when available
+    if let Some(snapshot) = &diagnostics {
+        anomalies.extend(
+            state_guard
+                .detection_system
+                .detect_diagnostic_anomalies(snapshot),
+        );
+    }
+
     // Filter by severity if requested
     let min_severity = arguments
         .get("min_severity")
@@ -167,6 +267,8 @@ async fn handle_detect(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
 
     let limited_anomalies: Vec<&Anomaly> = filtered_anomalies.into_iter().take(limit).collect();
 
+    let recordings = state_guard.auto_record(&anomalies).await;
+
     info!(
         "Detected {} anomalies (showing {} after filtering)",
         anomalies.len(),
@@ -184,7 +286,8 @@ async fn handle_detect(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
             "timestamp": chrono::Utc::now().to_rfc3339()
         },
         "severity_breakdown": calculate_severity_breakdown(&anomalies),
-        "type_breakdown": calculate_type_breakdown(&anomalies)
+        "type_breakdown": calculate_type_breakdown(&anomalies),
+        "recordings": recordings
     }))
 }
 
@@ -230,6 +333,21 @@ async fn handle_configure(arguments: Value) -> Result<Value> {
     let mut state_guard = state.write().await;
     state_guard.detection_system.update_config(config.clone());
 
+    // Automatic recording is only changed when given
+    if let Some(auto_record) = arguments.get("auto_record") {
+        let mut merged = serde_json::to_value(&state_guard.auto_record)?;
+        if let (Some(merged), Some(updates)) = (merged.as_object_mut(), auto_record.as_object()) {
+            merged.extend(updates.clone());
+        }
+        let auto_record: AutoRecordConfig = serde_json::from_value(merged)?;
+        if !(0.0..=1.0).contains(&auto_record.min_severity) {
+            return Err(crate::error::Error::Validation(
+                "auto_record.min_severity must be between 0 and 1".to_string(),
+            ));
+        }
+        state_guard.auto_record = auto_record;
+    }
+
     info!("Anomaly detection configuration updated");
 
     Ok(json!({
@@ -242,7 +360,8 @@ async fn handle_configure(arguments: Value) -> Result<Value> {
             "performance_threshold": config.performance_threshold,
             "entity_growth_threshold": config.entity_growth_threshold,
             "whitelist_count": config.whitelist.len()
-        }
+        },
+        "auto_record": state_guard.auto_record
     }))
 }
 
@@ -304,6 +423,7 @@ async fn handle_status() -> Result<Value> {
 
     Ok(json!({
         "is_monitoring": state_guard.is_monitoring,
+        "auto_record": state_guard.auto_record,
         "detectors": [
             "PhysicsDetector",
             "PerformanceDetector",
@@ -373,6 +493,32 @@ mod tests {
         assert_eq!(result["config"]["z_score_threshold"], 2.5);
     }
 
+    #[tokio::test]
+    async fn test_auto_record_requires_recording() {
+        let mut state = AnomalyState::new();
+        let anomaly = Anomaly {
+            id: "spike-1".to_string(),
+            anomaly_type: crate::anomaly_detector::AnomalyType::PerformanceSpike,
+            entity_id: None,
+            component: None,
+            severity: 0.95,
+            description: "Frame time tripled".to_string(),
+            detected_at: chrono::Utc::now(),
+            metadata: std::collections::HashMap::new(),
+        };
+
+        // Disabled by default
+        assert!(state.auto_record(&[anomaly.clone()]).await.is_empty());
+
+        state.auto_record.enabled = true;
+        state.auto_record.directory = tempfile::tempdir().unwrap().path().to_path_buf();
+        let captures = state.auto_record(&[anomaly]).await;
+        assert_eq!(captures[0]["anomaly_id"], "spike-1");
+        // Nothing is being recorded, so there is nothing to capture
+        assert!(captures[0]["error"].is_string());
+        assert!(state.capture_pending_until.is_none());
+    }
+
     #[tokio::test]
     async fn test_anomaly_status() {
         let result = handle_status().await.unwrap();
@@ -0,0 +1,453 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+/// Benchmark tool measuring the debugger's own overhead on the connected game
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse, DebugCommand, DebugOverlayType};
+use crate::diagnostics_bridge::{fetch_diagnostics, paths};
+use crate::error::{Error, Result};
+
+/// Environment variable overriding where baselines are stored
+pub const BASELINES_PATH_ENV: &str = "BEVY_DEBUGGER_BENCHMARK_BASELINES";
+
+/// Default baselines file, relative to the working directory so each project has its own
+pub const DEFAULT_BASELINES_PATH: &str = ".bevy_debugger/benchmark_baselines.json";
+
+/// Default number of requests per measurement
+pub const DEFAULT_ITERATIONS: usize = 50;
+
+/// Upper bound for requests per measurement
+pub const MAX_ITERATIONS: usize = 1000;
+
+/// Default regression tolerance in percent
+pub const DEFAULT_TOLERANCE_PCT: f64 = 10.0;
+
+/// Frame time samples taken per frame time measurement
+const FRAME_SAMPLES: usize = 10;
+
+/// Pause between frame time samples
+const FRAME_SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Round-trip latency distribution in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl LatencyStats {
+    fn from_samples(mut samples: Vec<f64>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_by(f64::total_cmp);
+        let percentile = |p: f64| samples[((samples.len() - 1) as f64 * p).round() as usize];
+        Some(Self {
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+/// Results of one benchmark run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub timestamp: DateTime<Utc>,
+    pub iterations: usize,
+    pub latency_ms: Option<LatencyStats>,
+    pub queries_per_second: Option<f64>,
+    /// Frame time with the debugger idle
+    pub idle_frame_time_ms: Option<f64>,
+    /// Frame time while queries are being answered
+    pub loaded_frame_time_ms: Option<f64>,
+    pub query_overhead_pct: Option<f64>,
+    pub overlay: Option<String>,
+    /// Frame time with `overlay` enabled
+    pub overlay_frame_time_ms: Option<f64>,
+    pub overlay_overhead_pct: Option<f64>,
+}
+
+/// One metric of a run compared with a stored baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    /// Positive when the current run is worse
+    pub change_pct: f64,
+    pub regressed: bool,
+}
+
+impl BenchmarkReport {
+    /// Metrics by name, with whether higher values are better
+    fn metrics(&self) -> Vec<(&'static str, Option<f64>, bool)> {
+        vec![
+            (
+                "latency_p50_ms",
+                self.latency_ms.as_ref().map(|l| l.p50),
+                false,
+            ),
+            (
+                "latency_p95_ms",
+                self.latency_ms.as_ref().map(|l| l.p95),
+                false,
+            ),
+            ("queries_per_second", self.queries_per_second, true),
+            ("idle_frame_time_ms", self.idle_frame_time_ms, false),
+            ("query_overhead_pct", self.query_overhead_pct, false),
+            ("overlay_overhead_pct", self.overlay_overhead_pct, false),
+        ]
+    }
+
+    /// Compare with `baseline`, flagging metrics worse by more than `tolerance_pct`
+    ///
+    /// Overhead percentages are compared in percentage points, since their
+    /// baselines are often close to zero.
+    pub fn compare(&self, baseline: &BenchmarkReport, tolerance_pct: f64) -> Vec<MetricComparison> {
+        self.metrics()
+            .into_iter()
+            .zip(baseline.metrics())
+            .filter_map(|((metric, current, higher_is_better), (_, baseline, _))| {
+                let (current, baseline) = (current?, baseline?);
+                let worse_by = if higher_is_better {
+                    baseline - current
+                } else {
+                    current - baseline
+                };
+                let change_pct = if metric.ends_with("_pct") {
+                    worse_by
+                } else if baseline.abs() > f64::EPSILON {
+                    worse_by / baseline.abs() * 100.0
+                } else {
+                    0.0
+                };
+                Some(MetricComparison {
+                    metric: metric.to_string(),
+                    baseline,
+                    current,
+                    change_pct,
+                    regressed: change_pct > tolerance_pct,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Named benchmark baselines saved for a project
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BaselineStore {
+    pub baselines: BTreeMap<String, BenchmarkReport>,
+}
+
+impl BaselineStore {
+    /// Baselines location from `BEVY_DEBUGGER_BENCHMARK_BASELINES`, or the default
+    pub fn default_path() -> PathBuf {
+        std::env::var(BASELINES_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_BASELINES_PATH))
+    }
+
+    /// Load saved baselines; a missing file yields none
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Handle benchmark tool requests
+///
+/// # Errors
+/// Returns error if arguments are invalid or the baselines file can't be read or written
+pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    debug!("Benchmark tool called with arguments: {}", arguments);
+
+    let action = arguments
+        .get("action")
+        .and_then(|a| a.as_str())
+        .unwrap_or("run");
+    let baseline_name = arguments
+        .get("baseline")
+        .and_then(|b| b.as_str())
+        .unwrap_or("default");
+    let path = BaselineStore::default_path();
+
+    match action {
+        "run" | "save_baseline" => {
+            if !brp_client.read().await.is_connected() {
+                return Ok(json!({
+                    "error": "BRP client not connected",
+                    "message": "Cannot benchmark - not connected to Bevy game",
+                    "brp_connected": false
+                }));
+            }
+            let report = run_benchmark(&arguments, &brp_client).await?;
+            let mut store = BaselineStore::load(&path)?;
+
+            if action == "save_baseline" {
+                store
+                    .baselines
+                    .insert(baseline_name.to_string(), report.clone());
+                store.save(&path)?;
+                info!("Saved benchmark baseline '{}'", baseline_name);
+                return Ok(json!({
+                    "message": format!("Saved baseline '{}'", baseline_name),
+                    "report": report,
+                }));
+            }
+
+            let tolerance_pct = arguments
+                .get("tolerance_pct")
+                .and_then(|t| t.as_f64())
+                .unwrap_or(DEFAULT_TOLERANCE_PCT);
+            let comparison = store
+                .baselines
+                .get(baseline_name)
+                .map(|baseline| report.compare(baseline, tolerance_pct));
+            let regressions = comparison
+                .as_ref()
+                .map(|c| c.iter().filter(|m| m.regressed).count())
+                .unwrap_or(0);
+            Ok(json!({
+                "report": report,
+                "baseline": baseline_name,
+                "comparison": comparison,
+                "regressions": regressions,
+                "tolerance_pct": tolerance_pct,
+            }))
+        }
+        "baselines" => Ok(json!(BaselineStore::load(&path)?)),
+        "delete_baseline" => {
+            let mut store = BaselineStore::load(&path)?;
+            let removed = store.baselines.remove(baseline_name).is_some();
+            if removed {
+                store.save(&path)?;
+            }
+            Ok(json!({ "baseline": baseline_name, "removed": removed }))
+        }
+        _ => Ok(json!({
+            "error": "Invalid action",
+            "message": format!("Unknown action: {}. Available actions: run, save_baseline, baselines, delete_baseline", action),
+            "available_actions": ["run", "save_baseline", "baselines", "delete_baseline"]
+        })),
+    }
+}
+
+/// Measure latency, throughput and frame time overheads against the game
+async fn run_benchmark(
+    arguments: &Value,
+    brp_client: &Arc<RwLock<BrpClient>>,
+) -> Result<BenchmarkReport> {
+    let iterations = arguments
+        .get("iterations")
+        .and_then(|i| i.as_u64())
+        .map(|i| (i as usize).clamp(1, MAX_ITERATIONS))
+        .unwrap_or(DEFAULT_ITERATIONS);
+    let overlay = match arguments.get("overlay") {
+        Some(Value::Bool(false)) => None,
+        Some(name) => Some(
+            serde_json::from_value::<DebugOverlayType>(name.clone())
+                .map_err(|e| Error::Validation(format!("Invalid 'overlay' field: {e}")))?,
+        ),
+        None => Some(DebugOverlayType::PerformanceMetrics),
+    };
+
+    let idle_frame_time_ms = sample_frame_time(brp_client).await;
+
+    // Round trips with the cheapest request the game answers
+    let mut latencies = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        if send(brp_client, &BrpRequest::ListComponents).await.is_ok() {
+            latencies.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+
+    // Throughput of real queries, reading frame time between them
+    let query = BrpRequest::Query {
+        filter: None,
+        limit: Some(100),
+        strict: Some(false),
+    };
+    let mut loaded_frame_times = Vec::new();
+    let mut answered = 0;
+    let start = Instant::now();
+    for i in 0..iterations {
+        if send(brp_client, &query).await.is_ok() {
+            answered += 1;
+        }
+        if i % (iterations / FRAME_SAMPLES).max(1) == 0 {
+            if let Ok(snapshot) =
+                fetch_diagnostics(brp_client, Some(vec![paths::FRAME_TIME.to_string()])).await
+            {
+                loaded_frame_times.extend(snapshot.frame_time_ms().map(f64::from));
+            }
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    let loaded_frame_time_ms = mean(&loaded_frame_times);
+
+    let overlay_frame_time_ms = match &overlay {
+        Some(overlay) => {
+            set_overlay(brp_client, overlay, true).await;
+            let frame_time = sample_frame_time(brp_client).await;
+            set_overlay(brp_client, overlay, false).await;
+            frame_time
+        }
+        None => None,
+    };
+
+    Ok(BenchmarkReport {
+        timestamp: Utc::now(),
+        iterations,
+        latency_ms: LatencyStats::from_samples(latencies),
+        queries_per_second: (answered > 0 && elapsed > 0.0).then(|| answered as f64 / elapsed),
+        idle_frame_time_ms,
+        loaded_frame_time_ms,
+        query_overhead_pct: overhead_pct(idle_frame_time_ms, loaded_frame_time_ms),
+        overlay: overlay.map(|o| format!("{:?}", o)),
+        overlay_frame_time_ms,
+        overlay_overhead_pct: overhead_pct(idle_frame_time_ms, overlay_frame_time_ms),
+    })
+}
+
+async fn send(brp_client: &Arc<RwLock<BrpClient>>, request: &BrpRequest) -> Result<()> {
+    match brp_client.write().await.send_request(request).await? {
+        BrpResponse::Success(_) => Ok(()),
+        BrpResponse::Error(error) => Err(Error::Brp(error.message)),
+    }
+}
+
+/// Average frame time over a short window, if the game publishes frame time diagnostics
+async fn sample_frame_time(brp_client: &Arc<RwLock<BrpClient>>) -> Option<f64> {
+    let mut samples = Vec::with_capacity(FRAME_SAMPLES);
+    for _ in 0..FRAME_SAMPLES {
+        match fetch_diagnostics(brp_client, Some(vec![paths::FRAME_TIME.to_string()])).await {
+            Ok(snapshot) => samples.extend(snapshot.frame_time_ms().map(f64::from)),
+            Err(e) => {
+                debug!("Frame time unavailable for benchmark: {}", e);
+                return None;
+            }
+        }
+        tokio::time::sleep(FRAME_SAMPLE_INTERVAL).await;
+    }
+    mean(&samples)
+}
+
+async fn set_overlay(
+    brp_client: &Arc<RwLock<BrpClient>>,
+    overlay: &DebugOverlayType,
+    enabled: bool,
+) {
+    let request = BrpRequest::Debug {
+        command: DebugCommand::SetVisualDebug {
+            overlay_type: overlay.clone(),
+            enabled,
+            config: None,
+        },
+        correlation_id: Uuid::new_v4().to_string(),
+        priority: Some(5),
+    };
+    if let Err(e) = send(brp_client, &request).await {
+        warn!(
+            "Failed to set {:?} overlay to {} for benchmark: {}",
+            overlay, enabled, e
+        );
+    }
+}
+
+fn mean(samples: &[f64]) -> Option<f64> {
+    (!samples.is_empty()).then(|| samples.iter().sum::<f64>() / samples.len() as f64)
+}
+
+fn overhead_pct(base: Option<f64>, measured: Option<f64>) -> Option<f64> {
+    match (base, measured) {
+        (Some(base), Some(measured)) if base > 0.0 => Some((measured - base) / base * 100.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(p50: f64, qps: f64, overlay_overhead_pct: f64) -> BenchmarkReport {
+        BenchmarkReport {
+            timestamp: Utc::now(),
+            iterations: 50,
+            latency_ms: LatencyStats::from_samples(vec![p50; 10]),
+            queries_per_second: Some(qps),
+            idle_frame_time_ms: Some(16.6),
+            loaded_frame_time_ms: Some(16.9),
+            query_overhead_pct: overhead_pct(Some(16.6), Some(16.9)),
+            overlay: Some("PerformanceMetrics".to_string()),
+            overlay_frame_time_ms: None,
+            overlay_overhead_pct: Some(overlay_overhead_pct),
+        }
+    }
+
+    #[test]
+    fn test_compare_flags_regressions() {
+        let baseline = report(2.0, 400.0, 1.0);
+        let current = report(2.1, 300.0, 13.5);
+        let comparison = current.compare(&baseline, DEFAULT_TOLERANCE_PCT);
+
+        let regressed: Vec<_> = comparison
+            .iter()
+            .filter(|m| m.regressed)
+            .map(|m| m.metric.as_str())
+            .collect();
+        // 5% slower round trips is within tolerance; a quarter of the throughput
+        // lost and 12.5 more points of overlay overhead are not
+        assert_eq!(
+            regressed,
+            vec!["queries_per_second", "overlay_overhead_pct"]
+        );
+        assert!(!current.compare(&current, 0.0).iter().any(|m| m.regressed));
+    }
+
+    #[test]
+    fn test_baselines_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baselines.json");
+        assert!(BaselineStore::load(&path).unwrap().baselines.is_empty());
+
+        let mut store = BaselineStore::default();
+        store
+            .baselines
+            .insert("release".to_string(), report(1.5, 500.0, 0.5));
+        store.save(&path).unwrap();
+
+        let loaded = BaselineStore::load(&path).unwrap();
+        let baseline = &loaded.baselines["release"];
+        assert_eq!(
+            baseline.latency_ms,
+            LatencyStats::from_samples(vec![1.5; 10])
+        );
+        assert_eq!(baseline.queries_per_second, Some(500.0));
+    }
+}
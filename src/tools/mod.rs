@@ -1,9 +1,15 @@
 pub mod anomaly;
+pub mod benchmark;
 pub mod experiment;
 pub mod hypothesis;
+pub mod mutate;
 pub mod observe;
 pub mod observe_optimized;
 pub mod orchestration;
+pub mod perf_timeline;
 pub mod replay;
 pub mod replay_v2;
+pub mod schema_history;
+pub mod search_world;
 pub mod stress;
+pub mod types;
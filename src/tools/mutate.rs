@@ -0,0 +1,735 @@
+//! Validated component mutation through reflection schemas
+//!
+//! A mutation names an entity, a component, an optional dotted field path and
+//! the new value. The value is checked against the component's schema (from
+//! the game's `bevy/list_components`, cached, or inferred from the current
+//! value when the game sends none) before the whole component is written back
+//! with `bevy/set`, so a wrong path, type or out-of-range number comes back as
+//! a list of errors instead of a failed or silently ignored set.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse, BrpResult, ComponentValue, EntityId};
+use crate::error::{Error, Result};
+
+/// Nesting depth past which values are accepted without further checks
+const MAX_VALIDATION_DEPTH: usize = 32;
+
+/// Known component types suggested when a name does not match
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Arguments of the `mutate` tool
+#[derive(Debug, Clone, Deserialize)]
+pub struct MutationRequest {
+    pub entity: EntityId,
+    /// Full type path, or a short name that matches exactly one known type
+    pub component: String,
+    /// Dotted field path within the component, e.g. `translation.x` or
+    /// `items.2`; empty replaces the whole component
+    #[serde(default)]
+    pub path: String,
+    pub value: Value,
+    /// Validate only, without sending anything to the game
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Re-read component schemas from the game before validating
+    #[serde(default)]
+    pub refresh_schemas: bool,
+}
+
+/// Why a proposed value was rejected
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MutationIssue {
+    /// Field path of the offending value within the component
+    pub path: String,
+    pub message: String,
+    /// What would have been accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+}
+
+impl MutationIssue {
+    fn new(path: &str, message: impl Into<String>, expected: Option<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+            expected,
+        }
+    }
+}
+
+/// Component schemas by type path, filled from `bevy/list_components`
+fn schema_cache() -> &'static std::sync::RwLock<HashMap<String, Value>> {
+    static SCHEMAS: OnceLock<std::sync::RwLock<HashMap<String, Value>>> = OnceLock::new();
+    SCHEMAS.get_or_init(Default::default)
+}
+
+pub(crate) fn cached_schemas() -> HashMap<String, Value> {
+    schema_cache()
+        .read()
+        .map(|schemas| schemas.clone())
+        .unwrap_or_default()
+}
+
+/// Handle `mutate` tool requests
+pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    debug!("Mutate tool called with arguments: {}", arguments);
+
+    let request: MutationRequest = serde_json::from_value(arguments)
+        .map_err(|e| Error::Validation(format!("Invalid mutate parameters: {e}")))?;
+
+    if !brp_client.read().await.is_connected() {
+        return Err(Error::Connection(
+            "Cannot mutate components - not connected to Bevy game".to_string(),
+        ));
+    }
+
+    let mut schemas = cached_schemas();
+    if request.refresh_schemas || resolve_component(&request.component, &schemas).is_none() {
+        match refresh_schemas(&brp_client).await {
+            Ok(refreshed) => schemas = refreshed,
+            Err(e) => warn!("Could not read component schemas from the game: {}", e),
+        }
+    }
+    let component = resolve_component(&request.component, &schemas)
+        .unwrap_or_else(|| request.component.clone());
+
+    let Some(current) = fetch_component(&brp_client, request.entity, &component).await? else {
+        return Ok(rejected(
+            &request,
+            &component,
+            vec![MutationIssue::new(
+                "",
+                format!("Entity {} has no component {}", request.entity, component),
+                suggest_components(&request.component, &schemas),
+            )],
+        ));
+    };
+
+    let schema = schemas
+        .get(&component)
+        .cloned()
+        .unwrap_or_else(|| infer_schema(&current));
+    let segments = path_segments(&request.path);
+
+    let mut issues = Vec::new();
+    match schema_at(&schema, &segments, &schemas) {
+        Ok(field_schema) => validate(
+            &field_schema,
+            &request.value,
+            &request.path,
+            &schemas,
+            0,
+            &mut issues,
+        ),
+        Err(issue) => issues.push(issue),
+    }
+
+    let mut updated = current.clone();
+    let previous = if issues.is_empty() {
+        match set_at_path(&mut updated, &segments, request.value.clone()) {
+            Ok(previous) => Some(previous),
+            Err(issue) => {
+                issues.push(issue);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if !issues.is_empty() {
+        return Ok(rejected(&request, &component, issues));
+    }
+
+    if request.dry_run {
+        return Ok(json!({
+            "applied": false,
+            "valid": true,
+            "entity": request.entity,
+            "component": component,
+            "path": request.path,
+            "previous": previous,
+            "component_value": updated,
+        }));
+    }
+
+    let set = BrpRequest::Set {
+        entity: request.entity,
+        components: HashMap::from([(component.clone(), updated.clone())]),
+    };
+    match brp_client.write().await.send_request(&set).await? {
+        BrpResponse::Success(_) => Ok(json!({
+            "applied": true,
+            "entity": request.entity,
+            "component": component,
+            "path": request.path,
+            "previous": previous,
+            "value": request.value,
+        })),
+        BrpResponse::Error(err) => Err(Error::Brp(format!(
+            "Game rejected {} on entity {}: {}",
+            component, request.entity, err.message
+        ))),
+    }
+}
+
+fn rejected(request: &MutationRequest, component: &str, errors: Vec<MutationIssue>) -> Value {
+    json!({
+        "applied": false,
+        "valid": false,
+        "entity": request.entity,
+        "component": component,
+        "path": request.path,
+        "errors": errors,
+    })
+}
+
+/// Re-read component schemas from the game into the cache
+pub(crate) async fn refresh_schemas(
+    brp_client: &Arc<RwLock<BrpClient>>,
+) -> Result<HashMap<String, Value>> {
+    let response = brp_client
+        .write()
+        .await
+        .send_request(&BrpRequest::ListComponents)
+        .await?;
+    let types = match response {
+        BrpResponse::Success(result) => match *result {
+            BrpResult::ComponentTypes(types) => types,
+            other => {
+                return Err(Error::Brp(format!(
+                    "Unexpected response listing components: {:?}",
+                    other
+                )))
+            }
+        },
+        BrpResponse::Error(err) => return Err(Error::Brp(err.message)),
+    };
+
+    let mut schemas = HashMap::new();
+    for info in types {
+        let Some(schema) = info.schema else {
+            continue;
+        };
+        // Definitions referenced with `$ref` resolve like any other type
+        if let Some(defs) = schema.get("$defs").and_then(Value::as_object) {
+            for (name, def) in defs {
+                schemas.insert(name.clone(), def.clone());
+            }
+        }
+        schemas.insert(info.id, schema);
+    }
+
+    if let Ok(mut cache) = schema_cache().write() {
+        *cache = schemas.clone();
+    }
+    Ok(schemas)
+}
+
+/// Read one component of an entity; `None` when the entity lacks it
+async fn fetch_component(
+    brp_client: &Arc<RwLock<BrpClient>>,
+    entity: EntityId,
+    component: &str,
+) -> Result<Option<ComponentValue>> {
+    let request = BrpRequest::Get {
+        entity,
+        components: Some(vec![component.to_string()]),
+    };
+    match brp_client.write().await.send_request(&request).await? {
+        BrpResponse::Success(result) => match *result {
+            BrpResult::Entity(mut data) => Ok(data.components.remove(component)),
+            other => Err(Error::Brp(format!(
+                "Unexpected response reading entity {}: {:?}",
+                entity, other
+            ))),
+        },
+        BrpResponse::Error(err) => Err(Error::Brp(format!(
+            "Could not read entity {}: {}",
+            entity, err.message
+        ))),
+    }
+}
+
+/// The known type path `name` refers to, by full path or unique short name
+fn resolve_component(name: &str, schemas: &HashMap<String, Value>) -> Option<String> {
+    if schemas.contains_key(name) {
+        return Some(name.to_string());
+    }
+    let suffix = format!("::{name}");
+    let mut matches = schemas.keys().filter(|path| path.ends_with(&suffix));
+    let first = matches.next()?;
+    matches.next().is_none().then(|| first.clone())
+}
+
+fn suggest_components(name: &str, schemas: &HashMap<String, Value>) -> Option<String> {
+    let needle = name.rsplit("::").next().unwrap_or(name).to_lowercase();
+    let mut similar: Vec<&String> = schemas
+        .keys()
+        .filter(|path| path.to_lowercase().contains(&needle))
+        .collect();
+    if similar.is_empty() {
+        return None;
+    }
+    similar.sort();
+    similar.truncate(MAX_SUGGESTIONS);
+    Some(format!(
+        "a component on the entity, e.g. {}",
+        similar
+            .iter()
+            .map(|path| path.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+fn path_segments(path: &str) -> Vec<&str> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// A schema matching the shape of `value`, for components the game sent no schema for
+pub fn infer_schema(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({}),
+        Value::Bool(_) => json!({"type": "boolean"}),
+        Value::Number(n) if n.is_f64() => json!({"type": "number"}),
+        Value::Number(_) => json!({"type": "integer"}),
+        Value::String(_) => json!({"type": "string"}),
+        Value::Array(items) => match items.first() {
+            Some(first) => json!({"type": "array", "items": infer_schema(first)}),
+            None => json!({"type": "array"}),
+        },
+        Value::Object(fields) => {
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(name, field)| (name.clone(), infer_schema(field)))
+                .collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            })
+        }
+    }
+}
+
+/// Schema of a Rust primitive named by a `$ref`, with its numeric range
+fn primitive_schema(type_path: &str) -> Option<Value> {
+    let integer =
+        |min: Value, max: Value| json!({"type": "integer", "minimum": min, "maximum": max});
+    Some(match type_path {
+        "bool" => json!({"type": "boolean"}),
+        "f32" | "f64" => json!({"type": "number"}),
+        "u8" => integer(json!(u8::MIN), json!(u8::MAX)),
+        "u16" => integer(json!(u16::MIN), json!(u16::MAX)),
+        "u32" => integer(json!(u32::MIN), json!(u32::MAX)),
+        "u64" | "usize" => integer(json!(u64::MIN), json!(u64::MAX)),
+        "i8" => integer(json!(i8::MIN), json!(i8::MAX)),
+        "i16" => integer(json!(i16::MIN), json!(i16::MAX)),
+        "i32" => integer(json!(i32::MIN), json!(i32::MAX)),
+        "i64" | "isize" => integer(json!(i64::MIN), json!(i64::MAX)),
+        "char" | "str" | "&str" | "String" | "alloc::string::String" => json!({"type": "string"}),
+        _ => return None,
+    })
+}
+
+/// Follow `$ref`s to the schema they name
+///
+/// Bevy's registry schema sometimes nests the reference as
+/// `{"type": {"$ref": ...}}`; both forms are accepted.
+pub(crate) fn resolve(schema: &Value, schemas: &HashMap<String, Value>) -> Value {
+    let mut schema = schema.clone();
+    for _ in 0..MAX_VALIDATION_DEPTH {
+        let reference = schema
+            .get("$ref")
+            .or_else(|| schema.get("type").and_then(|t| t.get("$ref")))
+            .and_then(Value::as_str)
+            .map(|r| r.trim_start_matches("#/$defs/").to_string());
+        let Some(reference) = reference else {
+            return schema;
+        };
+        schema = match schemas.get(&reference) {
+            Some(target) => target.clone(),
+            None => primitive_schema(&reference).unwrap_or_else(|| json!({})),
+        };
+    }
+    schema
+}
+
+/// The schema of the field at `segments`, or why the path does not exist
+fn schema_at(
+    schema: &Value,
+    segments: &[&str],
+    schemas: &HashMap<String, Value>,
+) -> std::result::Result<Value, MutationIssue> {
+    let mut current = resolve(schema, schemas);
+    for (depth, segment) in segments.iter().enumerate() {
+        let parent = segments[..depth].join(".");
+        current = if let Some(properties) = current.get("properties").and_then(Value::as_object) {
+            match properties.get(*segment) {
+                Some(field) => resolve(field, schemas),
+                None => {
+                    let mut fields: Vec<&str> = properties.keys().map(String::as_str).collect();
+                    fields.sort_unstable();
+                    return Err(MutationIssue::new(
+                        &parent,
+                        format!("Unknown field '{segment}'"),
+                        Some(format!("one of: {}", fields.join(", "))),
+                    ));
+                }
+            }
+        } else if let Some(items) = current.get("items") {
+            if segment.parse::<usize>().is_err() {
+                return Err(MutationIssue::new(
+                    &parent,
+                    format!("'{segment}' is not a list index"),
+                    Some("a non-negative integer index".to_string()),
+                ));
+            }
+            resolve(items, schemas)
+        } else if let Some(values) = current
+            .get("additionalProperties")
+            .filter(|v| v.is_object())
+        {
+            resolve(values, schemas)
+        } else if type_names(&current).is_empty() {
+            // Nothing is known about the field, so nothing below it can be checked
+            json!({})
+        } else {
+            return Err(MutationIssue::new(
+                &parent,
+                format!("Cannot descend into '{segment}'; the value has no fields"),
+                None,
+            ));
+        };
+    }
+    Ok(current)
+}
+
+fn type_names(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_path(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{path}.{segment}")
+    }
+}
+
+/// Check `value` against `schema`, collecting every problem found
+pub fn validate(
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    schemas: &HashMap<String, Value>,
+    depth: usize,
+    issues: &mut Vec<MutationIssue>,
+) {
+    if depth > MAX_VALIDATION_DEPTH {
+        return;
+    }
+    let schema = resolve(schema, schemas);
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let names: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            issues.push(MutationIssue::new(
+                path,
+                format!("{value} is not an allowed value"),
+                Some(format!("one of: {}", names.join(", "))),
+            ));
+        }
+        return;
+    }
+
+    if let Some(variants) = schema.get("oneOf").and_then(Value::as_array) {
+        let matches_any = variants.iter().any(|variant| {
+            let mut variant_issues = Vec::new();
+            validate(
+                variant,
+                value,
+                path,
+                schemas,
+                depth + 1,
+                &mut variant_issues,
+            );
+            variant_issues.is_empty()
+        });
+        if !matches_any {
+            issues.push(MutationIssue::new(
+                path,
+                "Value does not match any variant",
+                Some(format!("one of {} variants", variants.len())),
+            ));
+        }
+        return;
+    }
+
+    let expected = type_names(&schema);
+    let found = json_type(value);
+    let type_ok = expected.is_empty()
+        || expected
+            .iter()
+            .any(|name| *name == found || (*name == "number" && found == "integer"));
+    if !type_ok {
+        issues.push(MutationIssue::new(
+            path,
+            format!("Expected {} but got {}", expected.join(" or "), found),
+            Some(expected.join(" or ")),
+        ));
+        return;
+    }
+
+    match value {
+        Value::Number(n) => {
+            let number = n.as_f64().unwrap_or_default();
+            let minimum = schema.get("minimum").and_then(Value::as_f64);
+            let maximum = schema.get("maximum").and_then(Value::as_f64);
+            if minimum.is_some_and(|min| number < min) || maximum.is_some_and(|max| number > max) {
+                let range = format!(
+                    "{} to {}",
+                    minimum.map_or("-inf".to_string(), |m| m.to_string()),
+                    maximum.map_or("inf".to_string(), |m| m.to_string())
+                );
+                issues.push(MutationIssue::new(
+                    path,
+                    format!("{n} is out of range"),
+                    Some(range),
+                ));
+            }
+        }
+        Value::Array(items) => {
+            let min_items = schema.get("minItems").and_then(Value::as_u64);
+            let max_items = schema.get("maxItems").and_then(Value::as_u64);
+            let len = items.len() as u64;
+            if min_items.is_some_and(|min| len < min) || max_items.is_some_and(|max| len > max) {
+                issues.push(MutationIssue::new(
+                    path,
+                    format!("List has {len} items"),
+                    Some(format!(
+                        "{} to {} items",
+                        min_items.unwrap_or(0),
+                        max_items.map_or("any number of".to_string(), |m| m.to_string())
+                    )),
+                ));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = join_path(path, &index.to_string());
+                    validate(item_schema, item, &item_path, schemas, depth + 1, issues);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let additional = schema.get("additionalProperties");
+            for (name, field) in fields {
+                let field_path = join_path(path, name);
+                match (properties.and_then(|p| p.get(name)), additional) {
+                    (Some(field_schema), _) => {
+                        validate(field_schema, field, &field_path, schemas, depth + 1, issues)
+                    }
+                    (None, Some(Value::Bool(false))) => {
+                        let mut known: Vec<&str> = properties
+                            .map(|p| p.keys().map(String::as_str).collect())
+                            .unwrap_or_default();
+                        known.sort_unstable();
+                        issues.push(MutationIssue::new(
+                            &field_path,
+                            format!("Unknown field '{name}'"),
+                            Some(format!("one of: {}", known.join(", "))),
+                        ));
+                    }
+                    (None, Some(values)) if values.is_object() => {
+                        validate(values, field, &field_path, schemas, depth + 1, issues)
+                    }
+                    (None, _) => {}
+                }
+            }
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !fields.contains_key(required) {
+                    issues.push(MutationIssue::new(
+                        &join_path(path, required),
+                        format!("Missing required field '{required}'"),
+                        None,
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace the value at `segments` in `target`, returning the old value
+pub fn set_at_path(
+    target: &mut Value,
+    segments: &[&str],
+    value: Value,
+) -> std::result::Result<Value, MutationIssue> {
+    let mut current = target;
+    for (depth, segment) in segments.iter().enumerate() {
+        let parent = segments[..depth].join(".");
+        current = match current {
+            Value::Object(fields) => fields.get_mut(*segment).ok_or_else(|| {
+                MutationIssue::new(
+                    &parent,
+                    format!("The current value has no field '{segment}'"),
+                    None,
+                )
+            })?,
+            Value::Array(items) => {
+                let len = items.len();
+                segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get_mut(index))
+                    .ok_or_else(|| {
+                        MutationIssue::new(
+                            &parent,
+                            format!("Index '{segment}' is out of bounds"),
+                            Some(format!("an index below {len}")),
+                        )
+                    })?
+            }
+            _ => {
+                return Err(MutationIssue::new(
+                    &parent,
+                    format!("Cannot descend into '{segment}'; the value has no fields"),
+                    None,
+                ))
+            }
+        };
+    }
+    Ok(std::mem::replace(current, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schemas() -> HashMap<String, Value> {
+        HashMap::from([
+            (
+                "game::Health".to_string(),
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "current": {"type": {"$ref": "#/$defs/f32"}},
+                        "armor": {"$ref": "#/$defs/u8"},
+                        "status": {"enum": ["Alive", "Dead"]}
+                    },
+                    "required": ["current", "armor"],
+                    "additionalProperties": false
+                }),
+            ),
+            ("f32".to_string(), json!({"type": "number"})),
+        ])
+    }
+
+    #[test]
+    fn test_validation_reports_paths_types_and_ranges() {
+        let schemas = schemas();
+        let schema = &schemas["game::Health"];
+
+        let field = schema_at(schema, &["armor"], &schemas).unwrap();
+        let mut issues = Vec::new();
+        validate(&field, &json!(300), "armor", &schemas, 0, &mut issues);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].path, "armor");
+        assert_eq!(issues[0].expected.as_deref(), Some("0 to 255"));
+
+        let unknown = schema_at(schema, &["shield"], &schemas).unwrap_err();
+        assert_eq!(
+            unknown.expected.as_deref(),
+            Some("one of: armor, current, status")
+        );
+
+        let mut issues = Vec::new();
+        validate(
+            schema,
+            &json!({"current": "full", "status": "Asleep", "mana": 1}),
+            "",
+            &schemas,
+            0,
+            &mut issues,
+        );
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(issues.len(), 4, "{issues:?}");
+        assert!(paths.contains(&"current"));
+        assert!(paths.contains(&"status"));
+        assert!(paths.contains(&"mana"));
+        assert!(paths.contains(&"armor"));
+
+        let mut issues = Vec::new();
+        validate(
+            schema,
+            &json!({"current": 50, "armor": 3}),
+            "",
+            &schemas,
+            0,
+            &mut issues,
+        );
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn test_set_at_path_with_inferred_schema() {
+        let mut transform = json!({
+            "translation": {"x": 1.0, "y": 2.0, "z": 3.0},
+            "layers": [1, 2]
+        });
+        let schema = infer_schema(&transform);
+        let schemas = HashMap::new();
+
+        let field = schema_at(&schema, &["translation", "y"], &schemas).unwrap();
+        let mut issues = Vec::new();
+        validate(
+            &field,
+            &json!(true),
+            "translation.y",
+            &schemas,
+            0,
+            &mut issues,
+        );
+        assert_eq!(issues[0].message, "Expected number but got boolean");
+
+        let previous = set_at_path(&mut transform, &["translation", "y"], json!(5.5)).unwrap();
+        assert_eq!(previous, json!(2.0));
+        assert_eq!(transform["translation"]["y"], json!(5.5));
+
+        let out_of_bounds = set_at_path(&mut transform, &["layers", "4"], json!(1)).unwrap_err();
+        assert_eq!(out_of_bounds.path, "layers");
+    }
+}
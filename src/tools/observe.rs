@@ -1,11 +1,19 @@
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::RwLock;
+use tokio::sync::{OnceCell, RwLock};
 use tracing::{debug, error, info, warn};
 
 use crate::brp_client::BrpClient;
-use crate::brp_messages::{BrpResponse, BrpResult, EntityData};
+use crate::brp_client_v2::BrpClientV2;
+use crate::brp_messages::{
+    BrpErrorCode, BrpRequest, BrpResponse, BrpResult, ComponentTypeId, ComponentValue,
+    EntityData, EntityId, QueryFilter,
+};
+use crate::config::Config;
 use crate::error::{Error, Result};
 use crate::query_parser::{QueryCache, QueryMetrics, QueryParser, RegexQueryParser};
 use crate::state_diff::{FuzzyCompareConfig, GameRules, StateDiff, StateDiffResult, StateSnapshot};
@@ -20,6 +28,87 @@ pub struct ObserveState {
     last_snapshot: Option<StateSnapshot>,
     snapshots_history: Vec<StateSnapshot>, // Keep last N snapshots for windowed diffs
     max_history_size: usize,
+    delta_baselines: HashMap<(String, String), DeltaBaseline>, // Keyed by (session, query)
+}
+
+/// Most (session, query) pairs remembered for delta mode; the least recently polled go first
+const MAX_DELTA_BASELINES: usize = 64;
+
+/// Entities last sent to a session for one query in delta mode
+struct DeltaBaseline {
+    entities: HashMap<EntityId, EntityData>,
+    last_polled: Instant,
+}
+
+/// Components of one entity that differ from the previous poll
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityDelta {
+    pub id: EntityId,
+    /// New or changed component values
+    pub components: HashMap<ComponentTypeId, ComponentValue>,
+    pub removed_components: Vec<ComponentTypeId>,
+}
+
+/// What changed in a query's result since the session last polled it
+#[derive(Debug, Clone, Serialize)]
+pub struct ObserveDelta {
+    /// No baseline existed, so every entity is reported as added
+    pub first_poll: bool,
+    pub added: Vec<EntityData>,
+    pub removed: Vec<EntityId>,
+    pub changed: Vec<EntityDelta>,
+    pub unchanged_count: usize,
+}
+
+impl ObserveDelta {
+    fn between(previous: Option<&HashMap<EntityId, EntityData>>, current: &[EntityData]) -> Self {
+        let empty = HashMap::new();
+        let baseline = previous.unwrap_or(&empty);
+        let mut delta = Self {
+            first_poll: previous.is_none(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+            unchanged_count: 0,
+        };
+
+        for entity in current {
+            let Some(before) = baseline.get(&entity.id) else {
+                delta.added.push(entity.clone());
+                continue;
+            };
+            let components: HashMap<_, _> = entity
+                .components
+                .iter()
+                .filter(|(name, value)| before.components.get(*name) != Some(*value))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            let removed_components: Vec<_> = before
+                .components
+                .keys()
+                .filter(|name| !entity.components.contains_key(*name))
+                .cloned()
+                .collect();
+            if components.is_empty() && removed_components.is_empty() {
+                delta.unchanged_count += 1;
+            } else {
+                delta.changed.push(EntityDelta {
+                    id: entity.id,
+                    components,
+                    removed_components,
+                });
+            }
+        }
+
+        let current_ids: std::collections::HashSet<_> = current.iter().map(|e| e.id).collect();
+        delta.removed = baseline
+            .keys()
+            .filter(|id| !current_ids.contains(id))
+            .copied()
+            .collect();
+        delta.removed.sort_unstable();
+        delta
+    }
 }
 
 impl ObserveState {
@@ -36,6 +125,7 @@ impl ObserveState {
             last_snapshot: None,
             snapshots_history: Vec::new(),
             max_history_size: 10, // Keep last 10 snapshots
+            delta_baselines: HashMap::new(),
         })
     }
 
@@ -52,6 +142,7 @@ impl ObserveState {
             last_snapshot: None,
             snapshots_history: Vec::new(),
             max_history_size: 10,
+            delta_baselines: HashMap::new(),
         })
     }
 
@@ -124,6 +215,47 @@ impl ObserveState {
         self.last_snapshot.as_ref()
     }
 
+    /// Compare `entities` with what `session_id` was last sent for `query`, then remember them
+    pub fn take_delta(
+        &mut self,
+        session_id: &str,
+        query: &str,
+        entities: &[EntityData],
+    ) -> ObserveDelta {
+        let key = (session_id.to_string(), query.to_string());
+        let delta = ObserveDelta::between(
+            self.delta_baselines.get(&key).map(|b| &b.entities),
+            entities,
+        );
+
+        if !self.delta_baselines.contains_key(&key)
+            && self.delta_baselines.len() >= MAX_DELTA_BASELINES
+        {
+            if let Some(oldest) = self
+                .delta_baselines
+                .iter()
+                .min_by_key(|(_, baseline)| baseline.last_polled)
+                .map(|(key, _)| key.clone())
+            {
+                self.delta_baselines.remove(&oldest);
+            }
+        }
+        self.delta_baselines.insert(
+            key,
+            DeltaBaseline {
+                entities: entities.iter().map(|e| (e.id, e.clone())).collect(),
+                last_polled: Instant::now(),
+            },
+        );
+        delta
+    }
+
+    /// Forget a session's delta baselines, so its next delta poll starts over
+    pub fn clear_delta_baselines(&mut self, session_id: &str) {
+        self.delta_baselines
+            .retain(|(session, _), _| session != session_id);
+    }
+
     /// Create a snapshot without adding it to history (for testing)
     #[must_use]
     pub fn create_snapshot(&mut self, entities: Vec<EntityData>) -> StateSnapshot {
@@ -152,6 +284,112 @@ fn get_observe_state() -> Arc<RwLock<ObserveState>> {
         .clone()
 }
 
+/// Extra BRP queries an observe request can expand into with `include`
+pub const SUB_QUERIES: &[&str] = &["hierarchy", "components", "schemas"];
+
+/// Most sub-queries in flight at once; the pool's connection limit also applies
+const MAX_PARALLEL_SUB_QUERIES: usize = 4;
+
+/// Pooled client for sub-queries, so they don't queue behind the main client's single socket
+static SUB_QUERY_CLIENT: OnceCell<Arc<BrpClientV2>> = OnceCell::const_new();
+
+async fn sub_query_client(config: &Config) -> Result<Arc<BrpClientV2>> {
+    SUB_QUERY_CLIENT
+        .get_or_try_init(|| async {
+            let client = BrpClientV2::new(config.clone())?;
+            client.start().await?;
+            Ok::<_, Error>(Arc::new(client))
+        })
+        .await
+        .cloned()
+}
+
+fn sub_query_request(name: &str) -> Option<BrpRequest> {
+    match name {
+        "hierarchy" => Some(BrpRequest::Query {
+            filter: Some(QueryFilter {
+                with: Some(vec![
+                    "bevy_hierarchy::components::children::Children".to_string()
+                ]),
+                ..QueryFilter::default()
+            }),
+            limit: None,
+            strict: Some(false),
+        }),
+        "components" | "schemas" => Some(BrpRequest::ListComponents),
+        _ => None,
+    }
+}
+
+/// Shape a sub-query's BRP result for the observe response
+fn sub_query_result(name: &str, result: &BrpResult) -> Result<Value> {
+    match (name, result) {
+        ("components", BrpResult::ComponentTypes(types)) => {
+            Ok(json!(types.iter().map(|t| &t.id).collect::<Vec<_>>()))
+        }
+        ("schemas", BrpResult::ComponentTypes(types)) => Ok(Value::Object(
+            types
+                .iter()
+                .filter_map(|t| t.schema.clone().map(|schema| (t.id.clone(), schema)))
+                .collect(),
+        )),
+        _ => serde_json::to_value(result).map_err(Error::Json),
+    }
+}
+
+/// Run the named sub-queries concurrently over pooled connections
+///
+/// Each entry reports its own timing; a failed sub-query reports its error
+/// without failing the others or the main query.
+async fn run_sub_queries(names: &[String], config: &Config) -> Value {
+    let start_time = Instant::now();
+    let parallelism = MAX_PARALLEL_SUB_QUERIES
+        .min(config.resilience.connection_pool.max_connections as usize)
+        .max(1);
+    let client = sub_query_client(config).await;
+
+    let queries: serde_json::Map<String, Value> = stream::iter(names.iter().cloned())
+        .map(|name| {
+            let client = client.as_ref().map(Arc::clone).map_err(|e| e.to_string());
+            async move {
+                let query_start = Instant::now();
+                let outcome = match (client, sub_query_request(&name)) {
+                    (Ok(client), Some(request)) => match client.send_request(request).await {
+                        Ok(BrpResponse::Success(result)) => sub_query_result(&name, &result),
+                        Ok(BrpResponse::Error(error)) => Err(Error::Brp(error.to_string())),
+                        Err(e) => Err(e),
+                    },
+                    (Err(e), _) => Err(Error::Connection(e)),
+                    (_, None) => Err(Error::Validation(format!("Unknown sub-query: {}", name))),
+                };
+                let execution_time_ms = query_start.elapsed().as_millis() as u64;
+                let entry = match outcome {
+                    Ok(result) => json!({
+                        "result": result,
+                        "execution_time_ms": execution_time_ms,
+                    }),
+                    Err(e) => {
+                        warn!("Observe sub-query '{}' failed: {}", name, e);
+                        json!({
+                            "error": e.to_string(),
+                            "execution_time_ms": execution_time_ms,
+                        })
+                    }
+                };
+                (name, entry)
+            }
+        })
+        .buffer_unordered(parallelism)
+        .collect()
+        .await;
+
+    json!({
+        "queries": queries,
+        "parallelism": parallelism,
+        "wall_time_ms": start_time.elapsed().as_millis() as u64,
+    })
+}
+
 /// Handle observe tool requests
 ///
 /// # Errors
@@ -179,6 +417,41 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
         .and_then(|r| r.as_bool())
         .unwrap_or(false);
 
+    // Delta mode returns only what changed since this session's previous poll
+    let delta_mode = arguments
+        .get("delta")
+        .and_then(|d| d.as_bool())
+        .unwrap_or(false);
+
+    let session_id = arguments
+        .get("session_id")
+        .and_then(|id| id.as_str())
+        .unwrap_or("default");
+
+    // Sub-queries to run alongside the main query, e.g. ["hierarchy", "schemas"]
+    let mut includes: Vec<String> = arguments
+        .get("include")
+        .and_then(|i| i.as_array())
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|n| n.as_str())
+                .map(|n| n.trim().to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+    includes.dedup();
+    if let Some(unknown) = includes.iter().find(|n| !SUB_QUERIES.contains(&n.as_str())) {
+        return Ok(json!({
+            "error": "Unknown sub-query",
+            "message": format!("'{}' is not a sub-query observe can include", unknown),
+            "available": SUB_QUERIES,
+        }));
+    }
+    // Responses with sub-queries aren't cached; the cache holds main results only.
+    // Delta mode needs the live result to compare against the session's baseline.
+    let cacheable = !diff_mode && !delta_mode && includes.is_empty();
+
     info!(
         "Processing observe query: {} (diff_mode: {}, diff_target: {}, reflection: {})",
         query, diff_mode, diff_target, use_reflection
@@ -201,7 +474,12 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
     let state_guard = state.read().await;
 
     // Check cache first (skip cache for diff mode to ensure fresh data)
-    if !diff_mode {
+    if cacheable {
+        if let Some(mut answer) = state_guard.cache.get_not_found(query) {
+            debug!("Cached not-found answer for query: {}", query);
+            answer["cache_hit"] = json!(true);
+            return Ok(answer);
+        }
         if let Some((cached_result, entity_count)) = state_guard.cache.get(query) {
             info!("Cache hit for query: {}", query);
             let metrics = QueryMetrics {
@@ -254,9 +532,9 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
     drop(state_guard); // Release the lock before async operations
 
     // Execute BRP request
-    let client_connected = {
+    let (client_connected, config) = {
         let client = brp_client.read().await;
-        client.is_connected()
+        (client.is_connected(), client.config().clone())
     };
 
     if !client_connected {
@@ -268,24 +546,38 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
         }));
     }
 
-    let brp_response = {
+    let main_query = async {
         let mut client = brp_client.write().await;
-        match client.send_request(&brp_request).await {
-            Ok(response) => response,
-            Err(e) => {
-                error!("BRP request failed: {}", e);
-                return Ok(json!({
-                    "error": "BRP request failed",
-                    "message": e.to_string(),
-                    "query": query
-                }));
-            }
+        client.send_request(&brp_request).await
+    };
+    let sub_queries = async {
+        if includes.is_empty() {
+            None
+        } else {
+            Some(run_sub_queries(&includes, &config).await)
+        }
+    };
+    let (brp_response, sub_queries) = tokio::join!(main_query, sub_queries);
+    let brp_response = match brp_response {
+        Ok(response) => response,
+        Err(e) => {
+            error!("BRP request failed: {}", e);
+            return Ok(json!({
+                "error": "BRP request failed",
+                "message": e.to_string(),
+                "query": query
+            }));
         }
     };
 
     // Process response and handle diff mode
-    let (result_json, entity_count, diff_result) = match brp_response {
+    let (result_json, entity_count, diff_result, delta) = match brp_response {
         BrpResponse::Success(result) => {
+            // A fresh component list may include newly registered types
+            if matches!(result.as_ref(), BrpResult::ComponentTypes(_)) {
+                state.read().await.cache.clear_not_found();
+            }
+
             let entity_count = match result.as_ref() {
                 BrpResult::Entities(entities) => entities.len(),
                 BrpResult::Entity(_) => 1,
@@ -361,23 +653,43 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
                 None
             };
 
-            (result_json, entity_count, diff_result)
+            let delta = match result.as_ref() {
+                BrpResult::Entities(entities) if delta_mode => Some(
+                    state
+                        .write()
+                        .await
+                        .take_delta(session_id, query, entities),
+                ),
+                _ => None,
+            };
+
+            (result_json, entity_count, diff_result, delta)
         }
         BrpResponse::Error(error) => {
             warn!("BRP returned error: {}", error);
-            return Ok(json!({
+            let not_found = matches!(
+                error.code,
+                BrpErrorCode::EntityNotFound | BrpErrorCode::ComponentNotFound
+            );
+            let answer = json!({
                 "error": "BRP error",
                 "code": error.code,
                 "message": error.message,
-                "details": error.details
-            }));
+                "details": error.details,
+                "not_found": not_found,
+            });
+            if not_found && cacheable {
+                let state_guard = state.read().await;
+                state_guard.cache.set_not_found(query.to_string(), answer.clone());
+            }
+            return Ok(answer);
         }
     };
 
     let execution_time = start_time.elapsed().as_millis() as u64;
 
     // Cache the result (only for non-diff queries)
-    if !diff_mode {
+    if cacheable {
         let state_guard = state.read().await;
         state_guard
             .cache
@@ -393,8 +705,11 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
     };
 
     info!(
-        "Query '{}' completed in {}ms, {} entities (diff_mode: {})",
-        query, execution_time, entity_count, diff_mode
+        query,
+        duration_ms = execution_time,
+        entity_count,
+        diff_mode,
+        "Observe query completed"
     );
 
     let mut response = json!({
@@ -411,6 +726,19 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
         }
     });
 
+    if let Some(sub_queries) = sub_queries {
+        response["sub_queries"] = sub_queries;
+    }
+
+    // The delta replaces the full result; non-entity results are sent whole
+    response["metadata"]["delta_mode"] = json!(delta.is_some());
+    if let Some(delta) = delta {
+        if let Some(obj) = response.as_object_mut() {
+            obj.remove("result");
+        }
+        response["delta"] = serde_json::to_value(&delta).map_err(Error::Json)?;
+    }
+
     // Add diff information if available
     if let Some(diff_result) = diff_result {
         let grouped_changes = {
@@ -445,6 +773,85 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
     Ok(response)
 }
 
+/// Whether `response` is an observe answer for a missing entity or component
+pub fn is_not_found(response: &Value) -> bool {
+    response.get("not_found").and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// Drop cached query results whenever the game changes
+///
+/// Any mutation can change an answer, and spawns or a new connection can
+/// make a missing entity or component appear, so both clear the cache,
+/// including its "not found" answers.
+pub fn spawn_query_cache_invalidation(brp_client: Arc<RwLock<BrpClient>>) {
+    tokio::spawn(async move {
+        let (mut mutations, mut connections) = {
+            let client = brp_client.read().await;
+            (client.subscribe_mutations(), client.subscribe_connections())
+        };
+        loop {
+            tokio::select! {
+                mutation = mutations.recv() => {
+                    if let Err(tokio::sync::broadcast::error::RecvError::Closed) = mutation {
+                        break;
+                    }
+                    clear_query_results().await;
+                }
+                changed = connections.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    clear_query_results().await;
+                }
+            }
+        }
+    });
+}
+
+/// Queries worth answering before anyone asks: cheap, and needed early in most sessions
+///
+/// The component list doubles as the schema of what can be queried, and the
+/// entity listing carries the entity count in its metadata.
+pub const WARM_QUERIES: &[&str] = &["list components", "list all entities"];
+
+/// Run [`WARM_QUERIES`] through `run` in the background whenever the game connects
+///
+/// `run` takes observe arguments, so callers route the queries through
+/// whichever caches sit in front of observe.
+pub fn spawn_cache_warming<F, Fut>(brp_client: Arc<RwLock<BrpClient>>, run: F)
+where
+    F: Fn(Value) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<Value>> + Send,
+{
+    tokio::spawn(async move {
+        let mut connections = brp_client.read().await.subscribe_connections();
+        // A connection made before we subscribed still needs warming
+        let mut connected = *connections.borrow_and_update() > 0;
+        loop {
+            if !connected && connections.changed().await.is_err() {
+                break;
+            }
+            connected = false;
+
+            let start_time = Instant::now();
+            let mut warmed = 0;
+            for query in WARM_QUERIES {
+                match run(json!({ "query": query })).await {
+                    Ok(response) if response.get("error").is_none() => warmed += 1,
+                    Ok(response) => debug!("Cache warming query '{}' failed: {}", query, response["error"]),
+                    Err(e) => debug!("Cache warming query '{}' failed: {}", query, e),
+                }
+            }
+            info!(
+                "Warmed {}/{} queries in {}ms after the game connected",
+                warmed,
+                WARM_QUERIES.len(),
+                start_time.elapsed().as_millis()
+            );
+        }
+    });
+}
+
 /// Get query cache statistics
 pub async fn get_cache_stats() -> Value {
     let state = get_observe_state();
@@ -453,6 +860,13 @@ pub async fn get_cache_stats() -> Value {
     json!(stats)
 }
 
+/// Drop cached query results but keep the snapshot history used for diffs
+pub async fn clear_query_results() {
+    let state = get_observe_state();
+    let state_guard = state.read().await;
+    state_guard.cache.clear();
+}
+
 /// Clear query cache
 pub async fn clear_cache() {
     let state = get_observe_state();
@@ -497,6 +911,86 @@ mod tests {
         assert!(result.get("help").is_some());
     }
 
+    #[tokio::test]
+    async fn test_unknown_sub_query_rejected() {
+        let brp_client = Arc::new(RwLock::new(crate::brp_client::BrpClient::new(
+            &Config::default(),
+        )));
+
+        let args = json!({"query": "list all entities", "include": ["hierarchy", "physics"]});
+        let result = handle(args, brp_client).await.unwrap();
+
+        assert_eq!(result.get("error").unwrap(), "Unknown sub-query");
+        assert_eq!(result["available"], json!(SUB_QUERIES));
+    }
+
+    #[test]
+    fn test_sub_query_results() {
+        assert!(SUB_QUERIES
+            .iter()
+            .all(|name| sub_query_request(name).is_some()));
+
+        let types = BrpResult::ComponentTypes(vec![
+            crate::brp_messages::ComponentTypeInfo {
+                id: "game::Health".to_string(),
+                name: "Health".to_string(),
+                schema: Some(json!({"type": "object"})),
+            },
+            crate::brp_messages::ComponentTypeInfo {
+                id: "game::Marker".to_string(),
+                name: "Marker".to_string(),
+                schema: None,
+            },
+        ]);
+        assert_eq!(
+            sub_query_result("components", &types).unwrap(),
+            json!(["game::Health", "game::Marker"])
+        );
+        assert_eq!(
+            sub_query_result("schemas", &types).unwrap(),
+            json!({"game::Health": {"type": "object"}})
+        );
+    }
+
+    fn entity(id: EntityId, components: Value) -> EntityData {
+        EntityData {
+            id,
+            components: serde_json::from_value(components).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_delta_per_session() {
+        let mut state = ObserveState::new().unwrap();
+        let first = vec![
+            entity(1, json!({"Health": 100, "Name": "player"})),
+            entity(2, json!({"Health": 50})),
+            entity(3, json!({"Health": 10})),
+        ];
+        let delta = state.take_delta("a", "list all entities", &first);
+        assert!(delta.first_poll);
+        assert_eq!(delta.added.len(), 3);
+
+        let second = vec![
+            entity(1, json!({"Health": 90})),
+            entity(2, json!({"Health": 50})),
+            entity(4, json!({"Health": 75})),
+        ];
+        let delta = state.take_delta("a", "list all entities", &second);
+        assert!(!delta.first_poll);
+        assert_eq!(delta.added.iter().map(|e| e.id).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(delta.removed, vec![3]);
+        assert_eq!(delta.unchanged_count, 1);
+        assert_eq!(delta.changed.len(), 1);
+        assert_eq!(delta.changed[0].components["Health"], json!(90));
+        assert_eq!(delta.changed[0].removed_components, vec!["Name".to_string()]);
+
+        // Another session polling the same query starts from its own baseline
+        assert!(state.take_delta("b", "list all entities", &second).first_poll);
+        state.clear_delta_baselines("a");
+        assert!(state.take_delta("a", "list all entities", &second).first_poll);
+    }
+
     #[tokio::test]
     async fn test_cache_stats() {
         let stats = get_cache_stats().await;
@@ -0,0 +1,648 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+/// Frame-time timeline tool for correlating performance spikes with game events
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::brp_client::BrpClient;
+use crate::diagnostics_bridge::{fetch_diagnostics, DiagnosticsSnapshot};
+use crate::error::{Error, Result};
+use crate::metrics_store;
+
+/// Default number of frames kept in the ring buffer (~1 minute at 60 FPS)
+pub const DEFAULT_TIMELINE_CAPACITY: usize = 3600;
+
+/// Upper bound for the ring buffer capacity
+pub const MAX_TIMELINE_CAPACITY: usize = 100_000;
+
+/// Default query window in milliseconds
+pub const DEFAULT_WINDOW_MS: u64 = 10_000;
+
+/// Default number of points in a downsampled series
+pub const DEFAULT_MAX_POINTS: usize = 200;
+
+/// Upper bound for the number of points returned by a query
+pub const MAX_POINTS: usize = 2000;
+
+/// Maximum number of game event markers kept
+const MAX_EVENT_MARKERS: usize = 1000;
+
+/// Default interval between samples of the game's diagnostics
+pub const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 250;
+
+/// Shortest interval between samples of the game's diagnostics
+pub const MIN_SAMPLE_INTERVAL_MS: u64 = 50;
+
+/// How often a paused sampler checks whether it was resumed
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Interval of the background sampler in milliseconds; 0 pauses it
+static SAMPLE_INTERVAL_MS: AtomicU64 = AtomicU64::new(DEFAULT_SAMPLE_INTERVAL_MS);
+
+/// Timing data for a single frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameSample {
+    /// Frame number reported by the game, if any
+    #[serde(default)]
+    pub frame: Option<u64>,
+    /// Milliseconds since the UNIX epoch
+    #[serde(default = "now_ms")]
+    pub timestamp_ms: u64,
+    /// Total frame time in milliseconds
+    pub frame_time_ms: f32,
+    /// System execution times (system name -> milliseconds)
+    #[serde(default)]
+    pub system_times_ms: HashMap<String, f32>,
+    /// Entity count at the end of the frame
+    #[serde(default)]
+    pub entity_count: usize,
+}
+
+impl FrameSample {
+    /// A sample of the game's diagnostics, if they include a frame time or FPS
+    pub fn from_diagnostics(snapshot: &DiagnosticsSnapshot) -> Option<Self> {
+        let frame_time_ms = snapshot.frame_time_ms().or_else(|| {
+            snapshot
+                .fps()
+                .filter(|fps| *fps > 0.0)
+                .map(|fps| 1000.0 / fps)
+        })?;
+        Some(Self {
+            frame: snapshot.frame_count(),
+            timestamp_ms: snapshot.collected_at.timestamp_millis().max(0) as u64,
+            frame_time_ms,
+            system_times_ms: HashMap::new(),
+            entity_count: snapshot.entity_count().unwrap_or(0),
+        })
+    }
+}
+
+/// A labelled game event used to annotate the timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventMarker {
+    pub label: String,
+    #[serde(default = "now_ms")]
+    pub timestamp_ms: u64,
+    #[serde(default)]
+    pub data: Value,
+}
+
+/// One point of a downsampled series, aggregating all frames in its bucket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelinePoint {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub frame_count: usize,
+    pub avg_frame_time_ms: f32,
+    pub min_frame_time_ms: f32,
+    /// Worst frame in the bucket so spikes survive downsampling
+    pub max_frame_time_ms: f32,
+    pub max_entity_count: usize,
+    /// Average time per system across the frames in the bucket
+    pub system_times_ms: HashMap<String, f32>,
+}
+
+/// Ring buffer of recent frame samples and event markers
+#[derive(Debug)]
+pub struct PerfTimeline {
+    frames: VecDeque<FrameSample>,
+    events: VecDeque<EventMarker>,
+    capacity: usize,
+    total_recorded: u64,
+}
+
+impl PerfTimeline {
+    /// Create a timeline holding at most `capacity` frames
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.clamp(1, MAX_TIMELINE_CAPACITY);
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            events: VecDeque::new(),
+            capacity,
+            total_recorded: 0,
+        }
+    }
+
+    /// Record a frame, evicting the oldest one when full
+    pub fn record(&mut self, sample: FrameSample) {
+        if self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(sample);
+        self.total_recorded += 1;
+    }
+
+    /// Record a game event marker
+    pub fn mark(&mut self, marker: EventMarker) {
+        if self.events.len() >= MAX_EVENT_MARKERS {
+            self.events.pop_front();
+        }
+        self.events.push_back(marker);
+    }
+
+    /// Change the capacity, dropping the oldest frames if it shrinks
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.clamp(1, MAX_TIMELINE_CAPACITY);
+        while self.frames.len() > self.capacity {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.events.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Timestamp of the most recent frame
+    pub fn latest_timestamp(&self) -> Option<u64> {
+        self.frames.iter().map(|f| f.timestamp_ms).max()
+    }
+
+    /// Frames with a timestamp in `[start_ms, end_ms]`
+    pub fn frames_in(&self, start_ms: u64, end_ms: u64) -> Vec<&FrameSample> {
+        self.frames
+            .iter()
+            .filter(|f| f.timestamp_ms >= start_ms && f.timestamp_ms <= end_ms)
+            .collect()
+    }
+
+    /// Event markers with a timestamp in `[start_ms, end_ms]`
+    pub fn events_in(&self, start_ms: u64, end_ms: u64) -> Vec<&EventMarker> {
+        self.events
+            .iter()
+            .filter(|e| e.timestamp_ms >= start_ms && e.timestamp_ms <= end_ms)
+            .collect()
+    }
+}
+
+impl Default for PerfTimeline {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMELINE_CAPACITY)
+    }
+}
+
+/// Downsample frames into at most `max_points` equal-width time buckets.
+///
+/// Empty buckets are omitted; each point keeps min/avg/max frame time.
+pub fn downsample(
+    frames: &[&FrameSample],
+    start_ms: u64,
+    end_ms: u64,
+    max_points: usize,
+) -> Vec<TimelinePoint> {
+    if frames.is_empty() || max_points == 0 {
+        return Vec::new();
+    }
+
+    let span = end_ms.saturating_sub(start_ms) + 1;
+    let points = max_points as u64;
+    let bucket_width = ((span + points - 1) / points).max(1);
+
+    let mut buckets: Vec<Vec<&FrameSample>> = vec![Vec::new(); max_points];
+    for &frame in frames {
+        let index = ((frame.timestamp_ms.saturating_sub(start_ms)) / bucket_width) as usize;
+        buckets[index.min(max_points - 1)].push(frame);
+    }
+
+    buckets
+        .into_iter()
+        .enumerate()
+        .filter(|(_, bucket)| !bucket.is_empty())
+        .map(|(i, bucket)| {
+            let bucket_start = start_ms + i as u64 * bucket_width;
+            let count = bucket.len();
+            let total: f32 = bucket.iter().map(|f| f.frame_time_ms).sum();
+            let min = bucket
+                .iter()
+                .map(|f| f.frame_time_ms)
+                .fold(f32::INFINITY, f32::min);
+            let max = bucket
+                .iter()
+                .map(|f| f.frame_time_ms)
+                .fold(f32::NEG_INFINITY, f32::max);
+
+            let mut system_totals: HashMap<String, f32> = HashMap::new();
+            for frame in &bucket {
+                for (system, time) in &frame.system_times_ms {
+                    *system_totals.entry(system.clone()).or_insert(0.0) += time;
+                }
+            }
+            let system_times_ms = system_totals
+                .into_iter()
+                .map(|(system, total)| (system, total / count as f32))
+                .collect();
+
+            TimelinePoint {
+                start_ms: bucket_start,
+                end_ms: (bucket_start + bucket_width - 1).min(end_ms),
+                frame_count: count,
+                avg_frame_time_ms: total / count as f32,
+                min_frame_time_ms: min,
+                max_frame_time_ms: max,
+                max_entity_count: bucket.iter().map(|f| f.entity_count).max().unwrap_or(0),
+                system_times_ms,
+            }
+        })
+        .collect()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Global timeline state
+static TIMELINE_STATE: std::sync::OnceLock<Arc<RwLock<PerfTimeline>>> = std::sync::OnceLock::new();
+
+fn get_timeline_state() -> Arc<RwLock<PerfTimeline>> {
+    TIMELINE_STATE
+        .get_or_init(|| Arc::new(RwLock::new(PerfTimeline::default())))
+        .clone()
+}
+
+/// Sample the game's diagnostics into the timeline in the background
+///
+/// The timeline fills without clients posting frames. Samples are taken every
+/// `sample_interval_ms` (set with the `configure` action; 0 pauses sampling)
+/// and skipped while the game is disconnected or reports no frame time.
+pub fn spawn_sampling(brp_client: Arc<RwLock<BrpClient>>) {
+    tokio::spawn(async move {
+        loop {
+            let interval_ms = SAMPLE_INTERVAL_MS.load(Ordering::Relaxed);
+            if interval_ms == 0 {
+                tokio::time::sleep(PAUSED_POLL_INTERVAL).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_millis(interval_ms)).await;
+
+            match fetch_diagnostics(&brp_client, None).await {
+                Ok(snapshot) => {
+                    if let Some(sample) = FrameSample::from_diagnostics(&snapshot) {
+                        let state = get_timeline_state();
+                        let mut timeline = state.write().await;
+                        record_frames(&mut timeline, vec![sample]);
+                    }
+                }
+                Err(e) => debug!("Perf timeline sample skipped: {}", e),
+            }
+        }
+    });
+}
+
+/// Add frames to the timeline and the metrics store
+fn record_frames(timeline: &mut PerfTimeline, frames: Vec<FrameSample>) {
+    let metrics = metrics_store::global();
+    for frame in frames {
+        metrics.record_at(
+            "frame_time_ms",
+            frame.timestamp_ms,
+            frame.frame_time_ms as f64,
+        );
+        metrics.record_at(
+            "entity_count",
+            frame.timestamp_ms,
+            frame.entity_count as f64,
+        );
+        timeline.record(frame);
+    }
+}
+
+/// Handle perf timeline tool requests
+///
+/// # Errors
+/// Returns error if recorded frames or markers are malformed
+pub async fn handle(arguments: Value) -> Result<Value> {
+    debug!("Perf timeline tool called with arguments: {}", arguments);
+
+    let action = arguments
+        .get("action")
+        .and_then(|a| a.as_str())
+        .unwrap_or("query");
+
+    let state = get_timeline_state();
+    match action {
+        "record" => handle_record(&arguments, &state).await,
+        "mark" => handle_mark(&arguments, &state).await,
+        "query" => handle_query(&arguments, &state).await,
+        "configure" => handle_configure(&arguments, &state).await,
+        "stats" => handle_stats(&state).await,
+        "clear" => {
+            state.write().await.clear();
+            Ok(json!({ "message": "Timeline cleared" }))
+        }
+        _ => Ok(json!({
+            "error": "Invalid action",
+            "message": format!("Unknown action: {}. Available actions: record, mark, query, configure, stats, clear", action),
+            "available_actions": ["record", "mark", "query", "configure", "stats", "clear"]
+        })),
+    }
+}
+
+/// Record one or more frame samples
+async fn handle_record(arguments: &Value, state: &Arc<RwLock<PerfTimeline>>) -> Result<Value> {
+    let frames: Vec<FrameSample> = match arguments.get("frames") {
+        Some(frames) => serde_json::from_value(frames.clone())
+            .map_err(|e| Error::Validation(format!("Invalid 'frames' field: {e}")))?,
+        None => vec![serde_json::from_value(arguments.clone())
+            .map_err(|e| Error::Validation(format!("Invalid frame sample: {e}")))?],
+    };
+
+    if let Some(bad) = frames
+        .iter()
+        .find(|f| !f.frame_time_ms.is_finite() || f.frame_time_ms < 0.0)
+    {
+        return Err(Error::Validation(format!(
+            "Invalid frame_time_ms: {}",
+            bad.frame_time_ms
+        )));
+    }
+
+    let recorded = frames.len();
+    let mut timeline = state.write().await;
+    record_frames(&mut timeline, frames);
+
+    Ok(json!({
+        "recorded": recorded,
+        "buffered_frames": timeline.len(),
+        "capacity": timeline.capacity
+    }))
+}
+
+/// Record a game event marker
+async fn handle_mark(arguments: &Value, state: &Arc<RwLock<PerfTimeline>>) -> Result<Value> {
+    let marker: EventMarker = serde_json::from_value(arguments.clone())
+        .map_err(|e| Error::Validation(format!("Invalid event marker: {e}")))?;
+
+    info!(
+        "Timeline event '{}' at {}",
+        marker.label, marker.timestamp_ms
+    );
+    let response = json!({
+        "message": "Event marked",
+        "label": marker.label,
+        "timestamp_ms": marker.timestamp_ms
+    });
+    state.write().await.mark(marker);
+    Ok(response)
+}
+
+/// Return a downsampled series for the requested window
+async fn handle_query(arguments: &Value, state: &Arc<RwLock<PerfTimeline>>) -> Result<Value> {
+    let timeline = state.read().await;
+
+    let max_points = arguments
+        .get("max_points")
+        .and_then(|m| m.as_u64())
+        .map(|m| m as usize)
+        .unwrap_or(DEFAULT_MAX_POINTS)
+        .clamp(1, MAX_POINTS);
+
+    // Default window ends at the latest recorded frame
+    let end_ms = arguments
+        .get("end_ms")
+        .and_then(|e| e.as_u64())
+        .or_else(|| timeline.latest_timestamp())
+        .unwrap_or_else(now_ms);
+    let start_ms = match arguments.get("start_ms").and_then(|s| s.as_u64()) {
+        Some(start) => start,
+        None => {
+            let window_ms = arguments
+                .get("window_ms")
+                .and_then(|w| w.as_u64())
+                .unwrap_or(DEFAULT_WINDOW_MS);
+            end_ms.saturating_sub(window_ms)
+        }
+    };
+
+    if start_ms > end_ms {
+        return Err(Error::Validation(format!(
+            "start_ms ({start_ms}) must not be after end_ms ({end_ms})"
+        )));
+    }
+
+    let frames = timeline.frames_in(start_ms, end_ms);
+    let series = downsample(&frames, start_ms, end_ms, max_points);
+
+    // Frames above the threshold are returned individually for correlation
+    let spike_threshold_ms = arguments
+        .get("spike_threshold_ms")
+        .and_then(|t| t.as_f64())
+        .map(|t| t as f32);
+    let spikes: Vec<&FrameSample> = match spike_threshold_ms {
+        Some(threshold) => frames
+            .iter()
+            .copied()
+            .filter(|f| f.frame_time_ms > threshold)
+            .take(MAX_POINTS)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let frame_count = frames.len();
+    let avg_frame_time_ms = if frame_count > 0 {
+        frames.iter().map(|f| f.frame_time_ms).sum::<f32>() / frame_count as f32
+    } else {
+        0.0
+    };
+    let max_frame_time_ms = frames
+        .iter()
+        .map(|f| f.frame_time_ms)
+        .fold(0.0_f32, f32::max);
+
+    Ok(json!({
+        "window": {
+            "start_ms": start_ms,
+            "end_ms": end_ms
+        },
+        "series": series,
+        "events": timeline.events_in(start_ms, end_ms),
+        "spikes": spikes,
+        "summary": {
+            "frame_count": frame_count,
+            "points": series.len(),
+            "avg_frame_time_ms": avg_frame_time_ms,
+            "max_frame_time_ms": max_frame_time_ms,
+            "spike_threshold_ms": spike_threshold_ms
+        }
+    }))
+}
+
+/// Configure the ring buffer capacity and the sampling interval
+async fn handle_configure(arguments: &Value, state: &Arc<RwLock<PerfTimeline>>) -> Result<Value> {
+    let capacity = arguments.get("capacity").and_then(|c| c.as_u64());
+    let sample_interval_ms = arguments.get("sample_interval_ms").and_then(|i| i.as_u64());
+    if capacity.is_none() && sample_interval_ms.is_none() {
+        return Err(Error::Validation(
+            "Missing 'capacity' or 'sample_interval_ms' field".to_string(),
+        ));
+    }
+
+    if let Some(interval_ms) = sample_interval_ms {
+        let interval_ms = if interval_ms == 0 {
+            0
+        } else {
+            interval_ms.max(MIN_SAMPLE_INTERVAL_MS)
+        };
+        SAMPLE_INTERVAL_MS.store(interval_ms, Ordering::Relaxed);
+    }
+
+    let mut timeline = state.write().await;
+    if let Some(capacity) = capacity {
+        timeline.set_capacity(capacity as usize);
+    }
+
+    Ok(json!({
+        "message": "Configuration updated successfully",
+        "capacity": timeline.capacity,
+        "buffered_frames": timeline.len(),
+        "sample_interval_ms": SAMPLE_INTERVAL_MS.load(Ordering::Relaxed)
+    }))
+}
+
+/// Get ring buffer statistics
+async fn handle_stats(state: &Arc<RwLock<PerfTimeline>>) -> Result<Value> {
+    let timeline = state.read().await;
+
+    Ok(json!({
+        "buffered_frames": timeline.len(),
+        "capacity": timeline.capacity,
+        "total_recorded": timeline.total_recorded,
+        "event_markers": timeline.events.len(),
+        "sample_interval_ms": SAMPLE_INTERVAL_MS.load(Ordering::Relaxed),
+        "oldest_timestamp_ms": timeline.frames.iter().map(|f| f.timestamp_ms).min(),
+        "latest_timestamp_ms": timeline.latest_timestamp()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp_ms: u64, frame_time_ms: f32) -> FrameSample {
+        FrameSample {
+            frame: None,
+            timestamp_ms,
+            frame_time_ms,
+            system_times_ms: HashMap::from([("physics".to_string(), frame_time_ms / 2.0)]),
+            entity_count: timestamp_ms as usize,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_evicts_oldest() {
+        let mut timeline = PerfTimeline::new(3);
+        for t in 0..5 {
+            timeline.record(sample(t, 16.0));
+        }
+
+        assert_eq!(timeline.len(), 3);
+        assert_eq!(timeline.frames.front().unwrap().timestamp_ms, 2);
+        assert_eq!(timeline.total_recorded, 5);
+
+        timeline.set_capacity(1);
+        assert_eq!(timeline.frames.front().unwrap().timestamp_ms, 4);
+    }
+
+    #[test]
+    fn test_downsample_keeps_spikes() {
+        let frames: Vec<FrameSample> = (0..100)
+            .map(|t| sample(t, if t == 42 { 80.0 } else { 16.0 }))
+            .collect();
+        let refs: Vec<&FrameSample> = frames.iter().collect();
+
+        let series = downsample(&refs, 0, 99, 10);
+        assert_eq!(series.len(), 10);
+        assert!(series.iter().all(|p| p.frame_count == 10));
+
+        let spike = &series[4];
+        assert_eq!(spike.max_frame_time_ms, 80.0);
+        assert_eq!(spike.min_frame_time_ms, 16.0);
+        assert!((spike.avg_frame_time_ms - 22.4).abs() < 0.01);
+        assert!((spike.system_times_ms["physics"] - 11.2).abs() < 0.01);
+        assert_eq!(spike.max_entity_count, 49);
+    }
+
+    #[test]
+    fn test_downsample_fewer_frames_than_points() {
+        let frames = [sample(0, 16.0), sample(1000, 17.0)];
+        let refs: Vec<&FrameSample> = frames.iter().collect();
+
+        let series = downsample(&refs, 0, 1000, 200);
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[1].max_frame_time_ms, 17.0);
+    }
+
+    #[tokio::test]
+    async fn test_query_window_with_events() {
+        let state = Arc::new(RwLock::new(PerfTimeline::default()));
+        let frames: Vec<FrameSample> = (0..20).map(|i| sample(1000 + i * 100, 16.0)).collect();
+        handle_record(&json!({ "frames": frames }), &state)
+            .await
+            .unwrap();
+        handle_mark(
+            &json!({ "label": "boss_spawned", "timestamp_ms": 2500 }),
+            &state,
+        )
+        .await
+        .unwrap();
+
+        let result = handle_query(
+            &json!({ "window_ms": 1000, "max_points": 5, "spike_threshold_ms": 10.0 }),
+            &state,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result["window"]["end_ms"], 2900);
+        assert_eq!(result["summary"]["frame_count"], 11);
+        assert_eq!(result["events"][0]["label"], "boss_spawned");
+        assert_eq!(result["spikes"].as_array().unwrap().len(), 11);
+        assert!(result["series"].as_array().unwrap().len() <= 5);
+    }
+
+    #[test]
+    fn test_sample_from_diagnostics() {
+        let measurement = |path: &str, value: f64| crate::brp_messages::DiagnosticMeasurement {
+            path: path.to_string(),
+            value: Some(value),
+            average: None,
+            smoothed: None,
+            suffix: None,
+        };
+        let snapshot = DiagnosticsSnapshot::new(vec![
+            measurement("fps", 50.0),
+            measurement("frame_count", 120.0),
+            measurement("entity_count", 300.0),
+        ]);
+
+        let sample = FrameSample::from_diagnostics(&snapshot).unwrap();
+        assert_eq!(sample.frame_time_ms, 20.0);
+        assert_eq!(sample.frame, Some(120));
+        assert_eq!(sample.entity_count, 300);
+
+        let no_frame_time = DiagnosticsSnapshot::new(vec![measurement("entity_count", 300.0)]);
+        assert!(FrameSample::from_diagnostics(&no_frame_time).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_record_rejects_invalid_frame_time() {
+        let state = Arc::new(RwLock::new(PerfTimeline::default()));
+        let result = handle_record(&json!({ "frame_time_ms": -1.0 }), &state).await;
+        assert!(result.is_err());
+    }
+}
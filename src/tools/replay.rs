@@ -1,14 +1,23 @@
 use serde_json::{json, Value};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock};
-use std::time::UNIX_EPOCH;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use crate::anomaly_detector::Anomaly;
 use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse};
+use crate::disk_ring_buffer::DiskRingConfig;
 use crate::error::{Error, Result};
-use crate::playback_system::{DirectSync, PlaybackController};
-use crate::recording_system::{RecordingBuffer, RecordingConfig, RecordingState};
+use crate::memory_budget::MemoryConsumer;
+use crate::playback_system::{
+    DirectSync, PlaybackController, MAX_PLAYBACK_SPEED, MIN_PLAYBACK_SPEED,
+};
+use crate::recording_format::{self, RECORDING_FORMAT_VERSION};
+use crate::recording_system::{Frame, Recording, RecordingBuffer, RecordingConfig, RecordingState};
+use crate::replay_assertions::ReplayAssertion;
 use crate::timeline_branching::{
     BranchId, MergeStrategy, Modification, ModificationLayer, TimelineBranchManager,
 };
@@ -18,10 +27,18 @@ static RECORDING_STATE: OnceLock<RecordingState> = OnceLock::new();
 static PLAYBACK_CONTROLLER: OnceLock<Arc<RwLock<PlaybackController>>> = OnceLock::new();
 static BRANCH_MANAGER: OnceLock<Arc<RwLock<TimelineBranchManager>>> = OnceLock::new();
 
+/// Upper bound on frames recorded into a single experiment branch
+const MAX_BRANCH_RECORD_FRAMES: usize = 10_000;
+
 fn get_recording_state() -> &'static RecordingState {
     RECORDING_STATE.get_or_init(|| RecordingState::new(RecordingConfig::default()))
 }
 
+/// The shared recording buffer as seen by the process memory budget
+pub fn recording_memory_consumer() -> Arc<dyn MemoryConsumer> {
+    get_recording_state().memory_consumer()
+}
+
 fn get_playback_controller() -> &'static Arc<RwLock<PlaybackController>> {
     PLAYBACK_CONTROLLER.get_or_init(|| {
         Arc::new(RwLock::new(PlaybackController::new(Box::new(DirectSync))))
@@ -48,15 +65,21 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
         "stop" => handle_stop(arguments, brp_client).await,
         "status" => handle_status(arguments, brp_client).await,
         "marker" => handle_marker(arguments, brp_client).await,
+        "annotate" => handle_annotate(arguments, brp_client).await,
         "save" => handle_save(arguments, brp_client).await,
         "load" => handle_load(arguments, brp_client).await,
+        "inspect" => handle_inspect(arguments, brp_client).await,
+        "migrate" => handle_migrate(arguments, brp_client).await,
         "stats" => handle_stats(arguments, brp_client).await,
+        "rewind" => handle_rewind(arguments, brp_client).await,
         "play" => handle_play(arguments, brp_client).await,
         "pause" => handle_pause(arguments, brp_client).await,
         "seek" => handle_seek(arguments, brp_client).await,
         "step" => handle_step(arguments, brp_client).await,
         "set_speed" => handle_set_speed(arguments, brp_client).await,
         "playback_status" => handle_playback_status(arguments, brp_client).await,
+        "assertion_results" => handle_assertion_results(arguments, brp_client).await,
+        "check_assertions" => handle_check_assertions(arguments, brp_client).await,
         "create_branch" => handle_create_branch(arguments, brp_client).await,
         "list_branches" => handle_list_branches(arguments, brp_client).await,
         "switch_branch" => handle_switch_branch(arguments, brp_client).await,
@@ -65,14 +88,16 @@ pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Res
         "compare_branches" => handle_compare_branches(arguments, brp_client).await,
         "delete_branch" => handle_delete_branch(arguments, brp_client).await,
         "branch_tree" => handle_branch_tree(arguments, brp_client).await,
+        "branch_experiment" => handle_branch_experiment(arguments, brp_client).await,
         _ => Ok(json!({
             "error": "Unknown action",
             "message": format!("Unknown action: {}", action),
             "available_actions": [
-                "record", "stop", "status", "marker", "save", "load", "stats",
+                "record", "stop", "status", "marker", "annotate", "save", "load", "inspect", "migrate", "stats", "rewind",
                 "play", "pause", "seek", "step", "set_speed", "playback_status",
+                "assertion_results", "check_assertions",
                 "create_branch", "list_branches", "switch_branch", "add_modification",
-                "merge_branch", "compare_branches", "delete_branch", "branch_tree"
+                "merge_branch", "compare_branches", "delete_branch", "branch_tree", "branch_experiment"
             ]
         })),
     }
@@ -101,6 +126,7 @@ async fn handle_record(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
     let max_buffer_size = config.max_buffer_size;
     let compression = config.compression;
     let checksums = config.checksums;
+    let disk_buffer = config.disk_buffer.clone();
 
     // Start recording
     let mut buffer = get_recording_state().buffer.write().await;
@@ -148,6 +174,7 @@ async fn handle_record(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
             "max_buffer_size": max_buffer_size,
             "compression": compression,
             "checksums": checksums,
+            "disk_buffer": disk_buffer,
         },
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
@@ -200,7 +227,9 @@ async fn handle_status(_arguments: Value, brp_client: Arc<RwLock<BrpClient>>) ->
             "marker_count": stats.marker_count,
             "duration_seconds": stats.duration.as_secs(),
             "buffer_usage": stats.buffer_usage,
+            "compression": stats.compression,
         },
+        "disk_buffer": stats.disk,
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
@@ -241,6 +270,72 @@ async fn handle_marker(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) ->
     }))
 }
 
+/// Handle annotate action - mark the live recording, or the one being played back
+async fn handle_annotate(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    let name = arguments
+        .get("name")
+        .and_then(|n| n.as_str())
+        .ok_or_else(|| Error::Validation("Missing 'name' parameter".to_string()))?
+        .to_string();
+    let description = arguments
+        .get("description")
+        .and_then(|d| d.as_str())
+        .map(String::from);
+    let seconds_before = arguments
+        .get("seconds_before")
+        .and_then(|s| s.as_f64())
+        .unwrap_or(0.0);
+    if !(0.0..=3600.0).contains(&seconds_before) {
+        return Err(Error::Validation(
+            "'seconds_before' must be between 0 and 3600".to_string(),
+        ));
+    }
+    let before = Duration::from_secs_f64(seconds_before);
+
+    let mut metadata: HashMap<String, Value> = arguments
+        .get("metadata")
+        .and_then(|m| m.as_object())
+        .map(|m| m.clone().into_iter().collect())
+        .unwrap_or_default();
+    metadata.insert("source".to_string(), json!("mcp"));
+
+    let mut buffer = get_recording_state().buffer.write().await;
+    let (target, marker) = if buffer.is_recording() {
+        let marker = buffer.annotate(name, description, metadata, before);
+        ("recording", marker)
+    } else {
+        drop(buffer);
+        let marker = get_playback_controller()
+            .read()
+            .await
+            .annotate(name, description, metadata, before)
+            .await;
+        // Keep the navigation timeline's markers in step for seek_to_marker
+        if let Some(marker) = &marker {
+            get_recording_state()
+                .timeline
+                .write()
+                .await
+                .add_marker(marker.clone());
+        }
+        ("playback", marker)
+    };
+
+    match marker {
+        Some(marker) => Ok(json!({
+            "success": true,
+            "message": format!("Marker added to the {target}"),
+            "target": target,
+            "marker": marker,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+        None => Ok(json!({
+            "error": "Nothing to annotate",
+            "message": "Start a recording or load one for playback before annotating",
+        })),
+    }
+}
+
 /// Handle save action - save recording to file
 async fn handle_save(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
     let buffer = get_recording_state().buffer.read().await;
@@ -277,6 +372,103 @@ async fn handle_save(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> R
     }
 }
 
+/// What the shared recording buffer currently holds
+pub async fn buffered_recording() -> Result<Recording> {
+    get_recording_state().buffer.read().await.to_recording()
+}
+
+/// Persist the recording around an anomaly: `before` from the ring buffer,
+/// plus `after` once it has been recorded
+///
+/// Marks the anomaly in the running recording and returns the file the
+/// capture will be written to after `after` has elapsed.
+pub async fn capture_anomaly(
+    anomaly: &Anomaly,
+    before: Duration,
+    after: Duration,
+    directory: &Path,
+) -> Result<PathBuf> {
+    let metadata: HashMap<String, Value> = [
+        ("anomaly_id", json!(anomaly.id)),
+        ("anomaly_type", json!(anomaly.anomaly_type)),
+        ("severity", json!(anomaly.severity)),
+        ("entity_id", json!(anomaly.entity_id)),
+        ("component", json!(anomaly.component)),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v))
+    .collect();
+
+    let marker = get_recording_state()
+        .buffer
+        .write()
+        .await
+        .add_marker_with_metadata(
+            format!("anomaly:{}", anomaly.id),
+            Some(anomaly.description.clone()),
+            metadata,
+        )
+        .ok_or_else(|| {
+            Error::Validation(
+                "Not recording; start a recording (e.g. with window_seconds) to capture anomalies"
+                    .to_string(),
+            )
+        })?;
+
+    std::fs::create_dir_all(directory)?;
+    let path = directory.join(format!("anomaly_{}.bevy", anomaly.id));
+    let write_path = path.clone();
+    let anomaly_id = anomaly.id.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(after).await;
+
+        let recording = match get_recording_state().buffer.read().await.to_recording() {
+            Ok(recording) => recording,
+            Err(e) => {
+                error!(
+                    "Failed to capture recording for anomaly {}: {}",
+                    anomaly_id, e
+                );
+                return;
+            }
+        };
+        let window = recording.window(
+            marker.timestamp.saturating_sub(before),
+            marker.timestamp + after,
+        );
+        let written = tokio::task::spawn_blocking(move || {
+            recording_format::write_recording(&write_path, &window).map(|()| write_path)
+        })
+        .await;
+        match written {
+            Ok(Ok(path)) => info!("Saved recording for anomaly {} to {:?}", anomaly_id, path),
+            Ok(Err(e)) => error!("Failed to save recording for anomaly {}: {}", anomaly_id, e),
+            Err(e) => error!("Anomaly recording task failed: {}", e),
+        }
+    });
+
+    Ok(path)
+}
+
+/// Make `recording` the one navigated, played back and branched from
+pub async fn load_for_playback(recording: Recording) -> Result<()> {
+    // Load into timeline for navigation
+    get_recording_state()
+        .timeline
+        .write()
+        .await
+        .load_recording(recording.clone());
+
+    // Also load into playback controller
+    let controller = get_playback_controller().read().await;
+    controller.load_recording(recording.clone()).await?;
+
+    // Load into branch manager
+    let mut branch_manager = get_branch_manager().write().await;
+    branch_manager.set_base_recording(recording);
+    Ok(())
+}
+
 /// Handle load action - load recording from file
 async fn handle_load(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
     let filename = arguments
@@ -288,22 +480,11 @@ async fn handle_load(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> R
 
     match RecordingBuffer::load_from_file(&path) {
         Ok(recording) => {
-            let mut timeline = get_recording_state().timeline.write().await;
-
             let total_frames = recording.total_frames;
             let duration = recording.duration;
             let marker_count = recording.markers.len();
 
-            // Load into timeline for navigation
-            timeline.load_recording(recording.clone());
-
-            // Also load into playback controller
-            let controller = get_playback_controller().read().await;
-            controller.load_recording(recording.clone()).await?;
-
-            // Load into branch manager
-            let mut branch_manager = get_branch_manager().write().await;
-            branch_manager.set_base_recording(recording);
+            load_for_playback(recording).await?;
 
             Ok(json!({
                 "success": true,
@@ -327,6 +508,76 @@ async fn handle_load(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> R
     }
 }
 
+/// Handle rewind action - load what the buffer holds for playback, even mid-recording
+async fn handle_rewind(_arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    let recording = buffered_recording().await?;
+    let Some(first_frame) = recording.frames.iter().map(|f| f.frame_number).min() else {
+        return Ok(json!({
+            "error": "Nothing recorded",
+            "message": "The recording buffer holds no frames to rewind to",
+        }));
+    };
+    let last_frame = recording
+        .delta_frames
+        .iter()
+        .map(|d| d.frame_number)
+        .chain(recording.frames.iter().map(|f| f.frame_number))
+        .max()
+        .unwrap_or(first_frame);
+
+    get_recording_state()
+        .timeline
+        .write()
+        .await
+        .load_recording(recording.clone());
+    get_playback_controller()
+        .read()
+        .await
+        .load_recording(recording)
+        .await?;
+
+    Ok(json!({
+        "success": true,
+        "message": "Buffered window loaded for playback",
+        "first_frame": first_frame,
+        "last_frame": last_frame,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+/// Handle inspect action - read a recording file's header without loading it
+async fn handle_inspect(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    let filename = arguments
+        .get("filename")
+        .and_then(|f| f.as_str())
+        .ok_or_else(|| Error::Validation("Missing 'filename' parameter".to_string()))?;
+
+    let header = recording_format::read_header(&PathBuf::from(filename))?;
+    Ok(json!({
+        "filename": filename,
+        "header": header,
+        "current_format_version": RECORDING_FORMAT_VERSION,
+        "needs_migration": header.format_version < RECORDING_FORMAT_VERSION,
+    }))
+}
+
+/// Handle migrate action - rewrite a recording file in the current format
+async fn handle_migrate(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    let filename = arguments
+        .get("filename")
+        .and_then(|f| f.as_str())
+        .ok_or_else(|| Error::Validation("Missing 'filename' parameter".to_string()))?;
+
+    let from_version = recording_format::migrate_file(&PathBuf::from(filename))?;
+    Ok(json!({
+        "success": true,
+        "filename": filename,
+        "from_version": from_version,
+        "to_version": RECORDING_FORMAT_VERSION,
+        "migrated": from_version < RECORDING_FORMAT_VERSION,
+    }))
+}
+
 /// Handle stats action - get detailed statistics
 async fn handle_stats(_arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
     let buffer = get_recording_state().buffer.read().await;
@@ -344,6 +595,7 @@ async fn handle_stats(_arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) ->
             "marker_count": stats.marker_count,
             "duration_seconds": stats.duration.as_secs(),
             "buffer_usage": stats.buffer_usage,
+            "compression": stats.compression,
         },
         "timeline_stats": {
             "has_recording": timeline.recording.is_some(),
@@ -381,6 +633,14 @@ fn parse_recording_config(arguments: &Value) -> RecordingConfig {
         config.checksums = checksums;
     }
 
+    if let Some(interval) = arguments.get("keyframe_interval").and_then(|k| k.as_u64()) {
+        config.keyframe_interval = (interval as usize).max(1);
+    }
+
+    if let Some(level) = arguments.get("compression_level").and_then(|l| l.as_i64()) {
+        config.compression_level = level.clamp(1, 22) as i32;
+    }
+
     if let Some(comp_filter) = arguments.get("component_filter").and_then(|f| f.as_array()) {
         config.component_filter = Some(
             comp_filter
@@ -390,6 +650,23 @@ fn parse_recording_config(arguments: &Value) -> RecordingConfig {
         );
     }
 
+    // Long sessions spool to disk and keep only the most recent window
+    let disk_buffer = arguments.get("disk_buffer").and_then(|d| d.as_bool());
+    let window_seconds = arguments.get("window_seconds").and_then(|w| w.as_u64());
+    if disk_buffer.unwrap_or(window_seconds.is_some()) {
+        let mut disk_config = DiskRingConfig::default();
+        if let Some(window) = window_seconds {
+            disk_config.window = Duration::from_secs(window);
+        }
+        if let Some(max_mb) = arguments.get("max_disk_mb").and_then(|m| m.as_u64()) {
+            disk_config.max_bytes = max_mb * 1024 * 1024;
+        }
+        if let Some(directory) = arguments.get("disk_directory").and_then(|d| d.as_str()) {
+            disk_config.directory = PathBuf::from(directory);
+        }
+        config.disk_buffer = Some(disk_config);
+    }
+
     if let Some(event_filter) = arguments.get("event_filter").and_then(|f| f.as_array()) {
         config.event_filter = Some(
             event_filter
@@ -403,13 +680,25 @@ fn parse_recording_config(arguments: &Value) -> RecordingConfig {
 }
 
 /// Handle play action - start or resume playback
-async fn handle_play(_arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+async fn handle_play(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
     let controller = get_playback_controller().read().await;
 
+    // Assertions given to play replace earlier ones; resuming without keeps them
+    let assertions = match arguments.get("assertions") {
+        Some(assertions) => {
+            let assertions = parse_assertions(assertions)?;
+            let count = assertions.len();
+            controller.set_assertions(assertions).await;
+            Some(count)
+        }
+        None => None,
+    };
+
     match controller.play(brp_client).await {
         Ok(()) => Ok(json!({
             "success": true,
             "message": "Playback started",
+            "assertions": assertions,
             "timestamp": chrono::Utc::now().to_rfc3339()
         })),
         Err(e) => {
@@ -484,19 +773,22 @@ async fn handle_seek(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>) -> R
     }
 }
 
-/// Handle step action - step forward or backward
+/// Handle step action - step forward or backward by one or more frames
 async fn handle_step(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
     let controller = get_playback_controller().read().await;
     let direction = arguments
         .get("direction")
         .and_then(|d| d.as_str())
         .unwrap_or("forward");
+    let frames = arguments
+        .get("frames")
+        .and_then(|f| f.as_u64())
+        .unwrap_or(1)
+        .max(1) as usize;
 
-    let mut client = brp_client.write().await;
-
-    let result = match direction {
-        "forward" => controller.step_forward(&mut client).await,
-        "backward" => controller.step_backward(&mut client).await,
+    let backward = match direction {
+        "forward" => false,
+        "backward" => true,
         _ => {
             return Ok(json!({
                 "error": "Invalid direction",
@@ -505,10 +797,14 @@ async fn handle_step(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Re
         }
     };
 
+    let mut client = brp_client.write().await;
+    let result = controller.step_by(frames, backward, &mut client).await;
+
     match result {
-        Ok(()) => Ok(json!({
+        Ok(current_frame) => Ok(json!({
             "success": true,
-            "message": format!("Stepped {}", direction),
+            "message": format!("Stepped {} {} frame(s)", direction, frames),
+            "current_frame": current_frame,
             "timestamp": chrono::Utc::now().to_rfc3339()
         })),
         Err(e) => {
@@ -528,6 +824,17 @@ async fn handle_set_speed(arguments: Value, _brp_client: Arc<RwLock<BrpClient>>)
         .and_then(|s| s.as_f64())
         .ok_or_else(|| Error::Validation("Missing 'speed' parameter".to_string()))?;
 
+    if !(MIN_PLAYBACK_SPEED as f64..=MAX_PLAYBACK_SPEED as f64).contains(&speed) {
+        return Ok(json!({
+            "error": "Invalid speed",
+            "message": format!(
+                "Speed must be between {}x and {}x",
+                MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED
+            ),
+            "speed": speed,
+        }));
+    }
+
     let controller = get_playback_controller().read().await;
 
     match controller.set_speed(speed as f32).await {
@@ -560,6 +867,64 @@ async fn handle_playback_status(
         "total_frames": stats.total_frames,
         "playback_time_seconds": stats.playback_time.as_secs(),
         "speed": stats.speed,
+        "speed_range": [MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED],
+        "assertions_passed": controller.assertion_report().await.map(|r| r.passed),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+/// Parse a list of assertions given as sentences or JSON objects
+fn parse_assertions(assertions: &Value) -> Result<Vec<ReplayAssertion>> {
+    assertions
+        .as_array()
+        .ok_or_else(|| Error::Validation("'assertions' must be an array".to_string()))?
+        .iter()
+        .map(ReplayAssertion::from_value)
+        .collect()
+}
+
+/// Handle assertion_results action - results of the assertions checked during playback
+async fn handle_assertion_results(
+    _arguments: Value,
+    _brp_client: Arc<RwLock<BrpClient>>,
+) -> Result<Value> {
+    let controller = get_playback_controller().read().await;
+    match controller.assertion_report().await {
+        Some(report) => Ok(json!({
+            "success": true,
+            "report": report,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+        None => Ok(json!({
+            "error": "No assertions",
+            "message": "Pass 'assertions' to play to check them during playback",
+        })),
+    }
+}
+
+/// Handle check_assertions action - check assertions against the whole loaded recording
+async fn handle_check_assertions(
+    arguments: Value,
+    _brp_client: Arc<RwLock<BrpClient>>,
+) -> Result<Value> {
+    let assertions = parse_assertions(
+        arguments
+            .get("assertions")
+            .ok_or_else(|| Error::Validation("Missing 'assertions' parameter".to_string()))?,
+    )?;
+
+    let controller = get_playback_controller().read().await;
+    let report = controller.check_assertions(assertions).await;
+    if report.frames_checked == 0 {
+        return Ok(json!({
+            "error": "Nothing to check",
+            "message": "Load a recording before checking assertions",
+        }));
+    }
+
+    Ok(json!({
+        "success": true,
+        "report": report,
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
@@ -663,6 +1028,7 @@ async fn handle_switch_branch(
                 "branch_id": branch_id.to_string(),
                 "branch_name": branch.metadata.name,
                 "modification_count": branch.modification_count(),
+                "recorded_frames": branch.recorded_frame_count(),
                 "timestamp": chrono::Utc::now().to_rfc3339()
             }))
         }
@@ -700,39 +1066,7 @@ async fn handle_add_modification(
         .unwrap_or("User modification")
         .to_string();
 
-    // Parse modification based on type
-    let modification = if let Some(entity_mod) = arguments.get("entity_modification") {
-        let entity_id = entity_mod
-            .get("entity_id")
-            .and_then(|e| e.as_u64())
-            .ok_or_else(|| {
-                Error::Validation("Missing entity_id in entity_modification".to_string())
-            })?;
-
-        let component_name = entity_mod
-            .get("component_name")
-            .and_then(|c| c.as_str())
-            .ok_or_else(|| {
-                Error::Validation("Missing component_name in entity_modification".to_string())
-            })?;
-
-        let new_value = entity_mod.get("new_value").ok_or_else(|| {
-            Error::Validation("Missing new_value in entity_modification".to_string())
-        })?;
-
-        Modification::EntityModification {
-            entity_id,
-            component_name: component_name.to_string(),
-            new_value: new_value.clone(),
-        }
-    } else if let Some(entity_removal) = arguments.get("entity_removal") {
-        let entity_id = entity_removal
-            .get("entity_id")
-            .and_then(|e| e.as_u64())
-            .ok_or_else(|| Error::Validation("Missing entity_id in entity_removal".to_string()))?;
-
-        Modification::EntityRemoval { entity_id }
-    } else {
+    let Some(modification) = parse_modification(&arguments)? else {
         return Ok(json!({
             "error": "Invalid modification",
             "message": "No valid modification type provided",
@@ -770,6 +1104,46 @@ async fn handle_add_modification(
     }
 }
 
+/// Parse an `entity_modification` or `entity_removal` argument
+fn parse_modification(arguments: &Value) -> Result<Option<Modification>> {
+    // Parse modification based on type
+    let modification = if let Some(entity_mod) = arguments.get("entity_modification") {
+        let entity_id = entity_mod
+            .get("entity_id")
+            .and_then(|e| e.as_u64())
+            .ok_or_else(|| {
+                Error::Validation("Missing entity_id in entity_modification".to_string())
+            })?;
+
+        let component_name = entity_mod
+            .get("component_name")
+            .and_then(|c| c.as_str())
+            .ok_or_else(|| {
+                Error::Validation("Missing component_name in entity_modification".to_string())
+            })?;
+
+        let new_value = entity_mod.get("new_value").ok_or_else(|| {
+            Error::Validation("Missing new_value in entity_modification".to_string())
+        })?;
+
+        Modification::EntityModification {
+            entity_id,
+            component_name: component_name.to_string(),
+            new_value: new_value.clone(),
+        }
+    } else if let Some(entity_removal) = arguments.get("entity_removal") {
+        let entity_id = entity_removal
+            .get("entity_id")
+            .and_then(|e| e.as_u64())
+            .ok_or_else(|| Error::Validation("Missing entity_id in entity_removal".to_string()))?;
+
+        Modification::EntityRemoval { entity_id }
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(modification))
+}
+
 /// Handle merge_branch action - merge branch into parent
 async fn handle_merge_branch(
     arguments: Value,
@@ -864,6 +1238,7 @@ async fn handle_compare_branches(
                                 "entity_id": diff.entity_id,
                                 "type": format!("{:?}", diff.difference_type),
                                 "details": diff.details,
+                                "changed_components": diff.changed_components,
                             })
                         }).collect::<Vec<_>>()
                     })
@@ -877,6 +1252,7 @@ async fn handle_compare_branches(
                 "frame_range": {"start": start_frame, "end": end_frame},
                 "differences": differences_json,
                 "total_differences": comparison.frame_differences.len(),
+                "first_divergence": comparison.first_divergence(),
                 "timestamp": chrono::Utc::now().to_rfc3339()
             }))
         }
@@ -950,6 +1326,190 @@ async fn handle_branch_tree(
     }))
 }
 
+/// Handle branch_experiment action - restore to a frame, apply a mutation and
+/// record what the game does next into a new branch
+async fn handle_branch_experiment(
+    arguments: Value,
+    brp_client: Arc<RwLock<BrpClient>>,
+) -> Result<Value> {
+    let frame_number = arguments
+        .get("frame")
+        .and_then(|f| f.as_u64())
+        .ok_or_else(|| Error::Validation("Missing 'frame' parameter".to_string()))?
+        as usize;
+
+    let record_frames = arguments
+        .get("record_frames")
+        .and_then(|r| r.as_u64())
+        .unwrap_or(300) as usize;
+    if record_frames == 0 || record_frames > MAX_BRANCH_RECORD_FRAMES {
+        return Ok(json!({
+            "error": "Invalid record_frames",
+            "message": format!("record_frames must be between 1 and {MAX_BRANCH_RECORD_FRAMES}"),
+        }));
+    }
+
+    let Some(modification) = parse_modification(&arguments)? else {
+        return Ok(json!({
+            "error": "Invalid modification",
+            "message": "Provide the experiment as entity_modification or entity_removal",
+        }));
+    };
+
+    let is_connected = brp_client.read().await.is_connected();
+    if !is_connected {
+        warn!("BRP client not connected");
+        return Ok(json!({
+            "error": "BRP client not connected",
+            "message": "Cannot run a branch experiment - not connected to Bevy game",
+            "brp_connected": false
+        }));
+    }
+
+    let name = arguments
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(String::from)
+        .unwrap_or_else(|| format!("experiment_{frame_number}"));
+    let description = arguments
+        .get("description")
+        .and_then(|d| d.as_str())
+        .unwrap_or("Experiment mutation")
+        .to_string();
+
+    let (parent_id, branch_id, restored) = {
+        let mut branch_manager = get_branch_manager().write().await;
+        let parent_id = match arguments.get("parent_id").and_then(|p| p.as_str()) {
+            Some(id) => BranchId::from_string(id)?,
+            None => branch_manager.master_branch_id().ok_or_else(|| {
+                Error::Validation("Load a recording before branching from it".to_string())
+            })?,
+        };
+        // Fail before creating the branch if the frame doesn't exist
+        branch_manager.get_frame_from_branch(parent_id, frame_number)?;
+
+        let branch_id =
+            branch_manager.create_branch(name.clone(), Some(parent_id), frame_number)?;
+        let mut layer = ModificationLayer::new(frame_number, description);
+        layer.add_modification(modification);
+        if let Some(branch) = branch_manager.get_branch_mut(branch_id) {
+            branch.add_modification_layer(layer)?;
+        }
+        let restored = branch_manager.get_frame_from_branch(branch_id, frame_number)?;
+        (parent_id, branch_id, restored)
+    };
+
+    // Keep playback navigation on the frame the game was restored to
+    if let Err(e) = get_playback_controller()
+        .read()
+        .await
+        .seek_to_frame(frame_number)
+        .await
+    {
+        warn!(
+            "Playback controller could not seek to frame {}: {}",
+            frame_number, e
+        );
+    }
+
+    let restored_entities = {
+        let mut client = brp_client.write().await;
+        restore_frame(&restored, &mut client).await?
+    };
+
+    let config = parse_recording_config(&arguments);
+    let sample_rate = config.sample_rate;
+    let sample_interval = Duration::from_secs_f32(1.0 / sample_rate);
+    tokio::spawn(async move {
+        let capture = RecordingBuffer::new(config);
+        let started = Instant::now();
+        let mut ticker = tokio::time::interval(sample_interval);
+        // The first tick completes immediately; frame N is the restored one
+        ticker.tick().await;
+
+        for n in 1..=record_frames {
+            ticker.tick().await;
+            let frame = {
+                let mut client = brp_client.write().await;
+                capture
+                    .capture_frame(
+                        &mut client,
+                        frame_number + n,
+                        restored.timestamp + started.elapsed(),
+                    )
+                    .await
+            };
+            match frame {
+                Ok(frame) => {
+                    let mut branch_manager = get_branch_manager().write().await;
+                    if let Err(e) = branch_manager.record_branch_frame(branch_id, frame) {
+                        // The branch was deleted while recording
+                        warn!("Stopping branch recording: {}", e);
+                        return;
+                    }
+                }
+                Err(e) => error!("Failed to record branch frame: {}", e),
+            }
+        }
+        info!(
+            "Recorded {} frames into branch {}",
+            record_frames,
+            branch_id.to_string()
+        );
+    });
+
+    Ok(json!({
+        "success": true,
+        "message": "Game restored with the experiment applied; recording branch",
+        "branch_id": branch_id.to_string(),
+        "parent_id": parent_id.to_string(),
+        "name": name,
+        "branch_point_frame": frame_number,
+        "restored_entities": restored_entities,
+        "record_frames": record_frames,
+        "sample_rate": sample_rate,
+        "compare": {
+            "action": "compare_branches",
+            "branch_a": parent_id.to_string(),
+            "branch_b": branch_id.to_string(),
+            "start_frame": frame_number,
+            "end_frame": frame_number + record_frames + 1,
+        },
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+/// Push a frame's entity state to the game, returning how many entities were restored
+async fn restore_frame(frame: &Frame, brp_client: &mut BrpClient) -> Result<usize> {
+    let mut restored = 0;
+    for entity in frame.entities.values() {
+        let request = if entity.active {
+            BrpRequest::ModifyEntity {
+                entity_id: entity.entity_id,
+                components: entity
+                    .components
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect(),
+            }
+        } else {
+            BrpRequest::DeleteEntity {
+                entity_id: entity.entity_id,
+            }
+        };
+        match brp_client.send_request(&request).await? {
+            BrpResponse::Success(_) => restored += 1,
+            BrpResponse::Error(err) => {
+                warn!(
+                    "Failed to restore entity {}: {}",
+                    entity.entity_id, err.message
+                )
+            }
+        }
+    }
+    Ok(restored)
+}
+
 // Static globals now use std::sync::OnceLock instead of lazy_static
 
 #[cfg(test)]
@@ -991,6 +1551,44 @@ mod tests {
         assert_eq!(result.get("error").unwrap(), "BRP client not connected");
     }
 
+    #[tokio::test]
+    async fn test_branch_experiment_requires_mutation() {
+        let config = Config::default();
+        let brp_client = Arc::new(RwLock::new(crate::brp_client::BrpClient::new(&config)));
+
+        let result = handle(
+            json!({"action": "branch_experiment", "frame": 10}),
+            brp_client.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.get("error").unwrap(), "Invalid modification");
+
+        let result = handle(
+            json!({
+                "action": "branch_experiment",
+                "frame": 10,
+                "entity_removal": {"entity_id": 42}
+            }),
+            brp_client,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.get("error").unwrap(), "BRP client not connected");
+    }
+
+    #[test]
+    fn test_parse_assertions() {
+        let assertions = parse_assertions(&json!([
+            "entity 42 never has Health < 0",
+            {"kind": "frame_time", "op": "<", "millis": 20.0}
+        ]))
+        .unwrap();
+        assert_eq!(assertions[1].to_string(), "frame time < 20ms");
+        assert!(parse_assertions(&json!("frame time < 20ms")).is_err());
+        assert!(parse_assertions(&json!(["frame time below 20ms"])).is_err());
+    }
+
     #[test]
     fn test_parse_recording_config() {
         let args = json!({
@@ -0,0 +1,69 @@
+//! Capture, list and diff component schemas per game build
+
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::brp_client::BrpClient;
+use crate::error::{Error, Result};
+use crate::schema_history::{self, SchemaSnapshot, SchemaStore};
+use crate::tools::types::fetch_registry_schemas;
+
+/// Build name used for the running game's schemas in a diff
+const CURRENT_BUILD: &str = "current";
+
+/// Handle `schema_history` tool requests
+pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    debug!("Schema history tool called with arguments: {}", arguments);
+
+    let store = SchemaStore::from_env();
+    let build = |key: &str| -> Result<&str> {
+        arguments
+            .get(key)
+            .and_then(|b| b.as_str())
+            .ok_or_else(|| Error::Validation(format!("Missing '{key}' field")))
+    };
+    let action = arguments
+        .get("action")
+        .and_then(|a| a.as_str())
+        .unwrap_or("list");
+
+    match action {
+        "list" => Ok(json!({ "snapshots": store.list()? })),
+        "capture" => {
+            let schemas = fetch_registry_schemas(&brp_client).await?;
+            let snapshot = SchemaSnapshot::from_registry(build("build")?, &schemas);
+            store.save(&snapshot)?;
+            info!(
+                "Captured {} component and resource schemas for build {}",
+                snapshot.types.len(),
+                snapshot.build
+            );
+            Ok(json!({
+                "build": snapshot.build,
+                "captured_at": snapshot.captured_at,
+                "type_count": snapshot.types.len(),
+            }))
+        }
+        "diff" => {
+            let from = store.load(build("from")?)?;
+            // Without a second build, compare against the running game
+            let to = match arguments.get("to").and_then(|b| b.as_str()) {
+                Some(to) => store.load(to)?,
+                None => SchemaSnapshot::from_registry(
+                    CURRENT_BUILD,
+                    &fetch_registry_schemas(&brp_client).await?,
+                ),
+            };
+            let diff = schema_history::diff(&from, &to);
+            Ok(json!({
+                "identical": diff.is_empty(),
+                "diff": diff,
+            }))
+        }
+        _ => Err(Error::Validation(format!(
+            "Unknown schema_history action: {action} (expected capture, list or diff)"
+        ))),
+    }
+}
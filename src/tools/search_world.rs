@@ -0,0 +1,396 @@
+//! Find where a value appears in any entity's components
+//!
+//! A search scans component values field by field for a pattern: text
+//! (case-insensitive substring), `/regex/`, a numeric comparison such as
+//! `> 1e6`, or an exact JSON value. Every hit is reported as entity,
+//! component and dotted field path. Big worlds are sampled evenly down to
+//! `max_entities` rather than scanned in full.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse, BrpResult, EntityData, EntityId};
+use crate::error::{Error, Result};
+use crate::redaction::RedactionRules;
+
+/// Matches returned when the request sets no limit
+pub const DEFAULT_MATCH_LIMIT: usize = 100;
+
+/// Upper bound on matches returned by one request
+pub const MAX_MATCH_LIMIT: usize = 1000;
+
+/// Entities scanned when the request sets no `max_entities`
+pub const DEFAULT_MAX_ENTITIES: usize = 1000;
+
+/// Upper bound on entities scanned by one request
+pub const MAX_SCANNED_ENTITIES: usize = 10_000;
+
+/// Nesting depth past which component values are not searched
+const MAX_SEARCH_DEPTH: usize = 32;
+
+/// What a field value is compared against
+#[derive(Debug, Clone)]
+pub enum ValuePattern {
+    /// Case-insensitive substring of a string value
+    Text(String),
+    /// Regular expression over string values
+    Regex(Regex),
+    /// Numeric comparison
+    Compare(CompareOp, f64),
+    /// Equal JSON value, at any depth
+    Exact(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOp {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ValuePattern {
+    /// Parse `> 1e6`, `/gob.*/`, `== "Idle"` or plain text
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(Error::Validation("Search pattern is empty".to_string()));
+        }
+        if pattern.len() >= 2 && pattern.starts_with('/') && pattern.ends_with('/') {
+            let regex = Regex::new(&pattern[1..pattern.len() - 1])
+                .map_err(|e| Error::Validation(format!("Invalid regex '{pattern}': {e}")))?;
+            return Ok(Self::Regex(regex));
+        }
+
+        // Longer operators first so `>=` is not read as `>`
+        let operators = [
+            (">=", CompareOp::GreaterOrEqual),
+            ("<=", CompareOp::LessOrEqual),
+            ("==", CompareOp::Equal),
+            ("!=", CompareOp::NotEqual),
+            (">", CompareOp::Greater),
+            ("<", CompareOp::Less),
+        ];
+        for (symbol, op) in operators {
+            let Some(operand) = pattern.strip_prefix(symbol).map(str::trim) else {
+                continue;
+            };
+            if let Ok(number) = operand.parse::<f64>() {
+                return Ok(Self::Compare(op, number));
+            }
+            return match op {
+                CompareOp::Equal => Ok(Self::Exact(
+                    serde_json::from_str(operand).unwrap_or_else(|_| json!(operand)),
+                )),
+                _ => Err(Error::Validation(format!(
+                    "'{operand}' is not a number; '{symbol}' compares numbers"
+                ))),
+            };
+        }
+        Ok(Self::Text(pattern.to_lowercase()))
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::Text(text), Value::String(s)) => s.to_lowercase().contains(text.as_str()),
+            (Self::Regex(regex), Value::String(s)) => regex.is_match(s),
+            (Self::Compare(op, operand), Value::Number(n)) => {
+                let Some(n) = n.as_f64() else {
+                    return false;
+                };
+                match op {
+                    CompareOp::Greater => n > *operand,
+                    CompareOp::GreaterOrEqual => n >= *operand,
+                    CompareOp::Less => n < *operand,
+                    CompareOp::LessOrEqual => n <= *operand,
+                    CompareOp::Equal => n == *operand,
+                    CompareOp::NotEqual => n != *operand,
+                }
+            }
+            (Self::Exact(expected), _) => value == expected,
+            _ => false,
+        }
+    }
+}
+
+/// Where a search hit
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub entity: EntityId,
+    pub component: String,
+    /// Dotted field path within the component, empty for the whole value
+    pub path: String,
+    pub value: Value,
+}
+
+/// Replace the component data `redaction` hides before a search
+///
+/// Hidden values must not match either, or the search would still reveal
+/// what they are like.
+pub fn redact_entities(entities: &mut [EntityData], redaction: &RedactionRules) {
+    for entity in entities {
+        for (component, value) in entity.components.iter_mut() {
+            *value = redaction.redact_component(component, value.take());
+        }
+    }
+}
+
+/// Which entities and components a search covers
+#[derive(Debug, Clone)]
+pub struct SearchScope {
+    /// Substrings of component type names to search; all components when empty
+    pub components: Vec<String>,
+    pub limit: usize,
+    pub max_entities: usize,
+}
+
+/// Result of searching a set of entities
+#[derive(Debug, Serialize)]
+pub struct SearchOutcome {
+    pub matches: Vec<SearchMatch>,
+    /// Whether more matches were found than `limit`
+    pub truncated: bool,
+    pub entities_total: usize,
+    pub entities_scanned: usize,
+    /// Whether only an even sample of the entities was scanned
+    pub sampled: bool,
+}
+
+/// Handle `search_world` tool requests
+pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    handle_redacted(arguments, brp_client, None).await
+}
+
+/// Handle a search whose caller may not see the data `redaction` hides
+pub async fn handle_redacted(
+    arguments: Value,
+    brp_client: Arc<RwLock<BrpClient>>,
+    redaction: Option<&RedactionRules>,
+) -> Result<Value> {
+    debug!("Search world tool called with arguments: {}", arguments);
+
+    let pattern = match (arguments.get("pattern"), arguments.get("value")) {
+        (Some(Value::String(pattern)), None) => ValuePattern::parse(pattern)?,
+        (None, Some(value)) => ValuePattern::Exact(value.clone()),
+        _ => {
+            return Err(Error::Validation(
+                "Pass exactly one of 'pattern' (a string) or 'value'".to_string(),
+            ))
+        }
+    };
+    let components = arguments
+        .get("components")
+        .and_then(Value::as_array)
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let scope = SearchScope {
+        components,
+        limit: arguments
+            .get("limit")
+            .and_then(Value::as_u64)
+            .map_or(DEFAULT_MATCH_LIMIT, |l| l as usize)
+            .min(MAX_MATCH_LIMIT),
+        max_entities: arguments
+            .get("max_entities")
+            .and_then(Value::as_u64)
+            .map_or(DEFAULT_MAX_ENTITIES, |m| m as usize)
+            .clamp(1, MAX_SCANNED_ENTITIES),
+    };
+
+    let mut entities = fetch_entities(&brp_client).await?;
+    if let Some(redaction) = redaction {
+        redact_entities(&mut entities, redaction);
+    }
+    let outcome = search_entities(&entities, &pattern, &scope);
+    Ok(json!(outcome))
+}
+
+async fn fetch_entities(brp_client: &Arc<RwLock<BrpClient>>) -> Result<Vec<EntityData>> {
+    let mut client = brp_client.write().await;
+    if !client.is_connected() {
+        return Err(Error::Connection(
+            "Cannot search entities - not connected to Bevy game".to_string(),
+        ));
+    }
+    let request = BrpRequest::Query {
+        filter: None,
+        limit: None,
+        strict: Some(false),
+    };
+    match client.send_request(&request).await? {
+        BrpResponse::Success(result) => match *result {
+            BrpResult::Entities(entities) => Ok(entities),
+            other => Err(Error::Brp(format!(
+                "Unexpected response listing entities: {:?}",
+                other
+            ))),
+        },
+        BrpResponse::Error(err) => Err(Error::Brp(format!(
+            "Could not list entities: {}",
+            err.message
+        ))),
+    }
+}
+
+/// Search `entities`, sampling evenly when there are more than `scope.max_entities`
+pub fn search_entities(
+    entities: &[EntityData],
+    pattern: &ValuePattern,
+    scope: &SearchScope,
+) -> SearchOutcome {
+    let mut ordered: Vec<&EntityData> = entities.iter().collect();
+    ordered.sort_by_key(|entity| entity.id);
+    let sampled = ordered.len() > scope.max_entities;
+    if sampled {
+        let stride = ordered.len() as f64 / scope.max_entities as f64;
+        ordered = (0..scope.max_entities)
+            .map(|i| ordered[(i as f64 * stride) as usize])
+            .collect();
+    }
+
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    'entities: for entity in &ordered {
+        let mut components: Vec<(&String, &Value)> = entity
+            .components
+            .iter()
+            .filter(|(name, _)| {
+                scope.components.is_empty()
+                    || scope.components.iter().any(|c| name.contains(c.as_str()))
+            })
+            .collect();
+        components.sort_by_key(|(name, _)| *name);
+        for (component, value) in components {
+            let mut found = Vec::new();
+            collect_matches(value, String::new(), 0, pattern, &mut found);
+            for (path, value) in found {
+                if matches.len() == scope.limit {
+                    truncated = true;
+                    break 'entities;
+                }
+                matches.push(SearchMatch {
+                    entity: entity.id,
+                    component: component.clone(),
+                    path,
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+
+    SearchOutcome {
+        matches,
+        truncated,
+        entities_total: entities.len(),
+        entities_scanned: ordered.len(),
+        sampled,
+    }
+}
+
+fn collect_matches<'a>(
+    value: &'a Value,
+    path: String,
+    depth: usize,
+    pattern: &ValuePattern,
+    found: &mut Vec<(String, &'a Value)>,
+) {
+    if pattern.matches(value) {
+        found.push((path.clone(), value));
+    }
+    if depth >= MAX_SEARCH_DEPTH {
+        return;
+    }
+    let child_path = |segment: &str| {
+        if path.is_empty() {
+            segment.to_string()
+        } else {
+            format!("{path}.{segment}")
+        }
+    };
+    match value {
+        Value::Object(obj) => {
+            for (key, child) in obj {
+                collect_matches(child, child_path(key), depth + 1, pattern, found);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                collect_matches(child, child_path(&i.to_string()), depth + 1, pattern, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(id: EntityId, components: Value) -> EntityData {
+        EntityData {
+            id,
+            components: serde_json::from_value(components).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_search_finds_field_locations() {
+        let entities = vec![
+            entity(
+                1,
+                json!({"game::Name": "Goblin King", "game::Gold": {"amount": 2e6}}),
+            ),
+            entity(
+                2,
+                json!({"game::Name": "Knight", "game::Inventory": {"items": ["goblin ear"]}}),
+            ),
+            entity(3, json!({"game::Gold": {"amount": 10}})),
+        ];
+        let scope = SearchScope {
+            components: Vec::new(),
+            limit: DEFAULT_MATCH_LIMIT,
+            max_entities: DEFAULT_MAX_ENTITIES,
+        };
+
+        let text = search_entities(&entities, &ValuePattern::parse("goblin").unwrap(), &scope);
+        let locations: Vec<(EntityId, &str, &str)> = text
+            .matches
+            .iter()
+            .map(|m| (m.entity, m.component.as_str(), m.path.as_str()))
+            .collect();
+        assert_eq!(
+            locations,
+            vec![(1, "game::Name", ""), (2, "game::Inventory", "items.0")]
+        );
+
+        let rich = search_entities(&entities, &ValuePattern::parse("> 1e6").unwrap(), &scope);
+        assert_eq!(rich.matches.len(), 1);
+        assert_eq!(rich.matches[0].path, "amount");
+
+        let sampled = search_entities(
+            &entities,
+            &ValuePattern::parse("/^Kn/").unwrap(),
+            &SearchScope {
+                limit: 1,
+                max_entities: 2,
+                ..scope
+            },
+        );
+        assert!(sampled.sampled);
+        assert_eq!(sampled.entities_scanned, 2);
+        assert!(ValuePattern::parse("> lots").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Search the reflected types of the connected game
+//!
+//! Types are read once with `bevy/registry/schema` into a
+//! [`TypeRegistryManager`] and searched from there; pass `refresh` after the
+//! game registers new types.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+use tracing::debug;
+
+use crate::bevy_reflection::inspector::FieldMetadata;
+use crate::bevy_reflection::type_registry_tools::{
+    type_path_utils, EcsRole, TypeQuery, TypeQueryMatch, TypeRegistryManager,
+};
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{BrpRequest, BrpResponse, BrpResult};
+use crate::error::{Error, Result};
+
+/// Types returned when the request sets no limit
+pub const DEFAULT_TYPE_LIMIT: usize = 50;
+
+/// Upper bound on types returned by one request
+pub const MAX_TYPE_LIMIT: usize = 1000;
+
+/// A type as listed by the `types` tool
+#[derive(Debug, Serialize)]
+pub struct TypeSummary {
+    pub type_name: String,
+    pub short_name: String,
+    /// `component`, `resource`, `event`, or `type` for everything else
+    pub category: String,
+    /// Reflection kind such as `Struct` or `Enum`
+    pub kind: String,
+    pub fields: Vec<FieldSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+    pub relevance_score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldSummary {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+impl From<&FieldMetadata> for FieldSummary {
+    fn from(field: &FieldMetadata) -> Self {
+        Self {
+            name: field.name.clone(),
+            type_name: field.type_name.clone(),
+        }
+    }
+}
+
+impl From<TypeQueryMatch> for TypeSummary {
+    fn from(found: TypeQueryMatch) -> Self {
+        let category = match found.ecs_role {
+            Some(EcsRole::Component) => "component",
+            Some(EcsRole::Resource) => "resource",
+            Some(EcsRole::Event) => "event",
+            None => "type",
+        };
+        Self {
+            short_name: type_path_utils::extract_short_name(&found.type_name).to_string(),
+            category: category.to_string(),
+            kind: format!("{:?}", found.metadata.type_category),
+            fields: found
+                .metadata
+                .fields
+                .iter()
+                .map(FieldSummary::from)
+                .collect(),
+            docs: found.docs,
+            relevance_score: found.relevance_score,
+            type_name: found.type_name,
+        }
+    }
+}
+
+/// The registry the `types` tool searches
+fn registry() -> &'static TypeRegistryManager {
+    static REGISTRY: OnceLock<TypeRegistryManager> = OnceLock::new();
+    REGISTRY.get_or_init(TypeRegistryManager::new)
+}
+
+/// Handle `types` tool requests
+pub async fn handle(arguments: Value, brp_client: Arc<RwLock<BrpClient>>) -> Result<Value> {
+    debug!("Types tool called with arguments: {}", arguments);
+
+    let search = arguments
+        .get("search")
+        .and_then(|s| s.as_str())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    let ecs_role = match arguments.get("category").and_then(|c| c.as_str()) {
+        None | Some("all") => None,
+        Some("component") => Some(EcsRole::Component),
+        Some("resource") => Some(EcsRole::Resource),
+        Some("event") => Some(EcsRole::Event),
+        Some(other) => {
+            return Err(Error::Validation(format!(
+                "Unknown category '{other}' (expected component, resource, event or all)"
+            )))
+        }
+    };
+    let limit = arguments
+        .get("limit")
+        .and_then(|l| l.as_u64())
+        .map_or(DEFAULT_TYPE_LIMIT, |l| l as usize)
+        .min(MAX_TYPE_LIMIT);
+    let refresh = arguments
+        .get("refresh")
+        .and_then(|r| r.as_bool())
+        .unwrap_or(false);
+
+    let registry = registry();
+    if refresh || registry.get_stats().await.total_types == 0 {
+        load_types(registry, &brp_client).await?;
+    }
+
+    let result = registry
+        .query_types(&TypeQuery {
+            name_pattern: search,
+            ecs_role,
+            limit,
+            ..Default::default()
+        })
+        .await?;
+    let types: Vec<TypeSummary> = result.types.into_iter().map(TypeSummary::from).collect();
+
+    Ok(json!({
+        "types": types,
+        "total_matches": result.total_matches,
+        "truncated": result.truncated,
+        "type_count": registry.get_stats().await.total_types,
+    }))
+}
+
+/// Replace the registry's types with those the game reports
+async fn load_types(
+    registry: &TypeRegistryManager,
+    brp_client: &Arc<RwLock<BrpClient>>,
+) -> Result<usize> {
+    let schemas = fetch_registry_schemas(brp_client).await?;
+    registry.discover_types_from_schemas(&schemas).await
+}
+
+/// Schemas of every reflected type in the game, by type path
+pub async fn fetch_registry_schemas(
+    brp_client: &Arc<RwLock<BrpClient>>,
+) -> Result<HashMap<String, Value>> {
+    let mut client = brp_client.write().await;
+    if !client.is_connected() {
+        return Err(Error::Connection(
+            "Cannot read the type registry - not connected to Bevy game".to_string(),
+        ));
+    }
+    let request = BrpRequest::RegistrySchema {
+        with_crates: None,
+        without_crates: None,
+    };
+    match client.send_request(&request).await? {
+        BrpResponse::Success(result) => match *result {
+            BrpResult::TypeSchemas(schemas) => Ok(schemas),
+            other => Err(Error::Brp(format!(
+                "Unexpected response reading the type registry: {:?}",
+                other
+            ))),
+        },
+        BrpResponse::Error(err) => Err(Error::Brp(format!(
+            "Could not read the type registry: {}",
+            err.message
+        ))),
+    }
+}
@@ -0,0 +1,284 @@
+/*
+ * Bevy Debugger MCP Server - TOTP Two-Factor Authentication
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use base64::Engine as _;
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Seconds each code is valid for (RFC 6238 default)
+pub const TOTP_STEP_SECONDS: u64 = 30;
+
+/// Digits per code
+pub const TOTP_DIGITS: u32 = 6;
+
+/// Steps either side of the current one that are accepted, for clock drift
+const ALLOWED_SKEW_STEPS: u64 = 1;
+
+/// Recovery codes issued at enrollment
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A user's second factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactor {
+    /// Base32 shared secret
+    pub secret: String,
+    /// Set once the user proved their authenticator works; required at login from then on
+    pub enabled: bool,
+    /// Hashes of unused recovery codes
+    pub recovery_code_hashes: Vec<String>,
+    /// Last accepted time step, so a code cannot be replayed
+    pub last_used_step: Option<u64>,
+}
+
+/// What a new enrollment hands to the user, once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    /// `otpauth://` URI for authenticator apps (usually shown as a QR code)
+    pub otpauth_uri: String,
+    pub recovery_codes: Vec<String>,
+}
+
+impl TwoFactor {
+    /// Start an enrollment for `account`; not enforced until confirmed
+    pub fn enroll(account: &str, issuer: &str) -> Result<(Self, TotpEnrollment)> {
+        let secret = base32_encode(&random_bytes::<20>()?);
+        let recovery_codes = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect::<Result<Vec<_>>>()?;
+
+        let two_factor = Self {
+            secret: secret.clone(),
+            enabled: false,
+            recovery_code_hashes: recovery_codes
+                .iter()
+                .map(|c| hash_recovery_code(c))
+                .collect(),
+            last_used_step: None,
+        };
+        let enrollment = TotpEnrollment {
+            otpauth_uri: provisioning_uri(&secret, account, issuer),
+            secret,
+            recovery_codes,
+        };
+        Ok((two_factor, enrollment))
+    }
+
+    /// Check a TOTP or recovery code at `unix_time`, consuming it when accepted
+    pub fn verify(&mut self, code: &str, unix_time: u64) -> bool {
+        let code = code.trim();
+        if code.len() == TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+            let Ok(key) = base32_decode(&self.secret) else {
+                return false;
+            };
+            let current = unix_time / TOTP_STEP_SECONDS;
+            let earliest = current.saturating_sub(ALLOWED_SKEW_STEPS);
+            for step in earliest..=current + ALLOWED_SKEW_STEPS {
+                let fresh = !matches!(self.last_used_step, Some(last) if step <= last);
+                if fresh && constant_time_eq(&hotp(&key, step), code) {
+                    self.last_used_step = Some(step);
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        let hash = hash_recovery_code(code);
+        match self
+            .recovery_code_hashes
+            .iter()
+            .position(|stored| constant_time_eq(stored, &hash))
+        {
+            Some(index) => {
+                self.recovery_code_hashes.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// HOTP value (RFC 4226) for `counter`
+pub fn hotp(key: &[u8], counter: u64) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, key);
+    let tag = hmac::sign(&key, &counter.to_be_bytes());
+    let digest = tag.as_ref();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// URI understood by authenticator apps
+pub fn provisioning_uri(secret: &str, account: &str, issuer: &str) -> String {
+    let encode = |s: &str| -> String {
+        s.bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    };
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&digits={}&period={}",
+        encode(issuer),
+        encode(account),
+        secret,
+        encode(issuer),
+        TOTP_DIGITS,
+        TOTP_STEP_SECONDS
+    )
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| Error::SecurityError("Failed to generate two-factor secret".to_string()))?;
+    Ok(bytes)
+}
+
+/// A code like `k3vq-9xmt`, from an alphabet without look-alike characters
+fn generate_recovery_code() -> Result<String> {
+    const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+    let bytes = random_bytes::<8>()?;
+    let chars: String = bytes
+        .iter()
+        .map(|b| ALPHABET[*b as usize % ALPHABET.len()] as char)
+        .collect();
+    Ok(format!("{}-{}", &chars[..4], &chars[4..]))
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalized = code.trim().to_lowercase();
+    let digest = ring::digest::digest(&ring::digest::SHA256, normalized.as_bytes());
+    base64::engine::general_purpose::STANDARD_NO_PAD.encode(digest.as_ref())
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// RFC 4648 base32 without padding
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity((data.len() + 4) / 5 * 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+/// Decode RFC 4648 base32, ignoring case, spaces and padding
+pub fn base32_decode(encoded: &str) -> Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(encoded.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in encoded.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())
+            .ok_or_else(|| Error::Validation(format!("Invalid base32 character: {}", c)))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        // RFC 6238 appendix B, SHA-1 with the ASCII key "12345678901234567890"
+        let key = b"12345678901234567890";
+        assert_eq!(base32_decode(&base32_encode(key)).unwrap(), key);
+        for (time, expected) in [
+            (59u64, "94287082"),
+            (1111111109, "07081804"),
+            (1234567890, "89005924"),
+        ] {
+            // The vectors are 8 digits; the low 6 digits are the 6 digit code
+            assert_eq!(hotp(key, time / TOTP_STEP_SECONDS), expected[2..]);
+        }
+    }
+
+    #[test]
+    fn test_codes_cannot_be_replayed() {
+        let (mut two_factor, enrollment) = TwoFactor::enroll("admin", "Bevy Debugger").unwrap();
+        assert!(enrollment
+            .otpauth_uri
+            .starts_with("otpauth://totp/Bevy%20Debugger:admin?secret="));
+
+        let now = 1_700_000_000;
+        let key = base32_decode(&two_factor.secret).unwrap();
+        let code = hotp(&key, now / TOTP_STEP_SECONDS);
+        assert!(two_factor.verify(&code, now));
+        assert!(!two_factor.verify(&code, now + 5));
+
+        // An older step within the skew window is refused once a newer one was used
+        let previous = hotp(&key, now / TOTP_STEP_SECONDS - 1);
+        assert!(!two_factor.verify(&previous, now));
+        assert!(!two_factor.verify("000000", now + TOTP_STEP_SECONDS * 10));
+    }
+
+    #[test]
+    fn test_recovery_codes_are_single_use() {
+        let (mut two_factor, enrollment) = TwoFactor::enroll("dev", "Bevy Debugger").unwrap();
+        assert_eq!(enrollment.recovery_codes.len(), RECOVERY_CODE_COUNT);
+
+        let code = enrollment.recovery_codes[3].to_uppercase();
+        assert!(two_factor.verify(&code, 0));
+        assert!(!two_factor.verify(&code, 0));
+        assert_eq!(
+            two_factor.recovery_code_hashes.len(),
+            RECOVERY_CODE_COUNT - 1
+        );
+    }
+}
@@ -0,0 +1,205 @@
+/// Remote transform manipulation
+///
+/// Applies nudge/rotate/scale operations from `ManipulateTransform` to an
+/// entity's local transform as read through BRP. The math is done here on the
+/// serialized transform so the server does not depend on Bevy.
+use crate::brp_messages::{TransformOperation, TransformSpace, TransformState};
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// Reflected type path of Bevy's `Transform` component
+pub const TRANSFORM_COMPONENT: &str = "bevy_transform::components::transform::Transform";
+
+/// Largest translation delta accepted in one operation
+pub const MAX_NUDGE_DISTANCE: f32 = 10_000.0;
+
+/// Smallest and largest scale factor accepted in one operation
+pub const SCALE_FACTOR_RANGE: (f32, f32) = (0.001, 1000.0);
+
+/// Validate an operation before it is applied
+pub fn validate_operation(operation: &TransformOperation) -> Result<()> {
+    let finite = |values: &[f32]| values.iter().all(|v| v.is_finite());
+    match operation {
+        TransformOperation::Nudge { delta } => {
+            if !finite(delta) || length(*delta) > MAX_NUDGE_DISTANCE {
+                return Err(Error::Validation(format!(
+                    "Nudge delta must be finite and at most {} units",
+                    MAX_NUDGE_DISTANCE
+                )));
+            }
+        }
+        TransformOperation::Rotate { axis, degrees } => {
+            if !finite(axis) || !degrees.is_finite() || length(*axis) < f32::EPSILON {
+                return Err(Error::Validation(
+                    "Rotation needs a finite, non-zero axis and angle".to_string(),
+                ));
+            }
+        }
+        TransformOperation::Scale { factor } => {
+            let (min, max) = SCALE_FACTOR_RANGE;
+            if !factor
+                .iter()
+                .all(|f| f.is_finite() && (min..=max).contains(f))
+            {
+                return Err(Error::Validation(format!(
+                    "Scale factors must be between {} and {}",
+                    min, max
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read a transform from the reflected component value
+pub fn parse_transform(value: &Value) -> Result<TransformState> {
+    serde_json::from_value(value.clone())
+        .map_err(|e| Error::Validation(format!("Unexpected Transform format: {}", e)))
+}
+
+/// Apply an operation to a local transform
+pub fn apply_operation(
+    transform: &TransformState,
+    operation: &TransformOperation,
+    space: TransformSpace,
+) -> TransformState {
+    let mut result = *transform;
+    match operation {
+        TransformOperation::Nudge { delta } => {
+            let delta = match space {
+                TransformSpace::Parent => *delta,
+                TransformSpace::Local => rotate_vector(transform.rotation, *delta),
+            };
+            for (axis, d) in result.translation.iter_mut().zip(delta) {
+                *axis += d;
+            }
+        }
+        TransformOperation::Rotate { axis, degrees } => {
+            let delta = quat_from_axis_angle(*axis, degrees.to_radians());
+            result.rotation = normalize_quat(match space {
+                TransformSpace::Parent => quat_mul(delta, transform.rotation),
+                TransformSpace::Local => quat_mul(transform.rotation, delta),
+            });
+        }
+        TransformOperation::Scale { factor } => {
+            // Scale is always along the entity's own axes
+            for (axis, f) in result.scale.iter_mut().zip(factor) {
+                *axis *= f;
+            }
+        }
+    }
+    result
+}
+
+fn length(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+fn quat_from_axis_angle(axis: [f32; 3], angle: f32) -> [f32; 4] {
+    let len = length(axis);
+    let (sin, cos) = (angle * 0.5).sin_cos();
+    [
+        axis[0] / len * sin,
+        axis[1] / len * sin,
+        axis[2] / len * sin,
+        cos,
+    ]
+}
+
+fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let [ax, ay, az, aw] = a;
+    let [bx, by, bz, bw] = b;
+    [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+}
+
+fn normalize_quat(q: [f32; 4]) -> [f32; 4] {
+    let len = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    if len < f32::EPSILON {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+    q.map(|c| c / len)
+}
+
+fn rotate_vector(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    let conjugate = [-q[0], -q[1], -q[2], q[3]];
+    let rotated = quat_mul(quat_mul(q, [v[0], v[1], v[2], 0.0]), conjugate);
+    [rotated[0], rotated[1], rotated[2]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_nudge_in_parent_and_local_space() {
+        let quarter_turn_y = quat_from_axis_angle([0.0, 1.0, 0.0], 90f32.to_radians());
+        let transform = TransformState {
+            rotation: quarter_turn_y,
+            ..TransformState::default()
+        };
+        let nudge = TransformOperation::Nudge {
+            delta: [1.0, 0.0, 0.0],
+        };
+
+        let parent = apply_operation(&transform, &nudge, TransformSpace::Parent);
+        assert_close(&parent.translation, &[1.0, 0.0, 0.0]);
+
+        // Local +X points along parent -Z after a quarter turn around Y
+        let local = apply_operation(&transform, &nudge, TransformSpace::Local);
+        assert_close(&local.translation, &[0.0, 0.0, -1.0]);
+    }
+
+    #[test]
+    fn test_rotate_and_scale() {
+        let rotate = TransformOperation::Rotate {
+            axis: [0.0, 0.0, 2.0],
+            degrees: 180.0,
+        };
+        let rotated = apply_operation(&TransformState::default(), &rotate, TransformSpace::Parent);
+        assert_close(&rotated.rotation, &[0.0, 0.0, 1.0, 0.0]);
+
+        let scale = TransformOperation::Scale {
+            factor: [2.0, 1.0, 0.5],
+        };
+        let scaled = apply_operation(&rotated, &scale, TransformSpace::Local);
+        assert_close(&scaled.scale, &[2.0, 1.0, 0.5]);
+        assert_eq!(scaled.rotation, rotated.rotation);
+    }
+
+    #[test]
+    fn test_validate_and_parse() {
+        assert!(validate_operation(&TransformOperation::Rotate {
+            axis: [0.0; 3],
+            degrees: 45.0
+        })
+        .is_err());
+        assert!(validate_operation(&TransformOperation::Scale {
+            factor: [0.0, 1.0, 1.0]
+        })
+        .is_err());
+        assert!(validate_operation(&TransformOperation::Nudge {
+            delta: [0.5, 0.0, f32::NAN]
+        })
+        .is_err());
+
+        let parsed = parse_transform(&serde_json::json!({
+            "translation": [1.0, 2.0, 3.0],
+            "rotation": [0.0, 0.0, 0.0, 1.0],
+            "scale": [1.0, 1.0, 1.0]
+        }))
+        .unwrap();
+        assert_eq!(parsed.translation, [1.0, 2.0, 3.0]);
+        assert!(parse_transform(&serde_json::json!({"translation": [1.0]})).is_err());
+    }
+}
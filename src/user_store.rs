@@ -0,0 +1,347 @@
+/*
+ * Bevy Debugger MCP Server - Persistent User Store
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+use crate::security::{ApiKey, FailedLogin, User};
+
+/// Current version of the stored user document
+pub const USER_STORE_VERSION: u32 = 1;
+
+/// Marker identifying an encrypted user store file
+const ENVELOPE_FORMAT: &str = "bevy-mcp-users";
+
+/// Migrations between document versions; entry `i` upgrades version `i` to `i + 1`
+const MIGRATIONS: &[fn(Value) -> Result<Value>] = &[migrate_v0_users_file];
+
+/// Users and lockout state that survive restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserStoreSnapshot {
+    pub users: HashMap<String, User>,
+    #[serde(default)]
+    pub lockouts: HashMap<String, FailedLogin>,
+    #[serde(default)]
+    pub api_keys: HashMap<String, ApiKey>,
+}
+
+/// Storage backend for the security manager's users
+#[async_trait]
+pub trait UserStore: Send + Sync + std::fmt::Debug {
+    /// Load stored state, or `None` when nothing has been stored yet
+    async fn load(&self) -> Result<Option<UserStoreSnapshot>>;
+
+    /// Replace the stored state
+    async fn save(&self, snapshot: &UserStoreSnapshot) -> Result<()>;
+}
+
+/// Keeps nothing; users live only as long as the process
+#[derive(Debug, Default)]
+pub struct InMemoryUserStore;
+
+#[async_trait]
+impl UserStore for InMemoryUserStore {
+    async fn load(&self) -> Result<Option<UserStoreSnapshot>> {
+        Ok(None)
+    }
+
+    async fn save(&self, _snapshot: &UserStoreSnapshot) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Stores users in a JSON file encrypted with AES-256-GCM
+///
+/// A plaintext users file (a JSON array of users, as returned by `list_users`)
+/// is imported on load and rewritten encrypted.
+pub struct EncryptedFileUserStore {
+    path: PathBuf,
+    key: LessSafeKey,
+    rng: SystemRandom,
+    /// Saves share the temporary file, so only one may write at a time
+    save_lock: tokio::sync::Mutex<()>,
+}
+
+impl std::fmt::Debug for EncryptedFileUserStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedFileUserStore")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// On-disk wrapper around the encrypted document
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    format: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedFileUserStore {
+    /// Create a store at `path`; the encryption key is derived from `secret`
+    pub fn new(path: impl AsRef<Path>, secret: &str) -> Result<Self> {
+        if secret.len() < 32 {
+            return Err(Error::SecurityError(
+                "User store key must be at least 32 characters long".to_string(),
+            ));
+        }
+        let key_bytes = digest(&SHA256, secret.as_bytes());
+        let key = UnboundKey::new(&AES_256_GCM, key_bytes.as_ref())
+            .map_err(|_| Error::SecurityError("Invalid user store key".to_string()))?;
+
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+            save_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn encrypt(&self, plaintext: Vec<u8>) -> Result<Envelope> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce_bytes)
+            .map_err(|_| Error::SecurityError("Failed to generate nonce".to_string()))?;
+
+        let mut in_out = plaintext;
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::from(ENVELOPE_FORMAT.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| Error::SecurityError("Failed to encrypt user store".to_string()))?;
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        Ok(Envelope {
+            format: ENVELOPE_FORMAT.to_string(),
+            nonce: engine.encode(nonce_bytes),
+            ciphertext: engine.encode(in_out),
+        })
+    }
+
+    fn decrypt(&self, envelope: &Envelope) -> Result<Vec<u8>> {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let corrupt = |_| Error::SecurityError("User store file is corrupt".to_string());
+        let nonce_bytes: [u8; NONCE_LEN] = engine
+            .decode(&envelope.nonce)
+            .map_err(corrupt)?
+            .try_into()
+            .map_err(|_| Error::SecurityError("User store file is corrupt".to_string()))?;
+        let mut in_out = engine.decode(&envelope.ciphertext).map_err(corrupt)?;
+
+        let plaintext = self
+            .key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::from(ENVELOPE_FORMAT.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| {
+                Error::SecurityError(
+                    "Failed to decrypt user store; check BEVY_MCP_USER_STORE_KEY".to_string(),
+                )
+            })?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+#[async_trait]
+impl UserStore for EncryptedFileUserStore {
+    async fn load(&self) -> Result<Option<UserStoreSnapshot>> {
+        let contents = match tokio::fs::read(&self.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let raw: Value = serde_json::from_slice(&contents)?;
+        let encrypted = raw.get("format").and_then(Value::as_str) == Some(ENVELOPE_FORMAT);
+        let document = if encrypted {
+            let envelope: Envelope = serde_json::from_value(raw)?;
+            serde_json::from_slice(&self.decrypt(&envelope)?)?
+        } else {
+            warn!(
+                "Importing plaintext users file {}; it will be rewritten encrypted",
+                self.path.display()
+            );
+            raw
+        };
+
+        let (snapshot, migrated) = migrate_document(document)?;
+        if migrated || !encrypted {
+            self.save(&snapshot).await?;
+            info!(
+                "User store {} upgraded to version {}",
+                self.path.display(),
+                USER_STORE_VERSION
+            );
+        }
+        Ok(Some(snapshot))
+    }
+
+    async fn save(&self, snapshot: &UserStoreSnapshot) -> Result<()> {
+        let mut document = serde_json::to_value(snapshot)?;
+        document["version"] = json!(USER_STORE_VERSION);
+        let envelope = self.encrypt(serde_json::to_vec(&document)?)?;
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        // Write to a sibling file and rename so a crash never leaves a partial store
+        let _saving = self.save_lock.lock().await;
+        let temp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&temp_path, serde_json::to_vec_pretty(&envelope)?).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            tokio::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o600)).await?;
+        }
+        tokio::fs::rename(&temp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+/// Upgrade a stored document to the current version; also reports whether it changed
+fn migrate_document(mut document: Value) -> Result<(UserStoreSnapshot, bool)> {
+    // A bare array of users predates versioning
+    let mut version = match &document {
+        Value::Array(_) => 0,
+        _ => document
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| Error::SecurityError("User store has no version".to_string()))?
+            as u32,
+    };
+    if version > USER_STORE_VERSION {
+        return Err(Error::SecurityError(format!(
+            "User store version {} is newer than supported version {}",
+            version, USER_STORE_VERSION
+        )));
+    }
+
+    let migrated = version < USER_STORE_VERSION;
+    while version < USER_STORE_VERSION {
+        document = MIGRATIONS[version as usize](document)?;
+        version += 1;
+    }
+    Ok((serde_json::from_value(document)?, migrated))
+}
+
+/// Version 0 is a plaintext list of users
+fn migrate_v0_users_file(document: Value) -> Result<Value> {
+    let users: Vec<User> = serde_json::from_value(document)?;
+    let users: HashMap<String, User> = users
+        .into_iter()
+        .map(|user| (user.username.clone(), user))
+        .collect();
+    Ok(json!({ "version": 1, "users": users, "lockouts": {} }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Role;
+    use chrono::Utc;
+
+    const KEY: &str = "test-user-store-key-at-least-32-characters";
+
+    fn test_user(name: &str) -> User {
+        User {
+            id: name.to_string(),
+            username: name.to_string(),
+            password_hash: "$argon2id$placeholder".to_string(),
+            role: Role::Developer,
+            created_at: Utc::now(),
+            last_login: None,
+            active: true,
+            two_factor: None,
+            password_changed_at: None,
+            password_history: Vec::new(),
+            must_change_password: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json");
+        let store = EncryptedFileUserStore::new(&path, KEY).unwrap();
+        assert!(store.load().await.unwrap().is_none());
+
+        let mut snapshot = UserStoreSnapshot::default();
+        snapshot
+            .users
+            .insert("alice".to_string(), test_user("alice"));
+        snapshot.lockouts.insert(
+            "bob".to_string(),
+            FailedLogin {
+                count: 5,
+                first_attempt: Utc::now(),
+                last_attempt: Utc::now(),
+                locked_until: Some(Utc::now()),
+            },
+        );
+        store.save(&snapshot).await.unwrap();
+
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert!(!on_disk.contains("alice"));
+
+        let loaded = store.load().await.unwrap().unwrap();
+        assert_eq!(loaded.users["alice"].role, Role::Developer);
+        assert_eq!(loaded.lockouts["bob"].count, 5);
+
+        let wrong_key = EncryptedFileUserStore::new(&path, &KEY.replace('t', "x")).unwrap();
+        assert!(wrong_key.load().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_users_file_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("users.json");
+        let users = vec![test_user("alice"), test_user("carol")];
+        std::fs::write(&path, serde_json::to_vec(&users).unwrap()).unwrap();
+
+        let store = EncryptedFileUserStore::new(&path, KEY).unwrap();
+        let loaded = store.load().await.unwrap().unwrap();
+        assert_eq!(loaded.users.len(), 2);
+        assert!(loaded.lockouts.is_empty());
+
+        // Rewritten encrypted at the current version
+        let raw: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(raw["format"], ENVELOPE_FORMAT);
+        assert!(migrate_document(json!({ "version": USER_STORE_VERSION + 1 })).is_err());
+        assert!(EncryptedFileUserStore::new(&path, "short").is_err());
+    }
+}
@@ -3,11 +3,16 @@
 /// This processor handles the MCP side of visual debug overlays. The actual
 /// rendering implementation is handled by the game-side Bevy systems.
 use crate::brp_messages::{
-    DebugCommand, DebugResponse, DebugOverlayType, BrpRequest, BrpResponse, BrpResult
+    CustomOverlayDefinition, DebugCommand, DebugResponse, DebugOverlayType, BrpRequest, BrpResponse,
+    BrpResult, EntityId, OverlayTheme, OverlayToggleState, ThemePalette, TransformOperation, TransformSpace, TransformState, WorldMarker
 };
 use crate::brp_client::BrpClient;
 use crate::debug_command_processor::DebugCommandProcessor;
 use crate::error::{Error, Result};
+use crate::overlay_theme::{overlay_theme_configs, resolve_palette, validate_theme};
+use crate::transform_manipulation::{
+    apply_operation, parse_transform, validate_operation, TRANSFORM_COMPONENT,
+};
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -17,6 +22,151 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+/// Maximum number of markers placed at once
+pub const MAX_MARKERS: usize = 200;
+
+/// Maximum marker time to live (1 hour)
+pub const MAX_MARKER_TTL_MS: u64 = 3_600_000;
+
+/// Maximum marker text length
+pub const MAX_MARKER_TEXT_LEN: usize = 256;
+
+/// Parse the overlays requested for a capture
+///
+/// Each entry is either an overlay type (`"BoundingBoxes"`) or an object with
+/// `type` and an optional `config`.
+pub fn parse_capture_overlays(value: &Value) -> Result<Vec<(DebugOverlayType, Option<Value>)>> {
+    let entries = value
+        .as_array()
+        .ok_or_else(|| Error::Validation("'overlays' must be an array".to_string()))?;
+
+    entries
+        .iter()
+        .map(|entry| {
+            let (overlay_type, config) = match entry.get("type") {
+                Some(overlay_type) => (overlay_type.clone(), entry.get("config").cloned()),
+                None => (entry.clone(), None),
+            };
+            let overlay_type: DebugOverlayType = serde_json::from_value(overlay_type)
+                .map_err(|e| Error::Validation(format!("Invalid overlay type: {}", e)))?;
+            Ok((overlay_type, config))
+        })
+        .collect()
+}
+
+/// Maximum shapes in a data-driven overlay
+pub const MAX_CUSTOM_OVERLAY_SHAPES: usize = 16;
+
+/// Maximum component filters in a data-driven overlay query
+pub const MAX_CUSTOM_OVERLAY_FILTERS: usize = 8;
+
+/// Maximum entities a data-driven overlay may draw per frame
+pub const MAX_CUSTOM_OVERLAY_ENTITIES: usize = 5000;
+
+/// Validate a data-driven overlay definition before it is sent to the game
+pub fn validate_overlay_definition(definition: &CustomOverlayDefinition) -> Result<()> {
+    if definition.name.is_empty()
+        || definition.name.len() > 64
+        || !definition
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(Error::Validation(format!(
+            "Invalid overlay name '{}': use up to 64 letters, digits, '_' or '-'",
+            definition.name
+        )));
+    }
+    if definition.shapes.is_empty() || definition.shapes.len() > MAX_CUSTOM_OVERLAY_SHAPES {
+        return Err(Error::Validation(format!(
+            "Overlay needs 1 to {} shapes",
+            MAX_CUSTOM_OVERLAY_SHAPES
+        )));
+    }
+    let filters = definition.query.with.len() + definition.query.without.len();
+    if filters > MAX_CUSTOM_OVERLAY_FILTERS {
+        return Err(Error::Validation(format!(
+            "Overlay query has {} component filters (max {})",
+            filters, MAX_CUSTOM_OVERLAY_FILTERS
+        )));
+    }
+    if definition.max_entities == 0 || definition.max_entities > MAX_CUSTOM_OVERLAY_ENTITIES {
+        return Err(Error::Validation(format!(
+            "max_entities must be between 1 and {}",
+            MAX_CUSTOM_OVERLAY_ENTITIES
+        )));
+    }
+    for shape in &definition.shapes {
+        if !shape.size.is_finite() || shape.size <= 0.0 {
+            return Err(Error::Validation("Shape size must be positive".to_string()));
+        }
+        let values = shape.offset.iter().chain(shape.direction.iter().flatten());
+        if !values.chain(shape.color.iter()).all(|v| v.is_finite()) {
+            return Err(Error::Validation("Shape values must be finite".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Validate a marker before it is placed
+pub fn validate_marker(marker: &WorldMarker) -> Result<()> {
+    if marker.id.is_empty() || marker.id.len() > 64 {
+        return Err(Error::Validation(
+            "Marker id must be between 1 and 64 characters".to_string(),
+        ));
+    }
+
+    let direction = marker.direction.unwrap_or([0.0; 3]);
+    if marker
+        .position
+        .iter()
+        .chain(direction.iter())
+        .chain(marker.color.iter())
+        .any(|v| !v.is_finite())
+    {
+        return Err(Error::Validation(format!(
+            "Marker '{}' has non-finite coordinates or color",
+            marker.id
+        )));
+    }
+
+    if !(marker.size > 0.0 && marker.size <= 1000.0) {
+        return Err(Error::Validation(format!(
+            "Marker size must be in (0, 1000], got {}",
+            marker.size
+        )));
+    }
+
+    if marker.ttl_ms.is_some_and(|ttl| ttl == 0 || ttl > MAX_MARKER_TTL_MS) {
+        return Err(Error::Validation(format!(
+            "Marker TTL must be between 1 and {} ms",
+            MAX_MARKER_TTL_MS
+        )));
+    }
+
+    if marker.text.as_ref().is_some_and(|t| t.len() > MAX_MARKER_TEXT_LEN) {
+        return Err(Error::Validation(format!(
+            "Marker text too long (max: {} bytes)",
+            MAX_MARKER_TEXT_LEN
+        )));
+    }
+
+    Ok(())
+}
+
+/// A placed marker and when it expires
+#[derive(Debug, Clone)]
+struct MarkerEntry {
+    marker: WorldMarker,
+    expires_at: Option<Instant>,
+}
+
+/// Overlay state to restore after overlays were enabled temporarily
+#[derive(Debug, Clone, Default)]
+pub struct OverlaySnapshot {
+    previous: Vec<(DebugOverlayType, Option<OverlayConfig>)>,
+}
+
 /// Configuration for individual overlay types
 #[derive(Debug, Clone)]
 pub struct OverlayConfig {
@@ -65,6 +215,8 @@ pub struct VisualDebugOverlayState {
     brp_client: Arc<RwLock<BrpClient>>,
     /// Performance budget (2ms = 2000us per frame)
     performance_budget_us: u64,
+    /// World-space markers by id
+    markers: HashMap<String, MarkerEntry>,
 }
 
 impl VisualDebugOverlayState {
@@ -75,6 +227,235 @@ impl VisualDebugOverlayState {
             total_metrics: OverlayMetrics::default(),
             brp_client,
             performance_budget_us: 2000, // 2ms budget as per requirements
+            markers: HashMap::new(),
+        }
+    }
+
+    /// Place a marker, replacing any marker with the same id
+    pub fn place_marker(&mut self, marker: WorldMarker) -> Result<()> {
+        validate_marker(&marker)?;
+        self.prune_expired_markers();
+
+        if !self.markers.contains_key(&marker.id) && self.markers.len() >= MAX_MARKERS {
+            return Err(Error::Validation(format!(
+                "Too many markers (max: {})",
+                MAX_MARKERS
+            )));
+        }
+
+        let expires_at = marker
+            .ttl_ms
+            .map(|ttl| Instant::now() + Duration::from_millis(ttl));
+        self.markers
+            .insert(marker.id.clone(), MarkerEntry { marker, expires_at });
+        Ok(())
+    }
+
+    /// Remove a marker, returning whether it existed
+    pub fn remove_marker(&mut self, id: &str) -> bool {
+        self.markers.remove(id).is_some()
+    }
+
+    /// Remove all markers
+    pub fn clear_markers(&mut self) {
+        self.markers.clear();
+    }
+
+    /// Active markers sorted by id, with `ttl_ms` set to the remaining lifetime
+    pub fn active_markers(&mut self) -> Vec<WorldMarker> {
+        self.prune_expired_markers();
+        let now = Instant::now();
+
+        let mut markers: Vec<WorldMarker> = self
+            .markers
+            .values()
+            .map(|entry| {
+                let mut marker = entry.marker.clone();
+                marker.ttl_ms = entry.expires_at.map(|expires_at| {
+                    expires_at.saturating_duration_since(now).as_millis().max(1) as u64
+                });
+                marker
+            })
+            .collect();
+        markers.sort_by(|a, b| a.id.cmp(&b.id));
+        markers
+    }
+
+    fn prune_expired_markers(&mut self) {
+        let now = Instant::now();
+        self.markers
+            .retain(|_, entry| entry.expires_at.map_or(true, |expires_at| expires_at > now));
+    }
+
+    /// Send the full marker set to the game's debug markers overlay
+    pub async fn sync_markers(&mut self) -> Result<Vec<WorldMarker>> {
+        let markers = self.active_markers();
+        let markers_by_id: serde_json::Map<String, Value> = markers
+            .iter()
+            .map(|marker| Ok((marker.id.clone(), serde_json::to_value(marker)?)))
+            .collect::<Result<_>>()?;
+
+        self.set_overlay_enabled(
+            &DebugOverlayType::DebugMarkers,
+            !markers.is_empty(),
+            Some(json!({ "markers": markers_by_id })),
+        )
+        .await?;
+
+        Ok(markers)
+    }
+
+    /// Apply a transform operation to an entity through BRP and show it with
+    /// the transforms overlay gizmo; returns the transform before and after
+    pub async fn manipulate_transform(
+        &mut self,
+        entity: EntityId,
+        operation: TransformOperation,
+        space: TransformSpace,
+    ) -> Result<(TransformState, TransformState)> {
+        validate_operation(&operation)?;
+
+        let get_request = BrpRequest::Get {
+            entity,
+            components: Some(vec![TRANSFORM_COMPONENT.to_string()]),
+        };
+        let before = match self.send_brp_request(&get_request).await? {
+            BrpResult::Entity(data) => data
+                .components
+                .get(TRANSFORM_COMPONENT)
+                .map(parse_transform)
+                .transpose()?
+                .ok_or_else(|| {
+                    Error::DebugError(format!("Entity {} has no Transform", entity))
+                })?,
+            other => {
+                return Err(Error::Brp(format!(
+                    "Unexpected response reading Transform: {:?}",
+                    other
+                )))
+            }
+        };
+
+        let after = apply_operation(&before, &operation, space);
+        let insert_request = BrpRequest::Insert {
+            entity,
+            components: HashMap::from([(
+                TRANSFORM_COMPONENT.to_string(),
+                serde_json::to_value(after)?,
+            )]),
+        };
+        self.send_brp_request(&insert_request).await?;
+
+        // Keep the rest of the transforms overlay config, replacing the manipulation
+        let overlay_type = DebugOverlayType::Transforms;
+        let mut config = self
+            .get_overlay_status(&overlay_type)
+            .map(|status| status.config.clone())
+            .filter(Value::is_object)
+            .unwrap_or_else(|| json!({}));
+        config["manipulation"] = json!({
+            "entity": entity,
+            "operation": operation,
+            "before": before,
+            "after": after,
+        });
+        self.set_overlay_enabled(&overlay_type, true, Some(config))
+            .await?;
+
+        info!(
+            "Manipulated transform of entity {}: {:?} -> {:?}",
+            entity, before.translation, after.translation
+        );
+        Ok((before, after))
+    }
+
+    /// Recolor every themed overlay, keeping its enabled state and other
+    /// config; returns the palette and the recolored overlay keys
+    pub async fn apply_theme(&mut self, theme: &OverlayTheme) -> Result<(ThemePalette, Vec<String>)> {
+        let palette = resolve_palette(theme)?;
+
+        // Hotkeys may have toggled overlays in the game since our last update
+        self.sync_overlay_state_from_bevy().await?;
+
+        let mut recolored = Vec::new();
+        for (overlay_type, colors) in overlay_theme_configs(&palette) {
+            let (enabled, mut config) = self
+                .get_overlay_status(&overlay_type)
+                .map(|status| (status.enabled, status.config.clone()))
+                .unwrap_or_else(|| (false, json!({})));
+            match (config.as_object_mut(), colors.as_object()) {
+                (Some(config), Some(colors)) => config.extend(colors.clone()),
+                _ => config = colors,
+            }
+            self.set_overlay_enabled(&overlay_type, enabled, Some(config))
+                .await?;
+            recolored.push(self.overlay_type_to_key(&overlay_type));
+        }
+
+        info!("Applied {:?} overlay theme to {} overlays", theme.preset, recolored.len());
+        Ok((palette, recolored))
+    }
+
+    /// Read overlay enabled flags from the game, which change with in-game
+    /// hotkeys, and adopt them; returns the game state
+    pub async fn sync_overlay_state_from_bevy(&mut self) -> Result<OverlayToggleState> {
+        let request = BrpRequest::Debug {
+            command: DebugCommand::GetOverlayState,
+            correlation_id: Uuid::new_v4().to_string(),
+            priority: Some(5),
+        };
+        let game_state = match self.send_brp_request(&request).await? {
+            BrpResult::Debug(response) => match *response {
+                DebugResponse::OverlayState(state) => state,
+                other => {
+                    return Err(Error::Brp(format!(
+                        "Unexpected response reading overlay state: {:?}",
+                        other
+                    )))
+                }
+            },
+            other => {
+                return Err(Error::Brp(format!(
+                    "Unexpected response reading overlay state: {:?}",
+                    other
+                )))
+            }
+        };
+
+        let changed = self.apply_game_overlay_state(&game_state);
+        if !changed.is_empty() {
+            info!(
+                "Overlay state changed in game (revision {}): {:?}",
+                game_state.revision, changed
+            );
+        }
+        Ok(game_state)
+    }
+
+    /// Adopt enabled flags reported by the game, keeping known configs;
+    /// returns the keys whose state changed
+    pub fn apply_game_overlay_state(&mut self, game_state: &OverlayToggleState) -> Vec<String> {
+        let mut changed = Vec::new();
+        for (key, &enabled) in &game_state.overlays {
+            let overlay = self.overlays.entry(key.clone()).or_default();
+            if overlay.enabled != enabled {
+                overlay.enabled = enabled;
+                overlay.last_updated = Instant::now();
+                changed.push(key.clone());
+            }
+        }
+        changed.sort();
+        changed
+    }
+
+    async fn send_brp_request(&self, request: &BrpRequest) -> Result<BrpResult> {
+        let mut client = self.brp_client.write().await;
+        if !client.is_connected() {
+            return Err(Error::Connection("BRP client not connected".to_string()));
+        }
+        match client.send_request(request).await? {
+            BrpResponse::Success(result) => Ok(*result),
+            BrpResponse::Error(error) => Err(Error::Brp(error.message)),
         }
     }
 
@@ -112,6 +493,83 @@ impl VisualDebugOverlayState {
         Ok(())
     }
 
+    /// Enable overlays temporarily (e.g. for a screenshot)
+    ///
+    /// Returns a snapshot for `restore_overlays`. If any overlay fails to
+    /// enable, those already changed are restored before the error is returned.
+    pub async fn apply_temporary_overlays(
+        &mut self,
+        overlays: &[(DebugOverlayType, Option<Value>)],
+    ) -> Result<OverlaySnapshot> {
+        let mut snapshot = OverlaySnapshot::default();
+
+        for (overlay_type, config) in overlays {
+            let previous = self.get_overlay_status(overlay_type).cloned();
+            if let Err(e) = self
+                .set_overlay_enabled(overlay_type, true, config.clone())
+                .await
+            {
+                if let Err(restore_error) = self.restore_overlays(snapshot).await {
+                    warn!("Failed to restore overlays: {}", restore_error);
+                }
+                return Err(e);
+            }
+            snapshot.previous.push((overlay_type.clone(), previous));
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Put overlays back the way they were before `apply_temporary_overlays`
+    pub async fn restore_overlays(&mut self, snapshot: OverlaySnapshot) -> Result<()> {
+        let mut first_error = None;
+
+        // Reverse order so an overlay listed twice ends up in its original state
+        for (overlay_type, previous) in snapshot.previous.into_iter().rev() {
+            let result = match previous {
+                Some(previous) => {
+                    self.set_overlay_enabled(&overlay_type, previous.enabled, Some(previous.config))
+                        .await
+                }
+                None => {
+                    let result = self.set_overlay_enabled(&overlay_type, false, None).await;
+                    let key = self.overlay_type_to_key(&overlay_type);
+                    self.overlays.remove(&key);
+                    result
+                }
+            };
+            if let Err(e) = result {
+                warn!("Failed to restore overlay {:?}: {}", overlay_type, e);
+                first_error.get_or_insert(e);
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Describe the enabled overlays, for annotating captures
+    pub fn active_overlay_annotations(&self) -> Vec<Value> {
+        let mut keys: Vec<&String> = self
+            .overlays
+            .iter()
+            .filter(|(_, overlay)| overlay.enabled)
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort();
+
+        keys.into_iter()
+            .map(|key| {
+                let overlay = &self.overlays[key];
+                json!({
+                    "overlay": key,
+                    "config": overlay.config,
+                    "element_count": overlay.metrics.element_count,
+                    "render_time_us": overlay.metrics.render_time_us,
+                })
+            })
+            .collect()
+    }
+
     /// Get current overlay status
     pub fn get_overlay_status(&self, overlay_type: &DebugOverlayType) -> Option<&OverlayConfig> {
         let overlay_key = self.overlay_type_to_key(overlay_type);
@@ -151,6 +609,12 @@ impl VisualDebugOverlayState {
             DebugOverlayType::SystemFlow => "system_flow".to_string(),
             DebugOverlayType::PerformanceMetrics => "performance_metrics".to_string(),
             DebugOverlayType::DebugMarkers => "debug_markers".to_string(),
+            DebugOverlayType::BoundingBoxes => "bounding_boxes".to_string(),
+            DebugOverlayType::Lights => "lights".to_string(),
+            DebugOverlayType::Frustum => "frustum".to_string(),
+            DebugOverlayType::Heatmap => "heatmap".to_string(),
+            DebugOverlayType::Labels => "labels".to_string(),
+            DebugOverlayType::UiLayout => "ui_layout".to_string(),
             DebugOverlayType::Custom(name) => format!("custom_{}", name),
         }
     }
@@ -290,6 +754,93 @@ impl DebugCommandProcessor for VisualDebugOverlayProcessor {
                     config: Some(status.config.clone()),
                 })
             }
+            DebugCommand::PlaceMarker { marker } => {
+                let mut state = self.state.write().await;
+                state.place_marker(marker)?;
+                let markers = state.sync_markers().await?;
+                Ok(DebugResponse::Markers { markers })
+            }
+            DebugCommand::RemoveMarker { id } => {
+                let mut state = self.state.write().await;
+                if !state.remove_marker(&id) {
+                    return Err(Error::DebugError(format!("Marker not found: {}", id)));
+                }
+                let markers = state.sync_markers().await?;
+                Ok(DebugResponse::Markers { markers })
+            }
+            DebugCommand::ClearMarkers => {
+                let mut state = self.state.write().await;
+                state.clear_markers();
+                let markers = state.sync_markers().await?;
+                Ok(DebugResponse::Markers { markers })
+            }
+            DebugCommand::ListMarkers => {
+                let mut state = self.state.write().await;
+                Ok(DebugResponse::Markers {
+                    markers: state.active_markers(),
+                })
+            }
+            DebugCommand::DefineOverlay { definition } => {
+                let overlay_type = DebugOverlayType::Custom(definition.name.clone());
+                let config = json!({ "definition": definition });
+
+                let mut state = self.state.write().await;
+                state
+                    .set_overlay_enabled(&overlay_type, true, Some(config.clone()))
+                    .await?;
+
+                Ok(DebugResponse::VisualDebugStatus {
+                    overlay_type,
+                    enabled: true,
+                    config: Some(config),
+                })
+            }
+            DebugCommand::RemoveCustomOverlay { name } => {
+                let overlay_type = DebugOverlayType::Custom(name.clone());
+
+                let mut state = self.state.write().await;
+                if state.get_overlay_status(&overlay_type).is_none() {
+                    return Err(Error::DebugError(format!("Custom overlay not found: {}", name)));
+                }
+                state
+                    .set_overlay_enabled(&overlay_type, false, Some(json!({ "remove": true })))
+                    .await?;
+                let key = state.overlay_type_to_key(&overlay_type);
+                state.overlays.remove(&key);
+
+                Ok(DebugResponse::VisualDebugStatus {
+                    overlay_type,
+                    enabled: false,
+                    config: None,
+                })
+            }
+            DebugCommand::ManipulateTransform {
+                entity,
+                operation,
+                space,
+            } => {
+                let mut state = self.state.write().await;
+                let (before, after) = state.manipulate_transform(entity, operation, space).await?;
+                Ok(DebugResponse::TransformManipulated {
+                    entity,
+                    before,
+                    after,
+                })
+            }
+            DebugCommand::SetTheme { theme } => {
+                let mut state = self.state.write().await;
+                let (palette, overlays) = state.apply_theme(&theme).await?;
+                Ok(DebugResponse::ThemeApplied {
+                    theme,
+                    palette,
+                    overlays,
+                })
+            }
+            DebugCommand::GetOverlayState => {
+                let mut state = self.state.write().await;
+                let game_state = state.sync_overlay_state_from_bevy().await?;
+                Ok(DebugResponse::OverlayState(game_state))
+            }
             DebugCommand::GetStatus => {
                 let state = self.state.read().await;
                 
@@ -341,6 +892,25 @@ impl DebugCommandProcessor for VisualDebugOverlayProcessor {
                 }
                 Ok(())
             }
+            DebugCommand::PlaceMarker { marker } => validate_marker(marker),
+            DebugCommand::RemoveMarker { id } => {
+                if id.is_empty() {
+                    return Err(Error::DebugError("Marker id must not be empty".to_string()));
+                }
+                Ok(())
+            }
+            DebugCommand::ClearMarkers
+            | DebugCommand::ListMarkers
+            | DebugCommand::GetOverlayState => Ok(()),
+            DebugCommand::DefineOverlay { definition } => validate_overlay_definition(definition),
+            DebugCommand::RemoveCustomOverlay { name } => {
+                if name.is_empty() {
+                    return Err(Error::DebugError("Overlay name must not be empty".to_string()));
+                }
+                Ok(())
+            }
+            DebugCommand::ManipulateTransform { operation, .. } => validate_operation(operation),
+            DebugCommand::SetTheme { theme } => validate_theme(theme),
             DebugCommand::GetStatus => Ok(()),
             _ => Err(Error::DebugError("Command not supported by visual debug overlay processor".to_string())),
         }
@@ -350,6 +920,9 @@ impl DebugCommandProcessor for VisualDebugOverlayProcessor {
         match command {
             DebugCommand::SetVisualDebug { .. } => Duration::from_millis(50), // Overlay changes can be more expensive
             DebugCommand::GetStatus => Duration::from_millis(5), // Status is quick
+            DebugCommand::ListMarkers => Duration::from_millis(1),
+            DebugCommand::ManipulateTransform { .. } => Duration::from_millis(30), // Read, write and overlay sync
+            DebugCommand::SetTheme { .. } => Duration::from_millis(100), // Syncs every themed overlay
             _ => Duration::from_millis(10),
         }
     }
@@ -357,7 +930,17 @@ impl DebugCommandProcessor for VisualDebugOverlayProcessor {
     fn supports_command(&self, command: &DebugCommand) -> bool {
         matches!(
             command,
-            DebugCommand::SetVisualDebug { .. } | DebugCommand::GetStatus
+            DebugCommand::SetVisualDebug { .. }
+                | DebugCommand::PlaceMarker { .. }
+                | DebugCommand::RemoveMarker { .. }
+                | DebugCommand::ClearMarkers
+                | DebugCommand::ListMarkers
+                | DebugCommand::DefineOverlay { .. }
+                | DebugCommand::RemoveCustomOverlay { .. }
+                | DebugCommand::ManipulateTransform { .. }
+                | DebugCommand::GetOverlayState
+                | DebugCommand::SetTheme { .. }
+                | DebugCommand::GetStatus
         )
     }
 }
@@ -447,6 +1030,33 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_apply_game_overlay_state() {
+        let processor = create_test_processor().await;
+        let state = processor.get_state();
+        let mut state_guard = state.write().await;
+        let _ = state_guard
+            .set_overlay_enabled(&DebugOverlayType::Lights, true, Some(json!({"range": 5.0})))
+            .await;
+
+        let game_state = OverlayToggleState {
+            revision: 3,
+            overlays: HashMap::from([
+                ("lights".to_string(), false),
+                ("heatmap".to_string(), true),
+                ("labels".to_string(), false),
+            ]),
+        };
+        let changed = state_guard.apply_game_overlay_state(&game_state);
+        assert_eq!(changed, vec!["heatmap".to_string(), "lights".to_string()]);
+
+        let lights = state_guard.get_overlay_status(&DebugOverlayType::Lights).unwrap();
+        assert!(!lights.enabled);
+        assert_eq!(lights.config["range"], json!(5.0));
+        assert!(state_guard.get_overlay_status(&DebugOverlayType::Heatmap).unwrap().enabled);
+        assert!(state_guard.apply_game_overlay_state(&game_state).is_empty());
+    }
+
     #[tokio::test]
     async fn test_performance_budget_tracking() {
         let processor = create_test_processor().await;
@@ -480,4 +1090,146 @@ mod tests {
             assert_eq!(key, "custom_test_overlay");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_capture_overlays() {
+        let overlays = parse_capture_overlays(&json!([
+            "BoundingBoxes",
+            {"type": "Labels", "config": {"max_labels": 20}},
+            {"type": {"Custom": "paths"}}
+        ]))
+        .unwrap();
+        assert_eq!(overlays.len(), 3);
+        assert_eq!(overlays[0], (DebugOverlayType::BoundingBoxes, None));
+        assert_eq!(overlays[1].1, Some(json!({"max_labels": 20})));
+        assert_eq!(overlays[2].0, DebugOverlayType::Custom("paths".to_string()));
+
+        assert!(parse_capture_overlays(&json!("BoundingBoxes")).is_err());
+        assert!(parse_capture_overlays(&json!(["NotAnOverlay"])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_temporary_overlays_restore_on_failure() {
+        let processor = create_test_processor().await;
+        let state = processor.get_state();
+        let mut state = state.write().await;
+
+        state.overlays.insert(
+            "lights".to_string(),
+            OverlayConfig {
+                enabled: true,
+                ..OverlayConfig::default()
+            },
+        );
+
+        // Not connected, so enabling fails and nothing is left behind
+        let result = state
+            .apply_temporary_overlays(&[(DebugOverlayType::Heatmap, None)])
+            .await;
+        assert!(result.is_err());
+        assert!(state.get_overlay_status(&DebugOverlayType::Heatmap).is_none());
+
+        let annotations = state.active_overlay_annotations();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0]["overlay"], "lights");
+    }
+
+    #[tokio::test]
+    async fn test_overlay_definition_validation() {
+        let processor = create_test_processor().await;
+        let definition: CustomOverlayDefinition = serde_json::from_value(json!({
+            "name": "enemy_paths",
+            "query": {"with": ["Enemy"], "name_contains": "Goblin"},
+            "shapes": [{"kind": "arrow", "direction": [0.0, 0.0, -1.0], "size": 2.0}]
+        }))
+        .unwrap();
+        assert_eq!(definition.max_entities, 500);
+
+        let command = DebugCommand::DefineOverlay {
+            definition: definition.clone(),
+        };
+        assert!(processor.supports_command(&command));
+        assert!(processor.validate(&command).await.is_ok());
+
+        let mut bad_name = definition.clone();
+        bad_name.name = "../paths".to_string();
+        assert!(validate_overlay_definition(&bad_name).is_err());
+
+        let mut no_shapes = definition.clone();
+        no_shapes.shapes.clear();
+        assert!(validate_overlay_definition(&no_shapes).is_err());
+
+        let mut bad_size = definition;
+        bad_size.shapes[0].size = f32::NAN;
+        assert!(validate_overlay_definition(&bad_size).is_err());
+    }
+
+    fn marker(id: &str, ttl_ms: Option<u64>) -> WorldMarker {
+        WorldMarker {
+            id: id.to_string(),
+            shape: crate::brp_messages::MarkerShape::Sphere,
+            position: [1.0, 2.0, 3.0],
+            direction: None,
+            text: Some("spawn point".to_string()),
+            color: [1.0, 0.0, 0.0, 1.0],
+            size: 0.5,
+            ttl_ms,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_marker_placement_and_expiry() {
+        let processor = create_test_processor().await;
+        let state = processor.get_state();
+        let mut state_guard = state.write().await;
+
+        state_guard.place_marker(marker("a", None)).unwrap();
+        state_guard.place_marker(marker("b", Some(10_000))).unwrap();
+        state_guard.place_marker(marker("expired", Some(1))).unwrap();
+
+        // Updating an existing id replaces it
+        let mut moved = marker("a", None);
+        moved.position = [5.0, 0.0, 0.0];
+        state_guard.place_marker(moved).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let markers = state_guard.active_markers();
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[0].id, "a");
+        assert_eq!(markers[0].position, [5.0, 0.0, 0.0]);
+        assert!(markers[1].ttl_ms.unwrap() <= 10_000);
+
+        assert!(state_guard.remove_marker("a"));
+        assert!(!state_guard.remove_marker("a"));
+        state_guard.clear_markers();
+        assert!(state_guard.active_markers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_marker_validation() {
+        let processor = create_test_processor().await;
+
+        let command = DebugCommand::PlaceMarker {
+            marker: marker("ok", Some(1000)),
+        };
+        assert!(processor.supports_command(&command));
+        assert!(processor.validate(&command).await.is_ok());
+
+        let mut bad = marker("bad", None);
+        bad.size = 0.0;
+        assert!(processor
+            .validate(&DebugCommand::PlaceMarker { marker: bad })
+            .await
+            .is_err());
+
+        let mut bad = marker("bad", Some(MAX_MARKER_TTL_MS + 1));
+        bad.position[0] = f32::NAN;
+        assert!(validate_marker(&bad).is_err());
+
+        // Markers deserialize with defaults for optional fields
+        let parsed: WorldMarker =
+            serde_json::from_value(json!({"id": "m", "position": [0.0, 1.0, 0.0]})).unwrap();
+        assert_eq!(parsed.size, 0.5);
+        assert!(validate_marker(&parsed).is_ok());
+    }
+}
@@ -0,0 +1,400 @@
+/// Adaptive Overlay Quality
+///
+/// When overlays exceed the manager's performance budget, lowers the quality
+/// of one overlay at a time, lowest priority first, until the budget is met.
+/// Lower quality draws fewer elements, hides labels and refreshes less often.
+/// Once there is headroom again, overlays are restored highest priority first.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// Rendering quality of an overlay
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityLevel {
+    #[default]
+    Full,
+    /// Half the elements, updated every other frame
+    Reduced,
+    /// A quarter of the elements, no labels, updated every fourth frame
+    Minimal,
+}
+
+impl QualityLevel {
+    /// Fraction of the configured element count that is drawn
+    pub fn element_scale(self) -> f32 {
+        match self {
+            QualityLevel::Full => 1.0,
+            QualityLevel::Reduced => 0.5,
+            QualityLevel::Minimal => 0.25,
+        }
+    }
+
+    /// Apply the element scale to a configured maximum, keeping at least one
+    pub fn scale_count(self, max: usize) -> usize {
+        if max == 0 {
+            return 0;
+        }
+        ((max as f32 * self.element_scale()).ceil() as usize).max(1)
+    }
+
+    /// Whether text labels are drawn
+    pub fn show_labels(self) -> bool {
+        self != QualityLevel::Minimal
+    }
+
+    /// Frames between updates of retained elements such as labels
+    pub fn update_interval(self) -> u32 {
+        match self {
+            QualityLevel::Full => 1,
+            QualityLevel::Reduced => 2,
+            QualityLevel::Minimal => 4,
+        }
+    }
+
+    fn lower(self) -> Option<Self> {
+        match self {
+            QualityLevel::Full => Some(QualityLevel::Reduced),
+            QualityLevel::Reduced => Some(QualityLevel::Minimal),
+            QualityLevel::Minimal => None,
+        }
+    }
+
+    fn higher(self) -> Option<Self> {
+        match self {
+            QualityLevel::Full => None,
+            QualityLevel::Reduced => Some(QualityLevel::Full),
+            QualityLevel::Minimal => Some(QualityLevel::Reduced),
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => QualityLevel::Full,
+            1 => QualityLevel::Reduced,
+            _ => QualityLevel::Minimal,
+        }
+    }
+}
+
+/// Quality level shared between an overlay and its systems
+#[derive(Debug, Clone, Default)]
+pub struct QualityHandle(Arc<AtomicU8>);
+
+impl QualityHandle {
+    pub fn get(&self) -> QualityLevel {
+        QualityLevel::from_u8(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, level: QualityLevel) {
+        self.0.store(level.to_u8(), Ordering::Relaxed);
+    }
+
+    /// Whether retained elements should be refreshed on this frame
+    pub fn should_update(&self, frame: u32) -> bool {
+        frame % self.get().update_interval() == 0
+    }
+}
+
+/// Adaptive quality settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveQualityConfig {
+    pub enabled: bool,
+    /// Overlay priority by key; higher priorities are degraded last
+    pub priorities: HashMap<String, u8>,
+    /// Priority of overlays missing from `priorities`
+    pub default_priority: u8,
+    /// Frames to wait after a change before degrading again
+    pub step_cooldown_frames: u32,
+    /// Consecutive frames with headroom before restoring an overlay
+    pub recovery_frames: u32,
+    /// Fraction of the budget that counts as headroom
+    pub recovery_ratio: f32,
+}
+
+impl Default for AdaptiveQualityConfig {
+    fn default() -> Self {
+        let priorities = [
+            ("entity_highlight", 200),
+            ("debug_markers", 180),
+            ("performance_metrics", 150),
+            ("colliders", 120),
+            ("bounding_boxes", 100),
+            ("transforms", 100),
+            ("lights", 90),
+            ("frustum", 90),
+            ("ui_layout", 80),
+            ("labels", 60),
+            ("heatmap", 50),
+            ("system_flow", 40),
+        ]
+        .into_iter()
+        .map(|(key, priority)| (key.to_string(), priority))
+        .collect();
+
+        Self {
+            enabled: true,
+            priorities,
+            default_priority: 70,
+            step_cooldown_frames: 30,
+            recovery_frames: 120,
+            recovery_ratio: 0.6,
+        }
+    }
+}
+
+/// Render cost of an overlay that supports quality levels
+#[derive(Debug, Clone)]
+pub struct OverlayLoad {
+    pub key: String,
+    pub render_time_us: u64,
+}
+
+/// Quality change made by `AdaptiveQuality::evaluate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QualityChange {
+    Degraded {
+        overlay: String,
+        level: QualityLevel,
+    },
+    Restored {
+        overlay: String,
+        level: QualityLevel,
+    },
+}
+
+/// Overlay currently running below full quality
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayDegradation {
+    pub overlay: String,
+    pub level: QualityLevel,
+    pub max_elements_percent: u32,
+    pub labels_shown: bool,
+    pub update_interval_frames: u32,
+}
+
+/// Tracks quality levels and decides when to change them
+#[derive(Debug, Clone, Default)]
+pub struct AdaptiveQuality {
+    config: AdaptiveQualityConfig,
+    levels: HashMap<String, QualityLevel>,
+    frames_since_change: u32,
+    frames_with_headroom: u32,
+}
+
+impl AdaptiveQuality {
+    pub fn new(config: AdaptiveQualityConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &AdaptiveQualityConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: AdaptiveQualityConfig) {
+        self.config = config;
+    }
+
+    pub fn level(&self, key: &str) -> QualityLevel {
+        self.levels.get(key).copied().unwrap_or_default()
+    }
+
+    fn priority(&self, key: &str) -> u8 {
+        self.config
+            .priorities
+            .get(key)
+            .copied()
+            .unwrap_or(self.config.default_priority)
+    }
+
+    /// Reset every overlay to full quality, returning the keys that changed
+    pub fn reset(&mut self) -> Vec<String> {
+        self.frames_since_change = 0;
+        self.frames_with_headroom = 0;
+        self.levels.drain().map(|(key, _)| key).collect()
+    }
+
+    /// Called once per frame with the total render time and the overlays
+    /// that can be degraded; returns at most one quality change
+    pub fn evaluate(
+        &mut self,
+        total_render_time_us: u64,
+        budget_us: u64,
+        candidates: &[OverlayLoad],
+    ) -> Option<QualityChange> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.frames_since_change = self.frames_since_change.saturating_add(1);
+
+        if total_render_time_us > budget_us {
+            self.frames_with_headroom = 0;
+            if self.frames_since_change < self.config.step_cooldown_frames {
+                return None;
+            }
+
+            // Lowest priority first; the most expensive breaks ties
+            let (key, level) = candidates
+                .iter()
+                .filter_map(|load| Some((load, self.level(&load.key).lower()?)))
+                .min_by_key(|(load, _)| {
+                    (
+                        self.priority(&load.key),
+                        std::cmp::Reverse(load.render_time_us),
+                    )
+                })
+                .map(|(load, level)| (load.key.clone(), level))?;
+
+            self.levels.insert(key.clone(), level);
+            self.frames_since_change = 0;
+            return Some(QualityChange::Degraded {
+                overlay: key,
+                level,
+            });
+        }
+
+        if (total_render_time_us as f32) > budget_us as f32 * self.config.recovery_ratio {
+            self.frames_with_headroom = 0;
+            return None;
+        }
+
+        self.frames_with_headroom += 1;
+        if self.frames_with_headroom < self.config.recovery_frames {
+            return None;
+        }
+
+        // Highest priority first
+        let (key, level) = self
+            .levels
+            .iter()
+            .filter_map(|(key, level)| Some((key, level.higher()?)))
+            .max_by_key(|(key, _)| self.priority(key))
+            .map(|(key, level)| (key.clone(), level))?;
+
+        if level == QualityLevel::Full {
+            self.levels.remove(&key);
+        } else {
+            self.levels.insert(key.clone(), level);
+        }
+        self.frames_with_headroom = 0;
+        self.frames_since_change = 0;
+        Some(QualityChange::Restored {
+            overlay: key,
+            level,
+        })
+    }
+
+    /// Overlays running below full quality
+    pub fn report(&self) -> Vec<OverlayDegradation> {
+        let mut report: Vec<OverlayDegradation> = self
+            .levels
+            .iter()
+            .map(|(key, level)| OverlayDegradation {
+                overlay: key.clone(),
+                level: *level,
+                max_elements_percent: (level.element_scale() * 100.0) as u32,
+                labels_shown: level.show_labels(),
+                update_interval_frames: level.update_interval(),
+            })
+            .collect();
+        report.sort_by(|a, b| a.overlay.cmp(&b.overlay));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(key: &str, render_time_us: u64) -> OverlayLoad {
+        OverlayLoad {
+            key: key.to_string(),
+            render_time_us,
+        }
+    }
+
+    #[test]
+    fn test_quality_level_scaling() {
+        assert_eq!(QualityLevel::Full.scale_count(500), 500);
+        assert_eq!(QualityLevel::Reduced.scale_count(5), 3);
+        assert_eq!(QualityLevel::Minimal.scale_count(1), 1);
+        assert_eq!(QualityLevel::Minimal.scale_count(0), 0);
+        assert!(!QualityLevel::Minimal.show_labels());
+
+        let handle = QualityHandle::default();
+        assert_eq!(handle.get(), QualityLevel::Full);
+        handle.set(QualityLevel::Reduced);
+        assert!(handle.should_update(2));
+        assert!(!handle.should_update(3));
+    }
+
+    #[test]
+    fn test_degrades_lowest_priority_first() {
+        let mut adaptive = AdaptiveQuality::new(AdaptiveQualityConfig {
+            step_cooldown_frames: 1,
+            ..AdaptiveQualityConfig::default()
+        });
+        let candidates = [load("bounding_boxes", 900), load("heatmap", 100)];
+
+        assert_eq!(
+            adaptive.evaluate(2500, 2000, &candidates),
+            Some(QualityChange::Degraded {
+                overlay: "heatmap".to_string(),
+                level: QualityLevel::Reduced
+            })
+        );
+        adaptive.evaluate(2500, 2000, &candidates);
+        assert_eq!(adaptive.level("heatmap"), QualityLevel::Minimal);
+
+        // Heatmap is exhausted, so bounding boxes are next
+        adaptive.evaluate(2500, 2000, &candidates);
+        assert_eq!(adaptive.level("bounding_boxes"), QualityLevel::Reduced);
+
+        let report = adaptive.report();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[1].overlay, "heatmap");
+        assert!(!report[1].labels_shown);
+    }
+
+    #[test]
+    fn test_restores_after_headroom() {
+        let mut adaptive = AdaptiveQuality::new(AdaptiveQualityConfig {
+            step_cooldown_frames: 0,
+            recovery_frames: 3,
+            ..AdaptiveQualityConfig::default()
+        });
+        let candidates = [load("labels", 500), load("lights", 500)];
+        adaptive.evaluate(3000, 2000, &candidates);
+        adaptive.evaluate(3000, 2000, &candidates);
+        assert_eq!(adaptive.level("labels"), QualityLevel::Minimal);
+
+        // Still close to the budget: no recovery
+        for _ in 0..5 {
+            assert_eq!(adaptive.evaluate(1900, 2000, &candidates), None);
+        }
+
+        assert_eq!(adaptive.evaluate(500, 2000, &candidates), None);
+        assert_eq!(adaptive.evaluate(500, 2000, &candidates), None);
+        assert_eq!(
+            adaptive.evaluate(500, 2000, &candidates),
+            Some(QualityChange::Restored {
+                overlay: "labels".to_string(),
+                level: QualityLevel::Reduced
+            })
+        );
+
+        assert_eq!(adaptive.reset(), vec!["labels".to_string()]);
+        assert!(adaptive.report().is_empty());
+    }
+}
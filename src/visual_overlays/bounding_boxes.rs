@@ -0,0 +1,425 @@
+/// Bounding Box Overlay Implementation
+///
+/// Draws the axis-aligned bounding box (`Aabb`) of queried entities as gizmo
+/// cuboids. Boxes are colored by the first matching filter rule, so e.g. all
+/// entities with a `Player` component can be drawn green and everything else grey.
+
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::DebugOverlayType;
+use bevy::ecs::component::Components;
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on boxes drawn per frame regardless of configuration
+const MAX_BOXES_LIMIT: usize = 5000;
+
+/// Rule assigning a color to entities that match a filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBoxColorRule {
+    /// Component type the entity must have (full path or short name)
+    pub component: Option<String>,
+    /// Substring the entity's `Name` must contain
+    pub name_contains: Option<String>,
+    /// Color used for matching entities
+    pub color: Color,
+}
+
+impl BoundingBoxColorRule {
+    /// Whether an entity with the given components and name matches this rule
+    pub fn matches(&self, component_names: &[&str], name: Option<&str>) -> bool {
+        let component_ok = self.component.as_deref().map_or(true, |wanted| {
+            component_names
+                .iter()
+                .any(|full| *full == wanted || full.rsplit("::").next() == Some(wanted))
+        });
+        let name_ok = self
+            .name_contains
+            .as_deref()
+            .map_or(true, |wanted| name.is_some_and(|n| n.contains(wanted)));
+        component_ok && name_ok
+    }
+
+    fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let component = value
+            .get("component")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        let name_contains = value
+            .get("name_contains")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        if component.is_none() && name_contains.is_none() {
+            return Err("Color rule needs 'component' or 'name_contains'".to_string());
+        }
+        let color = parse_color(value.get("color").ok_or("Color rule is missing 'color'")?)?;
+        Ok(Self {
+            component,
+            name_contains,
+            color,
+        })
+    }
+}
+
+/// Configuration for the bounding box overlay
+#[derive(Debug, Clone)]
+pub struct BoundingBoxConfig {
+    /// Color for entities that match no rule
+    pub default_color: Color,
+    /// Color rules, first match wins
+    pub color_rules: Vec<BoundingBoxColorRule>,
+    /// Only draw these entities (`Entity::to_bits`); empty draws all
+    pub entity_ids: HashSet<u64>,
+    /// Only draw entities matching at least one color rule
+    pub only_matching: bool,
+    /// Maximum number of boxes drawn per frame
+    pub max_boxes: usize,
+    /// Draw a small marker at each box center
+    pub show_centers: bool,
+}
+
+impl Default for BoundingBoxConfig {
+    fn default() -> Self {
+        Self {
+            default_color: Color::srgb(0.0, 1.0, 1.0), // Cyan
+            color_rules: Vec::new(),
+            entity_ids: HashSet::new(),
+            only_matching: false,
+            max_boxes: 500,
+            show_centers: false,
+        }
+    }
+}
+
+impl BoundingBoxConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(color) = config.get("default_color") {
+            self.default_color = parse_color(color)?;
+        }
+
+        if let Some(rules) = config.get("color_rules").and_then(|v| v.as_array()) {
+            self.color_rules = rules
+                .iter()
+                .map(BoundingBoxColorRule::from_json)
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        if let Some(ids) = config.get("entity_ids").and_then(|v| v.as_array()) {
+            self.entity_ids = ids
+                .iter()
+                .map(|id| {
+                    id.as_u64()
+                        .ok_or_else(|| format!("Invalid entity id: {}", id))
+                })
+                .collect::<Result<HashSet<_>, _>>()?;
+        }
+
+        if let Some(only_matching) = config.get("only_matching").and_then(|v| v.as_bool()) {
+            self.only_matching = only_matching;
+        }
+
+        if let Some(max) = config.get("max_boxes").and_then(|v| v.as_u64()) {
+            self.max_boxes = (max as usize).min(MAX_BOXES_LIMIT);
+        }
+
+        if let Some(show_centers) = config.get("show_centers").and_then(|v| v.as_bool()) {
+            self.show_centers = show_centers;
+        }
+
+        Ok(())
+    }
+
+    /// Color for an entity, or `None` if it should not be drawn
+    pub fn color_for(&self, component_names: &[&str], name: Option<&str>) -> Option<Color> {
+        match self
+            .color_rules
+            .iter()
+            .find(|rule| rule.matches(component_names, name))
+        {
+            Some(rule) => Some(rule.color),
+            None if self.only_matching => None,
+            None => Some(self.default_color),
+        }
+    }
+}
+
+/// Parse an `[r, g, b]` or `[r, g, b, a]` color array
+pub(super) fn parse_color(value: &serde_json::Value) -> Result<Color, String> {
+    let color_array = value.as_array().ok_or("Color must be an array")?;
+    if color_array.len() < 3 {
+        return Err("Color needs at least 3 components".to_string());
+    }
+    let r = color_array[0].as_f64().ok_or("Invalid red component")? as f32;
+    let g = color_array[1].as_f64().ok_or("Invalid green component")? as f32;
+    let b = color_array[2].as_f64().ok_or("Invalid blue component")? as f32;
+    let a = color_array.get(3).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32;
+    Ok(Color::srgba(r, g, b, a))
+}
+
+/// State shared between the overlay and its render system
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BoundingBoxSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<BoundingBoxConfig>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Bounding Box Overlay implementation
+#[derive(Debug)]
+pub struct BoundingBoxesOverlay {
+    settings: BoundingBoxSettings,
+    metrics: OverlayMetrics,
+}
+
+impl BoundingBoxesOverlay {
+    pub fn new() -> Self {
+        Self {
+            settings: BoundingBoxSettings::default(),
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> BoundingBoxConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for BoundingBoxesOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisualOverlay for BoundingBoxesOverlay {
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, render_bounding_boxes);
+
+        info!("Bounding box overlay initialized with Gizmo rendering");
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Bounding box config lock poisoned".to_string())?;
+        // Apply to a copy so an invalid update leaves the config untouched
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("Bounding box overlay config updated: {:?}", *current);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 64, // Estimated Gizmo overhead per box
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::BoundingBoxes
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// System to draw bounding boxes using Gizmos
+fn render_bounding_boxes(
+    mut gizmos: Gizmos,
+    settings: Res<BoundingBoxSettings>,
+    components: &Components,
+    query: Query<(Entity, EntityRef, &Aabb, &GlobalTransform, Option<&Name>)>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let needs_components = config
+        .color_rules
+        .iter()
+        .any(|rule| rule.component.is_some());
+    let quality = settings.quality.get();
+    let max_boxes = quality.scale_count(config.max_boxes);
+    let mut rendered = 0;
+
+    for (entity, entity_ref, aabb, global_transform, name) in &query {
+        if rendered >= max_boxes {
+            break;
+        }
+        if !config.entity_ids.is_empty() && !config.entity_ids.contains(&entity.to_bits()) {
+            continue;
+        }
+
+        let component_names: Vec<&str> = if needs_components {
+            entity_ref
+                .archetype()
+                .components()
+                .filter_map(|id| components.get_info(id))
+                .map(|info| info.name())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let Some(color) = config.color_for(&component_names, name.map(|n| n.as_str())) else {
+            continue;
+        };
+
+        let (scale, rotation, _) = global_transform.to_scale_rotation_translation();
+        let center = global_transform.transform_point(Vec3::from(aabb.center));
+        gizmos.cuboid(
+            Transform {
+                translation: center,
+                rotation,
+                scale: scale * Vec3::from(aabb.half_extents) * 2.0,
+            },
+            color,
+        );
+
+        if config.show_centers && quality == QualityLevel::Full {
+            gizmos.sphere(center, 0.05, color);
+        }
+
+        rendered += 1;
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+    if render_time > 1000 {
+        warn!(
+            "Bounding box rendering took {}μs for {} entities",
+            render_time, rendered
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounding_box_config_json_update() {
+        let mut config = BoundingBoxConfig::default();
+
+        let json_config = serde_json::json!({
+            "default_color": [0.5, 0.5, 0.5],
+            "color_rules": [
+                {"component": "Player", "color": [0.0, 1.0, 0.0]},
+                {"name_contains": "Enemy", "color": [1.0, 0.0, 0.0, 0.5]}
+            ],
+            "entity_ids": [1, 2, 3],
+            "max_boxes": 100000,
+            "show_centers": true
+        });
+
+        assert!(config.update_from_json(&json_config).is_ok());
+        assert_eq!(config.default_color, Color::srgba(0.5, 0.5, 0.5, 1.0));
+        assert_eq!(config.color_rules.len(), 2);
+        assert_eq!(config.entity_ids.len(), 3);
+        assert_eq!(config.max_boxes, MAX_BOXES_LIMIT);
+        assert!(config.show_centers);
+
+        let invalid = serde_json::json!({"color_rules": [{"color": [1.0, 0.0, 0.0]}]});
+        assert!(config.update_from_json(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_color_by_filter() {
+        let mut config = BoundingBoxConfig::default();
+        config
+            .update_from_json(&serde_json::json!({
+                "color_rules": [
+                    {"component": "game::Player", "color": [0.0, 1.0, 0.0]},
+                    {"component": "Collider", "name_contains": "Wall", "color": [1.0, 0.0, 0.0]}
+                ]
+            }))
+            .unwrap();
+
+        let player = ["bevy_transform::components::Transform", "game::Player"];
+        assert_eq!(
+            config.color_for(&player, None),
+            Some(Color::srgba(0.0, 1.0, 0.0, 1.0))
+        );
+
+        let wall = ["physics::Collider"];
+        assert_eq!(
+            config.color_for(&wall, Some("North Wall")),
+            Some(Color::srgba(1.0, 0.0, 0.0, 1.0))
+        );
+        assert_eq!(
+            config.color_for(&wall, Some("Floor")),
+            Some(config.default_color)
+        );
+
+        config.only_matching = true;
+        assert_eq!(config.color_for(&wall, Some("Floor")), None);
+    }
+
+    #[test]
+    fn test_overlay_enable_and_config() {
+        let mut overlay = BoundingBoxesOverlay::new();
+        assert!(!overlay.is_enabled());
+
+        overlay.set_enabled(true);
+        assert!(overlay.is_enabled());
+        assert_eq!(overlay.overlay_type(), DebugOverlayType::BoundingBoxes);
+
+        // Invalid update leaves the previous config intact
+        assert!(overlay
+            .update_config(&serde_json::json!({"max_boxes": 10, "default_color": "red"}))
+            .is_err());
+        assert_eq!(overlay.config().max_boxes, 500);
+
+        overlay
+            .update_config(&serde_json::json!({"max_boxes": 10}))
+            .unwrap();
+        assert_eq!(overlay.config().max_boxes, 10);
+    }
+}
@@ -1,26 +1,181 @@
 /// Collider Visualization Overlay Implementation
-/// 
-/// Provides visual representation of physics colliders for debugging
-/// collision detection, physics shapes, and spatial relationships.
+///
+/// Draws the actual shapes of physics colliders (cuboids, balls, capsules,
+/// cylinders, meshes and compounds) at their real offsets, with sensors and
+/// solid colliders in different colors. Shapes come from the engine-agnostic
+/// `DebugCollider` component, which is kept in sync from avian3d or
+/// bevy_rapier3d colliders when the `physics-avian` / `physics-rapier` feature
+/// is enabled and can be inserted directly for any other physics engine.
 
-use super::{OverlayMetrics, VisualOverlay};
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
 use crate::brp_messages::DebugOverlayType;
 use bevy::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 
-/// Colliders Overlay implementation (stub for now)
+/// Upper bound on colliders drawn per frame
+const MAX_COLLIDERS_LIMIT: usize = 5000;
+
+/// Upper bound on mesh triangles drawn per frame
+const MAX_TRIANGLES_LIMIT: usize = 50_000;
+
+/// Collider shape in the collider's local space
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColliderGeometry {
+    Cuboid {
+        half_extents: Vec3,
+    },
+    Ball {
+        radius: f32,
+    },
+    /// Capsule around the segment from `a` to `b`
+    Capsule {
+        a: Vec3,
+        b: Vec3,
+        radius: f32,
+    },
+    /// Cylinder along the local Y axis
+    Cylinder {
+        half_height: f32,
+        radius: f32,
+    },
+    /// Triangle mesh (also used for convex hulls)
+    Mesh {
+        vertices: Vec<Vec3>,
+        indices: Vec<[u32; 3]>,
+    },
+    /// Child shapes with their transforms relative to the collider
+    Compound(Vec<(Transform, ColliderGeometry)>),
+    /// Shape the overlay cannot draw; holds the shape type for diagnostics
+    Unsupported(String),
+}
+
+impl ColliderGeometry {
+    /// Leaf shapes with their transforms, with compounds expanded
+    pub fn flatten(&self, transform: Transform) -> Vec<(Transform, &ColliderGeometry)> {
+        match self {
+            ColliderGeometry::Compound(children) => children
+                .iter()
+                .flat_map(|(local, child)| child.flatten(transform * *local))
+                .collect(),
+            leaf => vec![(transform, leaf)],
+        }
+    }
+}
+
+/// Collider shape to draw for an entity
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct DebugCollider {
+    pub geometry: ColliderGeometry,
+    /// Collider offset from the entity's transform
+    pub offset: Transform,
+    /// Sensors detect overlaps without a physical response
+    pub sensor: bool,
+}
+
+impl DebugCollider {
+    pub fn new(geometry: ColliderGeometry) -> Self {
+        Self {
+            geometry,
+            offset: Transform::IDENTITY,
+            sensor: false,
+        }
+    }
+}
+
+/// Configuration for the colliders overlay
+#[derive(Debug, Clone)]
+pub struct CollidersConfig {
+    pub solid_color: Color,
+    pub sensor_color: Color,
+    pub show_solids: bool,
+    pub show_sensors: bool,
+    /// Maximum number of colliders drawn per frame
+    pub max_colliders: usize,
+    /// Maximum mesh triangles drawn per frame, across all mesh colliders
+    pub max_triangles: usize,
+}
+
+impl Default for CollidersConfig {
+    fn default() -> Self {
+        Self {
+            solid_color: Color::srgb(0.2, 1.0, 0.2),
+            sensor_color: Color::srgba(1.0, 0.8, 0.0, 0.8),
+            show_solids: true,
+            show_sensors: true,
+            max_colliders: 1000,
+            max_triangles: 10_000,
+        }
+    }
+}
+
+impl CollidersConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(color) = config.get("solid_color") {
+            self.solid_color = super::bounding_boxes::parse_color(color)?;
+        }
+        if let Some(color) = config.get("sensor_color") {
+            self.sensor_color = super::bounding_boxes::parse_color(color)?;
+        }
+        if let Some(show) = config.get("show_solids").and_then(|v| v.as_bool()) {
+            self.show_solids = show;
+        }
+        if let Some(show) = config.get("show_sensors").and_then(|v| v.as_bool()) {
+            self.show_sensors = show;
+        }
+        if let Some(max) = config.get("max_colliders").and_then(|v| v.as_u64()) {
+            self.max_colliders = (max as usize).min(MAX_COLLIDERS_LIMIT);
+        }
+        if let Some(max) = config.get("max_triangles").and_then(|v| v.as_u64()) {
+            self.max_triangles = (max as usize).min(MAX_TRIANGLES_LIMIT);
+        }
+        Ok(())
+    }
+
+    /// Color for a collider, or `None` if its kind is hidden
+    pub fn color_for(&self, sensor: bool) -> Option<Color> {
+        match sensor {
+            true if self.show_sensors => Some(self.sensor_color),
+            false if self.show_solids => Some(self.solid_color),
+            _ => None,
+        }
+    }
+}
+
+/// State shared between the overlay and its systems
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CollidersSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<CollidersConfig>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Colliders Overlay implementation
 #[derive(Debug)]
 pub struct CollidersOverlay {
-    enabled: bool,
+    settings: CollidersSettings,
     metrics: OverlayMetrics,
 }
 
 impl CollidersOverlay {
     pub fn new() -> Self {
         Self {
-            enabled: false,
+            settings: CollidersSettings::default(),
             metrics: OverlayMetrics::default(),
         }
     }
+
+    /// Current configuration
+    pub fn config(&self) -> CollidersConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl Default for CollidersOverlay {
@@ -30,31 +185,397 @@ impl Default for CollidersOverlay {
 }
 
 impl VisualOverlay for CollidersOverlay {
-    fn initialize(&mut self, _app: &mut App) {
-        info!("Colliders overlay initialized (placeholder)");
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, render_colliders);
+
+        #[cfg(feature = "physics-avian")]
+        app.add_systems(Update, avian::sync_avian_colliders.before(render_colliders));
+        #[cfg(feature = "physics-rapier")]
+        app.add_systems(
+            Update,
+            rapier::sync_rapier_colliders.before(render_colliders),
+        );
+
+        info!("Colliders overlay initialized with Gizmo rendering");
     }
-    
-    fn update_config(&mut self, _config: &serde_json::Value) -> Result<(), String> {
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Colliders config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("Colliders overlay config updated: {:?}", *current);
         Ok(())
     }
-    
+
     fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
     }
-    
+
     fn is_enabled(&self) -> bool {
-        self.enabled
+        self.settings.enabled.load(Ordering::Relaxed)
     }
-    
+
     fn get_metrics(&self) -> OverlayMetrics {
-        self.metrics.clone()
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 128, // Estimated Gizmo overhead per collider
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
     }
-    
+
     fn overlay_type(&self) -> DebugOverlayType {
         DebugOverlayType::Colliders
     }
-    
+
     fn cleanup(&mut self) {
-        // Placeholder
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
     }
-}
\ No newline at end of file
+}
+
+/// Draw one leaf shape; returns the number of mesh triangles drawn
+fn draw_geometry(
+    gizmos: &mut Gizmos,
+    transform: Transform,
+    geometry: &ColliderGeometry,
+    color: Color,
+    triangle_budget: usize,
+) -> usize {
+    match geometry {
+        ColliderGeometry::Cuboid { half_extents } => {
+            gizmos.cuboid(
+                transform * Transform::from_scale(*half_extents * 2.0),
+                color,
+            );
+        }
+        ColliderGeometry::Ball { radius } => {
+            gizmos.sphere(
+                Isometry3d::new(transform.translation, transform.rotation),
+                radius * transform.scale.max_element(),
+                color,
+            );
+        }
+        ColliderGeometry::Capsule { a, b, radius } => {
+            let a = transform.transform_point(*a);
+            let b = transform.transform_point(*b);
+            let axis = b - a;
+            let rotation = Quat::from_rotation_arc(Vec3::Y, axis.normalize_or(Vec3::Y));
+            gizmos.primitive_3d(
+                &Capsule3d::new(radius * transform.scale.max_element(), axis.length()),
+                Isometry3d::new((a + b) * 0.5, rotation),
+                color,
+            );
+        }
+        ColliderGeometry::Cylinder {
+            half_height,
+            radius,
+        } => {
+            gizmos.primitive_3d(
+                &Cylinder::new(
+                    radius * transform.scale.x.max(transform.scale.z),
+                    half_height * 2.0 * transform.scale.y,
+                ),
+                Isometry3d::new(transform.translation, transform.rotation),
+                color,
+            );
+        }
+        ColliderGeometry::Mesh { vertices, indices } => {
+            let mut drawn = 0;
+            for triangle in indices.iter().take(triangle_budget) {
+                let [Some(p0), Some(p1), Some(p2)] =
+                    triangle.map(|i| vertices.get(i as usize).copied())
+                else {
+                    continue;
+                };
+                gizmos.linestrip(
+                    [p0, p1, p2, p0].map(|p| transform.transform_point(p)),
+                    color,
+                );
+                drawn += 1;
+            }
+            return drawn;
+        }
+        ColliderGeometry::Compound(_) => {
+            // Expanded by `flatten` before drawing
+        }
+        ColliderGeometry::Unsupported(_) => {
+            gizmos.sphere(
+                Isometry3d::from_translation(transform.translation),
+                0.1,
+                color,
+            );
+        }
+    }
+    0
+}
+
+/// System to draw collider shapes using Gizmos
+fn render_colliders(
+    mut gizmos: Gizmos,
+    settings: Res<CollidersSettings>,
+    query: Query<(&DebugCollider, &GlobalTransform)>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let quality = settings.quality.get();
+    let max_colliders = quality.scale_count(config.max_colliders);
+    let mut triangle_budget = quality.scale_count(config.max_triangles);
+    let mut rendered = 0;
+
+    for (collider, global_transform) in &query {
+        if rendered >= max_colliders {
+            break;
+        }
+        let Some(color) = config.color_for(collider.sensor) else {
+            continue;
+        };
+
+        let transform = global_transform.compute_transform() * collider.offset;
+        for (shape_transform, shape) in collider.geometry.flatten(transform) {
+            triangle_budget -=
+                draw_geometry(&mut gizmos, shape_transform, shape, color, triangle_budget);
+        }
+        rendered += 1;
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+    if render_time > 1000 {
+        warn!(
+            "Collider rendering took {}μs for {} colliders",
+            render_time, rendered
+        );
+    }
+}
+
+/// Convert parry shapes (used by both avian3d and rapier) into `ColliderGeometry`.
+/// Expects a `parry` module in scope at the call site.
+#[cfg(any(feature = "physics-avian", feature = "physics-rapier"))]
+macro_rules! parry_geometry {
+    () => {
+        fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+            Vec3::new(x, y, z)
+        }
+
+        fn geometry_from_shape(shape: &dyn parry::shape::Shape) -> ColliderGeometry {
+            use parry::shape::TypedShape;
+
+            match shape.as_typed_shape() {
+                TypedShape::Ball(ball) => ColliderGeometry::Ball {
+                    radius: ball.radius,
+                },
+                TypedShape::Cuboid(cuboid) => ColliderGeometry::Cuboid {
+                    half_extents: vec3(
+                        cuboid.half_extents.x,
+                        cuboid.half_extents.y,
+                        cuboid.half_extents.z,
+                    ),
+                },
+                TypedShape::Capsule(capsule) => {
+                    let (a, b) = (capsule.segment.a, capsule.segment.b);
+                    ColliderGeometry::Capsule {
+                        a: vec3(a.x, a.y, a.z),
+                        b: vec3(b.x, b.y, b.z),
+                        radius: capsule.radius,
+                    }
+                }
+                TypedShape::Cylinder(cylinder) => ColliderGeometry::Cylinder {
+                    half_height: cylinder.half_height,
+                    radius: cylinder.radius,
+                },
+                TypedShape::TriMesh(mesh) => ColliderGeometry::Mesh {
+                    vertices: mesh
+                        .vertices()
+                        .iter()
+                        .map(|p| vec3(p.x, p.y, p.z))
+                        .collect(),
+                    indices: mesh.indices().to_vec(),
+                },
+                TypedShape::ConvexPolyhedron(polyhedron) => {
+                    let (vertices, indices) = polyhedron.to_trimesh();
+                    ColliderGeometry::Mesh {
+                        vertices: vertices.iter().map(|p| vec3(p.x, p.y, p.z)).collect(),
+                        indices,
+                    }
+                }
+                TypedShape::Compound(compound) => ColliderGeometry::Compound(
+                    compound
+                        .shapes()
+                        .iter()
+                        .map(|(isometry, child)| {
+                            let t = isometry.translation.vector;
+                            let r = isometry.rotation;
+                            let transform = Transform::from_translation(vec3(t.x, t.y, t.z))
+                                .with_rotation(Quat::from_xyzw(r.i, r.j, r.k, r.w));
+                            (transform, geometry_from_shape(&**child))
+                        })
+                        .collect(),
+                ),
+                _ => ColliderGeometry::Unsupported(format!("{:?}", shape.shape_type())),
+            }
+        }
+    };
+}
+
+/// Keeps `DebugCollider` in sync with avian3d colliders
+#[cfg(feature = "physics-avian")]
+mod avian {
+    use super::{ColliderGeometry, DebugCollider};
+    use avian3d::parry;
+    use avian3d::prelude::{Collider, Sensor};
+    use bevy::prelude::*;
+
+    parry_geometry!();
+
+    pub(super) fn sync_avian_colliders(
+        mut commands: Commands,
+        colliders: Query<(Entity, Ref<Collider>, Has<Sensor>, Option<&DebugCollider>)>,
+        mut removed: RemovedComponents<Collider>,
+    ) {
+        for (entity, collider, sensor, existing) in &colliders {
+            let stale = existing.is_none_or(|existing| existing.sensor != sensor);
+            if !collider.is_changed() && !stale {
+                continue;
+            }
+            commands.entity(entity).insert(DebugCollider {
+                geometry: geometry_from_shape(&**collider.shape_scaled()),
+                offset: Transform::IDENTITY,
+                sensor,
+            });
+        }
+        for entity in removed.read() {
+            if let Ok(mut entity) = commands.get_entity(entity) {
+                entity.remove::<DebugCollider>();
+            }
+        }
+    }
+}
+
+/// Keeps `DebugCollider` in sync with bevy_rapier3d colliders
+#[cfg(feature = "physics-rapier")]
+mod rapier {
+    use super::{ColliderGeometry, DebugCollider};
+    use bevy::prelude::*;
+    use bevy_rapier3d::parry;
+    use bevy_rapier3d::prelude::{Collider, Sensor};
+
+    parry_geometry!();
+
+    pub(super) fn sync_rapier_colliders(
+        mut commands: Commands,
+        colliders: Query<(Entity, Ref<Collider>, Has<Sensor>, Option<&DebugCollider>)>,
+        mut removed: RemovedComponents<Collider>,
+    ) {
+        for (entity, collider, sensor, existing) in &colliders {
+            let stale = existing.is_none_or(|existing| existing.sensor != sensor);
+            if !collider.is_changed() && !stale {
+                continue;
+            }
+            commands.entity(entity).insert(DebugCollider {
+                geometry: geometry_from_shape(&*collider.raw),
+                offset: Transform::IDENTITY,
+                sensor,
+            });
+        }
+        for entity in removed.read() {
+            if let Ok(mut entity) = commands.get_entity(entity) {
+                entity.remove::<DebugCollider>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colliders_config_json_update() {
+        let mut config = CollidersConfig::default();
+        config
+            .update_from_json(&serde_json::json!({
+                "sensor_color": [1.0, 0.0, 0.0],
+                "show_solids": false,
+                "max_colliders": 100000
+            }))
+            .unwrap();
+
+        assert_eq!(config.max_colliders, MAX_COLLIDERS_LIMIT);
+        assert_eq!(config.color_for(false), None);
+        assert_eq!(config.color_for(true), Some(Color::srgb(1.0, 0.0, 0.0)));
+        assert!(config
+            .update_from_json(&serde_json::json!({"solid_color": [1.0]}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_compound_flatten_applies_offsets() {
+        let geometry = ColliderGeometry::Compound(vec![
+            (
+                Transform::from_xyz(1.0, 0.0, 0.0),
+                ColliderGeometry::Ball { radius: 0.5 },
+            ),
+            (
+                Transform::from_xyz(0.0, 2.0, 0.0),
+                ColliderGeometry::Compound(vec![(
+                    Transform::from_xyz(0.0, 0.0, 3.0),
+                    ColliderGeometry::Cuboid {
+                        half_extents: Vec3::ONE,
+                    },
+                )]),
+            ),
+        ]);
+
+        let leaves = geometry.flatten(Transform::from_xyz(10.0, 0.0, 0.0));
+        assert_eq!(leaves.len(), 2);
+        assert_eq!(leaves[0].0.translation, Vec3::new(11.0, 0.0, 0.0));
+        assert_eq!(leaves[1].0.translation, Vec3::new(10.0, 2.0, 3.0));
+        assert!(matches!(leaves[1].1, ColliderGeometry::Cuboid { .. }));
+    }
+
+    #[test]
+    fn test_overlay_updates_shared_config() {
+        let mut overlay = CollidersOverlay::new();
+        assert_eq!(overlay.overlay_type(), DebugOverlayType::Colliders);
+        assert!(overlay.supports_quality());
+
+        overlay
+            .update_config(&serde_json::json!({"show_sensors": false}))
+            .unwrap();
+        assert!(!overlay.config().show_sensors);
+        assert!(overlay
+            .update_config(&serde_json::json!({"sensor_color": "yellow"}))
+            .is_err());
+        assert!(!overlay.config().show_sensors);
+    }
+}
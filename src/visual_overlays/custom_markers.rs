@@ -1,26 +1,115 @@
 /// Custom Debug Markers and Labels Implementation
-/// 
-/// Provides customizable debug markers, labels, and annotations that can be
-/// placed in 3D space or as UI overlays for debugging purposes.
+///
+/// Draws named world-space markers (spheres, arrows and text) placed through
+/// the `PlaceMarker` / `RemoveMarker` / `ClearMarkers` debug commands. The
+/// server sends the full marker set as overlay config; markers with a TTL
+/// expire locally so they disappear even if no further commands arrive.
 
-use super::{OverlayMetrics, VisualOverlay};
-use crate::brp_messages::DebugOverlayType;
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::{DebugOverlayType, MarkerShape, WorldMarker};
 use bevy::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
-/// Custom Markers Overlay implementation (stub for now)
+/// A marker and when it expires
+#[derive(Debug, Clone)]
+struct PlacedMarker {
+    marker: WorldMarker,
+    expires_at: Option<Instant>,
+}
+
+/// Parse the `markers` config, either an id-keyed object or an array
+pub fn parse_markers(config: &serde_json::Value) -> Result<Vec<WorldMarker>, String> {
+    let Some(markers) = config.get("markers") else {
+        return Ok(Vec::new());
+    };
+    let values: Vec<&serde_json::Value> = match markers {
+        serde_json::Value::Object(map) => map.values().collect(),
+        serde_json::Value::Array(list) => list.iter().collect(),
+        serde_json::Value::Null => Vec::new(),
+        _ => return Err("'markers' must be an object or array".to_string()),
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            serde_json::from_value(value.clone()).map_err(|e| format!("Invalid marker: {}", e))
+        })
+        .collect()
+}
+
+fn marker_color(color: [f32; 4]) -> Color {
+    Color::srgba(color[0], color[1], color[2], color[3])
+}
+
+/// Screen-space label attached to a marker
+#[derive(Component, Debug)]
+struct MarkerLabel {
+    id: String,
+}
+
+/// State shared between the overlay and its systems
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MarkerSettings {
+    enabled: Arc<AtomicBool>,
+    markers: Arc<RwLock<HashMap<String, PlacedMarker>>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Custom Markers Overlay implementation
 #[derive(Debug)]
 pub struct CustomMarkersOverlay {
-    enabled: bool,
+    settings: MarkerSettings,
     metrics: OverlayMetrics,
 }
 
 impl CustomMarkersOverlay {
     pub fn new() -> Self {
         Self {
-            enabled: false,
+            settings: MarkerSettings::default(),
             metrics: OverlayMetrics::default(),
         }
     }
+
+    /// Replace the marker set
+    pub fn set_markers(&mut self, markers: Vec<WorldMarker>) -> Result<(), String> {
+        let now = Instant::now();
+        let placed = markers
+            .into_iter()
+            .map(|marker| {
+                let expires_at = marker.ttl_ms.map(|ttl| now + Duration::from_millis(ttl));
+                (marker.id.clone(), PlacedMarker { marker, expires_at })
+            })
+            .collect();
+        *self
+            .settings
+            .markers
+            .write()
+            .map_err(|_| "Marker lock poisoned".to_string())? = placed;
+        Ok(())
+    }
+
+    /// Ids of markers that have not expired
+    pub fn marker_ids(&self) -> Vec<String> {
+        let now = Instant::now();
+        let Ok(markers) = self.settings.markers.read() else {
+            return Vec::new();
+        };
+        let mut ids: Vec<String> = markers
+            .values()
+            .filter(|placed| {
+                placed
+                    .expires_at
+                    .map_or(true, |expires_at| expires_at > now)
+            })
+            .map(|placed| placed.marker.id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
 }
 
 impl Default for CustomMarkersOverlay {
@@ -30,31 +119,209 @@ impl Default for CustomMarkersOverlay {
 }
 
 impl VisualOverlay for CustomMarkersOverlay {
-    fn initialize(&mut self, _app: &mut App) {
-        info!("Custom markers overlay initialized (placeholder)");
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, (render_markers, update_marker_labels));
+
+        info!("Custom markers overlay initialized with Gizmo rendering");
     }
-    
-    fn update_config(&mut self, _config: &serde_json::Value) -> Result<(), String> {
-        Ok(())
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let markers = parse_markers(config)?;
+        info!("Custom markers overlay received {} markers", markers.len());
+        self.set_markers(markers)
     }
-    
+
     fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
     }
-    
+
     fn is_enabled(&self) -> bool {
-        self.enabled
+        self.settings.enabled.load(Ordering::Relaxed)
     }
-    
+
     fn get_metrics(&self) -> OverlayMetrics {
-        self.metrics.clone()
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * std::mem::size_of::<WorldMarker>(),
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
     }
-    
+
     fn overlay_type(&self) -> DebugOverlayType {
-        DebugOverlayType::Custom("markers".to_string())
+        DebugOverlayType::DebugMarkers
     }
-    
+
     fn cleanup(&mut self) {
-        // Placeholder
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// System to drop expired markers and draw the rest
+fn render_markers(mut gizmos: Gizmos, settings: Res<MarkerSettings>) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        settings.rendered.store(0, Ordering::Relaxed);
+        return;
+    }
+    let Ok(mut markers) = settings.markers.write() else {
+        return;
+    };
+
+    let now = Instant::now();
+    markers.retain(|_, placed| {
+        placed
+            .expires_at
+            .map_or(true, |expires_at| expires_at > now)
+    });
+
+    for placed in markers.values() {
+        let marker = &placed.marker;
+        let position = Vec3::from_array(marker.position);
+        let color = marker_color(marker.color);
+
+        match marker.shape {
+            MarkerShape::Sphere => {
+                gizmos.sphere(position, marker.size, color);
+            }
+            MarkerShape::Arrow => {
+                let direction = marker.direction.map(Vec3::from_array).unwrap_or(Vec3::Y);
+                gizmos.arrow(position, position + direction, color);
+            }
+            MarkerShape::Text => {
+                // Small cross so the label anchor is visible
+                let half = marker.size * 0.2;
+                gizmos.line(position - Vec3::X * half, position + Vec3::X * half, color);
+                gizmos.line(position - Vec3::Y * half, position + Vec3::Y * half, color);
+            }
+        }
+    }
+
+    settings.rendered.store(markers.len(), Ordering::Relaxed);
+    let render_time = now.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+}
+
+/// System to keep one screen-space label per marker with text
+fn update_marker_labels(
+    mut commands: Commands,
+    settings: Res<MarkerSettings>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut labels: Query<(Entity, &MarkerLabel, &mut Text, &mut Node, &mut TextColor)>,
+) {
+    let mut wanted: HashMap<String, (String, Vec3, Color)> = HashMap::new();
+    if settings.enabled.load(Ordering::Relaxed) && settings.quality.get().show_labels() {
+        if let Ok(markers) = settings.markers.read() {
+            for placed in markers.values() {
+                if let Some(text) = &placed.marker.text {
+                    wanted.insert(
+                        placed.marker.id.clone(),
+                        (
+                            text.clone(),
+                            Vec3::from_array(placed.marker.position),
+                            marker_color(placed.marker.color),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    let camera = cameras.iter().find(|(camera, _)| camera.is_active);
+
+    for (label_entity, label, mut text, mut node, mut text_color) in &mut labels {
+        let Some((content, position, color)) = wanted.remove(&label.id) else {
+            commands.entity(label_entity).despawn();
+            continue;
+        };
+        let screen = camera.and_then(|(camera, camera_transform)| {
+            camera.world_to_viewport(camera_transform, position).ok()
+        });
+        match screen {
+            Some(screen) => {
+                node.display = Display::Flex;
+                node.left = Val::Px(screen.x);
+                node.top = Val::Px(screen.y);
+            }
+            None => node.display = Display::None,
+        }
+        if text.0 != content {
+            text.0 = content;
+        }
+        text_color.0 = color;
+    }
+
+    for (id, (content, _, color)) in wanted {
+        commands.spawn((
+            Text::new(content),
+            TextColor(color),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                ..default()
+            },
+            MarkerLabel { id },
+        ));
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markers_object_and_array() {
+        let by_id = serde_json::json!({
+            "markers": {
+                "a": {"id": "a", "position": [0.0, 1.0, 0.0], "shape": "arrow", "direction": [0.0, 0.0, 2.0]},
+                "b": {"id": "b", "position": [1.0, 1.0, 1.0], "text": "here"}
+            }
+        });
+        let markers = parse_markers(&by_id).unwrap();
+        assert_eq!(markers.len(), 2);
+        assert!(markers.iter().any(|m| m.shape == MarkerShape::Arrow));
+
+        let list = serde_json::json!({"markers": [{"id": "c", "position": [0.0, 0.0, 0.0]}]});
+        assert_eq!(parse_markers(&list).unwrap()[0].shape, MarkerShape::Sphere);
+
+        assert!(parse_markers(&serde_json::json!({})).unwrap().is_empty());
+        assert!(parse_markers(&serde_json::json!({"markers": 5})).is_err());
+        assert!(parse_markers(&serde_json::json!({"markers": [{"id": "x"}]})).is_err());
+    }
+
+    #[test]
+    fn test_marker_config_replaces_set() {
+        let mut overlay = CustomMarkersOverlay::new();
+        assert_eq!(overlay.overlay_type(), DebugOverlayType::DebugMarkers);
+
+        overlay
+            .update_config(&serde_json::json!({"markers": [
+                {"id": "a", "position": [0.0, 0.0, 0.0]},
+                {"id": "b", "position": [0.0, 0.0, 0.0], "ttl_ms": 1}
+            ]}))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(overlay.marker_ids(), vec!["a".to_string()]);
+
+        overlay
+            .update_config(&serde_json::json!({"markers": {}}))
+            .unwrap();
+        assert!(overlay.marker_ids().is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+/// Data-Driven Custom Overlays
+///
+/// Renders overlays defined at runtime by the MCP server through
+/// `DefineOverlay`. A definition selects entities by component and name and
+/// lists shapes to draw at each one, so new visualizations need no game
+/// rebuild. One system draws every registered definition.
+
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::{
+    CustomOverlayDefinition, DebugOverlayType, OverlayShape, OverlayShapeKind,
+};
+use bevy::ecs::component::Components;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Per-frame time budget shared by all data-driven overlays
+const RENDER_BUDGET_US: u128 = 1000;
+
+/// Parse the `definition` from an overlay config
+pub fn parse_definition(
+    config: &serde_json::Value,
+) -> Result<Option<CustomOverlayDefinition>, String> {
+    config
+        .get("definition")
+        .map(|definition| {
+            serde_json::from_value(definition.clone())
+                .map_err(|e| format!("Invalid overlay definition: {}", e))
+        })
+        .transpose()
+}
+
+/// Whether an entity with the given components and name is selected by a definition
+pub fn matches_query(
+    definition: &CustomOverlayDefinition,
+    component_names: &[&str],
+    name: Option<&str>,
+) -> bool {
+    let has = |wanted: &String| {
+        component_names.iter().any(|full| {
+            *full == wanted.as_str() || full.rsplit("::").next() == Some(wanted.as_str())
+        })
+    };
+    let query = &definition.query;
+    query.with.iter().all(has)
+        && !query.without.iter().any(has)
+        && query
+            .name_contains
+            .as_deref()
+            .map_or(true, |wanted| name.is_some_and(|n| n.contains(wanted)))
+}
+
+/// State of one data-driven overlay, shared with the render system
+#[derive(Debug, Clone)]
+struct DataDrivenSettings {
+    enabled: Arc<AtomicBool>,
+    definition: Arc<RwLock<Option<CustomOverlayDefinition>>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Registry of data-driven overlays, by overlay name
+#[derive(Resource, Debug, Clone, Default)]
+pub struct DataDrivenOverlays {
+    overlays: Arc<RwLock<HashMap<String, DataDrivenSettings>>>,
+}
+
+impl DataDrivenOverlays {
+    /// Create an overlay for `name`, sharing its state with the render system
+    pub fn create(&self, name: &str) -> DataDrivenOverlay {
+        let settings = DataDrivenSettings {
+            enabled: Arc::new(AtomicBool::new(false)),
+            definition: Arc::new(RwLock::new(None)),
+            rendered: Arc::new(AtomicUsize::new(0)),
+            render_time_us: Arc::new(AtomicU64::new(0)),
+            quality: QualityHandle::default(),
+        };
+        if let Ok(mut overlays) = self.overlays.write() {
+            overlays.insert(name.to_string(), settings.clone());
+        }
+        DataDrivenOverlay {
+            name: name.to_string(),
+            settings,
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Stop rendering `name`
+    pub fn remove(&self, name: &str) {
+        if let Ok(mut overlays) = self.overlays.write() {
+            overlays.remove(name);
+        }
+    }
+
+    /// Names of registered overlays
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .overlays
+            .read()
+            .map(|overlays| overlays.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+}
+
+/// Overlay drawn from a runtime definition
+#[derive(Debug)]
+pub struct DataDrivenOverlay {
+    name: String,
+    settings: DataDrivenSettings,
+    metrics: OverlayMetrics,
+}
+
+impl DataDrivenOverlay {
+    pub fn definition(&self) -> Option<CustomOverlayDefinition> {
+        self.settings.definition.read().ok()?.clone()
+    }
+}
+
+impl VisualOverlay for DataDrivenOverlay {
+    fn initialize(&mut self, _app: &mut App) {
+        // Rendered by the shared `render_data_driven_overlays` system
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let Some(definition) = parse_definition(config)? else {
+            return Ok(());
+        };
+        if definition.name != self.name {
+            return Err(format!(
+                "Definition '{}' sent to overlay '{}'",
+                definition.name, self.name
+            ));
+        }
+        info!(
+            "Custom overlay '{}' defined with {} shapes",
+            self.name,
+            definition.shapes.len()
+        );
+        *self
+            .settings
+            .definition
+            .write()
+            .map_err(|_| "Overlay definition lock poisoned".to_string())? = Some(definition);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::Custom(self.name.clone())
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+fn shape_color(color: [f32; 4]) -> Color {
+    Color::srgba(color[0], color[1], color[2], color[3])
+}
+
+fn draw_shape(gizmos: &mut Gizmos, shape: &OverlayShape, transform: &GlobalTransform) {
+    let (_, rotation, _) = transform.to_scale_rotation_translation();
+    let center = transform.transform_point(Vec3::from_array(shape.offset));
+    let color = shape_color(shape.color);
+
+    match shape.kind {
+        OverlayShapeKind::Sphere => {
+            gizmos.sphere(center, shape.size, color);
+        }
+        OverlayShapeKind::Cuboid => {
+            gizmos.cuboid(
+                Transform {
+                    translation: center,
+                    rotation,
+                    scale: Vec3::splat(shape.size * 2.0),
+                },
+                color,
+            );
+        }
+        OverlayShapeKind::Cross => {
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                gizmos.line(
+                    center - axis * shape.size,
+                    center + axis * shape.size,
+                    color,
+                );
+            }
+        }
+        OverlayShapeKind::Arrow => {
+            let direction = shape
+                .direction
+                .map(Vec3::from_array)
+                .unwrap_or(Vec3::NEG_Z)
+                .normalize_or_zero();
+            gizmos.arrow(center, center + rotation * direction * shape.size, color);
+        }
+        OverlayShapeKind::Circle => {
+            gizmos.circle(
+                Isometry3d::new(center, Quat::from_rotation_arc(Vec3::Z, Vec3::Y)),
+                shape.size,
+                color,
+            );
+        }
+    }
+}
+
+/// System to draw every enabled data-driven overlay
+pub(super) fn render_data_driven_overlays(
+    mut gizmos: Gizmos,
+    registry: Res<DataDrivenOverlays>,
+    components: &Components,
+    query: Query<(EntityRef, &GlobalTransform, Option<&Name>)>,
+) {
+    let Ok(overlays) = registry.overlays.read() else {
+        return;
+    };
+    let start_time = std::time::Instant::now();
+
+    for (name, settings) in overlays.iter() {
+        if !settings.enabled.load(Ordering::Relaxed) {
+            settings.rendered.store(0, Ordering::Relaxed);
+            continue;
+        }
+        let Ok(definition) = settings.definition.read() else {
+            continue;
+        };
+        let Some(definition) = definition.as_ref() else {
+            continue;
+        };
+
+        let overlay_start = std::time::Instant::now();
+        let needs_components =
+            !definition.query.with.is_empty() || !definition.query.without.is_empty();
+        let max_entities = settings.quality.get().scale_count(definition.max_entities);
+        let mut rendered = 0;
+
+        for (entity_ref, transform, entity_name) in &query {
+            if rendered >= max_entities {
+                break;
+            }
+            if start_time.elapsed().as_micros() > RENDER_BUDGET_US {
+                warn!(
+                    "Custom overlay '{}' stopped after exceeding render budget",
+                    name
+                );
+                break;
+            }
+
+            let component_names: Vec<&str> = if needs_components {
+                entity_ref
+                    .archetype()
+                    .components()
+                    .filter_map(|id| components.get_info(id))
+                    .map(|info| info.name())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            if !matches_query(
+                definition,
+                &component_names,
+                entity_name.map(|n| n.as_str()),
+            ) {
+                continue;
+            }
+
+            for shape in &definition.shapes {
+                draw_shape(&mut gizmos, shape, transform);
+            }
+            rendered += 1;
+        }
+
+        settings.rendered.store(rendered, Ordering::Relaxed);
+        settings.render_time_us.store(
+            overlay_start.elapsed().as_micros() as u64,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition() -> CustomOverlayDefinition {
+        serde_json::from_value(serde_json::json!({
+            "name": "enemies",
+            "query": {"with": ["Enemy"], "without": ["Dead"], "name_contains": "Goblin"},
+            "shapes": [{"kind": "circle", "size": 1.5}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_matches_query() {
+        let definition = definition();
+        let components = [
+            "game::Enemy",
+            "bevy_transform::components::transform::Transform",
+        ];
+
+        assert!(matches_query(&definition, &components, Some("Goblin 1")));
+        assert!(!matches_query(&definition, &components, Some("Orc")));
+        assert!(!matches_query(&definition, &components, None));
+        assert!(!matches_query(
+            &definition,
+            &["game::Enemy", "game::Dead"],
+            Some("Goblin 2")
+        ));
+        assert!(!matches_query(
+            &definition,
+            &["game::Player"],
+            Some("Goblin 3")
+        ));
+    }
+
+    #[test]
+    fn test_registry_and_config() {
+        let registry = DataDrivenOverlays::default();
+        let mut overlay = registry.create("enemies");
+        assert_eq!(registry.names(), vec!["enemies".to_string()]);
+        assert_eq!(
+            overlay.overlay_type(),
+            DebugOverlayType::Custom("enemies".to_string())
+        );
+
+        let config = serde_json::json!({"definition": definition()});
+        overlay.update_config(&config).unwrap();
+        assert_eq!(overlay.definition(), Some(definition()));
+
+        let mut other = definition();
+        other.name = "other".to_string();
+        assert!(overlay
+            .update_config(&serde_json::json!({"definition": other}))
+            .is_err());
+
+        registry.remove("enemies");
+        assert!(registry.names().is_empty());
+    }
+}
@@ -398,7 +398,7 @@ fn render_highlighted_entities(
             // Render debug label if enabled and high detail
             if gizmo_config.show_labels && detail_factor > 0.7 {
                 let label_pos = transform.translation + Vec3::Y * 2.0;
-                gizmos.sphere(label_pos, Quat::IDENTITY, 0.1 * detail_factor, color);
+                gizmos.sphere(label_pos, 0.1 * detail_factor, color);
             }
             
             viewport_rendered += 1;
@@ -538,7 +538,7 @@ fn render_glow_gizmo(gizmos: &mut Gizmos, transform: &Transform, color: Color, i
         let alpha = color.alpha() / (i as f32 * 2.0);
         let glow_color = color.with_alpha(alpha);
         
-        gizmos.sphere(position, Quat::IDENTITY, radius, glow_color);
+        gizmos.sphere(position, radius, glow_color);
     }
 }
 
@@ -575,6 +575,7 @@ fn update_highlight_metrics(
         memory_usage_bytes: estimated_memory,
         frame_updates: if count > 0 { 1 } else { 0 },
         active_this_frame: count > 0,
+        ..Default::default()
     };
     
     // Update the specific overlay metrics
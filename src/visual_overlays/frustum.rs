@@ -0,0 +1,368 @@
+/// Camera Frustum Overlay Implementation
+///
+/// Draws the view frustum of every camera and marks entities that Bevy's
+/// visibility system culled this frame (visible in the hierarchy but not in
+/// any view). Useful when objects disappear unexpectedly at certain angles.
+
+use super::{OverlayMetrics, VisualOverlay};
+use crate::brp_messages::DebugOverlayType;
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on culled-entity markers drawn per frame
+const MAX_CULLED_MARKERS_LIMIT: usize = 5000;
+
+/// Configuration for the frustum overlay
+#[derive(Debug, Clone)]
+pub struct FrustumConfig {
+    /// Color of active camera frustums
+    pub frustum_color: Color,
+    /// Color of culled-entity markers
+    pub culled_color: Color,
+    /// Also draw frustums of inactive cameras
+    pub show_inactive: bool,
+    /// Mark entities culled by the visibility system
+    pub mark_culled: bool,
+    /// Far distance used when drawing frustums (infinite projections are capped here)
+    pub max_distance: f32,
+    /// Maximum culled-entity markers drawn per frame
+    pub max_culled_markers: usize,
+}
+
+impl Default for FrustumConfig {
+    fn default() -> Self {
+        Self {
+            frustum_color: Color::srgb(1.0, 1.0, 0.0), // Yellow
+            culled_color: Color::srgb(1.0, 0.0, 0.0),  // Red
+            show_inactive: false,
+            mark_culled: true,
+            max_distance: 50.0,
+            max_culled_markers: 500,
+        }
+    }
+}
+
+impl FrustumConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(color) = config.get("frustum_color") {
+            self.frustum_color = parse_color(color)?;
+        }
+
+        if let Some(color) = config.get("culled_color") {
+            self.culled_color = parse_color(color)?;
+        }
+
+        if let Some(show) = config.get("show_inactive").and_then(|v| v.as_bool()) {
+            self.show_inactive = show;
+        }
+
+        if let Some(mark) = config.get("mark_culled").and_then(|v| v.as_bool()) {
+            self.mark_culled = mark;
+        }
+
+        if let Some(distance) = config.get("max_distance").and_then(|v| v.as_f64()) {
+            self.max_distance = (distance as f32).clamp(0.1, 10_000.0);
+        }
+
+        if let Some(max) = config.get("max_culled_markers").and_then(|v| v.as_u64()) {
+            self.max_culled_markers = (max as usize).min(MAX_CULLED_MARKERS_LIMIT);
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse an `[r, g, b]` or `[r, g, b, a]` color array
+fn parse_color(value: &serde_json::Value) -> Result<Color, String> {
+    let color_array = value.as_array().ok_or("Color must be an array")?;
+    if color_array.len() < 3 {
+        return Err("Color needs at least 3 components".to_string());
+    }
+    let r = color_array[0].as_f64().ok_or("Invalid red component")? as f32;
+    let g = color_array[1].as_f64().ok_or("Invalid green component")? as f32;
+    let b = color_array[2].as_f64().ok_or("Invalid blue component")? as f32;
+    let a = color_array.get(3).and_then(|v| v.as_f64()).unwrap_or(1.0) as f32;
+    Ok(Color::srgba(r, g, b, a))
+}
+
+/// View-space corners of a frustum: four near corners followed by four far corners.
+///
+/// The far plane is placed at `max_distance` (or the projection's own far plane
+/// if closer), since Bevy's perspective projections are infinite by default.
+pub fn frustum_corners(clip_from_view: Mat4, max_distance: f32) -> [Vec3; 8] {
+    let view_from_clip = clip_from_view.inverse();
+    // Perspective matrices have no constant w term
+    let is_perspective = clip_from_view.w_axis.w == 0.0;
+    let ndc = [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(1.0, -1.0),
+        Vec2::new(1.0, 1.0),
+        Vec2::new(-1.0, 1.0),
+    ];
+
+    let mut corners = [Vec3::ZERO; 8];
+    for (i, xy) in ndc.iter().enumerate() {
+        // Bevy uses reverse-z: the near plane is at NDC depth 1
+        let near = view_from_clip.project_point3(xy.extend(1.0));
+        let projected_far = view_from_clip.project_point3(xy.extend(0.0));
+        let far_depth = if projected_far.is_finite() {
+            (-projected_far.z).min(max_distance)
+        } else {
+            max_distance
+        };
+
+        corners[i] = near;
+        corners[i + 4] = if is_perspective && near.z != 0.0 {
+            near * (far_depth / -near.z)
+        } else {
+            Vec3::new(near.x, near.y, -far_depth)
+        };
+    }
+    corners
+}
+
+/// State shared between the overlay and its render system
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FrustumSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<FrustumConfig>>,
+    rendered: Arc<AtomicUsize>,
+    culled: Arc<AtomicUsize>,
+}
+
+/// Frustum Overlay implementation
+#[derive(Debug)]
+pub struct FrustumOverlay {
+    settings: FrustumSettings,
+    metrics: OverlayMetrics,
+}
+
+impl FrustumOverlay {
+    pub fn new() -> Self {
+        Self {
+            settings: FrustumSettings::default(),
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> FrustumConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// Number of entities culled in the last rendered frame
+    pub fn culled_count(&self) -> usize {
+        self.settings.culled.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for FrustumOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisualOverlay for FrustumOverlay {
+    fn initialize(&mut self, app: &mut App) {
+        // Visibility is computed in PostUpdate; read it after it settles
+        app.insert_resource(self.settings.clone()).add_systems(
+            PostUpdate,
+            render_frustums.after(bevy::render::view::VisibilitySystems::CheckVisibility),
+        );
+
+        info!("Frustum overlay initialized with Gizmo rendering");
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Frustum config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("Frustum overlay config updated: {:?}", *current);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            memory_usage_bytes: count * 64,
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::Frustum
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.culled.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Draw the twelve edges of a frustum given its world-space corners
+fn draw_frustum(gizmos: &mut Gizmos, corners: &[Vec3; 8], color: Color) {
+    for i in 0..4 {
+        let next = (i + 1) % 4;
+        gizmos.line(corners[i], corners[next], color);
+        gizmos.line(corners[i + 4], corners[next + 4], color);
+        gizmos.line(corners[i], corners[i + 4], color);
+    }
+}
+
+/// System to draw camera frustums and mark culled entities
+fn render_frustums(
+    mut gizmos: Gizmos,
+    settings: Res<FrustumSettings>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    entities: Query<(
+        &GlobalTransform,
+        &Aabb,
+        &InheritedVisibility,
+        &ViewVisibility,
+    )>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let mut rendered = 0;
+
+    for (camera, camera_transform) in &cameras {
+        if !camera.is_active && !config.show_inactive {
+            continue;
+        }
+
+        let world_from_view = camera_transform.compute_matrix();
+        let corners = frustum_corners(camera.clip_from_view(), config.max_distance)
+            .map(|corner| world_from_view.transform_point3(corner));
+        let color = if camera.is_active {
+            config.frustum_color
+        } else {
+            config.frustum_color.with_alpha(0.3)
+        };
+        draw_frustum(&mut gizmos, &corners, color);
+        rendered += 1;
+    }
+
+    let mut culled = 0;
+    if config.mark_culled {
+        for (transform, aabb, inherited, view) in &entities {
+            // Hidden by the hierarchy is intentional, not culling
+            if !inherited.get() || view.get() {
+                continue;
+            }
+            culled += 1;
+            if culled > config.max_culled_markers {
+                continue;
+            }
+
+            let center = transform.transform_point(Vec3::from(aabb.center));
+            let (scale, rotation, _) = transform.to_scale_rotation_translation();
+            gizmos.cuboid(
+                Transform {
+                    translation: center,
+                    rotation,
+                    scale: scale * Vec3::from(aabb.half_extents) * 2.0,
+                },
+                config.culled_color,
+            );
+            rendered += 1;
+        }
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+    settings.culled.store(culled, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    if render_time > 1000 {
+        warn!(
+            "Frustum rendering took {}μs ({} culled entities)",
+            render_time, culled
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frustum_config_json_update() {
+        let mut config = FrustumConfig::default();
+
+        let json_config = serde_json::json!({
+            "culled_color": [1.0, 0.5, 0.0],
+            "show_inactive": true,
+            "max_distance": 100.0,
+            "max_culled_markers": 1_000_000
+        });
+
+        assert!(config.update_from_json(&json_config).is_ok());
+        assert_eq!(config.culled_color, Color::srgba(1.0, 0.5, 0.0, 1.0));
+        assert!(config.show_inactive);
+        assert_eq!(config.max_distance, 100.0);
+        assert_eq!(config.max_culled_markers, MAX_CULLED_MARKERS_LIMIT);
+
+        assert!(config
+            .update_from_json(&serde_json::json!({"frustum_color": [1.0]}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_perspective_frustum_corners() {
+        let projection =
+            Mat4::perspective_infinite_reverse_rh(std::f32::consts::FRAC_PI_2, 1.0, 0.1);
+        let corners = frustum_corners(projection, 10.0);
+
+        // Near plane at 0.1, far plane capped at 10
+        for corner in &corners[..4] {
+            assert!((corner.z + 0.1).abs() < 1e-4);
+        }
+        for corner in &corners[4..] {
+            assert!((corner.z + 10.0).abs() < 1e-3);
+            // 90 degree FOV: half-width equals depth
+            assert!((corner.x.abs() - 10.0).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_orthographic_frustum_corners() {
+        let projection = Mat4::orthographic_rh(-2.0, 2.0, -1.0, 1.0, 100.0, 0.0);
+        let corners = frustum_corners(projection, 20.0);
+
+        for (near, far) in corners[..4].iter().zip(&corners[4..]) {
+            assert!((near.x - far.x).abs() < 1e-4);
+            assert!((far.z + 20.0).abs() < 1e-3);
+        }
+        assert!((corners[1].x - 2.0).abs() < 1e-4);
+    }
+}
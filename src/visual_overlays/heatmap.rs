@@ -0,0 +1,484 @@
+/// Entity Density Heatmap Overlay Implementation
+///
+/// Bins the positions of (optionally filtered) entities into a world-space grid
+/// and draws each occupied cell color-coded by entity count, from blue for
+/// sparse cells to red for the densest. Makes spawn storms and clustering bugs
+/// visible at a glance.
+
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::DebugOverlayType;
+use bevy::ecs::component::Components;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on grid cells drawn per frame
+const MAX_CELLS_LIMIT: usize = 10_000;
+
+/// World plane the grid is laid out on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatmapPlane {
+    /// Ground plane, for 3D games
+    XZ,
+    /// Screen plane, for 2D games
+    XY,
+}
+
+impl HeatmapPlane {
+    /// Project a world position onto the plane's 2D coordinates
+    fn project(self, position: Vec3) -> Vec2 {
+        match self {
+            HeatmapPlane::XZ => Vec2::new(position.x, position.z),
+            HeatmapPlane::XY => Vec2::new(position.x, position.y),
+        }
+    }
+}
+
+/// Configuration for the heatmap overlay
+#[derive(Debug, Clone)]
+pub struct HeatmapConfig {
+    /// Size of a grid cell in world units
+    pub cell_size: f32,
+    pub plane: HeatmapPlane,
+    /// Height (XZ) or depth (XY) at which the grid is drawn
+    pub plane_offset: f32,
+    /// Only count entities with this component (full path or short name)
+    pub component: Option<String>,
+    /// Only count entities whose `Name` contains this substring
+    pub name_contains: Option<String>,
+    /// Cells with fewer entities are not drawn
+    pub min_count: usize,
+    /// Maximum number of cells drawn per frame, densest first
+    pub max_cells: usize,
+    /// Sparse, middle and dense colors; blue → green → red when `None`
+    pub gradient: Option<[Color; 3]>,
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: 5.0,
+            plane: HeatmapPlane::XZ,
+            plane_offset: 0.0,
+            component: None,
+            name_contains: None,
+            min_count: 1,
+            max_cells: 1000,
+            gradient: None,
+        }
+    }
+}
+
+impl HeatmapConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(size) = config.get("cell_size").and_then(|v| v.as_f64()) {
+            if size <= 0.0 {
+                return Err(format!("cell_size must be positive, got {}", size));
+            }
+            self.cell_size = size as f32;
+        }
+
+        if let Some(plane) = config.get("plane").and_then(|v| v.as_str()) {
+            self.plane = match plane {
+                "xz" => HeatmapPlane::XZ,
+                "xy" => HeatmapPlane::XY,
+                _ => return Err(format!("Invalid heatmap plane: {}", plane)),
+            };
+        }
+
+        if let Some(offset) = config.get("plane_offset").and_then(|v| v.as_f64()) {
+            self.plane_offset = offset as f32;
+        }
+
+        // Explicit null clears a filter
+        if let Some(component) = config.get("component") {
+            self.component = component.as_str().map(str::to_string);
+        }
+
+        if let Some(name) = config.get("name_contains") {
+            self.name_contains = name.as_str().map(str::to_string);
+        }
+
+        if let Some(min) = config.get("min_count").and_then(|v| v.as_u64()) {
+            self.min_count = (min as usize).max(1);
+        }
+
+        if let Some(max) = config.get("max_cells").and_then(|v| v.as_u64()) {
+            self.max_cells = (max as usize).min(MAX_CELLS_LIMIT);
+        }
+
+        // Explicit null restores the default gradient
+        if let Some(gradient) = config.get("gradient") {
+            self.gradient = match gradient.as_array() {
+                Some(stops) if stops.len() == 3 => Some([
+                    super::bounding_boxes::parse_color(&stops[0])?,
+                    super::bounding_boxes::parse_color(&stops[1])?,
+                    super::bounding_boxes::parse_color(&stops[2])?,
+                ]),
+                Some(_) => return Err("'gradient' needs exactly 3 colors".to_string()),
+                None => None,
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Whether an entity with the given components and name should be counted
+    pub fn matches(&self, component_names: &[&str], name: Option<&str>) -> bool {
+        let component_ok = self.component.as_deref().map_or(true, |wanted| {
+            component_names
+                .iter()
+                .any(|full| *full == wanted || full.rsplit("::").next() == Some(wanted))
+        });
+        let name_ok = self
+            .name_contains
+            .as_deref()
+            .map_or(true, |wanted| name.is_some_and(|n| n.contains(wanted)));
+        component_ok && name_ok
+    }
+}
+
+/// Entity counts binned into grid cells
+#[derive(Debug, Clone, Default)]
+pub struct DensityGrid {
+    pub cell_size: f32,
+    pub cells: HashMap<(i32, i32), usize>,
+}
+
+impl DensityGrid {
+    /// Bin 2D plane positions into cells of `cell_size`
+    pub fn from_positions(positions: impl IntoIterator<Item = Vec2>, cell_size: f32) -> Self {
+        let mut cells = HashMap::new();
+        for position in positions {
+            let cell = (
+                (position.x / cell_size).floor() as i32,
+                (position.y / cell_size).floor() as i32,
+            );
+            *cells.entry(cell).or_insert(0) += 1;
+        }
+        Self { cell_size, cells }
+    }
+
+    /// Count in the densest cell
+    pub fn max_count(&self) -> usize {
+        self.cells.values().copied().max().unwrap_or(0)
+    }
+
+    /// Cells with at least `min_count` entities, densest first, capped at `limit`
+    pub fn hottest(&self, min_count: usize, limit: usize) -> Vec<((i32, i32), usize)> {
+        let mut cells: Vec<_> = self
+            .cells
+            .iter()
+            .filter(|(_, count)| **count >= min_count)
+            .map(|(cell, count)| (*cell, *count))
+            .collect();
+        cells.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        cells.truncate(limit);
+        cells
+    }
+
+    /// Center of a cell in plane coordinates
+    pub fn cell_center(&self, cell: (i32, i32)) -> Vec2 {
+        Vec2::new(
+            (cell.0 as f32 + 0.5) * self.cell_size,
+            (cell.1 as f32 + 0.5) * self.cell_size,
+        )
+    }
+}
+
+/// Map a normalized density (0..=1) to a blue → green → red color
+pub fn heat_color(t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        let k = t * 2.0;
+        Color::srgb(0.0, k, 1.0 - k)
+    } else {
+        let k = (t - 0.5) * 2.0;
+        Color::srgb(k, 1.0 - k, 0.0)
+    }
+}
+
+/// Map a normalized density (0..=1) onto a three-stop gradient
+pub fn gradient_color(stops: &[Color; 3], t: f32) -> Color {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        stops[0].mix(&stops[1], t * 2.0)
+    } else {
+        stops[1].mix(&stops[2], (t - 0.5) * 2.0)
+    }
+}
+
+/// State shared between the overlay and its render system
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HeatmapSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<HeatmapConfig>>,
+    rendered: Arc<AtomicUsize>,
+    max_density: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Heatmap Overlay implementation
+#[derive(Debug)]
+pub struct HeatmapOverlay {
+    settings: HeatmapSettings,
+    metrics: OverlayMetrics,
+}
+
+impl HeatmapOverlay {
+    pub fn new() -> Self {
+        Self {
+            settings: HeatmapSettings::default(),
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> HeatmapConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// Entity count of the densest cell in the last rendered frame
+    pub fn max_density(&self) -> usize {
+        self.settings.max_density.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for HeatmapOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisualOverlay for HeatmapOverlay {
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, render_heatmap);
+
+        info!("Heatmap overlay initialized with Gizmo rendering");
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Heatmap config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("Heatmap overlay config updated: {:?}", *current);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 96, // Cell entry plus gizmo lines
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::Heatmap
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.max_density.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// System to bin entity positions and draw the density grid
+fn render_heatmap(
+    mut gizmos: Gizmos,
+    settings: Res<HeatmapSettings>,
+    components: &Components,
+    query: Query<(EntityRef, &GlobalTransform, Option<&Name>)>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let needs_components = config.component.is_some();
+
+    let positions = query.iter().filter_map(|(entity_ref, transform, name)| {
+        let component_names: Vec<&str> = if needs_components {
+            entity_ref
+                .archetype()
+                .components()
+                .filter_map(|id| components.get_info(id))
+                .map(|info| info.name())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        config
+            .matches(&component_names, name.map(|n| n.as_str()))
+            .then(|| config.plane.project(transform.translation()))
+    });
+    let grid = DensityGrid::from_positions(positions, config.cell_size);
+    let max_count = grid.max_count();
+
+    let rotation = match config.plane {
+        HeatmapPlane::XZ => Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2),
+        HeatmapPlane::XY => Quat::IDENTITY,
+    };
+    let quality = settings.quality.get();
+    let cells = grid.hottest(config.min_count, quality.scale_count(config.max_cells));
+    for (cell, count) in &cells {
+        let center = grid.cell_center(*cell);
+        let position = match config.plane {
+            HeatmapPlane::XZ => Vec3::new(center.x, config.plane_offset, center.y),
+            HeatmapPlane::XY => Vec3::new(center.x, center.y, config.plane_offset),
+        };
+        let t = *count as f32 / max_count.max(1) as f32;
+        let color = config
+            .gradient
+            .as_ref()
+            .map_or_else(|| heat_color(t), |stops| gradient_color(stops, t));
+
+        // Nested rectangles approximate a fill that grows with density
+        let rings = if quality == QualityLevel::Full {
+            1 + (t * 3.0).round() as usize
+        } else {
+            1
+        };
+        for ring in 0..rings {
+            let inset = 1.0 - ring as f32 * 0.2;
+            gizmos.rect(
+                Isometry3d::new(position, rotation),
+                Vec2::splat(grid.cell_size * inset),
+                color,
+            );
+        }
+    }
+
+    settings.rendered.store(cells.len(), Ordering::Relaxed);
+    settings.max_density.store(max_count, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+    if render_time > 1000 {
+        warn!(
+            "Heatmap rendering took {}μs for {} cells",
+            render_time,
+            cells.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_config_json_update() {
+        let mut config = HeatmapConfig::default();
+
+        let json_config = serde_json::json!({
+            "cell_size": 2.0,
+            "plane": "xy",
+            "component": "Enemy",
+            "min_count": 0,
+            "max_cells": 50000
+        });
+
+        assert!(config.update_from_json(&json_config).is_ok());
+        assert_eq!(config.cell_size, 2.0);
+        assert_eq!(config.plane, HeatmapPlane::XY);
+        assert_eq!(config.component.as_deref(), Some("Enemy"));
+        assert_eq!(config.min_count, 1);
+        assert_eq!(config.max_cells, MAX_CELLS_LIMIT);
+
+        assert!(config.matches(&["game::Enemy"], None));
+        assert!(!config.matches(&["game::Player"], None));
+
+        config
+            .update_from_json(&serde_json::json!({"component": null}))
+            .unwrap();
+        assert!(config.component.is_none());
+
+        assert!(config
+            .update_from_json(&serde_json::json!({"cell_size": 0.0}))
+            .is_err());
+        assert!(config
+            .update_from_json(&serde_json::json!({"plane": "yz"}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_density_grid_binning() {
+        let positions = vec![
+            Vec2::new(0.5, 0.5),
+            Vec2::new(1.5, 1.5),
+            Vec2::new(1.9, 0.1),
+            Vec2::new(-0.5, 0.5),
+            Vec2::new(12.0, 3.0),
+        ];
+        let grid = DensityGrid::from_positions(positions, 2.0);
+
+        assert_eq!(grid.cells.get(&(0, 0)), Some(&3));
+        assert_eq!(grid.cells.get(&(-1, 0)), Some(&1));
+        assert_eq!(grid.cells.get(&(6, 1)), Some(&1));
+        assert_eq!(grid.max_count(), 3);
+        assert_eq!(grid.cell_center((0, 0)), Vec2::new(1.0, 1.0));
+
+        let hottest = grid.hottest(1, 2);
+        assert_eq!(hottest.len(), 2);
+        assert_eq!(hottest[0], ((0, 0), 3));
+        assert!(grid.hottest(2, 10).len() == 1);
+    }
+
+    #[test]
+    fn test_heat_color_gradient() {
+        assert_eq!(heat_color(0.0), Color::srgb(0.0, 0.0, 1.0));
+        assert_eq!(heat_color(0.5), Color::srgb(0.0, 1.0, 0.0));
+        assert_eq!(heat_color(1.0), Color::srgb(1.0, 0.0, 0.0));
+        assert_eq!(heat_color(7.0), heat_color(1.0));
+
+        let stops = [
+            Color::srgb(0.0, 0.0, 0.0),
+            Color::srgb(0.5, 0.5, 0.5),
+            Color::srgb(1.0, 1.0, 1.0),
+        ];
+        assert_eq!(gradient_color(&stops, 0.0), stops[0]);
+        assert_eq!(gradient_color(&stops, 1.0), stops[2]);
+    }
+}
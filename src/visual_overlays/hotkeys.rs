@@ -0,0 +1,275 @@
+/// In-Game Overlay Hotkeys
+///
+/// Keyboard bindings for developers at the game window: toggle an overlay,
+/// cycle through overlays one at a time (F9 / Shift+F9 by default) or turn
+/// every overlay off (Ctrl+F9). Hotkeys go through the same manager as MCP
+/// commands, so the MCP side sees the change through `GetOverlayState`.
+use super::VisualOverlayManager;
+use crate::brp_messages::DebugOverlayType;
+use bevy::prelude::*;
+
+/// A key plus required modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            shift: false,
+            ctrl: false,
+            alt: false,
+        }
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Whether the binding was triggered this frame; modifiers must match exactly
+    pub fn just_pressed(&self, keys: &ButtonInput<KeyCode>) -> bool {
+        let held = |left, right| keys.any_pressed([left, right]);
+        keys.just_pressed(self.key)
+            && held(KeyCode::ShiftLeft, KeyCode::ShiftRight) == self.shift
+            && held(KeyCode::ControlLeft, KeyCode::ControlRight) == self.ctrl
+            && held(KeyCode::AltLeft, KeyCode::AltRight) == self.alt
+    }
+}
+
+/// What a hotkey does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Flip one overlay on or off
+    Toggle(DebugOverlayType),
+    /// Show the next overlay of the cycle, hiding the current one
+    CycleNext,
+    /// Show the previous overlay of the cycle, hiding the current one
+    CyclePrevious,
+    /// Turn every overlay off
+    DisableAll,
+}
+
+/// Keybindings for overlay toggles
+#[derive(Resource, Debug, Clone)]
+pub struct OverlayHotkeys {
+    bindings: Vec<(KeyBinding, HotkeyAction)>,
+    /// Overlays visited by the cycle actions, in order
+    cycle: Vec<DebugOverlayType>,
+    /// Index in `cycle` of the overlay the cycle last showed
+    position: Option<usize>,
+}
+
+impl Default for OverlayHotkeys {
+    fn default() -> Self {
+        Self::new()
+            .with_binding(KeyBinding::new(KeyCode::F9), HotkeyAction::CycleNext)
+            .with_binding(
+                KeyBinding::new(KeyCode::F9).with_shift(),
+                HotkeyAction::CyclePrevious,
+            )
+            .with_binding(
+                KeyBinding::new(KeyCode::F9).with_ctrl(),
+                HotkeyAction::DisableAll,
+            )
+    }
+}
+
+impl OverlayHotkeys {
+    /// No bindings, with the default cycle order
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            cycle: vec![
+                DebugOverlayType::BoundingBoxes,
+                DebugOverlayType::Colliders,
+                DebugOverlayType::Transforms,
+                DebugOverlayType::Lights,
+                DebugOverlayType::Frustum,
+                DebugOverlayType::Heatmap,
+                DebugOverlayType::Labels,
+                DebugOverlayType::UiLayout,
+                DebugOverlayType::SystemFlow,
+                DebugOverlayType::PerformanceMetrics,
+            ],
+            position: None,
+        }
+    }
+
+    /// Add a binding; later bindings for the same keys are also triggered
+    pub fn with_binding(mut self, binding: KeyBinding, action: HotkeyAction) -> Self {
+        self.bindings.push((binding, action));
+        self
+    }
+
+    /// Set the overlays the cycle actions step through
+    pub fn with_cycle(mut self, cycle: Vec<DebugOverlayType>) -> Self {
+        self.cycle = cycle;
+        self.position = None;
+        self
+    }
+
+    pub fn bindings(&self) -> &[(KeyBinding, HotkeyAction)] {
+        &self.bindings
+    }
+
+    /// Overlay changes for an action, as `(overlay, enabled)` pairs
+    pub fn resolve(
+        &mut self,
+        action: &HotkeyAction,
+        is_enabled: impl Fn(&DebugOverlayType) -> bool,
+    ) -> Vec<(DebugOverlayType, bool)> {
+        match action {
+            HotkeyAction::Toggle(overlay_type) => {
+                vec![(overlay_type.clone(), !is_enabled(overlay_type))]
+            }
+            HotkeyAction::CycleNext | HotkeyAction::CyclePrevious => {
+                let len = self.cycle.len();
+                if len == 0 {
+                    return Vec::new();
+                }
+                let mut changes: Vec<(DebugOverlayType, bool)> = self
+                    .position
+                    .and_then(|index| self.cycle.get(index))
+                    .map(|current| (current.clone(), false))
+                    .into_iter()
+                    .collect();
+
+                // Stepping past either end shows no overlay
+                self.position = match (action, self.position) {
+                    (HotkeyAction::CycleNext, None) => Some(0),
+                    (HotkeyAction::CycleNext, Some(index)) if index + 1 < len => Some(index + 1),
+                    (HotkeyAction::CyclePrevious, None) => Some(len - 1),
+                    (HotkeyAction::CyclePrevious, Some(index)) if index > 0 => Some(index - 1),
+                    _ => None,
+                };
+                if let Some(index) = self.position {
+                    changes.push((self.cycle[index].clone(), true));
+                }
+                changes
+            }
+            HotkeyAction::DisableAll => {
+                self.position = None;
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// System to apply triggered hotkeys through the overlay manager
+pub(super) fn handle_overlay_hotkeys(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    hotkeys: Option<ResMut<OverlayHotkeys>>,
+    manager: Option<ResMut<VisualOverlayManager>>,
+) {
+    let (Some(keys), Some(mut hotkeys), Some(mut manager)) = (keys, hotkeys, manager) else {
+        return;
+    };
+
+    let triggered: Vec<HotkeyAction> = hotkeys
+        .bindings
+        .iter()
+        .filter(|(binding, _)| binding.just_pressed(&keys))
+        .map(|(_, action)| action.clone())
+        .collect();
+
+    for action in triggered {
+        if action == HotkeyAction::DisableAll {
+            manager.disable_all_overlays();
+        }
+        let changes = hotkeys.resolve(&action, |overlay_type| {
+            manager
+                .get_overlay_status(overlay_type)
+                .is_some_and(|(enabled, _)| enabled)
+        });
+        for (overlay_type, enabled) in changes {
+            if let Err(e) = manager.set_overlay_enabled(&overlay_type, enabled, None) {
+                warn!("Hotkey could not change overlay {:?}: {}", overlay_type, e);
+            }
+        }
+        info!("Overlay hotkey {:?} applied", action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_steps_through_overlays_and_off() {
+        let mut hotkeys = OverlayHotkeys::new().with_cycle(vec![
+            DebugOverlayType::BoundingBoxes,
+            DebugOverlayType::Lights,
+        ]);
+        let never = |_: &DebugOverlayType| false;
+
+        assert_eq!(
+            hotkeys.resolve(&HotkeyAction::CycleNext, never),
+            vec![(DebugOverlayType::BoundingBoxes, true)]
+        );
+        assert_eq!(
+            hotkeys.resolve(&HotkeyAction::CycleNext, never),
+            vec![
+                (DebugOverlayType::BoundingBoxes, false),
+                (DebugOverlayType::Lights, true)
+            ]
+        );
+        assert_eq!(
+            hotkeys.resolve(&HotkeyAction::CycleNext, never),
+            vec![(DebugOverlayType::Lights, false)]
+        );
+        assert_eq!(
+            hotkeys.resolve(&HotkeyAction::CyclePrevious, never),
+            vec![(DebugOverlayType::Lights, true)]
+        );
+    }
+
+    #[test]
+    fn test_toggle_uses_current_state() {
+        let mut hotkeys = OverlayHotkeys::default();
+        assert_eq!(hotkeys.bindings().len(), 3);
+
+        let toggle = HotkeyAction::Toggle(DebugOverlayType::Heatmap);
+        assert_eq!(
+            hotkeys.resolve(&toggle, |t| *t == DebugOverlayType::Heatmap),
+            vec![(DebugOverlayType::Heatmap, false)]
+        );
+        assert_eq!(
+            hotkeys.resolve(&toggle, |_| false),
+            vec![(DebugOverlayType::Heatmap, true)]
+        );
+        assert!(hotkeys
+            .resolve(&HotkeyAction::DisableAll, |_| true)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_key_binding_modifiers() {
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::ShiftLeft);
+        keys.press(KeyCode::F9);
+
+        assert!(KeyBinding::new(KeyCode::F9)
+            .with_shift()
+            .just_pressed(&keys));
+        assert!(!KeyBinding::new(KeyCode::F9).just_pressed(&keys));
+        assert!(!KeyBinding::new(KeyCode::F10)
+            .with_shift()
+            .just_pressed(&keys));
+    }
+}
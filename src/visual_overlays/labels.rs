@@ -0,0 +1,532 @@
+/// World-Space Label Overlay Implementation
+///
+/// Shows selected component fields (e.g. `Health.current`) and entity names as
+/// floating text next to entities matching a filter. Field values are read
+/// through reflection, so components must be registered with `#[reflect(Component)]`.
+/// Labels are limited in count and culled by camera distance to stay within
+/// the overlay render budget.
+
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::DebugOverlayType;
+use bevy::ecs::component::Components;
+use bevy::prelude::*;
+use bevy::reflect::GetPath;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on labels shown at once
+const MAX_LABELS_LIMIT: usize = 500;
+
+/// Longest value shown for a single field before truncation
+const MAX_VALUE_CHARS: usize = 48;
+
+/// Time after which label building stops for the frame
+const LABEL_BUDGET_US: u128 = 1500;
+
+/// A value to display in an entity's label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LabelField {
+    /// The entity's `Name`
+    Name,
+    /// The entity id
+    EntityId,
+    /// A reflected component field; an empty path shows the whole component
+    Component { component: String, path: String },
+}
+
+impl LabelField {
+    /// Parse `name`, `id` or `Component.field.path`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        match spec {
+            "" => Err("Label field must not be empty".to_string()),
+            "name" => Ok(LabelField::Name),
+            "id" | "entity" => Ok(LabelField::EntityId),
+            _ => {
+                let (component, path) = spec.split_once('.').unwrap_or((spec, ""));
+                if component.is_empty() {
+                    return Err(format!("Invalid label field: {}", spec));
+                }
+                Ok(LabelField::Component {
+                    component: component.to_string(),
+                    path: path.to_string(),
+                })
+            }
+        }
+    }
+
+    /// Caption shown before the value
+    fn caption(&self) -> String {
+        match self {
+            LabelField::Name => "name".to_string(),
+            LabelField::EntityId => "id".to_string(),
+            LabelField::Component { component, path } if path.is_empty() => component.clone(),
+            LabelField::Component { component, path } => format!("{}.{}", component, path),
+        }
+    }
+}
+
+/// Configuration for the labels overlay
+#[derive(Debug, Clone)]
+pub struct LabelsConfig {
+    /// Fields shown for each entity, one line each
+    pub fields: Vec<LabelField>,
+    /// Only label entities with this component (full path or short name)
+    pub component: Option<String>,
+    /// Only label entities whose `Name` contains this substring
+    pub name_contains: Option<String>,
+    /// Maximum labels shown at once, nearest entities first
+    pub max_labels: usize,
+    /// Entities farther than this from the camera are not labeled
+    pub max_distance: f32,
+    pub font_size: f32,
+    pub text_color: Color,
+    /// World-space height above the entity origin where the label is anchored
+    pub vertical_offset: f32,
+}
+
+impl Default for LabelsConfig {
+    fn default() -> Self {
+        Self {
+            fields: vec![LabelField::Name],
+            component: None,
+            name_contains: None,
+            max_labels: 50,
+            max_distance: 50.0,
+            font_size: 12.0,
+            text_color: Color::WHITE,
+            vertical_offset: 1.0,
+        }
+    }
+}
+
+impl LabelsConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(fields) = config.get("fields") {
+            let fields = fields.as_array().ok_or("'fields' must be an array")?;
+            self.fields = fields
+                .iter()
+                .map(|f| {
+                    f.as_str()
+                        .ok_or_else(|| format!("Invalid label field: {}", f))
+                        .and_then(LabelField::parse)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+
+        // Explicit null clears a filter
+        if let Some(component) = config.get("component") {
+            self.component = component.as_str().map(str::to_string);
+        }
+
+        if let Some(name) = config.get("name_contains") {
+            self.name_contains = name.as_str().map(str::to_string);
+        }
+
+        if let Some(max) = config.get("max_labels").and_then(|v| v.as_u64()) {
+            self.max_labels = (max as usize).min(MAX_LABELS_LIMIT);
+        }
+
+        if let Some(distance) = config.get("max_distance").and_then(|v| v.as_f64()) {
+            self.max_distance = (distance as f32).clamp(0.1, 10_000.0);
+        }
+
+        if let Some(size) = config.get("font_size").and_then(|v| v.as_f64()) {
+            self.font_size = (size as f32).clamp(6.0, 72.0);
+        }
+
+        if let Some(color) = config.get("text_color") {
+            self.text_color = super::bounding_boxes::parse_color(color)?;
+        }
+
+        if let Some(offset) = config.get("vertical_offset").and_then(|v| v.as_f64()) {
+            self.vertical_offset = offset as f32;
+        }
+
+        Ok(())
+    }
+
+    /// Whether an entity with the given components and name should be labeled
+    pub fn matches(&self, component_names: &[&str], name: Option<&str>) -> bool {
+        let component_ok = self
+            .component
+            .as_deref()
+            .map_or(true, |wanted| has_component(component_names, wanted));
+        let name_ok = self
+            .name_contains
+            .as_deref()
+            .map_or(true, |wanted| name.is_some_and(|n| n.contains(wanted)));
+        component_ok && name_ok
+    }
+
+    /// Whether any field or filter needs the entity's component list
+    fn needs_components(&self) -> bool {
+        self.component.is_some()
+            || self
+                .fields
+                .iter()
+                .any(|f| matches!(f, LabelField::Component { .. }))
+    }
+}
+
+/// Match a component by full type path or short name
+fn has_component(component_names: &[&str], wanted: &str) -> bool {
+    component_names
+        .iter()
+        .any(|full| *full == wanted || full.rsplit("::").next() == Some(wanted))
+}
+
+/// Format a label line, truncating long values
+pub fn format_label_line(caption: &str, value: &str) -> String {
+    if value.chars().count() > MAX_VALUE_CHARS {
+        let truncated: String = value.chars().take(MAX_VALUE_CHARS - 1).collect();
+        format!("{}: {}…", caption, truncated)
+    } else {
+        format!("{}: {}", caption, value)
+    }
+}
+
+/// Screen-space label attached to an entity
+#[derive(Component, Debug)]
+struct FieldLabel {
+    target: Entity,
+}
+
+/// State shared between the overlay and its system
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LabelsSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<LabelsConfig>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Labels Overlay implementation
+#[derive(Debug)]
+pub struct LabelsOverlay {
+    settings: LabelsSettings,
+    metrics: OverlayMetrics,
+}
+
+impl LabelsOverlay {
+    pub fn new() -> Self {
+        Self {
+            settings: LabelsSettings::default(),
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> LabelsConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for LabelsOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisualOverlay for LabelsOverlay {
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, update_field_labels);
+
+        info!("Labels overlay initialized");
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Labels config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("Labels overlay config updated: {:?}", *current);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 256, // Text entity and layout
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::Labels
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// Read a label field's value from an entity
+fn read_field(
+    field: &LabelField,
+    entity: Entity,
+    entity_ref: EntityRef,
+    name: Option<&Name>,
+    registry: &bevy::reflect::TypeRegistry,
+) -> String {
+    match field {
+        LabelField::Name => name.map_or_else(|| "-".to_string(), |n| n.as_str().to_string()),
+        LabelField::EntityId => format!("{}", entity),
+        LabelField::Component { component, path } => {
+            let Some(registration) = registry
+                .get_with_type_path(component)
+                .or_else(|| registry.get_with_short_type_path(component))
+            else {
+                return "<unregistered>".to_string();
+            };
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                return "<not reflected>".to_string();
+            };
+            let Some(value) = reflect_component.reflect(entity_ref) else {
+                return "-".to_string();
+            };
+            if path.is_empty() {
+                return format!("{:?}", value);
+            }
+            match value.reflect_path(path.as_str()) {
+                Ok(field_value) => format!("{:?}", field_value),
+                Err(_) => "<no such field>".to_string(),
+            }
+        }
+    }
+}
+
+/// System to keep one screen-space label per nearby matching entity
+fn update_field_labels(
+    mut commands: Commands,
+    settings: Res<LabelsSettings>,
+    type_registry: Res<AppTypeRegistry>,
+    components: &Components,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    entities: Query<(Entity, EntityRef, &GlobalTransform, Option<&Name>), Without<FieldLabel>>,
+    mut labels: Query<(
+        Entity,
+        &FieldLabel,
+        &mut Text,
+        &mut Node,
+        &mut TextFont,
+        &mut TextColor,
+    )>,
+    mut frame: Local<u32>,
+) {
+    let start_time = std::time::Instant::now();
+    let enabled = settings.enabled.load(Ordering::Relaxed);
+    let quality = settings.quality.get();
+
+    // At reduced quality existing labels are kept between refreshes
+    *frame = frame.wrapping_add(1);
+    if enabled && !settings.quality.should_update(*frame) {
+        return;
+    }
+    let config = match settings.config.read() {
+        Ok(config) => config.clone(),
+        Err(_) => return,
+    };
+    let camera = cameras.iter().find(|(camera, _)| camera.is_active);
+
+    let mut wanted: HashMap<Entity, (String, Vec3)> = HashMap::new();
+    if let (true, Some((_, camera_transform))) = (enabled, camera) {
+        let camera_pos = camera_transform.translation();
+        let needs_components = config.needs_components();
+
+        // Distance culling first so reflection only runs for labels that are shown
+        let mut candidates: Vec<(f32, Entity)> = entities
+            .iter()
+            .filter_map(|(entity, _, transform, _)| {
+                let distance = transform.translation().distance(camera_pos);
+                (distance <= config.max_distance).then_some((distance, entity))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let registry = type_registry.read();
+        for (_, entity) in candidates {
+            if wanted.len() >= quality.scale_count(config.max_labels) {
+                break;
+            }
+            if start_time.elapsed().as_micros() > LABEL_BUDGET_US {
+                debug!("Label overlay budget reached after {} labels", wanted.len());
+                break;
+            }
+            let Ok((entity, entity_ref, transform, name)) = entities.get(entity) else {
+                continue;
+            };
+
+            let component_names: Vec<&str> = if needs_components {
+                entity_ref
+                    .archetype()
+                    .components()
+                    .filter_map(|id| components.get_info(id))
+                    .map(|info| info.name())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            if !config.matches(&component_names, name.map(|n| n.as_str())) {
+                continue;
+            }
+
+            let text = config
+                .fields
+                .iter()
+                .map(|field| {
+                    let value = read_field(field, entity, entity_ref, name, &registry);
+                    format_label_line(&field.caption(), &value)
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let anchor = transform.translation() + Vec3::Y * config.vertical_offset;
+            wanted.insert(entity, (text, anchor));
+        }
+    }
+
+    settings.rendered.store(wanted.len(), Ordering::Relaxed);
+    let render_time = start_time.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+
+    // Update or remove existing labels
+    for (label_entity, label, mut text, mut node, mut font, mut text_color) in &mut labels {
+        let Some((content, anchor)) = wanted.remove(&label.target) else {
+            commands.entity(label_entity).despawn();
+            continue;
+        };
+        let screen = camera.and_then(|(camera, camera_transform)| {
+            camera.world_to_viewport(camera_transform, anchor).ok()
+        });
+        match screen {
+            Some(screen) => {
+                node.display = Display::Flex;
+                node.left = Val::Px(screen.x);
+                node.top = Val::Px(screen.y);
+            }
+            None => node.display = Display::None,
+        }
+        if text.0 != content {
+            text.0 = content;
+        }
+        font.font_size = config.font_size;
+        text_color.0 = config.text_color;
+    }
+
+    // Spawn labels for newly labeled entities; positioned next frame
+    for (target, (content, _)) in wanted {
+        commands.spawn((
+            Text::new(content),
+            TextFont {
+                font_size: config.font_size,
+                ..default()
+            },
+            TextColor(config.text_color),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                ..default()
+            },
+            FieldLabel { target },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_field_parsing() {
+        assert_eq!(LabelField::parse("name"), Ok(LabelField::Name));
+        assert_eq!(LabelField::parse("id"), Ok(LabelField::EntityId));
+        assert_eq!(
+            LabelField::parse("Health.current"),
+            Ok(LabelField::Component {
+                component: "Health".to_string(),
+                path: "current".to_string(),
+            })
+        );
+        assert_eq!(
+            LabelField::parse("Transform.translation.x")
+                .unwrap()
+                .caption(),
+            "Transform.translation.x"
+        );
+        assert_eq!(LabelField::parse("Velocity").unwrap().caption(), "Velocity");
+        assert!(LabelField::parse("").is_err());
+        assert!(LabelField::parse(".current").is_err());
+    }
+
+    #[test]
+    fn test_labels_config_json_update() {
+        let mut config = LabelsConfig::default();
+
+        let json_config = serde_json::json!({
+            "fields": ["name", "Health.current"],
+            "component": "Enemy",
+            "max_labels": 10000,
+            "max_distance": 25.0
+        });
+
+        assert!(config.update_from_json(&json_config).is_ok());
+        assert_eq!(config.fields.len(), 2);
+        assert_eq!(config.max_labels, MAX_LABELS_LIMIT);
+        assert_eq!(config.max_distance, 25.0);
+        assert!(config.needs_components());
+        assert!(config.matches(&["game::Enemy"], None));
+        assert!(!config.matches(&["game::Player"], Some("Enemy")));
+
+        assert!(config
+            .update_from_json(&serde_json::json!({"fields": ["name", 3]}))
+            .is_err());
+        assert_eq!(config.fields.len(), 2);
+    }
+
+    #[test]
+    fn test_format_label_line_truncates() {
+        assert_eq!(format_label_line("hp", "42"), "hp: 42");
+
+        let long_value = "x".repeat(100);
+        let line = format_label_line("data", &long_value);
+        assert!(line.ends_with('…'));
+        assert_eq!(line.chars().count(), "data: ".len() + MAX_VALUE_CHARS);
+    }
+}
@@ -0,0 +1,509 @@
+/// Light and Shadow Overlay Implementation
+///
+/// Draws gizmos for point, spot and directional lights: range spheres for point
+/// lights, cones for spot lights and direction arrows for directional lights.
+/// Each light can carry a screen-space label with its intensity and shadow
+/// state, which makes "why is this scene dark" questions answerable remotely.
+
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::DebugOverlayType;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on lights drawn per frame regardless of configuration
+const MAX_LIGHTS_LIMIT: usize = 1000;
+
+/// Kind of light being visualized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightKind {
+    Point,
+    Spot,
+    Directional,
+}
+
+/// Configuration for the lights overlay
+#[derive(Debug, Clone)]
+pub struct LightsConfig {
+    pub show_point: bool,
+    pub show_spot: bool,
+    pub show_directional: bool,
+    /// Draw the range sphere / cone of point and spot lights
+    pub show_range: bool,
+    /// Show intensity labels next to each light
+    pub show_labels: bool,
+    /// Only draw lights that cast shadows
+    pub only_shadow_casters: bool,
+    /// Maximum number of lights drawn per frame
+    pub max_lights: usize,
+    /// Length of the arrow drawn for directional lights
+    pub directional_arrow_length: f32,
+    /// Font size of intensity labels
+    pub label_font_size: f32,
+}
+
+impl Default for LightsConfig {
+    fn default() -> Self {
+        Self {
+            show_point: true,
+            show_spot: true,
+            show_directional: true,
+            show_range: true,
+            show_labels: true,
+            only_shadow_casters: false,
+            max_lights: 100,
+            directional_arrow_length: 5.0,
+            label_font_size: 12.0,
+        }
+    }
+}
+
+impl LightsConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let flags: [(&str, &mut bool); 6] = [
+            ("show_point", &mut self.show_point),
+            ("show_spot", &mut self.show_spot),
+            ("show_directional", &mut self.show_directional),
+            ("show_range", &mut self.show_range),
+            ("show_labels", &mut self.show_labels),
+            ("only_shadow_casters", &mut self.only_shadow_casters),
+        ];
+        for (key, flag) in flags {
+            if let Some(value) = config.get(key) {
+                *flag = value
+                    .as_bool()
+                    .ok_or_else(|| format!("'{}' must be a boolean", key))?;
+            }
+        }
+
+        if let Some(max) = config.get("max_lights").and_then(|v| v.as_u64()) {
+            self.max_lights = (max as usize).min(MAX_LIGHTS_LIMIT);
+        }
+
+        if let Some(length) = config
+            .get("directional_arrow_length")
+            .and_then(|v| v.as_f64())
+        {
+            self.directional_arrow_length = (length as f32).clamp(0.1, 1000.0);
+        }
+
+        if let Some(size) = config.get("label_font_size").and_then(|v| v.as_f64()) {
+            self.label_font_size = (size as f32).clamp(6.0, 72.0);
+        }
+
+        Ok(())
+    }
+
+    /// Whether lights of this kind should be drawn
+    pub fn shows(&self, kind: LightKind, shadows_enabled: bool) -> bool {
+        if self.only_shadow_casters && !shadows_enabled {
+            return false;
+        }
+        match kind {
+            LightKind::Point => self.show_point,
+            LightKind::Spot => self.show_spot,
+            LightKind::Directional => self.show_directional,
+        }
+    }
+}
+
+/// Label text for a light, e.g. `Spot 800 lm, shadows`
+pub fn format_light_label(kind: LightKind, intensity: f32, shadows_enabled: bool) -> String {
+    let (name, unit) = match kind {
+        LightKind::Point => ("Point", "lm"),
+        LightKind::Spot => ("Spot", "lm"),
+        LightKind::Directional => ("Directional", "lux"),
+    };
+    let shadows = if shadows_enabled {
+        "shadows"
+    } else {
+        "no shadows"
+    };
+    format!("{} {:.0} {}, {}", name, intensity, unit, shadows)
+}
+
+/// Radius of a spot light's cone at its maximum range
+pub fn spot_cone_radius(range: f32, outer_angle: f32) -> f32 {
+    range
+        * outer_angle
+            .clamp(0.0, std::f32::consts::FRAC_PI_2 - 0.01)
+            .tan()
+}
+
+/// Screen-space label attached to a light
+#[derive(Component, Debug)]
+struct LightLabel {
+    light: Entity,
+}
+
+/// State shared between the overlay and its systems
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LightsSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<LightsConfig>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Lights Overlay implementation
+#[derive(Debug)]
+pub struct LightsOverlay {
+    settings: LightsSettings,
+    metrics: OverlayMetrics,
+}
+
+impl LightsOverlay {
+    pub fn new() -> Self {
+        Self {
+            settings: LightsSettings::default(),
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> LightsConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for LightsOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisualOverlay for LightsOverlay {
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, (render_light_gizmos, update_light_labels));
+
+        info!("Lights overlay initialized with Gizmo rendering");
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Lights config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("Lights overlay config updated: {:?}", *current);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 128, // Gizmo lines plus label entity
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::Lights
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// Dim the gizmo color of lights that do not cast shadows
+fn gizmo_color(color: Color, shadows_enabled: bool) -> Color {
+    if shadows_enabled {
+        color
+    } else {
+        color.with_alpha(color.alpha() * 0.5)
+    }
+}
+
+/// System to draw light gizmos
+fn render_light_gizmos(
+    mut gizmos: Gizmos,
+    settings: Res<LightsSettings>,
+    point_lights: Query<(&PointLight, &GlobalTransform)>,
+    spot_lights: Query<(&SpotLight, &GlobalTransform)>,
+    directional_lights: Query<(&DirectionalLight, &GlobalTransform)>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        settings.rendered.store(0, Ordering::Relaxed);
+        return;
+    }
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let quality = settings.quality.get();
+    let max_lights = quality.scale_count(config.max_lights);
+    let show_range = config.show_range && quality == QualityLevel::Full;
+    let mut rendered = 0;
+
+    if config.show_point {
+        for (light, transform) in &point_lights {
+            if rendered >= max_lights {
+                break;
+            }
+            if !config.shows(LightKind::Point, light.shadows_enabled) {
+                continue;
+            }
+            let color = gizmo_color(light.color, light.shadows_enabled);
+            let position = transform.translation();
+            gizmos.sphere(position, light.radius.max(0.1), color);
+            if show_range {
+                gizmos.sphere(position, light.range, color.with_alpha(color.alpha() * 0.3));
+            }
+            rendered += 1;
+        }
+    }
+
+    if config.show_spot {
+        for (light, transform) in &spot_lights {
+            if rendered >= max_lights {
+                break;
+            }
+            if !config.shows(LightKind::Spot, light.shadows_enabled) {
+                continue;
+            }
+            let color = gizmo_color(light.color, light.shadows_enabled);
+            let apex = transform.translation();
+            let direction = transform.forward().as_vec3();
+            gizmos.sphere(apex, light.radius.max(0.1), color);
+
+            if show_range {
+                let base_center = apex + direction * light.range;
+                let rotation = Quat::from_rotation_arc(Vec3::Z, direction);
+                let outer = spot_cone_radius(light.range, light.outer_angle);
+                let inner = spot_cone_radius(light.range, light.inner_angle);
+                gizmos.circle(Isometry3d::new(base_center, rotation), outer, color);
+                if inner > 0.0 && inner < outer {
+                    gizmos.circle(
+                        Isometry3d::new(base_center, rotation),
+                        inner,
+                        color.with_alpha(color.alpha() * 0.5),
+                    );
+                }
+                for axis in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y] {
+                    gizmos.line(apex, base_center + rotation * axis * outer, color);
+                }
+            } else {
+                gizmos.arrow(apex, apex + direction, color);
+            }
+            rendered += 1;
+        }
+    }
+
+    if config.show_directional {
+        for (light, transform) in &directional_lights {
+            if rendered >= max_lights {
+                break;
+            }
+            if !config.shows(LightKind::Directional, light.shadows_enabled) {
+                continue;
+            }
+            let color = gizmo_color(light.color, light.shadows_enabled);
+            let start = transform.translation();
+            let end = start + transform.forward().as_vec3() * config.directional_arrow_length;
+            gizmos.arrow(start, end, color);
+            rendered += 1;
+        }
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+    if render_time > 1000 {
+        warn!(
+            "Light gizmo rendering took {}μs for {} lights",
+            render_time, rendered
+        );
+    }
+}
+
+/// System to keep one screen-space intensity label per visible light
+fn update_light_labels(
+    mut commands: Commands,
+    settings: Res<LightsSettings>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    point_lights: Query<(Entity, &PointLight, &GlobalTransform)>,
+    spot_lights: Query<(Entity, &SpotLight, &GlobalTransform)>,
+    directional_lights: Query<(Entity, &DirectionalLight, &GlobalTransform)>,
+    mut labels: Query<(Entity, &LightLabel, &mut Text, &mut Node, &mut TextFont)>,
+) {
+    let enabled = settings.enabled.load(Ordering::Relaxed);
+    let show_labels = settings.quality.get().show_labels();
+    let config = match settings.config.read() {
+        Ok(config) => config.clone(),
+        Err(_) => return,
+    };
+
+    let mut wanted: HashMap<Entity, (String, Vec3)> = HashMap::new();
+    if enabled && config.show_labels && show_labels {
+        let point = point_lights.iter().map(|(entity, light, transform)| {
+            (
+                entity,
+                LightKind::Point,
+                light.intensity,
+                light.shadows_enabled,
+                transform,
+            )
+        });
+        let spot = spot_lights.iter().map(|(entity, light, transform)| {
+            (
+                entity,
+                LightKind::Spot,
+                light.intensity,
+                light.shadows_enabled,
+                transform,
+            )
+        });
+        let directional = directional_lights.iter().map(|(entity, light, transform)| {
+            (
+                entity,
+                LightKind::Directional,
+                light.illuminance,
+                light.shadows_enabled,
+                transform,
+            )
+        });
+
+        for (entity, kind, intensity, shadows, transform) in point.chain(spot).chain(directional) {
+            if wanted.len() >= config.max_lights {
+                break;
+            }
+            if config.shows(kind, shadows) {
+                wanted.insert(
+                    entity,
+                    (
+                        format_light_label(kind, intensity, shadows),
+                        transform.translation(),
+                    ),
+                );
+            }
+        }
+    }
+
+    let camera = cameras.iter().find(|(camera, _)| camera.is_active);
+
+    // Update or remove existing labels
+    for (label_entity, label, mut text, mut node, mut font) in &mut labels {
+        let Some((content, position)) = wanted.remove(&label.light) else {
+            commands.entity(label_entity).despawn();
+            continue;
+        };
+        let screen = camera.and_then(|(camera, camera_transform)| {
+            camera.world_to_viewport(camera_transform, position).ok()
+        });
+        match screen {
+            Some(screen) => {
+                node.display = Display::Flex;
+                node.left = Val::Px(screen.x);
+                node.top = Val::Px(screen.y);
+            }
+            None => node.display = Display::None,
+        }
+        if text.0 != content {
+            text.0 = content;
+        }
+        font.font_size = config.label_font_size;
+    }
+
+    // Spawn labels for newly visible lights
+    for (light, (content, _)) in wanted {
+        commands.spawn((
+            Text::new(content),
+            TextFont {
+                font_size: config.label_font_size,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                ..default()
+            },
+            LightLabel { light },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lights_config_json_update() {
+        let mut config = LightsConfig::default();
+
+        let json_config = serde_json::json!({
+            "show_directional": false,
+            "only_shadow_casters": true,
+            "max_lights": 5000,
+            "label_font_size": 200.0
+        });
+
+        assert!(config.update_from_json(&json_config).is_ok());
+        assert!(!config.show_directional);
+        assert!(config.only_shadow_casters);
+        assert_eq!(config.max_lights, MAX_LIGHTS_LIMIT);
+        assert_eq!(config.label_font_size, 72.0);
+
+        assert!(!config.shows(LightKind::Point, false));
+        assert!(config.shows(LightKind::Point, true));
+        assert!(!config.shows(LightKind::Directional, true));
+
+        let invalid = serde_json::json!({"show_point": "yes"});
+        assert!(config.update_from_json(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_light_labels_and_cone() {
+        assert_eq!(
+            format_light_label(LightKind::Point, 800.0, true),
+            "Point 800 lm, shadows"
+        );
+        assert_eq!(
+            format_light_label(LightKind::Directional, 10000.4, false),
+            "Directional 10000 lux, no shadows"
+        );
+
+        let radius = spot_cone_radius(10.0, std::f32::consts::FRAC_PI_4);
+        assert!((radius - 10.0).abs() < 1e-4);
+        assert!(spot_cone_radius(10.0, std::f32::consts::PI).is_finite());
+    }
+}
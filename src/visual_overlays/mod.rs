@@ -12,8 +12,19 @@ pub mod transforms;
 pub mod system_flow;
 pub mod performance_metrics;
 pub mod custom_markers;
+pub mod bounding_boxes;
+pub mod lights;
+pub mod frustum;
+pub mod heatmap;
+pub mod labels;
+pub mod ui_layout;
+pub mod persistence;
+pub mod viewport_target;
+pub mod data_driven;
+pub mod adaptive_quality;
+pub mod hotkeys;
 
-use crate::brp_messages::DebugOverlayType;
+use crate::brp_messages::{DebugOverlayType, OverlayToggleState};
 #[cfg(feature = "visual_overlays")]
 use bevy::prelude::*;
 #[cfg(feature = "visual_overlays")]
@@ -43,6 +54,14 @@ pub trait VisualOverlay: Send + Sync + std::fmt::Debug {
     
     /// Cleanup when the overlay is disabled
     fn cleanup(&mut self);
+    
+    /// Whether the overlay can render at reduced quality
+    fn supports_quality(&self) -> bool {
+        false
+    }
+    
+    /// Set the rendering quality (used by adaptive quality)
+    fn set_quality(&mut self, _level: QualityLevel) {}
 }
 
 /// Performance metrics for individual overlays
@@ -159,8 +178,23 @@ pub struct VisualOverlayManager {
     performance_budget_us: u64,
     /// Total metrics across all overlays
     total_metrics: OverlayMetrics,
+    /// Where overlay state is saved between runs, if anywhere
+    persistence: Option<OverlayPersistence>,
+    /// Overlay state as last saved
+    persisted: PersistedOverlays,
+    /// Cameras overlays are drawn for
+    viewport_target: ViewportTarget,
+    /// Overlays defined at runtime from MCP data
+    data_driven: DataDrivenOverlays,
+    /// Quality reduction when over the performance budget
+    adaptive_quality: AdaptiveQuality,
+    /// Incremented whenever an overlay is enabled or disabled
+    revision: u64,
 }
 
+/// Overlays whose state is transient and never saved
+const NON_PERSISTED_OVERLAYS: &[&str] = &["debug_markers"];
+
 impl VisualOverlayManager {
     /// Create new visual overlay manager
     pub fn new() -> Self {
@@ -169,9 +203,30 @@ impl VisualOverlayManager {
             global_enabled: true,
             performance_budget_us: 2000, // 2ms as per requirements
             total_metrics: OverlayMetrics::default(),
+            persistence: None,
+            persisted: PersistedOverlays::default(),
+            viewport_target: ViewportTarget::All,
+            data_driven: DataDrivenOverlays::default(),
+            adaptive_quality: AdaptiveQuality::new(AdaptiveQualityConfig {
+                enabled: false,
+                ..AdaptiveQualityConfig::default()
+            }),
+            revision: 0,
         }
     }
     
+    /// Save overlay state to `persistence` and restore it on initialize
+    pub fn with_persistence(mut self, persistence: OverlayPersistence) -> Self {
+        self.persistence = Some(persistence);
+        self
+    }
+    
+    /// Automatically lower overlay quality while over the performance budget
+    pub fn with_adaptive_quality(mut self, config: AdaptiveQualityConfig) -> Self {
+        self.adaptive_quality.set_config(config);
+        self
+    }
+    
     /// Initialize the manager with all overlay types
     pub fn initialize(&mut self, app: &mut App) {
         // Register all overlay implementations
@@ -180,12 +235,25 @@ impl VisualOverlayManager {
         self.register_overlay("transforms", Box::new(transforms::TransformsOverlay::new()));
         self.register_overlay("system_flow", Box::new(system_flow::SystemFlowOverlay::new()));
         self.register_overlay("performance_metrics", Box::new(performance_metrics::PerformanceMetricsOverlay::new()));
+        self.register_overlay("debug_markers", Box::new(custom_markers::CustomMarkersOverlay::new()));
+        self.register_overlay("bounding_boxes", Box::new(bounding_boxes::BoundingBoxesOverlay::new()));
+        self.register_overlay("lights", Box::new(lights::LightsOverlay::new()));
+        self.register_overlay("frustum", Box::new(frustum::FrustumOverlay::new()));
+        self.register_overlay("heatmap", Box::new(heatmap::HeatmapOverlay::new()));
+        self.register_overlay("labels", Box::new(labels::LabelsOverlay::new()));
+        self.register_overlay("ui_layout", Box::new(ui_layout::UiLayoutOverlay::new()));
         
         // Initialize all overlays
         for overlay in self.overlays.values_mut() {
             overlay.initialize(app);
         }
         
+        // Data-driven overlays are registered at runtime and drawn by one system
+        app.insert_resource(self.data_driven.clone())
+            .add_systems(Update, data_driven::render_data_driven_overlays);
+        
+        self.restore_persisted_state();
+        
         // Add viewport configuration resource
         app.insert_resource(ViewportConfig::default());
         
@@ -194,6 +262,7 @@ impl VisualOverlayManager {
             Self::update_performance_metrics,
             Self::check_performance_budget.after(Self::update_performance_metrics),
             Self::manage_viewport_config,
+            viewport_target::apply_viewport_target.after(Self::manage_viewport_config),
         ));
     }
     
@@ -211,6 +280,22 @@ impl VisualOverlayManager {
     ) -> Result<(), String> {
         let key = self.overlay_type_to_key(overlay_type);
         
+        if let DebugOverlayType::Custom(name) = overlay_type {
+            let remove = config
+                .and_then(|config| config.get("remove"))
+                .and_then(|remove| remove.as_bool())
+                .unwrap_or(false);
+            if remove {
+                return self.remove_custom_overlay(name, &key);
+            }
+            if !self.overlays.contains_key(&key)
+                && config.is_some_and(|config| config.get("definition").is_some())
+            {
+                info!("Registering data-driven overlay '{}'", name);
+                self.overlays.insert(key.clone(), Box::new(self.data_driven.create(name)));
+            }
+        }
+        
         if let Some(overlay) = self.overlays.get_mut(&key) {
             overlay.set_enabled(enabled);
             
@@ -218,6 +303,9 @@ impl VisualOverlayManager {
                 overlay.update_config(config)?;
             }
             
+            self.record_overlay_state(&key, enabled, config);
+            self.revision += 1;
+            
             info!(
                 "Visual overlay '{}' {} with config: {:?}",
                 key,
@@ -231,6 +319,161 @@ impl VisualOverlayManager {
         }
     }
     
+    /// Unregister a data-driven overlay and forget its saved state
+    fn remove_custom_overlay(&mut self, name: &str, key: &str) -> Result<(), String> {
+        if self.overlays.remove(key).is_none() {
+            return Err(format!("Unknown custom overlay: {}", name));
+        }
+        self.data_driven.remove(name);
+        
+        if self.persisted.overlays.remove(key).is_some() {
+            if let Some(persistence) = &self.persistence {
+                if let Err(e) = persistence.save(&self.persisted) {
+                    warn!("Failed to save overlay state: {}", e);
+                }
+            }
+        }
+        
+        info!("Removed data-driven overlay '{}'", name);
+        Ok(())
+    }
+    
+    /// Apply saved overlay state to the registered overlays
+    fn restore_persisted_state(&mut self) {
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        
+        let state = match persistence.load() {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Not restoring overlay state: {}", e);
+                return;
+            }
+        };
+        
+        for (key, saved) in &state.overlays {
+            // Data-driven overlays are recreated from their saved definition
+            if let Some(name) = key.strip_prefix("custom_") {
+                if !self.overlays.contains_key(key) && saved.config.get("definition").is_some() {
+                    self.overlays.insert(key.clone(), Box::new(self.data_driven.create(name)));
+                }
+            }
+            let Some(overlay) = self.overlays.get_mut(key) else {
+                debug!("Skipping saved state for unknown overlay '{}'", key);
+                continue;
+            };
+            if !saved.config.is_null() {
+                if let Err(e) = overlay.update_config(&saved.config) {
+                    warn!("Saved config for overlay '{}' is invalid: {}", key, e);
+                    continue;
+                }
+            }
+            overlay.set_enabled(saved.enabled);
+        }
+        
+        info!(
+            "Restored state of {} overlays from {}",
+            state.overlays.len(),
+            persistence.path().display()
+        );
+        self.persisted = state;
+    }
+    
+    /// Remember an overlay's state and save it if persistence is enabled
+    fn record_overlay_state(&mut self, key: &str, enabled: bool, config: Option<&serde_json::Value>) {
+        if NON_PERSISTED_OVERLAYS.contains(&key) {
+            return;
+        }
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        
+        let entry = self
+            .persisted
+            .overlays
+            .entry(key.to_string())
+            .or_insert_with(|| PersistedOverlay {
+                enabled,
+                config: serde_json::Value::Null,
+            });
+        entry.enabled = enabled;
+        if let Some(config) = config {
+            merge_config(&mut entry.config, config);
+        }
+        
+        if let Err(e) = persistence.save(&self.persisted) {
+            warn!("Failed to save overlay state: {}", e);
+        }
+    }
+    
+    /// Restrict overlays to some cameras, e.g. only a debug fly camera
+    pub fn set_viewport_target(&mut self, target: ViewportTarget) {
+        info!("Visual overlay viewport target: {:?}", target);
+        self.viewport_target = target;
+    }
+    
+    /// Cameras overlays are currently drawn for
+    pub fn viewport_target(&self) -> &ViewportTarget {
+        &self.viewport_target
+    }
+    
+    /// Change adaptive quality settings; disabling restores full quality
+    pub fn set_adaptive_quality(&mut self, config: AdaptiveQualityConfig) {
+        let enabled = config.enabled;
+        self.adaptive_quality.set_config(config);
+        if !enabled {
+            for key in self.adaptive_quality.reset() {
+                if let Some(overlay) = self.overlays.get_mut(&key) {
+                    overlay.set_quality(QualityLevel::Full);
+                }
+            }
+        }
+    }
+    
+    /// Overlays currently degraded by adaptive quality
+    pub fn adaptive_quality_report(&self) -> Vec<OverlayDegradation> {
+        self.adaptive_quality.report()
+    }
+    
+    /// Run one adaptive quality step against the current metrics
+    fn adapt_quality(&mut self) {
+        let candidates: Vec<OverlayLoad> = self
+            .overlays
+            .iter()
+            .filter(|(_, overlay)| overlay.is_enabled() && overlay.supports_quality())
+            .map(|(key, overlay)| OverlayLoad {
+                key: key.clone(),
+                render_time_us: overlay.get_metrics().render_time_us,
+            })
+            .collect();
+        
+        let change = self.adaptive_quality.evaluate(
+            self.total_metrics.render_time_us,
+            self.performance_budget_us,
+            &candidates,
+        );
+        
+        match change {
+            Some(QualityChange::Degraded { overlay: key, level }) => {
+                if let Some(overlay) = self.overlays.get_mut(&key) {
+                    overlay.set_quality(level);
+                }
+                warn!(
+                    "Over overlay budget ({}μs > {}μs): '{}' reduced to {:?} quality",
+                    self.total_metrics.render_time_us, self.performance_budget_us, key, level
+                );
+            }
+            Some(QualityChange::Restored { overlay: key, level }) => {
+                if let Some(overlay) = self.overlays.get_mut(&key) {
+                    overlay.set_quality(level);
+                }
+                info!("Overlay budget recovered: '{}' restored to {:?} quality", key, level);
+            }
+            None => {}
+        }
+    }
+    
     /// Get overlay status
     pub fn get_overlay_status(&self, overlay_type: &DebugOverlayType) -> Option<(bool, OverlayMetrics)> {
         let key = self.overlay_type_to_key(overlay_type);
@@ -249,6 +492,33 @@ impl VisualOverlayManager {
             .collect()
     }
     
+    /// Disable every enabled overlay
+    pub fn disable_all_overlays(&mut self) {
+        let enabled: Vec<DebugOverlayType> = self
+            .overlays
+            .values()
+            .filter(|overlay| overlay.is_enabled())
+            .map(|overlay| overlay.overlay_type())
+            .collect();
+        for overlay_type in enabled {
+            if let Err(e) = self.set_overlay_enabled(&overlay_type, false, None) {
+                warn!("Failed to disable overlay {:?}: {}", overlay_type, e);
+            }
+        }
+    }
+    
+    /// Enabled flags of all overlays, for keeping the MCP side in sync with hotkeys
+    pub fn overlay_toggle_state(&self) -> OverlayToggleState {
+        OverlayToggleState {
+            revision: self.revision,
+            overlays: self
+                .overlays
+                .iter()
+                .map(|(key, overlay)| (key.clone(), overlay.is_enabled()))
+                .collect(),
+        }
+    }
+    
     /// Get total performance metrics
     pub fn get_total_metrics(&self) -> &OverlayMetrics {
         &self.total_metrics
@@ -270,6 +540,12 @@ impl VisualOverlayManager {
             DebugOverlayType::ColliderVisualization => "collider_visualization".to_string(),
             DebugOverlayType::TransformGizmos => "transform_gizmos".to_string(),
             DebugOverlayType::DebugMarkers => "debug_markers".to_string(),
+            DebugOverlayType::BoundingBoxes => "bounding_boxes".to_string(),
+            DebugOverlayType::Lights => "lights".to_string(),
+            DebugOverlayType::Frustum => "frustum".to_string(),
+            DebugOverlayType::Heatmap => "heatmap".to_string(),
+            DebugOverlayType::Labels => "labels".to_string(),
+            DebugOverlayType::UiLayout => "ui_layout".to_string(),
             DebugOverlayType::Custom(name) => format!("custom_{}", name),
         }
     }
@@ -287,6 +563,7 @@ impl VisualOverlayManager {
         let mut total_memory_usage = 0;
         let mut total_frame_updates = 0;
         let mut any_active = false;
+        let mut viewport_stats: HashMap<String, ViewportRenderStats> = HashMap::new();
         
         for overlay in overlay_manager.overlays.values() {
             let metrics = overlay.get_metrics();
@@ -295,6 +572,13 @@ impl VisualOverlayManager {
             total_memory_usage += metrics.memory_usage_bytes;
             total_frame_updates += metrics.frame_updates;
             any_active |= metrics.active_this_frame;
+            for (viewport_id, stats) in metrics.viewport_stats {
+                let total = viewport_stats.entry(viewport_id).or_default();
+                total.elements_rendered += stats.elements_rendered;
+                total.render_time_us += stats.render_time_us;
+                total.active |= stats.active;
+                total.viewport_size = total.viewport_size.or(stats.viewport_size);
+            }
         }
         
         overlay_manager.total_metrics = OverlayMetrics {
@@ -303,6 +587,7 @@ impl VisualOverlayManager {
             memory_usage_bytes: total_memory_usage,
             frame_updates: total_frame_updates,
             active_this_frame: any_active,
+            viewport_stats,
         };
         
         // Track system execution time
@@ -310,10 +595,12 @@ impl VisualOverlayManager {
         overlay_manager.total_metrics.render_time_us += execution_time;
     }
     
-    /// System to check performance budget and warn if exceeded
+    /// System to check performance budget, warn if exceeded and adapt quality
     fn check_performance_budget(
-        overlay_manager: Res<VisualOverlayManager>,
+        mut overlay_manager: ResMut<VisualOverlayManager>,
     ) {
+        overlay_manager.adapt_quality();
+        
         if overlay_manager.is_performance_budget_exceeded() {
             warn!(
                 "Visual debug overlay performance budget exceeded: {}μs > {}μs",
@@ -359,10 +646,10 @@ impl VisualOverlayManager {
             // Add new viewport if not exists
             if !viewport_config.viewport_overlays.contains_key(&viewport_id) {
                 if viewport_config.viewport_overlays.len() < viewport_config.max_viewports {
-                    viewport_config.viewport_overlays.insert(
-                        viewport_id.clone(),
-                        viewport_config.default_settings.clone(),
-                    );
+                    let settings = viewport_config.default_settings.clone();
+                    viewport_config
+                        .viewport_overlays
+                        .insert(viewport_id.clone(), settings);
                     info!("Auto-detected new viewport: {}", viewport_id);
                 } else {
                     warn!("Maximum viewport limit reached: {}", viewport_config.max_viewports);
@@ -391,16 +678,40 @@ impl Default for VisualOverlayManager {
     }
 }
 
+/// Overlay configs are applied as partial updates, so saved configs are merged key by key
+fn merge_config(saved: &mut serde_json::Value, update: &serde_json::Value) {
+    match (saved.as_object_mut(), update.as_object()) {
+        (Some(saved), Some(update)) => {
+            for (key, value) in update {
+                saved.insert(key.clone(), value.clone());
+            }
+        }
+        _ => *saved = update.clone(),
+    }
+}
+
 /// Plugin to add visual debug overlay support to a Bevy app
 pub struct VisualDebugOverlayPlugin {
     /// Whether to initialize overlays immediately
     pub auto_initialize: bool,
+    /// Save overlay state between runs
+    pub persist_state: bool,
+    /// Key the saved state is stored under; defaults to the executable name
+    pub project_key: Option<String>,
+    /// Lower overlay quality automatically while over budget (off when `None`)
+    pub adaptive_quality: Option<AdaptiveQualityConfig>,
+    /// In-game keybindings for toggling overlays (none when `None`)
+    pub hotkeys: Option<OverlayHotkeys>,
 }
 
 impl Default for VisualDebugOverlayPlugin {
     fn default() -> Self {
         Self {
             auto_initialize: true,
+            persist_state: true,
+            project_key: None,
+            adaptive_quality: None,
+            hotkeys: Some(OverlayHotkeys::default()),
         }
     }
 }
@@ -409,16 +720,54 @@ impl Plugin for VisualDebugOverlayPlugin {
     fn build(&self, app: &mut App) {
         if self.auto_initialize {
             let mut manager = VisualOverlayManager::new();
+            if self.persist_state {
+                let project_key = self
+                    .project_key
+                    .clone()
+                    .unwrap_or_else(OverlayPersistence::default_project_key);
+                manager = manager.with_persistence(OverlayPersistence::for_project(&project_key));
+            }
+            if let Some(config) = &self.adaptive_quality {
+                manager = manager.with_adaptive_quality(config.clone());
+            }
             manager.initialize(app);
             app.insert_resource(manager);
         } else {
             app.insert_resource(VisualOverlayManager::new());
         }
+        
+        if let Some(hotkeys) = &self.hotkeys {
+            app.insert_resource(hotkeys.clone())
+                .add_systems(Update, hotkeys::handle_overlay_hotkeys);
+        }
     }
 }
 
 // Re-export overlay implementations
 pub use entity_highlight::{EntityHighlightOverlay, HighlightedEntity, HighlightMode, HighlightConfig};
+pub use bounding_boxes::{BoundingBoxesOverlay, BoundingBoxConfig, BoundingBoxColorRule};
+pub use colliders::{CollidersOverlay, CollidersConfig, ColliderGeometry, DebugCollider};
+pub use transforms::{TransformManipulation, TransformsConfig, TransformsOverlay};
+pub use performance_metrics::{
+    MetricHistory, PerformanceGraph, PerformanceMetricsConfig, PerformanceMetricsOverlay,
+};
+pub use system_flow::{
+    system_timing_layer, SystemFlowConfig, SystemFlowOverlay, SystemTimingEntry, SystemTimingLayer,
+    SystemTimings,
+};
+pub use lights::{LightsOverlay, LightsConfig};
+pub use frustum::{FrustumOverlay, FrustumConfig};
+pub use heatmap::{HeatmapOverlay, HeatmapConfig};
+pub use labels::{LabelsOverlay, LabelsConfig, LabelField};
+pub use ui_layout::{UiLayoutOverlay, UiLayoutConfig};
+pub use persistence::{OverlayPersistence, PersistedOverlay, PersistedOverlays};
+pub use viewport_target::{ViewportTarget, OVERLAY_RENDER_LAYER};
+pub use data_driven::{DataDrivenOverlay, DataDrivenOverlays};
+pub use hotkeys::{HotkeyAction, KeyBinding, OverlayHotkeys};
+pub use adaptive_quality::{
+    AdaptiveQuality, AdaptiveQualityConfig, OverlayDegradation, OverlayLoad, QualityChange, QualityHandle,
+    QualityLevel,
+};
 
 #[cfg(test)]
 mod tests {
@@ -440,6 +789,70 @@ mod tests {
             manager.overlay_type_to_key(&DebugOverlayType::Custom("test".to_string())),
             "custom_test"
         );
+        assert_eq!(
+            manager.overlay_type_to_key(&DebugOverlayType::BoundingBoxes),
+            "bounding_boxes"
+        );
+    }
+
+    #[test]
+    fn test_overlay_state_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = OverlayPersistence::in_dir(dir.path(), "test_game");
+        
+        let mut manager = VisualOverlayManager::new().with_persistence(persistence.clone());
+        manager.register_overlay("bounding_boxes", Box::new(bounding_boxes::BoundingBoxesOverlay::new()));
+        manager.register_overlay("debug_markers", Box::new(custom_markers::CustomMarkersOverlay::new()));
+        
+        let boxes = DebugOverlayType::BoundingBoxes;
+        manager.set_overlay_enabled(&boxes, true, Some(&serde_json::json!({"max_boxes": 10}))).unwrap();
+        manager.set_overlay_enabled(&boxes, true, Some(&serde_json::json!({"show_centers": true}))).unwrap();
+        manager.set_overlay_enabled(&DebugOverlayType::DebugMarkers, true, None).unwrap();
+        
+        let saved = persistence.load().unwrap();
+        assert_eq!(saved.overlays.len(), 1); // Markers are transient
+        assert_eq!(
+            saved.overlays["bounding_boxes"].config,
+            serde_json::json!({"max_boxes": 10, "show_centers": true})
+        );
+        
+        // A fresh manager restores the saved state
+        let mut restored = VisualOverlayManager::new().with_persistence(persistence);
+        restored.register_overlay("bounding_boxes", Box::new(bounding_boxes::BoundingBoxesOverlay::new()));
+        restored.restore_persisted_state();
+        let (enabled, _) = restored.get_overlay_status(&boxes).unwrap();
+        assert!(enabled);
+    }
+
+    #[test]
+    fn test_data_driven_overlay_lifecycle() {
+        let mut manager = VisualOverlayManager::new();
+        let overlay_type = DebugOverlayType::Custom("enemies".to_string());
+        
+        // Unknown until a definition arrives
+        assert!(manager.set_overlay_enabled(&overlay_type, true, None).is_err());
+        
+        let definition = serde_json::json!({
+            "definition": {"name": "enemies", "shapes": [{"kind": "sphere"}]}
+        });
+        manager.set_overlay_enabled(&overlay_type, true, Some(&definition)).unwrap();
+        assert_eq!(manager.get_overlay_status(&overlay_type).map(|(enabled, _)| enabled), Some(true));
+        assert_eq!(manager.data_driven.names(), vec!["enemies".to_string()]);
+        
+        manager
+            .set_overlay_enabled(&overlay_type, false, Some(&serde_json::json!({"remove": true})))
+            .unwrap();
+        assert!(manager.get_overlay_status(&overlay_type).is_none());
+        assert!(manager.data_driven.names().is_empty());
+    }
+
+    #[test]
+    fn test_viewport_target() {
+        let mut manager = VisualOverlayManager::new();
+        assert_eq!(manager.viewport_target(), &ViewportTarget::All);
+        
+        manager.set_viewport_target(ViewportTarget::Exclude(vec!["PlayerCamera".to_string()]));
+        assert!(!manager.viewport_target().includes("camera_0", Some("PlayerCamera")));
     }
 
     #[test]
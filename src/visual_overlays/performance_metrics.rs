@@ -1,26 +1,309 @@
 /// Performance Metrics Overlay Implementation
-/// 
-/// Provides on-screen display of performance metrics like FPS, frame time,
-/// memory usage, and system-specific performance data.
+///
+/// Shows an on-screen HUD with scrolling graphs of frame time, entity count
+/// and process memory. Each graph has an optional budget line, and samples
+/// that spike above the recent average are highlighted. Graphs, history
+/// length, budgets and layout are configurable through the overlay config JSON.
 
-use super::{OverlayMetrics, VisualOverlay};
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
 use crate::brp_messages::DebugOverlayType;
+use bevy::ecs::entity::Entities;
 use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
 
-/// Performance Metrics Overlay implementation (stub for now)
+/// Upper bound on samples kept per graph
+const MAX_HISTORY_LIMIT: usize = 600;
+
+/// Frames between process memory samples; refreshing process info is costly
+const MEMORY_SAMPLE_INTERVAL: u32 = 30;
+
+/// Metric shown in a HUD graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PerformanceGraph {
+    FrameTime,
+    EntityCount,
+    Memory,
+}
+
+impl PerformanceGraph {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "frame_time" => Ok(PerformanceGraph::FrameTime),
+            "entity_count" | "entities" => Ok(PerformanceGraph::EntityCount),
+            "memory" => Ok(PerformanceGraph::Memory),
+            other => Err(format!("Unknown performance graph: {}", other)),
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            PerformanceGraph::FrameTime => "Frame time",
+            PerformanceGraph::EntityCount => "Entities",
+            PerformanceGraph::Memory => "Memory",
+        }
+    }
+
+    fn format_value(self, value: f32) -> String {
+        match self {
+            PerformanceGraph::FrameTime => format!("{:.2} ms", value),
+            PerformanceGraph::EntityCount => format!("{:.0}", value),
+            PerformanceGraph::Memory => format!("{:.1} MB", value),
+        }
+    }
+}
+
+/// Ring buffer of samples for one graph
+#[derive(Debug, Clone, Default)]
+pub struct MetricHistory {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl MetricHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, value: f32) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().copied()
+    }
+
+    pub fn max(&self) -> f32 {
+        self.samples.iter().copied().fold(0.0, f32::max)
+    }
+
+    pub fn average(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    /// Whether a sample exceeds the history average by `spike_ratio`
+    pub fn is_spike(&self, value: f32, spike_ratio: f32) -> bool {
+        self.samples.len() > 1 && value > self.average() * spike_ratio
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = f32> + '_ {
+        self.samples.iter().copied()
+    }
+}
+
+/// Configuration for the performance HUD
+#[derive(Debug, Clone)]
+pub struct PerformanceMetricsConfig {
+    /// Graphs shown, top to bottom
+    pub graphs: Vec<PerformanceGraph>,
+    /// Samples kept per graph (one per frame)
+    pub history_length: usize,
+    pub frame_budget_ms: Option<f32>,
+    pub entity_budget: Option<f32>,
+    pub memory_budget_mb: Option<f32>,
+    /// Samples above `average * spike_ratio` are marked as spikes
+    pub spike_ratio: f32,
+    pub graph_width: f32,
+    pub graph_height: f32,
+    /// Top-left corner of the HUD in pixels
+    pub position: Vec2,
+    pub graph_color: Color,
+    pub spike_color: Color,
+    pub budget_color: Color,
+}
+
+impl Default for PerformanceMetricsConfig {
+    fn default() -> Self {
+        Self {
+            graphs: vec![
+                PerformanceGraph::FrameTime,
+                PerformanceGraph::EntityCount,
+                PerformanceGraph::Memory,
+            ],
+            history_length: 120,
+            frame_budget_ms: Some(16.67),
+            entity_budget: None,
+            memory_budget_mb: None,
+            spike_ratio: 1.5,
+            graph_width: 240.0,
+            graph_height: 50.0,
+            position: Vec2::new(10.0, 10.0),
+            graph_color: Color::srgb(0.3, 0.9, 0.4),
+            spike_color: Color::srgb(1.0, 0.2, 0.2),
+            budget_color: Color::srgb(1.0, 0.9, 0.2),
+        }
+    }
+}
+
+impl PerformanceMetricsConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(graphs) = config.get("graphs").and_then(|v| v.as_array()) {
+            self.graphs = graphs
+                .iter()
+                .map(|graph| {
+                    graph
+                        .as_str()
+                        .ok_or_else(|| "Graph names must be strings".to_string())
+                        .and_then(PerformanceGraph::parse)
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(length) = config.get("history_length").and_then(|v| v.as_u64()) {
+            if length < 2 {
+                return Err("history_length must be at least 2".to_string());
+            }
+            self.history_length = (length as usize).min(MAX_HISTORY_LIMIT);
+        }
+        // A null budget removes the budget line
+        if let Some(budget) = config.get("frame_budget_ms") {
+            self.frame_budget_ms = budget.as_f64().map(|v| v as f32);
+        }
+        if let Some(budget) = config.get("entity_budget") {
+            self.entity_budget = budget.as_f64().map(|v| v as f32);
+        }
+        if let Some(budget) = config.get("memory_budget_mb") {
+            self.memory_budget_mb = budget.as_f64().map(|v| v as f32);
+        }
+        if let Some(ratio) = config.get("spike_ratio").and_then(|v| v.as_f64()) {
+            if ratio <= 1.0 {
+                return Err("spike_ratio must be greater than 1".to_string());
+            }
+            self.spike_ratio = ratio as f32;
+        }
+        if let Some(width) = config.get("graph_width").and_then(|v| v.as_f64()) {
+            self.graph_width = (width as f32).max(20.0);
+        }
+        if let Some(height) = config.get("graph_height").and_then(|v| v.as_f64()) {
+            self.graph_height = (height as f32).max(10.0);
+        }
+        if let Some(position) = config.get("position").and_then(|v| v.as_array()) {
+            let coord = |i: usize| position.get(i).and_then(|v| v.as_f64());
+            match (coord(0), coord(1)) {
+                (Some(x), Some(y)) => self.position = Vec2::new(x as f32, y as f32),
+                _ => return Err("position must be [x, y]".to_string()),
+            }
+        }
+        if let Some(color) = config.get("graph_color") {
+            self.graph_color = super::bounding_boxes::parse_color(color)?;
+        }
+        if let Some(color) = config.get("spike_color") {
+            self.spike_color = super::bounding_boxes::parse_color(color)?;
+        }
+        if let Some(color) = config.get("budget_color") {
+            self.budget_color = super::bounding_boxes::parse_color(color)?;
+        }
+        Ok(())
+    }
+
+    pub fn budget(&self, graph: PerformanceGraph) -> Option<f32> {
+        match graph {
+            PerformanceGraph::FrameTime => self.frame_budget_ms,
+            PerformanceGraph::EntityCount => self.entity_budget,
+            PerformanceGraph::Memory => self.memory_budget_mb,
+        }
+    }
+}
+
+/// Root node of the HUD
+#[derive(Component, Debug)]
+struct PerformanceHud {
+    generation: u64,
+}
+
+/// Title text of a graph
+#[derive(Component, Debug)]
+struct GraphTitle(PerformanceGraph);
+
+/// One column of a graph; index 0 is the oldest sample
+#[derive(Component, Debug)]
+struct GraphBar {
+    graph: PerformanceGraph,
+    index: usize,
+}
+
+/// Budget line of a graph
+#[derive(Component, Debug)]
+struct BudgetLine(PerformanceGraph);
+
+/// State shared between the overlay and its systems
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PerformanceMetricsSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<PerformanceMetricsConfig>>,
+    /// Bumped on every config change so the HUD layout is rebuilt
+    generation: Arc<AtomicU64>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Sample histories, kept while the overlay is disabled
+#[derive(Resource, Debug, Default)]
+struct PerformanceHistory {
+    frame_time: MetricHistory,
+    entity_count: MetricHistory,
+    memory: MetricHistory,
+}
+
+impl PerformanceHistory {
+    fn get(&self, graph: PerformanceGraph) -> &MetricHistory {
+        match graph {
+            PerformanceGraph::FrameTime => &self.frame_time,
+            PerformanceGraph::EntityCount => &self.entity_count,
+            PerformanceGraph::Memory => &self.memory,
+        }
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        for history in [
+            &mut self.frame_time,
+            &mut self.entity_count,
+            &mut self.memory,
+        ] {
+            if history.capacity != capacity {
+                let mut resized = MetricHistory::new(capacity);
+                let skip = history.samples.len().saturating_sub(capacity);
+                history.samples().skip(skip).for_each(|v| resized.push(v));
+                *history = resized;
+            }
+        }
+    }
+}
+
+/// Performance Metrics Overlay implementation
 #[derive(Debug)]
 pub struct PerformanceMetricsOverlay {
-    enabled: bool,
+    settings: PerformanceMetricsSettings,
     metrics: OverlayMetrics,
 }
 
 impl PerformanceMetricsOverlay {
     pub fn new() -> Self {
         Self {
-            enabled: false,
+            settings: PerformanceMetricsSettings::default(),
             metrics: OverlayMetrics::default(),
         }
     }
+
+    /// Current configuration
+    pub fn config(&self) -> PerformanceMetricsConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl Default for PerformanceMetricsOverlay {
@@ -30,31 +313,325 @@ impl Default for PerformanceMetricsOverlay {
 }
 
 impl VisualOverlay for PerformanceMetricsOverlay {
-    fn initialize(&mut self, _app: &mut App) {
-        info!("Performance metrics overlay initialized (placeholder)");
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .init_resource::<PerformanceHistory>()
+            .add_systems(Update, (sample_performance, update_performance_hud).chain());
+
+        info!("Performance metrics overlay initialized");
     }
-    
-    fn update_config(&mut self, _config: &serde_json::Value) -> Result<(), String> {
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Performance metrics config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        self.settings.generation.fetch_add(1, Ordering::Relaxed);
         Ok(())
     }
-    
+
     fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
     }
-    
+
     fn is_enabled(&self) -> bool {
-        self.enabled
+        self.settings.enabled.load(Ordering::Relaxed)
     }
-    
+
     fn get_metrics(&self) -> OverlayMetrics {
-        self.metrics.clone()
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 64, // UI node per graph column
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
     }
-    
+
     fn overlay_type(&self) -> DebugOverlayType {
         DebugOverlayType::PerformanceMetrics
     }
-    
+
     fn cleanup(&mut self) {
-        // Placeholder
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// System to record one sample per graph each frame
+fn sample_performance(
+    settings: Res<PerformanceMetricsSettings>,
+    mut history: ResMut<PerformanceHistory>,
+    time: Res<Time<Real>>,
+    entities: &Entities,
+    mut system: Local<Option<sysinfo::System>>,
+    mut frame: Local<u32>,
+    mut memory_mb: Local<f32>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Ok(config) = settings.config.read() {
+        history.resize(config.history_length);
+    }
+
+    if *frame % MEMORY_SAMPLE_INTERVAL == 0 {
+        let system = system.get_or_insert_with(sysinfo::System::new);
+        let pid = sysinfo::Pid::from_u32(std::process::id());
+        if system.refresh_process(pid) {
+            if let Some(process) = system.process(pid) {
+                *memory_mb = process.memory() as f32 / (1024.0 * 1024.0);
+            }
+        }
     }
-}
\ No newline at end of file
+    *frame = frame.wrapping_add(1);
+
+    history.frame_time.push(time.delta().as_secs_f32() * 1000.0);
+    history.entity_count.push(entities.len() as f32);
+    history.memory.push(*memory_mb);
+}
+
+/// System to build the HUD and update graph columns from the histories
+fn update_performance_hud(
+    mut commands: Commands,
+    settings: Res<PerformanceMetricsSettings>,
+    history: Res<PerformanceHistory>,
+    huds: Query<(Entity, &PerformanceHud)>,
+    mut titles: Query<(&GraphTitle, &mut Text)>,
+    mut bars: Query<(&GraphBar, &mut Node, &mut BackgroundColor), Without<BudgetLine>>,
+    mut budget_lines: Query<(&BudgetLine, &mut Node), Without<GraphBar>>,
+    mut frame: Local<u32>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        for (hud, _) in &huds {
+            commands.entity(hud).despawn();
+        }
+        return;
+    }
+    let config = match settings.config.read() {
+        Ok(config) => config.clone(),
+        Err(_) => return,
+    };
+
+    // Rebuild the layout after config changes; columns are filled next frame
+    let generation = settings.generation.load(Ordering::Relaxed);
+    let current = huds.iter().find(|(_, hud)| hud.generation == generation);
+    if current.is_none() {
+        for (hud, _) in &huds {
+            commands.entity(hud).despawn();
+        }
+        spawn_hud(&mut commands, &config, generation);
+        return;
+    }
+
+    *frame = frame.wrapping_add(1);
+    if !settings.quality.should_update(*frame) {
+        return;
+    }
+    let start_time = std::time::Instant::now();
+
+    let scales: Vec<(PerformanceGraph, f32)> = config
+        .graphs
+        .iter()
+        .map(|&graph| {
+            let peak = history
+                .get(graph)
+                .max()
+                .max(config.budget(graph).unwrap_or(0.0));
+            (graph, (peak * 1.1).max(f32::EPSILON))
+        })
+        .collect();
+    let scale_of = |graph: PerformanceGraph| {
+        scales
+            .iter()
+            .find(|(g, _)| *g == graph)
+            .map(|(_, scale)| *scale)
+            .unwrap_or(1.0)
+    };
+
+    for (title, mut text) in &mut titles {
+        let graph_history = history.get(title.0);
+        let latest = graph_history.latest().unwrap_or(0.0);
+        let content = format!(
+            "{}: {} (avg {}, max {})",
+            title.0.title(),
+            title.0.format_value(latest),
+            title.0.format_value(graph_history.average()),
+            title.0.format_value(graph_history.max())
+        );
+        if text.0 != content {
+            text.0 = content;
+        }
+    }
+
+    let mut rendered = 0;
+    for (bar, mut node, mut color) in &mut bars {
+        let graph_history = history.get(bar.graph);
+        // Right-align samples so the newest is always the last column
+        let offset = config.history_length - graph_history.samples.len().min(config.history_length);
+        let value = bar
+            .index
+            .checked_sub(offset)
+            .and_then(|i| graph_history.samples.get(i).copied());
+        let Some(value) = value else {
+            node.height = Val::Px(0.0);
+            continue;
+        };
+
+        node.height = Val::Percent((value / scale_of(bar.graph) * 100.0).min(100.0));
+        let spike = graph_history.is_spike(value, config.spike_ratio)
+            || config
+                .budget(bar.graph)
+                .is_some_and(|budget| value > budget);
+        color.0 = if spike {
+            config.spike_color
+        } else {
+            config.graph_color
+        };
+        rendered += 1;
+    }
+
+    for (line, mut node) in &mut budget_lines {
+        match config.budget(line.0) {
+            Some(budget) => {
+                node.display = Display::Flex;
+                node.bottom = Val::Percent((budget / scale_of(line.0) * 100.0).min(100.0));
+            }
+            None => node.display = Display::None,
+        }
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+    settings
+        .render_time_us
+        .store(start_time.elapsed().as_micros() as u64, Ordering::Relaxed);
+}
+
+fn spawn_hud(commands: &mut Commands, config: &PerformanceMetricsConfig, generation: u64) {
+    let bar_width = config.graph_width / config.history_length as f32;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(config.position.x),
+                top: Val::Px(config.position.y),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            PerformanceHud { generation },
+        ))
+        .with_children(|hud| {
+            for &graph in &config.graphs {
+                hud.spawn((
+                    Text::new(graph.title()),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    GraphTitle(graph),
+                ));
+                hud.spawn((
+                    Node {
+                        width: Val::Px(config.graph_width),
+                        height: Val::Px(config.graph_height),
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.05)),
+                ))
+                .with_children(|chart| {
+                    for index in 0..config.history_length {
+                        chart.spawn((
+                            Node {
+                                width: Val::Px(bar_width),
+                                height: Val::Px(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(config.graph_color),
+                            GraphBar { graph, index },
+                        ));
+                    }
+                    chart.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Percent(100.0),
+                            height: Val::Px(1.0),
+                            display: Display::None,
+                            ..default()
+                        },
+                        BackgroundColor(config.budget_color),
+                        BudgetLine(graph),
+                    ));
+                });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_history_and_spikes() {
+        let mut history = MetricHistory::new(3);
+        for value in [10.0, 10.0, 10.0, 40.0] {
+            history.push(value);
+        }
+        assert_eq!(
+            history.samples().collect::<Vec<_>>(),
+            vec![10.0, 10.0, 40.0]
+        );
+        assert_eq!(history.latest(), Some(40.0));
+        assert_eq!(history.max(), 40.0);
+        assert!(history.is_spike(40.0, 1.5));
+        assert!(!history.is_spike(10.0, 1.5));
+    }
+
+    #[test]
+    fn test_performance_config_json_update() {
+        let mut config = PerformanceMetricsConfig::default();
+        config
+            .update_from_json(&serde_json::json!({
+                "graphs": ["frame_time", "memory"],
+                "history_length": 5000,
+                "frame_budget_ms": null,
+                "memory_budget_mb": 512
+            }))
+            .unwrap();
+
+        assert_eq!(
+            config.graphs,
+            vec![PerformanceGraph::FrameTime, PerformanceGraph::Memory]
+        );
+        assert_eq!(config.history_length, MAX_HISTORY_LIMIT);
+        assert_eq!(config.budget(PerformanceGraph::FrameTime), None);
+        assert_eq!(config.budget(PerformanceGraph::Memory), Some(512.0));
+
+        assert!(config
+            .update_from_json(&serde_json::json!({"graphs": ["gpu"]}))
+            .is_err());
+        assert!(config
+            .update_from_json(&serde_json::json!({"spike_ratio": 0.5}))
+            .is_err());
+    }
+}
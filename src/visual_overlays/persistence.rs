@@ -0,0 +1,175 @@
+/// Overlay Configuration Persistence
+///
+/// Saves which overlays are enabled, together with the last configuration
+/// applied to each, to a JSON file keyed by game/project. The manager restores
+/// this state when it initializes so favorite overlays survive restarts.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the directory overlay state is stored in
+pub const OVERLAY_STATE_DIR_ENV: &str = "BEVY_DEBUGGER_OVERLAY_DIR";
+
+/// Default directory (relative to the working directory) for overlay state
+pub const DEFAULT_OVERLAY_STATE_DIR: &str = ".bevy_debugger/overlays";
+
+/// Current file format version
+const PERSISTED_VERSION: u32 = 1;
+
+/// Saved state of a single overlay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedOverlay {
+    pub enabled: bool,
+    /// Last configuration applied; `null` if never configured
+    #[serde(default)]
+    pub config: serde_json::Value,
+}
+
+/// Saved state of all overlays for one project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedOverlays {
+    pub version: u32,
+    /// Overlay state by overlay key (e.g. `bounding_boxes`)
+    pub overlays: HashMap<String, PersistedOverlay>,
+}
+
+impl Default for PersistedOverlays {
+    fn default() -> Self {
+        Self {
+            version: PERSISTED_VERSION,
+            overlays: HashMap::new(),
+        }
+    }
+}
+
+/// File-backed store for overlay state of one game/project
+#[derive(Debug, Clone)]
+pub struct OverlayPersistence {
+    path: PathBuf,
+}
+
+impl OverlayPersistence {
+    /// Store for `project` in the default directory (or `BEVY_DEBUGGER_OVERLAY_DIR`)
+    pub fn for_project(project: &str) -> Self {
+        let dir = std::env::var(OVERLAY_STATE_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_OVERLAY_STATE_DIR));
+        Self::in_dir(&dir, project)
+    }
+
+    /// Store for `project` in a specific directory
+    pub fn in_dir(dir: &Path, project: &str) -> Self {
+        Self {
+            path: dir.join(format!("{}.json", sanitize_project_key(project))),
+        }
+    }
+
+    /// Project key derived from the running executable's name
+    pub fn default_project_key() -> String {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "default".to_string())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load saved state; a missing file yields empty state
+    pub fn load(&self) -> Result<PersistedOverlays, String> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(PersistedOverlays::default())
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", self.path.display(), e)),
+        };
+
+        let state: PersistedOverlays = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid overlay state in {}: {}", self.path.display(), e))?;
+        if state.version > PERSISTED_VERSION {
+            return Err(format!(
+                "Overlay state in {} has unsupported version {}",
+                self.path.display(),
+                state.version
+            ));
+        }
+        Ok(state)
+    }
+
+    /// Save state, writing to a temporary file first so a crash never leaves a partial file
+    pub fn save(&self, state: &PersistedOverlays) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+
+        let contents = serde_json::to_string_pretty(state)
+            .map_err(|e| format!("Failed to serialize overlay state: {}", e))?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+        std::fs::rename(&tmp_path, &self.path)
+            .map_err(|e| format!("Failed to replace {}: {}", self.path.display(), e))
+    }
+}
+
+/// Restrict a project key to characters that are safe in a file name
+fn sanitize_project_key(project: &str) -> String {
+    let key: String = project
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if key.is_empty() {
+        "default".to_string()
+    } else {
+        key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = OverlayPersistence::in_dir(dir.path(), "my game/../x");
+        assert_eq!(persistence.path().file_name().unwrap(), "my_game____x.json");
+
+        // Missing file is empty state
+        assert!(persistence.load().unwrap().overlays.is_empty());
+
+        let mut state = PersistedOverlays::default();
+        state.overlays.insert(
+            "bounding_boxes".to_string(),
+            PersistedOverlay {
+                enabled: true,
+                config: serde_json::json!({"max_boxes": 10}),
+            },
+        );
+        persistence.save(&state).unwrap();
+
+        assert_eq!(persistence.load().unwrap(), state);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let persistence = OverlayPersistence::in_dir(dir.path(), "game");
+
+        std::fs::write(persistence.path(), "not json").unwrap();
+        assert!(persistence.load().is_err());
+
+        std::fs::write(persistence.path(), r#"{"version": 99, "overlays": {}}"#).unwrap();
+        assert!(persistence.load().is_err());
+    }
+}
@@ -1,26 +1,345 @@
 /// System Execution Flow Visualization Overlay Implementation
-/// 
-/// Provides visual representation of system execution order, dependencies,
-/// and performance metrics in real-time.
+///
+/// Shows the previous frame's system execution timeline as an on-screen bar
+/// chart: one row per system in execution order, with each bar placed at the
+/// system's start offset and sized by its duration. Timings come from the
+/// `system` spans Bevy emits with its `trace` feature, captured by
+/// `SystemTimingLayer` (install with `LogPlugin { custom_layer: system_timing_layer, .. }`),
+/// or are streamed in by the profiler through the overlay config `timeline`.
 
-use super::{OverlayMetrics, VisualOverlay};
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
 use crate::brp_messages::DebugOverlayType;
+use bevy::log::tracing::field::{Field, Visit};
+use bevy::log::tracing::span::{Attributes, Id};
+use bevy::log::tracing::Subscriber;
+use bevy::log::tracing_subscriber::layer::{Context, Layer};
+use bevy::log::tracing_subscriber::registry::LookupSpan;
+use bevy::log::BoxedLayer;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
-/// System Flow Overlay implementation (stub for now)
+/// Upper bound on systems shown in the chart
+const MAX_TOP_N_LIMIT: usize = 50;
+
+/// Span name Bevy uses for system execution
+const SYSTEM_SPAN_NAME: &str = "system";
+
+/// One system run within a frame
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemTimingEntry {
+    pub name: String,
+    /// Offset from the start of the frame
+    pub start_us: u64,
+    pub duration_us: u64,
+}
+
+#[derive(Debug)]
+struct TimingFrames {
+    frame_start: Instant,
+    current: Vec<SystemTimingEntry>,
+    last: Vec<SystemTimingEntry>,
+}
+
+impl Default for TimingFrames {
+    fn default() -> Self {
+        Self {
+            frame_start: Instant::now(),
+            current: Vec::new(),
+            last: Vec::new(),
+        }
+    }
+}
+
+/// Per-frame system timings, shared between the timing layer and the overlay
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SystemTimings {
+    frames: Arc<Mutex<TimingFrames>>,
+}
+
+impl SystemTimings {
+    /// Record a system run in the frame being measured
+    pub fn record(&self, name: &str, started: Instant, duration_us: u64) {
+        if let Ok(mut frames) = self.frames.lock() {
+            let start_us = started
+                .saturating_duration_since(frames.frame_start)
+                .as_micros() as u64;
+            frames.current.push(SystemTimingEntry {
+                name: name.to_string(),
+                start_us,
+                duration_us,
+            });
+        }
+    }
+
+    /// Complete the frame being measured and start the next one. Frames
+    /// without recorded systems keep the previous (or profiler-provided) timeline.
+    pub fn finish_frame(&self) {
+        if let Ok(mut frames) = self.frames.lock() {
+            let mut finished = std::mem::take(&mut frames.current);
+            if !finished.is_empty() {
+                finished.sort_by_key(|entry| entry.start_us);
+                frames.last = finished;
+            }
+            frames.frame_start = Instant::now();
+        }
+    }
+
+    /// Replace the last frame with timings from an external profiler
+    pub fn set_last_frame(&self, mut entries: Vec<SystemTimingEntry>) {
+        entries.sort_by_key(|entry| entry.start_us);
+        if let Ok(mut frames) = self.frames.lock() {
+            frames.last = entries;
+        }
+    }
+
+    /// Timings of the last completed frame, in execution order
+    pub fn last_frame(&self) -> Vec<SystemTimingEntry> {
+        self.frames
+            .lock()
+            .map(|frames| frames.last.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Merge repeated runs of a system and keep the `top_n` slowest, in execution order
+pub fn top_systems(entries: &[SystemTimingEntry], top_n: usize) -> Vec<SystemTimingEntry> {
+    let mut merged: Vec<SystemTimingEntry> = Vec::new();
+    let mut index_by_name: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        match index_by_name.get(entry.name.as_str()) {
+            Some(&index) => {
+                let system = &mut merged[index];
+                system.start_us = system.start_us.min(entry.start_us);
+                system.duration_us += entry.duration_us;
+            }
+            None => {
+                index_by_name.insert(&entry.name, merged.len());
+                merged.push(entry.clone());
+            }
+        }
+    }
+
+    let mut by_duration: Vec<usize> = (0..merged.len()).collect();
+    by_duration.sort_by_key(|&index| std::cmp::Reverse(merged[index].duration_us));
+    by_duration.truncate(top_n);
+    by_duration.sort_by_key(|&index| merged[index].start_us);
+    by_duration
+        .into_iter()
+        .map(|index| merged[index].clone())
+        .collect()
+}
+
+/// Span data kept for a Bevy system span
+struct SystemSpan {
+    name: String,
+    entered: Option<Instant>,
+}
+
+#[derive(Default)]
+struct SystemNameVisitor(Option<String>);
+
+impl Visit for SystemNameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+/// Tracing layer that records Bevy system spans into `SystemTimings`
+pub struct SystemTimingLayer {
+    timings: SystemTimings,
+}
+
+impl SystemTimingLayer {
+    pub fn new(timings: SystemTimings) -> Self {
+        Self { timings }
+    }
+}
+
+impl<S> Layer<S> for SystemTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != SYSTEM_SPAN_NAME {
+            return;
+        }
+        let mut visitor = SystemNameVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SystemSpan {
+                name,
+                entered: None,
+            });
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(system) = span.extensions_mut().get_mut::<SystemSpan>() {
+                system.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(system) = span.extensions_mut().get_mut::<SystemSpan>() {
+                if let Some(entered) = system.entered.take() {
+                    self.timings.record(
+                        &system.name,
+                        entered,
+                        entered.elapsed().as_micros() as u64,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// `LogPlugin::custom_layer` hook that installs `SystemTimingLayer`
+pub fn system_timing_layer(app: &mut App) -> Option<BoxedLayer> {
+    let timings = app
+        .world_mut()
+        .get_resource_or_insert_with(SystemTimings::default)
+        .clone();
+    Some(Box::new(SystemTimingLayer::new(timings)))
+}
+
+/// Configuration for the system flow overlay
+#[derive(Debug, Clone)]
+pub struct SystemFlowConfig {
+    /// Number of slowest systems shown
+    pub top_n: usize,
+    /// Systems faster than this are hidden
+    pub min_duration_us: u64,
+    /// Systems slower than this are drawn in `over_budget_color`
+    pub system_budget_us: u64,
+    /// Chart width in pixels
+    pub chart_width: f32,
+    /// Top-left corner of the chart in pixels
+    pub position: Vec2,
+    pub font_size: f32,
+    pub bar_color: Color,
+    pub over_budget_color: Color,
+}
+
+impl Default for SystemFlowConfig {
+    fn default() -> Self {
+        Self {
+            top_n: 10,
+            min_duration_us: 0,
+            system_budget_us: 1000,
+            chart_width: 300.0,
+            position: Vec2::new(10.0, 10.0),
+            font_size: 12.0,
+            bar_color: Color::srgb(0.3, 0.6, 1.0),
+            over_budget_color: Color::srgb(1.0, 0.3, 0.2),
+        }
+    }
+}
+
+impl SystemFlowConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(top_n) = config.get("top_n").and_then(|v| v.as_u64()) {
+            if top_n == 0 {
+                return Err("top_n must be at least 1".to_string());
+            }
+            self.top_n = (top_n as usize).min(MAX_TOP_N_LIMIT);
+        }
+        if let Some(min) = config.get("min_duration_us").and_then(|v| v.as_u64()) {
+            self.min_duration_us = min;
+        }
+        if let Some(budget) = config.get("system_budget_us").and_then(|v| v.as_u64()) {
+            self.system_budget_us = budget;
+        }
+        if let Some(width) = config.get("chart_width").and_then(|v| v.as_f64()) {
+            if width <= 0.0 {
+                return Err("chart_width must be positive".to_string());
+            }
+            self.chart_width = width as f32;
+        }
+        if let Some(position) = config.get("position").and_then(|v| v.as_array()) {
+            let coord = |i: usize| position.get(i).and_then(|v| v.as_f64());
+            match (coord(0), coord(1)) {
+                (Some(x), Some(y)) => self.position = Vec2::new(x as f32, y as f32),
+                _ => return Err("position must be [x, y]".to_string()),
+            }
+        }
+        if let Some(size) = config.get("font_size").and_then(|v| v.as_f64()) {
+            self.font_size = size as f32;
+        }
+        if let Some(color) = config.get("bar_color") {
+            self.bar_color = super::bounding_boxes::parse_color(color)?;
+        }
+        if let Some(color) = config.get("over_budget_color") {
+            self.over_budget_color = super::bounding_boxes::parse_color(color)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a profiler `timeline` pushed through the overlay config
+fn parse_timeline(config: &serde_json::Value) -> Result<Option<Vec<SystemTimingEntry>>, String> {
+    config
+        .get("timeline")
+        .map(|timeline| {
+            serde_json::from_value(timeline.clone())
+                .map_err(|e| format!("Invalid system timeline: {}", e))
+        })
+        .transpose()
+}
+
+/// Root node of the on-screen chart
+#[derive(Component, Debug)]
+struct SystemFlowPanel;
+
+/// State shared between the overlay and its systems
+#[derive(Resource, Debug, Clone, Default)]
+pub struct SystemFlowSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<SystemFlowConfig>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+    timings: SystemTimings,
+}
+
+/// System Flow Overlay implementation
 #[derive(Debug)]
 pub struct SystemFlowOverlay {
-    enabled: bool,
+    settings: SystemFlowSettings,
     metrics: OverlayMetrics,
 }
 
 impl SystemFlowOverlay {
     pub fn new() -> Self {
         Self {
-            enabled: false,
+            settings: SystemFlowSettings::default(),
             metrics: OverlayMetrics::default(),
         }
     }
+
+    /// Current configuration
+    pub fn config(&self) -> SystemFlowConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl Default for SystemFlowOverlay {
@@ -30,31 +349,260 @@ impl Default for SystemFlowOverlay {
 }
 
 impl VisualOverlay for SystemFlowOverlay {
-    fn initialize(&mut self, _app: &mut App) {
-        info!("System flow overlay initialized (placeholder)");
+    fn initialize(&mut self, app: &mut App) {
+        // Share timings with a `SystemTimingLayer` installed by the log plugin
+        self.settings.timings = app
+            .world_mut()
+            .get_resource_or_insert_with(SystemTimings::default)
+            .clone();
+
+        app.insert_resource(self.settings.clone())
+            .add_systems(First, finish_timing_frame)
+            .add_systems(Update, render_system_flow);
+
+        info!("System flow overlay initialized");
     }
-    
-    fn update_config(&mut self, _config: &serde_json::Value) -> Result<(), String> {
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let timeline = parse_timeline(config)?;
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "System flow config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+
+        if let Some(timeline) = timeline {
+            self.settings.timings.set_last_frame(timeline);
+        }
         Ok(())
     }
-    
+
     fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
     }
-    
+
     fn is_enabled(&self) -> bool {
-        self.enabled
+        self.settings.enabled.load(Ordering::Relaxed)
     }
-    
+
     fn get_metrics(&self) -> OverlayMetrics {
-        self.metrics.clone()
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 512, // Text and bar nodes per row
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
     }
-    
+
     fn overlay_type(&self) -> DebugOverlayType {
         DebugOverlayType::SystemFlow
     }
-    
+
     fn cleanup(&mut self) {
-        // Placeholder
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// System to close the measured frame before any other system runs
+fn finish_timing_frame(settings: Res<SystemFlowSettings>) {
+    // Runs while disabled too so recorded runs do not accumulate
+    settings.timings.finish_frame();
+}
+
+/// System to rebuild the on-screen timeline chart
+fn render_system_flow(
+    mut commands: Commands,
+    settings: Res<SystemFlowSettings>,
+    panels: Query<Entity, With<SystemFlowPanel>>,
+    mut frame: Local<u32>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        for panel in &panels {
+            commands.entity(panel).despawn();
+        }
+        return;
+    }
+
+    *frame = frame.wrapping_add(1);
+    if !settings.quality.should_update(*frame) {
+        return;
+    }
+    let config = match settings.config.read() {
+        Ok(config) => config.clone(),
+        Err(_) => return,
+    };
+
+    let start_time = std::time::Instant::now();
+    let frame_entries = settings.timings.last_frame();
+    let frame_us = frame_entries
+        .iter()
+        .map(|entry| entry.start_us + entry.duration_us)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let total_us: u64 = frame_entries.iter().map(|entry| entry.duration_us).sum();
+    let rows: Vec<SystemTimingEntry> = top_systems(
+        &frame_entries,
+        settings.quality.get().scale_count(config.top_n),
+    )
+    .into_iter()
+    .filter(|entry| entry.duration_us >= config.min_duration_us)
+    .collect();
+
+    for panel in &panels {
+        commands.entity(panel).despawn();
     }
-}
\ No newline at end of file
+
+    let text_font = TextFont {
+        font_size: config.font_size,
+        ..default()
+    };
+    let scale = config.chart_width / frame_us as f32;
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(config.position.x),
+                top: Val::Px(config.position.y),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(2.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            SystemFlowPanel,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Text::new(format!(
+                    "Systems: {} runs, {:.2} ms",
+                    frame_entries.len(),
+                    total_us as f32 / 1000.0
+                )),
+                text_font.clone(),
+                TextColor(Color::WHITE),
+            ));
+
+            for (order, entry) in rows.iter().enumerate() {
+                let short_name = entry.name.rsplit("::").next().unwrap_or(&entry.name);
+                let color = if entry.duration_us > config.system_budget_us {
+                    config.over_budget_color
+                } else {
+                    config.bar_color
+                };
+                panel.spawn((
+                    Text::new(format!(
+                        "{}. {} {:.3} ms",
+                        order + 1,
+                        short_name,
+                        entry.duration_us as f32 / 1000.0
+                    )),
+                    text_font.clone(),
+                    TextColor(Color::WHITE),
+                ));
+                panel
+                    .spawn(Node {
+                        width: Val::Px(config.chart_width),
+                        height: Val::Px(config.font_size * 0.5),
+                        ..default()
+                    })
+                    .with_child((
+                        Node {
+                            margin: UiRect::left(Val::Px(entry.start_us as f32 * scale)),
+                            width: Val::Px((entry.duration_us as f32 * scale).max(1.0)),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(color),
+                    ));
+            }
+        });
+
+    settings.rendered.store(rows.len(), Ordering::Relaxed);
+    settings
+        .render_time_us
+        .store(start_time.elapsed().as_micros() as u64, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, start_us: u64, duration_us: u64) -> SystemTimingEntry {
+        SystemTimingEntry {
+            name: name.to_string(),
+            start_us,
+            duration_us,
+        }
+    }
+
+    #[test]
+    fn test_top_systems_merges_and_keeps_execution_order() {
+        let entries = [
+            entry("game::input", 0, 50),
+            entry("game::physics", 100, 400),
+            entry("game::ai", 600, 900),
+            entry("game::physics", 1600, 300),
+            entry("game::render_prep", 2000, 10),
+        ];
+
+        let top = top_systems(&entries, 2);
+        assert_eq!(
+            top,
+            vec![
+                entry("game::physics", 100, 700),
+                entry("game::ai", 600, 900)
+            ]
+        );
+        assert_eq!(top_systems(&entries, 10).len(), 4);
+    }
+
+    #[test]
+    fn test_timings_frames_and_profiler_timeline() {
+        let timings = SystemTimings::default();
+        timings.record("a", Instant::now(), 20);
+        assert!(timings.last_frame().is_empty());
+        timings.finish_frame();
+        assert_eq!(timings.last_frame().len(), 1);
+        timings.finish_frame();
+        assert_eq!(timings.last_frame().len(), 1);
+
+        let mut overlay = SystemFlowOverlay::new();
+        overlay.settings.timings = timings.clone();
+        overlay
+            .update_config(&serde_json::json!({
+                "top_n": 500,
+                "timeline": [
+                    {"name": "late", "start_us": 300, "duration_us": 5},
+                    {"name": "early", "start_us": 0, "duration_us": 80}
+                ]
+            }))
+            .unwrap();
+        assert_eq!(overlay.config().top_n, MAX_TOP_N_LIMIT);
+        assert_eq!(timings.last_frame()[0].name, "early");
+
+        assert!(overlay
+            .update_config(&serde_json::json!({"top_n": 0}))
+            .is_err());
+        assert!(overlay
+            .update_config(&serde_json::json!({"timeline": [{"name": "x"}]}))
+            .is_err());
+    }
+}
@@ -1,26 +1,170 @@
 /// Transform Hierarchy Visualization Overlay Implementation
-/// 
-/// Provides visual representation of transform hierarchies with gizmos
-/// showing local/world space transforms, parent-child relationships.
+///
+/// Draws local axes for entities and lines from children to their parents.
+/// Transforms changed remotely through `ManipulateTransform` are highlighted:
+/// the previous transform is drawn as a faded ghost next to the new one, with
+/// an arrow for nudges, the rotation axis for rotations and both extents for
+/// scaling, so each step of a remote editing loop is visible in the game.
 
-use super::{OverlayMetrics, VisualOverlay};
-use crate::brp_messages::DebugOverlayType;
+use super::{OverlayMetrics, QualityHandle, QualityLevel, VisualOverlay};
+use crate::brp_messages::{DebugOverlayType, TransformOperation, TransformState};
 use bevy::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
-/// Transforms Overlay implementation (stub for now)
+/// Upper bound on entities drawn per frame
+const MAX_ENTITIES_LIMIT: usize = 2000;
+
+/// Configuration for the transforms overlay
+#[derive(Debug, Clone)]
+pub struct TransformsConfig {
+    /// Length of the drawn axes, before entity scale
+    pub axis_length: f32,
+    /// Draw a line from each child to its parent
+    pub show_hierarchy: bool,
+    /// Draw axes for every entity rather than only manipulated ones
+    pub show_all: bool,
+    /// Maximum number of entities drawn per frame
+    pub max_entities: usize,
+    /// How long a manipulation stays highlighted
+    pub manipulation_display: Duration,
+    pub hierarchy_color: Color,
+    pub manipulation_color: Color,
+}
+
+impl Default for TransformsConfig {
+    fn default() -> Self {
+        Self {
+            axis_length: 0.5,
+            show_hierarchy: true,
+            show_all: true,
+            max_entities: 200,
+            manipulation_display: Duration::from_secs(3),
+            hierarchy_color: Color::srgba(0.8, 0.8, 0.8, 0.5),
+            manipulation_color: Color::srgb(1.0, 0.6, 0.0),
+        }
+    }
+}
+
+impl TransformsConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(length) = config.get("axis_length").and_then(|v| v.as_f64()) {
+            if length <= 0.0 {
+                return Err("axis_length must be positive".to_string());
+            }
+            self.axis_length = length as f32;
+        }
+        if let Some(show) = config.get("show_hierarchy").and_then(|v| v.as_bool()) {
+            self.show_hierarchy = show;
+        }
+        if let Some(show) = config.get("show_all").and_then(|v| v.as_bool()) {
+            self.show_all = show;
+        }
+        if let Some(max) = config.get("max_entities").and_then(|v| v.as_u64()) {
+            self.max_entities = (max as usize).min(MAX_ENTITIES_LIMIT);
+        }
+        if let Some(secs) = config
+            .get("manipulation_display_secs")
+            .and_then(|v| v.as_f64())
+        {
+            self.manipulation_display = Duration::from_secs_f64(secs.clamp(0.0, 60.0));
+        }
+        if let Some(color) = config.get("hierarchy_color") {
+            self.hierarchy_color = super::bounding_boxes::parse_color(color)?;
+        }
+        if let Some(color) = config.get("manipulation_color") {
+            self.manipulation_color = super::bounding_boxes::parse_color(color)?;
+        }
+        Ok(())
+    }
+}
+
+/// A remote transform change being highlighted
+#[derive(Debug, Clone)]
+pub struct TransformManipulation {
+    pub entity: Entity,
+    pub operation: TransformOperation,
+    pub before: Transform,
+    pub after: Transform,
+    pub received: Instant,
+}
+
+impl TransformManipulation {
+    /// Parse the `manipulation` sent with `ManipulateTransform`
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        let field = |name: &str| {
+            value
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Manipulation is missing '{}'", name))
+        };
+        let entity_bits = field("entity")?
+            .as_u64()
+            .ok_or("Manipulation entity must be an entity id")?;
+        let entity = Entity::try_from_bits(entity_bits)
+            .map_err(|_| format!("Invalid entity id: {}", entity_bits))?;
+        let parse = |name: &str| -> Result<Transform, String> {
+            let state: TransformState = serde_json::from_value(field(name)?)
+                .map_err(|e| format!("Invalid '{}' transform: {}", name, e))?;
+            Ok(Transform {
+                translation: Vec3::from_array(state.translation),
+                rotation: Quat::from_array(state.rotation),
+                scale: Vec3::from_array(state.scale),
+            })
+        };
+
+        Ok(Self {
+            entity,
+            operation: serde_json::from_value(field("operation")?)
+                .map_err(|e| format!("Invalid operation: {}", e))?,
+            before: parse("before")?,
+            after: parse("after")?,
+            received: Instant::now(),
+        })
+    }
+}
+
+/// State shared between the overlay and its render system
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TransformsSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<TransformsConfig>>,
+    manipulation: Arc<RwLock<Option<TransformManipulation>>>,
+    rendered: Arc<AtomicUsize>,
+    render_time_us: Arc<AtomicU64>,
+    quality: QualityHandle,
+}
+
+/// Transforms Overlay implementation
 #[derive(Debug)]
 pub struct TransformsOverlay {
-    enabled: bool,
+    settings: TransformsSettings,
     metrics: OverlayMetrics,
 }
 
 impl TransformsOverlay {
     pub fn new() -> Self {
         Self {
-            enabled: false,
+            settings: TransformsSettings::default(),
             metrics: OverlayMetrics::default(),
         }
     }
+
+    /// Current configuration
+    pub fn config(&self) -> TransformsConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+
+    /// Manipulation currently highlighted, if any
+    pub fn manipulation(&self) -> Option<TransformManipulation> {
+        self.settings.manipulation.read().ok()?.clone()
+    }
 }
 
 impl Default for TransformsOverlay {
@@ -30,31 +174,247 @@ impl Default for TransformsOverlay {
 }
 
 impl VisualOverlay for TransformsOverlay {
-    fn initialize(&mut self, _app: &mut App) {
-        info!("Transforms overlay initialized (placeholder)");
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_systems(Update, render_transforms);
+
+        info!("Transforms overlay initialized with Gizmo rendering");
     }
-    
-    fn update_config(&mut self, _config: &serde_json::Value) -> Result<(), String> {
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let manipulation = config
+            .get("manipulation")
+            .map(TransformManipulation::from_json)
+            .transpose()?;
+
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "Transforms config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+
+        if let Some(manipulation) = manipulation {
+            debug!(
+                "Highlighting {:?} on entity {:?}",
+                manipulation.operation, manipulation.entity
+            );
+            if let Ok(mut current) = self.settings.manipulation.write() {
+                *current = Some(manipulation);
+            }
+        }
         Ok(())
     }
-    
+
     fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
     }
-    
+
     fn is_enabled(&self) -> bool {
-        self.enabled
+        self.settings.enabled.load(Ordering::Relaxed)
     }
-    
+
     fn get_metrics(&self) -> OverlayMetrics {
-        self.metrics.clone()
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            render_time_us: self.settings.render_time_us.load(Ordering::Relaxed),
+            memory_usage_bytes: count * 96, // Estimated Gizmo overhead per entity
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
     }
-    
+
     fn overlay_type(&self) -> DebugOverlayType {
         DebugOverlayType::Transforms
     }
-    
+
     fn cleanup(&mut self) {
-        // Placeholder
+        self.settings.rendered.store(0, Ordering::Relaxed);
+        self.settings.render_time_us.store(0, Ordering::Relaxed);
+    }
+
+    fn supports_quality(&self) -> bool {
+        true
+    }
+
+    fn set_quality(&mut self, level: QualityLevel) {
+        self.settings.quality.set(level);
+    }
+}
+
+/// Draw X/Y/Z axes with the given opacity
+fn draw_axes(gizmos: &mut Gizmos, transform: &GlobalTransform, length: f32, alpha: f32) {
+    let origin = transform.translation();
+    for (axis, color) in [
+        (Vec3::X, Color::srgba(1.0, 0.2, 0.2, alpha)),
+        (Vec3::Y, Color::srgba(0.2, 1.0, 0.2, alpha)),
+        (Vec3::Z, Color::srgba(0.2, 0.4, 1.0, alpha)),
+    ] {
+        gizmos.line(origin, transform.transform_point(axis * length), color);
+    }
+}
+
+fn draw_manipulation(
+    gizmos: &mut Gizmos,
+    manipulation: &TransformManipulation,
+    current: &GlobalTransform,
+    config: &TransformsConfig,
+) {
+    let progress =
+        manipulation.received.elapsed().as_secs_f32() / config.manipulation_display.as_secs_f32();
+    let fade = (1.0 - progress).clamp(0.2, 1.0);
+    let color = config.manipulation_color.with_alpha(fade);
+
+    // Recover the parent's transform so the old local transform can be placed in the world
+    let parent = current.affine() * manipulation.after.compute_affine().inverse();
+    let before = GlobalTransform::from(Mat4::from(parent * manipulation.before.compute_affine()));
+
+    draw_axes(gizmos, &before, config.axis_length, 0.3 * fade);
+    draw_axes(gizmos, current, config.axis_length * 1.5, fade);
+
+    let position = current.translation();
+    match &manipulation.operation {
+        TransformOperation::Nudge { .. } => {
+            gizmos.arrow(before.translation(), position, color);
+        }
+        TransformOperation::Rotate { .. } => {
+            // Rotation axis in world space, recovered from the actual change
+            let delta = current.rotation() * before.rotation().inverse();
+            let (axis, _) = delta.to_axis_angle();
+            let axis = axis.normalize_or(Vec3::Y);
+            let reach = config.axis_length * 2.0;
+            gizmos.line(position - axis * reach, position + axis * reach, color);
+            gizmos.circle(
+                Isometry3d::new(position, Quat::from_rotation_arc(Vec3::Z, axis)),
+                config.axis_length,
+                color,
+            );
+        }
+        TransformOperation::Scale { .. } => {
+            gizmos.cuboid(before.compute_transform(), color.with_alpha(0.3 * fade));
+            gizmos.cuboid(current.compute_transform(), color);
+        }
+    }
+}
+
+/// System to draw entity axes, hierarchy lines and the active manipulation
+fn render_transforms(
+    mut gizmos: Gizmos,
+    settings: Res<TransformsSettings>,
+    query: Query<(Entity, &GlobalTransform, Option<&ChildOf>)>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
     }
-}
\ No newline at end of file
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let mut rendered = 0;
+
+    if config.show_all {
+        let max_entities = settings.quality.get().scale_count(config.max_entities);
+        for (_, transform, child_of) in query.iter().take(max_entities) {
+            draw_axes(&mut gizmos, transform, config.axis_length, 0.8);
+            if config.show_hierarchy {
+                if let Some(Ok((_, parent, _))) = child_of.map(|c| query.get(c.parent())) {
+                    gizmos.line(
+                        transform.translation(),
+                        parent.translation(),
+                        config.hierarchy_color,
+                    );
+                }
+            }
+            rendered += 1;
+        }
+    }
+
+    if let Ok(mut manipulation) = settings.manipulation.write() {
+        let expired = manipulation
+            .as_ref()
+            .is_some_and(|m| m.received.elapsed() > config.manipulation_display);
+        if expired {
+            *manipulation = None;
+        }
+        if let Some(manipulation) = manipulation.as_ref() {
+            if let Ok((_, transform, _)) = query.get(manipulation.entity) {
+                draw_manipulation(&mut gizmos, manipulation, transform, &config);
+                rendered += 1;
+            }
+        }
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    settings
+        .render_time_us
+        .store(render_time, Ordering::Relaxed);
+    if render_time > 1000 {
+        warn!(
+            "Transform rendering took {}μs for {} entities",
+            render_time, rendered
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms_config_json_update() {
+        let mut config = TransformsConfig::default();
+        config
+            .update_from_json(&serde_json::json!({
+                "axis_length": 2.0,
+                "show_all": false,
+                "max_entities": 100000,
+                "manipulation_display_secs": 0.5
+            }))
+            .unwrap();
+
+        assert_eq!(config.axis_length, 2.0);
+        assert!(!config.show_all);
+        assert_eq!(config.max_entities, MAX_ENTITIES_LIMIT);
+        assert_eq!(config.manipulation_display, Duration::from_millis(500));
+        assert!(config
+            .update_from_json(&serde_json::json!({"axis_length": 0.0}))
+            .is_err());
+    }
+
+    #[test]
+    fn test_manipulation_from_config() {
+        let entity = Entity::from_raw(42);
+        let mut overlay = TransformsOverlay::new();
+        overlay
+            .update_config(&serde_json::json!({
+                "manipulation": {
+                    "entity": entity.to_bits(),
+                    "operation": {"op": "nudge", "delta": [1.0, 0.0, 0.0]},
+                    "before": TransformState::default(),
+                    "after": {"translation": [1.0, 0.0, 0.0], "rotation": [0.0, 0.0, 0.0, 1.0], "scale": [1.0, 1.0, 1.0]}
+                }
+            }))
+            .unwrap();
+
+        let manipulation = overlay.manipulation().unwrap();
+        assert_eq!(manipulation.entity, entity);
+        assert_eq!(manipulation.after.translation, Vec3::X);
+        assert!(matches!(
+            manipulation.operation,
+            TransformOperation::Nudge { .. }
+        ));
+
+        assert!(overlay
+            .update_config(&serde_json::json!({"manipulation": {"entity": 1}}))
+            .is_err());
+    }
+}
@@ -0,0 +1,475 @@
+/// UI Layout Overlay Implementation
+///
+/// Draws bevy_ui node rectangles together with their margin, border and
+/// padding boxes and an arrow along the main flex axis of container nodes.
+/// Drawing can be limited to selected UI roots, identified by the root
+/// entity's `Entity::to_bits` value.
+
+use super::{OverlayMetrics, VisualOverlay};
+use crate::brp_messages::DebugOverlayType;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Upper bound on nodes drawn per frame regardless of configuration
+const MAX_NODES_LIMIT: usize = 5000;
+
+/// Edge sizes of a box model layer, in logical pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Edges {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Edges {
+    fn from_border_rect(rect: BorderRect, scale: f32) -> Self {
+        Self {
+            left: rect.left * scale,
+            right: rect.right * scale,
+            top: rect.top * scale,
+            bottom: rect.bottom * scale,
+        }
+    }
+
+    /// Resolve pixel margins; relative values need the parent size and are ignored
+    fn from_margin(margin: &UiRect) -> Self {
+        let px = |val: Val| match val {
+            Val::Px(px) => px,
+            _ => 0.0,
+        };
+        Self {
+            left: px(margin.left),
+            right: px(margin.right),
+            top: px(margin.top),
+            bottom: px(margin.bottom),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Screen-space boxes of a UI node, outermost first
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutRects {
+    pub margin: Rect,
+    pub border: Rect,
+    pub padding: Rect,
+    pub content: Rect,
+}
+
+/// Compute the box model rectangles of a node from its center and size
+pub fn layout_rects(
+    center: Vec2,
+    size: Vec2,
+    margin: Edges,
+    border: Edges,
+    padding: Edges,
+) -> LayoutRects {
+    let border_box = Rect::from_center_size(center, size);
+    let shrink = |rect: Rect, edges: Edges| Rect {
+        min: rect.min + Vec2::new(edges.left, edges.top),
+        max: rect.max - Vec2::new(edges.right, edges.bottom),
+    };
+    let grow = |rect: Rect, edges: Edges| Rect {
+        min: rect.min - Vec2::new(edges.left, edges.top),
+        max: rect.max + Vec2::new(edges.right, edges.bottom),
+    };
+    let padding_box = shrink(border_box, border);
+    LayoutRects {
+        margin: grow(border_box, margin),
+        border: border_box,
+        padding: padding_box,
+        content: shrink(padding_box, padding),
+    }
+}
+
+/// Start and end of the main-axis arrow for a flex container (screen space, y down)
+pub fn flex_axis(rect: Rect, direction: FlexDirection) -> (Vec2, Vec2) {
+    let center = rect.center();
+    let half = rect.half_size() * 0.8;
+    match direction {
+        FlexDirection::Row => (center - Vec2::X * half.x, center + Vec2::X * half.x),
+        FlexDirection::RowReverse => (center + Vec2::X * half.x, center - Vec2::X * half.x),
+        FlexDirection::Column => (center - Vec2::Y * half.y, center + Vec2::Y * half.y),
+        FlexDirection::ColumnReverse => (center + Vec2::Y * half.y, center - Vec2::Y * half.y),
+    }
+}
+
+/// Configuration for the UI layout overlay
+#[derive(Debug, Clone)]
+pub struct UiLayoutConfig {
+    pub show_margin: bool,
+    pub show_padding: bool,
+    pub show_flex_axes: bool,
+    /// Only draw these UI roots (`Entity::to_bits`); empty draws all
+    pub roots: HashSet<u64>,
+    /// Never draw these UI roots
+    pub excluded_roots: HashSet<u64>,
+    /// Maximum number of nodes drawn per frame
+    pub max_nodes: usize,
+    /// Distance in front of the camera at which the overlay is drawn
+    pub depth: f32,
+}
+
+impl Default for UiLayoutConfig {
+    fn default() -> Self {
+        Self {
+            show_margin: true,
+            show_padding: true,
+            show_flex_axes: true,
+            roots: HashSet::new(),
+            excluded_roots: HashSet::new(),
+            max_nodes: 1000,
+            depth: 1.0,
+        }
+    }
+}
+
+impl UiLayoutConfig {
+    /// Update configuration from JSON
+    pub fn update_from_json(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        if let Some(show) = config.get("show_margin").and_then(|v| v.as_bool()) {
+            self.show_margin = show;
+        }
+
+        if let Some(show) = config.get("show_padding").and_then(|v| v.as_bool()) {
+            self.show_padding = show;
+        }
+
+        if let Some(show) = config.get("show_flex_axes").and_then(|v| v.as_bool()) {
+            self.show_flex_axes = show;
+        }
+
+        if let Some(roots) = config.get("roots") {
+            self.roots = parse_entity_ids(roots)?;
+        }
+
+        if let Some(roots) = config.get("excluded_roots") {
+            self.excluded_roots = parse_entity_ids(roots)?;
+        }
+
+        if let Some(max) = config.get("max_nodes").and_then(|v| v.as_u64()) {
+            self.max_nodes = (max as usize).min(MAX_NODES_LIMIT);
+        }
+
+        if let Some(depth) = config.get("depth").and_then(|v| v.as_f64()) {
+            self.depth = (depth as f32).max(0.001);
+        }
+
+        Ok(())
+    }
+
+    /// Whether nodes under this UI root should be drawn
+    pub fn shows_root(&self, root: u64) -> bool {
+        !self.excluded_roots.contains(&root)
+            && (self.roots.is_empty() || self.roots.contains(&root))
+    }
+}
+
+fn parse_entity_ids(value: &serde_json::Value) -> Result<HashSet<u64>, String> {
+    value
+        .as_array()
+        .ok_or("Root list must be an array of entity ids")?
+        .iter()
+        .map(|id| {
+            id.as_u64()
+                .ok_or_else(|| format!("Invalid entity id: {}", id))
+        })
+        .collect()
+}
+
+/// State shared between the overlay and its render system
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UiLayoutSettings {
+    enabled: Arc<AtomicBool>,
+    config: Arc<RwLock<UiLayoutConfig>>,
+    rendered: Arc<AtomicUsize>,
+}
+
+/// UI Layout Overlay implementation
+#[derive(Debug)]
+pub struct UiLayoutOverlay {
+    settings: UiLayoutSettings,
+    metrics: OverlayMetrics,
+}
+
+impl UiLayoutOverlay {
+    pub fn new() -> Self {
+        Self {
+            settings: UiLayoutSettings::default(),
+            metrics: OverlayMetrics::default(),
+        }
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> UiLayoutConfig {
+        self.settings
+            .config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for UiLayoutOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VisualOverlay for UiLayoutOverlay {
+    fn initialize(&mut self, app: &mut App) {
+        app.insert_resource(self.settings.clone()).add_systems(
+            PostUpdate,
+            render_ui_layout.after(bevy::ui::UiSystem::Layout),
+        );
+
+        info!("UI layout overlay initialized with Gizmo rendering");
+    }
+
+    fn update_config(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let mut current = self
+            .settings
+            .config
+            .write()
+            .map_err(|_| "UI layout config lock poisoned".to_string())?;
+        let mut updated = current.clone();
+        updated.update_from_json(config)?;
+        *current = updated;
+        info!("UI layout overlay config updated: {:?}", *current);
+        Ok(())
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.settings.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.cleanup();
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.settings.enabled.load(Ordering::Relaxed)
+    }
+
+    fn get_metrics(&self) -> OverlayMetrics {
+        let count = self.settings.rendered.load(Ordering::Relaxed);
+        OverlayMetrics {
+            element_count: count,
+            memory_usage_bytes: count * 128, // Up to four rectangles per node
+            active_this_frame: count > 0,
+            ..self.metrics.clone()
+        }
+    }
+
+    fn overlay_type(&self) -> DebugOverlayType {
+        DebugOverlayType::UiLayout
+    }
+
+    fn cleanup(&mut self) {
+        self.settings.rendered.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Walk up the hierarchy to the UI root of a node
+fn find_root(entity: Entity, parents: &Query<&ChildOf, With<Node>>) -> Entity {
+    let mut current = entity;
+    while let Ok(child_of) = parents.get(current) {
+        current = child_of.parent();
+    }
+    current
+}
+
+/// System to draw UI node boxes and flex axes
+fn render_ui_layout(
+    mut gizmos: Gizmos,
+    settings: Res<UiLayoutSettings>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform)>,
+    nodes: Query<(
+        Entity,
+        &Node,
+        &ComputedNode,
+        &ComputedNodeTarget,
+        &GlobalTransform,
+        Option<&Children>,
+    )>,
+    parents: Query<&ChildOf, With<Node>>,
+) {
+    if !settings.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(config) = settings.config.read() else {
+        return;
+    };
+
+    let start_time = std::time::Instant::now();
+    let default_camera = cameras.iter().find(|(_, camera, _)| camera.is_active);
+    let mut root_visible: HashMap<Entity, bool> = HashMap::new();
+    let mut rendered = 0;
+
+    for (entity, node, computed, target, transform, children) in &nodes {
+        if rendered >= config.max_nodes {
+            break;
+        }
+        if computed.is_empty() || node.display == Display::None {
+            continue;
+        }
+
+        let root = find_root(entity, &parents);
+        let visible = *root_visible
+            .entry(root)
+            .or_insert_with(|| config.shows_root(root.to_bits()));
+        if !visible {
+            continue;
+        }
+
+        let camera = target
+            .camera()
+            .and_then(|camera| cameras.get(camera).ok())
+            .or(default_camera);
+        let Some((_, camera, camera_transform)) = camera else {
+            continue;
+        };
+
+        // Layout is in physical pixels; viewport conversion expects logical ones
+        let scale = computed.inverse_scale_factor();
+        let rects = layout_rects(
+            transform.translation().truncate() * scale,
+            computed.size() * scale,
+            Edges::from_margin(&node.margin),
+            Edges::from_border_rect(computed.border(), scale),
+            Edges::from_border_rect(computed.padding(), scale),
+        );
+
+        let to_world = |point: Vec2| {
+            camera
+                .viewport_to_world(camera_transform, point)
+                .ok()
+                .map(|ray| ray.get_point(config.depth))
+        };
+        let mut draw_rect = |rect: Rect, color: Color| {
+            let corners = [
+                rect.min,
+                Vec2::new(rect.max.x, rect.min.y),
+                rect.max,
+                Vec2::new(rect.min.x, rect.max.y),
+                rect.min,
+            ];
+            if let Some(points) = corners
+                .into_iter()
+                .map(to_world)
+                .collect::<Option<Vec<_>>>()
+            {
+                gizmos.linestrip(points, color);
+            }
+        };
+
+        draw_rect(rects.border, Color::srgb(0.0, 1.0, 0.0));
+        if config.show_margin && !Edges::from_margin(&node.margin).is_zero() {
+            draw_rect(rects.margin, Color::srgba(1.0, 0.6, 0.0, 0.6));
+        }
+        if config.show_padding && rects.content != rects.border {
+            draw_rect(rects.content, Color::srgba(0.3, 0.6, 1.0, 0.6));
+        }
+
+        if config.show_flex_axes
+            && node.display == Display::Flex
+            && children.is_some_and(|c| !c.is_empty())
+        {
+            let (start, end) = flex_axis(rects.content, node.flex_direction);
+            if let (Some(start), Some(end)) = (to_world(start), to_world(end)) {
+                gizmos.arrow(start, end, Color::srgb(1.0, 0.0, 1.0));
+            }
+        }
+
+        rendered += 1;
+    }
+
+    settings.rendered.store(rendered, Ordering::Relaxed);
+
+    let render_time = start_time.elapsed().as_micros() as u64;
+    if render_time > 1000 {
+        warn!(
+            "UI layout rendering took {}μs for {} nodes",
+            render_time, rendered
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_rects() {
+        let rects = layout_rects(
+            Vec2::new(100.0, 50.0),
+            Vec2::new(40.0, 20.0),
+            Edges {
+                left: 5.0,
+                right: 5.0,
+                top: 0.0,
+                bottom: 10.0,
+            },
+            Edges {
+                left: 1.0,
+                right: 1.0,
+                top: 1.0,
+                bottom: 1.0,
+            },
+            Edges {
+                left: 4.0,
+                right: 4.0,
+                top: 2.0,
+                bottom: 2.0,
+            },
+        );
+
+        assert_eq!(rects.border.min, Vec2::new(80.0, 40.0));
+        assert_eq!(rects.border.max, Vec2::new(120.0, 60.0));
+        assert_eq!(rects.margin.min, Vec2::new(75.0, 40.0));
+        assert_eq!(rects.margin.max, Vec2::new(125.0, 70.0));
+        assert_eq!(rects.padding.min, Vec2::new(81.0, 41.0));
+        assert_eq!(rects.content.min, Vec2::new(85.0, 43.0));
+        assert_eq!(rects.content.max, Vec2::new(115.0, 57.0));
+    }
+
+    #[test]
+    fn test_flex_axis_direction() {
+        let rect = Rect::new(0.0, 0.0, 100.0, 50.0);
+
+        let (start, end) = flex_axis(rect, FlexDirection::Row);
+        assert!(end.x > start.x);
+        assert_eq!(start.y, end.y);
+
+        let (start, end) = flex_axis(rect, FlexDirection::ColumnReverse);
+        assert!(end.y < start.y);
+        assert_eq!(start.x, end.x);
+    }
+
+    #[test]
+    fn test_ui_layout_root_filtering() {
+        let mut config = UiLayoutConfig::default();
+        assert!(config.shows_root(42));
+
+        config
+            .update_from_json(&serde_json::json!({
+                "roots": [1, 2],
+                "excluded_roots": [2],
+                "max_nodes": 100000
+            }))
+            .unwrap();
+        assert!(config.shows_root(1));
+        assert!(!config.shows_root(2));
+        assert!(!config.shows_root(3));
+        assert_eq!(config.max_nodes, MAX_NODES_LIMIT);
+
+        assert!(config
+            .update_from_json(&serde_json::json!({"roots": ["main"]}))
+            .is_err());
+    }
+}
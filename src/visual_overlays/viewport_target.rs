@@ -0,0 +1,131 @@
+/// Per-Camera Overlay Targeting
+///
+/// Restricts where overlays are drawn. When a target is set, gizmos move to a
+/// dedicated render layer that only targeted cameras see, so a debug fly
+/// camera can show overlays while the player camera (and screenshots taken
+/// from it) stays clean. Cameras are identified by viewport id
+/// (`camera_<index>`) or by their `Name`.
+
+use super::{ViewportConfig, VisualOverlayManager};
+use bevy::gizmos::config::GizmoConfigStore;
+use bevy::prelude::*;
+use bevy::render::view::{Layer, RenderLayers};
+use serde::{Deserialize, Serialize};
+
+/// Render layer reserved for overlay gizmos while a target is active
+pub const OVERLAY_RENDER_LAYER: Layer = 31;
+
+/// Which cameras overlays are drawn for
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "cameras", rename_all = "snake_case")]
+pub enum ViewportTarget {
+    /// Every camera (default)
+    #[default]
+    All,
+    /// Only the listed cameras
+    Only(Vec<String>),
+    /// Every camera except the listed ones
+    Exclude(Vec<String>),
+}
+
+impl ViewportTarget {
+    /// Whether overlays should be drawn for a camera
+    pub fn includes(&self, viewport_id: &str, name: Option<&str>) -> bool {
+        let listed = |cameras: &[String]| {
+            cameras
+                .iter()
+                .any(|camera| camera == viewport_id || Some(camera.as_str()) == name)
+        };
+        match self {
+            ViewportTarget::All => true,
+            ViewportTarget::Only(cameras) => listed(cameras),
+            ViewportTarget::Exclude(cameras) => !listed(cameras),
+        }
+    }
+}
+
+/// Viewport id used for a camera entity throughout the overlay module
+pub fn viewport_id(camera: Entity) -> String {
+    format!("camera_{}", camera.index())
+}
+
+/// System to apply the manager's viewport target to gizmo and camera render layers
+pub(super) fn apply_viewport_target(
+    mut commands: Commands,
+    manager: Option<Res<VisualOverlayManager>>,
+    mut gizmo_store: ResMut<GizmoConfigStore>,
+    mut viewport_config: ResMut<ViewportConfig>,
+    cameras: Query<(Entity, Option<&Name>, Option<&RenderLayers>), With<Camera>>,
+    mut applied: Local<ViewportTarget>,
+) {
+    let Some(manager) = manager else {
+        return;
+    };
+    let target = manager.viewport_target();
+
+    if *applied != *target {
+        let gizmo_layers = match target {
+            ViewportTarget::All => RenderLayers::default(),
+            _ => RenderLayers::layer(OVERLAY_RENDER_LAYER),
+        };
+        for (_, config, _) in gizmo_store.iter_mut() {
+            config.render_layers = gizmo_layers.clone();
+        }
+        info!("Overlay viewport target set to {:?}", target);
+        *applied = target.clone();
+    }
+
+    // Re-checked every frame so newly spawned cameras pick up the target
+    for (entity, name, layers) in &cameras {
+        let id = viewport_id(entity);
+        let included = target.includes(&id, name.map(|name| name.as_str()));
+
+        let current = layers.cloned().unwrap_or_default();
+        let wanted = if included && *target != ViewportTarget::All {
+            current.clone().with(OVERLAY_RENDER_LAYER)
+        } else {
+            current.clone().without(OVERLAY_RENDER_LAYER)
+        };
+        if wanted != current {
+            commands.entity(entity).insert(wanted);
+        }
+
+        if let Some(settings) = viewport_config.viewport_overlays.get_mut(&id) {
+            settings.enabled = included;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport_target_includes() {
+        assert!(ViewportTarget::All.includes("camera_3", None));
+
+        let only = ViewportTarget::Only(vec!["DebugCamera".to_string(), "camera_7".to_string()]);
+        assert!(only.includes("camera_1", Some("DebugCamera")));
+        assert!(only.includes("camera_7", None));
+        assert!(!only.includes("camera_2", Some("PlayerCamera")));
+
+        let exclude = ViewportTarget::Exclude(vec!["PlayerCamera".to_string()]);
+        assert!(!exclude.includes("camera_2", Some("PlayerCamera")));
+        assert!(exclude.includes("camera_1", Some("DebugCamera")));
+    }
+
+    #[test]
+    fn test_viewport_target_serde() {
+        let target: ViewportTarget =
+            serde_json::from_value(serde_json::json!({"mode": "only", "cameras": ["DebugCamera"]}))
+                .unwrap();
+        assert_eq!(
+            target,
+            ViewportTarget::Only(vec!["DebugCamera".to_string()])
+        );
+
+        let all: ViewportTarget =
+            serde_json::from_value(serde_json::json!({"mode": "all"})).unwrap();
+        assert_eq!(all, ViewportTarget::All);
+    }
+}
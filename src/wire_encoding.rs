@@ -0,0 +1,542 @@
+/*
+ * Bevy Debugger MCP Server - Negotiated Wire Encoding
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+    DuplexStream,
+};
+use tracing::{debug, warn};
+
+use crate::error::{Error, Result};
+
+/// Line a client sends first to ask for a binary encoding, followed by
+/// encodings it accepts in order of preference, e.g. `MCP-ENCODING: msgpack, cbor`
+///
+/// Appending `; chunk-size=<bytes>` also asks for large responses to be chunked.
+pub const ENCODING_PREAMBLE: &str = "MCP-ENCODING:";
+
+/// Largest binary frame accepted from a client
+pub const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Notification carrying one part of a chunked message
+pub const CHUNK_METHOD: &str = "notifications/chunk";
+
+/// Smallest chunk size a client can ask for
+pub const MIN_CHUNK_SIZE: usize = 1024;
+
+const MAX_PREAMBLE_LEN: usize = 256;
+
+/// How MCP messages are written on a connection
+///
+/// JSON is newline delimited, as without negotiation. Binary encodings
+/// carry one message per frame, prefixed by its length as a big-endian u32.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireEncoding {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl WireEncoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+            Self::Cbor => "cbor",
+        }
+    }
+
+    pub fn encode(&self, message: &Value) -> Result<Vec<u8>> {
+        match self {
+            Self::Json => Ok(serde_json::to_vec(message)?),
+            Self::MessagePack => rmp_serde::to_vec_named(message)
+                .map_err(|e| Error::Validation(format!("Failed to encode MessagePack: {}", e))),
+            Self::Cbor => {
+                let mut encoded = Vec::new();
+                ciborium::into_writer(message, &mut encoded)
+                    .map_err(|e| Error::Validation(format!("Failed to encode CBOR: {}", e)))?;
+                Ok(encoded)
+            }
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        match self {
+            Self::Json => Ok(serde_json::from_slice(bytes)?),
+            Self::MessagePack => rmp_serde::from_slice(bytes)
+                .map_err(|e| Error::Validation(format!("Invalid MessagePack message: {}", e))),
+            Self::Cbor => ciborium::from_reader(bytes)
+                .map_err(|e| Error::Validation(format!("Invalid CBOR message: {}", e))),
+        }
+    }
+}
+
+impl fmt::Display for WireEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for WireEncoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "msgpack" | "messagepack" => Ok(Self::MessagePack),
+            "cbor" => Ok(Self::Cbor),
+            other => Err(Error::Validation(format!(
+                "Unknown wire encoding: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// What a connection agreed on in its preamble
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WireOptions {
+    pub encoding: WireEncoding,
+    /// Responses longer than this many bytes of JSON are sent as chunks
+    pub chunk_size: Option<usize>,
+}
+
+impl WireOptions {
+    /// Whether the connection needs [`bridge`] rather than plain newline-delimited JSON
+    pub fn needs_bridge(&self) -> bool {
+        self.encoding != WireEncoding::Json || self.chunk_size.is_some()
+    }
+}
+
+impl Default for WireOptions {
+    fn default() -> Self {
+        Self {
+            encoding: WireEncoding::Json,
+            chunk_size: None,
+        }
+    }
+}
+
+impl fmt::Display for WireOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.encoding)?;
+        if let Some(chunk_size) = self.chunk_size {
+            write!(f, "; chunk-size={}", chunk_size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read an optional encoding preamble from a new connection
+///
+/// Clients that start straight away with JSON get JSON. Otherwise the first
+/// encoding in the client's list that the server knows is chosen and
+/// confirmed with a `MCP-ENCODING: <name>` line; unknown lists fall back to
+/// JSON. A requested chunk size is clamped to what the server accepts and
+/// echoed back. The returned reader still holds any bytes read past the preamble.
+pub async fn negotiate<S>(stream: S) -> Result<(WireOptions, BufReader<S>)>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(stream);
+    let starts_with_preamble = {
+        let buffered = reader.fill_buf().await?;
+        !buffered.is_empty() && ENCODING_PREAMBLE.as_bytes().starts_with(&buffered[..1])
+    };
+    if !starts_with_preamble {
+        return Ok((WireOptions::default(), reader));
+    }
+
+    let mut line = Vec::new();
+    (&mut reader)
+        .take(MAX_PREAMBLE_LEN as u64)
+        .read_until(b'\n', &mut line)
+        .await?;
+    let line = String::from_utf8_lossy(&line);
+    let requested = line
+        .trim()
+        .strip_prefix(ENCODING_PREAMBLE)
+        .ok_or_else(|| Error::Validation("Malformed encoding preamble".to_string()))?;
+    let (names, params) = requested.split_once(';').unwrap_or((requested, ""));
+    let options = WireOptions {
+        encoding: names
+            .split(',')
+            .find_map(|name| name.parse().ok())
+            .unwrap_or(WireEncoding::Json),
+        chunk_size: params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| key.trim() == "chunk-size")
+            .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+            .map(|size| size.clamp(MIN_CHUNK_SIZE, MAX_FRAME_SIZE)),
+    };
+
+    reader
+        .write_all(format!("{} {}\n", ENCODING_PREAMBLE, options).as_bytes())
+        .await?;
+    reader.flush().await?;
+    debug!(
+        "Negotiated {} wire options (requested: {})",
+        options,
+        requested.trim()
+    );
+    Ok((options, reader))
+}
+
+/// Translate a connection to the newline-delimited JSON the MCP service reads
+///
+/// Messages are converted in background tasks; the returned stream is the
+/// JSON side. A malformed or oversized frame closes the connection.
+///
+/// With a chunk size, a response that doesn't fit in one chunk is sent as
+/// [`CHUNK_METHOD`] notifications, each holding the next piece of the
+/// response's JSON text, numbered from 0 with `final: true` on the last.
+/// Only one chunk's worth of the response is buffered at a time.
+pub fn bridge<S>(stream: S, options: WireOptions) -> DuplexStream
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let encoding = options.encoding;
+    let (service_side, bridge_side) = tokio::io::duplex(64 * 1024);
+    let (mut client_reader, mut client_writer) = tokio::io::split(stream);
+    let (bridge_reader, mut bridge_writer) = tokio::io::split(bridge_side);
+
+    tokio::spawn(async move {
+        let result: Result<()> = async {
+            if encoding == WireEncoding::Json {
+                tokio::io::copy(&mut client_reader, &mut bridge_writer).await?;
+                return Ok(());
+            }
+            loop {
+                let Some(frame) = read_frame(&mut client_reader).await? else {
+                    return Ok(());
+                };
+                let mut line = serde_json::to_vec(&encoding.decode(&frame)?)?;
+                line.push(b'\n');
+                bridge_writer.write_all(&line).await?;
+            }
+        }
+        .await;
+        if let Err(e) = result {
+            warn!("Closing {} connection: {}", encoding, e);
+        }
+        let _ = bridge_writer.shutdown().await;
+    });
+
+    tokio::spawn(async move {
+        let result = match options.chunk_size {
+            Some(chunk_size) => {
+                forward_chunked(bridge_reader, &mut client_writer, encoding, chunk_size).await
+            }
+            None => forward(bridge_reader, &mut client_writer, encoding).await,
+        };
+        if let Err(e) = result {
+            warn!("Failed to send {} response: {}", encoding, e);
+        }
+        let _ = client_writer.shutdown().await;
+    });
+
+    service_side
+}
+
+/// Send each JSON line from the service as one message
+async fn forward<R, W>(service: R, client: &mut W, encoding: WireEncoding) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(service).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        write_message(client, encoding, &serde_json::from_str(&line)?).await?;
+    }
+    Ok(())
+}
+
+/// Like [`forward`], but split lines longer than `chunk_size` into chunk notifications
+async fn forward_chunked<R, W>(
+    service: R,
+    client: &mut W,
+    encoding: WireEncoding,
+    chunk_size: usize,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::with_capacity(chunk_size.max(8 * 1024), service);
+    let mut stream_id: u64 = 0;
+    loop {
+        let mut piece = Vec::new();
+        if read_piece(&mut reader, chunk_size, &mut piece).await? == 0 {
+            return Ok(());
+        }
+        if piece.ends_with(b"\n") {
+            if !piece.iter().all(u8::is_ascii_whitespace) {
+                write_message(client, encoding, &serde_json::from_slice(&piece)?).await?;
+            }
+            continue;
+        }
+
+        // The message didn't fit; send it a chunk at a time as it is read
+        stream_id += 1;
+        let mut seq: u64 = 0;
+        loop {
+            let mut eof = false;
+            while !eof && !piece.ends_with(b"\n") && piece.len() < chunk_size {
+                eof = read_piece(&mut reader, chunk_size - piece.len(), &mut piece).await? == 0;
+            }
+            let finished = eof || piece.ends_with(b"\n");
+            if piece.ends_with(b"\n") {
+                piece.pop();
+            }
+            // Keep a split multi-byte character whole for the next chunk
+            let valid = match std::str::from_utf8(&piece) {
+                Ok(_) => piece.len(),
+                Err(e) if e.error_len().is_none() && !finished => e.valid_up_to(),
+                Err(e) => return Err(Error::Validation(format!("Response is not UTF-8: {}", e))),
+            };
+            let rest = piece.split_off(valid);
+            let data = String::from_utf8(std::mem::replace(&mut piece, rest))
+                .map_err(|e| Error::Validation(format!("Response is not UTF-8: {}", e)))?;
+            let chunk = json!({
+                "jsonrpc": "2.0",
+                "method": CHUNK_METHOD,
+                "params": {"stream": stream_id, "seq": seq, "data": data, "final": finished},
+            });
+            write_message(client, encoding, &chunk).await?;
+            if finished {
+                break;
+            }
+            seq += 1;
+        }
+        debug!("Sent response as {} chunks", seq + 1);
+    }
+}
+
+/// Append up to `limit` bytes to `piece`, stopping after a newline
+async fn read_piece<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    limit: usize,
+    piece: &mut Vec<u8>,
+) -> Result<usize> {
+    Ok(reader.take(limit as u64).read_until(b'\n', piece).await?)
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(
+    client: &mut W,
+    encoding: WireEncoding,
+    message: &Value,
+) -> Result<()> {
+    let mut encoded = encoding.encode(message)?;
+    if encoding == WireEncoding::Json {
+        encoded.push(b'\n');
+    } else {
+        client.write_u32(encoded.len() as u32).await?;
+    }
+    client.write_all(&encoded).await?;
+    client.flush().await?;
+    Ok(())
+}
+
+/// Reassembles chunked responses on the client side
+#[derive(Debug)]
+pub struct ChunkAssembler {
+    max_message_size: usize,
+    partial: HashMap<u64, (u64, String)>,
+}
+
+impl ChunkAssembler {
+    pub fn new(max_message_size: usize) -> Self {
+        Self {
+            max_message_size,
+            partial: HashMap::new(),
+        }
+    }
+
+    /// Take one received message, returning it, or the whole message once its last chunk arrives
+    pub fn accept(&mut self, message: Value) -> Result<Option<Value>> {
+        if message.get("method").and_then(Value::as_str) != Some(CHUNK_METHOD) {
+            return Ok(Some(message));
+        }
+        let params = &message["params"];
+        let (Some(stream), Some(seq), Some(data)) = (
+            params["stream"].as_u64(),
+            params["seq"].as_u64(),
+            params["data"].as_str(),
+        ) else {
+            return Err(Error::Validation("Malformed chunk".to_string()));
+        };
+
+        let (next_seq, text) = self.partial.entry(stream).or_default();
+        if seq != *next_seq {
+            self.partial.remove(&stream);
+            return Err(Error::Validation(format!(
+                "Chunk {} of stream {} arrived out of order",
+                seq, stream
+            )));
+        }
+        if text.len() + data.len() > self.max_message_size {
+            self.partial.remove(&stream);
+            return Err(Error::Validation(format!(
+                "Chunked message exceeds the {} byte limit",
+                self.max_message_size
+            )));
+        }
+        text.push_str(data);
+        *next_seq += 1;
+
+        if !params["final"].as_bool().unwrap_or(false) {
+            return Ok(None);
+        }
+        let (_, text) = self.partial.remove(&stream).unwrap_or_default();
+        Ok(Some(serde_json::from_str(&text)?))
+    }
+}
+
+/// Read one length-prefixed frame, or `None` at a clean end of stream
+async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    let len = match reader.read_u32().await {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if len > MAX_FRAME_SIZE {
+        return Err(Error::Validation(format!(
+            "Frame of {} bytes exceeds the {} byte limit",
+            len, MAX_FRAME_SIZE
+        )));
+    }
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_msgpack_round_trip_through_bridge() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        client
+            .write_all(b"MCP-ENCODING: brotli, msgpack, cbor\n")
+            .await
+            .unwrap();
+        let (options, server) = negotiate(server).await.unwrap();
+        let encoding = options.encoding;
+        assert_eq!(encoding, WireEncoding::MessagePack);
+        assert_eq!(options.chunk_size, None);
+
+        let mut reply = vec![0; "MCP-ENCODING: msgpack\n".len()];
+        client.read_exact(&mut reply).await.unwrap();
+        assert_eq!(reply, b"MCP-ENCODING: msgpack\n");
+
+        let mut service = BufReader::new(bridge(server, options));
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"});
+        let encoded = encoding.encode(&request).unwrap();
+        client.write_u32(encoded.len() as u32).await.unwrap();
+        client.write_all(&encoded).await.unwrap();
+
+        let mut line = String::new();
+        service.read_line(&mut line).await.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&line).unwrap(), request);
+
+        let response = json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": []}});
+        service
+            .write_all(format!("{}\n", response).as_bytes())
+            .await
+            .unwrap();
+        let frame = read_frame(&mut client).await.unwrap().unwrap();
+        assert_eq!(encoding.decode(&frame).unwrap(), response);
+    }
+
+    #[tokio::test]
+    async fn test_json_clients_need_no_preamble() {
+        let (mut client, server) = tokio::io::duplex(4096);
+        client.write_all(b"{\"jsonrpc\":\"2.0\"}\n").await.unwrap();
+        let (options, mut server) = negotiate(server).await.unwrap();
+        assert_eq!(options, WireOptions::default());
+        assert!(!options.needs_bridge());
+
+        // Nothing was consumed from the first message
+        let mut line = String::new();
+        server.read_line(&mut line).await.unwrap();
+        assert_eq!(line, "{\"jsonrpc\":\"2.0\"}\n");
+
+        let cbor = WireEncoding::Cbor;
+        let message = json!({"entities": [{"id": 4, "components": {"Transform": [1.0, 2.0]}}]});
+        assert_eq!(
+            cbor.decode(&cbor.encode(&message).unwrap()).unwrap(),
+            message
+        );
+        assert!("yaml".parse::<WireEncoding>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_large_responses_are_chunked() {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let mut client = BufReader::new(client);
+        client
+            .write_all(b"MCP-ENCODING: cbor; chunk-size=10\n")
+            .await
+            .unwrap();
+        let (options, server) = negotiate(server).await.unwrap();
+        assert_eq!(options.chunk_size, Some(MIN_CHUNK_SIZE));
+
+        let mut reply = String::new();
+        client.read_line(&mut reply).await.unwrap();
+        assert_eq!(reply, "MCP-ENCODING: cbor; chunk-size=1024\n");
+
+        let mut service = bridge(server, options);
+        // Multi-byte characters land on chunk boundaries somewhere in here
+        let names: Vec<String> = (0..300).map(|i| format!("entité_{}", i)).collect();
+        let large = json!({"jsonrpc": "2.0", "id": 2, "result": {"names": names}});
+        let small = json!({"jsonrpc": "2.0", "id": 3, "result": {}});
+        service
+            .write_all(format!("{}\n{}\n", large, small).as_bytes())
+            .await
+            .unwrap();
+
+        let mut assembler = ChunkAssembler::new(MAX_FRAME_SIZE);
+        let mut received = Vec::new();
+        let mut chunks = 0;
+        while received.len() < 2 {
+            let frame = read_frame(&mut client).await.unwrap().unwrap();
+            let message = options.encoding.decode(&frame).unwrap();
+            if message["method"] == CHUNK_METHOD {
+                chunks += 1;
+            }
+            if let Some(message) = assembler.accept(message).unwrap() {
+                received.push(message);
+            }
+        }
+        assert!(chunks > 1);
+        assert_eq!(received, vec![large, small]);
+    }
+}
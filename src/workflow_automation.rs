@@ -27,6 +27,8 @@ use crate::brp_messages::DebugCommand;
 use crate::error::Result;
 use crate::pattern_learning::{DebugPattern, PatternLearningSystem};
 use crate::suggestion_engine::SuggestionEngine;
+#[cfg(feature = "scripting")]
+use crate::workflow_scripts::{ScriptLibrary, ScriptRun, ScriptSummary};
 
 /// Minimum occurrences before automation is offered
 const MIN_AUTOMATION_OCCURRENCES: usize = 5;
@@ -155,6 +157,9 @@ pub struct WorkflowAutomation {
     suggestion_engine: Arc<SuggestionEngine>,
     /// Default user preferences
     default_preferences: UserPreferences,
+    /// Rhai scripts written by users
+    #[cfg(feature = "scripting")]
+    scripts: ScriptLibrary,
 }
 
 impl WorkflowAutomation {
@@ -173,6 +178,8 @@ impl WorkflowAutomation {
                 require_confirmation: true,
                 auto_rollback: true,
             },
+            #[cfg(feature = "scripting")]
+            scripts: ScriptLibrary::from_env(),
         }
     }
     
@@ -365,6 +372,30 @@ impl WorkflowAutomation {
         workflows.values().cloned().collect()
     }
     
+    /// Scripts in the scripts directory
+    #[cfg(feature = "scripting")]
+    pub fn list_scripts(&self) -> Result<Vec<ScriptSummary>> {
+        self.scripts.list()
+    }
+    
+    /// Run a script from the scripts directory against the game
+    #[cfg(feature = "scripting")]
+    pub async fn run_script(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+        brp_client: Arc<RwLock<crate::brp_client::BrpClient>>,
+    ) -> Result<ScriptRun> {
+        let run = self.scripts.run(name, args, brp_client).await?;
+        info!(
+            "Workflow script {} finished after {} tool calls in {}ms",
+            name,
+            run.tool_calls.len(),
+            run.duration_ms
+        );
+        Ok(run)
+    }
+    
     /// Helper methods (simplified implementations)
     async fn get_frequent_patterns(&self) -> Result<Vec<DebugPattern>> {
         // In practice, would query the pattern learning system
@@ -0,0 +1,579 @@
+/*
+ * Bevy Debugger MCP Server - Workflow Scheduler
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Tool calls run on a schedule
+//!
+//! A scheduled workflow calls one tool with fixed arguments every
+//! `every_secs` or whenever a five-field cron expression matches (UTC), e.g.
+//! a nightly `stress` run against the dev server or an hourly `health_check`.
+//! Scripts and learned workflows are scheduled through the `workflow_script`
+//! and `execute_workflow` tools.
+//!
+//! Every run is kept in a short history. Failed runs are logged, sent to
+//! subscribers and, with the `alert-webhooks` feature, POSTed to the
+//! workflow's webhook.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::error::{Error, Result};
+
+/// How often due workflows are looked for
+pub const SCHEDULER_TICK: Duration = Duration::from_secs(1);
+
+/// Shortest accepted interval
+pub const MIN_INTERVAL_SECS: u64 = 10;
+
+/// Longest one run may take before it is recorded as failed
+pub const DEFAULT_RUN_TIMEOUT_SECS: u64 = 600;
+
+/// Runs kept for the `schedules` tool
+const MAX_RUN_HISTORY: usize = 200;
+
+/// Furthest ahead a cron expression is searched for its next match
+const MAX_CRON_LOOKAHEAD_DAYS: i64 = 366 * 4;
+
+/// A tool call to run on a schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledWorkflow {
+    pub name: String,
+    pub tool: String,
+    #[serde(default)]
+    pub arguments: Value,
+    /// Run every this many seconds
+    #[serde(default)]
+    pub every_secs: Option<u64>,
+    /// Run when this `minute hour day-of-month month day-of-week` expression matches, in UTC
+    #[serde(default)]
+    pub cron: Option<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// URL failed runs are POSTed to as JSON
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_RUN_TIMEOUT_SECS
+}
+
+impl ScheduledWorkflow {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() || self.tool.trim().is_empty() {
+            return Err(Error::Validation(
+                "Scheduled workflows need a name and a tool".to_string(),
+            ));
+        }
+        if self.tool == "schedules" {
+            return Err(Error::Validation(
+                "Scheduled workflows cannot call the schedules tool".to_string(),
+            ));
+        }
+        match (self.every_secs, &self.cron) {
+            (Some(secs), None) if secs < MIN_INTERVAL_SECS => {
+                return Err(Error::Validation(format!(
+                    "Workflow '{}' runs every {secs}s; the shortest interval is {MIN_INTERVAL_SECS}s",
+                    self.name
+                )))
+            }
+            (Some(_), None) => {}
+            (None, Some(cron)) => {
+                CronSchedule::parse(cron)?;
+            }
+            _ => {
+                return Err(Error::Validation(format!(
+                    "Workflow '{}' needs exactly one of every_secs or cron",
+                    self.name
+                )))
+            }
+        }
+        if self.webhook.is_some() && !cfg!(feature = "alert-webhooks") {
+            return Err(Error::Validation(format!(
+                "Workflow '{}' has a webhook, which requires the alert-webhooks feature",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// First run time after `after`
+    fn next_run(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match (self.every_secs, &self.cron) {
+            (Some(secs), _) => Some(after + ChronoDuration::seconds(secs as i64)),
+            (None, Some(cron)) => CronSchedule::parse(cron).ok()?.next_after(after),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A parsed five-field cron expression
+///
+/// Fields accept `*`, numbers, ranges `a-b`, steps `*/n` or `a-b/n` and comma
+/// lists. Day of week runs from 0 (Sunday) to 6, and 7 is Sunday too. As in
+/// cron, when both day fields are restricted a day matching either one runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(Error::Validation(format!(
+                "Cron expression '{expr}' needs 5 fields: minute hour day-of-month month day-of-week"
+            )));
+        };
+        let mut days_of_week = parse_field(day_of_week, 0, 7)?;
+        // 7 is another name for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(day_of_month, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// First matching minute after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = after + ChronoDuration::days(MAX_CRON_LOOKAHEAD_DAYS);
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + ChronoDuration::minutes(1);
+        while t <= limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = match t.month() {
+                    12 => (t.year() + 1, 1),
+                    month => (t.year(), month + 1),
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if !self.day_matches(t.date_naive()) {
+                t = (t.date_naive() + ChronoDuration::days(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + ChronoDuration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += ChronoDuration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// Bitmask of the values `field` allows between `min` and `max`
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let invalid = || {
+        Error::Validation(format!(
+            "Invalid cron field '{field}': expected values between {min} and {max}"
+        ))
+    };
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (
+                    start.parse().map_err(|_| invalid())?,
+                    end.parse().map_err(|_| invalid())?,
+                ),
+                None => {
+                    let value: u32 = range.parse().map_err(|_| invalid())?;
+                    (value, value)
+                }
+            },
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+/// One finished run of a scheduled workflow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledRun {
+    pub workflow: String,
+    pub tool: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A scheduled workflow and when it runs next
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleStatus {
+    #[serde(flatten)]
+    pub workflow: ScheduledWorkflow,
+    pub next_run: Option<DateTime<Utc>>,
+    pub running: bool,
+    pub consecutive_failures: u32,
+}
+
+#[derive(Debug)]
+struct ScheduleEntry {
+    workflow: ScheduledWorkflow,
+    next_run: Option<DateTime<Utc>>,
+    running: bool,
+    consecutive_failures: u32,
+}
+
+/// Scheduled workflows, their next runs and run history
+#[derive(Debug)]
+pub struct WorkflowScheduler {
+    entries: Mutex<Vec<ScheduleEntry>>,
+    history: Mutex<VecDeque<ScheduledRun>>,
+    events: broadcast::Sender<ScheduledRun>,
+    runner_started: AtomicBool,
+}
+
+impl Default for WorkflowScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorkflowScheduler {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            entries: Mutex::new(Vec::new()),
+            history: Mutex::new(VecDeque::new()),
+            events,
+            runner_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Add the workflows in the JSON file named by `BEVY_MCP_SCHEDULES`, returning how many
+    pub fn load_from_env(&self) -> Result<usize> {
+        let Ok(path) = std::env::var("BEVY_MCP_SCHEDULES") else {
+            return Ok(0);
+        };
+        let workflows: Vec<ScheduledWorkflow> = serde_json::from_slice(&std::fs::read(&path)?)
+            .map_err(|e| Error::Config(format!("Invalid scheduled workflows in {path}: {e}")))?;
+        let count = workflows.len();
+        for workflow in workflows {
+            self.add(workflow, Utc::now())?;
+        }
+        Ok(count)
+    }
+
+    /// Add `workflow`, replacing any workflow with the same name, and return its first run time
+    pub fn add(
+        &self,
+        workflow: ScheduledWorkflow,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        workflow.validate()?;
+        self.remove(&workflow.name);
+        let next_run = workflow.next_run(now);
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(ScheduleEntry {
+                workflow,
+                next_run,
+                running: false,
+                consecutive_failures: 0,
+            });
+        }
+        Ok(next_run)
+    }
+
+    pub fn remove(&self, name: &str) -> bool {
+        let Ok(mut entries) = self.entries.lock() else {
+            return false;
+        };
+        let before = entries.len();
+        entries.retain(|entry| entry.workflow.name != name);
+        entries.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries
+            .lock()
+            .map(|entries| entries.is_empty())
+            .unwrap_or(true)
+    }
+
+    pub fn list(&self) -> Vec<ScheduleStatus> {
+        self.entries
+            .lock()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|entry| ScheduleStatus {
+                        workflow: entry.workflow.clone(),
+                        next_run: entry.next_run,
+                        running: entry.running,
+                        consecutive_failures: entry.consecutive_failures,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Recent runs, oldest first, optionally of one workflow
+    pub fn history(&self, workflow: Option<&str>) -> Vec<ScheduledRun> {
+        self.history
+            .lock()
+            .map(|history| {
+                history
+                    .iter()
+                    .filter(|run| workflow.map_or(true, |name| run.workflow == name))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Runs from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<ScheduledRun> {
+        self.events.subscribe()
+    }
+
+    /// Workflows due at `now`, marked running with their next run moved on
+    ///
+    /// A workflow still running from its last run is skipped, and runs missed
+    /// while the server was busy are not caught up.
+    pub fn take_due(&self, now: DateTime<Utc>) -> Vec<ScheduledWorkflow> {
+        let Ok(mut entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut due = Vec::new();
+        for entry in entries.iter_mut() {
+            if entry.next_run.map_or(true, |next| next > now) {
+                continue;
+            }
+            entry.next_run = entry.workflow.next_run(now);
+            if entry.running {
+                warn!(
+                    "Skipping scheduled workflow '{}': the previous run has not finished",
+                    entry.workflow.name
+                );
+                continue;
+            }
+            entry.running = true;
+            due.push(entry.workflow.clone());
+        }
+        due
+    }
+
+    /// Record a finished run, notifying about failures
+    pub fn record(&self, run: ScheduledRun) {
+        let webhook = {
+            let Ok(mut entries) = self.entries.lock() else {
+                return;
+            };
+            let entry = entries
+                .iter_mut()
+                .find(|entry| entry.workflow.name == run.workflow);
+            entry.and_then(|entry| {
+                entry.running = false;
+                if run.success {
+                    entry.consecutive_failures = 0;
+                } else {
+                    entry.consecutive_failures += 1;
+                }
+                entry.workflow.webhook.clone()
+            })
+        };
+
+        if run.success {
+            info!(
+                "Scheduled workflow '{}' finished in {}ms",
+                run.workflow, run.duration_ms
+            );
+        } else {
+            warn!(
+                "Scheduled workflow '{}' failed: {}",
+                run.workflow,
+                run.error.as_deref().unwrap_or("unknown error")
+            );
+            if let Some(url) = webhook {
+                crate::alerting::send_webhook(url, run.clone());
+            }
+        }
+        if let Ok(mut history) = self.history.lock() {
+            if history.len() >= MAX_RUN_HISTORY {
+                history.pop_front();
+            }
+            history.push_back(run.clone());
+        }
+        // No subscribers is fine
+        let _ = self.events.send(run);
+    }
+
+    /// Run due workflows with `call_tool` in the background, once per scheduler
+    pub fn spawn_runner<F, Fut>(self: &Arc<Self>, call_tool: F)
+    where
+        F: Fn(String, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value>> + Send + 'static,
+    {
+        if self.runner_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let scheduler = Arc::clone(self);
+        let call_tool = Arc::new(call_tool);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SCHEDULER_TICK);
+            loop {
+                interval.tick().await;
+                for workflow in scheduler.take_due(Utc::now()) {
+                    let scheduler = Arc::clone(&scheduler);
+                    let call_tool = Arc::clone(&call_tool);
+                    tokio::spawn(async move {
+                        let started_at = Utc::now();
+                        let started = Instant::now();
+                        let timeout = Duration::from_secs(workflow.timeout_secs);
+                        let call = (*call_tool)(workflow.tool.clone(), workflow.arguments.clone());
+                        let error = match tokio::time::timeout(timeout, call).await {
+                            Ok(Ok(_)) => None,
+                            Ok(Err(e)) => Some(e.to_string()),
+                            Err(_) => Some(format!("Timed out after {}s", workflow.timeout_secs)),
+                        };
+                        scheduler.record(ScheduledRun {
+                            workflow: workflow.name,
+                            tool: workflow.tool,
+                            started_at,
+                            duration_ms: started.elapsed().as_millis() as u64,
+                            success: error.is_none(),
+                            error,
+                        });
+                    });
+                }
+            }
+        });
+    }
+}
+
+/// The scheduler the server runs
+pub fn global() -> Arc<WorkflowScheduler> {
+    static GLOBAL: OnceLock<Arc<WorkflowScheduler>> = OnceLock::new();
+    GLOBAL.get_or_init(Default::default).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_cron_and_interval_schedules_run_and_record_failures() {
+        let nightly = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(
+            nightly.next_after(at(2025, 12, 31, 3, 0)),
+            Some(at(2026, 1, 1, 2, 30))
+        );
+        // Weekdays at the top of every sixth hour
+        let weekdays = CronSchedule::parse("0 */6 * * 1-5").unwrap();
+        assert_eq!(
+            weekdays.next_after(at(2025, 6, 6, 19, 0)), // Friday
+            Some(at(2025, 6, 9, 0, 0))
+        );
+        assert!(CronSchedule::parse("61 * * * *").is_err());
+        assert!(CronSchedule::parse("* * *").is_err());
+
+        let scheduler = WorkflowScheduler::new();
+        let now = at(2025, 6, 1, 0, 0);
+        let health = ScheduledWorkflow {
+            name: "hourly-health".to_string(),
+            tool: "health_check".to_string(),
+            arguments: json!({}),
+            every_secs: Some(3600),
+            cron: None,
+            timeout_secs: DEFAULT_RUN_TIMEOUT_SECS,
+            webhook: None,
+        };
+        assert_eq!(
+            scheduler.add(health.clone(), now).unwrap(),
+            Some(at(2025, 6, 1, 1, 0))
+        );
+        assert!(scheduler
+            .add(
+                ScheduledWorkflow {
+                    every_secs: Some(1),
+                    ..health.clone()
+                },
+                now
+            )
+            .is_err());
+
+        assert!(scheduler.take_due(at(2025, 6, 1, 0, 59)).is_empty());
+        assert_eq!(scheduler.take_due(at(2025, 6, 1, 1, 0)), vec![health]);
+        // Still running when next due
+        assert!(scheduler.take_due(at(2025, 6, 1, 2, 0)).is_empty());
+
+        let mut failures = scheduler.subscribe();
+        scheduler.record(ScheduledRun {
+            workflow: "hourly-health".to_string(),
+            tool: "health_check".to_string(),
+            started_at: at(2025, 6, 1, 1, 0),
+            duration_ms: 5,
+            success: false,
+            error: Some("not connected".to_string()),
+        });
+        assert!(!failures.try_recv().unwrap().success);
+        assert_eq!(scheduler.list()[0].consecutive_failures, 1);
+        assert_eq!(scheduler.history(Some("hourly-health")).len(), 1);
+        assert_eq!(scheduler.take_due(at(2025, 6, 1, 3, 0)).len(), 1);
+    }
+}
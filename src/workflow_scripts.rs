@@ -0,0 +1,419 @@
+/*
+ * Bevy Debugger MCP Server - Workflow Scripts
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Rhai scripts for team-specific debugging routines
+//!
+//! Each `<name>.rhai` file in `BEVY_MCP_SCRIPTS_DIR` is a workflow. A script
+//! reads its arguments from `args`, calls tools with
+//! `tool("observe", #{ query: "entities with Health" })`, inspects the
+//! returned maps and branches on them; a failed tool call throws, so
+//! `try`/`catch` handles it. The value of the last expression is the result.
+//!
+//! Scripts are sandboxed: no `import` or `eval`, no file access, and runs are
+//! stopped past [`ScriptLimits`] on operations, tool calls and wall time.
+
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use crate::brp_client::BrpClient;
+use crate::error::{Error, Result};
+use crate::tools::{anomaly, experiment, hypothesis, mutate, observe, search_world, stress, types};
+
+/// Directory scripts are loaded from unless `BEVY_MCP_SCRIPTS_DIR` is set
+pub const DEFAULT_SCRIPTS_DIR: &str = "./.bevy_debugger/scripts";
+
+/// File extension of workflow scripts
+const SCRIPT_EXTENSION: &str = "rhai";
+
+/// Longest accepted script name
+const MAX_SCRIPT_NAME_LEN: usize = 100;
+
+/// Tools scripts may call
+pub const SCRIPT_TOOLS: &[&str] = &[
+    "observe",
+    "types",
+    "search_world",
+    "hypothesis",
+    "anomaly",
+    "experiment",
+    "mutate",
+    "stress",
+];
+
+/// How far one script run may go before it is stopped
+#[derive(Debug, Clone)]
+pub struct ScriptLimits {
+    /// Rhai operations, roughly one per expression evaluated
+    pub max_operations: u64,
+    pub max_tool_calls: usize,
+    /// Wall time, including time spent waiting on tools
+    pub timeout: Duration,
+    pub max_call_depth: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 1_000_000,
+            max_tool_calls: 100,
+            timeout: Duration::from_secs(30),
+            max_call_depth: 32,
+        }
+    }
+}
+
+/// A script in the library
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptSummary {
+    pub name: String,
+    /// Leading `//` comment lines of the script
+    pub description: String,
+}
+
+/// One `tool(...)` call made by a script
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptToolCall {
+    pub tool: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+}
+
+/// Result of a finished script run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptRun {
+    pub script: String,
+    /// Value of the script's last expression
+    pub result: Value,
+    pub tool_calls: Vec<ScriptToolCall>,
+    /// Lines written with `print` or `debug`
+    pub output: Vec<String>,
+    pub operations: u64,
+    pub duration_ms: u64,
+}
+
+/// The `.rhai` files in one directory
+#[derive(Debug, Clone)]
+pub struct ScriptLibrary {
+    dir: PathBuf,
+    limits: ScriptLimits,
+}
+
+impl ScriptLibrary {
+    pub fn new(dir: impl Into<PathBuf>, limits: ScriptLimits) -> Self {
+        Self {
+            dir: dir.into(),
+            limits,
+        }
+    }
+
+    /// Library in `BEVY_MCP_SCRIPTS_DIR`, or [`DEFAULT_SCRIPTS_DIR`]
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("BEVY_MCP_SCRIPTS_DIR")
+                .unwrap_or_else(|_| DEFAULT_SCRIPTS_DIR.to_string()),
+            ScriptLimits::default(),
+        )
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name.len() <= MAX_SCRIPT_NAME_LEN
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+        if !valid {
+            return Err(Error::Validation(format!(
+                "Invalid script name '{name}': use up to {MAX_SCRIPT_NAME_LEN} letters, digits, '_' or '-'"
+            )));
+        }
+        Ok(self.dir.join(format!("{name}.{SCRIPT_EXTENSION}")))
+    }
+
+    /// Scripts in the library, by name
+    pub fn list(&self) -> Result<Vec<ScriptSummary>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut scripts = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(SCRIPT_EXTENSION) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(source) => scripts.push(ScriptSummary {
+                    name: name.to_string(),
+                    description: description(&source),
+                }),
+                Err(e) => warn!("Skipping unreadable script {}: {}", path.display(), e),
+            }
+        }
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(scripts)
+    }
+
+    /// Run the script `name` with `args` bound to `args`
+    pub async fn run(
+        &self,
+        name: &str,
+        args: Value,
+        brp_client: Arc<RwLock<BrpClient>>,
+    ) -> Result<ScriptRun> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(Error::Validation(format!(
+                "No script '{name}' in {}",
+                self.dir.display()
+            )));
+        }
+        let source = tokio::fs::read_to_string(&path).await?;
+        run_source(name, &source, args, brp_client, &self.limits).await
+    }
+}
+
+/// Leading `//` comment lines, joined
+fn description(source: &str) -> String {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.starts_with("//"))
+        .map(|line| line.trim_start_matches('/').trim())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Compile and run `source` on the blocking thread pool
+pub async fn run_source(
+    name: &str,
+    source: &str,
+    args: Value,
+    brp_client: Arc<RwLock<BrpClient>>,
+    limits: &ScriptLimits,
+) -> Result<ScriptRun> {
+    let runtime = tokio::runtime::Handle::current();
+    let name = name.to_string();
+    let source = source.to_string();
+    let limits = limits.clone();
+    tokio::task::spawn_blocking(move || {
+        run_blocking(&name, &source, args, brp_client, &limits, &runtime)
+    })
+    .await
+    .map_err(|e| Error::Internal(format!("Script task failed: {e}")))?
+}
+
+fn run_blocking(
+    name: &str,
+    source: &str,
+    args: Value,
+    brp_client: Arc<RwLock<BrpClient>>,
+    limits: &ScriptLimits,
+    runtime: &tokio::runtime::Handle,
+) -> Result<ScriptRun> {
+    let started = Instant::now();
+    let deadline = started + limits.timeout;
+    let operations = Arc::new(AtomicU64::new(0));
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let tool_calls = Arc::new(Mutex::new(Vec::<ScriptToolCall>::new()));
+
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+        .set_max_operations(limits.max_operations)
+        .set_max_call_levels(limits.max_call_depth)
+        .set_max_string_size(1 << 20)
+        .set_max_array_size(100_000)
+        .set_max_map_size(100_000);
+    engine.disable_symbol("eval");
+
+    let ops = Arc::clone(&operations);
+    engine.on_progress(move |count| {
+        ops.store(count, Ordering::Relaxed);
+        (Instant::now() >= deadline).then(|| "time limit reached".into())
+    });
+    let lines = Arc::clone(&output);
+    engine.on_print(move |text| lines.lock().unwrap().push(text.to_string()));
+    let lines = Arc::clone(&output);
+    engine.on_debug(move |text, _, _| lines.lock().unwrap().push(text.to_string()));
+
+    let calls = Arc::clone(&tool_calls);
+    let max_tool_calls = limits.max_tool_calls;
+    let runtime = runtime.clone();
+    engine.register_fn(
+        "tool",
+        move |tool: &str, arguments: Map| -> std::result::Result<Dynamic, Box<EvalAltResult>> {
+            if calls.lock().unwrap().len() >= max_tool_calls {
+                return Err(format!("Tool call limit of {max_tool_calls} reached").into());
+            }
+            let arguments: Value = rhai::serde::from_dynamic(&Dynamic::from_map(arguments))?;
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let call_started = Instant::now();
+            let result = runtime.block_on(async {
+                tokio::time::timeout(
+                    remaining,
+                    call_tool(tool, arguments, Arc::clone(&brp_client)),
+                )
+                .await
+                .unwrap_or_else(|_| {
+                    Err(Error::Timeout(format!(
+                        "Tool '{tool}' ran past the script time limit"
+                    )))
+                })
+            });
+            calls.lock().unwrap().push(ScriptToolCall {
+                tool: tool.to_string(),
+                ok: result.is_ok(),
+                duration_ms: call_started.elapsed().as_millis() as u64,
+            });
+            match result {
+                Ok(value) => rhai::serde::to_dynamic(value),
+                Err(e) => Err(format!("Tool '{tool}' failed: {e}").into()),
+            }
+        },
+    );
+
+    let ast = engine
+        .compile(source)
+        .map_err(|e| Error::Validation(format!("Script '{name}' does not compile: {e}")))?;
+    let mut scope = Scope::new();
+    let args = rhai::serde::to_dynamic(args)
+        .map_err(|e| Error::Validation(format!("Invalid script arguments: {e}")))?;
+    scope.push_constant("args", args);
+
+    debug!("Running workflow script '{}'", name);
+    let result = engine
+        .eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+        .map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) => Error::Timeout(format!(
+                "Script '{name}' stopped after {}s",
+                limits.timeout.as_secs()
+            )),
+            _ => Error::Validation(format!("Script '{name}' failed: {e}")),
+        })?;
+    let result: Value = rhai::serde::from_dynamic(&result).map_err(|e| {
+        Error::Validation(format!(
+            "Script '{name}' returned an unsupported value: {e}"
+        ))
+    })?;
+
+    let tool_calls = std::mem::take(&mut *tool_calls.lock().unwrap());
+    let output = std::mem::take(&mut *output.lock().unwrap());
+    Ok(ScriptRun {
+        script: name.to_string(),
+        result,
+        tool_calls,
+        output,
+        operations: operations.load(Ordering::Relaxed),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// Call one of [`SCRIPT_TOOLS`]
+async fn call_tool(
+    tool: &str,
+    arguments: Value,
+    brp_client: Arc<RwLock<BrpClient>>,
+) -> Result<Value> {
+    match tool {
+        "observe" => observe::handle(arguments, brp_client).await,
+        "types" => types::handle(arguments, brp_client).await,
+        "search_world" => search_world::handle(arguments, brp_client).await,
+        "hypothesis" => hypothesis::handle(arguments, brp_client).await,
+        "anomaly" => anomaly::handle(arguments, brp_client).await,
+        "experiment" => experiment::handle(arguments, brp_client).await,
+        "mutate" => mutate::handle(arguments, brp_client).await,
+        "stress" => stress::handle(arguments, brp_client).await,
+        _ => Err(Error::Validation(format!(
+            "Scripts cannot call '{tool}'; callable tools: {}",
+            SCRIPT_TOOLS.join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+
+    fn disconnected_client() -> Arc<RwLock<BrpClient>> {
+        Arc::new(RwLock::new(BrpClient::new(&Config::default())))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_scripts_branch_on_tool_results_within_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("find_goblins.rhai"),
+            r#"// Find goblins, or report why not
+let status = "found";
+try {
+    tool("search_world", #{ pattern: "Goblin" });
+} catch (err) {
+    print(`search failed: ${err}`);
+    status = "offline";
+}
+#{ status: status, limit: args.limit }
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("spin.rhai"), "loop { }").unwrap();
+        let library = ScriptLibrary::new(
+            dir.path(),
+            ScriptLimits {
+                max_operations: 10_000,
+                ..ScriptLimits::default()
+            },
+        );
+
+        let scripts = library.list().unwrap();
+        assert_eq!(scripts.len(), 2);
+        assert_eq!(scripts[0].description, "Find goblins, or report why not");
+
+        let run = library
+            .run("find_goblins", json!({"limit": 3}), disconnected_client())
+            .await
+            .unwrap();
+        assert_eq!(run.result, json!({"status": "offline", "limit": 3}));
+        assert_eq!(run.tool_calls.len(), 1);
+        assert!(!run.tool_calls[0].ok);
+        assert_eq!(run.output.len(), 1);
+
+        assert!(library
+            .run("spin", json!({}), disconnected_client())
+            .await
+            .is_err());
+        assert!(library
+            .run("../find_goblins", json!({}), disconnected_client())
+            .await
+            .is_err());
+    }
+}
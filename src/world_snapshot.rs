@@ -0,0 +1,384 @@
+/*
+ * Bevy Debugger MCP Server - World Snapshots
+ * Copyright (C) 2025 ladvien
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::brp_client::BrpClient;
+use crate::brp_messages::{
+    BrpRequest, BrpResponse, BrpResult, ComponentTypeId, ComponentValue, EntityData, EntityId,
+    QueryFilter,
+};
+use crate::error::{Error, Result};
+
+/// Key a snapshot is stored under in a checkpoint's `state_data`
+pub const WORLD_SNAPSHOT_KEY: &str = "world";
+
+/// Which part of the game world a snapshot covers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotFilter {
+    /// Only entities with all of these components
+    #[serde(default)]
+    pub with: Vec<ComponentTypeId>,
+    /// Skip entities with any of these components
+    #[serde(default)]
+    pub without: Vec<ComponentTypeId>,
+    /// Only these entities
+    #[serde(default)]
+    pub entities: Option<Vec<EntityId>>,
+    /// Components to store per entity; every component when empty
+    #[serde(default)]
+    pub components: Vec<ComponentTypeId>,
+    /// Resources to store
+    #[serde(default)]
+    pub resources: Vec<String>,
+}
+
+impl SnapshotFilter {
+    fn query(&self) -> BrpRequest {
+        let non_empty = |types: &Vec<ComponentTypeId>| (!types.is_empty()).then(|| types.clone());
+        BrpRequest::Query {
+            filter: Some(QueryFilter {
+                with: non_empty(&self.with),
+                without: non_empty(&self.without),
+                where_clause: None,
+            }),
+            limit: None,
+            strict: Some(false),
+        }
+    }
+
+    /// Drop entities and components outside the filter
+    fn apply(&self, entities: Vec<EntityData>) -> Vec<EntityData> {
+        entities
+            .into_iter()
+            .filter(|e| match &self.entities {
+                Some(ids) => ids.contains(&e.id),
+                None => true,
+            })
+            .map(|mut e| {
+                if !self.components.is_empty() {
+                    e.components.retain(|id, _| self.components.contains(id));
+                }
+                e
+            })
+            .collect()
+    }
+}
+
+/// Entities, components and resources captured from the live game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub filter: SnapshotFilter,
+    pub captured_at: DateTime<Utc>,
+    pub entities: Vec<EntityData>,
+    pub resources: BTreeMap<String, ComponentValue>,
+}
+
+/// Requests that bring the game back to a snapshot
+#[derive(Debug, Clone, Default)]
+pub struct RestorePlan {
+    /// Entities spawned since the snapshot
+    pub despawn: Vec<EntityId>,
+    /// Entities still alive; their snapshot components are written back
+    pub update: Vec<EntityData>,
+    /// Components added since the snapshot, per entity
+    pub remove: Vec<(EntityId, Vec<ComponentTypeId>)>,
+    /// Entities despawned since the snapshot
+    pub spawn: Vec<EntityData>,
+}
+
+/// Outcome of restoring a snapshot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub despawned: usize,
+    pub updated: usize,
+    /// Original ID to the ID of the entity spawned in its place
+    pub respawned: HashMap<EntityId, EntityId>,
+    pub resources_restored: usize,
+    /// Requests the game rejected; the rest of the restore still ran
+    pub errors: Vec<String>,
+}
+
+impl WorldSnapshot {
+    /// Read the entities and resources selected by `filter` from the game
+    pub async fn capture(
+        brp_client: &Arc<RwLock<BrpClient>>,
+        filter: SnapshotFilter,
+    ) -> Result<Self> {
+        let mut client = brp_client.write().await;
+        if !client.is_connected() {
+            return Err(Error::Connection("BRP client not connected".to_string()));
+        }
+
+        let entities = filter.apply(query_entities(&mut client, &filter).await?);
+
+        let mut resources = BTreeMap::new();
+        for resource in &filter.resources {
+            let request = BrpRequest::GetResource {
+                resource: resource.clone(),
+            };
+            match expect_success(client.send_request(&request).await?, "get_resource")? {
+                BrpResult::Resource(value) => {
+                    resources.insert(resource.clone(), value);
+                }
+                _ => {
+                    return Err(Error::Brp(format!(
+                        "Expected resource value for {resource}"
+                    )))
+                }
+            }
+        }
+
+        info!(
+            "Captured world snapshot: {} entities, {} resources",
+            entities.len(),
+            resources.len()
+        );
+        Ok(Self {
+            filter,
+            captured_at: Utc::now(),
+            entities,
+            resources,
+        })
+    }
+
+    /// Snapshot stored in a checkpoint's `state_data`, if any
+    pub fn from_state_data(state_data: &serde_json::Value) -> Result<Option<Self>> {
+        state_data
+            .get(WORLD_SNAPSHOT_KEY)
+            .map(|world| serde_json::from_value(world.clone()))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Work out what to change given the entities currently matching the filter
+    pub fn restore_plan(&self, current: &[EntityData]) -> RestorePlan {
+        let current: HashMap<EntityId, &EntityData> = current.iter().map(|e| (e.id, e)).collect();
+        let saved: HashSet<EntityId> = self.entities.iter().map(|e| e.id).collect();
+
+        let mut plan = RestorePlan {
+            despawn: current
+                .keys()
+                .filter(|id| !saved.contains(id))
+                .copied()
+                .collect(),
+            ..RestorePlan::default()
+        };
+        plan.despawn.sort_unstable();
+
+        for entity in &self.entities {
+            let Some(live) = current.get(&entity.id) else {
+                plan.spawn.push(entity.clone());
+                continue;
+            };
+            plan.update.push(entity.clone());
+
+            // Only a snapshot of every component knows which ones are new
+            if self.filter.components.is_empty() {
+                let mut added: Vec<ComponentTypeId> = live
+                    .components
+                    .keys()
+                    .filter(|id| !entity.components.contains_key(*id))
+                    .cloned()
+                    .collect();
+                if !added.is_empty() {
+                    added.sort();
+                    plan.remove.push((entity.id, added));
+                }
+            }
+        }
+        plan
+    }
+
+    /// Put the game back into the captured state
+    ///
+    /// Despawned entities are spawned again but receive new IDs, which are
+    /// listed in the report.
+    pub async fn restore(&self, brp_client: &Arc<RwLock<BrpClient>>) -> Result<RestoreReport> {
+        let mut client = brp_client.write().await;
+        if !client.is_connected() {
+            return Err(Error::Connection("BRP client not connected".to_string()));
+        }
+
+        let current = self
+            .filter
+            .apply(query_entities(&mut client, &self.filter).await?);
+        let plan = self.restore_plan(&current);
+        let mut report = RestoreReport::default();
+
+        for entity in plan.despawn {
+            let request = BrpRequest::Destroy { entity };
+            match send(&mut client, &request, "destroy").await {
+                Ok(_) => report.despawned += 1,
+                Err(e) => report.errors.push(format!("Entity {entity}: {e}")),
+            }
+        }
+
+        for entity in plan.update {
+            let request = BrpRequest::Insert {
+                entity: entity.id,
+                components: entity.components,
+            };
+            match send(&mut client, &request, "insert").await {
+                Ok(_) => report.updated += 1,
+                Err(e) => report.errors.push(format!("Entity {}: {e}", entity.id)),
+            }
+        }
+
+        for (entity, components) in plan.remove {
+            let request = BrpRequest::Remove { entity, components };
+            if let Err(e) = send(&mut client, &request, "remove").await {
+                report.errors.push(format!("Entity {entity}: {e}"));
+            }
+        }
+
+        for entity in plan.spawn {
+            let request = BrpRequest::Spawn {
+                components: entity.components,
+            };
+            match send(&mut client, &request, "spawn").await {
+                Ok(BrpResult::EntityId(new_id) | BrpResult::EntitySpawned(new_id)) => {
+                    report.respawned.insert(entity.id, new_id);
+                }
+                Ok(_) => report
+                    .errors
+                    .push(format!("Entity {}: spawn returned no entity ID", entity.id)),
+                Err(e) => report.errors.push(format!("Entity {}: {e}", entity.id)),
+            }
+        }
+
+        for (resource, value) in &self.resources {
+            let request = BrpRequest::InsertResource {
+                resource: resource.clone(),
+                value: value.clone(),
+            };
+            match send(&mut client, &request, "insert_resource").await {
+                Ok(_) => report.resources_restored += 1,
+                Err(e) => report.errors.push(format!("Resource {resource}: {e}")),
+            }
+        }
+
+        if !report.errors.is_empty() {
+            warn!("World restore finished with {} errors", report.errors.len());
+        }
+        Ok(report)
+    }
+}
+
+async fn query_entities(
+    client: &mut BrpClient,
+    filter: &SnapshotFilter,
+) -> Result<Vec<EntityData>> {
+    match send(client, &filter.query(), "query").await? {
+        BrpResult::Entities(entities) => Ok(entities),
+        _ => Err(Error::Brp("Expected entities from BRP query".to_string())),
+    }
+}
+
+async fn send(client: &mut BrpClient, request: &BrpRequest, method: &str) -> Result<BrpResult> {
+    expect_success(client.send_request(request).await?, method)
+}
+
+fn expect_success(response: BrpResponse, method: &str) -> Result<BrpResult> {
+    match response {
+        BrpResponse::Success(result) => Ok(*result),
+        BrpResponse::Error(error) => Err(Error::Brp(format!(
+            "{method} request failed: {}",
+            error.message
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entity(id: EntityId, components: &[(&str, ComponentValue)]) -> EntityData {
+        EntityData {
+            id,
+            components: components
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    fn snapshot(filter: SnapshotFilter, entities: Vec<EntityData>) -> WorldSnapshot {
+        WorldSnapshot {
+            filter,
+            captured_at: Utc::now(),
+            entities,
+            resources: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_restore_plan_reverts_world() {
+        let saved = snapshot(
+            SnapshotFilter::default(),
+            vec![
+                entity(1, &[("Transform", json!({"y": 4.0}))]),
+                entity(2, &[("Health", json!(100))]),
+            ],
+        );
+        let current = vec![
+            entity(
+                1,
+                &[("Transform", json!({"y": -50.0})), ("Falling", json!({}))],
+            ),
+            entity(3, &[("Projectile", json!({}))]),
+        ];
+
+        let plan = saved.restore_plan(&current);
+        assert_eq!(plan.despawn, vec![3]);
+        assert_eq!(plan.update[0].components["Transform"], json!({"y": 4.0}));
+        assert_eq!(plan.remove, vec![(1, vec!["Falling".to_string()])]);
+        assert_eq!(plan.spawn[0].id, 2);
+    }
+
+    #[test]
+    fn test_filter_limits_snapshot() {
+        let filter = SnapshotFilter {
+            entities: Some(vec![1]),
+            components: vec!["Transform".to_string()],
+            ..SnapshotFilter::default()
+        };
+        let entities = filter.apply(vec![
+            entity(1, &[("Transform", json!({})), ("Sprite", json!({}))]),
+            entity(2, &[("Transform", json!({}))]),
+        ]);
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].components.len(), 1);
+
+        // Components outside the filter are left alone on restore
+        let plan = snapshot(filter, entities).restore_plan(&[entity(
+            1,
+            &[("Transform", json!({})), ("Sprite", json!({}))],
+        )]);
+        assert!(plan.remove.is_empty());
+        assert!(WorldSnapshot::from_state_data(&json!({"note": "no world"}))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -46,7 +46,7 @@ async fn test_epic_6_security_observability_integration() -> Result<()> {
 
     // Test 2: Initialize security system
     let security_config = bevy_debugger_mcp::security::config::SecurityConfig::default();
-    let security_manager = SecurityManager::new(security_config)?;
+    let security_manager = SecurityManager::new(security_config).await?;
     
     // Create a test user first
     // We need an admin token to create users, so let's authenticate as admin first
@@ -146,7 +146,7 @@ async fn test_security_brp_isolation() -> Result<()> {
 
     // Test that security failures don't affect BRP connection state
     let security_config = bevy_debugger_mcp::security::config::SecurityConfig::default();
-    let security_manager = SecurityManager::new(security_config)?;
+    let security_manager = SecurityManager::new(security_config).await?;
 
     // Simulate authentication failures
     for i in 0..5 {
@@ -177,7 +177,7 @@ async fn test_security_brp_isolation() -> Result<()> {
 async fn test_security_performance_overhead() -> Result<()> {
     let config = Config::from_env()?;
     let security_config = bevy_debugger_mcp::security::config::SecurityConfig::default();
-    let security_manager = SecurityManager::new(security_config)?;
+    let security_manager = SecurityManager::new(security_config).await?;
 
     // Create admin token and test user
     let admin_token = security_manager
@@ -1,5 +1,7 @@
 use bevy_debugger_mcp::checkpoint::{Checkpoint, CheckpointConfig, CheckpointManager};
-use bevy_debugger_mcp::dead_letter_queue::{DeadLetterConfig, DeadLetterQueue, FailedOperation};
+use bevy_debugger_mcp::dead_letter_queue::{
+    DeadLetterConfig, DeadLetterQueue, FailedOperation, RetryPolicy,
+};
 use bevy_debugger_mcp::diagnostics::DiagnosticCollector;
 use bevy_debugger_mcp::error::{Error, ErrorContext, ErrorSeverity};
 use serde_json::json;
@@ -118,6 +120,68 @@ async fn test_dead_letter_queue_capacity() {
     assert!(!operation_names.contains(&"operation_0"));
 }
 
+/// Test automatic retries under a retry policy
+#[tokio::test]
+async fn test_dead_letter_queue_retry_policy() {
+    let dlq = DeadLetterQueue::new(DeadLetterConfig::default());
+    let policy = RetryPolicy {
+        max_attempts: 2,
+        initial_backoff_ms: 0,
+        jitter: 0.0,
+        ..Default::default()
+    };
+    dlq.set_retry_policy("observe", Some(policy)).await.unwrap();
+
+    let failed_op = FailedOperation::new(
+        "observe",
+        "mcp_server",
+        0,
+        ErrorContext::new("observe", "mcp_server"),
+        json!({"query": "entities with Transform"}),
+        "Connection error: Not connected to BRP",
+    );
+    let id = failed_op.id.clone();
+    dlq.add_failed_operation(failed_op).await.unwrap();
+
+    let now_ms = u64::MAX;
+    let due = dlq.take_due_retries(now_ms).await;
+    assert_eq!(due.len(), 1);
+    // Claimed until the outcome is recorded
+    assert!(dlq.take_due_retries(now_ms).await.is_empty());
+
+    dlq.record_retry_outcome(&id, Err("still disconnected".to_string()))
+        .await;
+    assert_eq!(dlq.take_due_retries(now_ms).await.len(), 1);
+
+    // The policy allows two attempts; the operation stays for manual handling
+    dlq.record_retry_outcome(&id, Err("still disconnected".to_string()))
+        .await;
+    assert!(dlq.take_due_retries(now_ms).await.is_empty());
+    let operations = dlq.get_failed_operations().await;
+    assert_eq!(operations[0].retry_outcomes.len(), 2);
+    assert_eq!(operations[0].failure_reason, "still disconnected");
+
+    let stats = dlq.get_statistics().await;
+    assert_eq!(stats.retries_failed, 2);
+    assert_eq!(stats.pending_retries, 0);
+
+    // A successful retry removes the operation
+    let retried = FailedOperation::new(
+        "observe",
+        "mcp_server",
+        0,
+        ErrorContext::new("observe", "mcp_server"),
+        json!({}),
+        "Timeout: BRP request",
+    );
+    let retried_id = retried.id.clone();
+    dlq.add_failed_operation(retried).await.unwrap();
+    assert_eq!(dlq.take_due_retries(now_ms).await.len(), 1);
+    dlq.record_retry_outcome(&retried_id, Ok(())).await;
+    assert_eq!(dlq.get_failed_operations().await.len(), 1);
+    assert!(!Error::Validation("bad query".to_string()).is_transient());
+}
+
 /// Test DiagnosticCollector functionality
 #[tokio::test]
 async fn test_diagnostic_collector() {
@@ -417,6 +481,28 @@ async fn test_bug_report_generation() {
     assert!(bug_report.contains("Report ID"));
 }
 
+#[tokio::test]
+async fn test_bug_report_redaction() {
+    let collector = DiagnosticCollector::new(10).with_redaction(
+        bevy_debugger_mcp::report_redaction::ReportRedactionPolicy {
+            deny: vec!["system_info.hostname".to_string()],
+            ..Default::default()
+        },
+    );
+    let diagnostic_report = collector.generate_report(None).await.unwrap();
+
+    let bug_report = collector.bug_report(&diagnostic_report, "Crash on load", "1. Load");
+    assert!(bug_report.contains("- Hostname: [REDACTED]"));
+    assert!(bug_report.contains(&format!("- OS: {}", std::env::consts::OS)));
+
+    let export = collector
+        .export_report_json(&diagnostic_report)
+        .await
+        .unwrap();
+    let exported: serde_json::Value = serde_json::from_str(&export).unwrap();
+    assert_eq!(exported["system_info"]["hostname"], "[REDACTED]");
+}
+
 /// Integration test for error recovery workflow
 #[tokio::test]
 async fn test_error_recovery_integration() {
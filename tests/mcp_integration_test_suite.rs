@@ -45,6 +45,8 @@ fn create_observe_params() -> ObserveRequest {
         diff: false,
         detailed: false,
         reflection: false,
+        include: vec![],
+        delta: false,
     }
 }
 
@@ -209,6 +211,8 @@ async fn test_tool_parameter_validation() {
         diff: true,
         detailed: true,
         reflection: true,
+        include: vec![],
+        delta: false,
     };
     
     // This should not panic and should be deserializable
@@ -249,6 +253,8 @@ async fn test_error_scenarios() {
         diff: true,
         detailed: true,
         reflection: true,
+        include: vec![],
+        delta: false,
     };
     
     let result = tools.observe(rmcp::handler::server::tool::Parameters(edge_case_observe)).await;
@@ -45,7 +45,7 @@ mod test_fixtures {
     pub async fn create_test_server() -> Result<McpServerV2, BevyDebuggerError> {
         let config = create_test_config();
         let brp_client = Arc::new(RwLock::new(BrpClient::new(&config)));
-        McpServerV2::new(config, brp_client)
+        McpServerV2::new(config, brp_client).await
     }
 }
 
@@ -131,6 +131,8 @@ mod tool_integration {
             diff: false,
             detailed: false,
             reflection: false,
+            include: vec![],
+            delta: false,
         };
         
         let result = tools.observe(Parameters(request)).await;
@@ -277,6 +279,8 @@ mod tool_integration {
             diff: true,
             detailed: true,
             reflection: true,
+            include: vec![],
+            delta: false,
         };
         
         let result = tools.observe(Parameters(invalid_observe)).await;
@@ -348,6 +352,8 @@ mod error_handling {
             diff: false,
             detailed: false,
             reflection: false,
+            include: vec![],
+            delta: false,
         };
         
         let result = tools.observe(rmcp::handler::server::tool::Parameters(request)).await;
@@ -382,6 +388,8 @@ mod load_testing {
                     diff: false,
                     detailed: false,
                     reflection: false,
+                    include: vec![],
+                    delta: false,
                 };
                 
                 let _result = tools_clone.observe(rmcp::handler::server::tool::Parameters(request)).await;
@@ -407,6 +415,8 @@ mod load_testing {
             diff: false,
             detailed: false,
             reflection: false,
+            include: vec![],
+            delta: false,
         };
         
         // Use timeout to simulate connection timeout scenarios
@@ -443,6 +453,8 @@ mod load_testing {
                     diff: false,
                     detailed: false,
                     reflection: false,
+                    include: vec![],
+                    delta: false,
                 };
                 
                 let result = tools_clone.observe(rmcp::handler::server::tool::Parameters(request)).await;
@@ -525,6 +537,8 @@ mod performance_validation {
             diff: false,
             detailed: false,
             reflection: false,
+            include: vec![],
+            delta: false,
         };
         
         let _result = tools.observe(rmcp::handler::server::tool::Parameters(request)).await;
@@ -548,6 +562,8 @@ mod performance_validation {
                 diff: false,
                 detailed: false,
                 reflection: false,
+                include: vec![],
+                delta: false,
             };
             
             let _result = tools.observe(rmcp::handler::server::tool::Parameters(request)).await;
@@ -7,7 +7,7 @@ use bevy_debugger_mcp::pattern_learning::{
     AnonymizedCommand, DebugPattern, PatternLearningSystem, PatternMiner, TimeBucket,
 };
 use bevy_debugger_mcp::suggestion_engine::{
-    DebugSuggestion, SuggestionContext, SuggestionEngine, SystemState,
+    DebugSuggestion, SuggestionContext, SuggestionEngine, SuggestionFeedback, SystemState,
 };
 use bevy_debugger_mcp::brp_messages::DebugCommand;
 use std::collections::HashMap;
@@ -246,6 +246,54 @@ async fn test_suggestion_tracking() {
     assert_eq!(*success_rate, 0.5); // 50% success rate
 }
 
+#[tokio::test]
+async fn test_suggestion_feedback_reweights_suggestions() {
+    let pattern_system = Arc::new(PatternLearningSystem::new());
+    let suggestion_engine = SuggestionEngine::new(pattern_system.clone());
+    let context = SuggestionContext {
+        session_id: "feedback_session".to_string(),
+        recent_commands: vec![],
+        system_state: SystemState {
+            entity_count: 100,
+            fps: 20.0,
+            memory_mb: 100.0,
+            active_systems: 10,
+            has_errors: false,
+        },
+        user_goal: None,
+    };
+    let confidence_of = |suggestions: &[DebugSuggestion], id: &str| {
+        suggestions.iter().find(|s| s.id == id).map(|s| s.confidence)
+    };
+    
+    let before = suggestion_engine.generate_suggestions(&context).await;
+    let initial = confidence_of(&before, "command:profile_system").unwrap();
+    
+    // Two rejections lower the suggestion, an accepted one that helped raises it again
+    for _ in 0..2 {
+        suggestion_engine
+            .record_feedback("command:profile_system", SuggestionFeedback::Rejected, None)
+            .await
+            .unwrap();
+    }
+    let after = suggestion_engine.generate_suggestions(&context).await;
+    assert!(confidence_of(&after, "command:profile_system").unwrap() < initial);
+    let weight = suggestion_engine
+        .record_feedback("command:profile_system", SuggestionFeedback::Accepted, Some(true))
+        .await
+        .unwrap();
+    assert!((weight - 0.9).abs() < 1e-9);
+    
+    assert!(suggestion_engine
+        .record_feedback("pattern:missing", SuggestionFeedback::Accepted, None)
+        .await
+        .is_err());
+    assert!(suggestion_engine
+        .record_feedback("made_up", SuggestionFeedback::Ignored, None)
+        .await
+        .is_err());
+}
+
 #[tokio::test]
 async fn test_pattern_export_import() {
     let system = PatternLearningSystem::new();
@@ -275,6 +323,37 @@ async fn test_pattern_export_import() {
     // (though they might be empty due to k-anonymity requirements)
 }
 
+#[tokio::test]
+async fn test_patterns_persist_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shared").join("patterns.json");
+    let system = PatternLearningSystem::with_store(&path).await;
+    assert_eq!(system.pattern_count(), 0);
+    
+    // Five sessions pass the k-anonymity buffer and are saved as learned
+    for i in 0..5 {
+        let session_id = format!("persist_session_{}", i);
+        system.start_session(session_id.clone()).await;
+        system.record_command(
+            &session_id,
+            DebugCommand::GetSystemInfo { system_name: None, include_scheduling: Some(true) },
+            Duration::from_millis(10),
+        ).await;
+        system.end_session(&session_id, true).await.unwrap();
+    }
+    assert!(system.pattern_count() > 0);
+    assert!(path.exists());
+    
+    let restarted = PatternLearningSystem::with_store(&path).await;
+    assert_eq!(restarted.pattern_count(), system.pattern_count());
+    
+    // Importing a teammate's export twice does not duplicate or inflate patterns
+    let exported = system.export_patterns().await.unwrap();
+    restarted.import_patterns(&exported).await.unwrap();
+    restarted.import_patterns(&exported).await.unwrap();
+    assert_eq!(restarted.export_patterns().await.unwrap(), exported);
+}
+
 #[tokio::test]
 async fn test_privacy_preservation() {
     let system = PatternLearningSystem::new();
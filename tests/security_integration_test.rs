@@ -16,16 +16,26 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde_json::json;
+use rmcp::handler::server::tool::Parameters;
 
 use bevy_debugger_mcp::{
-    security::{SecurityManager, SecurityConfig, Role},
-    secure_mcp_tools::SecureMcpTools,
+    security::{SecurityAudit, SecurityManager, SecurityMiddleware, SecurityConfig, Role, API_KEY_PREFIX, REFRESH_TOKEN_PREFIX},
+    secure_mcp_tools::{AuthRequest, SecureMcpTools},
     brp_client::BrpClient,
+    mcp_server::McpServer,
     config::Config,
     error::Error,
+    mtls::{parse_cn_mapping, MtlsConfig},
+    totp::{base32_decode, hotp, TOTP_STEP_SECONDS},
+    ip_filter::{parse_ip_list, IpFilter},
+    redaction::{RedactionRules, REDACTED},
+    brp_messages::EntityData,
+    tools::search_world::{self, SearchScope, ValuePattern},
+    user_store::{EncryptedFileUserStore, UserStore, UserStoreSnapshot},
 };
 
 /// Create a test security manager
@@ -36,7 +46,7 @@ async fn create_test_security_manager() -> Arc<SecurityManager> {
     config.password_min_length = 4;
     config.rate_limit_per_ip = 1000; // High limit for tests
     
-    Arc::new(SecurityManager::new(config).expect("Failed to create security manager"))
+    Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"))
 }
 
 /// Create a test BRP client (mock)
@@ -45,6 +55,11 @@ fn create_test_brp_client() -> Arc<RwLock<BrpClient>> {
     Arc::new(RwLock::new(BrpClient::new(&config)))
 }
 
+/// Create a tool server around a test BRP client
+fn create_test_tool_server() -> McpServer {
+    McpServer::new(Config::default(), create_test_brp_client())
+}
+
 #[tokio::test]
 async fn test_authentication_flow() {
     let security_manager = create_test_security_manager().await;
@@ -151,6 +166,583 @@ async fn test_token_revocation() {
     assert!(result.is_err(), "Token should be invalid after revocation");
 }
 
+#[tokio::test]
+async fn test_token_renewal() {
+    let security_manager = create_test_security_manager().await;
+    
+    let pair = security_manager
+        .authenticate_with_refresh("admin", "admin123", None, None, None, None)
+        .await
+        .expect("Authentication should succeed");
+    assert!(pair.refresh_token.starts_with(REFRESH_TOKEN_PREFIX), "Refresh token should carry its prefix");
+    
+    // Renewal keeps the session and rotates the refresh token
+    let renewed = security_manager
+        .renew_token(&pair.refresh_token, None)
+        .await
+        .expect("Renewal should succeed");
+    assert_ne!(renewed.refresh_token, pair.refresh_token, "Refresh token should rotate");
+    let old_claims = security_manager.validate_token(&pair.access_token).await.unwrap();
+    let new_claims = security_manager.validate_token(&renewed.access_token).await.unwrap();
+    assert_eq!(old_claims.session_id, new_claims.session_id, "Renewal should keep the session");
+    
+    // Reusing a rotated refresh token revokes the whole session
+    assert!(security_manager.renew_token(&pair.refresh_token, None).await.is_err(), "Rotated refresh token should be rejected");
+    assert!(security_manager.validate_token(&renewed.access_token).await.is_err(), "Session should be revoked after reuse");
+    assert!(security_manager.renew_token(&renewed.refresh_token, None).await.is_err(), "Refresh tokens of a revoked session should be rejected");
+}
+
+#[tokio::test]
+async fn test_two_factor_authentication() {
+    let security_manager = create_test_security_manager().await;
+    let current_code = |secret: &str, offset_steps: u64| {
+        let step = chrono::Utc::now().timestamp() as u64 / TOTP_STEP_SECONDS;
+        hotp(&base32_decode(secret).unwrap(), step + offset_steps)
+    };
+    
+    let admin_token = security_manager
+        .authenticate("admin", "admin123", None, None)
+        .await
+        .expect("Admin authentication should succeed");
+    security_manager
+        .create_user(&admin_token, "analyst", "analyst1", Role::Viewer)
+        .await
+        .expect("User creation should succeed");
+    let viewer_token = security_manager.authenticate("analyst", "analyst1", None, None).await.unwrap();
+    assert!(security_manager.enroll_two_factor(&viewer_token).await.is_err(), "Viewers should not enroll in two-factor");
+    
+    // Enrollment is not enforced until confirmed
+    let enrollment = security_manager.enroll_two_factor(&admin_token).await.expect("Enrollment should succeed");
+    assert!(enrollment.otpauth_uri.starts_with("otpauth://totp/"));
+    assert!(security_manager.authenticate("admin", "admin123", None, None).await.is_ok());
+    assert!(security_manager.confirm_two_factor(&admin_token, &enrollment.recovery_codes[0]).await.is_err(), "Recovery codes should not confirm enrollment");
+    security_manager
+        .confirm_two_factor(&admin_token, &current_code(&enrollment.secret, 0))
+        .await
+        .expect("Confirmation should succeed");
+    
+    // Password alone or a wrong code is rejected
+    assert!(security_manager.authenticate("admin", "admin123", None, None).await.is_err(), "Second factor should be required");
+    assert!(security_manager.authenticate_with_second_factor("admin", "admin123", Some("not-a-code"), None, None).await.is_err());
+    
+    // The next step's code works once; the confirmation code cannot be replayed
+    let code = current_code(&enrollment.secret, 1);
+    assert!(security_manager.authenticate_with_second_factor("admin", "admin123", Some(&code), None, None).await.is_ok());
+    assert!(security_manager.authenticate_with_second_factor("admin", "admin123", Some(&code), None, None).await.is_err(), "Codes should not be replayed");
+    
+    // Recovery codes work once each
+    let recovery = &enrollment.recovery_codes[0];
+    assert!(security_manager.authenticate_with_second_factor("admin", "admin123", Some(recovery), None, None).await.is_ok());
+    assert!(security_manager.authenticate_with_second_factor("admin", "admin123", Some(recovery), None, None).await.is_err(), "Recovery codes should be single use");
+    
+    assert!(security_manager.disable_two_factor(&admin_token, "admin", None).await.is_err(), "Disabling your own should need a code");
+    security_manager
+        .disable_two_factor(&admin_token, "admin", Some(&current_code(&enrollment.secret, 2)))
+        .await
+        .expect("Disabling should succeed");
+    assert!(security_manager.authenticate("admin", "admin123", None, None).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_disabling_two_factor_requires_code_or_admin() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.rate_limit_per_ip = 1000;
+    config.password_min_length = 4;
+    config.mtls = Some(MtlsConfig {
+        cert_path: "server.pem".to_string(),
+        key_path: "server.key".to_string(),
+        client_ca_path: "ca.pem".to_string(),
+        bind_address: "127.0.0.1".to_string(),
+        identity_mapping: parse_cn_mapping("admin.studio=admin").unwrap(),
+        require_jwt: false,
+    });
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    let current_code = |secret: &str| {
+        let step = chrono::Utc::now().timestamp() as u64 / TOTP_STEP_SECONDS;
+        hotp(&base32_decode(secret).unwrap(), step)
+    };
+
+    let admin_token = security_manager.authenticate_client_certificate("admin.studio", None).await.unwrap();
+    security_manager.create_user(&admin_token, "tester", "tester1", Role::Developer).await.unwrap();
+    let tester_token = security_manager.authenticate("tester", "tester1", None, None).await.unwrap();
+    let enrollment = security_manager.enroll_two_factor(&tester_token).await.unwrap();
+    security_manager.confirm_two_factor(&tester_token, &current_code(&enrollment.secret)).await.unwrap();
+
+    // A session alone can't remove its own second factor
+    assert!(security_manager.disable_two_factor(&tester_token, "tester", None).await.is_err());
+    assert!(security_manager.disable_two_factor(&tester_token, "tester", Some("000000x")).await.is_err());
+    security_manager
+        .disable_two_factor(&tester_token, "tester", Some(&enrollment.recovery_codes[0]))
+        .await
+        .expect("A recovery code should disable two-factor");
+
+    // An admin can disable another user's without a code
+    let tester_token = security_manager.authenticate("tester", "tester1", None, None).await.unwrap();
+    let enrollment = security_manager.enroll_two_factor(&tester_token).await.unwrap();
+    security_manager.confirm_two_factor(&tester_token, &current_code(&enrollment.secret)).await.unwrap();
+    security_manager
+        .disable_two_factor(&admin_token, "tester", None)
+        .await
+        .expect("Admins should disable another user's two-factor");
+    assert!(security_manager.authenticate("tester", "tester1", None, None).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_default_secret_rejected_in_production() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "default_jwt_secret_change_in_production_0123456789".to_string();
+    assert!(SecurityManager::new(config.clone()).await.is_ok(), "Default secrets are tolerated in development");
+    
+    config.production_mode = true;
+    assert!(SecurityManager::new(config).await.is_err(), "Production mode should refuse a default JWT secret");
+}
+
+#[tokio::test]
+async fn test_unreadable_user_store_fails_startup_and_is_kept() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("users.json");
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.user_store_path = Some(path.to_string_lossy().into_owned());
+    config.user_store_key = Some("first-user-store-key-at-least-32-characters".to_string());
+    SecurityManager::new(config.clone()).await.expect("Failed to create security manager");
+    let stored = std::fs::read(&path).unwrap();
+
+    config.user_store_key = Some("other-user-store-key-at-least-32-characters".to_string());
+    assert!(SecurityManager::new(config).await.is_err(), "A store that can't be decrypted must stop startup");
+    assert_eq!(std::fs::read(&path).unwrap(), stored, "The store must not be overwritten");
+}
+
+#[tokio::test]
+async fn test_user_store_keeps_lockouts_of_existing_users_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("users.json");
+    let key = "lockout-user-store-key-at-least-32-characters";
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.rate_limit_per_ip = 1000;
+    config.user_store_path = Some(path.to_string_lossy().into_owned());
+    config.user_store_key = Some(key.to_string());
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+
+    for username in ["ghost-1", "ghost-2", "admin"] {
+        assert!(security_manager.authenticate(username, "wrong_password", None, None).await.is_err());
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let snapshot = EncryptedFileUserStore::new(&path, key).unwrap().load().await.unwrap().unwrap();
+    assert!(snapshot.lockouts.contains_key("admin"), "Existing users' lockouts should be saved");
+    assert!(!snapshot.lockouts.keys().any(|username| username.starts_with("ghost")), "Unknown usernames must not grow the store");
+}
+
+#[tokio::test]
+async fn test_concurrent_user_store_saves_leave_a_readable_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("users.json");
+    let store = Arc::new(EncryptedFileUserStore::new(&path, "concurrent-user-store-key-at-least-32-chars").unwrap());
+    let snapshot = Arc::new(UserStoreSnapshot {
+        users: HashMap::new(),
+        lockouts: HashMap::new(),
+        api_keys: HashMap::new(),
+    });
+
+    let saves: Vec<_> = (0..16)
+        .map(|_| {
+            let store = store.clone();
+            let snapshot = snapshot.clone();
+            tokio::spawn(async move { store.save(&snapshot).await })
+        })
+        .collect();
+    for save in saves {
+        save.await.unwrap().expect("Concurrent saves should all succeed");
+    }
+    assert!(store.load().await.expect("The store should stay readable").is_some());
+}
+
+#[tokio::test]
+async fn test_ip_filter_rejects_and_audits_clients() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("audit.log");
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.rate_limit_per_ip = 1000;
+    config.ip_failure_delay_ms = 0;
+    config.audit_log_persistence = true;
+    config.audit_log_path = log_path.to_string_lossy().into_owned();
+    config.ip_filter = IpFilter {
+        allow: parse_ip_list("127.0.0.1, 10.0.0.0/8").unwrap(),
+        deny: parse_ip_list("10.13.0.0/16").unwrap(),
+    };
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    let middleware = SecurityMiddleware::new(security_manager.clone());
+    
+    assert!(middleware.check_client_address("10.2.3.4".parse().unwrap(), "tcp").await.is_ok());
+    assert!(middleware.check_client_address("10.13.0.9".parse().unwrap(), "tcp").await.is_err(), "Denied range should win over the allow list");
+    assert!(middleware.check_client_address("192.168.1.20".parse().unwrap(), "tcp").await.is_err(), "Addresses outside the allow list should be rejected");
+    
+    // The authenticate tool checks the connection's address before the credentials
+    let login = || AuthRequest {
+        username: "admin".to_string(),
+        password: "Winter2025!".to_string(),
+        otp_code: None,
+        new_password: None,
+    };
+    let tools = SecureMcpTools::new(create_test_tool_server(), security_manager.clone());
+    let error = tools
+        .with_client_address("192.168.1.20".parse().unwrap())
+        .authenticate(Parameters(login()))
+        .await
+        .unwrap_err();
+    assert!(error.message.contains("Client address is not allowed"), "{}", error.message);
+    let error = tools
+        .with_client_address("127.0.0.1".parse().unwrap())
+        .authenticate(Parameters(login()))
+        .await
+        .unwrap_err();
+    assert!(!error.message.contains("Client address is not allowed"), "Allowed address should reach the credential check");
+    
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let rejected = std::fs::read_to_string(&log_path)
+        .unwrap()
+        .lines()
+        .filter(|line| line.contains("\"connection_rejected\""))
+        .count();
+    assert_eq!(rejected, 3, "Each rejected client should be audited");
+}
+
+#[tokio::test]
+async fn test_session_revocation() {
+    let security_manager = create_test_security_manager().await;
+    
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    security_manager.create_user(&admin_token, "tester", "tester1", Role::Developer).await.unwrap();
+    let first = security_manager.authenticate("tester", "tester1", Some("10.0.0.5".to_string()), None).await.unwrap();
+    let second = security_manager.authenticate("tester", "tester1", None, None).await.unwrap();
+    
+    let sessions = security_manager.get_active_sessions(&admin_token).await.unwrap();
+    let first_session = sessions
+        .iter()
+        .find(|session| session.ip_address.as_deref() == Some("10.0.0.5"))
+        .expect("Session should record its IP address");
+    assert_eq!(first_session.user_id, "tester");
+    
+    // Revoking one session leaves the user's other sessions alone
+    security_manager.revoke_session(&admin_token, &first_session.id).await.unwrap();
+    assert!(security_manager.validate_token(&first).await.is_err());
+    assert!(security_manager.validate_token(&second).await.is_ok());
+    assert!(security_manager.revoke_session(&admin_token, &first_session.id).await.is_err(), "Ended sessions cannot be revoked twice");
+    
+    assert_eq!(security_manager.revoke_user_sessions(&admin_token, "tester").await.unwrap(), 1);
+    assert!(security_manager.validate_token(&second).await.is_err());
+    assert!(security_manager.revoke_user_sessions(&second, "admin").await.is_err(), "Only admins can revoke sessions");
+}
+
+#[tokio::test]
+async fn test_security_metrics_and_scheduled_scan() {
+    let security_manager = create_test_security_manager().await;
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    assert!(security_manager.authenticate("admin", "wrong_password", None, None).await.is_err());
+    // Failed logins are recorded in the background
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    
+    let metrics = security_manager.security_metrics(&admin_token).await.unwrap();
+    assert_eq!(metrics.active_sessions, 1);
+    assert_eq!(metrics.failed_login_attempts, 1);
+    assert_eq!(metrics.recent_failures.get("authentication"), Some(&1));
+    
+    // Scheduled scans need no token and record each finding
+    let audit = SecurityAudit::new(security_manager.clone());
+    let report = audit.run_scheduled_scan().await;
+    assert!(!report.vulnerabilities.is_empty(), "Test configuration has a weak password policy");
+    let findings = security_manager
+        .get_audit_log(&admin_token, None, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|entry| entry.action == "security_scan_finding")
+        .count();
+    assert_eq!(findings, report.vulnerabilities.len());
+}
+
+#[tokio::test]
+async fn test_per_role_rate_limits() {
+    let dir = tempfile::tempdir().unwrap();
+    let limits = dir.path().join("limits.toml");
+    std::fs::write(&limits, r#"
+        default = { per_minute = 60, burst = 2 }
+
+        [roles.Admin]
+        per_minute = 600
+        burst = 20
+    "#).unwrap();
+    
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.rate_limit_per_ip = 1000;
+    config.rate_limit_config_path = Some(limits.to_string_lossy().into_owned());
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    let middleware = SecurityMiddleware::new(security_manager.clone());
+    
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    security_manager.create_user(&admin_token, "watcher", "watcher1", Role::Viewer).await.unwrap();
+    let viewer_token = security_manager.authenticate("watcher", "watcher1", None, None).await.unwrap();
+    
+    for _ in 0..2 {
+        middleware.authorize_tool_call(Some(&viewer_token), "observe").await.expect("Within quota");
+    }
+    assert!(middleware.authorize_tool_call(Some(&viewer_token), "observe").await.is_err(), "Viewer quota should be exhausted");
+    for _ in 0..5 {
+        middleware.authorize_tool_call(Some(&admin_token), "observe").await.expect("Admins have a higher limit");
+    }
+    
+    let status = security_manager.rate_limit_status(&viewer_token, false).await.unwrap();
+    assert_eq!(status.quotas[0].remaining, 0);
+    assert!(security_manager.rate_limit_status(&viewer_token, true).await.is_err(), "Only admins can reload limits");
+    
+    // Reloading with a bigger burst refills over time, not instantly
+    std::fs::write(&limits, r#"default = { per_minute = 60, burst = 50 }"#).unwrap();
+    let status = security_manager.rate_limit_status(&admin_token, true).await.unwrap();
+    assert_eq!(status.quotas[0].burst, 50);
+}
+
+#[tokio::test]
+async fn test_guest_access_is_read_only_and_redacted() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.guest_redaction = RedactionRules::new(vec!["PlayerName.*".to_string(), "ChatMessage.text".to_string()]);
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    let middleware = SecurityMiddleware::new(security_manager.clone());
+    
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    security_manager.create_user(&admin_token, "visitor", "visitor1", Role::Guest).await.unwrap();
+    let guest_token = security_manager.authenticate("visitor", "visitor1", None, None).await.unwrap();
+    
+    let claims = middleware.authorize_tool_call(Some(&guest_token), "observe").await.expect("Guests can observe");
+    assert_eq!(claims.role, Role::Guest);
+    middleware.authorize_tool_call(Some(&guest_token), "detect_anomaly").await.expect("Guests can run diagnostics");
+    for tool in ["hypothesis", "experiment", "stress_test", "user_management"] {
+        assert!(middleware.authorize_tool_call(Some(&guest_token), tool).await.is_err(), "Guests must not call {}", tool);
+    }
+    
+    let output = json!({"game::PlayerName": "alice", "game::ChatMessage": {"text": "hi", "channel": "all"}});
+    let redacted = security_manager.redact_for_role(&Role::Guest, output.clone());
+    assert_eq!(redacted["game::PlayerName"], REDACTED);
+    assert_eq!(redacted["game::ChatMessage"]["text"], REDACTED);
+    assert_eq!(redacted["game::ChatMessage"]["channel"], "all");
+    assert_eq!(security_manager.redact_for_role(&Role::Viewer, output.clone()), output);
+}
+
+#[tokio::test]
+async fn test_guest_cannot_search_redacted_fields() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.guest_redaction = RedactionRules::new(vec!["PlayerName.*".to_string(), "Wallet.balance".to_string()]);
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+    let redaction = security_manager.redaction_for_role(&Role::Guest).expect("Guests have redaction rules");
+    assert!(security_manager.redaction_for_role(&Role::Viewer).is_none());
+
+    let mut entities = vec![EntityData {
+        id: 7,
+        components: HashMap::from([
+            ("game::PlayerName".to_string(), json!("alice")),
+            ("game::Wallet".to_string(), json!({"balance": 5000, "currency": "gold"})),
+        ]),
+    }];
+    search_world::redact_entities(&mut entities, redaction);
+    let scope = SearchScope {
+        components: Vec::new(),
+        limit: 100,
+        max_entities: 100,
+    };
+
+    // Match-anything and numeric patterns find nothing in the hidden values
+    let anything = search_world::search_entities(&entities, &ValuePattern::parse("/.*/").unwrap(), &scope);
+    assert!(anything.matches.iter().all(|m| m.value != json!("alice") && m.value != json!(5000)));
+    assert!(anything.matches.iter().any(|m| m.path == "currency"), "Fields that aren't redacted stay searchable");
+    assert!(search_world::search_entities(&entities, &ValuePattern::parse("> 100").unwrap(), &scope).matches.is_empty());
+    assert!(search_world::search_entities(&entities, &ValuePattern::parse("alice").unwrap(), &scope).matches.is_empty());
+}
+
+#[tokio::test]
+async fn test_password_policy_forces_change_and_prevents_reuse() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.force_initial_password_change = true;
+    config.password_history_size = 2;
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+    
+    // Default accounts must pick a new password before they get a token
+    let error = security_manager.authenticate("admin", "admin123", None, None).await.unwrap_err();
+    assert!(error.to_string().contains("Password change required"));
+    let error = security_manager
+        .authenticate_with_password_change("admin", "admin123", None, "admin123", None, None)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("used recently"), "The current password cannot be kept");
+    let admin_token = security_manager
+        .authenticate_with_password_change("admin", "admin123", None, "fresh-admin-pass", None, None)
+        .await
+        .expect("New password should be accepted");
+    assert!(security_manager.authenticate("admin", "fresh-admin-pass", None, None).await.is_ok());
+    
+    // Passwords set by an admin are temporary too
+    security_manager.create_user(&admin_token, "rotator", "first-pass-1", Role::Developer).await.unwrap();
+    assert!(security_manager.authenticate("rotator", "first-pass-1", None, None).await.is_err());
+    security_manager.authenticate_with_password_change("rotator", "first-pass-1", None, "second-pass-2", None, None).await.unwrap();
+    let token = security_manager.authenticate("rotator", "second-pass-2", None, None).await.unwrap();
+    security_manager.change_password(&token, "rotator", "third-pass-3").await.unwrap();
+    
+    // The last two previous passwords are remembered
+    for reused in ["first-pass-1", "second-pass-2", "third-pass-3"] {
+        assert!(security_manager.change_password(&token, "rotator", reused).await.is_err(), "{} was used recently", reused);
+    }
+    security_manager.change_password(&token, "rotator", "fourth-pass-4").await.unwrap();
+    security_manager.change_password(&token, "rotator", "first-pass-1").await.expect("Old enough to reuse");
+}
+
+#[tokio::test]
+async fn test_audit_log_hash_chain_detects_tampering() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("audit.log");
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.audit_log_persistence = true;
+    config.audit_log_path = log_path.to_string_lossy().into_owned();
+    config.audit_integrity = true;
+    config.audit_signing_key = Some("audit-signing-key-for-tests".to_string());
+    config.audit_signature_interval = 2;
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+    
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    let _ = security_manager.authenticate("admin", "wrong-password", None, None).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    
+    let report = security_manager.verify_audit_log(&admin_token).await.unwrap();
+    assert!(report.valid, "{:?}", report.first_break);
+    assert!(report.starts_at_genesis);
+    assert!(report.signatures_checked >= 1);
+    
+    // Flip one recorded outcome in the file
+    let contents = std::fs::read_to_string(&log_path).unwrap();
+    let tampered = contents.replacen("\"success\":false", "\"success\":true", 1);
+    assert_ne!(contents, tampered);
+    std::fs::write(&log_path, tampered).unwrap();
+    
+    let report = security_manager.verify_audit_log(&admin_token).await.unwrap();
+    assert!(!report.valid);
+    assert!(report.first_break.unwrap().reason.contains("modified"));
+}
+
+#[tokio::test]
+async fn test_signing_key_rotation_keeps_live_sessions() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.jwt_key_grace_hours = 1;
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+    
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    let keys = security_manager.list_signing_keys(&admin_token).await.unwrap();
+    assert_eq!(keys.len(), 1);
+    assert!(keys[0].active);
+    
+    let rotated = security_manager.rotate_signing_key(&admin_token).await.unwrap();
+    assert_ne!(rotated.kid, keys[0].kid);
+    
+    // The token signed before the rotation still works during the grace window
+    assert!(security_manager.validate_token(&admin_token).await.is_ok());
+    let new_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    assert_eq!(jsonwebtoken::decode_header(&new_token).unwrap().kid, Some(rotated.kid.clone()));
+    
+    let keys = security_manager.list_signing_keys(&new_token).await.unwrap();
+    assert_eq!(keys.len(), 2);
+    assert!(!keys[0].active && keys[0].expires_at.is_some());
+    assert!(keys[1].active);
+    
+    let audit_log = security_manager.get_audit_log(&new_token, None, None).await.unwrap();
+    assert!(audit_log.iter().any(|entry| entry.action == "signing_key_rotation" && entry.success));
+    
+    // Without a grace window old tokens stop validating straight away
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.jwt_key_grace_hours = 0;
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+    let admin_token = security_manager.authenticate("admin", "admin123", None, None).await.unwrap();
+    security_manager.rotate_signing_key(&admin_token).await.unwrap();
+    assert!(security_manager.validate_token(&admin_token).await.is_err());
+}
+
+#[tokio::test]
+async fn test_ip_brute_force_ban_spans_usernames() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.ip_max_failed_logins = 3;
+    config.ip_ban_duration_minutes = 15;
+    config.ip_failure_delay_ms = 0;
+    let security_manager = SecurityManager::new(config).await.expect("Failed to create security manager");
+    let attacker = Some("203.0.113.50".to_string());
+    
+    // One attempt per username never trips the per-account lockout
+    for username in ["alice", "bob", "carol"] {
+        let result = security_manager.authenticate(username, "Winter2025!", attacker.clone(), None).await;
+        assert!(result.is_err());
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    
+    // Even valid credentials are refused from the banned address
+    let result = security_manager.authenticate("admin", "admin123", attacker.clone(), None).await;
+    assert!(result.unwrap_err().to_string().contains("Too many failed logins"));
+    
+    let admin_token = security_manager
+        .authenticate("admin", "admin123", Some("198.51.100.8".to_string()), None)
+        .await
+        .expect("Other addresses are unaffected");
+    
+    let metrics = security_manager.security_metrics(&admin_token).await.unwrap();
+    assert_eq!(metrics.banned_ips, 1);
+    assert_eq!(metrics.locked_accounts, 0);
+    
+    let audit_log = security_manager.get_audit_log(&admin_token, None, None).await.unwrap();
+    assert!(audit_log.iter().any(|entry| entry.action == "ip_ban" && entry.ip_address.as_deref() == Some("203.0.113.50")));
+}
+
+#[tokio::test]
+async fn test_authenticate_tool_bans_connection_address() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("audit.log");
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.ip_max_failed_logins = 3;
+    config.ip_ban_duration_minutes = 15;
+    config.ip_failure_delay_ms = 0;
+    config.audit_log_persistence = true;
+    config.audit_log_path = log_path.to_string_lossy().into_owned();
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    let tools = SecureMcpTools::new(create_test_tool_server(), security_manager)
+        .with_client_address("203.0.113.51".parse().unwrap());
+    let login = |username: &str| AuthRequest {
+        username: username.to_string(),
+        password: "Winter2025!".to_string(),
+        otp_code: None,
+        new_password: None,
+    };
+
+    for username in ["alice", "bob", "carol"] {
+        assert!(tools.authenticate(Parameters(login(username))).await.is_err());
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+
+    let error = tools.authenticate(Parameters(login("dave"))).await.unwrap_err();
+    assert!(error.message.contains("Too many failed logins"), "{}", error.message);
+    let data = error.data.expect("Tool errors should carry an error code");
+    assert_eq!(data["code"], "RATE_LIMITED");
+    assert_eq!(data["retryable"], true);
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let audit = std::fs::read_to_string(&log_path).unwrap();
+    assert!(audit.lines().any(|line| line.contains("\"ip_ban\"") && line.contains("203.0.113.51")));
+}
+
 #[tokio::test]
 async fn test_user_management() {
     let security_manager = create_test_security_manager().await;
@@ -203,11 +795,93 @@ async fn test_user_management() {
     assert!(result.is_err(), "Deleted user should not be able to authenticate");
 }
 
+#[tokio::test]
+async fn test_api_key_lifecycle() {
+    let security_manager = create_test_security_manager().await;
+    
+    let admin_token = security_manager
+        .authenticate("admin", "admin123", None, None)
+        .await
+        .expect("Admin authentication should succeed");
+    
+    // Create a key scoped to observing only
+    let (api_key, key) = security_manager
+        .create_api_key(&admin_token, "ci-observer", Role::Developer, vec!["observe".to_string()], Some(30))
+        .await
+        .expect("API key creation should succeed");
+    
+    assert!(key.starts_with(API_KEY_PREFIX), "Key should carry the API key prefix");
+    assert!(!api_key.key_hash.contains(&key), "Only a hash of the key should be stored");
+    
+    let claims = security_manager
+        .validate_token(&key)
+        .await
+        .expect("API key should be accepted as a token");
+    assert_eq!(claims.role, Role::Developer, "API key should carry its role");
+    
+    let middleware = SecurityMiddleware::new(security_manager.clone());
+    assert!(middleware.authorize_tool_call(Some(&key), "observe").await.is_ok(), "Scoped operation should be allowed");
+    assert!(middleware.authorize_tool_call(Some(&key), "experiment").await.is_err(), "Operation outside scopes should be denied");
+    
+    // A tampered secret must not validate
+    let tampered = format!("{}x", key);
+    assert!(security_manager.validate_token(&tampered).await.is_err(), "Tampered key should be rejected");
+    
+    // Revoked keys are rejected immediately
+    security_manager
+        .revoke_api_key(&admin_token, &api_key.id)
+        .await
+        .expect("API key revocation should succeed");
+    assert!(security_manager.validate_token(&key).await.is_err(), "Revoked key should be rejected");
+    assert!(security_manager.list_api_keys(&admin_token).await.unwrap().is_empty(), "No keys should remain");
+}
+
+#[tokio::test]
+async fn test_client_certificate_authentication() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.rate_limit_per_ip = 1000;
+    config.mtls = Some(MtlsConfig {
+        cert_path: "server.pem".to_string(),
+        key_path: "server.key".to_string(),
+        client_ca_path: "ca.pem".to_string(),
+        bind_address: "127.0.0.1".to_string(),
+        identity_mapping: parse_cn_mapping("ci-runner.studio=Viewer,admin.studio=admin").unwrap(),
+        require_jwt: false,
+    });
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    
+    // A CN mapped to a role needs no local user
+    let token = security_manager
+        .authenticate_client_certificate("ci-runner.studio", Some("10.0.0.5".to_string()))
+        .await
+        .expect("Mapped certificate should authenticate");
+    let claims = security_manager.validate_token(&token).await.expect("Certificate session should be valid");
+    assert_eq!(claims.role, Role::Viewer, "Certificate should carry its mapped role");
+    
+    let middleware = SecurityMiddleware::new(security_manager.clone());
+    assert!(middleware.authorize_tool_call(Some(&token), "observe").await.is_ok(), "Viewer operation should be allowed");
+    assert!(middleware.authorize_tool_call(Some(&token), "experiment").await.is_err(), "Developer operation should be denied");
+    
+    // A CN mapped to a local user takes that user's role
+    let admin_token = security_manager
+        .authenticate_client_certificate("admin.studio", None)
+        .await
+        .expect("Certificate mapped to a user should authenticate");
+    assert_eq!(security_manager.validate_token(&admin_token).await.unwrap().role, Role::Admin);
+    
+    assert!(security_manager.authenticate_client_certificate("unknown.studio", None).await.is_err(), "Unmapped CN should be rejected");
+    
+    // Closing the connection ends the session
+    security_manager.revoke_token(&token).await.expect("Session should end");
+    assert!(security_manager.validate_token(&token).await.is_err(), "Ended session should be rejected");
+}
+
 #[tokio::test]
 async fn test_secure_tool_authentication() {
     let security_manager = create_test_security_manager().await;
-    let brp_client = create_test_brp_client();
-    let secure_tools = SecureMcpTools::new(brp_client, security_manager.clone());
+    let secure_tools = SecureMcpTools::new(create_test_tool_server(), security_manager.clone());
     
     // Test authentication tool
     let auth_params = json!({
@@ -231,6 +905,52 @@ async fn test_secure_tool_authentication() {
     assert_eq!(claims.role, Role::Admin);
 }
 
+#[tokio::test]
+async fn test_tool_server_tools_require_authorization() {
+    let mut config = SecurityConfig::default();
+    config.jwt_secret = "test_secret_for_testing_only".to_string();
+    config.rate_limit_per_ip = 1000;
+    config.mtls = Some(MtlsConfig {
+        cert_path: "server.pem".to_string(),
+        key_path: "server.key".to_string(),
+        client_ca_path: "ca.pem".to_string(),
+        bind_address: "127.0.0.1".to_string(),
+        identity_mapping: parse_cn_mapping("viewer.studio=Viewer,admin.studio=Admin").unwrap(),
+        require_jwt: false,
+    });
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
+    let secure_tools = SecureMcpTools::new(create_test_tool_server(), security_manager.clone());
+
+    // Tools served by the tool server go through the same authorization as the security tools
+    assert!(
+        secure_tools.schedules(Parameters(json!({"action": "list"}))).await.is_err(),
+        "Tool server tools should require a token"
+    );
+
+    let viewer_token = security_manager
+        .authenticate_client_certificate("viewer.studio", None)
+        .await
+        .expect("Viewer certificate should authenticate");
+    assert!(
+        secure_tools
+            .schedules(Parameters(json!({"action": "list", "auth_token": viewer_token})))
+            .await
+            .is_err(),
+        "Viewer should not manage schedules"
+    );
+
+    let admin_token = security_manager
+        .authenticate_client_certificate("admin.studio", None)
+        .await
+        .expect("Admin certificate should authenticate");
+    let result = secure_tools
+        .with_session_token(admin_token)
+        .schedules(Parameters(json!({"action": "list"})))
+        .await
+        .expect("Admin should list schedules");
+    assert_eq!(result.is_error, Some(false));
+}
+
 #[tokio::test]
 async fn test_password_security() {
     let security_manager = create_test_security_manager().await;
@@ -265,7 +985,7 @@ async fn test_failed_login_tracking() {
     config.lockout_duration_minutes = 1;
     config.jwt_secret = "test_secret".to_string();
     
-    let security_manager = Arc::new(SecurityManager::new(config).expect("Failed to create security manager"));
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
     
     // Attempt multiple failed logins
     for _ in 0..3 {
@@ -343,7 +1063,7 @@ async fn test_session_cleanup() {
     config.session_timeout_hours = 0; // Immediate expiry for testing
     config.jwt_secret = "test_secret".to_string();
     
-    let security_manager = Arc::new(SecurityManager::new(config).expect("Failed to create security manager"));
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
     
     let token = security_manager
         .authenticate("admin", "admin123", None, None)
@@ -405,7 +1125,7 @@ async fn test_rate_limiting() {
     config.rate_limit_burst = 2;
     config.jwt_secret = "test_secret".to_string();
     
-    let security_manager = Arc::new(SecurityManager::new(config).expect("Failed to create security manager"));
+    let security_manager = Arc::new(SecurityManager::new(config).await.expect("Failed to create security manager"));
     
     // First few requests should succeed
     let result1 = security_manager
@@ -428,9 +1148,9 @@ async fn test_rate_limiting() {
     assert!(result3.is_err());
     
     // The error message should indicate rate limiting
-    if let Err(Error::SecurityError(msg)) = result3 {
+    if let Err(Error::RateLimited(msg)) = result3 {
         assert!(msg.contains("Rate limit"), "Error should mention rate limiting");
     } else {
-        panic!("Expected SecurityError with rate limit message");
+        panic!("Expected RateLimited error");
     }
 }
\ No newline at end of file
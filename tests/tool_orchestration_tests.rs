@@ -192,6 +192,7 @@ async fn test_pipeline_execution_sequential() {
         condition: None,
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     });
     pipeline.add_step(PipelineStep {
         name: "second".to_string(),
@@ -204,6 +205,7 @@ async fn test_pipeline_execution_sequential() {
         }),
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     });
 
     let context = ToolContext::new();
@@ -251,6 +253,7 @@ async fn test_pipeline_execution_with_failure() {
         condition: None,
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     });
     pipeline.add_step(PipelineStep {
         name: "second".to_string(),
@@ -259,6 +262,7 @@ async fn test_pipeline_execution_with_failure() {
         condition: None,
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     });
 
     let context = ToolContext::new();
@@ -355,6 +359,7 @@ async fn test_retry_mechanism() {
             max_delay: Duration::from_secs(1),
         }),
         timeout: None,
+        repeat_until: None,
     };
 
     let mut context = ToolContext::new();
@@ -388,6 +393,7 @@ async fn test_pipeline_timeout() {
         condition: None,
         retry_config: None,
         timeout: Some(Duration::from_millis(100)),
+        repeat_until: None,
     });
 
     let context = ToolContext::new();
@@ -436,6 +442,7 @@ async fn test_step_conditions() {
         }),
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     };
 
     assert!(orchestrator.should_execute_step(&step_on_success, &context));
@@ -452,6 +459,7 @@ async fn test_step_conditions() {
         }),
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     };
 
     assert!(!orchestrator.should_execute_step(&step_on_failure, &context));
@@ -469,6 +477,7 @@ async fn test_step_conditions() {
         }),
         retry_config: None,
         timeout: None,
+        repeat_until: None,
     };
 
     assert!(orchestrator.should_execute_step(&step_var_equals, &context));